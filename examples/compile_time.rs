@@ -0,0 +1,75 @@
+//! Compile time benchmark for crates with many composite keys.
+//!
+//! This defines a large number of composite key enums and makes use of every
+//! iterator generated for them, so that the cost of the code generated by
+//! `derive(Key)` can be tracked. The `compile_time` job in CI builds it with
+//! `--timings`, uploads the timing report as an artifact, and reports the
+//! number of lines in its expansion. To measure it locally, run:
+//!
+//! ```sh
+//! cargo build --release --example compile_time --timings
+//! cargo +nightly rustc --release --example compile_time -- -Zunpretty=expanded | wc -l
+//! ```
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Clone, Copy, Key)]
+enum Part {
+    One,
+    Two,
+    Three,
+}
+
+macro_rules! keys {
+    ($($name:ident),* $(,)?) => {
+        $(
+            #[derive(Clone, Copy, Key)]
+            enum $name {
+                First,
+                Second(Part),
+                Third(bool),
+                Fourth(Option<Part>),
+                Fifth,
+                Sixth(Part),
+            }
+        )*
+
+        fn exercise() -> usize {
+            let mut total = 0;
+
+            $(
+                let mut map = Map::new();
+                map.insert($name::Second(Part::Two), 1);
+                map.insert($name::Fourth(None), 2);
+                map.insert($name::Fifth, 3);
+
+                total += map.iter().map(|(_, v)| *v).sum::<usize>();
+                total += map.keys().rev().count();
+                total += map.values().count();
+                total += map.iter_mut().map(|(_, v)| *v).sum::<usize>();
+                total += map.values_mut().rev().count();
+                total += map.into_iter().count();
+
+                let mut set = Set::new();
+                set.insert($name::Third(true));
+                set.insert($name::Sixth(Part::Three));
+
+                total += set.iter().rev().count();
+                total += set.into_iter().count();
+            )*
+
+            total
+        }
+    };
+}
+
+keys! {
+    Key00, Key01, Key02, Key03, Key04, Key05, Key06, Key07,
+    Key08, Key09, Key10, Key11, Key12, Key13, Key14, Key15,
+    Key16, Key17, Key18, Key19, Key20, Key21, Key22, Key23,
+    Key24, Key25, Key26, Key27, Key28, Key29, Key30, Key31,
+}
+
+fn main() {
+    assert_eq!(exercise(), 32 * (6 + 3 + 3 + 6 + 3 + 3 + 2 + 2));
+}
@@ -0,0 +1,29 @@
+use fixed_map::{layout, Key};
+
+#[derive(Clone, Copy, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Clone, Copy, Key)]
+enum MyKey {
+    Simple,
+    Composite(Part),
+    Flag(bool),
+    Optional(Option<Part>),
+    #[key(lazy)]
+    Lazy(Part),
+}
+
+fn main() {
+    let report = layout::report::<MyKey, u32>();
+    println!("{report}");
+
+    for branch in &report.branches {
+        let offset = branch.offset.expect("derived storage reports offsets");
+        assert!(offset + branch.size <= report.map.size);
+    }
+
+    println!("{}", layout::report::<Part, u64>());
+}
@@ -0,0 +1,99 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DataEnum, Fields, Ident};
+
+use crate::context::Ctxt;
+
+/// Generate an extension trait for `Map<K, V>` with per-variant accessors.
+pub(crate) fn implement(cx: &Ctxt<'_>, en: &DataEnum) -> Result<TokenStream, ()> {
+    let ident = &cx.ast.ident;
+    let vis = &cx.ast.vis;
+
+    let map_t = cx.toks.map_t();
+    let option = cx.toks.option();
+
+    let trait_ident = format_ident!("{}MapExt", ident);
+    let trait_doc = format!(
+        "Per-variant accessors for a `Map` keyed by [`{ident}`], generated by `#[key(accessors)]`."
+    );
+
+    let mut decls = Vec::with_capacity(en.variants.len());
+    let mut impls = Vec::with_capacity(en.variants.len());
+
+    for variant in &en.variants {
+        let var = &variant.ident;
+        let name = crate::snake_case(&var.to_string());
+
+        let get = method_ident(&name, var);
+        let get_mut = format_ident!("{}_mut", name, span = var.span());
+        let set = format_ident!("set_{}", name, span = var.span());
+
+        // Other kinds of variants are rejected when implementing storage.
+        let (params, key) = match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                (Some(quote!(field: #ty)), quote!(#ident::#var(field)))
+            }
+            _ => (None, quote!(#ident::#var)),
+        };
+
+        let comma = params.as_ref().map(|_| quote!(,));
+
+        let get_doc = format!("Get the value associated with [`{ident}::{var}`].");
+        let get_mut_doc =
+            format!("Get a mutable reference to the value associated with [`{ident}::{var}`].");
+        let set_doc = format!(
+            "Set the value associated with [`{ident}::{var}`], returning the previous value if one was present."
+        );
+
+        decls.push(quote! {
+            #[doc = #get_doc]
+            fn #get(&self, #params) -> #option<&V>;
+
+            #[doc = #get_mut_doc]
+            fn #get_mut(&mut self, #params) -> #option<&mut V>;
+
+            #[doc = #set_doc]
+            fn #set(&mut self, #params #comma value: V) -> #option<V>;
+        });
+
+        impls.push(quote! {
+            #[inline]
+            fn #get(&self, #params) -> #option<&V> {
+                #map_t::get(self, #key)
+            }
+
+            #[inline]
+            fn #get_mut(&mut self, #params) -> #option<&mut V> {
+                #map_t::get_mut(self, #key)
+            }
+
+            #[inline]
+            fn #set(&mut self, #params #comma value: V) -> #option<V> {
+                #map_t::insert(self, #key, value)
+            }
+        });
+    }
+
+    Ok(quote! {
+        #[doc = #trait_doc]
+        #vis trait #trait_ident<V> {
+            #(#decls)*
+        }
+
+        #[automatically_derived]
+        impl<V> #trait_ident<V> for #map_t<#ident, V> {
+            #(#impls)*
+        }
+    })
+}
+
+/// Construct the identifier of a getter, which has to be a raw identifier if
+/// the snake case name of the variant is a keyword.
+fn method_ident(name: &str, var: &Ident) -> Ident {
+    if syn::parse_str::<Ident>(name).is_ok() {
+        Ident::new(name, var.span())
+    } else {
+        Ident::new_raw(name, var.span())
+    }
+}
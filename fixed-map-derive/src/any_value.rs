@@ -0,0 +1,247 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{DataEnum, Fields, Ident, Visibility};
+
+use crate::attrs;
+use crate::context::{Ctxt, Opts};
+
+/// Implement `AnyValueKey` for enums where variants are annotated with
+/// `#[key(value = "Type")]`.
+///
+/// Produces no output if no variant is annotated.
+pub(crate) fn implement(cx: &Ctxt<'_>, opts: &Opts, en: &DataEnum) -> Result<TokenStream, ()> {
+    let mut types = Vec::with_capacity(en.variants.len());
+
+    for variant in &en.variants {
+        types.push(attrs::parse_variant_attrs(cx, variant)?.value);
+    }
+
+    if types.iter().all(Option::is_none) {
+        if let Some(module) = &opts.value_module {
+            cx.span_error(
+                module.span(),
+                "#[key(value_module = ...)] requires #[key(value = \"...\")] on the variants",
+            );
+            return Err(());
+        }
+
+        return Ok(TokenStream::new());
+    }
+
+    let mut values = Vec::with_capacity(types.len());
+
+    for (variant, ty) in en.variants.iter().zip(types) {
+        if !matches!(variant.fields, Fields::Unit) {
+            cx.span_error(
+                variant.span(),
+                "#[key(value = \"...\")] is only supported on enums with unit variants",
+            );
+            return Err(());
+        }
+
+        let Some(ty) = ty else {
+            cx.span_error(
+                variant.span(),
+                "#[key(value = \"...\")] must be specified on every variant if it is specified on one",
+            );
+            return Err(());
+        };
+
+        values.push(ty);
+    }
+
+    let ident = &cx.ast.ident;
+    let vis = &cx.ast.vis;
+
+    let any_value_key_t = cx.toks.any_value_key_t();
+    let any_value_storage_t = cx.toks.any_value_storage_t();
+    let any_value_variant_t = cx.toks.any_value_variant_t();
+    let clone_t = cx.toks.clone_t();
+    let debug_t = cx.toks.debug_t();
+    let eq_t = cx.toks.eq_t();
+    let fmt_result = cx.toks.fmt_result();
+    let formatter = cx.toks.formatter();
+    let option = cx.toks.option();
+    let partial_eq_t = cx.toks.partial_eq_t();
+    let set_t = cx.toks.set_t();
+
+    let module = match &opts.value_module {
+        Some(module) => module.clone(),
+        None => Ident::new(&crate::snake_case(&ident.to_string()), ident.span()),
+    };
+    let variants = en.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+    let names = (0..variants.len())
+        .map(|index| format_ident!("_{}", index))
+        .collect::<Vec<_>>();
+
+    let module_doc = format!(
+        "Marker types for the variants of [`{ident}`](super::{ident}), used to access the values of an `AnyValueMap`."
+    );
+
+    let marker_docs = variants
+        .iter()
+        .map(|variant| {
+            format!("Marker type for [`{ident}::{variant}`](super::{ident}::{variant}).")
+        })
+        .collect::<Vec<_>>();
+
+    let storage = Ident::new("__AnyValueStorage", Span::call_site());
+    let marker_vis = nested_vis(vis);
+
+    Ok(quote! {
+        #[doc = #module_doc]
+        #vis mod #module {
+            #(
+                #[doc = #marker_docs]
+                #marker_vis struct #variants;
+            )*
+        }
+
+        const _: () = {
+            #vis struct #storage {
+                #(#names: #option<#values>,)*
+            }
+
+            #[automatically_derived]
+            impl #clone_t for #storage where #(for<'trivial_bounds> #values: #clone_t,)* {
+                #[inline]
+                fn clone(&self) -> Self {
+                    Self {
+                        #(#names: #clone_t::clone(&self.#names),)*
+                    }
+                }
+            }
+
+            #[automatically_derived]
+            impl #partial_eq_t for #storage where #(for<'trivial_bounds> #values: #partial_eq_t,)* {
+                #[inline]
+                fn eq(&self, other: &Self) -> bool {
+                    #(if #partial_eq_t::ne(&self.#names, &other.#names) {
+                        return false;
+                    })*
+
+                    true
+                }
+            }
+
+            #[automatically_derived]
+            impl #eq_t for #storage where #(for<'trivial_bounds> #values: #eq_t,)* {}
+
+            #[automatically_derived]
+            impl #debug_t for #storage where for<'trivial_bounds> #ident: #debug_t, #(for<'trivial_bounds> #values: #debug_t,)* {
+                #[inline]
+                fn fmt(&self, f: &mut #formatter<'_>) -> #fmt_result {
+                    let mut f = f.debug_map();
+
+                    #(if let #option::Some(value) = &self.#names {
+                        f.entry(&#ident::#variants, value);
+                    })*
+
+                    f.finish()
+                }
+            }
+
+            #[automatically_derived]
+            impl #any_value_storage_t<#ident> for #storage {
+                #[inline]
+                fn empty() -> Self {
+                    Self {
+                        #(#names: #option::None,)*
+                    }
+                }
+
+                #[inline]
+                fn len(&self) -> usize {
+                    let mut len = 0;
+
+                    #(if #option::is_some(&self.#names) {
+                        len += 1;
+                    })*
+
+                    len
+                }
+
+                #[inline]
+                fn is_empty(&self) -> bool {
+                    #(if #option::is_some(&self.#names) {
+                        return false;
+                    })*
+
+                    true
+                }
+
+                #[inline]
+                fn contains_key(&self, key: #ident) -> bool {
+                    match key {
+                        #(#ident::#variants => #option::is_some(&self.#names),)*
+                    }
+                }
+
+                #[inline]
+                fn remove_key(&mut self, key: #ident) -> bool {
+                    match key {
+                        #(#ident::#variants => #option::is_some(&#option::take(&mut self.#names)),)*
+                    }
+                }
+
+                #[inline]
+                fn clear(&mut self) {
+                    #(self.#names = #option::None;)*
+                }
+
+                #[inline]
+                fn keys_into(&self, keys: &mut #set_t<#ident>) {
+                    #(if #option::is_some(&self.#names) {
+                        keys.insert(#ident::#variants);
+                    })*
+                }
+            }
+
+            #[automatically_derived]
+            impl #any_value_key_t for #ident {
+                type AnyValueStorage = #storage;
+            }
+
+            #(
+                #[automatically_derived]
+                impl #any_value_variant_t for #module::#variants {
+                    type Key = #ident;
+                    type Value = #values;
+
+                    const KEY: #ident = #ident::#variants;
+
+                    #[inline]
+                    fn slot(storage: &#storage) -> &#option<#values> {
+                        &storage.#names
+                    }
+
+                    #[inline]
+                    fn slot_mut(storage: &mut #storage) -> &mut #option<#values> {
+                        &mut storage.#names
+                    }
+                }
+            )*
+        };
+    })
+}
+
+/// Translate the visibility of the enum into the same visibility as seen from
+/// inside of the generated module.
+fn nested_vis(vis: &Visibility) -> TokenStream {
+    match vis {
+        Visibility::Public(..) => quote!(pub),
+        Visibility::Inherited => quote!(pub(super)),
+        Visibility::Restricted(restricted) => {
+            let path = &restricted.path;
+
+            if path.leading_colon.is_some() || path.is_ident("crate") {
+                quote!(pub(in #path))
+            } else if path.is_ident("self") {
+                quote!(pub(super))
+            } else {
+                quote!(pub(in super::#path))
+            }
+        }
+    }
+}
@@ -6,24 +6,65 @@ use syn::spanned::Spanned;
 const MAP_STORAGE: &str = "__MapStorage";
 const SET_STORAGE: &str = "__SetStorage";
 
-use crate::context::Ctxt;
+use crate::attrs;
+use crate::capacity;
+use crate::context::{Ctxt, Opts};
+use crate::debug;
+use crate::schema;
+use crate::values;
+use crate::variant_name;
 
 /// Implement the `Key` trait for an enum.
-pub(crate) fn implement(cx: &Ctxt<'_>, en: &syn::DataEnum) -> Result<TokenStream, ()> {
+pub(crate) fn implement(cx: &Ctxt<'_>, opts: &Opts, en: &syn::DataEnum) -> Result<TokenStream, ()> {
     let ident = &cx.ast.ident;
 
     let key_t = cx.toks.key_t();
+    let schema_variant = cx.toks.schema_variant();
     let map_storage_t = cx.toks.map_storage_t();
     let set_storage_t = cx.toks.set_storage_t();
 
     let mut fields = Fields::default();
+    let mut checks = Vec::new();
 
     for (index, variant) in en.variants.iter().enumerate() {
         let var = &variant.ident;
         let name = format_ident!("_{}", index);
+        let variant_opts = attrs::parse_variant_attrs(cx, variant)?;
 
         let kind = match &variant.fields {
             syn::Fields::Unit => {
+                if variant_opts.values.is_some() {
+                    cx.span_error(
+                        variant.span(),
+                        "#[key(values(...))] is only supported on variants with a single field",
+                    );
+                    continue;
+                }
+
+                if let Some(span) = variant_opts.lazy {
+                    cx.span_error(
+                        span,
+                        "#[key(lazy)] is only supported on variants with a single field",
+                    );
+                    continue;
+                }
+
+                if let Some(span) = variant_opts.dynamic {
+                    cx.span_error(
+                        span,
+                        "#[key(dynamic = \"ordered\")] is only supported on variants with a single field",
+                    );
+                    continue;
+                }
+
+                if let Some(span) = variant_opts.optional {
+                    cx.span_error(
+                        span,
+                        "#[key(optional)] is only supported on variants with a single field",
+                    );
+                    continue;
+                }
+
                 fields
                     .patterns
                     .push(build_tuple_struct_pat(ident, var, None));
@@ -39,9 +80,119 @@ pub(crate) fn implement(cx: &Ctxt<'_>, en: &syn::DataEnum) -> Result<TokenStream
                 }
 
                 let element = unnamed.unnamed.first().expect("Expected one element");
-                let map_storage = quote!(<#element as #key_t>::MapStorage::<V>);
+                let lazy = variant_opts.lazy;
+                let ordered = variant_opts.dynamic;
+                let optional = variant_opts.optional;
+
+                if let Some(span) = optional {
+                    if !is_dynamic(&element.ty) {
+                        cx.span_error(
+                            span,
+                            "#[key(optional)] is only supported on variants holding a dynamic type such as `u32` or `&'static str`",
+                        );
+                        continue;
+                    }
+
+                    if lazy.is_some() || ordered.is_some() || variant_opts.values.is_some() {
+                        cx.span_error(
+                            span,
+                            "#[key(optional)] is not supported together with #[key(lazy)], #[key(dynamic = \"ordered\")] or #[key(values(...))]",
+                        );
+                        continue;
+                    }
+                }
+
+                let (map_storage, set_storage, nested) = match &variant_opts.values {
+                    Some(values) => {
+                        if let Some(span) = lazy {
+                            cx.span_error(
+                                span,
+                                "#[key(lazy)] is not supported together with #[key(values(...))]",
+                            );
+                            continue;
+                        }
+
+                        if let Some(span) = ordered {
+                            cx.span_error(
+                                span,
+                                "#[key(dynamic = \"ordered\")] is not supported together with #[key(values(...))]",
+                            );
+                            continue;
+                        }
+
+                        let values = values::implement(cx, index, var, &element.ty, values)?;
+                        let map_storage = &values.map_storage;
+                        let set_storage = &values.set_storage;
+                        let storage = (quote!(#map_storage::<V>), quote!(#set_storage), false);
+                        checks.push(values.impls);
+                        storage
+                    }
+                    None if ordered.is_some() => {
+                        if let Some(span) = lazy {
+                            cx.span_error(
+                                span,
+                                "#[key(lazy)] is not supported together with #[key(dynamic = \"ordered\")]",
+                            );
+                            continue;
+                        }
+
+                        let span = variant.span();
+                        let ordered_map_storage = cx.toks.ordered_map_storage();
+                        let ordered_set_storage = cx.toks.ordered_set_storage();
+                        let require_alloc = respan(cx.toks.require_alloc(), span);
+
+                        checks.push(quote_spanned! { span =>
+                            #require_alloc!(ordered #var);
+                        });
+
+                        (
+                            quote!(#ordered_map_storage::<#element, V>),
+                            quote!(#ordered_set_storage::<#element>),
+                            false,
+                        )
+                    }
+                    None if optional.is_some() => {
+                        let optional_map_storage = cx.toks.optional_map_storage();
+                        let optional_set_storage = cx.toks.optional_set_storage();
+
+                        // NB: The storage is picked by the enabled features
+                        // of fixed-map, so the field isn't used as a key.
+                        (
+                            quote!(#optional_map_storage::<#element, V>),
+                            quote!(#optional_set_storage::<#element>),
+                            false,
+                        )
+                    }
+                    None => {
+                        if is_dynamic(&element.ty) {
+                            let span = variant.span();
+                            let ty = &element.ty;
+
+                            let require_hashbrown = respan(cx.toks.require_hashbrown(), span);
+
+                            checks.push(quote_spanned! { span =>
+                                #require_hashbrown!(#var, #ty);
+                            });
+                        }
+
+                        let map_storage = if let Some(span) = lazy {
+                            let lazy_map_storage = cx.toks.lazy_map_storage();
+                            let require_alloc = respan(cx.toks.require_alloc(), span);
+
+                            checks.push(quote_spanned! { span =>
+                                #require_alloc!(#var);
+                            });
+
+                            quote!(#lazy_map_storage::<#element, V>)
+                        } else {
+                            quote!(<#element as #key_t>::MapStorage::<V>)
+                        };
+
+                        (map_storage, quote!(<#element as #key_t>::SetStorage), true)
+                    }
+                };
+
                 let as_map_storage = quote!(<#map_storage as #map_storage_t<#element, V>>);
-                let set_storage = quote!(<#element as #key_t>::SetStorage);
                 let as_set_storage = quote!(<#set_storage as #set_storage_t<#element>>);
 
                 let pat =
@@ -55,6 +206,7 @@ pub(crate) fn implement(cx: &Ctxt<'_>, en: &syn::DataEnum) -> Result<TokenStream
                     as_map_storage,
                     set_storage,
                     as_set_storage,
+                    nested,
                 })
             }
             syn::Fields::Named(_) => {
@@ -72,11 +224,23 @@ pub(crate) fn implement(cx: &Ctxt<'_>, en: &syn::DataEnum) -> Result<TokenStream
         });
     }
 
-    let (map_storage_type_name, map_storage_impl) = impl_map_storage(cx, &fields)?;
-    let (set_storage_type_name, set_storage_impl) = impl_set_storage(cx, &fields)?;
+    // Variants which failed to validate were skipped above, so their errors
+    // have to be reported instead of the incomplete implementation.
+    if cx.has_errors() {
+        return Err(());
+    }
+
+    let (map_storage_type_name, map_storage_impl) = impl_map_storage(cx, opts, &fields)?;
+    let (set_storage_type_name, set_storage_impl) = impl_set_storage(cx, opts, &fields)?;
+
+    let key_schema_hash = schema::key_schema_hash(cx, en);
+    let key_variants = schema::key_variants(cx, opts, en)?;
+    let variant_name = variant_name::implement(cx, en);
+    let capacity = capacity::implement(cx, en);
 
     Ok(quote! {
         const _: () = {
+            #(#checks)*
             #map_storage_impl
             #set_storage_impl
 
@@ -84,11 +248,52 @@ pub(crate) fn implement(cx: &Ctxt<'_>, en: &syn::DataEnum) -> Result<TokenStream
             impl #key_t for #ident {
                 type MapStorage<V> = #map_storage_type_name<V>;
                 type SetStorage = #set_storage_type_name;
+                const KEY_SCHEMA_HASH: u64 = #key_schema_hash;
+                const KEY_VARIANTS: &'static [#schema_variant] = #key_variants;
+                #capacity
+                #variant_name
             }
         };
     })
 }
 
+/// Test if the given type is one of the dynamic key types which are only
+/// available when the `hashbrown` or `std-collections` feature of fixed-map is
+/// enabled.
+fn is_dynamic(ty: &syn::Type) -> bool {
+    const DYNAMIC: [&str; 11] = [
+        "char", "u8", "u32", "u64", "u128", "usize", "i8", "i32", "i64", "i128", "isize",
+    ];
+
+    match ty {
+        syn::Type::Path(path) if path.qself.is_none() => path
+            .path
+            .get_ident()
+            .is_some_and(|ident| DYNAMIC.iter().any(|name| ident == name)),
+        syn::Type::Reference(reference) => match &*reference.elem {
+            syn::Type::Path(path) => path.path.is_ident("str"),
+            syn::Type::Slice(slice) => {
+                matches!(&*slice.elem, syn::Type::Path(path) if path.path.is_ident("u8"))
+            }
+            _ => false,
+        },
+        syn::Type::Group(group) => is_dynamic(&group.elem),
+        _ => false,
+    }
+}
+
+/// Re-span the given path, so that an error produced by the macro it names is
+/// reported on the variant rather than on the derive.
+fn respan(path: syn::Path, span: Span) -> TokenStream {
+    path.into_token_stream()
+        .into_iter()
+        .map(|mut tt| {
+            tt.set_span(span);
+            tt
+        })
+        .collect()
+}
+
 fn build_tuple_struct_pat(
     ident: &syn::Ident,
     var: &syn::Ident,
@@ -139,7 +344,12 @@ fn build_tuple_struct_pat(
 }
 
 /// Implement `MapStorage` implementation.
-fn impl_map_storage(cx: &Ctxt<'_>, fields: &Fields<'_>) -> Result<(syn::Ident, TokenStream), ()> {
+fn impl_map_storage(
+    cx: &Ctxt<'_>,
+    opts: &Opts,
+    fields: &Fields<'_>,
+) -> Result<(syn::Ident, TokenStream), ()> {
+    let repr = opts.storage_repr(false);
     let vis = &cx.ast.vis;
     let ident = &cx.ast.ident;
 
@@ -158,6 +368,41 @@ fn impl_map_storage(cx: &Ctxt<'_>, fields: &Fields<'_>) -> Result<(syn::Ident, T
     map_storage_into_iter(cx, "IntoIter", fields, &mut output)?;
     map_storage_entry(cx, fields, &type_name, &mut output)?;
 
+    {
+        let storage_remaining_t = cx.toks.storage_remaining_t();
+        let remaining = fields.remaining(cx);
+        let end = fields.len();
+
+        let present = fields.iter().map(
+            |Field {
+                 index, name, kind, ..
+             }| {
+                let present = match kind {
+                    Kind::Complex(Complex { as_map_storage, .. }) => {
+                        quote!(!#as_map_storage::is_empty(&self.#name))
+                    }
+                    Kind::Simple => quote!(#option::is_some(&self.#name)),
+                };
+
+                quote!(present = #storage_remaining_t::retain(present, #index, #present);)
+            },
+        );
+
+        output.impls.extend(quote! {
+            #[automatically_derived]
+            impl<V> #type_name<V> {
+                /// The variants which have entries, so that iterators can skip
+                /// the empty ones without polling them.
+                #[inline]
+                fn __present(&self) -> #remaining {
+                    let mut present = <#remaining as #storage_remaining_t>::all(#end);
+                    #(#present)*
+                    present
+                }
+            }
+        });
+    }
+
     {
         let partial_eq_t = cx.toks.partial_eq_t();
         let eq_t = cx.toks.eq_t();
@@ -312,121 +557,912 @@ fn impl_map_storage(cx: &Ctxt<'_>, fields: &Fields<'_>) -> Result<(syn::Ident, T
                     #(#patterns => #get,)*
                 }
             }
-        });
+        });
+    }
+
+    {
+        let patterns = &fields.patterns;
+
+        let get_mut = fields.iter().map(|Field { name, kind, .. }| match kind {
+            Kind::Complex(Complex { as_map_storage, .. }) => {
+                quote!(#as_map_storage::get_mut(&mut self.#name, v))
+            }
+            Kind::Simple => quote!(#option::as_mut(&mut self.#name)),
+        });
+
+        output.items.extend(quote! {
+            #[inline]
+            fn get_mut(&mut self, value: #ident) -> #option<&mut V> {
+                match value {
+                    #(#patterns => #get_mut,)*
+                }
+            }
+        });
+    }
+
+    {
+        let remove = fields.iter().map(|Field { name, kind, .. }| match kind {
+            Kind::Complex(Complex { as_map_storage, .. }) => {
+                quote!(#as_map_storage::remove(&mut self.#name, v))
+            }
+            Kind::Simple => quote!(#option::take(&mut self.#name)),
+        });
+
+        let patterns = &fields.patterns;
+
+        let remove_and_shrink = fields.iter().map(|Field { name, kind, .. }| match kind {
+            Kind::Complex(Complex { as_map_storage, .. }) => {
+                quote!(#as_map_storage::remove_and_shrink(&mut self.#name, v))
+            }
+            Kind::Simple => quote!(#option::take(&mut self.#name)),
+        });
+
+        output.items.extend(quote! {
+            #[inline]
+            fn remove(&mut self, value: #ident) -> #option<V> {
+                match value {
+                    #(#patterns => #remove,)*
+                }
+            }
+
+            #[inline]
+            fn remove_and_shrink(&mut self, value: #ident) -> #option<V> {
+                match value {
+                    #(#patterns => #remove_and_shrink,)*
+                }
+            }
+        });
+    }
+
+    {
+        let retain = fields.iter().map(
+            |Field {
+                 var, name, kind, ..
+             }| match kind {
+                Kind::Complex(Complex { as_map_storage, .. }) => quote! {
+                    #as_map_storage::retain(&mut self.#name, |k, v| func(#ident::#var(k), v));
+                },
+                Kind::Simple => quote! {
+                    if let #option::Some(val) = #option::as_mut(&mut self.#name) {
+                        if !func(#ident::#var, val) {
+                            self.#name = None;
+                        }
+                    }
+                },
+            },
+        );
+
+        output.items.extend(quote! {
+            #[inline]
+            fn retain<F>(&mut self, mut func: F)
+            where
+                F: FnMut(#ident, &mut V) -> bool
+            {
+                #(#retain;)*
+            }
+        });
+    }
+
+    {
+        let key_t = cx.toks.key_t();
+
+        let values_with_index = fields.iter().map(
+            |Field {
+                 index, name, kind, ..
+             }| match kind {
+                Kind::Complex(Complex { as_map_storage, .. }) => quote! {
+                    for value in #as_map_storage::values(&self.#name) {
+                        func(#index, value);
+                    }
+                },
+                Kind::Simple => quote! {
+                    if let #option::Some(value) = #option::as_ref(&self.#name) {
+                        func(#index, value);
+                    }
+                },
+            },
+        );
+
+        output.items.extend(quote! {
+            #[inline]
+            fn values_with_index<F>(&self, mut func: F)
+            where
+                #ident: #key_t,
+                F: FnMut(usize, &V),
+            {
+                #(#values_with_index)*
+            }
+        });
+    }
+
+    {
+        let find_map = fields.iter().map(
+            |Field {
+                 name,
+                 var,
+                 kind,
+                 ..
+             }| match kind {
+                Kind::Complex(Complex { as_map_storage, .. }) => quote! {
+                    if let #option::Some(output) = #as_map_storage::find_map(&self.#name, |v, value| func(#ident::#var(v), value)) {
+                        return #option::Some(output);
+                    }
+                },
+                Kind::Simple => quote! {
+                    if let #option::Some(value) = #option::as_ref(&self.#name) {
+                        if let #option::Some(output) = func(#ident::#var, value) {
+                            return #option::Some(output);
+                        }
+                    }
+                },
+            },
+        );
+
+        output.items.extend(quote! {
+            #[inline]
+            fn find_map<__B, __F>(&self, mut func: __F) -> #option<__B>
+            where
+                __F: FnMut(#ident, &V) -> #option<__B>,
+            {
+                #(#find_map)*
+                #option::None
+            }
+        });
+    }
+
+    {
+        let option = &option;
+
+        let key_value = |method: syn::Ident| {
+            move |Field {
+                      name, var, kind, ..
+                  }: &Field<'_>| match kind {
+                Kind::Complex(Complex { as_map_storage, .. }) => quote! {
+                    if let #option::Some((v, value)) = #as_map_storage::#method(&self.#name) {
+                        return #option::Some((#ident::#var(v), value));
+                    }
+                },
+                Kind::Simple => quote! {
+                    if let #option::Some(value) = #option::as_ref(&self.#name) {
+                        return #option::Some((#ident::#var, value));
+                    }
+                },
+            }
+        };
+
+        let first = fields
+            .iter()
+            .map(key_value(format_ident!("first_key_value")));
+        let last = fields
+            .iter()
+            .rev()
+            .map(key_value(format_ident!("last_key_value")));
+
+        output.items.extend(quote! {
+            #[inline]
+            fn first_key_value(&self) -> #option<(#ident, &V)> {
+                #(#first)*
+                #option::None
+            }
+
+            #[inline]
+            fn last_key_value(&self) -> #option<(#ident, &V)> {
+                #(#last)*
+                #option::None
+            }
+        });
+    }
+
+    {
+        let key_t = cx.toks.key_t();
+        let set_storage_t = cx.toks.set_storage_t();
+
+        let key_set = fields.iter().map(|Field { name, kind, .. }| match kind {
+            Kind::Complex(Complex {
+                as_map_storage,
+                nested: true,
+                ..
+            }) => quote! {
+                {
+                    output.#name = #as_map_storage::key_set(&self.#name);
+                }
+            },
+            Kind::Complex(Complex {
+                as_map_storage,
+                as_set_storage,
+                ..
+            }) => quote! {
+                for v in #as_map_storage::keys(&self.#name) {
+                    #as_set_storage::insert(&mut output.#name, v);
+                }
+            },
+            Kind::Simple => quote! {
+                {
+                    output.#name = #option::is_some(&self.#name);
+                }
+            },
+        });
+
+        output.items.extend(quote! {
+            #[inline]
+            fn key_set(&self) -> <#ident as #key_t>::SetStorage
+            where
+                #ident: #key_t,
+            {
+                let mut output = <<#ident as #key_t>::SetStorage as #set_storage_t<#ident>>::empty();
+                #(#key_set)*
+                output
+            }
+        });
+    }
+
+    {
+        let key_t = cx.toks.key_t();
+
+        let retain_keys = fields.iter().map(|Field { name, kind, .. }| match kind {
+            Kind::Complex(Complex {
+                as_map_storage,
+                nested: true,
+                ..
+            }) => quote! {
+                #as_map_storage::retain_keys(&mut self.#name, &allowed.#name);
+            },
+            Kind::Complex(Complex {
+                as_map_storage,
+                as_set_storage,
+                ..
+            }) => quote! {
+                #as_map_storage::retain(&mut self.#name, |k, _| #as_set_storage::contains(&allowed.#name, k));
+            },
+            Kind::Simple => quote! {
+                if !allowed.#name {
+                    self.#name = #option::None;
+                }
+            },
+        });
+
+        output.items.extend(quote! {
+            #[inline]
+            fn retain_keys(&mut self, allowed: &<#ident as #key_t>::SetStorage)
+            where
+                #ident: #key_t,
+            {
+                #(#retain_keys)*
+            }
+        });
+    }
+
+    {
+        let fill_fixed = fields.iter().map(|Field { name, kind, .. }| match kind {
+            Kind::Complex(Complex { as_map_storage, .. }) => quote! {
+                #as_map_storage::fill_fixed(&mut self.#name, &mut func);
+            },
+            Kind::Simple => quote! {
+                if #option::is_none(&self.#name) {
+                    self.#name = #option::Some(func());
+                }
+            },
+        });
+
+        output.items.extend(quote! {
+            #[inline]
+            fn fill_fixed<__F>(&mut self, mut func: __F)
+            where
+                __F: FnMut() -> V,
+            {
+                #(#fill_fixed)*
+            }
+        });
+    }
+
+    {
+        let key_t = cx.toks.key_t();
+
+        let map_values = fields.iter().map(
+            |Field {
+                 name, var, kind, ..
+             }| match kind {
+                Kind::Complex(Complex { as_map_storage, .. }) => quote! {
+                    for (v, value) in #as_map_storage::into_iter(self.#name) {
+                        #map_storage_t::insert(&mut output.#name, v, func(#ident::#var(v), value));
+                    }
+                },
+                Kind::Simple => quote! {
+                    {
+                        output.#name = #option::map(self.#name, |value| func(#ident::#var, value));
+                    }
+                },
+            },
+        );
+
+        output.items.extend(quote! {
+            #[inline]
+            fn map_values<__W, __F>(self, mut func: __F) -> <#ident as #key_t>::MapStorage<__W>
+            where
+                #ident: #key_t,
+                __F: FnMut(#ident, V) -> __W,
+            {
+                let mut output = <#type_name<__W> as #map_storage_t<#ident, __W>>::empty();
+                #(#map_values)*
+                output
+            }
+        });
+    }
+
+    {
+        let layout_branch = cx.toks.layout_branch();
+        let offset_of = cx.toks.offset_of();
+
+        let layout = fields.iter().map(
+            |Field {
+                 name,
+                 var,
+                 kind,
+                 ..
+             }| {
+                let ty = match kind {
+                    Kind::Complex(Complex { map_storage, .. }) => quote!(#map_storage),
+                    Kind::Simple => quote!(#option<V>),
+                };
+
+                let var = var.to_string();
+
+                quote! {
+                    func(#layout_branch::new::<#ty>(#var, #option::Some(#offset_of(&storage, &storage.#name))));
+                }
+            },
+        );
+
+        output.items.extend(quote! {
+            #[inline]
+            fn layout<F>(mut func: F)
+            where
+                F: FnMut(#layout_branch),
+            {
+                let storage = <Self as #map_storage_t<#ident, V>>::empty();
+                #(#layout)*
+            }
+        });
+    }
+
+    {
+        let lt = cx.lt;
+
+        let mut simple = Vec::new();
+        let mut arms = Vec::new();
+        let mut complex = Vec::new();
+
+        for Field {
+            name, var, kind, ..
+        } in fields.iter()
+        {
+            match kind {
+                Kind::Complex(Complex { as_map_storage, .. }) => complex.push(quote! {
+                    #as_map_storage::get_disjoint_mut(
+                        &mut self.#name,
+                        &|n| match key(n) {
+                            #option::Some(#ident::#var(k)) => #option::Some(k),
+                            _ => #option::None,
+                        },
+                        &|a, b| eq(&#ident::#var(*a), &#ident::#var(*b)),
+                        output,
+                    );
+                }),
+                Kind::Simple => {
+                    simple.push(quote! {
+                        let mut #name = #option::as_mut(&mut self.#name);
+                    });
+
+                    arms.push(quote! {
+                        #option::Some(#ident::#var) => {
+                            output[n] = #option::take(&mut #name);
+                        }
+                    });
+                }
+            }
+        }
+
+        // Unit variants are looked up directly, and the storage of every
+        // other variant only sees the keys which belong to it.
+        let simple = (!arms.is_empty()).then(|| {
+            quote! {
+                #(#simple)*
+
+                for n in 0..output.len() {
+                    match key(n) {
+                        #(#arms)*
+                        _ => {}
+                    }
+                }
+            }
+        });
+
+        output.items.extend(quote! {
+            #[inline]
+            fn get_disjoint_mut<#lt>(
+                &#lt mut self,
+                key: &dyn Fn(usize) -> #option<#ident>,
+                eq: &dyn Fn(&#ident, &#ident) -> bool,
+                output: &mut [#option<&#lt mut V>],
+            ) {
+                #simple
+                #(#complex)*
+            }
+        });
+    }
+
+    {
+        let clear = fields.iter().map(|Field { name, kind, .. }| match kind {
+            Kind::Complex(Complex { as_map_storage, .. }) => quote! {
+                #as_map_storage::clear(&mut self.#name)
+            },
+            Kind::Simple => quote! {
+                self.#name = #option::None
+            },
+        });
+
+        output.items.extend(quote! {
+            #[inline]
+            fn clear(&mut self) {
+                #(#clear;)*
+            }
+        });
+    }
+
+    let field_decls = fields.iter().map(|Field { name, kind, .. }| match kind {
+        Kind::Complex(Complex { map_storage, .. }) => quote!(#name: #map_storage),
+        Kind::Simple => quote!(#name: #option<V>),
+    });
+
+    let entries_storage_impl = impl_entries_storage(cx, fields, &type_name)?;
+    let drain_storage_impl = impl_drain_storage(cx, fields, &type_name);
+    let extract_if_storage_impl = impl_extract_if_storage(cx, fields, &type_name);
+    let stats_storage_impl = impl_stats_storage(cx, fields, &type_name);
+    let class_storage_impl = impl_class_storage(cx, fields, &type_name);
+
+    let Output { impls, items } = output;
+
+    let debug_impl = debug::impl_map(cx, ident, &type_name);
+
+    let map_storage_impl = quote! {
+        #repr
+        #vis struct #type_name<V> {
+            #(#field_decls,)*
+        }
+
+        #debug_impl
+
+        #[automatically_derived]
+        impl<V> #map_storage_t<#ident, V> for #type_name<V> {
+            #items
+        }
+
+        #impls
+        #entries_storage_impl
+        #drain_storage_impl
+        #extract_if_storage_impl
+        #stats_storage_impl
+        #class_storage_impl
+    };
+
+    Ok((type_name, map_storage_impl))
+}
+
+/// Implement `EntriesStorage`, which is available as long as the storage of
+/// every field implements it.
+fn impl_entries_storage(
+    cx: &Ctxt<'_>,
+    fields: &Fields<'_>,
+    map_storage: &syn::Ident,
+) -> Result<TokenStream, ()> {
+    let type_name = format_ident!("{MAP_STORAGE}EntriesMut");
+
+    let ident = &cx.ast.ident;
+    let lt = cx.lt;
+    let vis = &cx.ast.vis;
+
+    let entries_storage_t = cx.toks.entries_storage_t();
+    let entry_enum = cx.toks.entry_enum();
+    let iterator_t = cx.toks.iterator_t();
+    let occupied_entry_t = cx.toks.occupied_entry_t();
+    let option = cx.toks.option();
+    let storage_iter = cx.toks.storage_iter();
+    let remaining = fields.remaining(cx);
+    let storage_step_t = cx.toks.storage_step_t();
+    let vacant_entry_t = cx.toks.vacant_entry_t();
+
+    let mut step_forward = IteratorNext::default();
+    let mut field_decls = Vec::new();
+    let mut init = Vec::new();
+    let mut bounds = Vec::new();
+
+    for Field {
+        index,
+        name,
+        var,
+        kind,
+        ..
+    } in fields
+    {
+        match kind {
+            Kind::Simple => {
+                field_decls.push(quote!(#name: #option<&#lt mut #option<V>>));
+                init.push(quote!(#name: #option::Some(&mut self.#name)));
+
+                step_forward.next.push(quote! {
+                    #index => {
+                        if let #option::Some(slot) = #option::take(&mut self.#name) {
+                            return #option::Some(option_to_entry(slot, #ident::#var));
+                        }
+                    }
+                });
+            }
+            Kind::Complex(Complex {
+                element,
+                map_storage,
+                ..
+            }) => {
+                let as_entries_storage = quote!(<#map_storage as #entries_storage_t<#element, V>>);
+
+                bounds.push(quote!(#map_storage: #entries_storage_t<#element, V>));
+                field_decls.push(quote!(#name: #as_entries_storage::EntriesMut<#lt>));
+                init.push(quote!(#name: #as_entries_storage::entries_mut(&mut self.#name)));
+
+                let pattern = quote!(#ident::#var);
+
+                step_forward.next.push(quote! {
+                    #index => {
+                        if let #option::Some(entry) = #iterator_t::next(&mut self.#name) {
+                            return #option::Some(match entry {
+                                #entry_enum::Occupied(entry) => #entry_enum::Occupied(OccupiedEntry::#name(#pattern(<_ as #occupied_entry_t<'_, #element, V>>::key(&entry)), entry)),
+                                #entry_enum::Vacant(entry) => #entry_enum::Vacant(VacantEntry::#name(#pattern(<_ as #vacant_entry_t<'_, #element, V>>::key(&entry)), entry)),
+                            });
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    let end = fields.len();
+
+    Ok(quote! {
+        #vis struct #type_name<#lt, V> where V: #lt, #(#bounds,)* {
+            #(#field_decls,)*
+        }
+
+        #[automatically_derived]
+        impl<#lt, V> #storage_step_t for #type_name<#lt, V> where V: #lt, #(#bounds,)* {
+            type Item = #entry_enum<#lt, #map_storage<V>, #ident, V>;
+
+            #[inline]
+            fn step(&mut self, index: usize) -> #option<Self::Item> {
+                #step_forward
+                #option::None
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #entries_storage_t<#ident, V> for #map_storage<V> where #(#bounds,)* {
+            type EntriesMut<#lt> = #storage_iter<#type_name<#lt, V>, #remaining> where V: #lt;
+
+            #[inline]
+            fn entries_mut(&mut self) -> Self::EntriesMut<'_> {
+                #storage_iter::new(#end, #type_name { #(#init,)* })
+            }
+        }
+    })
+}
+
+/// Implement `DrainStorage`, which is available as long as the storage of
+/// every field implements it.
+fn impl_drain_storage(cx: &Ctxt<'_>, fields: &Fields<'_>, map_storage: &syn::Ident) -> TokenStream {
+    let type_name = format_ident!("{MAP_STORAGE}Drain");
+
+    let ident = &cx.ast.ident;
+    let lt = cx.lt;
+    let vis = &cx.ast.vis;
+
+    let drain_storage_t = cx.toks.drain_storage_t();
+    let iterator_t = cx.toks.iterator_t();
+    let option = cx.toks.option();
+    let storage_iter = cx.toks.storage_iter();
+    let remaining = fields.remaining(cx);
+    let storage_step_t = cx.toks.storage_step_t();
+
+    let mut step_forward = IteratorNext::default();
+    let mut field_decls = Vec::new();
+    let mut init = Vec::new();
+    let mut bounds = Vec::new();
+
+    for Field {
+        index,
+        name,
+        var,
+        kind,
+        ..
+    } in fields
+    {
+        match kind {
+            Kind::Simple => {
+                field_decls.push(quote!(#name: #option<V>));
+                init.push(quote!(#name: #option::take(&mut self.#name)));
+
+                step_forward.next.push(quote! {
+                    #index => {
+                        if let #option::Some(value) = #option::take(&mut self.#name) {
+                            return #option::Some((#ident::#var, value));
+                        }
+                    }
+                });
+            }
+            Kind::Complex(Complex {
+                element,
+                map_storage,
+                ..
+            }) => {
+                let as_drain_storage = quote!(<#map_storage as #drain_storage_t<#element, V>>);
+
+                bounds.push(quote!(#map_storage: #drain_storage_t<#element, V>));
+                field_decls.push(quote!(#name: #as_drain_storage::Drain<#lt>));
+                init.push(quote!(#name: #as_drain_storage::drain(&mut self.#name)));
+
+                step_forward.next.push(quote! {
+                    #index => {
+                        if let #option::Some((key, value)) = #iterator_t::next(&mut self.#name) {
+                            return #option::Some((#ident::#var(key), value));
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    quote! {
+        #vis struct #type_name<#lt, V> where V: #lt, #(#bounds,)* {
+            #(#field_decls,)*
+        }
+
+        #[automatically_derived]
+        impl<#lt, V> #storage_step_t for #type_name<#lt, V> where V: #lt, #(#bounds,)* {
+            type Item = (#ident, V);
+
+            #[inline]
+            fn step(&mut self, index: usize) -> #option<Self::Item> {
+                #step_forward
+                #option::None
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #drain_storage_t<#ident, V> for #map_storage<V> where #(#bounds,)* {
+            type Drain<#lt> = #storage_iter<#type_name<#lt, V>, #remaining> where V: #lt;
+
+            #[inline]
+            fn drain(&mut self) -> Self::Drain<'_> {
+                #storage_iter::with_remaining(Self::__present(self), #type_name { #(#init,)* })
+            }
+        }
+    }
+}
+
+/// Implement `ExtractIfStorage`, which is available as long as the storage of
+/// every field implements it.
+fn impl_extract_if_storage(
+    cx: &Ctxt<'_>,
+    fields: &Fields<'_>,
+    map_storage: &syn::Ident,
+) -> TokenStream {
+    let type_name = format_ident!("{MAP_STORAGE}ExtractIf");
+
+    let ident = &cx.ast.ident;
+    let lt = cx.lt;
+    let vis = &cx.ast.vis;
+
+    let extract_if_cursor_t = cx.toks.extract_if_cursor_t();
+    let extract_if_storage_t = cx.toks.extract_if_storage_t();
+    let option = cx.toks.option();
+    let remaining = fields.remaining(cx);
+    let storage_remaining_t = cx.toks.storage_remaining_t();
+
+    let mut step_forward = IteratorNext::default();
+    let mut field_decls = Vec::new();
+    let mut init = Vec::new();
+    let mut bounds = Vec::new();
+
+    for Field {
+        index,
+        name,
+        var,
+        kind,
+        ..
+    } in fields
+    {
+        match kind {
+            Kind::Simple => {
+                field_decls.push(quote!(#name: &#lt mut #option<V>));
+                init.push(quote!(#name: &mut self.#name));
+
+                step_forward.next.push(quote! {
+                    #index => {
+                        if let #option::Some(value) = #option::as_mut(self.#name) {
+                            if filter(#ident::#var, value) {
+                                return #option::map(#option::take(self.#name), |value| (#ident::#var, value));
+                            }
+                        }
+                    }
+                });
+            }
+            Kind::Complex(Complex {
+                element,
+                map_storage,
+                ..
+            }) => {
+                let as_extract_if_storage =
+                    quote!(<#map_storage as #extract_if_storage_t<#element, V>>);
+
+                bounds.push(quote!(#map_storage: #extract_if_storage_t<#element, V>));
+                field_decls.push(quote!(#name: #as_extract_if_storage::ExtractIf<#lt>));
+                init.push(quote!(#name: #as_extract_if_storage::extract_if(&mut self.#name)));
+
+                step_forward.next.push(quote! {
+                    #index => {
+                        if let #option::Some((key, value)) = #extract_if_cursor_t::next_if(&mut self.#name, &mut |key, value| filter(#ident::#var(key), value)) {
+                            return #option::Some((#ident::#var(key), value));
+                        }
+                    }
+                });
+            }
+        }
     }
 
-    {
-        let patterns = &fields.patterns;
-
-        let get_mut = fields.iter().map(|Field { name, kind, .. }| match kind {
-            Kind::Complex(Complex { as_map_storage, .. }) => {
-                quote!(#as_map_storage::get_mut(&mut self.#name, v))
-            }
-            Kind::Simple => quote!(#option::as_mut(&mut self.#name)),
-        });
+    quote! {
+        #vis struct #type_name<#lt, V> where V: #lt, #(#bounds,)* {
+            remaining: #remaining,
+            #(#field_decls,)*
+        }
 
-        output.items.extend(quote! {
+        #[automatically_derived]
+        impl<#lt, V> #extract_if_cursor_t<#ident, V> for #type_name<#lt, V> where V: #lt, #(#bounds,)* {
             #[inline]
-            fn get_mut(&mut self, value: #ident) -> #option<&mut V> {
-                match value {
-                    #(#patterns => #get_mut,)*
+            fn next_if<F>(&mut self, filter: &mut F) -> #option<(#ident, V)>
+            where
+                F: ?Sized + FnMut(#ident, &mut V) -> bool,
+            {
+                while let #option::Some(index) = #storage_remaining_t::first(&self.remaining) {
+                    #step_forward
+                    #storage_remaining_t::remove(&mut self.remaining, index);
                 }
-            }
-        });
-    }
 
-    {
-        let remove = fields.iter().map(|Field { name, kind, .. }| match kind {
-            Kind::Complex(Complex { as_map_storage, .. }) => {
-                quote!(#as_map_storage::remove(&mut self.#name, v))
+                #option::None
             }
-            Kind::Simple => quote!(#option::take(&mut self.#name)),
-        });
+        }
 
-        let patterns = &fields.patterns;
+        #[automatically_derived]
+        impl<V> #extract_if_storage_t<#ident, V> for #map_storage<V> where #(#bounds,)* {
+            type ExtractIf<#lt> = #type_name<#lt, V> where V: #lt;
 
-        output.items.extend(quote! {
             #[inline]
-            fn remove(&mut self, value: #ident) -> #option<V> {
-                match value {
-                    #(#patterns => #remove,)*
+            fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+                #type_name {
+                    remaining: Self::__present(self),
+                    #(#init,)*
                 }
             }
-        });
+        }
     }
+}
 
-    {
-        let retain = fields.iter().map(
-            |Field {
-                 var, name, kind, ..
-             }| match kind {
-                Kind::Complex(Complex { as_map_storage, .. }) => quote! {
-                    #as_map_storage::retain(&mut self.#name, |k, v| func(#ident::#var(k), v));
-                },
-                Kind::Simple => quote! {
-                    if let #option::Some(val) = #option::as_mut(&mut self.#name) {
-                        if !func(#ident::#var, val) {
-                            self.#name = None;
-                        }
-                    }
-                },
-            },
-        );
+/// Implement `StatsStorage`, which is available as long as the storage of
+/// every field implements it.
+fn impl_stats_storage(cx: &Ctxt<'_>, fields: &Fields<'_>, map_storage: &syn::Ident) -> TokenStream {
+    let ident = &cx.ast.ident;
 
-        output.items.extend(quote! {
-            #[inline]
-            fn retain<F>(&mut self, mut func: F)
-            where
-                F: FnMut(#ident, &mut V) -> bool
-            {
-                #(#retain;)*
+    let stats = cx.toks.stats();
+    let stats_storage_t = cx.toks.stats_storage_t();
+
+    let mut merge = Vec::new();
+    let mut bounds = Vec::new();
+
+    for Field { name, kind, .. } in fields {
+        match kind {
+            Kind::Simple => {
+                merge.push(quote! {
+                    let stats = stats.merge(#stats::fixed(1, usize::from(self.#name.is_some())));
+                });
             }
-        });
-    }
+            Kind::Complex(Complex {
+                element,
+                map_storage,
+                ..
+            }) => {
+                bounds.push(quote!(#map_storage: #stats_storage_t<#element, V>));
 
-    {
-        let clear = fields.iter().map(|Field { name, kind, .. }| match kind {
-            Kind::Complex(Complex { as_map_storage, .. }) => quote! {
-                #as_map_storage::clear(&mut self.#name)
-            },
-            Kind::Simple => quote! {
-                self.#name = #option::None
-            },
-        });
+                merge.push(quote! {
+                    let stats = stats.merge(<#map_storage as #stats_storage_t<#element, V>>::stats(&self.#name));
+                });
+            }
+        }
+    }
 
-        output.items.extend(quote! {
+    quote! {
+        #[automatically_derived]
+        impl<V> #stats_storage_t<#ident, V> for #map_storage<V> where #(#bounds,)* {
             #[inline]
-            fn clear(&mut self) {
-                #(#clear;)*
+            fn stats(&self) -> #stats {
+                let stats = #stats::fixed(0, 0);
+                #(#merge)*
+                stats
             }
-        });
+        }
     }
+}
 
-    let field_decls = fields.iter().map(|Field { name, kind, .. }| match kind {
-        Kind::Complex(Complex { map_storage, .. }) => quote!(#name: #map_storage),
-        Kind::Simple => quote!(#name: #option<V>),
-    });
+/// Implement `ClassStorage`, where every variant is a class of its own.
+fn impl_class_storage(cx: &Ctxt<'_>, fields: &Fields<'_>, map_storage: &syn::Ident) -> TokenStream {
+    let ident = &cx.ast.ident;
+    let lt = cx.lt;
 
-    let Output { impls, items } = output;
+    let class_storage_t = cx.toks.class_storage_t();
+    let map_storage_t = cx.toks.map_storage_t();
+    let option = cx.toks.option();
+    let storage_iter = cx.toks.storage_iter();
 
-    let map_storage_impl = quote! {
-        #vis struct #type_name<V> {
-            #(#field_decls,)*
+    let mut len = Vec::new();
+    let mut clear = Vec::new();
+
+    for Field {
+        index, name, kind, ..
+    } in fields
+    {
+        match kind {
+            Kind::Simple => {
+                len.push(quote!(#index => usize::from(#option::is_some(&self.#name))));
+                clear.push(quote!(#index => self.#name = #option::None));
+            }
+            Kind::Complex(Complex { as_map_storage, .. }) => {
+                len.push(quote!(#index => #as_map_storage::len(&self.#name)));
+                clear.push(quote!(#index => #as_map_storage::clear(&mut self.#name)));
+            }
         }
+    }
 
+    quote! {
         #[automatically_derived]
-        impl<V> #map_storage_t<#ident, V> for #type_name<V> {
-            #items
-        }
+        impl<V> #class_storage_t<#ident, V> for #map_storage<V> {
+            type ClassIter<#lt> = <Self as #map_storage_t<#ident, V>>::Iter<#lt> where V: #lt;
 
-        #impls
-    };
+            #[inline]
+            fn class_len(&self, class: usize) -> usize {
+                match class {
+                    #(#len,)*
+                    _ => 0,
+                }
+            }
 
-    Ok((type_name, map_storage_impl))
+            #[inline]
+            fn clear_class(&mut self, class: usize) {
+                match class {
+                    #(#clear,)*
+                    _ => {}
+                }
+            }
+
+            #[inline]
+            fn iter_class(&self, class: usize) -> Self::ClassIter<'_> {
+                #storage_iter::restrict(#map_storage_t::iter(self), class)
+            }
+        }
+    }
 }
 
 /// Implement `SetStorage` implementation.
-fn impl_set_storage(cx: &Ctxt<'_>, fields: &Fields<'_>) -> Result<(syn::Ident, TokenStream), ()> {
+fn impl_set_storage(
+    cx: &Ctxt<'_>,
+    opts: &Opts,
+    fields: &Fields<'_>,
+) -> Result<(syn::Ident, TokenStream), ()> {
+    let repr = opts.storage_repr(false);
     let vis = &cx.ast.vis;
     let ident = &cx.ast.ident;
 
@@ -440,6 +1476,41 @@ fn impl_set_storage(cx: &Ctxt<'_>, fields: &Fields<'_>) -> Result<(syn::Ident, T
     set_storage_iter(cx, "Iter", fields, &mut output)?;
     set_storage_into_iter(cx, "IntoIter", fields, &mut output)?;
 
+    {
+        let storage_remaining_t = cx.toks.storage_remaining_t();
+        let remaining = fields.remaining(cx);
+        let end = fields.len();
+
+        let present = fields.iter().map(
+            |Field {
+                 index, name, kind, ..
+             }| {
+                let present = match kind {
+                    Kind::Complex(Complex { as_set_storage, .. }) => {
+                        quote!(!#as_set_storage::is_empty(&self.#name))
+                    }
+                    Kind::Simple => quote!(self.#name),
+                };
+
+                quote!(present = #storage_remaining_t::retain(present, #index, #present);)
+            },
+        );
+
+        output.impls.extend(quote! {
+            #[automatically_derived]
+            impl #type_name {
+                /// The variants which have values, so that iterators can skip
+                /// the empty ones without polling them.
+                #[inline]
+                fn __present(&self) -> #remaining {
+                    let mut present = <#remaining as #storage_remaining_t>::all(#end);
+                    #(#present)*
+                    present
+                }
+            }
+        });
+    }
+
     {
         let partial_eq_t = cx.toks.partial_eq_t();
         let eq_t = cx.toks.eq_t();
@@ -625,6 +1696,41 @@ fn impl_set_storage(cx: &Ctxt<'_>, fields: &Fields<'_>) -> Result<(syn::Ident, T
         });
     }
 
+    {
+        let option = cx.toks.option();
+
+        let find = fields.iter().map(
+            |Field {
+                 var,
+                 name,
+                 kind,
+                 ..
+             }| match kind {
+                Kind::Complex(Complex { as_set_storage, .. }) => quote! {
+                    if let #option::Some(k) = #as_set_storage::find(&self.#name, |k| f(#ident::#var(k))) {
+                        return #option::Some(#ident::#var(k));
+                    }
+                },
+                Kind::Simple => quote! {
+                    if self.#name && f(#ident::#var) {
+                        return #option::Some(#ident::#var);
+                    }
+                },
+            },
+        );
+
+        output.items.extend(quote! {
+            #[inline]
+            fn find<F>(&self, mut f: F) -> #option<#ident>
+            where
+                F: FnMut(#ident) -> bool
+            {
+                #(#find)*
+                #option::None
+            }
+        });
+    }
+
     {
         let clear = fields.iter().map(|Field { name, kind, .. }| match kind {
             Kind::Complex(Complex { as_set_storage, .. }) => quote! {
@@ -648,24 +1754,67 @@ fn impl_set_storage(cx: &Ctxt<'_>, fields: &Fields<'_>) -> Result<(syn::Ident, T
         Kind::Simple => quote!(#name: bool),
     });
 
+    let class_storage_impl = impl_set_class_storage(cx, fields, &type_name);
+
     let Output { impls, items } = output;
 
+    let debug_impl = debug::impl_set(cx, ident, &type_name);
+
     let map_storage_impl = quote! {
+        #repr
         #vis struct #type_name {
             #(#field_decls,)*
         }
 
+        #debug_impl
+
         #[automatically_derived]
         impl #set_storage_t<#ident> for #type_name {
             #items
         }
 
         #impls
+        #class_storage_impl
     };
 
     Ok((type_name, map_storage_impl))
 }
 
+/// Implement `set::ClassStorage`, where every variant is a class of its own.
+fn impl_set_class_storage(
+    cx: &Ctxt<'_>,
+    fields: &Fields<'_>,
+    set_storage: &syn::Ident,
+) -> TokenStream {
+    let ident = &cx.ast.ident;
+
+    let set_class_storage_t = cx.toks.set_class_storage_t();
+
+    let contains = fields.iter().map(
+        |Field {
+             index, name, kind, ..
+         }| match kind {
+            Kind::Simple => quote!(#index => self.#name),
+            Kind::Complex(Complex { as_set_storage, .. }) => {
+                quote!(#index => !#as_set_storage::is_empty(&self.#name))
+            }
+        },
+    );
+
+    quote! {
+        #[automatically_derived]
+        impl #set_class_storage_t<#ident> for #set_storage {
+            #[inline]
+            fn contains_class(&self, class: usize) -> bool {
+                match class {
+                    #(#contains,)*
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
 /// Build iterator next.
 fn build_iter_next(
     cx: &Ctxt<'_>,
@@ -759,8 +1908,10 @@ fn map_storage_iter(
     let vis = &cx.ast.vis;
 
     let option = cx.toks.option();
-    let iterator_t = cx.toks.iterator_t();
-    let double_ended_iterator_t = cx.toks.double_ended_iterator_t();
+    let storage_iter = cx.toks.storage_iter();
+    let remaining = fields.remaining(cx);
+    let storage_step_back_t = cx.toks.storage_step_back_t();
+    let storage_step_t = cx.toks.storage_step_t();
     let clone_t = cx.toks.clone_t();
 
     let mut step_forward = IteratorNext::default();
@@ -801,8 +1952,6 @@ fn map_storage_iter(
 
     output.impls.extend(quote! {
         #vis struct #type_name<#lt, V> where V: #lt {
-            start: usize,
-            end: usize,
             #(#field_decls,)*
         }
 
@@ -811,42 +1960,38 @@ fn map_storage_iter(
             #[inline]
             fn clone(&self) -> Self {
                 Self {
-                    start: self.start,
-                    end: self.end,
                     #(#names: #clone_t::clone(&self.#names),)*
                 }
             }
         }
 
         #[automatically_derived]
-        impl<#lt, V> #iterator_t for #type_name<#lt, V> where V: #lt {
+        impl<#lt, V> #storage_step_t for #type_name<#lt, V> where V: #lt {
             type Item = (#ident, &#lt V);
 
             #[inline]
-            fn next(&mut self) -> #option<Self::Item> {
+            fn step(&mut self, index: usize) -> #option<Self::Item> {
                 #step_forward
                 #option::None
             }
         }
 
         #[automatically_derived]
-        impl<#lt, V> #double_ended_iterator_t for #type_name<#lt, V> #double_ended_where_clause {
+        impl<#lt, V> #storage_step_back_t for #type_name<#lt, V> #double_ended_where_clause {
             #[inline]
-            fn next_back(&mut self) -> #option<Self::Item> {
+            fn step_back(&mut self, index: usize) -> #option<Self::Item> {
                 #step_backward
                 #option::None
             }
         }
     });
 
-    let end = fields.len();
-
     output.items.extend(quote! {
-        type #assoc_type<#lt> = #type_name<#lt, V> where V: #lt;
+        type #assoc_type<#lt> = #storage_iter<#type_name<#lt, V>, #remaining> where V: #lt;
 
         #[inline]
         fn iter(&self) -> Self::#assoc_type<'_> {
-            #type_name { start: 0, end: #end, #(#init,)* }
+            #storage_iter::with_remaining(Self::__present(self), #type_name { #(#init,)* })
         }
     });
 
@@ -873,6 +2018,10 @@ fn map_storage_keys(
     let iterator_t = cx.toks.iterator_t();
     let mem = cx.toks.mem();
     let option = cx.toks.option();
+    let storage_iter = cx.toks.storage_iter();
+    let remaining = fields.remaining(cx);
+    let storage_step_back_t = cx.toks.storage_step_back_t();
+    let storage_step_t = cx.toks.storage_step_t();
 
     let mut step_forward = IteratorNext::default();
     let mut step_backward = IteratorNextBack::default();
@@ -951,8 +2100,6 @@ fn map_storage_keys(
 
     output.impls.extend(quote! {
         #vis struct #type_name<#lt, V> where V: #lt {
-            start: usize,
-            end: usize,
             #(#field_decls,)*
         }
 
@@ -961,42 +2108,38 @@ fn map_storage_keys(
             #[inline]
             fn clone(&self) -> Self {
                 Self {
-                    start: self.start,
-                    end: self.end,
                     #(#names: #clone_t::clone(&self.#names),)*
                 }
             }
         }
 
         #[automatically_derived]
-        impl<#lt, V> #iterator_t for #type_name<#lt, V> where V: #lt {
+        impl<#lt, V> #storage_step_t for #type_name<#lt, V> where V: #lt {
             type Item = #ident;
 
             #[inline]
-            fn next(&mut self) -> #option<Self::Item> {
+            fn step(&mut self, index: usize) -> #option<Self::Item> {
                 #step_forward
                 #option::None
             }
         }
 
         #[automatically_derived]
-        impl<#lt, V> #double_ended_iterator_t for #type_name<#lt, V> #double_ended_where_clause {
+        impl<#lt, V> #storage_step_back_t for #type_name<#lt, V> #double_ended_where_clause {
             #[inline]
-            fn next_back(&mut self) -> #option<Self::Item> {
+            fn step_back(&mut self, index: usize) -> #option<Self::Item> {
                 #step_backward
                 #option::None
             }
         }
     });
 
-    let end = fields.len();
-
     output.items.extend(quote! {
-        type #assoc_type<#lt> = #type_name<#lt, V> where V: #lt;
+        type #assoc_type<#lt> = #storage_iter<#type_name<#lt, V>, #remaining> where V: #lt;
 
         #[inline]
         fn keys(&self) -> Self::#assoc_type<'_> {
-            #type_name { start: 0, end: #end, #(#init,)* }
+            #storage_iter::with_remaining(Self::__present(self), #type_name { #(#init,)* })
         }
     });
 
@@ -1020,6 +2163,10 @@ fn map_storage_values(
     let double_ended_iterator_t = cx.toks.double_ended_iterator_t();
     let iterator_t = cx.toks.iterator_t();
     let option = cx.toks.option();
+    let storage_iter = cx.toks.storage_iter();
+    let remaining = fields.remaining(cx);
+    let storage_step_back_t = cx.toks.storage_step_back_t();
+    let storage_step_t = cx.toks.storage_step_t();
 
     let mut step_forward = IteratorNext::default();
     let mut step_backward = IteratorNextBack::default();
@@ -1097,8 +2244,6 @@ fn map_storage_values(
 
     output.impls.extend(quote! {
         #vis struct #type_name<#lt, V> where V: #lt {
-            start: usize,
-            end: usize,
             #(#field_decls,)*
         }
 
@@ -1107,42 +2252,38 @@ fn map_storage_values(
             #[inline]
             fn clone(&self) -> Self {
                 Self {
-                    start: self.start,
-                    end: self.end,
                     #(#names: #clone_t::clone(&self.#names),)*
                 }
             }
         }
 
         #[automatically_derived]
-        impl<#lt, V> #iterator_t for #type_name<#lt, V> where V: #lt {
+        impl<#lt, V> #storage_step_t for #type_name<#lt, V> where V: #lt {
             type Item = &#lt V;
 
             #[inline]
-            fn next(&mut self) -> #option<Self::Item> {
+            fn step(&mut self, index: usize) -> #option<Self::Item> {
                 #step_forward
                 #option::None
             }
         }
 
         #[automatically_derived]
-        impl<#lt, V> #double_ended_iterator_t for #type_name<#lt, V> #double_ended_where_clause {
+        impl<#lt, V> #storage_step_back_t for #type_name<#lt, V> #double_ended_where_clause {
             #[inline]
-            fn next_back(&mut self) -> #option<Self::Item> {
+            fn step_back(&mut self, index: usize) -> #option<Self::Item> {
                 #step_backward
                 #option::None
             }
         }
     });
 
-    let end = fields.len();
-
     output.items.extend(quote! {
-        type #assoc_type<#lt> = #type_name<#lt, V> where V: #lt;
+        type #assoc_type<#lt> = #storage_iter<#type_name<#lt, V>, #remaining> where V: #lt;
 
         #[inline]
         fn values(&self) -> Self::#assoc_type<'_> {
-            #type_name { start: 0, end: #end, #(#init,)* }
+            #storage_iter::with_remaining(Self::__present(self), #type_name { #(#init,)* })
         }
     });
 
@@ -1163,9 +2304,11 @@ fn map_storage_iter_mut(
     let lt = cx.lt;
     let vis = &cx.ast.vis;
 
-    let double_ended_iterator_t = cx.toks.double_ended_iterator_t();
-    let iterator_t = cx.toks.iterator_t();
     let option = cx.toks.option();
+    let storage_iter = cx.toks.storage_iter();
+    let remaining = fields.remaining(cx);
+    let storage_step_back_t = cx.toks.storage_step_back_t();
+    let storage_step_t = cx.toks.storage_step_t();
 
     let mut step_forward = IteratorNext::default();
     let mut step_backward = IteratorNextBack::default();
@@ -1208,40 +2351,36 @@ fn map_storage_iter_mut(
 
     output.impls.extend(quote! {
         #vis struct #type_name<#lt, V> where V: #lt {
-            start: usize,
-            end: usize,
             #(#field_decls,)*
         }
 
         #[automatically_derived]
-        impl<#lt, V> #iterator_t for #type_name<#lt, V> where V: #lt {
+        impl<#lt, V> #storage_step_t for #type_name<#lt, V> where V: #lt {
             type Item = (#ident, &#lt mut V);
 
             #[inline]
-            fn next(&mut self) -> #option<Self::Item> {
+            fn step(&mut self, index: usize) -> #option<Self::Item> {
                 #step_forward
                 #option::None
             }
         }
 
         #[automatically_derived]
-        impl<#lt, V> #double_ended_iterator_t for #type_name<#lt, V> #double_ended_where {
+        impl<#lt, V> #storage_step_back_t for #type_name<#lt, V> #double_ended_where {
             #[inline]
-            fn next_back(&mut self) -> #option<Self::Item> {
+            fn step_back(&mut self, index: usize) -> #option<Self::Item> {
                 #step_backward
                 #option::None
             }
         }
     });
 
-    let end = fields.len();
-
     output.items.extend(quote! {
-        type #assoc_type<#lt> = #type_name<#lt, V> where V: #lt;
+        type #assoc_type<#lt> = #storage_iter<#type_name<#lt, V>, #remaining> where V: #lt;
 
         #[inline]
         fn iter_mut(&mut self) -> Self::#assoc_type<'_> {
-            #type_name { start: 0, end: #end, #(#init,)* }
+            #storage_iter::with_remaining(Self::__present(self), #type_name { #(#init,)* })
         }
     });
 
@@ -1262,6 +2401,10 @@ fn map_storage_values_mut(
     let vis = &cx.ast.vis;
 
     let option = cx.toks.option();
+    let storage_iter = cx.toks.storage_iter();
+    let remaining = fields.remaining(cx);
+    let storage_step_back_t = cx.toks.storage_step_back_t();
+    let storage_step_t = cx.toks.storage_step_t();
     let iterator_t = cx.toks.iterator_t();
     let double_ended_iterator_t = cx.toks.double_ended_iterator_t();
 
@@ -1340,40 +2483,36 @@ fn map_storage_values_mut(
 
     output.impls.extend(quote! {
         #vis struct #type_name<#lt, V> where V: #lt {
-            start: usize,
-            end: usize,
             #(#field_decls,)*
         }
 
         #[automatically_derived]
-        impl<#lt, V> #iterator_t for #type_name<#lt, V> where V: #lt {
+        impl<#lt, V> #storage_step_t for #type_name<#lt, V> where V: #lt {
             type Item = &#lt mut V;
 
             #[inline]
-            fn next(&mut self) -> #option<Self::Item> {
+            fn step(&mut self, index: usize) -> #option<Self::Item> {
                 #step_forward
                 #option::None
             }
         }
 
         #[automatically_derived]
-        impl<#lt, V> #double_ended_iterator_t for #type_name<#lt, V> #double_ended_where_clause {
+        impl<#lt, V> #storage_step_back_t for #type_name<#lt, V> #double_ended_where_clause {
             #[inline]
-            fn next_back(&mut self) -> #option<Self::Item> {
+            fn step_back(&mut self, index: usize) -> #option<Self::Item> {
                 #step_backward
                 #option::None
             }
         }
     });
 
-    let end = fields.len();
-
     output.items.extend(quote! {
-        type #assoc_type<#lt> = #type_name<#lt, V> where V: #lt;
+        type #assoc_type<#lt> = #storage_iter<#type_name<#lt, V>, #remaining> where V: #lt;
 
         #[inline]
         fn values_mut(&mut self) -> Self::#assoc_type<'_> {
-            #type_name { start: 0, end: #end, #(#init,)* }
+            #storage_iter::with_remaining(Self::__present(self), #type_name { #(#init,)* })
         }
     });
 
@@ -1394,9 +2533,11 @@ fn map_storage_into_iter(
     let vis = &cx.ast.vis;
 
     let option = cx.toks.option();
+    let storage_iter = cx.toks.storage_iter();
+    let remaining = fields.remaining(cx);
+    let storage_step_back_t = cx.toks.storage_step_back_t();
+    let storage_step_t = cx.toks.storage_step_t();
     let clone_t = cx.toks.clone_t();
-    let iterator_t = cx.toks.iterator_t();
-    let double_ended_iterator_t = cx.toks.double_ended_iterator_t();
 
     let mut step_forward = IteratorNext::default();
     let mut step_backward = IteratorNextBack::default();
@@ -1438,8 +2579,6 @@ fn map_storage_into_iter(
 
     output.impls.extend(quote! {
         #vis struct #type_name<V> {
-            start: usize,
-            end: usize,
             #(#field_decls,)*
         }
 
@@ -1448,42 +2587,38 @@ fn map_storage_into_iter(
             #[inline]
             fn clone(&self) -> Self {
                 Self {
-                    start: self.start,
-                    end: self.end,
                     #(#names: #clone_t::clone(&self.#names),)*
                 }
             }
         }
 
         #[automatically_derived]
-        impl<V> #iterator_t for #type_name<V> {
+        impl<V> #storage_step_t for #type_name<V> {
             type Item = (#ident, V);
 
             #[inline]
-            fn next(&mut self) -> Option<Self::Item> {
+            fn step(&mut self, index: usize) -> #option<Self::Item> {
                 #step_forward
                 #option::None
             }
         }
 
         #[automatically_derived]
-        impl<V> #double_ended_iterator_t for #type_name<V> #double_ended_where {
+        impl<V> #storage_step_back_t for #type_name<V> #double_ended_where {
             #[inline]
-            fn next_back(&mut self) -> #option<Self::Item> {
+            fn step_back(&mut self, index: usize) -> #option<Self::Item> {
                 #step_backward
                 #option::None
             }
         }
     });
 
-    let end = fields.len();
-
     output.items.extend(quote! {
-        type #assoc_type = #type_name<V>;
+        type #assoc_type = #storage_iter<#type_name<V>, #remaining>;
 
         #[inline]
         fn into_iter(self) -> Self::#assoc_type {
-            #type_name { start: 0, end: #end, #(#init,)* }
+            #storage_iter::with_remaining(Self::__present(&self), #type_name { #(#init,)* })
         }
     });
 
@@ -1510,6 +2645,10 @@ fn set_storage_iter(
     let iterator_t = cx.toks.iterator_t();
     let mem = cx.toks.mem();
     let option = cx.toks.option();
+    let storage_iter = cx.toks.storage_iter();
+    let remaining = fields.remaining(cx);
+    let storage_step_back_t = cx.toks.storage_step_back_t();
+    let storage_step_t = cx.toks.storage_step_t();
 
     let mut step_forward = IteratorNext::default();
     let mut step_backward = IteratorNextBack::default();
@@ -1583,8 +2722,6 @@ fn set_storage_iter(
 
     output.impls.extend(quote! {
         #vis struct #type_name<#lt> {
-            start: usize,
-            end: usize,
             #(#field_decls,)*
         }
 
@@ -1593,42 +2730,38 @@ fn set_storage_iter(
             #[inline]
             fn clone(&self) -> Self {
                 Self {
-                    start: self.start,
-                    end: self.end,
                     #(#names: #clone_t::clone(&self.#names),)*
                 }
             }
         }
 
         #[automatically_derived]
-        impl<#lt> #iterator_t for #type_name<#lt> {
+        impl<#lt> #storage_step_t for #type_name<#lt> {
             type Item = #ident;
 
             #[inline]
-            fn next(&mut self) -> #option<Self::Item> {
+            fn step(&mut self, index: usize) -> #option<Self::Item> {
                 #step_forward
                 #option::None
             }
         }
 
         #[automatically_derived]
-        impl<#lt> #double_ended_iterator_t for #type_name<#lt> #double_ended_where_clause {
+        impl<#lt> #storage_step_back_t for #type_name<#lt> #double_ended_where_clause {
             #[inline]
-            fn next_back(&mut self) -> #option<Self::Item> {
+            fn step_back(&mut self, index: usize) -> #option<Self::Item> {
                 #step_backward
                 #option::None
             }
         }
     });
 
-    let end = fields.len();
-
     output.items.extend(quote! {
-        type #assoc_type<#lt> = #type_name<#lt>;
+        type #assoc_type<#lt> = #storage_iter<#type_name<#lt>, #remaining>;
 
         #[inline]
         fn iter(&self) -> Self::#assoc_type<'_> {
-            #type_name { start: 0, end: #end, #(#init,)* }
+            #storage_iter::with_remaining(Self::__present(self), #type_name { #(#init,)* })
         }
     });
 
@@ -1654,6 +2787,10 @@ fn set_storage_into_iter(
     let iterator_t = cx.toks.iterator_t();
     let mem = cx.toks.mem();
     let option = cx.toks.option();
+    let storage_iter = cx.toks.storage_iter();
+    let remaining = fields.remaining(cx);
+    let storage_step_back_t = cx.toks.storage_step_back_t();
+    let storage_step_t = cx.toks.storage_step_t();
 
     let mut step_forward = IteratorNext::default();
     let mut step_backward = IteratorNextBack::default();
@@ -1726,8 +2863,6 @@ fn set_storage_into_iter(
 
     output.impls.extend(quote! {
         #vis struct #type_name {
-            start: usize,
-            end: usize,
             #(#field_decls,)*
         }
     });
@@ -1743,8 +2878,6 @@ fn set_storage_into_iter(
                 #[inline]
                 fn clone(&self) -> Self {
                     Self {
-                        start: self.start,
-                        end: self.end,
                         #(#names: #clone_t::clone(&self.#names),)*
                     }
                 }
@@ -1754,11 +2887,11 @@ fn set_storage_into_iter(
 
     output.impls.extend(quote! {
         #[automatically_derived]
-        impl #iterator_t for #type_name {
+        impl #storage_step_t for #type_name {
             type Item = #ident;
 
             #[inline]
-            fn next(&mut self) -> #option<Self::Item> {
+            fn step(&mut self, index: usize) -> #option<Self::Item> {
                 #step_forward
                 #option::None
             }
@@ -1769,23 +2902,21 @@ fn set_storage_into_iter(
 
     output.impls.extend(quote! {
         #[automatically_derived]
-        impl #double_ended_iterator_t for #type_name #double_ended_where_clause {
+        impl #storage_step_back_t for #type_name #double_ended_where_clause {
             #[inline]
-            fn next_back(&mut self) -> #option<Self::Item> {
+            fn step_back(&mut self, index: usize) -> #option<Self::Item> {
                 #step_backward
                 #option::None
             }
         }
     });
 
-    let end = fields.len();
-
     output.items.extend(quote! {
-        type #assoc_type = #type_name;
+        type #assoc_type = #storage_iter<#type_name, #remaining>;
 
         #[inline]
         fn into_iter(self) -> Self::#assoc_type {
-            #type_name { start: 0, end: #end, #(#init,)* }
+            #storage_iter::with_remaining(Self::__present(&self), #type_name { #(#init,)* })
         }
     });
 
@@ -1802,13 +2933,9 @@ impl ToTokens for IteratorNext {
         let iter_next = &self.next;
 
         tokens.extend(quote! {
-            while self.start < self.end {
-                match self.start {
-                    #(#iter_next,)*
-                    _ => break,
-                }
-
-                self.start = usize::min(self.start.wrapping_add(1), self.end);
+            match index {
+                #(#iter_next,)*
+                _ => {}
             }
         });
     }
@@ -1835,15 +2962,9 @@ impl ToTokens for IteratorNextBack {
         let iter_next = &self.next;
 
         tokens.extend(quote! {
-            while self.start < self.end {
-                let next = self.end.wrapping_sub(1);
-
-                match next {
-                    #(#iter_next,)*
-                    _ => break,
-                }
-
-                self.end = usize::max(next, self.start);
+            match index {
+                #(#iter_next,)*
+                _ => {}
             }
         });
     }
@@ -1891,7 +3012,7 @@ fn map_storage_entry(
 
         match kind {
             Kind::Simple => {
-                init.push(quote!( #pattern => option_to_entry(&mut self.#name, key) ));
+                init.push(quote!( #pattern => option_to_entry(&mut self.#name, *key) ));
             }
             Kind::Complex(Complex {
                 element,
@@ -1900,41 +3021,41 @@ fn map_storage_entry(
             }) => {
                 let as_map_storage = quote!(<#map_storage as #map_storage_t<#element, V>>);
 
-                occupied_variant.push(quote!( #name(#as_map_storage::Occupied<#lt>) ));
-                vacant_variant.push(quote!( #name(#as_map_storage::Vacant<#lt>) ));
+                occupied_variant.push(quote!( #name(#ident, #as_map_storage::Occupied<#lt>) ));
+                vacant_variant.push(quote!( #name(#ident, #as_map_storage::Vacant<#lt>) ));
+
+                let as_vacant_entry =
+                    quote!(<#as_map_storage::Vacant<#lt> as #vacant_entry_t<#lt, #element, V>>);
+                let as_occupied_entry =
+                    quote!(<#as_map_storage::Occupied<#lt> as #occupied_entry_t<#lt, #element, V>>);
 
+                // NB: The key is reconstructed from the nested entry, so that
+                // it's canonical for the storage of the nested key.
                 init.push(quote! {
-                    #pattern(key) => match #map_storage_t::entry(&mut self.#name, key) {
-                        #entry_enum::Occupied(entry) => #entry_enum::Occupied(OccupiedEntry::#name(entry)),
-                        #entry_enum::Vacant(entry) => #entry_enum::Vacant(VacantEntry::#name(entry)),
+                    #pattern(key) => match #map_storage_t::entry_ref(&mut self.#name, key) {
+                        #entry_enum::Occupied(entry) => #entry_enum::Occupied(OccupiedEntry::#name(#pattern(<_ as #occupied_entry_t<'_, #element, V>>::key(&entry)), entry)),
+                        #entry_enum::Vacant(entry) => #entry_enum::Vacant(VacantEntry::#name(#pattern(<_ as #vacant_entry_t<'_, #element, V>>::key(&entry)), entry)),
                     }
                 });
 
-                let as_vacant_entry =
-                    quote!(<#as_map_storage::Vacant<#lt> as #vacant_entry_t<#lt, #element, V>>);
-
-                vacant_key.push(
-                    quote!( VacantEntry::#name(entry) => #pattern(#as_vacant_entry::key(entry)) ),
-                );
+                vacant_key.push(quote!( VacantEntry::#name(key, _) => key ));
                 vacant_insert.push(
-                    quote!( VacantEntry::#name(entry) => #as_vacant_entry::insert(entry, value) ),
+                    quote!( VacantEntry::#name(_, entry) => #as_vacant_entry::insert(entry, value) ),
                 );
 
-                let as_occupied_entry =
-                    quote!(<#as_map_storage::Occupied<#lt> as #occupied_entry_t<#lt, #element, V>>);
-
-                occupied_key.push(quote!( OccupiedEntry::#name(entry) => #pattern(#as_occupied_entry::key(entry)) ));
-                occupied_get
-                    .push(quote!( OccupiedEntry::#name(entry) => #as_occupied_entry::get(entry) ));
+                occupied_key.push(quote!( OccupiedEntry::#name(key, _) => key ));
+                occupied_get.push(
+                    quote!( OccupiedEntry::#name(_, entry) => #as_occupied_entry::get(entry) ),
+                );
                 occupied_get_mut.push(
-                    quote!( OccupiedEntry::#name(entry) => #as_occupied_entry::get_mut(entry) ),
+                    quote!( OccupiedEntry::#name(_, entry) => #as_occupied_entry::get_mut(entry) ),
                 );
                 occupied_into_mut.push(
-                    quote!( OccupiedEntry::#name(entry) => #as_occupied_entry::into_mut(entry) ),
+                    quote!( OccupiedEntry::#name(_, entry) => #as_occupied_entry::into_mut(entry) ),
                 );
-                occupied_insert.push(quote!( OccupiedEntry::#name(entry) => #as_occupied_entry::insert(entry, value) ));
+                occupied_insert.push(quote!( OccupiedEntry::#name(_, entry) => #as_occupied_entry::insert(entry, value) ));
                 occupied_remove.push(
-                    quote!( OccupiedEntry::#name(entry) => #as_occupied_entry::remove(entry) ),
+                    quote!( OccupiedEntry::#name(_, entry) => #as_occupied_entry::remove(entry) ),
                 );
             }
         }
@@ -1946,6 +3067,7 @@ fn map_storage_entry(
             inner: #option_bucket_none<#lt, V>,
         }
 
+        #[automatically_derived]
         impl<#lt, V> SimpleVacantEntry<#lt, V> {
             #[inline]
             fn insert(self, value: V) -> &#lt mut V {
@@ -1958,6 +3080,7 @@ fn map_storage_entry(
             inner: #option_bucket_some<#lt, V>,
         }
 
+        #[automatically_derived]
         impl<#lt, V> SimpleOccupiedEntry<#lt, V> {
             #[inline]
             fn get(&self) -> &V {
@@ -1999,8 +3122,13 @@ fn map_storage_entry(
         impl<#lt, V> #vacant_entry_t<#lt, #ident, V> for VacantEntry<#lt, V> {
             #[inline]
             fn key(&self) -> #ident {
+                *#vacant_entry_t::key_ref(self)
+            }
+
+            #[inline]
+            fn key_ref(&self) -> &#ident {
                 match self {
-                    VacantEntry::Simple(entry) => entry.key,
+                    VacantEntry::Simple(entry) => &entry.key,
                     #(#vacant_key,)*
                 }
             }
@@ -2018,8 +3146,13 @@ fn map_storage_entry(
         impl<#lt, V> #occupied_entry_t<#lt, #ident, V> for OccupiedEntry<#lt, V> {
             #[inline]
             fn key(&self) -> #ident {
+                *#occupied_entry_t::key_ref(self)
+            }
+
+            #[inline]
+            fn key_ref(&self) -> &#ident {
                 match self {
-                    OccupiedEntry::Simple(entry) => entry.key,
+                    OccupiedEntry::Simple(entry) => &entry.key,
                     #(#occupied_key,)*
                 }
             }
@@ -2080,6 +3213,11 @@ fn map_storage_entry(
 
         #[inline]
         fn entry(&mut self, key: #ident) -> #entry_enum<'_, Self, #ident, V> {
+            #map_storage_t::entry_ref(self, &key)
+        }
+
+        #[inline]
+        fn entry_ref(&mut self, key: &#ident) -> #entry_enum<'_, Self, #ident, V> {
             match key {
                 #(#init,)*
             }
@@ -2125,6 +3263,9 @@ pub(crate) struct Complex<'a> {
     pub(crate) set_storage: TokenStream,
     /// `<<E as Key>::SetStorage as SetStorage<E>>` (`E` = type of variant field)
     pub(crate) as_set_storage: TokenStream,
+    /// Whether the storage is the one provided by `E` itself, rather than one
+    /// selected through `#[key(values(...))]` or `#[key(dynamic = "...")]`.
+    pub(crate) nested: bool,
 }
 
 #[derive(Default)]
@@ -2156,6 +3297,27 @@ impl<'a> Fields<'a> {
     fn len(&self) -> usize {
         self.fields.len()
     }
+
+    /// The type tracking which variants remain in generated iterators, which
+    /// is the narrowest integer mask that has a bit for every variant.
+    fn remaining(&self, cx: &Ctxt<'_>) -> TokenStream {
+        match self.fields.len() {
+            0..=8 => quote!(u8),
+            9..=16 => quote!(u16),
+            17..=32 => quote!(u32),
+            33..=64 => quote!(u64),
+            65..=128 => quote!(u128),
+            len => {
+                let storage_span = cx.toks.storage_span();
+
+                if len <= usize::from(u8::MAX) {
+                    quote!(#storage_span<u8>)
+                } else {
+                    quote!(#storage_span<usize>)
+                }
+            }
+        }
+    }
 }
 
 impl<'b, 'a> IntoIterator for &'b Fields<'a> {
@@ -1,4 +1,7 @@
-use crate::context::{Ctxt, Opts};
+use syn::parse::Parse;
+use syn::spanned::Spanned;
+
+use crate::context::{Ctxt, Opts, VariantOpts};
 use crate::symbol;
 
 /// Parse attributes.
@@ -11,8 +14,110 @@ pub(crate) fn parse(cx: &Ctxt<'_>) -> Result<Opts, ()> {
         }
 
         let result = attr.parse_nested_meta(|input| {
-            if input.path == symbol::BITSET {
+            if input.path == symbol::ACCESSORS {
+                opts.accessors = Some(input.input.span());
+            } else if input.path == symbol::ALIGN {
+                let align: syn::LitInt = input.value()?.parse()?;
+
+                if !align.base10_parse::<u32>()?.is_power_of_two() {
+                    return Err(syn::Error::new(
+                        align.span(),
+                        "#[key(align = N)] must be a power of two",
+                    ));
+                }
+
+                opts.align = Some(align);
+            } else if input.path == symbol::BITSET {
                 opts.bitset = Some(input.input.span());
+            } else if input.path == symbol::BIT_CONSTS {
+                opts.bit_consts = Some(input.input.span());
+            } else if input.path == symbol::BITFLAGS {
+                opts.bitflags = Some(input.value()?.parse()?);
+            } else if input.path == symbol::GROUPS {
+                opts.groups = Some(input.input.span());
+            } else if input.path == symbol::ITER {
+                opts.iter = Some(input.input.span());
+            } else if input.path == symbol::LAYOUT_TEST {
+                opts.layout_test = Some(input.input.span());
+            } else if input.path == symbol::MAPS_TO {
+                let content;
+                syn::parenthesized!(content in input.input);
+                let types = content.parse_terminated(syn::Type::parse, syn::Token![,])?;
+                opts.maps_to.extend(types);
+            } else if input.path == symbol::MASKED {
+                opts.masked = Some(input.input.span());
+            } else if input.path == symbol::PRIMITIVE {
+                opts.primitive = Some(input.value()?.parse()?);
+            } else if input.path == symbol::SPARSE {
+                opts.sparse = Some(input.input.span());
+            } else if input.path == symbol::TRANSPARENT {
+                opts.transparent = Some(input.input.span());
+            } else if input.path == symbol::VALUE_MODULE {
+                opts.value_module = Some(input.value()?.parse()?);
+            } else if input.path == symbol::VARIANTS {
+                let content;
+                syn::parenthesized!(content in input.input);
+                let idents = content.parse_terminated(syn::Ident::parse, syn::Token![,])?;
+                opts.variants = Some(idents.into_iter().collect());
+            } else {
+                return Err(syn::Error::new(input.input.span(), "Unsupported attribute"));
+            }
+
+            Ok(())
+        });
+
+        if let Err(error) = result {
+            cx.error(error);
+        }
+    }
+
+    Ok(opts)
+}
+
+/// Parse the `#[key(...)]` attributes of a variant.
+pub(crate) fn parse_variant_attrs(
+    cx: &Ctxt<'_>,
+    variant: &syn::Variant,
+) -> Result<VariantOpts, ()> {
+    let mut opts = VariantOpts::default();
+
+    for attr in &variant.attrs {
+        if attr.path() != symbol::KEY {
+            continue;
+        }
+
+        let result = attr.parse_nested_meta(|input| {
+            if input.path == symbol::DYNAMIC {
+                let string: syn::LitStr = input.value()?.parse()?;
+
+                if string.value() != "ordered" {
+                    return Err(syn::Error::new(
+                        string.span(),
+                        "#[key(dynamic = ...)] only supports \"ordered\"",
+                    ));
+                }
+
+                opts.dynamic = Some(input.path.span());
+            } else if input.path == symbol::LAZY {
+                opts.lazy = Some(input.path.span());
+            } else if input.path == symbol::OPTIONAL {
+                opts.optional = Some(input.path.span());
+            } else if input.path == symbol::VALUE {
+                let string: syn::LitStr = input.value()?.parse()?;
+                opts.value = Some(string.parse::<syn::Type>()?);
+            } else if input.path == symbol::VALUES {
+                let content;
+                syn::parenthesized!(content in input.input);
+                let exprs = content.parse_terminated(syn::Expr::parse, syn::Token![,])?;
+
+                if exprs.is_empty() {
+                    return Err(syn::Error::new(
+                        input.path.span(),
+                        "#[key(values(...))] requires at least one value",
+                    ));
+                }
+
+                opts.values = Some(exprs.into_iter().collect());
             } else {
                 return Err(syn::Error::new(input.input.span(), "Unsupported attribute"));
             }
@@ -22,6 +127,7 @@ pub(crate) fn parse(cx: &Ctxt<'_>) -> Result<Opts, ()> {
 
         if let Err(error) = result {
             cx.error(error);
+            return Err(());
         }
     }
 
@@ -0,0 +1,52 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{DataEnum, LitInt};
+
+use crate::context::Ctxt;
+use crate::unit_variants;
+
+/// Generate a module with a `pub const` mask for the bit of every variant, as
+/// it appears in the raw value of a bitset.
+pub(crate) fn implement(cx: &Ctxt<'_>, en: &DataEnum) -> Result<TokenStream, ()> {
+    let ident = &cx.ast.ident;
+    let vis = &cx.ast.vis;
+
+    let (ty, _) = unit_variants::determine_bits(cx, en)?;
+
+    let name = crate::snake_case(&ident.to_string());
+    let module = format_ident!("{}_bits", name);
+    let prefix = name.to_uppercase();
+
+    let module_doc = format!(
+        "Bit masks for the variants of [`{ident}`] in a bitset, generated by `#[key(bit_consts)]`."
+    );
+
+    let mut consts = Vec::with_capacity(en.variants.len());
+
+    for (index, variant) in en.variants.iter().enumerate() {
+        let var = &variant.ident;
+
+        let name = format_ident!(
+            "{}_{}_BIT",
+            prefix,
+            crate::snake_case(&var.to_string()).to_uppercase(),
+            span = var.span()
+        );
+
+        let mask = LitInt::new(&format!("{}", 1u128 << index), variant.span());
+        let doc = format!("The bit of [`{ident}::{var}`](super::{ident}::{var}).");
+
+        consts.push(quote! {
+            #[doc = #doc]
+            pub const #name: #ty = #mask;
+        });
+    }
+
+    Ok(quote! {
+        #[doc = #module_doc]
+        #vis mod #module {
+            #(#consts)*
+        }
+    })
+}
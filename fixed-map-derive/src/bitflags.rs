@@ -0,0 +1,63 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::DataEnum;
+
+use crate::context::Ctxt;
+
+/// Implement conversions between a set of a bitset key and the `bitflags` type
+/// given through `#[key(bitflags = T)]`, which has one flag for every variant
+/// named after it in screaming snake case.
+pub(crate) fn implement(cx: &Ctxt<'_>, en: &DataEnum, ty: &syn::Type) -> TokenStream {
+    let ident = &cx.ast.ident;
+
+    let from_t = cx.toks.from_t();
+    let panic = cx.toks.panic();
+    let set_t = cx.toks.set_t();
+
+    let flags = en
+        .variants
+        .iter()
+        .map(|variant| {
+            let var = &variant.ident;
+            let name = crate::snake_case(&var.to_string()).to_uppercase();
+            format_ident!("{}", name, span = var.span())
+        })
+        .collect::<Vec<_>>();
+
+    let ty_name = ty.to_token_stream().to_string();
+
+    let messages = en.variants.iter().zip(&flags).map(|(variant, flag)| {
+        let var = &variant.ident;
+        format!("`{ty_name}::{flag}` must be the bit of `{ident}::{var}` in a bitset")
+    });
+
+    let indexes = 0..en.variants.len() as u32;
+
+    quote! {
+        const _: () = {
+            #(
+                if <#ty>::#flags.bits() as u128 != 1u128 << #indexes {
+                    #panic!(#messages);
+                }
+            )*
+        };
+
+        #[automatically_derived]
+        impl #from_t<#set_t<#ident>> for #ty {
+            #[inline]
+            fn from(set: #set_t<#ident>) -> #ty {
+                <#ty>::from_bits_truncate(set.as_raw() as _)
+            }
+        }
+
+        #[automatically_derived]
+        impl #from_t<#ty> for #set_t<#ident> {
+            #[inline]
+            fn from(flags: #ty) -> #set_t<#ident> {
+                let mut mask = <#ty>::empty();
+                #(mask |= <#ty>::#flags;)*
+                #set_t::from_raw((flags & mask).bits() as _)
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::DataEnum;
+
+use crate::attrs;
+use crate::context::Ctxt;
+
+/// Build the `Key::CAPACITY` constant, which adds up the number of keys every
+/// enabled variant can hold.
+pub(crate) fn implement(cx: &Ctxt<'_>, en: &DataEnum) -> TokenStream {
+    let key_t = cx.toks.key_t();
+    let option = cx.toks.option();
+    let capacity_add = cx.toks.capacity_add();
+
+    let variants = en.variants.iter().map(|variant| {
+        let capacity = match &variant.fields {
+            syn::Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                let ty = &unnamed.unnamed[0].ty;
+
+                let variant_opts = attrs::parse_variant_attrs(cx, variant).unwrap_or_default();
+
+                if let Some(values) = &variant_opts.values {
+                    let len = values.len();
+                    quote!(#option::Some(#len))
+                } else if variant_opts.dynamic.is_some() || variant_opts.optional.is_some() {
+                    quote!(#option::None)
+                } else {
+                    quote!(<#ty as #key_t>::CAPACITY)
+                }
+            }
+            _ => quote!(#option::Some(1)),
+        };
+
+        quote!(let capacity = #capacity_add(capacity, #capacity);)
+    });
+
+    quote! {
+        const CAPACITY: #option<usize> = {
+            let capacity = #option::Some(0);
+            #(#variants)*
+            capacity
+        };
+    }
+}
@@ -1,7 +1,8 @@
 use core::cell::RefCell;
 use core::fmt;
 
-use proc_macro2::Span;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
 use syn::{DeriveInput, Path};
 
 // Builder function to use when constructing token.
@@ -50,39 +51,101 @@ macro_rules! toks {
 
 toks! {
     pub(crate) struct Toks<'a> {
+        any_value_key_t = [crate::any_value_map::AnyValueKey],
+        any_value_storage_t = [crate::any_value_map::AnyValueStorage],
+        any_value_variant_t = [crate::any_value_map::AnyValueVariant],
         array_into_iter = [core::array::IntoIter],
         bool_type = [core::primitive::bool],
+        capacity_add = [crate::macro_support::__storage_capacity_add],
+        class_storage_t = [crate::map::ClassStorage],
+        bit_index_t = [crate::raw::BitIndex],
         clone_t = [core::clone::Clone],
         copy_t = [core::marker::Copy],
+        debug_t = [core::fmt::Debug],
         double_ended_iterator_t = [core::iter::DoubleEndedIterator],
+        drain_storage_t = [crate::map::DrainStorage],
+        entries_storage_t = [crate::map::EntriesStorage],
         entry_enum = [crate::map::Entry],
         eq_t = [core::cmp::Eq],
+        extract_if_cursor_t = [crate::map::ExtractIfCursor],
+        extract_if_storage_t = [crate::map::ExtractIfStorage],
+        finite_key_t = [crate::key_iter::FiniteKey],
+        fmt_result = [core::fmt::Result],
+        formatter = [core::fmt::Formatter],
+        from_t = [core::convert::From],
+        get_disjoint_mut = [crate::macro_support::__storage_get_disjoint_mut],
         hash_t = [core::hash::Hash],
         hasher_t = [core::hash::Hasher],
         into_iterator_t = [core::iter::IntoIterator],
+        invalid_primitive = [crate::raw::InvalidPrimitive],
         iterator_cmp = [crate::macro_support::__storage_iterator_cmp],
         iterator_cmp_bool = [crate::macro_support::__storage_iterator_cmp_bool],
+        iterator_filter_map = [core::iter::FilterMap],
         iterator_flat_map = [core::iter::FlatMap],
         iterator_flatten = [core::iter::Flatten],
+        iterator_map = [core::iter::Map],
+        iterator_once = [core::iter::Once],
         iterator_partial_cmp = [crate::macro_support::__storage_iterator_partial_cmp],
         iterator_partial_cmp_bool = [crate::macro_support::__storage_iterator_partial_cmp_bool],
         iterator_t = [core::iter::Iterator],
+        iterator_zip = [core::iter::Zip],
+        key_group_t = [crate::KeyGroup],
+        key_index_t = [crate::raw::KeyIndex],
         key_t = [crate::Key],
+        lazy_map_storage = [crate::map::storage::LazyMapStorage],
+        layout_branch = [crate::layout::Branch],
+        map_t = [crate::Map],
+        maps_to_t = [crate::migrate::MapsTo],
+        masked_map_storage = [crate::map::storage::MaskedMapStorage],
         mem = [core::mem],
+        once = [core::iter::once],
         occupied_entry_t = [crate::map::OccupiedEntry],
+        offset_of = [crate::macro_support::__storage_offset_of],
         option = [core::option::Option],
+        optional_map_storage = [crate::macro_support::__OptionalMapStorage],
+        optional_schema_hash = [crate::macro_support::__storage_optional_schema_hash],
+        optional_set_storage = [crate::macro_support::__OptionalSetStorage],
         option_bucket_none = [crate::option_bucket::NoneBucket],
         option_bucket_option = [crate::option_bucket::OptionBucket],
         option_bucket_some = [crate::option_bucket::SomeBucket],
+        option_into_iter = [core::option::IntoIter],
+        option_iter = [core::option::Iter],
+        option_iter_mut = [core::option::IterMut],
         ord_t = [core::cmp::Ord],
+        ordered_map_storage = [crate::map::storage::OrderedMapStorage],
+        ordered_set_storage = [crate::set::storage::OrderedSetStorage],
         ordering = [core::cmp::Ordering],
+        panic = [core::panic],
         partial_eq_t = [core::cmp::PartialEq],
         partial_ord_t = [core::cmp::PartialOrd],
         slice_iter = [core::slice::Iter],
+        sparse_index_t = [crate::raw::SparseIndex],
+        sparse_map_storage = [crate::map::storage::SparseMapStorage],
+        sparse_set_storage = [crate::set::storage::SparseSetStorage],
         slice_iter_mut = [core::slice::IterMut],
         map_storage_t = [crate::map::MapStorage],
+        set_class_storage_t = [crate::set::ClassStorage],
+        set_range_storage_t = [crate::set::RangeStorage],
         set_storage_t = [crate::set::SetStorage],
+        storage_drain = [crate::macro_support::__storage_drain],
+        storage_extract_slots = [crate::macro_support::StorageExtractSlots],
+        storage_iter = [crate::macro_support::StorageIter],
+        storage_remaining_t = [crate::macro_support::StorageRemaining],
+        storage_span = [crate::macro_support::StorageSpan],
+        storage_step_back_t = [crate::macro_support::StorageStepBack],
+        storage_step_t = [crate::macro_support::StorageStep],
+        schema_hasher = [crate::schema::Hasher],
+        schema_variant = [crate::schema::Variant],
+        set_t = [crate::Set],
+        slice_storage_t = [crate::map::SliceStorage],
+        stats = [crate::map::Stats],
+        stats_storage_t = [crate::map::StatsStorage],
+        raw_storage_sealed_t = [crate::macro_support::__RawStorageSealed],
         raw_storage_t = [crate::raw::RawStorage],
+        require_alloc = [crate::macro_support::__storage_require_alloc],
+        require_hashbrown = [crate::macro_support::__storage_require_hashbrown],
+        result = [core::result::Result],
+        try_from_t = [core::convert::TryFrom],
         vacant_entry_t = [crate::map::VacantEntry],
     }
 }
@@ -117,8 +180,68 @@ fn suffixed<const N: usize>(prefix: &Path, parts: [&'static str; N]) -> Path {
 /// Options for derive.
 #[derive(Default)]
 pub(crate) struct Opts {
+    /// Generates an extension trait with per-variant accessors for maps.
+    pub(crate) accessors: Option<Span>,
+    /// Aligns generated storage to the given number of bytes.
+    pub(crate) align: Option<syn::LitInt>,
     /// Implements sets as bitsets when possible.
     pub(crate) bitset: Option<Span>,
+    /// Generates a module with constants for the bits of a bitset.
+    pub(crate) bit_consts: Option<Span>,
+    /// Generates conversions between sets and the given `bitflags` type.
+    pub(crate) bitflags: Option<syn::Type>,
+    /// Generates an enum selecting the class of every variant.
+    pub(crate) groups: Option<Span>,
+    /// Implements `FiniteKey` so that every key can be iterated over.
+    pub(crate) iter: Option<Span>,
+    /// Generates a test asserting that storage is laid out in declaration
+    /// order.
+    pub(crate) layout_test: Option<Span>,
+    /// Keys which this key converts into injectively.
+    pub(crate) maps_to: Vec<syn::Type>,
+    /// Stores maps as a presence mask next to an array of values.
+    pub(crate) masked: Option<Span>,
+    /// Generates conversions to and from the given primitive type.
+    pub(crate) primitive: Option<syn::Type>,
+    /// Only stores the entries which are present, sorted by their index.
+    pub(crate) sparse: Option<Span>,
+    /// Delegates to the storage of the single field of a newtype struct.
+    pub(crate) transparent: Option<Span>,
+    /// The name of the module holding the marker types of an `AnyValueMap`
+    /// key.
+    pub(crate) value_module: Option<syn::Ident>,
+    /// The declared order of every variant, including ones which are
+    /// disabled through `#[cfg(...)]`.
+    pub(crate) variants: Option<Vec<syn::Ident>>,
+}
+
+/// Options of a single variant.
+#[derive(Default)]
+pub(crate) struct VariantOpts {
+    /// Stores keys of the variant in ordered storage, through
+    /// `#[key(dynamic = "ordered")]`.
+    pub(crate) dynamic: Option<Span>,
+    /// Only allocates storage for the variant once it's used.
+    pub(crate) lazy: Option<Span>,
+    /// Only stores a dynamic field if the collections it requires are
+    /// enabled.
+    pub(crate) optional: Option<Span>,
+    /// The type of values stored for the variant in an `AnyValueMap`.
+    pub(crate) value: Option<syn::Type>,
+    /// Every value of the field, which are stored in an array.
+    pub(crate) values: Option<Vec<syn::Expr>>,
+}
+
+impl Opts {
+    /// The `#[repr(...)]` attribute of generated storage, which aligns it if
+    /// `#[key(align = N)]` is specified.
+    pub(crate) fn storage_repr(&self, transparent: bool) -> TokenStream {
+        match &self.align {
+            Some(align) => quote!(#[repr(align(#align))]),
+            None if transparent => quote!(#[repr(transparent)]),
+            None => TokenStream::new(),
+        }
+    }
 }
 
 pub(crate) struct Ctxt<'a> {
@@ -152,6 +275,11 @@ impl<'a> Ctxt<'a> {
         self.error(syn::Error::new(span, message));
     }
 
+    /// Test if any errors have been emitted.
+    pub(crate) fn has_errors(&self) -> bool {
+        !self.errors.borrow().is_empty()
+    }
+
     /// Convert into interior errors.
     pub(crate) fn into_errors(self) -> Vec<syn::Error> {
         self.errors.into_inner()
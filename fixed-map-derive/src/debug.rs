@@ -0,0 +1,45 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+use crate::context::Ctxt;
+
+/// Implement `Debug` for generated map storage, which formats its entries
+/// like a map.
+///
+/// The key is bounded through a trivial bound, so that storage for keys which
+/// don't implement `Debug` still compiles.
+pub(crate) fn impl_map(cx: &Ctxt<'_>, key: impl ToTokens, storage: impl ToTokens) -> TokenStream {
+    let debug_t = cx.toks.debug_t();
+    let fmt_result = cx.toks.fmt_result();
+    let formatter = cx.toks.formatter();
+    let map_storage_t = cx.toks.map_storage_t();
+
+    quote! {
+        #[automatically_derived]
+        impl<V> #debug_t for #storage<V> where V: #debug_t, for<'trivial_bounds> #key: #debug_t {
+            #[inline]
+            fn fmt(&self, f: &mut #formatter<'_>) -> #fmt_result {
+                #formatter::debug_map(f).entries(#map_storage_t::iter(self)).finish()
+            }
+        }
+    }
+}
+
+/// Implement `Debug` for generated set storage, which formats its values like
+/// a set.
+pub(crate) fn impl_set(cx: &Ctxt<'_>, key: impl ToTokens, storage: impl ToTokens) -> TokenStream {
+    let debug_t = cx.toks.debug_t();
+    let fmt_result = cx.toks.fmt_result();
+    let formatter = cx.toks.formatter();
+    let set_storage_t = cx.toks.set_storage_t();
+
+    quote! {
+        #[automatically_derived]
+        impl #debug_t for #storage where for<'trivial_bounds> #key: #debug_t {
+            #[inline]
+            fn fmt(&self, f: &mut #formatter<'_>) -> #fmt_result {
+                #formatter::debug_set(f).entries(#set_storage_t::iter(self)).finish()
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{DataEnum, LitInt};
+
+use crate::context::Ctxt;
+
+/// Generate an enum with a unit variant for every variant of the key, which
+/// selects its class through `KeyGroup`.
+pub(crate) fn implement(cx: &Ctxt<'_>, en: &DataEnum) -> TokenStream {
+    let ident = &cx.ast.ident;
+    let vis = &cx.ast.vis;
+
+    let clone_t = cx.toks.clone_t();
+    let copy_t = cx.toks.copy_t();
+    let debug_t = cx.toks.debug_t();
+    let eq_t = cx.toks.eq_t();
+    let from_t = cx.toks.from_t();
+    let hash_t = cx.toks.hash_t();
+    let key_group_t = cx.toks.key_group_t();
+    let partial_eq_t = cx.toks.partial_eq_t();
+
+    let group_ident = format_ident!("{}Group", ident);
+    let group_doc =
+        format!("Selects a class of [`{ident}`] by its variant, generated by `#[key(groups)]`.");
+
+    let variants = en.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+
+    let docs = variants
+        .iter()
+        .map(|var| format!("Selects [`{ident}::{var}`]."))
+        .collect::<Vec<_>>();
+
+    let indexes = en
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(n, v)| LitInt::new(&n.to_string(), v.span()))
+        .collect::<Vec<_>>();
+
+    quote! {
+        #[doc = #group_doc]
+        #[derive(#clone_t, #copy_t, #debug_t, #partial_eq_t, #eq_t, #hash_t)]
+        #vis enum #group_ident {
+            #(#[doc = #docs] #variants,)*
+        }
+
+        #[automatically_derived]
+        impl #key_group_t for #group_ident {
+            type Key = #ident;
+
+            #[inline]
+            fn class_index(self) -> usize {
+                match self {
+                    #(#group_ident::#variants => #indexes,)*
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #from_t<#ident> for #group_ident {
+            #[inline]
+            fn from(key: #ident) -> #group_ident {
+                match key {
+                    #(#ident::#variants { .. } => #group_ident::#variants,)*
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::DataEnum;
+
+use crate::attrs;
+use crate::context::Ctxt;
+
+/// Implement `FiniteKey`, which numbers every key by visiting the variants in
+/// declaration order and the keys of a field in the order of their own
+/// ordinals.
+pub(crate) fn implement(cx: &Ctxt<'_>, en: &DataEnum) -> Result<TokenStream, ()> {
+    let ident = &cx.ast.ident;
+
+    let finite_key_t = cx.toks.finite_key_t();
+    let option = cx.toks.option();
+
+    let mut lens = Vec::with_capacity(en.variants.len());
+    let mut keys = Vec::with_capacity(en.variants.len());
+
+    for variant in &en.variants {
+        let var = &variant.ident;
+
+        let (len, key) = match &variant.fields {
+            syn::Fields::Unit => (quote!(1), quote!(#ident::#var)),
+            syn::Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                let ty = &unnamed.unnamed[0].ty;
+
+                let variant_opts = attrs::parse_variant_attrs(cx, variant)?;
+
+                if let Some(span) = variant_opts.dynamic {
+                    cx.span_error(
+                        span,
+                        "#[key(iter)] is not supported on variants with #[key(dynamic = \"ordered\")]",
+                    );
+                    return Err(());
+                }
+
+                if let Some(span) = variant_opts.optional {
+                    cx.span_error(
+                        span,
+                        "#[key(iter)] is not supported on variants with #[key(optional)]",
+                    );
+                    return Err(());
+                }
+
+                match variant_opts.values {
+                    Some(values) => {
+                        let len = values.len();
+
+                        (
+                            quote!(#len),
+                            quote! {{
+                                let values: [#ty; #len] = [#(#values),*];
+                                #ident::#var(values[ordinal])
+                            }},
+                        )
+                    }
+                    None => (
+                        quote_spanned!(ty.span() => <#ty as #finite_key_t>::LEN),
+                        quote_spanned! {
+                            ty.span() => #ident::#var(<#ty as #finite_key_t>::from_ordinal(ordinal)?)
+                        },
+                    ),
+                }
+            }
+            // NB: Rejected when implementing storage.
+            _ => return Err(()),
+        };
+
+        lens.push(len);
+        keys.push(key);
+    }
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #finite_key_t for #ident {
+            const LEN: usize = 0 #(+ #lens)*;
+
+            #[inline]
+            fn from_ordinal(mut ordinal: usize) -> #option<Self> {
+                #(
+                    if ordinal < #lens {
+                        return #option::Some(#keys);
+                    }
+
+                    ordinal -= #lens;
+                )*
+
+                #option::None
+            }
+        }
+    })
+}
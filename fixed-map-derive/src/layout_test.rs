@@ -0,0 +1,49 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::DataEnum;
+
+use crate::context::Ctxt;
+
+/// Generate a test module asserting that the storage of the unit variants of
+/// an enum is laid out in declaration order, which is what iteration order,
+/// `Ord`, `Hash` and serialization depend on.
+pub(crate) fn implement(cx: &Ctxt<'_>, en: &DataEnum) -> TokenStream {
+    let ident = &cx.ast.ident;
+    let map_t = cx.toks.map_t();
+    let set_t = cx.toks.set_t();
+    let key_t = cx.toks.key_t();
+
+    let module = format_ident!("__{}_layout_test", crate::snake_case(&ident.to_string()));
+
+    let declared = en
+        .variants
+        .iter()
+        .filter(|variant| matches!(variant.fields, syn::Fields::Unit))
+        .map(|variant| {
+            let var = &variant.ident;
+            quote!(super::#ident::#var)
+        });
+
+    let message = format!("storage of `{ident}` is not laid out in declaration order");
+
+    quote! {
+        #[cfg(test)]
+        mod #module {
+            #[test]
+            fn layout() {
+                let declared: &[super::#ident] = &[#(#declared),*];
+
+                let mut map = #map_t::new();
+                let mut set = #set_t::new();
+
+                for (index, key) in declared.iter().enumerate().rev() {
+                    map.insert(*key, index);
+                    set.insert(*key);
+                }
+
+                assert!(map.values().copied().eq(0..declared.len()), #message);
+                assert!(set.iter().map(#key_t::variant_name).eq(declared.iter().map(|key| #key_t::variant_name(*key))), #message);
+            }
+        }
+    }
+}
@@ -20,11 +20,26 @@ use quote::quote;
 use syn::spanned::Spanned;
 use syn::{Data, DataEnum, DeriveInput, Fields};
 
+mod accessors;
+mod any_value;
 mod any_variants;
 mod attrs;
+mod bit_consts;
+mod bitflags;
+mod capacity;
 mod context;
+mod debug;
+mod groups;
+mod key_iter;
+mod layout_test;
+mod primitive;
+mod schema;
+mod single_variant;
 mod symbol;
+mod transparent;
 mod unit_variants;
+mod values;
+mod variant_name;
 
 /// See <https://docs.rs/fixed-map>.
 #[proc_macro_derive(Key, attributes(key))]
@@ -50,15 +65,220 @@ pub fn storage_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 fn impl_storage(cx: &context::Ctxt<'_>) -> Result<TokenStream, ()> {
     let opts = attrs::parse(cx)?;
 
-    if let Data::Enum(en) = &cx.ast.data {
-        if is_all_unit_variants(en) {
-            unit_variants::implement(cx, &opts, en)
-        } else {
-            any_variants::implement(cx, en)
+    match &cx.ast.data {
+        Data::Struct(st) if opts.transparent.is_some() => {
+            let unsupported = [
+                ("bitset", opts.bitset),
+                ("accessors", opts.accessors),
+                ("bit_consts", opts.bit_consts),
+                ("bitflags = T", opts.bitflags.as_ref().map(|ty| ty.span())),
+                ("groups", opts.groups),
+                ("iter", opts.iter),
+                ("layout_test", opts.layout_test),
+                ("primitive = T", opts.primitive.as_ref().map(|ty| ty.span())),
+                ("sparse", opts.sparse),
+                ("masked", opts.masked),
+                (
+                    "variants(...)",
+                    opts.variants.as_ref().map(|_| cx.ast.span()),
+                ),
+            ];
+
+            if let Some((name, span)) = unsupported
+                .iter()
+                .find_map(|&(name, span)| Some((name, span?)))
+            {
+                cx.span_error(
+                    span,
+                    format_args!("#[key({name})] is not supported with #[key(transparent)]"),
+                );
+                return Err(());
+            }
+
+            let storage = transparent::implement(cx, &opts, st)?;
+            let maps_to = maps_to(cx, &opts);
+
+            Ok(quote! {
+                #storage
+                #maps_to
+            })
+        }
+        Data::Enum(en) if opts.transparent.is_none() => {
+            if let Some(span) = opts.sparse {
+                if !is_all_unit_variants(en) {
+                    cx.span_error(
+                        span,
+                        "#[key(sparse)] is only supported on enums with unit variants",
+                    );
+                    return Err(());
+                }
+
+                if opts.bitset.is_some() {
+                    cx.span_error(span, "#[key(sparse)] is not supported with #[key(bitset)]");
+                    return Err(());
+                }
+
+                if opts.align.is_some() {
+                    cx.span_error(
+                        span,
+                        "#[key(sparse)] is not supported with #[key(align = N)]",
+                    );
+                    return Err(());
+                }
+
+                if en.variants.len() > usize::from(u16::MAX) + 1 {
+                    cx.span_error(
+                        span,
+                        "#[key(sparse)] is only supported on enums with at most 65536 variants",
+                    );
+                    return Err(());
+                }
+            }
+
+            if let Some(span) = opts.masked {
+                if !is_all_unit_variants(en) {
+                    cx.span_error(
+                        span,
+                        "#[key(masked)] is only supported on enums with unit variants",
+                    );
+                    return Err(());
+                }
+
+                if opts.sparse.is_some() {
+                    cx.span_error(span, "#[key(masked)] is not supported with #[key(sparse)]");
+                    return Err(());
+                }
+
+                if opts.align.is_some() {
+                    cx.span_error(
+                        span,
+                        "#[key(masked)] is not supported with #[key(align = N)]",
+                    );
+                    return Err(());
+                }
+            }
+
+            let storage = if is_all_unit_variants(en) {
+                unit_variants::implement(cx, &opts, en)?
+            } else {
+                any_variants::implement(cx, &opts, en)?
+            };
+
+            let any_value = any_value::implement(cx, &opts, en)?;
+
+            let accessors = if opts.accessors.is_some() {
+                accessors::implement(cx, en)?
+            } else {
+                TokenStream::new()
+            };
+
+            let bit_consts = if let Some(span) = opts.bit_consts {
+                if opts.bitset.is_none() || !is_all_unit_variants(en) {
+                    cx.span_error(
+                        span,
+                        "#[key(bit_consts)] is only supported together with #[key(bitset)] on enums with unit variants",
+                    );
+                    return Err(());
+                }
+
+                bit_consts::implement(cx, en)?
+            } else {
+                TokenStream::new()
+            };
+
+            let bitflags = if let Some(ty) = &opts.bitflags {
+                if opts.bitset.is_none() || !is_all_unit_variants(en) {
+                    cx.span_error(
+                        ty.span(),
+                        "#[key(bitflags = T)] is only supported together with #[key(bitset)] on enums with unit variants",
+                    );
+                    return Err(());
+                }
+
+                bitflags::implement(cx, en, ty)
+            } else {
+                TokenStream::new()
+            };
+
+            let groups = if opts.groups.is_some() {
+                groups::implement(cx, en)
+            } else {
+                TokenStream::new()
+            };
+
+            let key_iter = if opts.iter.is_some() {
+                key_iter::implement(cx, en)?
+            } else {
+                TokenStream::new()
+            };
+
+            let layout_test = if opts.layout_test.is_some() {
+                layout_test::implement(cx, en)
+            } else {
+                TokenStream::new()
+            };
+
+            let primitive = if let Some(ty) = &opts.primitive {
+                if !is_all_unit_variants(en) {
+                    cx.span_error(
+                        ty.span(),
+                        "#[key(primitive = T)] is only supported on enums with unit variants",
+                    );
+                    return Err(());
+                }
+
+                primitive::implement(cx, en, ty)
+            } else {
+                TokenStream::new()
+            };
+
+            let maps_to = maps_to(cx, &opts);
+
+            Ok(quote! {
+                #storage
+                #any_value
+                #accessors
+                #bit_consts
+                #bitflags
+                #groups
+                #key_iter
+                #layout_test
+                #primitive
+                #maps_to
+            })
+        }
+        Data::Enum(..) => {
+            cx.span_error(
+                cx.ast.span(),
+                "#[key(transparent)] is only supported on structs",
+            );
+            Err(())
         }
-    } else {
-        cx.span_error(cx.ast.span(), "named fields are not supported");
-        Err(())
+        Data::Struct(..) => {
+            cx.span_error(
+                cx.ast.span(),
+                "structs are only supported with #[key(transparent)]",
+            );
+            Err(())
+        }
+        Data::Union(..) => {
+            cx.span_error(cx.ast.span(), "unions are not supported");
+            Err(())
+        }
+    }
+}
+
+/// Implement `MapsTo` for every key listed in `#[key(maps_to(...))]`.
+fn maps_to(cx: &context::Ctxt<'_>, opts: &context::Opts) -> TokenStream {
+    let ident = &cx.ast.ident;
+    let maps_to_t = cx.toks.maps_to_t();
+    let types = &opts.maps_to;
+
+    quote! {
+        #(
+            #[automatically_derived]
+            impl #maps_to_t<#types> for #ident {}
+        )*
     }
 }
 
@@ -71,3 +291,27 @@ fn is_all_unit_variants(en: &DataEnum) -> bool {
 
     true
 }
+
+/// Convert a type or variant name into snake case.
+pub(crate) fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut output = String::with_capacity(name.len() + 4);
+
+    for (n, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && n > 0 {
+            let prev = chars[n - 1];
+            let next_lower = chars.get(n + 1).is_some_and(|c| c.is_lowercase());
+
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_lower) {
+                output.push('_');
+            }
+        }
+
+        output.extend(c.to_lowercase());
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests;
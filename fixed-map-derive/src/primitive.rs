@@ -0,0 +1,52 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{DataEnum, LitInt};
+
+use crate::context::Ctxt;
+
+/// Implement conversions between an enum with unit variants and the primitive
+/// given through `#[key(primitive = T)]`, where the value of every variant is
+/// its index in declaration order.
+pub(crate) fn implement(cx: &Ctxt<'_>, en: &DataEnum, ty: &syn::Type) -> TokenStream {
+    let ident = &cx.ast.ident;
+
+    let from_t = cx.toks.from_t();
+    let try_from_t = cx.toks.try_from_t();
+    let invalid_primitive = cx.toks.invalid_primitive();
+    let result = cx.toks.result();
+
+    let variants = en.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+
+    let indexes = en
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(n, v)| LitInt::new(&n.to_string(), v.span()))
+        .collect::<Vec<_>>();
+
+    quote! {
+        #[automatically_derived]
+        impl #from_t<#ident> for #ty {
+            #[inline]
+            fn from(key: #ident) -> #ty {
+                match key {
+                    #(#ident::#variants => #indexes,)*
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #try_from_t<#ty> for #ident {
+            type Error = #invalid_primitive<#ty>;
+
+            #[inline]
+            fn try_from(value: #ty) -> #result<Self, Self::Error> {
+                match value {
+                    #(#indexes => #result::Ok(#ident::#variants),)*
+                    _ => #result::Err(#invalid_primitive::new(value)),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::DataEnum;
+
+use crate::attrs;
+use crate::context::{Ctxt, Opts};
+
+/// Build the expression for `Key::KEY_SCHEMA_HASH`, which hashes the names of
+/// the variants in declaration order together with the schema of any nested
+/// keys.
+pub(crate) fn key_schema_hash(cx: &Ctxt<'_>, en: &DataEnum) -> TokenStream {
+    let key_t = cx.toks.key_t();
+    let schema_hasher = cx.toks.schema_hasher();
+
+    let mut writes = Vec::with_capacity(en.variants.len());
+
+    for variant in &en.variants {
+        let name = variant.ident.to_string();
+
+        let nested = match &variant.fields {
+            syn::Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                // NB: Storage for declared values only depends on the values
+                // themselves, so they make up the schema of the field.
+                let variant_opts = attrs::parse_variant_attrs(cx, variant).unwrap_or_default();
+
+                match &variant_opts.values {
+                    Some(values) => {
+                        let values = values
+                            .iter()
+                            .map(|value| value.to_token_stream().to_string());
+                        Some(quote!(#(.write_str(#values))*))
+                    }
+                    _ => {
+                        let ty = &unnamed.unnamed[0].ty;
+
+                        if variant_opts.optional.is_some() {
+                            let optional_schema_hash = cx.toks.optional_schema_hash();
+                            Some(quote!(.write_u64(#optional_schema_hash!(#ty))))
+                        } else {
+                            Some(quote!(.write_u64(<#ty as #key_t>::KEY_SCHEMA_HASH)))
+                        }
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        writes.push(quote! {
+            let hasher = hasher.write_str(#name) #nested;
+        });
+    }
+
+    quote! {{
+        let hasher = #schema_hasher::new();
+        #(#writes)*
+        hasher.finish()
+    }}
+}
+
+/// Build the expression for `Key::KEY_VARIANTS`.
+///
+/// Variants disabled through `#[cfg(...)]` are stripped before the derive
+/// sees them, so the full order of variants has to be spelled out through
+/// `#[key(variants(...))]` for them to be kept as reserved slots.
+pub(crate) fn key_variants(cx: &Ctxt<'_>, opts: &Opts, en: &DataEnum) -> Result<TokenStream, ()> {
+    let schema_variant = cx.toks.schema_variant();
+
+    let Some(declared) = &opts.variants else {
+        let variants = en.variants.iter().map(|variant| {
+            let name = variant.ident.to_string();
+            quote!(#schema_variant::new(#name, true))
+        });
+
+        return Ok(quote!(&[#(#variants),*]));
+    };
+
+    let mut ok = true;
+
+    for (index, ident) in declared.iter().enumerate() {
+        if declared[..index].contains(ident) {
+            cx.span_error(
+                ident.span(),
+                format_args!("variant `{ident}` is listed more than once"),
+            );
+            ok = false;
+        }
+    }
+
+    for variant in &en.variants {
+        if !declared.contains(&variant.ident) {
+            cx.span_error(
+                variant.ident.span(),
+                format_args!(
+                    "variant `{}` is missing from #[key(variants(...))]",
+                    variant.ident
+                ),
+            );
+            ok = false;
+        }
+    }
+
+    if !ok {
+        return Err(());
+    }
+
+    let variants = declared.iter().map(|ident| {
+        let name = ident.to_string();
+        let enabled = en.variants.iter().any(|variant| variant.ident == *ident);
+        quote!(#schema_variant::new(#name, #enabled))
+    });
+
+    Ok(quote!(&[#(#variants),*]))
+}
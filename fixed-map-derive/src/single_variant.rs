@@ -0,0 +1,365 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, Variant};
+
+use crate::context::{Ctxt, Opts};
+use crate::debug;
+
+/// Implement map storage for an enum with a single unit variant, which is
+/// stored as a plain `Option<V>`.
+pub(crate) fn impl_map(
+    cx: &Ctxt<'_>,
+    opts: &Opts,
+    variant: &Variant,
+    map_storage: &Ident,
+) -> TokenStream {
+    let repr = opts.storage_repr(true);
+    let ident = &cx.ast.ident;
+    let lt = cx.lt;
+    let vis = &cx.ast.vis;
+    let var = &variant.ident;
+
+    let clone_t = cx.toks.clone_t();
+    let copy_t = cx.toks.copy_t();
+    let entries_storage_t = cx.toks.entries_storage_t();
+    let entry_enum = cx.toks.entry_enum();
+    let eq_t = cx.toks.eq_t();
+    let hash_t = cx.toks.hash_t();
+    let into_iterator_t = cx.toks.into_iterator_t();
+    let iterator_once = cx.toks.iterator_once();
+    let map_storage_t = cx.toks.map_storage_t();
+    let once = cx.toks.once();
+    let option = cx.toks.option();
+    let option_into_iter = cx.toks.option_into_iter();
+    let option_iter = cx.toks.option_iter();
+    let option_iter_mut = cx.toks.option_iter_mut();
+    let ord_t = cx.toks.ord_t();
+    let partial_eq_t = cx.toks.partial_eq_t();
+    let partial_ord_t = cx.toks.partial_ord_t();
+    let stats = cx.toks.stats();
+    let drain_storage_t = cx.toks.drain_storage_t();
+    let storage_drain = cx.toks.storage_drain();
+    let extract_if_storage_t = cx.toks.extract_if_storage_t();
+    let storage_extract_slots = cx.toks.storage_extract_slots();
+    let stats_storage_t = cx.toks.stats_storage_t();
+    let class_storage_t = cx.toks.class_storage_t();
+
+    let debug_impl = debug::impl_map(cx, ident, map_storage);
+
+    quote! {
+        #repr
+        #[derive(#clone_t, #copy_t, #partial_eq_t, #eq_t, #hash_t, #partial_ord_t, #ord_t)]
+        #vis struct #map_storage<V> {
+            data: #option<V>,
+        }
+
+        #debug_impl
+
+        #[automatically_derived]
+        impl<V> #map_storage_t<#ident, V> for #map_storage<V> {
+            type Iter<#lt> = #option_into_iter<(#ident, &#lt V)> where V: #lt;
+            type Keys<#lt> = #option_into_iter<#ident> where V: #lt;
+            type Values<#lt> = #option_iter<#lt, V> where V: #lt;
+            type IterMut<#lt> = #option_into_iter<(#ident, &#lt mut V)> where V: #lt;
+            type ValuesMut<#lt> = #option_iter_mut<#lt, V> where V: #lt;
+            type IntoIter = #option_into_iter<(#ident, V)>;
+            type Occupied<#lt> = OccupiedEntry<#lt, V> where V: #lt;
+            type Vacant<#lt> = VacantEntry<#lt, V> where V: #lt;
+
+            #[inline]
+            fn empty() -> Self {
+                Self {
+                    data: #option::None,
+                }
+            }
+
+            #[inline]
+            fn len(&self) -> usize {
+                usize::from(#option::is_some(&self.data))
+            }
+
+            #[inline]
+            fn is_empty(&self) -> bool {
+                #option::is_none(&self.data)
+            }
+
+            #[inline]
+            fn insert(&mut self, _: #ident, value: V) -> #option<V> {
+                #option::replace(&mut self.data, value)
+            }
+
+            #[inline]
+            fn contains_key(&self, _: #ident) -> bool {
+                #option::is_some(&self.data)
+            }
+
+            #[inline]
+            fn get(&self, _: #ident) -> #option<&V> {
+                #option::as_ref(&self.data)
+            }
+
+            #[inline]
+            fn get_mut(&mut self, _: #ident) -> #option<&mut V> {
+                #option::as_mut(&mut self.data)
+            }
+
+            #[inline]
+            fn remove(&mut self, _: #ident) -> #option<V> {
+                #option::take(&mut self.data)
+            }
+
+            #[inline]
+            fn retain<F>(&mut self, mut func: F)
+            where
+                F: FnMut(#ident, &mut V) -> bool
+            {
+                if let #option::Some(val) = #option::as_mut(&mut self.data) {
+                    if !func(#ident::#var, val) {
+                        self.data = #option::None;
+                    }
+                }
+            }
+
+            #[inline]
+            fn fill_fixed<F>(&mut self, mut func: F)
+            where
+                F: FnMut() -> V,
+            {
+                if #option::is_none(&self.data) {
+                    self.data = #option::Some(func());
+                }
+            }
+
+            #[inline]
+            fn clear(&mut self) {
+                self.data = #option::None;
+            }
+
+            #[inline]
+            fn iter(&self) -> Self::Iter<'_> {
+                #into_iterator_t::into_iter(#option::map(#option::as_ref(&self.data), |v| (#ident::#var, v)))
+            }
+
+            #[inline]
+            fn keys(&self) -> Self::Keys<'_> {
+                #into_iterator_t::into_iter(#option::map(#option::as_ref(&self.data), |_| #ident::#var))
+            }
+
+            #[inline]
+            fn values(&self) -> Self::Values<'_> {
+                #option::iter(&self.data)
+            }
+
+            #[inline]
+            fn iter_mut(&mut self) -> Self::IterMut<'_> {
+                #into_iterator_t::into_iter(#option::map(#option::as_mut(&mut self.data), |v| (#ident::#var, v)))
+            }
+
+            #[inline]
+            fn values_mut(&mut self) -> Self::ValuesMut<'_> {
+                #option::iter_mut(&mut self.data)
+            }
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                #into_iterator_t::into_iter(#option::map(self.data, |v| (#ident::#var, v)))
+            }
+
+            #[inline]
+            fn entry(&mut self, key: #ident) -> #entry_enum<'_, Self, #ident, V> {
+                option_to_entry(&mut self.data, key)
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #stats_storage_t<#ident, V> for #map_storage<V> {
+            #[inline]
+            fn stats(&self) -> #stats {
+                #stats::fixed(1, #map_storage_t::len(self))
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #drain_storage_t<#ident, V> for #map_storage<V> {
+            type Drain<#lt> = <Self as #map_storage_t<#ident, V>>::IntoIter where V: #lt;
+
+            #[inline]
+            fn drain(&mut self) -> Self::Drain<'_> {
+                #storage_drain(self)
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #extract_if_storage_t<#ident, V> for #map_storage<V> {
+            type ExtractIf<#lt> = #storage_extract_slots<#iterator_once<(#ident, &#lt mut #option<V>)>> where V: #lt;
+
+            #[inline]
+            fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+                #storage_extract_slots::new(#once((#ident::#var, &mut self.data)))
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #class_storage_t<#ident, V> for #map_storage<V> {
+            type ClassIter<#lt> = #option_into_iter<(#ident, &#lt V)> where V: #lt;
+
+            #[inline]
+            fn class_len(&self, class: usize) -> usize {
+                usize::from(class == 0 && #option::is_some(&self.data))
+            }
+
+            #[inline]
+            fn clear_class(&mut self, class: usize) {
+                if class == 0 {
+                    self.data = #option::None;
+                }
+            }
+
+            #[inline]
+            fn iter_class(&self, class: usize) -> Self::ClassIter<'_> {
+                let data = if class == 0 { #option::as_ref(&self.data) } else { #option::None };
+                #into_iterator_t::into_iter(#option::map(data, |v| (#ident::#var, v)))
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #entries_storage_t<#ident, V> for #map_storage<V> {
+            type EntriesMut<#lt> = #iterator_once<#entry_enum<#lt, Self, #ident, V>> where V: #lt;
+
+            #[inline]
+            fn entries_mut(&mut self) -> Self::EntriesMut<'_> {
+                #once(option_to_entry(&mut self.data, #ident::#var))
+            }
+        }
+    }
+}
+
+/// Implement set storage for an enum with a single unit variant, which is
+/// stored as a plain `bool`.
+pub(crate) fn impl_set(
+    cx: &Ctxt<'_>,
+    opts: &Opts,
+    variant: &Variant,
+    set_storage: &Ident,
+) -> TokenStream {
+    let repr = opts.storage_repr(true);
+    let ident = &cx.ast.ident;
+    let lt = cx.lt;
+    let vis = &cx.ast.vis;
+    let var = &variant.ident;
+
+    let clone_t = cx.toks.clone_t();
+    let copy_t = cx.toks.copy_t();
+    let eq_t = cx.toks.eq_t();
+    let hash_t = cx.toks.hash_t();
+    let into_iterator_t = cx.toks.into_iterator_t();
+    let mem = cx.toks.mem();
+    let option = cx.toks.option();
+    let option_into_iter = cx.toks.option_into_iter();
+    let ord_t = cx.toks.ord_t();
+    let partial_eq_t = cx.toks.partial_eq_t();
+    let partial_ord_t = cx.toks.partial_ord_t();
+    let set_class_storage_t = cx.toks.set_class_storage_t();
+    let set_storage_t = cx.toks.set_storage_t();
+
+    let debug_impl = debug::impl_set(cx, ident, set_storage);
+
+    quote! {
+        #repr
+        #[derive(#clone_t, #copy_t, #partial_eq_t, #eq_t, #hash_t, #partial_ord_t, #ord_t)]
+        #vis struct #set_storage {
+            data: bool,
+        }
+
+        #debug_impl
+
+        #[automatically_derived]
+        impl #set_storage_t<#ident> for #set_storage {
+            type Iter<#lt> = #option_into_iter<#ident>;
+            type IntoIter = #option_into_iter<#ident>;
+
+            #[inline]
+            fn empty() -> Self {
+                Self { data: false }
+            }
+
+            #[inline]
+            fn len(&self) -> usize {
+                usize::from(self.data)
+            }
+
+            #[inline]
+            fn is_empty(&self) -> bool {
+                !self.data
+            }
+
+            #[inline]
+            fn insert(&mut self, _: #ident) -> bool {
+                !#mem::replace(&mut self.data, true)
+            }
+
+            #[inline]
+            fn contains(&self, _: #ident) -> bool {
+                self.data
+            }
+
+            #[inline]
+            fn remove(&mut self, _: #ident) -> bool {
+                #mem::replace(&mut self.data, false)
+            }
+
+            #[inline]
+            fn retain<F>(&mut self, mut f: F)
+            where
+                F: FnMut(#ident) -> bool
+            {
+                if self.data {
+                    self.data = f(#ident::#var);
+                }
+            }
+
+            #[inline]
+            fn clear(&mut self) {
+                self.data = false;
+            }
+
+            #[inline]
+            fn union_with(&mut self, other: &Self) {
+                self.data |= other.data;
+            }
+
+            #[inline]
+            fn intersect_with(&mut self, other: &Self) {
+                self.data &= other.data;
+            }
+
+            #[inline]
+            fn subtract(&mut self, other: &Self) {
+                self.data &= !other.data;
+            }
+
+            #[inline]
+            fn intersection_len(&self, other: &Self) -> usize {
+                usize::from(self.data & other.data)
+            }
+
+            #[inline]
+            fn iter(&self) -> Self::Iter<'_> {
+                #into_iterator_t::into_iter(if self.data { #option::Some(#ident::#var) } else { #option::None })
+            }
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                #into_iterator_t::into_iter(if self.data { #option::Some(#ident::#var) } else { #option::None })
+            }
+        }
+
+        #[automatically_derived]
+        impl #set_class_storage_t<#ident> for #set_storage {
+            #[inline]
+            fn contains_class(&self, class: usize) -> bool {
+                class == 0 && self.data
+            }
+        }
+    }
+}
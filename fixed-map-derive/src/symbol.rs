@@ -6,7 +6,26 @@ use syn::{Ident, Path};
 pub struct Symbol(&'static str);
 
 pub(crate) const KEY: Symbol = Symbol("key");
+pub(crate) const ACCESSORS: Symbol = Symbol("accessors");
+pub(crate) const ALIGN: Symbol = Symbol("align");
 pub(crate) const BITSET: Symbol = Symbol("bitset");
+pub(crate) const BIT_CONSTS: Symbol = Symbol("bit_consts");
+pub(crate) const BITFLAGS: Symbol = Symbol("bitflags");
+pub(crate) const DYNAMIC: Symbol = Symbol("dynamic");
+pub(crate) const GROUPS: Symbol = Symbol("groups");
+pub(crate) const ITER: Symbol = Symbol("iter");
+pub(crate) const LAZY: Symbol = Symbol("lazy");
+pub(crate) const LAYOUT_TEST: Symbol = Symbol("layout_test");
+pub(crate) const MASKED: Symbol = Symbol("masked");
+pub(crate) const MAPS_TO: Symbol = Symbol("maps_to");
+pub(crate) const OPTIONAL: Symbol = Symbol("optional");
+pub(crate) const PRIMITIVE: Symbol = Symbol("primitive");
+pub(crate) const SPARSE: Symbol = Symbol("sparse");
+pub(crate) const TRANSPARENT: Symbol = Symbol("transparent");
+pub(crate) const VALUE: Symbol = Symbol("value");
+pub(crate) const VALUE_MODULE: Symbol = Symbol("value_module");
+pub(crate) const VALUES: Symbol = Symbol("values");
+pub(crate) const VARIANTS: Symbol = Symbol("variants");
 
 impl PartialEq<Symbol> for Ident {
     fn eq(&self, word: &Symbol) -> bool {
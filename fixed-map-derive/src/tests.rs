@@ -0,0 +1,290 @@
+use quote::{quote, ToTokens};
+use syn::{DeriveInput, Expr, Item, Stmt};
+
+use crate::context;
+
+/// Expand the derive for the given input.
+fn expand(ast: DeriveInput) -> syn::File {
+    let lt = syn::Lifetime::new("'a", proc_macro2::Span::call_site());
+    let crate_prefix = context::leading_path(["fixed_map"]);
+    let tokens = context::Toks::new(&crate_prefix);
+    let cx = context::Ctxt::new(&tokens, &ast, &lt);
+
+    let Ok(output) = crate::impl_storage(&cx) else {
+        panic!("expansion failed: {:?}", cx.into_errors());
+    };
+
+    syn::parse2(output).expect("expansion should parse")
+}
+
+/// Expand the derive for the given input, which is expected to fail, and
+/// collect the messages of the errors it reports.
+fn expand_errors(ast: DeriveInput) -> Vec<String> {
+    let lt = syn::Lifetime::new("'a", proc_macro2::Span::call_site());
+    let crate_prefix = context::leading_path(["fixed_map"]);
+    let tokens = context::Toks::new(&crate_prefix);
+    let cx = context::Ctxt::new(&tokens, &ast, &lt);
+
+    if crate::impl_storage(&cx).is_ok() {
+        panic!("expansion should fail");
+    }
+
+    cx.into_errors().iter().map(ToString::to_string).collect()
+}
+
+/// Assert that expanding the derive fails with exactly the given error.
+fn assert_error(ast: DeriveInput, expected: &str) {
+    assert_eq!(expand_errors(ast), [expected]);
+}
+
+/// Assert that every impl in the given items is marked as
+/// `#[automatically_derived]`, including ones nested in modules and
+/// `const _: () = { .. };` blocks.
+fn assert_automatically_derived(items: &[Item]) {
+    for item in items {
+        match item {
+            Item::Impl(item) => {
+                let present = item
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("automatically_derived"));
+
+                assert!(
+                    present,
+                    "missing #[automatically_derived] on `impl {} for {}`",
+                    item.trait_
+                        .as_ref()
+                        .map(|(_, path, _)| quote!(#path).to_string())
+                        .unwrap_or_default(),
+                    item.self_ty.to_token_stream(),
+                );
+            }
+            Item::Mod(item) => {
+                if let Some((_, items)) = &item.content {
+                    assert_automatically_derived(items);
+                }
+            }
+            Item::Const(item) => {
+                if let Expr::Block(block) = &*item.expr {
+                    let items = block
+                        .block
+                        .stmts
+                        .iter()
+                        .filter_map(|stmt| match stmt {
+                            Stmt::Item(item) => Some(item.clone()),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>();
+
+                    assert_automatically_derived(&items);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn assert_all_automatically_derived(ast: DeriveInput) {
+    assert_automatically_derived(&expand(ast).items);
+}
+
+#[test]
+fn automatically_derived() {
+    assert_all_automatically_derived(syn::parse_quote! {
+        #[key(groups, primitive = u8, accessors, iter)]
+        enum Unit {
+            First,
+            Second,
+            Third,
+        }
+    });
+
+    assert_all_automatically_derived(syn::parse_quote! {
+        #[key(bitset, bit_consts, bitflags = Flags)]
+        enum Bitset {
+            First,
+            Second,
+        }
+    });
+
+    assert_all_automatically_derived(syn::parse_quote! {
+        #[key(sparse)]
+        enum Sparse {
+            First,
+            Second,
+        }
+    });
+
+    assert_all_automatically_derived(syn::parse_quote! {
+        enum Single {
+            First,
+        }
+    });
+
+    assert_all_automatically_derived(syn::parse_quote! {
+        #[key(groups)]
+        enum Composite {
+            Unit,
+            Nested(Part),
+            Number(u32),
+            #[key(lazy)]
+            Lazy(u32),
+            #[key(values("a", "b"))]
+            Name(&'static str),
+        }
+    });
+
+    assert_all_automatically_derived(syn::parse_quote! {
+        #[key(iter)]
+        enum Finite {
+            Unit,
+            Nested(Part),
+            #[key(values(1, 2))]
+            Number(u8),
+        }
+    });
+
+    assert_all_automatically_derived(syn::parse_quote! {
+        #[key(transparent)]
+        struct Wrapper(Part);
+    });
+}
+
+#[test]
+fn optional_errors() {
+    assert_error(
+        syn::parse_quote! {
+            enum Key {
+                #[key(optional)]
+                First,
+                Second(bool),
+            }
+        },
+        "#[key(optional)] is only supported on variants with a single field",
+    );
+
+    assert_error(
+        syn::parse_quote! {
+            enum Key {
+                #[key(optional)]
+                First(bool),
+            }
+        },
+        "#[key(optional)] is only supported on variants holding a dynamic type such as `u32` or `&'static str`",
+    );
+
+    assert_error(
+        syn::parse_quote! {
+            enum Key {
+                #[key(lazy, optional)]
+                First(u32),
+            }
+        },
+        "#[key(optional)] is not supported together with #[key(lazy)], #[key(dynamic = \"ordered\")] or #[key(values(...))]",
+    );
+}
+
+#[test]
+fn lazy_errors() {
+    assert_error(
+        syn::parse_quote! {
+            enum Key {
+                #[key(lazy)]
+                First,
+                Second(bool),
+            }
+        },
+        "#[key(lazy)] is only supported on variants with a single field",
+    );
+
+    assert_error(
+        syn::parse_quote! {
+            enum Key {
+                #[key(lazy, dynamic = "ordered")]
+                First(u32),
+            }
+        },
+        "#[key(lazy)] is not supported together with #[key(dynamic = \"ordered\")]",
+    );
+}
+
+#[test]
+fn values_errors() {
+    assert_error(
+        syn::parse_quote! {
+            enum Key {
+                #[key(values(1, 2))]
+                First,
+                Second(bool),
+            }
+        },
+        "#[key(values(...))] is only supported on variants with a single field",
+    );
+
+    assert_error(
+        syn::parse_quote! {
+            enum Key {
+                #[key(lazy, values(1, 2))]
+                First(u8),
+            }
+        },
+        "#[key(lazy)] is not supported together with #[key(values(...))]",
+    );
+
+    assert_error(
+        syn::parse_quote! {
+            enum Key {
+                #[key(dynamic = "ordered", values(1, 2))]
+                First(u8),
+            }
+        },
+        "#[key(dynamic = \"ordered\")] is not supported together with #[key(values(...))]",
+    );
+}
+
+#[test]
+fn sparse_errors() {
+    assert_error(
+        syn::parse_quote! {
+            #[key(sparse, align = 64)]
+            enum Key {
+                First,
+                Second,
+            }
+        },
+        "#[key(sparse)] is not supported with #[key(align = N)]",
+    );
+}
+
+#[test]
+fn masked_errors() {
+    assert_error(
+        syn::parse_quote! {
+            #[key(masked, align = 64)]
+            enum Key {
+                First,
+                Second,
+            }
+        },
+        "#[key(masked)] is not supported with #[key(align = N)]",
+    );
+}
+
+#[test]
+fn transparent_errors() {
+    assert_error(
+        syn::parse_quote! {
+            #[key(transparent, bitset)]
+            struct Key(Inner);
+        },
+        "#[key(bitset)] is not supported with #[key(transparent)]",
+    );
+
+    assert_error(
+        syn::parse_quote! {
+            #[key(transparent, primitive = u8)]
+            struct Key(Inner);
+        },
+        "#[key(primitive = T)] is not supported with #[key(transparent)]",
+    );
+}
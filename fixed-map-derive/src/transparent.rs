@@ -0,0 +1,688 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{DataStruct, Fields};
+
+use crate::context::{Ctxt, Opts};
+use crate::debug;
+use crate::variant_name;
+
+/// Implement the `Key` trait for a newtype struct by delegating to the storage
+/// of the wrapped key.
+pub(crate) fn implement(cx: &Ctxt<'_>, opts: &Opts, st: &DataStruct) -> Result<TokenStream, ()> {
+    let element = match &st.fields {
+        Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => &unnamed.unnamed[0].ty,
+        _ => {
+            cx.span_error(
+                st.fields.span(),
+                "#[key(transparent)] is only supported on structs with a single unnamed field",
+            );
+            return Err(());
+        }
+    };
+
+    if !cx.ast.generics.params.is_empty() {
+        cx.span_error(
+            cx.ast.generics.span(),
+            "#[key(transparent)] is not supported on generic structs",
+        );
+        return Err(());
+    }
+
+    let map_storage = impl_map_storage(cx, opts, element);
+    let set_storage = impl_set_storage(cx, opts, element);
+    let key_index = impl_key_index(cx, element);
+    let variant_name = variant_name::transparent(cx, element);
+
+    let ident = &cx.ast.ident;
+    let key_t = cx.toks.key_t();
+    let schema_variant = cx.toks.schema_variant();
+    let option = cx.toks.option();
+
+    Ok(quote! {
+        const _: () = {
+            #map_storage
+            #set_storage
+            #key_index
+
+            #[automatically_derived]
+            impl #key_t for #ident {
+                type MapStorage<V> = __MapStorage<V>;
+                type SetStorage = __SetStorage;
+                const KEY_SCHEMA_HASH: u64 = <#element as #key_t>::KEY_SCHEMA_HASH;
+                const KEY_VARIANTS: &'static [#schema_variant] = <#element as #key_t>::KEY_VARIANTS;
+                const CAPACITY: #option<usize> = <#element as #key_t>::CAPACITY;
+                #variant_name
+            }
+        };
+    })
+}
+
+/// Implement `KeyIndex` by delegating to the wrapped key, if it implements
+/// it.
+fn impl_key_index(cx: &Ctxt<'_>, element: &syn::Type) -> TokenStream {
+    let ident = &cx.ast.ident;
+    let key_index_t = cx.toks.key_index_t();
+    let option = cx.toks.option();
+
+    quote! {
+        #[automatically_derived]
+        impl #key_index_t for #ident where for<'trivial_bounds> #element: #key_index_t {
+            const LEN: usize = <#element as #key_index_t>::LEN;
+
+            #[inline]
+            fn to_index(self) -> usize {
+                <#element as #key_index_t>::to_index(self.0)
+            }
+
+            #[inline]
+            fn from_index(index: usize) -> #option<Self> {
+                #option::map(<#element as #key_index_t>::from_index(index), #ident)
+            }
+        }
+    }
+}
+
+/// Implement `MapStorage` implementation.
+fn impl_map_storage(cx: &Ctxt<'_>, opts: &Opts, element: &syn::Type) -> TokenStream {
+    let repr = opts.storage_repr(true);
+    let ident = &cx.ast.ident;
+    let vis = &cx.ast.vis;
+    let lt = cx.lt;
+
+    let clone_t = cx.toks.clone_t();
+    let copy_t = cx.toks.copy_t();
+    let entry_enum = cx.toks.entry_enum();
+    let eq_t = cx.toks.eq_t();
+    let hash_t = cx.toks.hash_t();
+    let hasher_t = cx.toks.hasher_t();
+    let iterator_map = cx.toks.iterator_map();
+    let iterator_t = cx.toks.iterator_t();
+    let key_t = cx.toks.key_t();
+    let layout_branch = cx.toks.layout_branch();
+    let map_storage_t = cx.toks.map_storage_t();
+    let occupied_entry_t = cx.toks.occupied_entry_t();
+    let option = cx.toks.option();
+    let ord_t = cx.toks.ord_t();
+    let ordering = cx.toks.ordering();
+    let partial_eq_t = cx.toks.partial_eq_t();
+    let partial_ord_t = cx.toks.partial_ord_t();
+    let vacant_entry_t = cx.toks.vacant_entry_t();
+
+    let inner = quote!(<#element as #key_t>::MapStorage<V>);
+    let as_inner = quote!(<#inner as #map_storage_t<#element, V>>);
+
+    let stats = cx.toks.stats();
+    let stats_storage_t = cx.toks.stats_storage_t();
+    let class_storage_t = cx.toks.class_storage_t();
+    let drain_storage_t = cx.toks.drain_storage_t();
+    let extract_if_cursor_t = cx.toks.extract_if_cursor_t();
+    let extract_if_storage_t = cx.toks.extract_if_storage_t();
+    let entries_storage_t = cx.toks.entries_storage_t();
+    let slice_storage_t = cx.toks.slice_storage_t();
+
+    let debug_impl = debug::impl_map(cx, ident, quote!(__MapStorage));
+
+    quote! {
+        #repr
+        #vis struct __MapStorage<V> {
+            inner: #inner,
+        }
+
+        #[automatically_derived]
+        impl<V> #clone_t for __MapStorage<V> where #inner: #clone_t {
+            #[inline]
+            fn clone(&self) -> Self {
+                Self {
+                    inner: #clone_t::clone(&self.inner),
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #copy_t for __MapStorage<V> where #inner: #copy_t {}
+
+        #[automatically_derived]
+        impl<V> #partial_eq_t for __MapStorage<V> where #inner: #partial_eq_t {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                #partial_eq_t::eq(&self.inner, &other.inner)
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #eq_t for __MapStorage<V> where #inner: #eq_t {}
+
+        #[automatically_derived]
+        impl<V> #hash_t for __MapStorage<V> where #inner: #hash_t {
+            #[inline]
+            fn hash<H>(&self, state: &mut H)
+            where
+                H: #hasher_t,
+            {
+                #hash_t::hash(&self.inner, state);
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #partial_ord_t for __MapStorage<V> where #inner: #partial_ord_t {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> #option<#ordering> {
+                #partial_ord_t::partial_cmp(&self.inner, &other.inner)
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #ord_t for __MapStorage<V> where #inner: #ord_t {
+            #[inline]
+            fn cmp(&self, other: &Self) -> #ordering {
+                #ord_t::cmp(&self.inner, &other.inner)
+            }
+        }
+
+        #debug_impl
+
+        #[automatically_derived]
+        impl<V> #map_storage_t<#ident, V> for __MapStorage<V> {
+            type Iter<#lt> = #iterator_map<#as_inner::Iter<#lt>, fn((#element, &#lt V)) -> (#ident, &#lt V)> where V: #lt;
+            type Keys<#lt> = #iterator_map<#as_inner::Keys<#lt>, fn(#element) -> #ident> where V: #lt;
+            type Values<#lt> = #as_inner::Values<#lt> where V: #lt;
+            type IterMut<#lt> = #iterator_map<#as_inner::IterMut<#lt>, fn((#element, &#lt mut V)) -> (#ident, &#lt mut V)> where V: #lt;
+            type ValuesMut<#lt> = #as_inner::ValuesMut<#lt> where V: #lt;
+            type IntoIter = #iterator_map<#as_inner::IntoIter, fn((#element, V)) -> (#ident, V)>;
+            type Occupied<#lt> = OccupiedEntry<#lt, V> where V: #lt;
+            type Vacant<#lt> = VacantEntry<#lt, V> where V: #lt;
+
+            #[inline]
+            fn empty() -> Self {
+                Self {
+                    inner: #as_inner::empty(),
+                }
+            }
+
+            #[inline]
+            fn len(&self) -> usize {
+                #as_inner::len(&self.inner)
+            }
+
+            #[inline]
+            fn is_empty(&self) -> bool {
+                #as_inner::is_empty(&self.inner)
+            }
+
+            #[inline]
+            fn insert(&mut self, key: #ident, value: V) -> #option<V> {
+                #as_inner::insert(&mut self.inner, key.0, value)
+            }
+
+            #[inline]
+            fn contains_key(&self, key: #ident) -> bool {
+                #as_inner::contains_key(&self.inner, key.0)
+            }
+
+            #[inline]
+            fn get(&self, key: #ident) -> #option<&V> {
+                #as_inner::get(&self.inner, key.0)
+            }
+
+            #[inline]
+            fn get_mut(&mut self, key: #ident) -> #option<&mut V> {
+                #as_inner::get_mut(&mut self.inner, key.0)
+            }
+
+            #[inline]
+            fn remove(&mut self, key: #ident) -> #option<V> {
+                #as_inner::remove(&mut self.inner, key.0)
+            }
+
+            #[inline]
+            fn remove_and_shrink(&mut self, key: #ident) -> #option<V> {
+                #as_inner::remove_and_shrink(&mut self.inner, key.0)
+            }
+
+            #[inline]
+            fn retain<F>(&mut self, mut func: F)
+            where
+                F: FnMut(#ident, &mut V) -> bool
+            {
+                #as_inner::retain(&mut self.inner, |k, v| func(#ident(k), v));
+            }
+
+            #[inline]
+            fn clear(&mut self) {
+                #as_inner::clear(&mut self.inner);
+            }
+
+            #[inline]
+            fn iter(&self) -> Self::Iter<'_> {
+                #iterator_t::map(#as_inner::iter(&self.inner), |(k, v)| (#ident(k), v))
+            }
+
+            #[inline]
+            fn keys(&self) -> Self::Keys<'_> {
+                #iterator_t::map(#as_inner::keys(&self.inner), #ident)
+            }
+
+            #[inline]
+            fn values(&self) -> Self::Values<'_> {
+                #as_inner::values(&self.inner)
+            }
+
+            #[inline]
+            fn iter_mut(&mut self) -> Self::IterMut<'_> {
+                #iterator_t::map(#as_inner::iter_mut(&mut self.inner), |(k, v)| (#ident(k), v))
+            }
+
+            #[inline]
+            fn values_mut(&mut self) -> Self::ValuesMut<'_> {
+                #as_inner::values_mut(&mut self.inner)
+            }
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                #iterator_t::map(#as_inner::into_iter(self.inner), |(k, v)| (#ident(k), v))
+            }
+
+            #[inline]
+            fn layout<F>(func: F)
+            where
+                F: FnMut(#layout_branch),
+            {
+                #as_inner::layout(func);
+            }
+
+            #[inline]
+            fn fill_fixed<F>(&mut self, func: F)
+            where
+                F: FnMut() -> V,
+            {
+                #as_inner::fill_fixed(&mut self.inner, func);
+            }
+
+            #[inline]
+            fn entry(&mut self, key: #ident) -> #entry_enum<'_, Self, #ident, V> {
+                #map_storage_t::entry_ref(self, &key)
+            }
+
+            #[inline]
+            fn entry_ref(&mut self, key: &#ident) -> #entry_enum<'_, Self, #ident, V> {
+                match #as_inner::entry_ref(&mut self.inner, &key.0) {
+                    #entry_enum::Occupied(inner) => #entry_enum::Occupied(OccupiedEntry {
+                        key: #ident(#occupied_entry_t::key(&inner)),
+                        inner,
+                    }),
+                    #entry_enum::Vacant(inner) => #entry_enum::Vacant(VacantEntry {
+                        key: #ident(#vacant_entry_t::key(&inner)),
+                        inner,
+                    }),
+                }
+            }
+        }
+
+        #vis struct OccupiedEntry<#lt, V> where V: #lt {
+            key: #ident,
+            inner: #as_inner::Occupied<#lt>,
+        }
+
+        #[automatically_derived]
+        impl<#lt, V> #occupied_entry_t<#lt, #ident, V> for OccupiedEntry<#lt, V> where V: #lt {
+            #[inline]
+            fn key(&self) -> #ident {
+                self.key
+            }
+
+            #[inline]
+            fn key_ref(&self) -> &#ident {
+                &self.key
+            }
+
+            #[inline]
+            fn get(&self) -> &V {
+                #occupied_entry_t::get(&self.inner)
+            }
+
+            #[inline]
+            fn get_mut(&mut self) -> &mut V {
+                #occupied_entry_t::get_mut(&mut self.inner)
+            }
+
+            #[inline]
+            fn into_mut(self) -> &#lt mut V {
+                #occupied_entry_t::into_mut(self.inner)
+            }
+
+            #[inline]
+            fn insert(&mut self, value: V) -> V {
+                #occupied_entry_t::insert(&mut self.inner, value)
+            }
+
+            #[inline]
+            fn remove(self) -> V {
+                #occupied_entry_t::remove(self.inner)
+            }
+        }
+
+        #vis struct VacantEntry<#lt, V> where V: #lt {
+            key: #ident,
+            inner: #as_inner::Vacant<#lt>,
+        }
+
+        #[automatically_derived]
+        impl<#lt, V> #vacant_entry_t<#lt, #ident, V> for VacantEntry<#lt, V> where V: #lt {
+            #[inline]
+            fn key(&self) -> #ident {
+                self.key
+            }
+
+            #[inline]
+            fn key_ref(&self) -> &#ident {
+                &self.key
+            }
+
+            #[inline]
+            fn insert(self, value: V) -> &#lt mut V {
+                #vacant_entry_t::insert(self.inner, value)
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #stats_storage_t<#ident, V> for __MapStorage<V> where #inner: #stats_storage_t<#element, V> {
+            #[inline]
+            fn stats(&self) -> #stats {
+                <#inner as #stats_storage_t<#element, V>>::stats(&self.inner)
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #drain_storage_t<#ident, V> for __MapStorage<V> where #inner: #drain_storage_t<#element, V> {
+            type Drain<#lt> = #iterator_map<<#inner as #drain_storage_t<#element, V>>::Drain<#lt>, fn((#element, V)) -> (#ident, V)> where V: #lt;
+
+            #[inline]
+            fn drain(&mut self) -> Self::Drain<'_> {
+                #iterator_t::map(<#inner as #drain_storage_t<#element, V>>::drain(&mut self.inner), |(k, v)| (#ident(k), v))
+            }
+        }
+
+        #vis struct ExtractIf<#lt, V> where V: #lt, #inner: #extract_if_storage_t<#element, V> {
+            inner: <#inner as #extract_if_storage_t<#element, V>>::ExtractIf<#lt>,
+        }
+
+        #[automatically_derived]
+        impl<#lt, V> #extract_if_cursor_t<#ident, V> for ExtractIf<#lt, V> where V: #lt, #inner: #extract_if_storage_t<#element, V> {
+            #[inline]
+            fn next_if<F>(&mut self, filter: &mut F) -> #option<(#ident, V)>
+            where
+                F: ?Sized + FnMut(#ident, &mut V) -> bool,
+            {
+                let (k, v) = #extract_if_cursor_t::next_if(&mut self.inner, &mut |k, v| filter(#ident(k), v))?;
+                #option::Some((#ident(k), v))
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #extract_if_storage_t<#ident, V> for __MapStorage<V> where #inner: #extract_if_storage_t<#element, V> {
+            type ExtractIf<#lt> = ExtractIf<#lt, V> where V: #lt;
+
+            #[inline]
+            fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+                ExtractIf {
+                    inner: <#inner as #extract_if_storage_t<#element, V>>::extract_if(&mut self.inner),
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #class_storage_t<#ident, V> for __MapStorage<V> where #inner: #class_storage_t<#element, V> {
+            type ClassIter<#lt> = #iterator_map<<#inner as #class_storage_t<#element, V>>::ClassIter<#lt>, fn((#element, &#lt V)) -> (#ident, &#lt V)> where V: #lt;
+
+            #[inline]
+            fn class_len(&self, class: usize) -> usize {
+                <#inner as #class_storage_t<#element, V>>::class_len(&self.inner, class)
+            }
+
+            #[inline]
+            fn clear_class(&mut self, class: usize) {
+                <#inner as #class_storage_t<#element, V>>::clear_class(&mut self.inner, class)
+            }
+
+            #[inline]
+            fn iter_class(&self, class: usize) -> Self::ClassIter<'_> {
+                #iterator_t::map(<#inner as #class_storage_t<#element, V>>::iter_class(&self.inner, class), |(k, v)| (#ident(k), v))
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #entries_storage_t<#ident, V> for __MapStorage<V> where #inner: #entries_storage_t<#element, V> {
+            type EntriesMut<#lt> = #iterator_map<<#inner as #entries_storage_t<#element, V>>::EntriesMut<#lt>, fn(#entry_enum<#lt, #inner, #element, V>) -> #entry_enum<#lt, Self, #ident, V>> where V: #lt;
+
+            #[inline]
+            fn entries_mut(&mut self) -> Self::EntriesMut<'_> {
+                #iterator_t::map(<#inner as #entries_storage_t<#element, V>>::entries_mut(&mut self.inner), |entry| match entry {
+                    #entry_enum::Occupied(inner) => #entry_enum::Occupied(OccupiedEntry {
+                        key: #ident(#occupied_entry_t::key(&inner)),
+                        inner,
+                    }),
+                    #entry_enum::Vacant(inner) => #entry_enum::Vacant(VacantEntry {
+                        key: #ident(#vacant_entry_t::key(&inner)),
+                        inner,
+                    }),
+                })
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #slice_storage_t<#ident, V> for __MapStorage<V> where #inner: #slice_storage_t<#element, V> {
+            #[inline]
+            fn as_slice(&self) -> &[#option<V>] {
+                <#inner as #slice_storage_t<#element, V>>::as_slice(&self.inner)
+            }
+
+            #[inline]
+            fn as_mut_slice(&mut self) -> &mut [#option<V>] {
+                <#inner as #slice_storage_t<#element, V>>::as_mut_slice(&mut self.inner)
+            }
+        }
+    }
+}
+
+/// Implement `SetStorage` implementation.
+fn impl_set_storage(cx: &Ctxt<'_>, opts: &Opts, element: &syn::Type) -> TokenStream {
+    let repr = opts.storage_repr(true);
+    let ident = &cx.ast.ident;
+    let vis = &cx.ast.vis;
+    let lt = cx.lt;
+
+    let clone_t = cx.toks.clone_t();
+    let copy_t = cx.toks.copy_t();
+    let eq_t = cx.toks.eq_t();
+    let hash_t = cx.toks.hash_t();
+    let hasher_t = cx.toks.hasher_t();
+    let iterator_map = cx.toks.iterator_map();
+    let iterator_t = cx.toks.iterator_t();
+    let key_t = cx.toks.key_t();
+    let option = cx.toks.option();
+    let ord_t = cx.toks.ord_t();
+    let ordering = cx.toks.ordering();
+    let partial_eq_t = cx.toks.partial_eq_t();
+    let partial_ord_t = cx.toks.partial_ord_t();
+    let raw_storage_t = cx.toks.raw_storage_t();
+    let raw_storage_sealed_t = cx.toks.raw_storage_sealed_t();
+    let set_class_storage_t = cx.toks.set_class_storage_t();
+    let set_storage_t = cx.toks.set_storage_t();
+
+    let inner = quote!(<#element as #key_t>::SetStorage);
+    let as_inner = quote!(<#inner as #set_storage_t<#element>>);
+
+    let debug_impl = debug::impl_set(cx, ident, quote!(__SetStorage));
+
+    quote! {
+        #repr
+        #vis struct __SetStorage {
+            inner: #inner,
+        }
+
+        #[automatically_derived]
+        impl #clone_t for __SetStorage where for<'trivial_bounds> #inner: #clone_t {
+            #[inline]
+            fn clone(&self) -> Self {
+                Self {
+                    inner: #clone_t::clone(&self.inner),
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #copy_t for __SetStorage where for<'trivial_bounds> #inner: #copy_t {}
+
+        #[automatically_derived]
+        impl #partial_eq_t for __SetStorage where for<'trivial_bounds> #inner: #partial_eq_t {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                #partial_eq_t::eq(&self.inner, &other.inner)
+            }
+        }
+
+        #[automatically_derived]
+        impl #eq_t for __SetStorage where for<'trivial_bounds> #inner: #eq_t {}
+
+        #[automatically_derived]
+        impl #hash_t for __SetStorage where for<'trivial_bounds> #inner: #hash_t {
+            #[inline]
+            fn hash<H>(&self, state: &mut H)
+            where
+                H: #hasher_t,
+            {
+                #hash_t::hash(&self.inner, state);
+            }
+        }
+
+        #[automatically_derived]
+        impl #partial_ord_t for __SetStorage where for<'trivial_bounds> #inner: #partial_ord_t {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> #option<#ordering> {
+                #partial_ord_t::partial_cmp(&self.inner, &other.inner)
+            }
+        }
+
+        #[automatically_derived]
+        impl #ord_t for __SetStorage where for<'trivial_bounds> #inner: #ord_t {
+            #[inline]
+            fn cmp(&self, other: &Self) -> #ordering {
+                #ord_t::cmp(&self.inner, &other.inner)
+            }
+        }
+
+        #debug_impl
+
+        #[automatically_derived]
+        impl #set_storage_t<#ident> for __SetStorage {
+            type Iter<#lt> = #iterator_map<#as_inner::Iter<#lt>, fn(#element) -> #ident>;
+            type IntoIter = #iterator_map<#as_inner::IntoIter, fn(#element) -> #ident>;
+
+            #[inline]
+            fn empty() -> Self {
+                Self {
+                    inner: #as_inner::empty(),
+                }
+            }
+
+            #[inline]
+            fn len(&self) -> usize {
+                #as_inner::len(&self.inner)
+            }
+
+            #[inline]
+            fn is_empty(&self) -> bool {
+                #as_inner::is_empty(&self.inner)
+            }
+
+            #[inline]
+            fn insert(&mut self, value: #ident) -> bool {
+                #as_inner::insert(&mut self.inner, value.0)
+            }
+
+            #[inline]
+            fn contains(&self, value: #ident) -> bool {
+                #as_inner::contains(&self.inner, value.0)
+            }
+
+            #[inline]
+            fn remove(&mut self, value: #ident) -> bool {
+                #as_inner::remove(&mut self.inner, value.0)
+            }
+
+            #[inline]
+            fn retain<F>(&mut self, mut func: F)
+            where
+                F: FnMut(#ident) -> bool
+            {
+                #as_inner::retain(&mut self.inner, |k| func(#ident(k)));
+            }
+
+            #[inline]
+            fn clear(&mut self) {
+                #as_inner::clear(&mut self.inner);
+            }
+
+            #[inline]
+            fn union_with(&mut self, other: &Self) {
+                #as_inner::union_with(&mut self.inner, &other.inner);
+            }
+
+            #[inline]
+            fn intersect_with(&mut self, other: &Self) {
+                #as_inner::intersect_with(&mut self.inner, &other.inner);
+            }
+
+            #[inline]
+            fn subtract(&mut self, other: &Self) {
+                #as_inner::subtract(&mut self.inner, &other.inner);
+            }
+
+            #[inline]
+            fn iter(&self) -> Self::Iter<'_> {
+                #iterator_t::map(#as_inner::iter(&self.inner), #ident)
+            }
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                #iterator_t::map(#as_inner::into_iter(self.inner), #ident)
+            }
+        }
+
+        #[automatically_derived]
+        impl #raw_storage_sealed_t for __SetStorage where for<'trivial_bounds> #inner: #raw_storage_t {}
+
+        #[automatically_derived]
+        impl #raw_storage_t for __SetStorage where for<'trivial_bounds> #inner: #raw_storage_t {
+            type Value = <#inner as #raw_storage_t>::Value;
+
+            const MASK: Self::Value = <#inner as #raw_storage_t>::MASK;
+
+            #[inline]
+            fn as_raw(&self) -> Self::Value {
+                #raw_storage_t::as_raw(&self.inner)
+            }
+
+            #[inline]
+            fn as_raw_ref(&self) -> &Self::Value {
+                #raw_storage_t::as_raw_ref(&self.inner)
+            }
+
+            #[inline]
+            fn from_raw(raw: Self::Value) -> Self {
+                Self {
+                    inner: #raw_storage_t::from_raw(raw),
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #set_class_storage_t<#ident> for __SetStorage where for<'trivial_bounds> #inner: #set_class_storage_t<#element> {
+            #[inline]
+            fn contains_class(&self, class: usize) -> bool {
+                <#inner as #set_class_storage_t<#element>>::contains_class(&self.inner, class)
+            }
+        }
+    }
+}
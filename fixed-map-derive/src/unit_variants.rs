@@ -3,7 +3,13 @@ use quote::{format_ident, quote};
 use syn::spanned::Spanned;
 use syn::{DataEnum, Ident, LitInt};
 
+use crate::attrs;
+use crate::capacity;
 use crate::context::{Ctxt, Opts};
+use crate::debug;
+use crate::schema;
+use crate::single_variant;
+use crate::variant_name;
 
 /// Every variant is a unit variant.
 pub(crate) fn implement(cx: &Ctxt<'_>, opts: &Opts, en: &DataEnum) -> Result<TokenStream, ()> {
@@ -13,37 +19,201 @@ pub(crate) fn implement(cx: &Ctxt<'_>, opts: &Opts, en: &DataEnum) -> Result<Tok
     let count = en.variants.len();
     let mut names = Vec::with_capacity(count);
 
-    for (index, _) in en.variants.iter().enumerate() {
+    for (index, variant) in en.variants.iter().enumerate() {
+        let variant_opts = attrs::parse_variant_attrs(cx, variant)?;
+
+        if variant_opts.values.is_some() {
+            cx.span_error(
+                variant.span(),
+                "#[key(values(...))] is only supported on variants with a single field",
+            );
+            return Err(());
+        }
+
+        if let Some(span) = variant_opts.lazy {
+            cx.span_error(
+                span,
+                "#[key(lazy)] is only supported on variants with a single field",
+            );
+            return Err(());
+        }
+
+        if let Some(span) = variant_opts.dynamic {
+            cx.span_error(
+                span,
+                "#[key(dynamic = \"ordered\")] is only supported on variants with a single field",
+            );
+            return Err(());
+        }
+
         names.push(format_ident!("_{}", index));
     }
 
-    let entry_impl = impl_entry(cx, &map_storage)?;
-    let map_storage_impl = impl_map(cx, en, &map_storage, &names)?;
+    let ident = &cx.ast.ident;
 
-    let set_storage_impl = if opts.bitset.is_some() {
-        impl_bitset(cx, en, &set_storage)?
+    // Sparse keys use storage provided by the crate, which only stores the
+    // entries which are present.
+    let (storage_impl, map_storage, set_storage) = if opts.sparse.is_some() {
+        let require_alloc = cx.toks.require_alloc();
+        let sparse_map_storage = cx.toks.sparse_map_storage();
+        let sparse_set_storage = cx.toks.sparse_set_storage();
+        let sparse_index_impl = impl_sparse_index(cx, en);
+
+        (
+            quote! {
+                #require_alloc!(sparse #ident);
+                #sparse_index_impl
+            },
+            quote!(#sparse_map_storage<#ident, V>),
+            quote!(#sparse_set_storage<#ident>),
+        )
     } else {
-        impl_set(cx, en, &set_storage, &names)?
+        // Enums with a single variant don't need to be laid out as arrays,
+        // since the key carries no information.
+        let single = if count == 1 {
+            en.variants.first()
+        } else {
+            None
+        };
+
+        // Masked keys store maps in storage provided by the crate, which
+        // tracks the entries which are present in a bit mask.
+        let (map_storage_impl, map_storage) = if opts.masked.is_some() {
+            let masked_map_storage = cx.toks.masked_map_storage();
+            let (ty, _) = determine_bits(cx, en)?;
+
+            (
+                TokenStream::new(),
+                quote!(#masked_map_storage<#ident, V, #ty, #count>),
+            )
+        } else {
+            let entry_impl = impl_entry(cx, &map_storage)?;
+
+            let map_storage_impl = match single {
+                Some(variant) => single_variant::impl_map(cx, opts, variant, &map_storage),
+                None => impl_map(cx, opts, en, &map_storage, &names)?,
+            };
+
+            (
+                quote! {
+                    #entry_impl
+                    #map_storage_impl
+                },
+                quote!(#map_storage<V>),
+            )
+        };
+
+        let set_storage_impl = match single {
+            _ if opts.bitset.is_some() => impl_bitset(cx, opts, en, &set_storage)?,
+            Some(variant) => single_variant::impl_set(cx, opts, variant, &set_storage),
+            None => impl_set(cx, opts, en, &set_storage, &names)?,
+        };
+
+        (
+            quote! {
+                #map_storage_impl
+                #set_storage_impl
+            },
+            map_storage,
+            quote!(#set_storage),
+        )
     };
 
-    let ident = &cx.ast.ident;
     let key_t = cx.toks.key_t();
+    let schema_variant = cx.toks.schema_variant();
+
+    let key_schema_hash = schema::key_schema_hash(cx, en);
+    let key_variants = schema::key_variants(cx, opts, en)?;
+    let variant_name = variant_name::implement(cx, en);
+    let capacity = capacity::implement(cx, en);
+    let key_index_impl = impl_key_index(cx, en);
 
     Ok(quote! {
         const _: () = {
-            #entry_impl
-            #map_storage_impl
-            #set_storage_impl
+            #storage_impl
 
             #[automatically_derived]
             impl #key_t for #ident {
-                type MapStorage<V> = #map_storage<V>;
+                type MapStorage<V> = #map_storage;
                 type SetStorage = #set_storage;
+                const KEY_SCHEMA_HASH: u64 = #key_schema_hash;
+                const KEY_VARIANTS: &'static [#schema_variant] = #key_variants;
+                #capacity
+                #variant_name
             }
+
+            #key_index_impl
         };
     })
 }
 
+/// Implement `KeyIndex`, where the index of a variant is the slot it occupies
+/// in map storage.
+fn impl_key_index(cx: &Ctxt<'_>, en: &DataEnum) -> TokenStream {
+    let ident = &cx.ast.ident;
+    let key_index_t = cx.toks.key_index_t();
+    let option = cx.toks.option();
+
+    let variants = en.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+    let count = en.variants.len();
+    let indexes = (0..count).collect::<Vec<_>>();
+
+    quote! {
+        #[automatically_derived]
+        impl #key_index_t for #ident {
+            const LEN: usize = #count;
+
+            #[inline]
+            fn to_index(self) -> usize {
+                match self {
+                    #(#ident::#variants => #indexes,)*
+                }
+            }
+
+            #[inline]
+            fn from_index(index: usize) -> #option<Self> {
+                match index {
+                    #(#indexes => #option::Some(#ident::#variants),)*
+                    _ => #option::None,
+                }
+            }
+        }
+    }
+}
+
+/// Implement `SparseIndex`, which is the same as the `KeyIndex` of a variant
+/// but as a `u16`.
+fn impl_sparse_index(cx: &Ctxt<'_>, en: &DataEnum) -> TokenStream {
+    let ident = &cx.ast.ident;
+    let sparse_index_t = cx.toks.sparse_index_t();
+    let option = cx.toks.option();
+
+    let variants = en.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+    let indexes = (0..variants.len())
+        .map(|index| index as u16)
+        .collect::<Vec<_>>();
+
+    quote! {
+        #[automatically_derived]
+        impl #sparse_index_t for #ident {
+            #[inline]
+            fn to_sparse_index(self) -> u16 {
+                match self {
+                    #(#ident::#variants => #indexes,)*
+                }
+            }
+
+            #[inline]
+            fn from_sparse_index(index: u16) -> #option<Self> {
+                match index {
+                    #(#indexes => #option::Some(#ident::#variants),)*
+                    _ => #option::None,
+                }
+            }
+        }
+    }
+}
+
 fn impl_entry(cx: &Ctxt<'_>, map_storage: &Ident) -> Result<TokenStream, ()> {
     let ident = &cx.ast.ident;
     let lt = cx.lt;
@@ -70,6 +240,11 @@ fn impl_entry(cx: &Ctxt<'_>, map_storage: &Ident) -> Result<TokenStream, ()> {
                 self.key
             }
 
+            #[inline]
+            fn key_ref(&self) -> &#ident {
+                &self.key
+            }
+
             #[inline]
             fn insert(self, value: V) -> &#lt mut V {
                 #option_bucket_none::insert(self.inner, value)
@@ -88,6 +263,11 @@ fn impl_entry(cx: &Ctxt<'_>, map_storage: &Ident) -> Result<TokenStream, ()> {
                 self.key
             }
 
+            #[inline]
+            fn key_ref(&self) -> &#ident {
+                &self.key
+            }
+
             #[inline]
             fn get(&self) -> &V {
                 #option_bucket_some::as_ref(&self.inner)
@@ -126,10 +306,12 @@ fn impl_entry(cx: &Ctxt<'_>, map_storage: &Ident) -> Result<TokenStream, ()> {
 
 fn impl_map(
     cx: &Ctxt<'_>,
+    opts: &Opts,
     en: &DataEnum,
     map_storage: &Ident,
     names: &[Ident],
 ) -> Result<TokenStream, ()> {
+    let repr = opts.storage_repr(true);
     let ident = &cx.ast.ident;
     let lt = &cx.lt;
     let vis = &cx.ast.vis;
@@ -139,6 +321,7 @@ fn impl_map(
     let array_into_iter = cx.toks.array_into_iter();
     let clone_t = cx.toks.clone_t();
     let copy_t = cx.toks.copy_t();
+    let entries_storage_t = cx.toks.entries_storage_t();
     let entry_enum = cx.toks.entry_enum();
     let eq_t = cx.toks.eq_t();
     let hash_t = cx.toks.hash_t();
@@ -146,9 +329,11 @@ fn impl_map(
     let iterator_cmp = cx.toks.iterator_cmp();
     let iterator_flat_map = cx.toks.iterator_flat_map();
     let iterator_flatten = cx.toks.iterator_flatten();
+    let iterator_map = cx.toks.iterator_map();
     let iterator_partial_cmp = cx.toks.iterator_partial_cmp();
     let mem = cx.toks.mem();
     let option = cx.toks.option();
+    let option_into_iter = cx.toks.option_into_iter();
     let ord_t = cx.toks.ord_t();
     let ordering = cx.toks.ordering();
     let partial_eq_t = cx.toks.partial_eq_t();
@@ -156,17 +341,36 @@ fn impl_map(
     let slice_iter = cx.toks.slice_iter();
     let slice_iter_mut = cx.toks.slice_iter_mut();
     let map_storage_t = cx.toks.map_storage_t();
+    let stats = cx.toks.stats();
+    let drain_storage_t = cx.toks.drain_storage_t();
+    let storage_drain = cx.toks.storage_drain();
+    let extract_if_storage_t = cx.toks.extract_if_storage_t();
+    let storage_extract_slots = cx.toks.storage_extract_slots();
+    let stats_storage_t = cx.toks.stats_storage_t();
+    let class_storage_t = cx.toks.class_storage_t();
+    let slice_storage_t = cx.toks.slice_storage_t();
+    let get_disjoint_mut = cx.toks.get_disjoint_mut();
+    let key_t = cx.toks.key_t();
+    let set_storage_t = cx.toks.set_storage_t();
+    let layout_branch = cx.toks.layout_branch();
+    let offset_of = cx.toks.offset_of();
 
     let variants = en.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+    let variant_names = variants.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+    let rev_names = names.iter().rev();
+    let rev_variants = variants.iter().rev();
     let init = en
         .variants
         .iter()
         .map(|_| quote!(#option::None))
         .collect::<Vec<_>>();
     let count = en.variants.len();
+    let indexes = (0..count).collect::<Vec<_>>();
+
+    let debug_impl = debug::impl_map(cx, ident, map_storage);
 
     Ok(quote! {
-        #[repr(transparent)]
+        #repr
         #vis struct #map_storage<V> {
             data: [#option<V>; #count],
         }
@@ -223,6 +427,8 @@ fn impl_map(
             }
         }
 
+        #debug_impl
+
         #[automatically_derived]
         impl<V> #map_storage_t<#ident, V> for #map_storage<V> {
             type Iter<#lt> = #iterator_flat_map<
@@ -324,6 +530,73 @@ fn impl_map(
                 })*
             }
 
+            #[inline]
+            fn first_key_value(&self) -> #option<(#ident, &V)> {
+                let [#(#names),*] = &self.data;
+
+                #(if let #option::Some(value) = #names {
+                    return #option::Some((#ident::#variants, value));
+                })*
+
+                #option::None
+            }
+
+            #[inline]
+            fn last_key_value(&self) -> #option<(#ident, &V)> {
+                let [#(#names),*] = &self.data;
+
+                #(if let #option::Some(value) = #rev_names {
+                    return #option::Some((#ident::#rev_variants, value));
+                })*
+
+                #option::None
+            }
+
+            #[inline]
+            fn key_set(&self) -> <#ident as #key_t>::SetStorage
+            where
+                #ident: #key_t,
+            {
+                let [#(#names),*] = &self.data;
+                let mut output = <<#ident as #key_t>::SetStorage as #set_storage_t<#ident>>::empty();
+                #(if #option::is_some(#names) { #set_storage_t::insert(&mut output, #ident::#variants); })*
+                output
+            }
+
+            #[inline]
+            fn retain_keys(&mut self, allowed: &<#ident as #key_t>::SetStorage)
+            where
+                #ident: #key_t,
+            {
+                let [#(#names),*] = &mut self.data;
+                #(if !#set_storage_t::contains(allowed, #ident::#variants) { *#names = #option::None; })*
+            }
+
+            #[inline]
+            fn fill_fixed<__F>(&mut self, mut func: __F)
+            where
+                __F: FnMut() -> V,
+            {
+                let [#(#names),*] = &mut self.data;
+                #(if #option::is_none(#names) { *#names = #option::Some(func()); })*
+            }
+
+            #[inline]
+            fn find_map<__B, __F>(&self, mut func: __F) -> #option<__B>
+            where
+                __F: FnMut(#ident, &V) -> #option<__B>
+            {
+                let [#(#names),*] = &self.data;
+
+                #(if let #option::Some(val) = #names {
+                    if let #option::Some(output) = func(#ident::#variants, val) {
+                        return #option::Some(output);
+                    }
+                })*
+
+                #option::None
+            }
+
             #[inline]
             fn clear(&mut self) {
                 self.data = [#(#init),*];
@@ -363,6 +636,29 @@ fn impl_map(
                 #iterator_t::flat_map(#into_iterator_t::into_iter([#((#ident::#variants, #names)),*]), |(k, v)| #option::Some((k, v?)))
             }
 
+            #[inline]
+            fn map_values<__W, __F>(self, mut func: __F) -> <#ident as #key_t>::MapStorage<__W>
+            where
+                #ident: #key_t,
+                __F: FnMut(#ident, V) -> __W,
+            {
+                let [#(#names),*] = self.data;
+                let mut output = #map_storage::<__W> { data: [#(#init),*] };
+                #(output.data[#indexes] = #option::map(#names, |v| func(#ident::#variants, v));)*
+                output
+            }
+
+            #[inline]
+            fn layout<F>(mut func: F)
+            where
+                F: FnMut(#layout_branch),
+            {
+                let storage = <Self as #map_storage_t<#ident, V>>::empty();
+                let offset = #offset_of(&storage, &storage.data);
+                let size = #mem::size_of::<#option<V>>();
+                #(func(#layout_branch::new::<#option<V>>(#variant_names, #option::Some(offset + #indexes * size)));)*
+            }
+
             #[inline]
             fn entry(&mut self, key: #ident) -> #entry_enum<'_, Self, #ident, V> {
                 let [#(#names),*] = &mut self.data;
@@ -371,12 +667,118 @@ fn impl_map(
                     #(#ident::#variants => option_to_entry(#names, key),)*
                 }
             }
+
+            #[inline]
+            fn get_disjoint_mut<#lt>(
+                &#lt mut self,
+                key: &dyn Fn(usize) -> #option<#ident>,
+                _: &dyn Fn(&#ident, &#ident) -> bool,
+                output: &mut [#option<&#lt mut V>],
+            ) {
+                #get_disjoint_mut(&mut self.data, key, output, |key| match key {
+                    #(#ident::#variants => #indexes,)*
+                })
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #stats_storage_t<#ident, V> for #map_storage<V> {
+            #[inline]
+            fn stats(&self) -> #stats {
+                #stats::fixed(#count, #map_storage_t::len(self))
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #drain_storage_t<#ident, V> for #map_storage<V> {
+            type Drain<#lt> = <Self as #map_storage_t<#ident, V>>::IntoIter where V: #lt;
+
+            #[inline]
+            fn drain(&mut self) -> Self::Drain<'_> {
+                #storage_drain(self)
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #extract_if_storage_t<#ident, V> for #map_storage<V> {
+            type ExtractIf<#lt> = #storage_extract_slots<#array_into_iter<(#ident, &#lt mut #option<V>), #count>> where V: #lt;
+
+            #[inline]
+            fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+                let [#(#names),*] = &mut self.data;
+                #storage_extract_slots::new([#((#ident::#variants, #names)),*])
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #class_storage_t<#ident, V> for #map_storage<V> {
+            type ClassIter<#lt> = #option_into_iter<(#ident, &#lt V)> where V: #lt;
+
+            #[inline]
+            fn class_len(&self, class: usize) -> usize {
+                match self.data.get(class) {
+                    #option::Some(#option::Some(..)) => 1,
+                    _ => 0,
+                }
+            }
+
+            #[inline]
+            fn clear_class(&mut self, class: usize) {
+                if let #option::Some(value) = self.data.get_mut(class) {
+                    *value = #option::None;
+                }
+            }
+
+            #[inline]
+            fn iter_class(&self, class: usize) -> Self::ClassIter<'_> {
+                let key = match class {
+                    #(#indexes => #option::Some(#ident::#variants),)*
+                    _ => #option::None,
+                };
+
+                #into_iterator_t::into_iter(#option::and_then(key, |key| {
+                    #option::map(#map_storage_t::get(self, key), |value| (key, value))
+                }))
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #slice_storage_t<#ident, V> for #map_storage<V> {
+            #[inline]
+            fn as_slice(&self) -> &[#option<V>] {
+                &self.data
+            }
+
+            #[inline]
+            fn as_mut_slice(&mut self) -> &mut [#option<V>] {
+                &mut self.data
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #entries_storage_t<#ident, V> for #map_storage<V> {
+            type EntriesMut<#lt> = #iterator_map<
+                #array_into_iter<(#ident, &#lt mut #option<V>), #count>,
+                fn((#ident, &#lt mut #option<V>)) -> #entry_enum<#lt, Self, #ident, V>
+            > where V: #lt;
+
+            #[inline]
+            fn entries_mut(&mut self) -> Self::EntriesMut<'_> {
+                let [#(#names),*] = &mut self.data;
+                #iterator_t::map(#into_iterator_t::into_iter([#((#ident::#variants, #names)),*]), |(k, v)| option_to_entry(v, k))
+            }
         }
     })
 }
 
 /// Implement as bitset storage.
-fn impl_bitset(cx: &Ctxt<'_>, en: &DataEnum, set_storage: &Ident) -> Result<TokenStream, ()> {
+fn impl_bitset(
+    cx: &Ctxt<'_>,
+    opts: &Opts,
+    en: &DataEnum,
+    set_storage: &Ident,
+) -> Result<TokenStream, ()> {
+    let repr = opts.storage_repr(true);
     let (ty, _) = determine_bits(cx, en)?;
 
     let vis = &cx.ast.vis;
@@ -399,7 +801,11 @@ fn impl_bitset(cx: &Ctxt<'_>, en: &DataEnum, set_storage: &Ident) -> Result<Toke
     let partial_eq_t = cx.toks.partial_eq_t();
     let partial_ord_t = cx.toks.partial_ord_t();
     let set_storage_t = cx.toks.set_storage_t();
+    let set_class_storage_t = cx.toks.set_class_storage_t();
+    let set_range_storage_t = cx.toks.set_range_storage_t();
     let raw_storage_t = cx.toks.raw_storage_t();
+    let raw_storage_sealed_t = cx.toks.raw_storage_sealed_t();
+    let bit_index_t = cx.toks.bit_index_t();
 
     let variants = en.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
 
@@ -410,6 +816,17 @@ fn impl_bitset(cx: &Ctxt<'_>, en: &DataEnum, set_storage: &Ident) -> Result<Toke
         .map(|(n, v)| LitInt::new(&format!("{}", 1u128 << n), v.span()))
         .collect::<Vec<_>>();
 
+    let indexes = en
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(n, v)| LitInt::new(&format!("{n}u32"), v.span()))
+        .collect::<Vec<_>>();
+
+    let classes = 0..en.variants.len();
+
+    let debug_impl = debug::impl_set(cx, ident, set_storage);
+
     Ok(quote! {
         #[inline]
         const fn to_bits(value: #ident) -> #ty {
@@ -418,7 +835,7 @@ fn impl_bitset(cx: &Ctxt<'_>, en: &DataEnum, set_storage: &Ident) -> Result<Toke
             }
         }
 
-        #[repr(transparent)]
+        #repr
         #[derive(#clone_t, #copy_t, #partial_eq_t, #eq_t, #hash_t)]
         #vis struct #set_storage {
             data: #ty,
@@ -440,6 +857,8 @@ fn impl_bitset(cx: &Ctxt<'_>, en: &DataEnum, set_storage: &Ident) -> Result<Toke
             }
         }
 
+        #debug_impl
+
         #[automatically_derived]
         impl #set_storage_t<#ident> for #set_storage {
             type Iter<#lt> = #iterator_flatten<#array_into_iter<#option<#ident>, #count>>;
@@ -497,11 +916,72 @@ fn impl_bitset(cx: &Ctxt<'_>, en: &DataEnum, set_storage: &Ident) -> Result<Toke
                 self.data = update;
             }
 
+            #[inline]
+            fn retain_indexed<F>(&mut self, mut f: F)
+            where
+                #ident: #bit_index_t,
+                F: FnMut(u32, #ident) -> bool
+            {
+                let mut update = 0;
+
+                #(if self.data & #numbers != 0 {
+                    if f(#indexes, #ident::#variants) {
+                        update |= #numbers;
+                    }
+                })*
+
+                self.data = update;
+            }
+
+            #[inline]
+            fn find<F>(&self, mut f: F) -> #option<#ident>
+            where
+                F: FnMut(#ident) -> bool
+            {
+                let mut data = self.data;
+
+                #(
+                    if data == 0 {
+                        return #option::None;
+                    }
+
+                    if data & #numbers != 0 {
+                        if f(#ident::#variants) {
+                            return #option::Some(#ident::#variants);
+                        }
+
+                        data &= !#numbers;
+                    }
+                )*
+
+                #option::None
+            }
+
             #[inline]
             fn clear(&mut self) {
                 self.data = 0;
             }
 
+            #[inline]
+            fn union_with(&mut self, other: &Self) {
+                self.data |= other.data;
+            }
+
+            #[inline]
+            fn intersect_with(&mut self, other: &Self) {
+                self.data &= other.data;
+            }
+
+            #[inline]
+            fn subtract(&mut self, other: &Self) {
+                self.data &= !other.data;
+            }
+
+            #[inline]
+            fn intersection_len(&self, other: &Self) -> usize {
+                <#ty>::count_ones(self.data & other.data) as usize
+            }
+
             #[inline]
             fn iter(&self) -> Self::Iter<'_> {
                 #iterator_t::flatten(#into_iterator_t::into_iter([#(if self.data & #numbers != 0 { Some(#ident::#variants) } else { None }),*]))
@@ -513,24 +993,75 @@ fn impl_bitset(cx: &Ctxt<'_>, en: &DataEnum, set_storage: &Ident) -> Result<Toke
             }
         }
 
+        #[automatically_derived]
+        impl #bit_index_t for #ident {
+            #[inline]
+            fn to_bit_index(self) -> u32 {
+                match self {
+                    #(#ident::#variants => #indexes,)*
+                }
+            }
+
+            #[inline]
+            fn from_bit_index(index: u32) -> #option<Self> {
+                match index {
+                    #(#indexes => #option::Some(#ident::#variants),)*
+                    _ => #option::None,
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #raw_storage_sealed_t for #set_storage {}
+
         #[automatically_derived]
         impl #raw_storage_t for #set_storage {
             type Value = #ty;
 
+            const MASK: #ty = 0 #(| #numbers)*;
+
             #[inline]
             fn as_raw(&self) -> #ty {
                 self.data
             }
 
+            #[inline]
+            fn as_raw_ref(&self) -> &#ty {
+                &self.data
+            }
+
             #[inline]
             fn from_raw(data: #ty) -> #set_storage {
                 #set_storage { data }
             }
         }
+
+        #[automatically_derived]
+        impl #set_class_storage_t<#ident> for #set_storage {
+            #[inline]
+            fn contains_class(&self, class: usize) -> bool {
+                match class {
+                    #(#classes => self.data & #numbers != 0,)*
+                    _ => false,
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #set_range_storage_t<#ident> for #set_storage {
+            #[inline]
+            fn insert_range(&mut self, start: usize, end: usize) {
+                let len = end - start;
+
+                if len != 0 {
+                    self.data |= (#ty::MAX >> (#ty::BITS - len as u32)) << start;
+                }
+            }
+        }
     })
 }
 
-fn determine_bits(cx: &Ctxt<'_>, en: &DataEnum) -> Result<(Ident, usize), ()> {
+pub(crate) fn determine_bits(cx: &Ctxt<'_>, en: &DataEnum) -> Result<(Ident, usize), ()> {
     Ok(match en.variants.len() {
         0..=8 => (Ident::new("u8", Span::call_site()), 8),
         9..=16 => (Ident::new("u16", Span::call_site()), 16),
@@ -550,10 +1081,12 @@ fn determine_bits(cx: &Ctxt<'_>, en: &DataEnum) -> Result<(Ident, usize), ()> {
 /// Implement set storage.
 fn impl_set(
     cx: &Ctxt<'_>,
+    opts: &Opts,
     en: &DataEnum,
     set_storage: &Ident,
     names: &[Ident],
 ) -> Result<TokenStream, ()> {
+    let repr = opts.storage_repr(true);
     let vis = &cx.ast.vis;
     let ident = &cx.ast.ident;
     let lt = cx.lt;
@@ -576,6 +1109,8 @@ fn impl_set(
     let partial_eq_t = cx.toks.partial_eq_t();
     let partial_ord_t = cx.toks.partial_ord_t();
     let set_storage_t = cx.toks.set_storage_t();
+    let set_class_storage_t = cx.toks.set_class_storage_t();
+    let set_range_storage_t = cx.toks.set_range_storage_t();
 
     let variants = en.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
     let init = en
@@ -584,8 +1119,10 @@ fn impl_set(
         .map(|_| quote!(false))
         .collect::<Vec<_>>();
 
+    let debug_impl = debug::impl_set(cx, ident, set_storage);
+
     Ok(quote! {
-        #[repr(transparent)]
+        #repr
         #[derive(#clone_t, #copy_t, #partial_eq_t, #eq_t, #hash_t)]
         #vis struct #set_storage {
             data: [bool; #count],
@@ -607,6 +1144,8 @@ fn impl_set(
             }
         }
 
+        #debug_impl
+
         #[automatically_derived]
         impl #set_storage_t<#ident> for #set_storage {
             type Iter<#lt> = #iterator_flatten<#array_into_iter<#option<#ident>, #count>>;
@@ -670,6 +1209,20 @@ fn impl_set(
                 })*
             }
 
+            #[inline]
+            fn find<F>(&self, mut f: F) -> #option<#ident>
+            where
+                F: FnMut(#ident) -> bool
+            {
+                let [#(#names),*] = &self.data;
+
+                #(if *#names && f(#ident::#variants) {
+                    return #option::Some(#ident::#variants);
+                })*
+
+                #option::None
+            }
+
             #[inline]
             fn clear(&mut self) {
                 self.data = [#(#init),*];
@@ -687,5 +1240,26 @@ fn impl_set(
                 #iterator_t::flatten(#into_iterator_t::into_iter([#(if *#names { Some(#ident::#variants) } else { None }),*]))
             }
         }
+
+        #[automatically_derived]
+        impl #set_class_storage_t<#ident> for #set_storage {
+            #[inline]
+            fn contains_class(&self, class: usize) -> bool {
+                match self.data.get(class) {
+                    #option::Some(data) => *data,
+                    #option::None => false,
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #set_range_storage_t<#ident> for #set_storage {
+            #[inline]
+            fn insert_range(&mut self, start: usize, end: usize) {
+                for data in &mut self.data[start..end] {
+                    *data = true;
+                }
+            }
+        }
     })
 }
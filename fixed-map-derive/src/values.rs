@@ -0,0 +1,626 @@
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::spanned::Spanned;
+use syn::Ident;
+
+use crate::context::Ctxt;
+use crate::debug;
+
+/// Storage generated for a variant field with a declared set of values through
+/// `#[key(values(...))]`.
+pub(crate) struct Values {
+    /// The name of the generated map storage.
+    pub(crate) map_storage: Ident,
+    /// The name of the generated set storage.
+    pub(crate) set_storage: Ident,
+    /// The generated storage implementations.
+    pub(crate) impls: TokenStream,
+}
+
+/// Generate dense storage for a variant field whose values are limited to
+/// `values`, where each value is stored at the index it is declared at.
+pub(crate) fn implement(
+    cx: &Ctxt<'_>,
+    index: usize,
+    var: &Ident,
+    ty: &syn::Type,
+    values: &[syn::Expr],
+) -> Result<Values, ()> {
+    let ident = &cx.ast.ident;
+    let vis = &cx.ast.vis;
+    let lt = cx.lt;
+
+    let array_into_iter = cx.toks.array_into_iter();
+    let clone_t = cx.toks.clone_t();
+    let copy_t = cx.toks.copy_t();
+    let drain_storage_t = cx.toks.drain_storage_t();
+    let entries_storage_t = cx.toks.entries_storage_t();
+    let entry_enum = cx.toks.entry_enum();
+    let eq_t = cx.toks.eq_t();
+    let extract_if_storage_t = cx.toks.extract_if_storage_t();
+    let hash_t = cx.toks.hash_t();
+    let hasher_t = cx.toks.hasher_t();
+    let into_iterator_t = cx.toks.into_iterator_t();
+    let iterator_cmp = cx.toks.iterator_cmp();
+    let iterator_cmp_bool = cx.toks.iterator_cmp_bool();
+    let iterator_filter_map = cx.toks.iterator_filter_map();
+    let iterator_flatten = cx.toks.iterator_flatten();
+    let iterator_map = cx.toks.iterator_map();
+    let iterator_partial_cmp = cx.toks.iterator_partial_cmp();
+    let iterator_partial_cmp_bool = cx.toks.iterator_partial_cmp_bool();
+    let iterator_t = cx.toks.iterator_t();
+    let iterator_zip = cx.toks.iterator_zip();
+    let map_storage_t = cx.toks.map_storage_t();
+    let mem = cx.toks.mem();
+    let occupied_entry_t = cx.toks.occupied_entry_t();
+    let option = cx.toks.option();
+    let option_bucket_none = cx.toks.option_bucket_none();
+    let option_bucket_option = cx.toks.option_bucket_option();
+    let option_bucket_some = cx.toks.option_bucket_some();
+    let ord_t = cx.toks.ord_t();
+    let ordering = cx.toks.ordering();
+    let panic = cx.toks.panic();
+    let partial_eq_t = cx.toks.partial_eq_t();
+    let partial_ord_t = cx.toks.partial_ord_t();
+    let set_storage_t = cx.toks.set_storage_t();
+    let slice_iter = cx.toks.slice_iter();
+    let slice_iter_mut = cx.toks.slice_iter_mut();
+    let stats = cx.toks.stats();
+    let stats_storage_t = cx.toks.stats_storage_t();
+    let storage_drain = cx.toks.storage_drain();
+    let storage_extract_slots = cx.toks.storage_extract_slots();
+    let vacant_entry_t = cx.toks.vacant_entry_t();
+
+    check_duplicates(cx, values)?;
+
+    let map_storage = format_ident!("__Values{}MapStorage", index);
+    let set_storage = format_ident!("__Values{}SetStorage", index);
+    let vacant = format_ident!("__Values{}Vacant", index);
+    let occupied = format_ident!("__Values{}Occupied", index);
+    let values_const = format_ident!("__VALUES{}", index);
+    let index_fn = format_ident!("__values{}_index", index);
+    let expect_fn = format_ident!("__values{}_expect", index);
+    let option_to_entry = format_ident!("__values{}_option_to_entry", index);
+
+    let count = values.len();
+    let indexes = 0..count;
+
+    let lookup = if let Some(lookup) = perfect_hash(cx, values) {
+        lookup
+    } else if values.iter().all(is_literal) {
+        quote! {
+            match value {
+                #(#values => #option::Some(#indexes),)*
+                _ => #option::None,
+            }
+        }
+    } else {
+        quote! {
+            #(if #partial_eq_t::eq(&value, &#values) {
+                return #option::Some(#indexes);
+            })*
+
+            #option::None
+        }
+    };
+
+    let message = format!(
+        "value is not one of the values declared through #[key(values(...))] on `{ident}::{var}`"
+    );
+
+    let none = (0..count).map(|_| quote!(#option::None));
+    let none = quote!([#(#none),*]);
+
+    let debug_map_impl = debug::impl_map(cx, ty, &map_storage);
+    let debug_set_impl = debug::impl_set(cx, ty, &set_storage);
+
+    let impls = quote! {
+        const #values_const: [#ty; #count] = [#(#values),*];
+
+        #[inline]
+        fn #index_fn(value: #ty) -> #option<usize> {
+            #lookup
+        }
+
+        #[inline]
+        #[track_caller]
+        fn #expect_fn(value: #ty) -> usize {
+            match #index_fn(value) {
+                #option::Some(index) => index,
+                #option::None => #panic!(#message),
+            }
+        }
+
+        #vis struct #vacant<#lt, V> {
+            key: #ty,
+            inner: #option_bucket_none<#lt, V>,
+        }
+
+        #[automatically_derived]
+        impl<#lt, V> #vacant_entry_t<#lt, #ty, V> for #vacant<#lt, V> {
+            #[inline]
+            fn key(&self) -> #ty {
+                self.key
+            }
+
+            #[inline]
+            fn key_ref(&self) -> &#ty {
+                &self.key
+            }
+
+            #[inline]
+            fn insert(self, value: V) -> &#lt mut V {
+                #option_bucket_none::insert(self.inner, value)
+            }
+        }
+
+        #vis struct #occupied<#lt, V> {
+            key: #ty,
+            inner: #option_bucket_some<#lt, V>,
+        }
+
+        #[automatically_derived]
+        impl<#lt, V> #occupied_entry_t<#lt, #ty, V> for #occupied<#lt, V> {
+            #[inline]
+            fn key(&self) -> #ty {
+                self.key
+            }
+
+            #[inline]
+            fn key_ref(&self) -> &#ty {
+                &self.key
+            }
+
+            #[inline]
+            fn get(&self) -> &V {
+                #option_bucket_some::as_ref(&self.inner)
+            }
+
+            #[inline]
+            fn get_mut(&mut self) -> &mut V {
+                #option_bucket_some::as_mut(&mut self.inner)
+            }
+
+            #[inline]
+            fn into_mut(self) -> &#lt mut V {
+                #option_bucket_some::into_mut(self.inner)
+            }
+
+            #[inline]
+            fn insert(&mut self, value: V) -> V {
+                #option_bucket_some::replace(&mut self.inner, value)
+            }
+
+            #[inline]
+            fn remove(self) -> V {
+                #option_bucket_some::take(self.inner)
+            }
+        }
+
+        #[repr(transparent)]
+        #vis struct #map_storage<V> {
+            data: [#option<V>; #count],
+        }
+
+        #[automatically_derived]
+        impl<V> #clone_t for #map_storage<V> where V: #clone_t {
+            #[inline]
+            fn clone(&self) -> Self {
+                Self {
+                    data: #clone_t::clone(&self.data),
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #copy_t for #map_storage<V> where V: #copy_t {}
+
+        #[automatically_derived]
+        impl<V> #partial_eq_t for #map_storage<V> where V: #partial_eq_t {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                #partial_eq_t::eq(&self.data, &other.data)
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #eq_t for #map_storage<V> where V: #eq_t {}
+
+        #[automatically_derived]
+        impl<V> #hash_t for #map_storage<V> where V: #hash_t {
+            #[inline]
+            fn hash<H>(&self, state: &mut H)
+            where
+                H: #hasher_t,
+            {
+                #hash_t::hash(&self.data, state);
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #partial_ord_t for #map_storage<V> where V: #partial_ord_t {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> #option<#ordering> {
+                #iterator_partial_cmp(&self.data, &other.data)
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #ord_t for #map_storage<V> where V: #ord_t {
+            #[inline]
+            fn cmp(&self, other: &Self) -> #ordering {
+                #iterator_cmp(&self.data, &other.data)
+            }
+        }
+
+        #debug_map_impl
+
+        #[automatically_derived]
+        impl<V> #map_storage_t<#ty, V> for #map_storage<V> {
+            type Iter<#lt> = #iterator_filter_map<
+                #iterator_zip<#array_into_iter<#ty, #count>, #slice_iter<#lt, #option<V>>>,
+                fn((#ty, &#lt #option<V>)) -> #option<(#ty, &#lt V)>
+            > where V: #lt;
+            type Keys<#lt> = #iterator_filter_map<
+                #iterator_zip<#array_into_iter<#ty, #count>, #slice_iter<#lt, #option<V>>>,
+                fn((#ty, &#lt #option<V>)) -> #option<#ty>
+            > where V: #lt;
+            type Values<#lt> = #iterator_flatten<#slice_iter<#lt, #option<V>>> where V: #lt;
+            type IterMut<#lt> = #iterator_filter_map<
+                #iterator_zip<#array_into_iter<#ty, #count>, #slice_iter_mut<#lt, #option<V>>>,
+                fn((#ty, &#lt mut #option<V>)) -> #option<(#ty, &#lt mut V)>
+            > where V: #lt;
+            type ValuesMut<#lt> = #iterator_flatten<#slice_iter_mut<#lt, #option<V>>> where V: #lt;
+            type IntoIter = #iterator_filter_map<
+                #iterator_zip<#array_into_iter<#ty, #count>, #array_into_iter<#option<V>, #count>>,
+                fn((#ty, #option<V>)) -> #option<(#ty, V)>
+            >;
+            type Occupied<#lt> = #occupied<#lt, V> where V: #lt;
+            type Vacant<#lt> = #vacant<#lt, V> where V: #lt;
+
+            #[inline]
+            fn empty() -> Self {
+                Self { data: #none }
+            }
+
+            #[inline]
+            fn len(&self) -> usize {
+                #iterator_t::count(#iterator_t::filter(self.data.iter(), |value| #option::is_some(value)))
+            }
+
+            #[inline]
+            fn is_empty(&self) -> bool {
+                #iterator_t::all(&mut self.data.iter(), #option::is_none)
+            }
+
+            #[inline]
+            fn insert(&mut self, key: #ty, value: V) -> #option<V> {
+                #option::replace(&mut self.data[#expect_fn(key)], value)
+            }
+
+            #[inline]
+            fn contains_key(&self, key: #ty) -> bool {
+                match #index_fn(key) {
+                    #option::Some(index) => #option::is_some(&self.data[index]),
+                    #option::None => false,
+                }
+            }
+
+            #[inline]
+            fn get(&self, key: #ty) -> #option<&V> {
+                #option::as_ref(&self.data[#index_fn(key)?])
+            }
+
+            #[inline]
+            fn get_mut(&mut self, key: #ty) -> #option<&mut V> {
+                #option::as_mut(&mut self.data[#index_fn(key)?])
+            }
+
+            #[inline]
+            fn remove(&mut self, key: #ty) -> #option<V> {
+                #option::take(&mut self.data[#index_fn(key)?])
+            }
+
+            #[inline]
+            fn retain<F>(&mut self, mut func: F)
+            where
+                F: FnMut(#ty, &mut V) -> bool
+            {
+                for (key, entry) in #iterator_t::zip(#into_iterator_t::into_iter(#values_const), self.data.iter_mut()) {
+                    if let #option::Some(value) = #option::as_mut(entry) {
+                        if !func(key, value) {
+                            *entry = #option::None;
+                        }
+                    }
+                }
+            }
+
+            #[inline]
+            fn clear(&mut self) {
+                self.data = #none;
+            }
+
+            #[inline]
+            fn iter(&self) -> Self::Iter<'_> {
+                #iterator_t::filter_map(#iterator_t::zip(#into_iterator_t::into_iter(#values_const), self.data.iter()), |(k, v)| #option::Some((k, #option::as_ref(v)?)))
+            }
+
+            #[inline]
+            fn keys(&self) -> Self::Keys<'_> {
+                #iterator_t::filter_map(#iterator_t::zip(#into_iterator_t::into_iter(#values_const), self.data.iter()), |(k, v)| #option::is_some(v).then_some(k))
+            }
+
+            #[inline]
+            fn values(&self) -> Self::Values<'_> {
+                #iterator_t::flatten(self.data.iter())
+            }
+
+            #[inline]
+            fn iter_mut(&mut self) -> Self::IterMut<'_> {
+                #iterator_t::filter_map(#iterator_t::zip(#into_iterator_t::into_iter(#values_const), self.data.iter_mut()), |(k, v)| #option::Some((k, #option::as_mut(v)?)))
+            }
+
+            #[inline]
+            fn values_mut(&mut self) -> Self::ValuesMut<'_> {
+                #iterator_t::flatten(self.data.iter_mut())
+            }
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                #iterator_t::filter_map(#iterator_t::zip(#into_iterator_t::into_iter(#values_const), #into_iterator_t::into_iter(self.data)), |(k, v)| #option::Some((k, v?)))
+            }
+
+            #[inline]
+            fn entry(&mut self, key: #ty) -> #entry_enum<'_, Self, #ty, V> {
+                #option_to_entry(&mut self.data[#expect_fn(key)], key)
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #stats_storage_t<#ty, V> for #map_storage<V> {
+            #[inline]
+            fn stats(&self) -> #stats {
+                #stats::fixed(#count, #map_storage_t::len(self))
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #drain_storage_t<#ty, V> for #map_storage<V> {
+            type Drain<#lt> = <Self as #map_storage_t<#ty, V>>::IntoIter where V: #lt;
+
+            #[inline]
+            fn drain(&mut self) -> Self::Drain<'_> {
+                #storage_drain(self)
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #extract_if_storage_t<#ty, V> for #map_storage<V> {
+            type ExtractIf<#lt> = #storage_extract_slots<
+                #iterator_zip<#array_into_iter<#ty, #count>, #slice_iter_mut<#lt, #option<V>>>
+            > where V: #lt;
+
+            #[inline]
+            fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+                #storage_extract_slots::new(#iterator_t::zip(#into_iterator_t::into_iter(#values_const), self.data.iter_mut()))
+            }
+        }
+
+        #[automatically_derived]
+        impl<V> #entries_storage_t<#ty, V> for #map_storage<V> {
+            type EntriesMut<#lt> = #iterator_map<
+                #iterator_zip<#array_into_iter<#ty, #count>, #slice_iter_mut<#lt, #option<V>>>,
+                fn((#ty, &#lt mut #option<V>)) -> #entry_enum<#lt, Self, #ty, V>
+            > where V: #lt;
+
+            #[inline]
+            fn entries_mut(&mut self) -> Self::EntriesMut<'_> {
+                #iterator_t::map(#iterator_t::zip(#into_iterator_t::into_iter(#values_const), self.data.iter_mut()), |(k, v)| #option_to_entry(v, k))
+            }
+        }
+
+        #[inline]
+        fn #option_to_entry<#lt, V>(option: &#lt mut #option<V>, key: #ty) -> #entry_enum<#lt, #map_storage<V>, #ty, V> {
+            match #option_bucket_option::new(option) {
+                #option_bucket_option::Some(inner) => #entry_enum::Occupied(#occupied { key, inner }),
+                #option_bucket_option::None(inner) => #entry_enum::Vacant(#vacant { key, inner }),
+            }
+        }
+
+        #[repr(transparent)]
+        #[derive(#clone_t, #copy_t, #partial_eq_t, #eq_t, #hash_t)]
+        #vis struct #set_storage {
+            data: [bool; #count],
+        }
+
+        #[automatically_derived]
+        impl #partial_ord_t for #set_storage {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> #option<#ordering> {
+                #iterator_partial_cmp_bool(&self.data, &other.data)
+            }
+        }
+
+        #[automatically_derived]
+        impl #ord_t for #set_storage {
+            #[inline]
+            fn cmp(&self, other: &Self) -> #ordering {
+                #iterator_cmp_bool(&self.data, &other.data)
+            }
+        }
+
+        #debug_set_impl
+
+        #[automatically_derived]
+        impl #set_storage_t<#ty> for #set_storage {
+            type Iter<#lt> = #iterator_filter_map<
+                #iterator_zip<#array_into_iter<#ty, #count>, #array_into_iter<bool, #count>>,
+                fn((#ty, bool)) -> #option<#ty>
+            >;
+            type IntoIter = #iterator_filter_map<
+                #iterator_zip<#array_into_iter<#ty, #count>, #array_into_iter<bool, #count>>,
+                fn((#ty, bool)) -> #option<#ty>
+            >;
+
+            #[inline]
+            fn empty() -> Self {
+                Self { data: [false; #count] }
+            }
+
+            #[inline]
+            fn len(&self) -> usize {
+                #iterator_t::count(#iterator_t::filter(self.data.iter(), |value| **value))
+            }
+
+            #[inline]
+            fn is_empty(&self) -> bool {
+                !#iterator_t::any(&mut self.data.iter(), |value| *value)
+            }
+
+            #[inline]
+            fn insert(&mut self, value: #ty) -> bool {
+                !#mem::replace(&mut self.data[#expect_fn(value)], true)
+            }
+
+            #[inline]
+            fn contains(&self, value: #ty) -> bool {
+                match #index_fn(value) {
+                    #option::Some(index) => self.data[index],
+                    #option::None => false,
+                }
+            }
+
+            #[inline]
+            fn remove(&mut self, value: #ty) -> bool {
+                match #index_fn(value) {
+                    #option::Some(index) => #mem::replace(&mut self.data[index], false),
+                    #option::None => false,
+                }
+            }
+
+            #[inline]
+            fn retain<F>(&mut self, mut f: F)
+            where
+                F: FnMut(#ty) -> bool
+            {
+                for (key, entry) in #iterator_t::zip(#into_iterator_t::into_iter(#values_const), self.data.iter_mut()) {
+                    if *entry {
+                        *entry = f(key);
+                    }
+                }
+            }
+
+            #[inline]
+            fn clear(&mut self) {
+                self.data = [false; #count];
+            }
+
+            #[inline]
+            fn iter(&self) -> Self::Iter<'_> {
+                #set_storage_t::into_iter(*self)
+            }
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                #iterator_t::filter_map(#iterator_t::zip(#into_iterator_t::into_iter(#values_const), #into_iterator_t::into_iter(self.data)), |(k, v)| v.then_some(k))
+            }
+        }
+    };
+
+    Ok(Values {
+        map_storage,
+        set_storage,
+        impls,
+    })
+}
+
+/// Build a perfect hash lookup if every value is a string literal.
+///
+/// This finds a byte position which together with the length of the string
+/// uniquely identifies each value, so that a lookup only has to perform a
+/// single string comparison to verify the candidate.
+fn perfect_hash(cx: &Ctxt<'_>, values: &[syn::Expr]) -> Option<TokenStream> {
+    let option = cx.toks.option();
+
+    let mut strings = Vec::with_capacity(values.len());
+
+    for value in values {
+        match value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(string),
+                ..
+            }) => strings.push(string.value()),
+            _ => return None,
+        }
+    }
+
+    let max = strings.iter().map(String::len).max().unwrap_or_default();
+
+    let position = (0..max.max(1)).find(|&position| {
+        let mut seen = HashSet::new();
+
+        strings
+            .iter()
+            .all(|string| seen.insert((string.len(), string.as_bytes().get(position))))
+    })?;
+
+    let arms = strings.iter().enumerate().map(|(index, string)| {
+        let len = string.len();
+
+        let byte = match string.as_bytes().get(position) {
+            Some(byte) => quote!(#option::Some(&#byte)),
+            None => quote!(#option::None),
+        };
+
+        quote!((#len, #byte) => (#index, #string))
+    });
+
+    Some(quote! {
+        let bytes = value.as_bytes();
+
+        let (index, expected) = match (bytes.len(), bytes.get(#position)) {
+            #(#arms,)*
+            _ => return #option::None,
+        };
+
+        if value == expected {
+            #option::Some(index)
+        } else {
+            #option::None
+        }
+    })
+}
+
+/// Test if the expression can be used as a pattern, in which case values are
+/// looked up through a `match`.
+fn is_literal(expr: &syn::Expr) -> bool {
+    match expr {
+        syn::Expr::Lit(..) => true,
+        syn::Expr::Unary(unary) => {
+            matches!(unary.op, syn::UnOp::Neg(..)) && matches!(&*unary.expr, syn::Expr::Lit(..))
+        }
+        syn::Expr::Group(group) => is_literal(&group.expr),
+        _ => false,
+    }
+}
+
+/// Report values which are declared more than once.
+fn check_duplicates(cx: &Ctxt<'_>, values: &[syn::Expr]) -> Result<(), ()> {
+    let mut seen = HashSet::new();
+    let mut ok = true;
+
+    for value in values {
+        if !seen.insert(value.to_token_stream().to_string()) {
+            cx.span_error(value.span(), "duplicate value in #[key(values(...))]");
+            ok = false;
+        }
+    }
+
+    if ok {
+        Ok(())
+    } else {
+        Err(())
+    }
+}
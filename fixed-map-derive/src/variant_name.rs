@@ -0,0 +1,129 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::DataEnum;
+
+use crate::attrs;
+use crate::context::Ctxt;
+
+/// Build the `Key::variant_name` method, which returns the name of the variant,
+/// the `Key::fmt_key` method, which formats it together with any nested key,
+/// and the `Key::class_index` method, which returns the index of the variant.
+pub(crate) fn implement(cx: &Ctxt<'_>, en: &DataEnum) -> TokenStream {
+    let ident = &cx.ast.ident;
+
+    let arms = en.variants.iter().map(|variant| {
+        let var = &variant.ident;
+        let name = var.to_string();
+        quote!(#ident::#var { .. } => #name,)
+    });
+
+    let class_arms = en.variants.iter().enumerate().map(|(index, variant)| {
+        let var = &variant.ident;
+        quote!(#ident::#var { .. } => #index,)
+    });
+
+    let debug_t = cx.toks.debug_t();
+    let key_t = cx.toks.key_t();
+    let fmt_result = cx.toks.fmt_result();
+    let formatter = cx.toks.formatter();
+    let option = cx.toks.option();
+
+    let fmt_arms = en.variants.iter().enumerate().map(|(index, variant)| {
+        let var = &variant.ident;
+        let name = var.to_string();
+
+        let syn::Fields::Unnamed(unnamed) = &variant.fields else {
+            return quote!(#ident::#var { .. } => f.write_str(#name),);
+        };
+
+        let [field] = unnamed.unnamed.iter().collect::<Vec<_>>()[..] else {
+            return quote!(#ident::#var { .. } => f.write_str(#name),);
+        };
+
+        let ty = &field.ty;
+        let open = format!("{name}(");
+
+        // NB: Values declared through `#[key(values(...))]` are looked up
+        // through the index function generated for their storage, since the
+        // field type isn't necessarily a key.
+        let variant_opts = attrs::parse_variant_attrs(cx, variant).unwrap_or_default();
+
+        let inner = match &variant_opts.values {
+            Some(values) => {
+                let index_fn = format_ident!("__values{}_index", index);
+                let values = values
+                    .iter()
+                    .map(|value| value.to_token_stream().to_string());
+
+                quote! {
+                    match #index_fn(v) {
+                        #option::Some(index) => f.write_str([#(#values),*][index])?,
+                        #option::None => f.write_str("..")?,
+                    }
+                }
+            }
+            // NB: Dynamic types are formatted through `Debug`, which is what
+            // their `Key` implementation does when it's available.
+            _ if variant_opts.optional.is_some() => {
+                quote!(#debug_t::fmt(&v, f)?;)
+            }
+            _ => quote!(<#ty as #key_t>::fmt_key(v, f)?;),
+        };
+
+        quote! {
+            #ident::#var(v) => {
+                f.write_str(#open)?;
+                #inner
+                f.write_str(")")
+            }
+        }
+    });
+
+    quote! {
+        #[inline]
+        fn variant_name(self) -> &'static str {
+            match self {
+                #(#arms)*
+            }
+        }
+
+        #[inline]
+        fn fmt_key(self, f: &mut #formatter<'_>) -> #fmt_result {
+            match self {
+                #(#fmt_arms)*
+            }
+        }
+
+        #[inline]
+        fn class_index(self) -> usize {
+            match self {
+                #(#class_arms)*
+            }
+        }
+    }
+}
+
+/// Build the `Key::variant_name`, `Key::fmt_key` and `Key::class_index`
+/// methods for a transparent key, which delegate to the wrapped key.
+pub(crate) fn transparent(cx: &Ctxt<'_>, element: &syn::Type) -> TokenStream {
+    let key_t = cx.toks.key_t();
+    let fmt_result = cx.toks.fmt_result();
+    let formatter = cx.toks.formatter();
+
+    quote! {
+        #[inline]
+        fn variant_name(self) -> &'static str {
+            <#element as #key_t>::variant_name(self.0)
+        }
+
+        #[inline]
+        fn fmt_key(self, f: &mut #formatter<'_>) -> #fmt_result {
+            <#element as #key_t>::fmt_key(self.0, f)
+        }
+
+        #[inline]
+        fn class_index(self) -> usize {
+            <#element as #key_t>::class_index(self.0)
+        }
+    }
+}
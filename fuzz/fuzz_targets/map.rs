@@ -0,0 +1,113 @@
+//! Differential test of a derived map against a `BTreeMap` model.
+
+#![no_main]
+
+use std::collections::BTreeMap;
+
+use arbitrary::Arbitrary;
+use fixed_map::map::{Entry, OccupiedEntry, VacantEntry};
+use fixed_map::{Key, Map};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Key, Arbitrary)]
+enum Inner {
+    First,
+    Second,
+    Third,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Key, Arbitrary)]
+enum Composite {
+    Unit,
+    Bool(bool),
+    Option(Option<Inner>),
+    OptionBool(Option<bool>),
+    Nested(Inner),
+    Dynamic(u8),
+}
+
+#[derive(Debug, Arbitrary)]
+enum Op {
+    Insert(Composite, u8),
+    Remove(Composite),
+    Get(Composite),
+    GetMut(Composite, u8),
+    ContainsKey(Composite),
+    EntryOrInsert(Composite, u8),
+    EntryInsert(Composite, u8),
+    EntryRemove(Composite),
+    Retain(u8),
+    Clear,
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+    let mut map = Map::new();
+    let mut model = BTreeMap::new();
+
+    for op in ops {
+        match op {
+            Op::Insert(key, value) => {
+                assert_eq!(map.insert(key, value), model.insert(key, value));
+            }
+            Op::Remove(key) => {
+                assert_eq!(map.remove(key), model.remove(&key));
+            }
+            Op::Get(key) => {
+                assert_eq!(map.get(key), model.get(&key));
+            }
+            Op::GetMut(key, value) => {
+                let a = map.get_mut(key);
+                let b = model.get_mut(&key);
+                assert_eq!(a, b);
+
+                if let (Some(a), Some(b)) = (a, b) {
+                    *a = value;
+                    *b = value;
+                }
+            }
+            Op::ContainsKey(key) => {
+                assert_eq!(map.contains_key(key), model.contains_key(&key));
+            }
+            Op::EntryOrInsert(key, value) => {
+                let a = *map.entry(key).or_insert(value);
+                let b = *model.entry(key).or_insert(value);
+                assert_eq!(a, b);
+            }
+            Op::EntryInsert(key, value) => match map.entry(key) {
+                Entry::Occupied(mut entry) => {
+                    assert_eq!(entry.key(), key);
+                    assert_eq!(Some(entry.insert(value)), model.insert(key, value));
+                }
+                Entry::Vacant(entry) => {
+                    assert_eq!(entry.key(), key);
+                    entry.insert(value);
+                    assert_eq!(model.insert(key, value), None);
+                }
+            },
+            Op::EntryRemove(key) => match map.entry(key) {
+                Entry::Occupied(entry) => {
+                    assert_eq!(Some(entry.remove()), model.remove(&key));
+                }
+                Entry::Vacant(..) => {
+                    assert!(!model.contains_key(&key));
+                }
+            },
+            Op::Retain(threshold) => {
+                map.retain(|_, value| *value < threshold);
+                model.retain(|_, value| *value < threshold);
+            }
+            Op::Clear => {
+                map.clear();
+                model.clear();
+            }
+        }
+
+        map.debug_assert_invariants();
+        assert_eq!(map.len(), model.len());
+        assert_eq!(map.is_empty(), model.is_empty());
+
+        let mut entries = map.iter().map(|(k, v)| (k, *v)).collect::<Vec<_>>();
+        entries.sort();
+        assert!(entries.into_iter().eq(model.iter().map(|(k, v)| (*k, *v))));
+    }
+});
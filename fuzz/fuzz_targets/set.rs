@@ -0,0 +1,78 @@
+//! Differential test of a derived set against a `BTreeSet` model.
+
+#![no_main]
+
+use std::collections::BTreeSet;
+
+use arbitrary::Arbitrary;
+use fixed_map::{Key, Set};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Key, Arbitrary)]
+enum Inner {
+    First,
+    Second,
+    Third,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Key, Arbitrary)]
+#[key(bitset)]
+enum Bits {
+    First,
+    Second,
+    Third,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Key, Arbitrary)]
+enum Composite {
+    Unit,
+    Bool(bool),
+    Option(Option<Inner>),
+    Nested(Inner),
+    Bits(Bits),
+    Dynamic(u8),
+}
+
+#[derive(Debug, Arbitrary)]
+enum Op {
+    Insert(Composite),
+    Remove(Composite),
+    Contains(Composite),
+    Retain(Vec<Composite>),
+    Clear,
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+    let mut set = Set::new();
+    let mut model = BTreeSet::new();
+
+    for op in ops {
+        match op {
+            Op::Insert(key) => {
+                assert_eq!(set.insert(key), model.insert(key));
+            }
+            Op::Remove(key) => {
+                assert_eq!(set.remove(key), model.remove(&key));
+            }
+            Op::Contains(key) => {
+                assert_eq!(set.contains(key), model.contains(&key));
+            }
+            Op::Retain(keep) => {
+                set.retain(|key| keep.contains(&key));
+                model.retain(|key| keep.contains(key));
+            }
+            Op::Clear => {
+                set.clear();
+                model.clear();
+            }
+        }
+
+        set.debug_assert_invariants();
+        assert_eq!(set.len(), model.len());
+        assert_eq!(set.is_empty(), model.is_empty());
+
+        let mut values = set.iter().collect::<Vec<_>>();
+        values.sort();
+        assert!(values.into_iter().eq(model.iter().copied()));
+    }
+});
@@ -0,0 +1,543 @@
+//! Contains the fixed [`AnyValueMap`] implementation.
+
+use core::fmt;
+
+use crate::set::{self, Set};
+use crate::Key;
+
+/// A key where every variant is associated with its own value type.
+///
+/// This is implemented by the [`Key` derive] when every variant of an enum is
+/// annotated with `#[key(value = "Type")]`.
+///
+/// [`Key` derive]: crate::Key
+pub trait AnyValueKey: Key {
+    /// The storage used by [`AnyValueMap`].
+    type AnyValueStorage: AnyValueStorage<Self>;
+}
+
+/// The trait defining how storage works for [`AnyValueMap`].
+///
+/// # Type Arguments
+///
+/// - `K` is the key being stored.
+pub trait AnyValueStorage<K>: Sized {
+    /// Construct empty storage.
+    fn empty() -> Self;
+
+    /// Get the length of storage.
+    fn len(&self) -> usize;
+
+    /// Check if storage is empty.
+    fn is_empty(&self) -> bool;
+
+    /// This is the storage abstraction for
+    /// [`AnyValueMap::contains_key`].
+    fn contains_key(&self, key: K) -> bool;
+
+    /// This is the storage abstraction for [`AnyValueMap::remove_key`].
+    fn remove_key(&mut self, key: K) -> bool;
+
+    /// This is the storage abstraction for [`AnyValueMap::clear`].
+    fn clear(&mut self);
+
+    /// Insert the keys which have a value in storage into the given set.
+    fn keys_into(&self, keys: &mut Set<K>)
+    where
+        K: Key;
+}
+
+/// A marker type for a single variant of an [`AnyValueKey`], which determines
+/// the type of the value associated with it.
+///
+/// The [`Key` derive] generates one marker type per variant inside of a module
+/// named after the enum in snake case. So the marker for `Setting::Width` is
+/// `setting::Width`.
+///
+/// [`Key` derive]: crate::Key
+pub trait AnyValueVariant {
+    /// The key this variant belongs to.
+    type Key: AnyValueKey;
+
+    /// The type of the value associated with this variant.
+    type Value;
+
+    /// The key corresponding to this variant.
+    const KEY: Self::Key;
+
+    /// Access the slot for the value in storage.
+    fn slot(storage: &<Self::Key as AnyValueKey>::AnyValueStorage) -> &Option<Self::Value>;
+
+    /// Mutably access the slot for the value in storage.
+    fn slot_mut(
+        storage: &mut <Self::Key as AnyValueKey>::AnyValueStorage,
+    ) -> &mut Option<Self::Value>;
+}
+
+/// A fixed map where every key is associated with a value of its own type.
+///
+/// Value types are declared on each variant of the key with
+/// `#[key(value = "Type")]`, and values are accessed through the marker types
+/// which the [`Key` derive] generates in a module named after the enum in snake
+/// case.
+///
+/// This serves as a replacement for a struct where every field is an
+/// [`Option`].
+///
+/// [`Key` derive]: crate::Key
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{AnyValueMap, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Setting {
+///     #[key(value = "u32")]
+///     Width,
+///     #[key(value = "String")]
+///     Title,
+/// }
+///
+/// let mut map = AnyValueMap::new();
+/// map.insert::<setting::Width>(800);
+/// map.insert::<setting::Title>(String::from("Hello"));
+///
+/// assert_eq!(map.get::<setting::Width>(), Some(&800));
+/// assert_eq!(map.get::<setting::Title>().map(String::as_str), Some("Hello"));
+///
+/// assert!(map.contains_key(Setting::Width));
+/// assert!(map.keys().eq([Setting::Width, Setting::Title]));
+/// ```
+pub struct AnyValueMap<K>
+where
+    K: AnyValueKey,
+{
+    storage: K::AnyValueStorage,
+}
+
+impl<K> AnyValueMap<K>
+where
+    K: AnyValueKey,
+{
+    /// Creates an empty [`AnyValueMap`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{AnyValueMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Setting {
+    ///     #[key(value = "u32")]
+    ///     Width,
+    /// }
+    ///
+    /// let map = AnyValueMap::<Setting>::new();
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new() -> AnyValueMap<K> {
+        AnyValueMap {
+            storage: K::AnyValueStorage::empty(),
+        }
+    }
+
+    /// Returns a reference to the value associated with the variant `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{AnyValueMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Setting {
+    ///     #[key(value = "u32")]
+    ///     Width,
+    ///     #[key(value = "bool")]
+    ///     Visible,
+    /// }
+    ///
+    /// let mut map = AnyValueMap::new();
+    /// map.insert::<setting::Width>(800);
+    ///
+    /// assert_eq!(map.get::<setting::Width>(), Some(&800));
+    /// assert_eq!(map.get::<setting::Visible>(), None);
+    /// ```
+    #[inline]
+    pub fn get<T>(&self) -> Option<&T::Value>
+    where
+        T: AnyValueVariant<Key = K>,
+    {
+        T::slot(&self.storage).as_ref()
+    }
+
+    /// Returns a mutable reference to the value associated with the variant
+    /// `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{AnyValueMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Setting {
+    ///     #[key(value = "Vec<u32>")]
+    ///     Sizes,
+    /// }
+    ///
+    /// let mut map = AnyValueMap::new();
+    /// map.insert::<setting::Sizes>(vec![1]);
+    ///
+    /// if let Some(sizes) = map.get_mut::<setting::Sizes>() {
+    ///     sizes.push(2);
+    /// }
+    ///
+    /// assert_eq!(map.get::<setting::Sizes>(), Some(&vec![1, 2]));
+    /// ```
+    #[inline]
+    pub fn get_mut<T>(&mut self) -> Option<&mut T::Value>
+    where
+        T: AnyValueVariant<Key = K>,
+    {
+        T::slot_mut(&mut self.storage).as_mut()
+    }
+
+    /// Inserts a value for the variant `T`, returning the previous value if
+    /// one was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{AnyValueMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Setting {
+    ///     #[key(value = "u32")]
+    ///     Width,
+    /// }
+    ///
+    /// let mut map = AnyValueMap::new();
+    /// assert_eq!(map.insert::<setting::Width>(800), None);
+    /// assert_eq!(map.insert::<setting::Width>(600), Some(800));
+    /// ```
+    #[inline]
+    pub fn insert<T>(&mut self, value: T::Value) -> Option<T::Value>
+    where
+        T: AnyValueVariant<Key = K>,
+    {
+        T::slot_mut(&mut self.storage).replace(value)
+    }
+
+    /// Removes the value associated with the variant `T`, returning it if it
+    /// was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{AnyValueMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Setting {
+    ///     #[key(value = "u32")]
+    ///     Width,
+    /// }
+    ///
+    /// let mut map = AnyValueMap::new();
+    /// map.insert::<setting::Width>(800);
+    /// assert_eq!(map.remove::<setting::Width>(), Some(800));
+    /// assert_eq!(map.remove::<setting::Width>(), None);
+    /// ```
+    #[inline]
+    pub fn remove<T>(&mut self) -> Option<T::Value>
+    where
+        T: AnyValueVariant<Key = K>,
+    {
+        T::slot_mut(&mut self.storage).take()
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{AnyValueMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Setting {
+    ///     #[key(value = "u32")]
+    ///     Width,
+    ///     #[key(value = "bool")]
+    ///     Visible,
+    /// }
+    ///
+    /// let mut map = AnyValueMap::new();
+    /// map.insert::<setting::Width>(800);
+    ///
+    /// assert!(map.contains_key(Setting::Width));
+    /// assert!(!map.contains_key(Setting::Visible));
+    /// ```
+    #[inline]
+    pub fn contains_key(&self, key: K) -> bool {
+        self.storage.contains_key(key)
+    }
+
+    /// Removes the value for the specified key, without knowing its type.
+    /// Returns `true` if a value was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{AnyValueMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Setting {
+    ///     #[key(value = "u32")]
+    ///     Width,
+    /// }
+    ///
+    /// let mut map = AnyValueMap::new();
+    /// map.insert::<setting::Width>(800);
+    ///
+    /// assert!(map.remove_key(Setting::Width));
+    /// assert!(!map.remove_key(Setting::Width));
+    /// ```
+    #[inline]
+    pub fn remove_key(&mut self, key: K) -> bool {
+        self.storage.remove_key(key)
+    }
+
+    /// An iterator visiting all keys which have a value in the map, in the
+    /// order in which the variants are declared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{AnyValueMap, Key};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum Setting {
+    ///     #[key(value = "u32")]
+    ///     Width,
+    ///     #[key(value = "u32")]
+    ///     Height,
+    ///     #[key(value = "bool")]
+    ///     Visible,
+    /// }
+    ///
+    /// let mut map = AnyValueMap::new();
+    /// map.insert::<setting::Visible>(true);
+    /// map.insert::<setting::Width>(800);
+    ///
+    /// assert!(map.keys().eq([Setting::Width, Setting::Visible]));
+    /// ```
+    #[inline]
+    pub fn keys(&self) -> set::IntoIter<K> {
+        let mut keys = Set::new();
+        self.storage.keys_into(&mut keys);
+        keys.into_iter()
+    }
+
+    /// Clears the map, removing all values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{AnyValueMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Setting {
+    ///     #[key(value = "u32")]
+    ///     Width,
+    /// }
+    ///
+    /// let mut map = AnyValueMap::new();
+    /// map.insert::<setting::Width>(800);
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.storage.clear();
+    }
+
+    /// Returns true if the map contains no values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{AnyValueMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Setting {
+    ///     #[key(value = "u32")]
+    ///     Width,
+    /// }
+    ///
+    /// let mut map = AnyValueMap::new();
+    /// assert!(map.is_empty());
+    /// map.insert::<setting::Width>(800);
+    /// assert!(!map.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+
+    /// Gets the number of values in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{AnyValueMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Setting {
+    ///     #[key(value = "u32")]
+    ///     Width,
+    ///     #[key(value = "bool")]
+    ///     Visible,
+    /// }
+    ///
+    /// let mut map = AnyValueMap::new();
+    /// map.insert::<setting::Width>(800);
+    /// map.insert::<setting::Visible>(true);
+    /// assert_eq!(map.len(), 2);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.storage.len()
+    }
+}
+
+/// The [`Default`] implementation for [`AnyValueMap`] produces an empty map.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{AnyValueMap, Key};
+///
+/// #[derive(Clone, Copy, Key)]
+/// enum Setting {
+///     #[key(value = "u32")]
+///     Width,
+/// }
+///
+/// let map: AnyValueMap<Setting> = AnyValueMap::default();
+/// assert!(map.is_empty());
+/// ```
+impl<K> Default for AnyValueMap<K>
+where
+    K: AnyValueKey,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// [`Clone`] implementation for an [`AnyValueMap`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{AnyValueMap, Key};
+///
+/// #[derive(Clone, Copy, Key)]
+/// enum Setting {
+///     #[key(value = "String")]
+///     Title,
+/// }
+///
+/// let mut a = AnyValueMap::new();
+/// a.insert::<setting::Title>(String::from("Hello"));
+///
+/// let b = a.clone();
+/// assert_eq!(b.get::<setting::Title>().map(String::as_str), Some("Hello"));
+/// ```
+impl<K> Clone for AnyValueMap<K>
+where
+    K: AnyValueKey,
+    K::AnyValueStorage: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            storage: self.storage.clone(),
+        }
+    }
+}
+
+/// [`PartialEq`] implementation for an [`AnyValueMap`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{AnyValueMap, Key};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum Setting {
+///     #[key(value = "u32")]
+///     Width,
+///     #[key(value = "bool")]
+///     Visible,
+/// }
+///
+/// let mut a = AnyValueMap::new();
+/// a.insert::<setting::Width>(800);
+///
+/// let mut b = AnyValueMap::new();
+/// assert_ne!(a, b);
+/// b.insert::<setting::Width>(800);
+/// assert_eq!(a, b);
+/// ```
+impl<K> PartialEq for AnyValueMap<K>
+where
+    K: AnyValueKey,
+    K::AnyValueStorage: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.storage == other.storage
+    }
+}
+
+impl<K> Eq for AnyValueMap<K>
+where
+    K: AnyValueKey,
+    K::AnyValueStorage: Eq,
+{
+}
+
+/// The [`Debug`][fmt::Debug] implementation for an [`AnyValueMap`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{AnyValueMap, Key};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum Setting {
+///     #[key(value = "u32")]
+///     Width,
+///     #[key(value = "bool")]
+///     Visible,
+/// }
+///
+/// let mut map = AnyValueMap::new();
+/// map.insert::<setting::Width>(800);
+/// map.insert::<setting::Visible>(true);
+///
+/// assert_eq!("{Width: 800, Visible: true}", format!("{:?}", map));
+/// ```
+impl<K> fmt::Debug for AnyValueMap<K>
+where
+    K: AnyValueKey,
+    K::AnyValueStorage: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.storage.fmt(f)
+    }
+}
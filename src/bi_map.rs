@@ -0,0 +1,557 @@
+//! Contains the fixed [`BiMap`] implementation.
+
+use core::fmt;
+
+use crate::map::{self, Map};
+use crate::Key;
+
+/// A bidirectional map between two keys.
+///
+/// Every left key is associated with at most one right key and the other way
+/// around, so pairs can be looked up efficiently from either side. This is
+/// backed by two fixed maps which are kept in sync.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{BiMap, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Internal {
+///     Ok,
+///     NotFound,
+///     Denied,
+/// }
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Wire {
+///     Code200,
+///     Code403,
+///     Code404,
+/// }
+///
+/// let mut codes = BiMap::new();
+/// codes.insert(Internal::Ok, Wire::Code200);
+/// codes.insert(Internal::NotFound, Wire::Code404);
+/// codes.insert(Internal::Denied, Wire::Code403);
+///
+/// assert_eq!(codes.get_by_left(Internal::NotFound), Some(&Wire::Code404));
+/// assert_eq!(codes.get_by_right(Wire::Code403), Some(&Internal::Denied));
+/// ```
+///
+/// Using composite keys:
+///
+/// ```
+/// use fixed_map::{BiMap, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Left {
+///     First(bool),
+///     Second,
+/// }
+///
+/// let mut map = BiMap::new();
+/// map.insert(Left::First(true), Some(false));
+/// map.insert(Left::Second, None);
+///
+/// assert_eq!(map.get_by_left(Left::First(true)), Some(&Some(false)));
+/// assert_eq!(map.get_by_right(None), Some(&Left::Second));
+/// ```
+pub struct BiMap<A, B>
+where
+    A: Key,
+    B: Key,
+{
+    left: Map<A, B>,
+    right: Map<B, A>,
+}
+
+impl<A, B> BiMap<A, B>
+where
+    A: Key,
+    B: Key,
+{
+    /// Creates an empty [`BiMap`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{BiMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Left {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map: BiMap<Left, bool> = BiMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new() -> BiMap<A, B> {
+        BiMap {
+            left: Map::new(),
+            right: Map::new(),
+        }
+    }
+
+    /// Returns the number of pairs in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{BiMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Left {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert(Left::First, true);
+    /// map.insert(Left::Second, true);
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.left.len()
+    }
+
+    /// Returns `true` if the map contains no pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{BiMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Left {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// assert!(map.is_empty());
+    /// map.insert(Left::First, true);
+    /// assert!(!map.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.left.is_empty()
+    }
+
+    /// Returns the right key associated with the given left key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{BiMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Left {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert(Left::First, true);
+    /// assert_eq!(map.get_by_left(Left::First), Some(&true));
+    /// assert_eq!(map.get_by_left(Left::Second), None);
+    /// ```
+    #[inline]
+    pub fn get_by_left(&self, left: A) -> Option<&B> {
+        self.left.get(left)
+    }
+
+    /// Returns the left key associated with the given right key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{BiMap, Key};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum Left {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert(Left::First, true);
+    /// assert_eq!(map.get_by_right(true), Some(&Left::First));
+    /// assert_eq!(map.get_by_right(false), None);
+    /// ```
+    #[inline]
+    pub fn get_by_right(&self, right: B) -> Option<&A> {
+        self.right.get(right)
+    }
+
+    /// Returns `true` if the left key is in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{BiMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Left {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert(Left::First, true);
+    /// assert!(map.contains_left(Left::First));
+    /// assert!(!map.contains_left(Left::Second));
+    /// ```
+    #[inline]
+    pub fn contains_left(&self, left: A) -> bool {
+        self.left.contains_key(left)
+    }
+
+    /// Returns `true` if the right key is in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{BiMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Left {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert(Left::First, true);
+    /// assert!(map.contains_right(true));
+    /// assert!(!map.contains_right(false));
+    /// ```
+    #[inline]
+    pub fn contains_right(&self, right: B) -> bool {
+        self.right.contains_key(right)
+    }
+
+    /// Inserts a pair into the map.
+    ///
+    /// Any existing pairs which contain either of the keys are removed to
+    /// preserve the bijection. The right key previously associated with `left`
+    /// and the left key previously associated with `right` are returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{BiMap, Key};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum Left {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// assert_eq!(map.insert(Left::First, true), (None, None));
+    /// assert_eq!(map.insert(Left::Second, false), (None, None));
+    ///
+    /// // Removes both `(First, true)` and `(Second, false)`.
+    /// assert_eq!(map.insert(Left::First, false), (Some(true), Some(Left::Second)));
+    ///
+    /// assert_eq!(map.len(), 1);
+    /// assert_eq!(map.get_by_right(true), None);
+    /// assert_eq!(map.get_by_left(Left::Second), None);
+    /// ```
+    pub fn insert(&mut self, left: A, right: B) -> (Option<B>, Option<A>) {
+        let old_right = self.remove_by_left(left);
+        let old_left = self.remove_by_right(right);
+        self.left.insert(left, right);
+        self.right.insert(right, left);
+        (old_right, old_left)
+    }
+
+    /// Removes the pair with the given left key, returning its right key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{BiMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Left {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert(Left::First, true);
+    /// assert_eq!(map.remove_by_left(Left::First), Some(true));
+    /// assert_eq!(map.remove_by_left(Left::First), None);
+    /// assert!(!map.contains_right(true));
+    /// ```
+    #[inline]
+    pub fn remove_by_left(&mut self, left: A) -> Option<B> {
+        let right = self.left.remove(left)?;
+        self.right.remove(right);
+        Some(right)
+    }
+
+    /// Removes the pair with the given right key, returning its left key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{BiMap, Key};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum Left {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert(Left::First, true);
+    /// assert_eq!(map.remove_by_right(true), Some(Left::First));
+    /// assert_eq!(map.remove_by_right(true), None);
+    /// assert!(!map.contains_left(Left::First));
+    /// ```
+    #[inline]
+    pub fn remove_by_right(&mut self, right: B) -> Option<A> {
+        let left = self.right.remove(right)?;
+        self.left.remove(left);
+        Some(left)
+    }
+
+    /// Removes every pair from the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{BiMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Left {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert(Left::First, true);
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.left.clear();
+        self.right.clear();
+    }
+
+    /// An iterator visiting all pairs in the order of their left keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{BiMap, Key};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum Left {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert(Left::Second, true);
+    /// map.insert(Left::First, false);
+    ///
+    /// assert!(map.iter().eq([(Left::First, false), (Left::Second, true)]));
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, A, B> {
+        Iter {
+            iter: self.left.iter(),
+        }
+    }
+
+    /// Returns a reference to the map from left to right keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{BiMap, Key};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum Left {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert(Left::Second, true);
+    /// assert!(map.left().keys().eq([Left::Second]));
+    /// ```
+    #[inline]
+    pub fn left(&self) -> &Map<A, B> {
+        &self.left
+    }
+
+    /// Returns a reference to the map from right to left keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{BiMap, Key};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum Left {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = BiMap::new();
+    /// map.insert(Left::Second, true);
+    /// assert!(map.right().keys().eq([true]));
+    /// ```
+    #[inline]
+    pub fn right(&self) -> &Map<B, A> {
+        &self.right
+    }
+}
+
+impl<A, B> Clone for BiMap<A, B>
+where
+    A: Key,
+    B: Key,
+    A::MapStorage<B>: Clone,
+    B::MapStorage<A>: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        BiMap {
+            left: self.left.clone(),
+            right: self.right.clone(),
+        }
+    }
+}
+
+impl<A, B> Default for BiMap<A, B>
+where
+    A: Key,
+    B: Key,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A, B> PartialEq for BiMap<A, B>
+where
+    A: Key,
+    B: Key,
+    A::MapStorage<B>: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.left == other.left
+    }
+}
+
+impl<A, B> Eq for BiMap<A, B>
+where
+    A: Key,
+    B: Key,
+    A::MapStorage<B>: Eq,
+{
+}
+
+impl<A, B> fmt::Debug for BiMap<A, B>
+where
+    A: Key + fmt::Debug,
+    B: Key + fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// A simple [`FromIterator`] implementation for [`BiMap`], where later pairs
+/// replace earlier ones which share a key with them.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{BiMap, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Left {
+///     First,
+///     Second,
+/// }
+///
+/// let map: BiMap<_, _> = [(Left::First, true), (Left::Second, true)].into_iter().collect();
+/// assert!(map.iter().eq([(Left::Second, true)]));
+/// ```
+impl<A, B> FromIterator<(A, B)> for BiMap<A, B>
+where
+    A: Key,
+    B: Key,
+{
+    #[inline]
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = (A, B)>,
+    {
+        let mut map = Self::new();
+
+        for (left, right) in iter {
+            map.insert(left, right);
+        }
+
+        map
+    }
+}
+
+impl<'a, A, B> IntoIterator for &'a BiMap<A, B>
+where
+    A: Key,
+    B: Key,
+{
+    type Item = (A, B);
+    type IntoIter = Iter<'a, A, B>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the pairs of a [`BiMap`].
+///
+/// This is created by [`BiMap::iter`].
+pub struct Iter<'a, A, B>
+where
+    A: 'a + Key,
+    B: 'a,
+{
+    iter: map::Iter<'a, A, B>,
+}
+
+impl<'a, A, B> Iterator for Iter<'a, A, B>
+where
+    A: 'a + Key,
+    B: 'a + Copy,
+{
+    type Item = (A, B);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (left, right) = self.iter.next()?;
+        Some((left, *right))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
@@ -0,0 +1,682 @@
+//! Contains the fixed [`CellMap`] implementation.
+
+use core::cell::Cell;
+use core::fmt;
+
+use crate::map::{self, Map, MapStorage};
+use crate::Key;
+
+/// A fixed map which supports updating values through a shared reference.
+///
+/// Every key in a [`CellMap`] is associated with a *slot*, and the value
+/// stored in a slot can be read and updated through `&self` as long as `V` is
+/// [`Copy`]. Keys with fixed storage, such as unit variants or variants
+/// holding a `bool`, always have a slot. Keys stored in dynamic or lazy
+/// branches get one through methods which take `&mut self` like
+/// [`CellMap::insert`] or [`CellMap::reserve`].
+///
+/// This avoids having to wrap the whole map in a [`RefCell`] for single
+/// threaded shared mutation.
+///
+/// [`RefCell`]: core::cell::RefCell
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{CellMap, Key};
+///
+/// #[derive(Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let mut map = CellMap::new();
+/// map.insert(MyKey::First, 1);
+///
+/// let shared = &map;
+/// assert_eq!(shared.replace_value(MyKey::First, 2), Ok(Some(1)));
+/// assert_eq!(shared.replace_value(MyKey::Second, 3), Ok(None));
+///
+/// assert_eq!(map.get(MyKey::First), Some(2));
+/// assert_eq!(map.get(MyKey::Second), Some(3));
+/// ```
+///
+/// Using a composite key:
+///
+/// ```
+/// use fixed_map::{CellMap, Key};
+///
+/// #[derive(Clone, Copy, Key)]
+/// enum MyKey {
+///     First(bool),
+///     Second,
+/// }
+///
+/// let map = CellMap::new();
+///
+/// assert_eq!(map.replace_value(MyKey::First(true), 1), Ok(None));
+/// assert_eq!(map.replace_value(MyKey::First(true), 2), Ok(Some(1)));
+/// assert_eq!(map.get(MyKey::First(true)), Some(2));
+/// assert_eq!(map.get(MyKey::First(false)), None);
+/// ```
+///
+/// Keys stored in a dynamic branch need a slot before they can be updated
+/// through a shared reference:
+///
+/// ```
+/// # #[cfg(feature = "hashbrown")] {
+/// use fixed_map::{CellMap, Key};
+///
+/// #[derive(Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second(u32),
+/// }
+///
+/// let mut map = CellMap::new();
+/// map.reserve(MyKey::Second(7));
+///
+/// let shared = &map;
+/// assert_eq!(shared.replace_value(MyKey::Second(7), 1), Ok(None));
+/// assert_eq!(shared.replace_value(MyKey::Second(8), 2), Err(2));
+/// # }
+/// ```
+pub struct CellMap<K, V>
+where
+    K: Key,
+{
+    map: Map<K, Cell<Option<V>>>,
+    /// The number of slots which currently store a value.
+    len: Cell<usize>,
+}
+
+impl<K, V> CellMap<K, V>
+where
+    K: Key,
+{
+    /// Creates an empty [`CellMap`], with a slot for every key which has
+    /// fixed storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{CellMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map: CellMap<MyKey, i32> = CellMap::new();
+    /// assert!(map.is_empty());
+    /// assert!(map.has_slot(MyKey::First));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new() -> CellMap<K, V> {
+        let mut map = CellMap {
+            map: Map::new(),
+            len: Cell::new(0),
+        };
+
+        map.fill_fixed();
+        map
+    }
+
+    /// Creates an empty slot for every key with fixed storage which doesn't
+    /// have one.
+    #[inline]
+    fn fill_fixed(&mut self) {
+        self.map.as_storage_mut().fill_fixed(|| Cell::new(None));
+    }
+
+    /// Inserts a key-value pair into the map, creating a slot for the key if
+    /// it doesn't already have one.
+    ///
+    /// Returns the value previously stored for the key, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{CellMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = CellMap::new();
+    /// assert_eq!(map.insert(MyKey::First, 1), None);
+    /// assert_eq!(map.insert(MyKey::First, 2), Some(1));
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let old = self
+            .map
+            .insert(key, Cell::new(Some(value)))
+            .and_then(Cell::into_inner);
+
+        if old.is_none() {
+            self.len.set(self.len.get() + 1);
+        }
+
+        old
+    }
+
+    /// Ensures that the given key has a slot, without storing a value in it.
+    ///
+    /// Once a slot is reserved it can be populated through a shared reference
+    /// using [`CellMap::replace_value`]. This is only needed for keys stored in
+    /// a dynamic or lazy branch, since every other key always has a slot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "hashbrown")] {
+    /// use fixed_map::{CellMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(u32),
+    /// }
+    ///
+    /// let mut map = CellMap::new();
+    /// map.reserve(MyKey::Second(1));
+    ///
+    /// assert!(map.has_slot(MyKey::Second(1)));
+    /// assert_eq!(map.get(MyKey::Second(1)), None);
+    ///
+    /// assert_eq!((&map).replace_value(MyKey::Second(1), 42), Ok(None));
+    /// assert_eq!(map.get(MyKey::Second(1)), Some(42));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn reserve(&mut self, key: K) {
+        self.map.entry(key).or_insert_with(|| Cell::new(None));
+    }
+
+    /// Removes a key from the map, returning the value stored for it if any.
+    ///
+    /// The slot of a key stored in a dynamic or lazy branch is removed as
+    /// well, while keys with fixed storage keep theirs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "hashbrown")] {
+    /// use fixed_map::{CellMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(u32),
+    /// }
+    ///
+    /// let mut map = CellMap::new();
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second(2), 2);
+    ///
+    /// assert_eq!(map.remove(MyKey::First), Some(1));
+    /// assert_eq!(map.remove(MyKey::Second(2)), Some(2));
+    /// assert!(map.has_slot(MyKey::First));
+    /// assert!(!map.has_slot(MyKey::Second(2)));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn remove(&mut self, key: K) -> Option<V> {
+        let value = self.map.remove(key).and_then(Cell::into_inner);
+        self.fill_fixed();
+
+        if value.is_some() {
+            self.len.set(self.len.get() - 1);
+        }
+
+        value
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{CellMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = CellMap::new();
+    /// map.insert(MyKey::First, String::from("a"));
+    ///
+    /// if let Some(value) = map.get_mut(MyKey::First) {
+    ///     value.push('b');
+    /// }
+    ///
+    /// assert_eq!(map.into_map().get(MyKey::First).map(String::as_str), Some("ab"));
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        self.map.get_mut(key)?.get_mut().as_mut()
+    }
+
+    /// Returns `true` if the given key has a slot in the map, regardless of
+    /// whether a value is currently stored in it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "hashbrown")] {
+    /// use fixed_map::{CellMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(u32),
+    /// }
+    ///
+    /// let mut map = CellMap::<_, u32>::new();
+    /// map.reserve(MyKey::Second(1));
+    ///
+    /// assert!(map.has_slot(MyKey::First));
+    /// assert!(map.has_slot(MyKey::Second(1)));
+    /// assert!(!map.has_slot(MyKey::Second(2)));
+    /// assert!(!map.contains_key(MyKey::First));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn has_slot(&self, key: K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Returns `true` if the map currently stores a value for the given key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{CellMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = CellMap::new();
+    /// map.insert(MyKey::First, 1);
+    ///
+    /// assert!(map.contains_key(MyKey::First));
+    /// assert!(!map.contains_key(MyKey::Second));
+    /// ```
+    #[inline]
+    pub fn contains_key(&self, key: K) -> bool {
+        self.map.get(key).is_some_and(|cell| {
+            // NB: `Cell<Option<V>>` can't be inspected without taking the
+            // value out, so we put it back immediately afterwards.
+            let value = cell.take();
+            let is_some = value.is_some();
+            cell.set(value);
+            is_some
+        })
+    }
+
+    /// Returns `true` if no values are stored in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{CellMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map = CellMap::new();
+    /// assert!(map.is_empty());
+    ///
+    /// assert_eq!(map.replace_value(MyKey::First, 1), Ok(None));
+    /// assert!(!map.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len.get() == 0
+    }
+
+    /// Returns the number of values stored in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{CellMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = CellMap::new();
+    /// map.insert(MyKey::First, 1);
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// Removes all values from the map, together with the slots of keys
+    /// stored in dynamic or lazy branches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{CellMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = CellMap::new();
+    /// map.insert(MyKey::First, 1);
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// assert!(map.has_slot(MyKey::First));
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.fill_fixed();
+        self.len.set(0);
+    }
+
+    /// Takes the value out of the slot for the given key through a shared
+    /// reference, leaving the slot in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{CellMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = CellMap::new();
+    /// map.insert(MyKey::First, String::from("a"));
+    ///
+    /// let shared = &map;
+    /// assert_eq!(shared.take(MyKey::First).as_deref(), Some("a"));
+    /// assert_eq!(shared.take(MyKey::First), None);
+    /// assert!(shared.has_slot(MyKey::First));
+    /// ```
+    #[inline]
+    pub fn take(&self, key: K) -> Option<V> {
+        let value = self.map.get(key)?.take()?;
+        self.len.set(self.len.get() - 1);
+        Some(value)
+    }
+
+    /// Converts the [`CellMap`] into a regular [`Map`], dropping any empty
+    /// slots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{CellMap, Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = CellMap::new();
+    /// map.insert(MyKey::First, 1);
+    ///
+    /// let map: Map<_, _> = map.into_map();
+    /// assert!(map.iter().eq([(MyKey::First, &1)]));
+    /// ```
+    #[inline]
+    pub fn into_map(self) -> Map<K, V> {
+        self.map
+            .into_iter()
+            .filter_map(|(key, cell)| Some((key, cell.into_inner()?)))
+            .collect()
+    }
+}
+
+impl<K, V> CellMap<K, V>
+where
+    K: Key,
+    V: Copy,
+{
+    /// Returns a copy of the value corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{CellMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = CellMap::new();
+    /// map.insert(MyKey::First, 1);
+    ///
+    /// assert_eq!(map.get(MyKey::First), Some(1));
+    /// assert_eq!(map.get(MyKey::Second), None);
+    /// ```
+    #[inline]
+    pub fn get(&self, key: K) -> Option<V> {
+        self.map.get(key)?.get()
+    }
+
+    /// Replaces the value stored in the slot for the given key through a
+    /// shared reference, returning the previous value if any.
+    ///
+    /// Keys with fixed storage always have a slot, while keys stored in a
+    /// dynamic or lazy branch need one to be created first, see
+    /// [`CellMap::reserve`].
+    ///
+    /// # Errors
+    ///
+    /// If the key has no slot, `value` is handed back as an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{CellMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = CellMap::new();
+    /// map.insert(MyKey::First, 1);
+    ///
+    /// let shared = &map;
+    /// assert_eq!(shared.replace_value(MyKey::First, 2), Ok(Some(1)));
+    /// assert_eq!(shared.replace_value(MyKey::Second, 3), Ok(None));
+    ///
+    /// assert_eq!(map.get(MyKey::First), Some(2));
+    /// assert_eq!(map.get(MyKey::Second), Some(3));
+    /// ```
+    #[inline]
+    pub fn replace_value(&self, key: K, value: V) -> Result<Option<V>, V> {
+        let Some(cell) = self.map.get(key) else {
+            return Err(value);
+        };
+
+        let old = cell.replace(Some(value));
+
+        if old.is_none() {
+            self.len.set(self.len.get() + 1);
+        }
+
+        Ok(old)
+    }
+
+    /// An iterator visiting all stored key-value pairs in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{CellMap, Key};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    ///     Third,
+    /// }
+    ///
+    /// let mut map = CellMap::new();
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Third, 3);
+    ///
+    /// assert!(map.iter().eq([(MyKey::First, 1), (MyKey::Third, 3)]));
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            iter: self.map.iter(),
+        }
+    }
+}
+
+/// The iterator produced by [`CellMap::iter`].
+pub struct Iter<'a, K, V>
+where
+    K: 'a + Key,
+    V: 'a,
+{
+    iter: map::Iter<'a, K, Cell<Option<V>>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V>
+where
+    K: 'a + Key,
+    V: Copy,
+{
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (key, cell) = self.iter.next()?;
+
+            if let Some(value) = cell.get() {
+                return Some((key, value));
+            }
+        }
+    }
+}
+
+impl<K, V> Default for CellMap<K, V>
+where
+    K: Key,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Clone for CellMap<K, V>
+where
+    K: Key,
+    K::MapStorage<Cell<Option<V>>>: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+            len: self.len.clone(),
+        }
+    }
+}
+
+/// Converts a [`Map`] into a [`CellMap`] where every present key has a slot,
+/// in addition to every key with fixed storage.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{CellMap, Key, Map};
+///
+/// #[derive(Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(MyKey::First, 1);
+///
+/// let map = CellMap::from(map);
+/// assert_eq!(map.get(MyKey::First), Some(1));
+/// assert_eq!(map.replace_value(MyKey::Second, 2), Ok(None));
+/// ```
+impl<K, V> From<Map<K, V>> for CellMap<K, V>
+where
+    K: Key,
+{
+    #[inline]
+    fn from(map: Map<K, V>) -> Self {
+        let mut map = Self {
+            len: Cell::new(map.len()),
+            map: map
+                .into_iter()
+                .map(|(key, value)| (key, Cell::new(Some(value))))
+                .collect(),
+        };
+
+        map.fill_fixed();
+        map
+    }
+}
+
+/// The [`Debug`][fmt::Debug] implementation for a [`CellMap`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{CellMap, Key};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let mut map = CellMap::new();
+/// map.insert(MyKey::First, 42);
+///
+/// assert_eq!("{First: 42}", format!("{:?}", map));
+/// ```
+impl<K, V> fmt::Debug for CellMap<K, V>
+where
+    K: Key + fmt::Debug,
+    V: Copy + fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,139 @@
+//! Helpers for the alternate [`Debug`][fmt::Debug] output of maps and sets,
+//! which groups entries by the variant of their key.
+
+use core::fmt;
+
+use crate::{Key, Map, Set};
+
+/// The name of a variant, which is formatted without quotes.
+struct VariantName(&'static str);
+
+impl fmt::Debug for VariantName {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+/// A key which is formatted on a single line, even in the alternate format.
+struct Compact<K>(K);
+
+impl<K> fmt::Debug for Compact<K>
+where
+    K: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+/// Iterate over the distinct variant names of `keys`.
+///
+/// Storage iterates over keys in variant order, so the keys of a variant are
+/// always adjacent.
+fn variant_names<K>(keys: impl Iterator<Item = K>) -> impl Iterator<Item = &'static str>
+where
+    K: Key,
+{
+    let mut last = None;
+
+    keys.filter_map(move |key| {
+        let name = key.variant_name();
+
+        if last == Some(name) {
+            return None;
+        }
+
+        last = Some(name);
+        Some(name)
+    })
+}
+
+/// The entries of a map which belong to the variant `name`.
+struct MapGroup<'a, K, V>
+where
+    K: Key,
+{
+    map: &'a Map<K, V>,
+    name: &'static str,
+}
+
+impl<K, V> fmt::Debug for MapGroup<'_, K, V>
+where
+    K: Key + fmt::Debug,
+    V: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.map
+                    .iter()
+                    .filter(|(key, _)| key.variant_name() == self.name)
+                    .map(|(key, value)| (Compact(key), value)),
+            )
+            .finish()
+    }
+}
+
+/// The values of a set which belong to the variant `name`.
+struct SetGroup<'a, T>
+where
+    T: Key,
+{
+    set: &'a Set<T>,
+    name: &'static str,
+}
+
+impl<T> fmt::Debug for SetGroup<'_, T>
+where
+    T: Key + fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(
+                self.set
+                    .iter()
+                    .filter(|value| value.variant_name() == self.name)
+                    .map(Compact),
+            )
+            .finish()
+    }
+}
+
+/// Format a map as a map from variant names to the entries of each variant.
+pub(crate) fn grouped_map<K, V>(map: &Map<K, V>, f: &mut fmt::Formatter<'_>) -> fmt::Result
+where
+    K: Key + fmt::Debug,
+    V: fmt::Debug,
+{
+    f.debug_map()
+        .entries(variant_names(map.keys()).map(|name| (VariantName(name), MapGroup { map, name })))
+        .finish()
+}
+
+/// Format a set as a map from variant names to the values of each variant.
+pub(crate) fn grouped_set<T>(set: &Set<T>, f: &mut fmt::Formatter<'_>) -> fmt::Result
+where
+    T: Key + fmt::Debug,
+{
+    f.debug_map()
+        .entries(variant_names(set.iter()).map(|name| (VariantName(name), SetGroup { set, name })))
+        .finish()
+}
+
+/// A key which is formatted through [`Key::fmt_key`], so that it can be
+/// included in diagnostics even if it doesn't implement [`Debug`][fmt::Debug].
+pub(crate) struct KeyName<K>(pub(crate) K);
+
+impl<K> fmt::Display for KeyName<K>
+where
+    K: Key,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_key(f)
+    }
+}
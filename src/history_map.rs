@@ -0,0 +1,468 @@
+//! Contains the fixed [`HistoryMap`] implementation.
+
+use core::fmt;
+
+use crate::map::{self, Map};
+use crate::Key;
+
+/// A fixed-size ring buffer holding the most recent values of a key.
+struct Ring<V, const N: usize> {
+    data: [Option<V>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<V, const N: usize> Ring<V, N> {
+    #[inline]
+    fn new() -> Self {
+        Self {
+            data: core::array::from_fn(|_| None),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    #[inline]
+    fn push(&mut self, value: V) -> Option<V> {
+        if self.len == N {
+            let old = self.data[self.head].replace(value);
+            self.head = (self.head + 1) % N;
+            return old;
+        }
+
+        self.data[(self.head + self.len) % N] = Some(value);
+        self.len += 1;
+        None
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> Option<&V> {
+        if index >= self.len {
+            return None;
+        }
+
+        self.data[(self.head + index) % N].as_ref()
+    }
+}
+
+/// A fixed map which keeps a bounded history of the most recent values
+/// associated with every key.
+///
+/// Every key is associated with a ring buffer holding up to `N` values. Once it
+/// is full, pushing another value evicts the oldest one. The storage for all
+/// buffers is laid out densely like in a [`Map`], so this doesn't allocate.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{HistoryMap, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Sensor {
+///     Temperature,
+///     Pressure,
+/// }
+///
+/// let mut history = HistoryMap::<_, _, 3>::new();
+///
+/// for reading in [20, 21, 23, 22] {
+///     history.push(Sensor::Temperature, reading);
+/// }
+///
+/// history.push(Sensor::Pressure, 1013);
+///
+/// assert_eq!(history.latest(Sensor::Temperature), Some(&22));
+/// assert!(history.history(Sensor::Temperature).eq(&[21, 23, 22]));
+/// assert!(history.history(Sensor::Pressure).eq(&[1013]));
+/// ```
+///
+/// Using a composite key:
+///
+/// ```
+/// use fixed_map::{HistoryMap, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Channel {
+///     Input(bool),
+///     Output,
+/// }
+///
+/// let mut history = HistoryMap::<_, _, 2>::new();
+/// history.push(Channel::Input(true), 'a');
+/// history.push(Channel::Input(true), 'b');
+/// history.push(Channel::Input(false), 'c');
+///
+/// assert!(history.history(Channel::Input(true)).eq(&['a', 'b']));
+/// assert!(history.history(Channel::Output).eq(&[]));
+/// ```
+pub struct HistoryMap<K, V, const N: usize>
+where
+    K: Key,
+{
+    map: Map<K, Ring<V, N>>,
+}
+
+impl<K, V, const N: usize> HistoryMap<K, V, N>
+where
+    K: Key,
+{
+    /// Creates an empty [`HistoryMap`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{HistoryMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let history: HistoryMap<MyKey, u32, 4> = HistoryMap::new();
+    /// assert!(history.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new() -> HistoryMap<K, V, N> {
+        HistoryMap { map: Map::new() }
+    }
+
+    /// Returns the number of keys which have a history.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{HistoryMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut history = HistoryMap::<_, _, 4>::new();
+    /// history.push(MyKey::First, 1);
+    /// history.push(MyKey::First, 2);
+    /// assert_eq!(history.len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if no key has a history.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{HistoryMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut history = HistoryMap::<_, _, 4>::new();
+    /// assert!(history.is_empty());
+    /// history.push(MyKey::First, 1);
+    /// assert!(!history.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Pushes a value onto the history of the given key.
+    ///
+    /// If the history of the key already holds `N` values, the oldest one is
+    /// evicted and returned. If `N` is zero, nothing can be stored so the value
+    /// is returned immediately and the key doesn't gain a history.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{HistoryMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut history = HistoryMap::<_, _, 2>::new();
+    /// assert_eq!(history.push(MyKey::First, 1), None);
+    /// assert_eq!(history.push(MyKey::First, 2), None);
+    /// assert_eq!(history.push(MyKey::First, 3), Some(1));
+    /// ```
+    #[inline]
+    pub fn push(&mut self, key: K, value: V) -> Option<V> {
+        if N == 0 {
+            return Some(value);
+        }
+
+        self.map.entry(key).or_insert_with(Ring::new).push(value)
+    }
+
+    /// Returns the most recent value pushed for the given key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{HistoryMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut history = HistoryMap::<_, _, 2>::new();
+    /// history.push(MyKey::First, 1);
+    /// history.push(MyKey::First, 2);
+    ///
+    /// assert_eq!(history.latest(MyKey::First), Some(&2));
+    /// assert_eq!(history.latest(MyKey::Second), None);
+    /// ```
+    #[inline]
+    pub fn latest(&self, key: K) -> Option<&V> {
+        let ring = self.map.get(key)?;
+        ring.get(ring.len.checked_sub(1)?)
+    }
+
+    /// Iterate over the history of the given key, from the oldest to the most
+    /// recent value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{HistoryMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut history = HistoryMap::<_, _, 3>::new();
+    ///
+    /// for n in 0..5 {
+    ///     history.push(MyKey::First, n);
+    /// }
+    ///
+    /// assert!(history.history(MyKey::First).eq(&[2, 3, 4]));
+    /// assert!(history.history(MyKey::First).rev().eq(&[4, 3, 2]));
+    /// assert_eq!(history.history(MyKey::Second).len(), 0);
+    /// ```
+    #[inline]
+    pub fn history(&self, key: K) -> History<'_, V, N> {
+        History::new(self.map.get(key))
+    }
+
+    /// Removes the history of the given key, returning `true` if it had one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{HistoryMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut history = HistoryMap::<_, _, 2>::new();
+    /// history.push(MyKey::First, 1);
+    ///
+    /// assert!(history.remove(MyKey::First));
+    /// assert!(!history.remove(MyKey::First));
+    /// assert_eq!(history.latest(MyKey::First), None);
+    /// ```
+    #[inline]
+    pub fn remove(&mut self, key: K) -> bool {
+        self.map.remove(key).is_some()
+    }
+
+    /// Removes the history of every key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{HistoryMap, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut history = HistoryMap::<_, _, 2>::new();
+    /// history.push(MyKey::First, 1);
+    /// history.clear();
+    /// assert!(history.is_empty());
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Iterate over every key which has a history together with it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{HistoryMap, Key};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut history = HistoryMap::<_, _, 2>::new();
+    /// history.push(MyKey::Second, 1);
+    /// history.push(MyKey::Second, 2);
+    ///
+    /// let mut it = history.iter();
+    /// let (key, values) = it.next().unwrap();
+    /// assert_eq!(key, MyKey::Second);
+    /// assert!(values.eq(&[1, 2]));
+    /// assert!(it.next().is_none());
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V, N> {
+        Iter {
+            iter: self.map.iter(),
+        }
+    }
+}
+
+impl<K, V, const N: usize> Default for HistoryMap<K, V, N>
+where
+    K: Key,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, const N: usize> fmt::Debug for HistoryMap<K, V, N>
+where
+    K: Key + fmt::Debug,
+    V: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the history of a single key in a [`HistoryMap`], from the
+/// oldest to the most recent value.
+///
+/// This is created by [`HistoryMap::history`].
+pub struct History<'a, V, const N: usize> {
+    ring: Option<&'a Ring<V, N>>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, V, const N: usize> History<'a, V, N> {
+    #[inline]
+    fn new(ring: Option<&'a Ring<V, N>>) -> Self {
+        Self {
+            ring,
+            front: 0,
+            back: ring.map_or(0, |ring| ring.len),
+        }
+    }
+}
+
+impl<V, const N: usize> Clone for History<'_, V, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            ring: self.ring,
+            front: self.front,
+            back: self.back,
+        }
+    }
+}
+
+impl<V, const N: usize> fmt::Debug for History<'_, V, N>
+where
+    V: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, V, const N: usize> Iterator for History<'a, V, N> {
+    type Item = &'a V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        let value = self.ring?.get(self.front);
+        self.front += 1;
+        value
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<V, const N: usize> DoubleEndedIterator for History<'_, V, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.back -= 1;
+        self.ring?.get(self.back)
+    }
+}
+
+impl<V, const N: usize> ExactSizeIterator for History<'_, V, N> {}
+
+/// An iterator over the keys of a [`HistoryMap`] and their histories.
+///
+/// This is created by [`HistoryMap::iter`].
+pub struct Iter<'a, K, V, const N: usize>
+where
+    K: 'a + Key,
+    V: 'a,
+{
+    iter: map::Iter<'a, K, Ring<V, N>>,
+}
+
+impl<'a, K, V, const N: usize> Iterator for Iter<'a, K, V, N>
+where
+    K: 'a + Key,
+    V: 'a,
+{
+    type Item = (K, History<'a, V, N>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, ring) = self.iter.next()?;
+        Some((key, History::new(Some(ring))))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
@@ -1,9 +1,13 @@
 //! Module for the trait to define a `Key`.
 
-#[cfg(feature = "hashbrown")]
+use core::fmt;
+
+use crate::macro_support::__storage_capacity_add;
+#[cfg(any(feature = "hashbrown", feature = "std-collections"))]
 use crate::map::storage::HashbrownMapStorage;
 use crate::map::storage::{BooleanMapStorage, MapStorage, OptionMapStorage, SingletonMapStorage};
-#[cfg(feature = "hashbrown")]
+use crate::schema::{Hasher, Variant};
+#[cfg(any(feature = "hashbrown", feature = "std-collections"))]
 use crate::set::storage::HashbrownSetStorage;
 use crate::set::storage::{BooleanSetStorage, OptionSetStorage, SetStorage, SingletonSetStorage};
 
@@ -51,7 +55,7 @@ use crate::set::storage::{BooleanSetStorage, OptionSetStorage, SetStorage, Singl
 ///
 /// #[derive(Clone, Copy, Key)]
 /// enum MyKey {
-///     # #[cfg(feature = "hashbrown")]
+///     # #[cfg(any(feature = "hashbrown", feature = "std-collections"))]
 ///     First(u32),
 ///     Second,
 /// }
@@ -132,11 +136,457 @@ pub trait Key: Copy {
     /// The [`Set`][crate::Set] storage implementation to use for the key
     /// implementing this trait.
     type SetStorage: SetStorage<Self>;
+
+    /// A hash of the names and order of the variants of the key, including
+    /// the schema of any keys nested in them.
+    ///
+    /// This changes if variants are added, removed, renamed or reordered, and
+    /// can be used to detect that data serialized with an older version of a
+    /// key can no longer be decoded safely. See the [`schema`][crate::schema]
+    /// module for helpers.
+    ///
+    /// The [`Key`][key-derive] derive hashes the names of every enabled
+    /// variant. The default is the hash of a key without any variants, so keys
+    /// which implement this trait by hand should override it for changes to
+    /// be detected.
+    ///
+    /// [key-derive]: derive@crate::Key
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::Key;
+    ///
+    /// mod v1 {
+    ///     #[derive(Clone, Copy, fixed_map::Key)]
+    ///     pub enum MyKey {
+    ///         First,
+    ///         Second,
+    ///     }
+    /// }
+    ///
+    /// mod v2 {
+    ///     #[derive(Clone, Copy, fixed_map::Key)]
+    ///     pub enum MyKey {
+    ///         Second,
+    ///         First,
+    ///     }
+    /// }
+    ///
+    /// assert_ne!(v1::MyKey::KEY_SCHEMA_HASH, v2::MyKey::KEY_SCHEMA_HASH);
+    /// ```
+    const KEY_SCHEMA_HASH: u64 = Hasher::new().finish();
+
+    /// The number of distinct keys which can be stored in a
+    /// [`Map`][crate::Map] or [`Set`][crate::Set], or `None` if storage for
+    /// the key is dynamic, such as for keys holding a `u32` or a
+    /// `&'static str`.
+    ///
+    /// The [`Key`][key-derive] derive adds up the capacity of every enabled
+    /// variant, where a unit variant counts as one key. The default is `None`.
+    ///
+    /// [key-derive]: derive@crate::Key
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::Key;
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Part {
+    ///     One,
+    ///     Two,
+    /// }
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(Part),
+    ///     Third(Option<bool>),
+    /// }
+    ///
+    /// # #[cfg(feature = "hashbrown")]
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Dynamic {
+    ///     First,
+    ///     Second(u32),
+    /// }
+    ///
+    /// assert_eq!(Part::CAPACITY, Some(2));
+    /// assert_eq!(MyKey::CAPACITY, Some(6));
+    /// # #[cfg(feature = "hashbrown")]
+    /// assert_eq!(Dynamic::CAPACITY, None);
+    /// ```
+    const CAPACITY: Option<usize> = None;
+
+    /// Every variant declared on the key in declaration order.
+    ///
+    /// If the order of variants is specified with `#[key(variants(...))]`,
+    /// variants which have been disabled through `#[cfg(...)]` are kept as
+    /// reserved slots, so the position of a variant in this list doesn't
+    /// depend on the active configuration. See
+    /// [`schema::slot`][crate::schema::slot] and
+    /// [`schema::layout_hash`][crate::schema::layout_hash].
+    ///
+    /// Reserved slots don't apply to the bits of a `#[key(bitset)]` set, which
+    /// only count enabled variants. So [`BitIndex`][crate::raw::BitIndex] and
+    /// [`Set::as_raw`][crate::Set::as_raw] can still change depending on the
+    /// active configuration.
+    ///
+    /// This is empty for keys which are not derived from an enum.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::Key;
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// #[key(variants(First, Second, Third))]
+    /// enum MyKey {
+    ///     First,
+    ///     #[cfg(any())]
+    ///     Second,
+    ///     Third(bool),
+    /// }
+    ///
+    /// let variants = MyKey::KEY_VARIANTS;
+    /// assert_eq!(variants.len(), 3);
+    /// assert_eq!(variants[1].name(), "Second");
+    /// assert!(!variants[1].is_enabled());
+    /// assert!(variants[2].is_enabled());
+    /// ```
+    const KEY_VARIANTS: &'static [Variant] = &[];
+
+    /// The name of the variant of this key, which is used to group entries in
+    /// the alternate [`Debug`][core::fmt::Debug] output of a [`Map`][crate::Map]
+    /// or [`Set`][crate::Set], and to label the events passed to a `Recorder` when the
+    /// `metrics` feature is enabled.
+    ///
+    /// The [`Key`][key-derive] derive implements this by returning the name of
+    /// the variant. The default implementation returns the name of the type.
+    ///
+    /// [key-derive]: derive@crate::Key
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::Key;
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    /// }
+    ///
+    /// assert_eq!(MyKey::First.variant_name(), "First");
+    /// assert_eq!(MyKey::Second(true).variant_name(), "Second");
+    /// ```
+    #[inline]
+    fn variant_name(self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
+
+    /// Format the key for diagnostics, such as the message of a panic caused
+    /// by a missing key, without requiring the key to implement
+    /// [`Debug`][core::fmt::Debug].
+    ///
+    /// The [`Key`][key-derive] derive implements this by formatting the name
+    /// of the variant together with any nested key. The default
+    /// implementation formats the [`variant_name`][Key::variant_name] of the
+    /// key.
+    ///
+    /// [key-derive]: derive@crate::Key
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::fmt;
+    ///
+    /// use fixed_map::Key;
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(Option<bool>),
+    /// }
+    ///
+    /// struct Name(MyKey);
+    ///
+    /// impl fmt::Display for Name {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         self.0.fmt_key(f)
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(Name(MyKey::First).to_string(), "First");
+    /// assert_eq!(Name(MyKey::Second(Some(true))).to_string(), "Second(Some(true))");
+    /// ```
+    #[inline]
+    fn fmt_key(self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.variant_name())
+    }
+
+    /// The index of the class of this key, where keys belong to the same
+    /// class if they share a [`variant_name`][Key::variant_name].
+    ///
+    /// This is used to address the storage of a single class through
+    /// [`ClassStorage`][crate::map::ClassStorage]. The [`Key`][key-derive]
+    /// derive implements this by returning the index of the variant. The
+    /// default implementation returns `0`, since every key is in the same
+    /// class.
+    ///
+    /// [key-derive]: derive@crate::Key
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::Key;
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    /// }
+    ///
+    /// assert_eq!(MyKey::First.class_index(), 0);
+    /// assert_eq!(MyKey::Second(true).class_index(), 1);
+    /// assert_eq!(MyKey::Second(false).class_index(), 1);
+    /// ```
+    #[inline]
+    fn class_index(self) -> usize {
+        0
+    }
 }
 
+/// A selector for one class of a [`Key`], as returned by
+/// [`Key::class_index`].
+///
+/// This is implemented by the `<Key>Group` enum generated through
+/// `#[key(groups)]`, which has one unit variant for every variant of the key.
+/// It is used to address a single class through methods such as
+/// [`Map::len_of`][crate::Map::len_of] without having to construct a key.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, KeyGroup};
+///
+/// #[derive(Clone, Copy, Key)]
+/// #[key(groups)]
+/// enum MyKey {
+///     First,
+///     Second(bool),
+/// }
+///
+/// assert_eq!(MyKeyGroup::Second.class_index(), 1);
+/// assert_eq!(MyKeyGroup::from(MyKey::Second(true)), MyKeyGroup::Second);
+/// ```
+pub trait KeyGroup: Copy {
+    /// The key whose classes are selected.
+    type Key: Key;
+
+    /// The class index selected, which matches [`Key::class_index`] for
+    /// every key in the class.
+    fn class_index(self) -> usize;
+}
+
+/// A [`Key`] whose map storage is [`Clone`] for values of type `V`.
+///
+/// This is implemented for every key where it holds, so that generic code can
+/// use `K: CloneableKey<V>` instead of spelling out `K: Key,
+/// K::MapStorage<V>: Clone`.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{CloneableKey, Key, Map};
+///
+/// fn duplicate<K, V>(map: &Map<K, V>) -> (Map<K, V>, Map<K, V>)
+/// where
+///     K: CloneableKey<V>,
+/// {
+///     (map.clone(), map.clone())
+/// }
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First(bool),
+///     Second,
+/// }
+///
+/// let map = Map::from_iter([(MyKey::First(true), String::from("a"))]);
+/// let (a, b) = duplicate(&map);
+/// assert_eq!(a, b);
+/// ```
+pub trait CloneableKey<V>: Key {
+    /// Clone the map storage of the key.
+    #[doc(hidden)]
+    fn __clone_storage(storage: &Self::MapStorage<V>) -> Self::MapStorage<V>;
+}
+
+impl<K, V> CloneableKey<V> for K
+where
+    K: Key,
+    K::MapStorage<V>: Clone,
+{
+    #[inline]
+    fn __clone_storage(storage: &Self::MapStorage<V>) -> Self::MapStorage<V> {
+        storage.clone()
+    }
+}
+
+/// A [`Key`] whose map storage is [`Copy`] for values of type `V`.
+///
+/// This is implemented for every key where it holds, so that generic code can
+/// use `K: CopyKey<V>` instead of spelling out `K: Key, K::MapStorage<V>:
+/// Copy`.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{CopyKey, Key, Map};
+///
+/// fn snapshot<K, V>(map: &Map<K, V>) -> Map<K, V>
+/// where
+///     K: CopyKey<V>,
+/// {
+///     *map
+/// }
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let map = Map::from_iter([(MyKey::First, 1)]);
+/// assert_eq!(snapshot(&map), map);
+/// ```
+pub trait CopyKey<V>:
+    CloneableKey<V> + Key<MapStorage<V> = <Self as CopyKey<V>>::CopyStorage>
+{
+    /// The map storage of the key, which is the same as
+    /// [`Key::MapStorage<V>`].
+    #[doc(hidden)]
+    type CopyStorage: Copy + MapStorage<Self, V>;
+}
+
+impl<K, V> CopyKey<V> for K
+where
+    K: Key,
+    K::MapStorage<V>: Copy,
+{
+    type CopyStorage = K::MapStorage<V>;
+}
+
+/// A [`Key`] whose map storage is [`Hash`][core::hash::Hash] for values of
+/// type `V`.
+///
+/// This is implemented for every key where it holds, so that generic code can
+/// use `K: HashableKey<V>` instead of spelling out `K: Key, K::MapStorage<V>:
+/// Hash`.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use fixed_map::{HashableKey, Key, Map};
+///
+/// fn count_unique<K, V>(maps: Vec<Map<K, V>>) -> usize
+/// where
+///     K: HashableKey<V>,
+///     Map<K, V>: Eq,
+/// {
+///     maps.into_iter().collect::<HashSet<_>>().len()
+/// }
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let a = Map::from_iter([(MyKey::First, 1)]);
+/// let b = Map::from_iter([(MyKey::Second, 1)]);
+/// assert_eq!(count_unique(vec![a, b, a]), 2);
+/// ```
+pub trait HashableKey<V>: Key {
+    /// Hash the map storage of the key.
+    #[doc(hidden)]
+    fn __hash_storage<H>(storage: &Self::MapStorage<V>, state: &mut H)
+    where
+        H: core::hash::Hasher;
+}
+
+impl<K, V> HashableKey<V> for K
+where
+    K: Key,
+    K::MapStorage<V>: core::hash::Hash,
+{
+    #[inline]
+    fn __hash_storage<H>(storage: &Self::MapStorage<V>, state: &mut H)
+    where
+        H: core::hash::Hasher,
+    {
+        core::hash::Hash::hash(storage, state);
+    }
+}
+
+/// A [`Key`] which can be serialized and deserialized, which is what
+/// [`Map`][crate::Map] and [`Set`][crate::Set] require of their keys to
+/// implement [`Serialize`] and [`DeserializeOwned`].
+///
+/// This is implemented for every key where it holds, so that generic code can
+/// use `K: SerdeKey` instead of spelling out the serde bounds.
+///
+/// This is only available when the `serde` feature is enabled.
+///
+/// [`Serialize`]: serde::Serialize
+/// [`DeserializeOwned`]: serde::de::DeserializeOwned
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Map, SerdeKey};
+///
+/// fn round_trip<K, V>(map: &Map<K, V>) -> serde_json::Result<Map<K, V>>
+/// where
+///     K: SerdeKey,
+///     V: serde::Serialize + serde::de::DeserializeOwned,
+/// {
+///     serde_json::from_str(&serde_json::to_string(map)?)
+/// }
+///
+/// let map = Map::from_iter([(true, 1), (false, 2)]);
+/// assert_eq!(round_trip(&map)?, map);
+/// # Ok::<_, serde_json::Error>(())
+/// ```
+#[cfg(feature = "serde")]
+pub trait SerdeKey: Key + serde::Serialize + serde::de::DeserializeOwned {}
+
+#[cfg(feature = "serde")]
+impl<K> SerdeKey for K where K: Key + serde::Serialize + serde::de::DeserializeOwned {}
+
 impl Key for bool {
     type MapStorage<V> = BooleanMapStorage<V>;
     type SetStorage = BooleanSetStorage;
+    const KEY_SCHEMA_HASH: u64 = Hasher::new().write_str("bool").finish();
+    const CAPACITY: Option<usize> = Some(2);
+
+    #[inline]
+    fn variant_name(self) -> &'static str {
+        if self {
+            "true"
+        } else {
+            "false"
+        }
+    }
+
+    #[inline]
+    fn class_index(self) -> usize {
+        usize::from(self)
+    }
 }
 
 impl<K> Key for Option<K>
@@ -145,14 +595,53 @@ where
 {
     type MapStorage<V> = OptionMapStorage<K, V>;
     type SetStorage = OptionSetStorage<K>;
+    const KEY_SCHEMA_HASH: u64 = Hasher::new()
+        .write_str("Option")
+        .write_u64(K::KEY_SCHEMA_HASH)
+        .finish();
+    const CAPACITY: Option<usize> = __storage_capacity_add(K::CAPACITY, Some(1));
+
+    #[inline]
+    fn variant_name(self) -> &'static str {
+        match self {
+            Some(..) => "Some",
+            None => "None",
+        }
+    }
+
+    #[inline]
+    fn class_index(self) -> usize {
+        match self {
+            Some(..) => 0,
+            None => 1,
+        }
+    }
+
+    #[inline]
+    fn fmt_key(self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Some(key) => {
+                f.write_str("Some(")?;
+                key.fmt_key(f)?;
+                f.write_str(")")
+            }
+            None => f.write_str("None"),
+        }
+    }
 }
 
 macro_rules! map_key {
     ($ty:ty) => {
-        #[cfg(feature = "hashbrown")]
+        #[cfg(any(feature = "hashbrown", feature = "std-collections"))]
         impl Key for $ty {
             type MapStorage<V> = HashbrownMapStorage<$ty, V>;
             type SetStorage = HashbrownSetStorage<$ty>;
+            const KEY_SCHEMA_HASH: u64 = Hasher::new().write_str(stringify!($ty)).finish();
+
+            #[inline]
+            fn fmt_key(self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&self, f)
+            }
         }
     };
 }
@@ -162,6 +651,8 @@ macro_rules! singleton_key {
         impl Key for $ty {
             type MapStorage<V> = SingletonMapStorage<V>;
             type SetStorage = SingletonSetStorage;
+            const KEY_SCHEMA_HASH: u64 = Hasher::new().write_str(stringify!($ty)).finish();
+            const CAPACITY: Option<usize> = Some(1);
         }
     };
 }
@@ -0,0 +1,248 @@
+//! Iteration over every key in a finite domain.
+//!
+//! Keys whose domain is finite can implement [`FiniteKey`], which numbers
+//! every key in the domain so that [`KeyIter`] can enumerate them without
+//! constructing a [`Map`] or [`Set`]. This is useful for things like listing
+//! every option of a key in a user interface.
+//!
+//! [`FiniteKey`] is implemented for `bool`, `()` and `Option<K>` where `K`
+//! implements it, and can be derived through `#[key(iter)]`.
+//!
+//! [`Map`]: crate::Map
+//! [`Set`]: crate::Set
+//!
+//! # Examples
+//!
+//! ```
+//! use fixed_map::key_iter::FiniteKey;
+//! use fixed_map::Key;
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq, Key)]
+//! #[key(iter)]
+//! enum Part {
+//!     One,
+//!     Two,
+//! }
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq, Key)]
+//! #[key(iter)]
+//! enum MyKey {
+//!     First,
+//!     Second(Part),
+//!     Third(bool),
+//! }
+//!
+//! assert_eq!(MyKey::LEN, 5);
+//!
+//! assert!(MyKey::all().eq([
+//!     MyKey::First,
+//!     MyKey::Second(Part::One),
+//!     MyKey::Second(Part::Two),
+//!     MyKey::Third(true),
+//!     MyKey::Third(false),
+//! ]));
+//! ```
+
+use core::fmt;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+
+use crate::Key;
+
+/// A key with a finite domain, where every key in the domain has an ordinal.
+///
+/// Ordinals number keys in the order a [`Map`][crate::Map] or
+/// [`Set`][crate::Set] visits them, starting at `0`. A composite key visits
+/// every key of a variant before moving on to the next variant, so the keys
+/// of a nested key occupy a contiguous range of ordinals.
+///
+/// This is implemented through `#[key(iter)]`, see the [module level
+/// documentation][self].
+pub trait FiniteKey: Key {
+    /// The number of keys in the domain.
+    const LEN: usize;
+
+    /// Get the key with the given ordinal, or `None` if it's out of range.
+    fn from_ordinal(ordinal: usize) -> Option<Self>;
+
+    /// Iterate over every key in the domain in the order of their ordinals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::key_iter::FiniteKey;
+    ///
+    /// assert!(bool::all().eq([true, false]));
+    /// assert!(Option::<bool>::all().eq([Some(true), Some(false), None]));
+    /// ```
+    #[inline]
+    fn all() -> KeyIter<Self> {
+        KeyIter::new()
+    }
+}
+
+/// An iterator over every key of a [`FiniteKey`].
+///
+/// This is created by [`FiniteKey::all`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::key_iter::{FiniteKey, KeyIter};
+/// use fixed_map::Key;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(iter)]
+/// enum Direction {
+///     North,
+///     East,
+///     South,
+///     West,
+/// }
+///
+/// let mut it = KeyIter::<Direction>::new();
+/// assert_eq!(it.len(), 4);
+/// assert_eq!(it.next(), Some(Direction::North));
+/// assert_eq!(it.next_back(), Some(Direction::West));
+/// assert!(it.eq([Direction::East, Direction::South]));
+/// ```
+pub struct KeyIter<K> {
+    front: usize,
+    back: usize,
+    _marker: PhantomData<fn() -> K>,
+}
+
+impl<K> KeyIter<K>
+where
+    K: FiniteKey,
+{
+    /// Construct an iterator over every key in the domain of `K`.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            front: 0,
+            back: K::LEN,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K> Default for KeyIter<K>
+where
+    K: FiniteKey,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Clone for KeyIter<K> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            front: self.front,
+            back: self.back,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K> fmt::Debug for KeyIter<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyIter")
+            .field("front", &self.front)
+            .field("back", &self.back)
+            .finish()
+    }
+}
+
+impl<K> Iterator for KeyIter<K>
+where
+    K: FiniteKey,
+{
+    type Item = K;
+
+    #[inline]
+    fn next(&mut self) -> Option<K> {
+        if self.front == self.back {
+            return None;
+        }
+
+        let key = K::from_ordinal(self.front)?;
+        self.front += 1;
+        Some(key)
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<K> {
+        self.front = self.front.saturating_add(n).min(self.back);
+        self.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<K> DoubleEndedIterator for KeyIter<K>
+where
+    K: FiniteKey,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<K> {
+        if self.front == self.back {
+            return None;
+        }
+
+        let key = K::from_ordinal(self.back - 1)?;
+        self.back -= 1;
+        Some(key)
+    }
+}
+
+impl<K> ExactSizeIterator for KeyIter<K> where K: FiniteKey {}
+
+impl<K> FusedIterator for KeyIter<K> where K: FiniteKey {}
+
+impl FiniteKey for bool {
+    const LEN: usize = 2;
+
+    #[inline]
+    fn from_ordinal(ordinal: usize) -> Option<Self> {
+        match ordinal {
+            0 => Some(true),
+            1 => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl FiniteKey for () {
+    const LEN: usize = 1;
+
+    #[inline]
+    fn from_ordinal(ordinal: usize) -> Option<Self> {
+        (ordinal == 0).then_some(())
+    }
+}
+
+/// Every `Some` key is visited before `None`.
+impl<K> FiniteKey for Option<K>
+where
+    K: FiniteKey,
+{
+    const LEN: usize = K::LEN + 1;
+
+    #[inline]
+    fn from_ordinal(ordinal: usize) -> Option<Self> {
+        if ordinal < K::LEN {
+            return Some(Some(K::from_ordinal(ordinal)?));
+        }
+
+        (ordinal == K::LEN).then_some(None)
+    }
+}
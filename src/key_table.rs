@@ -0,0 +1,108 @@
+/// Construct a [`Map`] with one value for every variant of a key, checked for
+/// exhaustiveness at compile time.
+///
+/// This is useful to replace a `match` which is only used to look up a value
+/// for each variant, while still getting an error when a variant is added to
+/// the key but not to the table.
+///
+/// Every variant of the key must be a unit variant, and must be listed
+/// exactly once. Values are evaluated in the order in which they are listed.
+///
+/// [`Map`]: crate::Map
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{key_table, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Direction {
+///     North,
+///     East,
+///     South,
+///     West,
+/// }
+///
+/// let degrees = key_table!(Direction {
+///     North => 0,
+///     East => 90,
+///     South => 180,
+///     West => 270,
+/// });
+///
+/// assert_eq!(degrees.len(), 4);
+/// assert_eq!(degrees.get(Direction::South), Some(&180));
+/// ```
+///
+/// Leaving out a variant is an error:
+///
+/// ```compile_fail
+/// use fixed_map::{key_table, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Direction {
+///     North,
+///     East,
+///     South,
+///     West,
+/// }
+///
+/// let degrees = key_table!(Direction {
+///     North => 0,
+///     East => 90,
+///     South => 180,
+/// });
+/// ```
+///
+/// And so is listing a variant more than once:
+///
+/// ```compile_fail
+/// use fixed_map::{key_table, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Direction {
+///     North,
+///     South,
+/// }
+///
+/// let degrees = key_table!(Direction {
+///     North => 0,
+///     South => 180,
+///     North => 360,
+/// });
+/// ```
+#[macro_export]
+macro_rules! key_table {
+    ($key:path { $($variant:ident => $value:expr),* $(,)? }) => {{
+        type Key = $key;
+
+        // NB: The match is never evaluated, it only makes the compiler check
+        // that every variant is listed.
+        let _ = |key: Key| match key {
+            $(Key::$variant => (),)*
+        };
+
+        const _: () = {
+            let variants = [$(Key::$variant as i128),*];
+            let mut i = 0;
+
+            while i < variants.len() {
+                let mut j = i + 1;
+
+                while j < variants.len() {
+                    if variants[i] == variants[j] {
+                        ::core::panic!("variant listed more than once in key_table!");
+                    }
+
+                    j += 1;
+                }
+
+                i += 1;
+            }
+        };
+
+        let mut map = $crate::Map::<Key, _>::new();
+        $(map.insert(Key::$variant, $value);)*
+        map
+    }};
+}
@@ -0,0 +1,637 @@
+//! Contains the fixed [`KeyedHeap`] implementation.
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::mem;
+
+use crate::map::{self, Map};
+use crate::Key;
+
+/// A node in the heap, which is linked to other nodes through their keys.
+struct Node<K, P> {
+    priority: P,
+    /// The first child of this node.
+    child: Option<K>,
+    /// The next sibling of this node.
+    next: Option<K>,
+    /// The previous sibling of this node, or its parent if this is the first
+    /// child. This is `None` for the root of a heap.
+    prev: Option<K>,
+    /// If `prev` refers to the parent of this node.
+    first: bool,
+}
+
+/// A priority queue where every element is a key with an associated
+/// priority.
+///
+/// This is implemented as a pairing heap which is stored intrusively in a
+/// [`Map`], so it doesn't allocate and every key can be in the queue at most
+/// once. Since elements are addressed by key, their priority can be changed
+/// after they've been pushed through [`KeyedHeap::change_priority`].
+///
+/// [`KeyedHeap::push`] runs in constant time, while [`KeyedHeap::pop_min`],
+/// [`KeyedHeap::remove`] and [`KeyedHeap::change_priority`] run in amortized
+/// `O(log N)` time.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, KeyedHeap};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Node {
+///     A,
+///     B,
+///     C,
+/// }
+///
+/// let mut heap = KeyedHeap::new();
+/// heap.push(Node::A, 10);
+/// heap.push(Node::B, 5);
+/// heap.push(Node::C, 7);
+///
+/// heap.change_priority(Node::A, 1);
+///
+/// assert_eq!(heap.pop_min(), Some((Node::A, 1)));
+/// assert_eq!(heap.pop_min(), Some((Node::B, 5)));
+/// assert_eq!(heap.pop_min(), Some((Node::C, 7)));
+/// assert_eq!(heap.pop_min(), None);
+/// ```
+///
+/// Using a composite key:
+///
+/// ```
+/// use fixed_map::{Key, KeyedHeap};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Node {
+///     Start,
+///     Tile(bool),
+/// }
+///
+/// let mut heap = KeyedHeap::new();
+/// heap.push(Node::Tile(true), 3);
+/// heap.push(Node::Start, 4);
+/// heap.push(Node::Tile(false), 2);
+///
+/// assert_eq!(heap.peek_min(), Some((Node::Tile(false), &2)));
+/// assert_eq!(heap.remove(Node::Tile(false)), Some(2));
+/// assert_eq!(heap.pop_min(), Some((Node::Tile(true), 3)));
+/// ```
+pub struct KeyedHeap<K, P>
+where
+    K: Key,
+{
+    nodes: Map<K, Node<K, P>>,
+    root: Option<K>,
+}
+
+impl<K, P> KeyedHeap<K, P>
+where
+    K: Key,
+{
+    /// Creates an empty [`KeyedHeap`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, KeyedHeap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let heap: KeyedHeap<MyKey, u32> = KeyedHeap::new();
+    /// assert!(heap.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new() -> KeyedHeap<K, P> {
+        KeyedHeap {
+            nodes: Map::new(),
+            root: None,
+        }
+    }
+
+    /// Returns the number of keys in the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, KeyedHeap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut heap = KeyedHeap::new();
+    /// heap.push(MyKey::First, 1);
+    /// heap.push(MyKey::First, 2);
+    /// assert_eq!(heap.len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the heap contains no keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, KeyedHeap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut heap = KeyedHeap::new();
+    /// assert!(heap.is_empty());
+    /// heap.push(MyKey::First, 1);
+    /// assert!(!heap.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns `true` if the key is in the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, KeyedHeap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut heap = KeyedHeap::new();
+    /// heap.push(MyKey::First, 1);
+    /// assert!(heap.contains_key(MyKey::First));
+    /// assert!(!heap.contains_key(MyKey::Second));
+    /// ```
+    #[inline]
+    pub fn contains_key(&self, key: K) -> bool {
+        self.nodes.contains_key(key)
+    }
+
+    /// Returns the priority of the key, or `None` if it isn't in the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, KeyedHeap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut heap = KeyedHeap::new();
+    /// heap.push(MyKey::First, 1);
+    /// assert_eq!(heap.priority(MyKey::First), Some(&1));
+    /// assert_eq!(heap.priority(MyKey::Second), None);
+    /// ```
+    #[inline]
+    pub fn priority(&self, key: K) -> Option<&P> {
+        Some(&self.nodes.get(key)?.priority)
+    }
+
+    /// Returns the key with the lowest priority without removing it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, KeyedHeap};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut heap = KeyedHeap::new();
+    /// assert_eq!(heap.peek_min(), None);
+    /// heap.push(MyKey::First, 2);
+    /// heap.push(MyKey::Second, 1);
+    /// assert_eq!(heap.peek_min(), Some((MyKey::Second, &1)));
+    /// ```
+    #[inline]
+    pub fn peek_min(&self) -> Option<(K, &P)> {
+        let root = self.root?;
+        Some((root, &self.node(root).priority))
+    }
+
+    /// Removes every key from the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, KeyedHeap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut heap = KeyedHeap::new();
+    /// heap.push(MyKey::First, 1);
+    /// heap.clear();
+    /// assert!(heap.is_empty());
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.root = None;
+    }
+
+    /// Iterate over all keys in the heap and their priorities, in the order
+    /// of the keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, KeyedHeap};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut heap = KeyedHeap::new();
+    /// heap.push(MyKey::Second, 1);
+    /// heap.push(MyKey::First, 2);
+    /// assert!(heap.iter().eq([(MyKey::First, &2), (MyKey::Second, &1)]));
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, P> {
+        Iter {
+            iter: self.nodes.iter(),
+        }
+    }
+
+    #[inline]
+    fn node(&self, key: K) -> &Node<K, P> {
+        match self.nodes.get(key) {
+            Some(node) => node,
+            None => unreachable!("key linked in heap is missing"),
+        }
+    }
+
+    #[inline]
+    fn node_mut(&mut self, key: K) -> &mut Node<K, P> {
+        match self.nodes.get_mut(key) {
+            Some(node) => node,
+            None => unreachable!("key linked in heap is missing"),
+        }
+    }
+
+    /// Unlink the given key from its siblings and take its next sibling, making
+    /// it the root of its own heap.
+    fn take_next(&mut self, key: K) -> Option<K> {
+        let node = self.node_mut(key);
+        node.prev = None;
+        node.first = false;
+        node.next.take()
+    }
+
+    /// Cut the given key and its children out of the heap it belongs to.
+    fn cut(&mut self, key: K) {
+        let node = self.node_mut(key);
+        let next = node.next.take();
+        let first = mem::take(&mut node.first);
+
+        let Some(prev) = node.prev.take() else {
+            return;
+        };
+
+        if first {
+            self.node_mut(prev).child = next;
+        } else {
+            self.node_mut(prev).next = next;
+        }
+
+        if let Some(next) = next {
+            let next = self.node_mut(next);
+            next.prev = Some(prev);
+            next.first = first;
+        }
+    }
+}
+
+impl<K, P> KeyedHeap<K, P>
+where
+    K: Key,
+    P: Ord,
+{
+    /// Pushes a key with the given priority onto the heap.
+    ///
+    /// If the key is already in the heap its priority is changed like with
+    /// [`KeyedHeap::change_priority`] and the old priority is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, KeyedHeap};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut heap = KeyedHeap::new();
+    /// assert_eq!(heap.push(MyKey::First, 2), None);
+    /// assert_eq!(heap.push(MyKey::Second, 3), None);
+    /// assert_eq!(heap.push(MyKey::First, 4), Some(2));
+    /// assert_eq!(heap.pop_min(), Some((MyKey::Second, 3)));
+    /// ```
+    pub fn push(&mut self, key: K, priority: P) -> Option<P> {
+        if self.nodes.contains_key(key) {
+            return self.change_priority(key, priority);
+        }
+
+        self.nodes.insert(
+            key,
+            Node {
+                priority,
+                child: None,
+                next: None,
+                prev: None,
+                first: false,
+            },
+        );
+
+        self.root = Some(match self.root {
+            Some(root) => self.meld(root, key),
+            None => key,
+        });
+
+        None
+    }
+
+    /// Removes the key with the lowest priority from the heap and returns it
+    /// together with its priority.
+    ///
+    /// If several keys share the lowest priority, which one of them is
+    /// returned is unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, KeyedHeap};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut heap = KeyedHeap::new();
+    /// heap.push(MyKey::First, 2);
+    /// heap.push(MyKey::Second, 1);
+    ///
+    /// assert_eq!(heap.pop_min(), Some((MyKey::Second, 1)));
+    /// assert_eq!(heap.pop_min(), Some((MyKey::First, 2)));
+    /// assert_eq!(heap.pop_min(), None);
+    /// ```
+    pub fn pop_min(&mut self) -> Option<(K, P)> {
+        let root = self.root?;
+        let node = self.nodes.remove(root)?;
+        self.root = self.merge_pairs(node.child);
+        Some((root, node.priority))
+    }
+
+    /// Removes the key from the heap and returns its priority, or `None` if it
+    /// wasn't in the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, KeyedHeap};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    ///     Third,
+    /// }
+    ///
+    /// let mut heap = KeyedHeap::new();
+    /// heap.push(MyKey::First, 1);
+    /// heap.push(MyKey::Second, 2);
+    /// heap.push(MyKey::Third, 3);
+    ///
+    /// assert_eq!(heap.remove(MyKey::Second), Some(2));
+    /// assert_eq!(heap.remove(MyKey::Second), None);
+    /// assert_eq!(heap.pop_min(), Some((MyKey::First, 1)));
+    /// assert_eq!(heap.pop_min(), Some((MyKey::Third, 3)));
+    /// ```
+    pub fn remove(&mut self, key: K) -> Option<P> {
+        let node = self.nodes.get(key)?;
+
+        if node.prev.is_none() {
+            return self.pop_min().map(|(_, priority)| priority);
+        }
+
+        self.cut(key);
+        let node = self.nodes.remove(key)?;
+
+        if let (Some(root), Some(children)) = (self.root, self.merge_pairs(node.child)) {
+            self.root = Some(self.meld(root, children));
+        }
+
+        Some(node.priority)
+    }
+
+    /// Changes the priority of a key which is in the heap and returns its old
+    /// priority, or `None` without modifying the heap if it isn't in it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, KeyedHeap};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    ///     Third,
+    /// }
+    ///
+    /// let mut heap = KeyedHeap::new();
+    /// heap.push(MyKey::First, 1);
+    /// heap.push(MyKey::Second, 2);
+    ///
+    /// assert_eq!(heap.change_priority(MyKey::First, 3), Some(1));
+    /// assert_eq!(heap.change_priority(MyKey::Third, 0), None);
+    ///
+    /// assert_eq!(heap.pop_min(), Some((MyKey::Second, 2)));
+    /// assert_eq!(heap.pop_min(), Some((MyKey::First, 3)));
+    /// ```
+    pub fn change_priority(&mut self, key: K, priority: P) -> Option<P> {
+        let node = self.nodes.get_mut(key)?;
+        let ordering = priority.cmp(&node.priority);
+        let old = mem::replace(&mut node.priority, priority);
+        let is_root = node.prev.is_none();
+
+        match ordering {
+            Ordering::Equal => {}
+            Ordering::Less => {
+                if !is_root {
+                    self.cut(key);
+
+                    if let Some(root) = self.root {
+                        self.root = Some(self.meld(root, key));
+                    }
+                }
+            }
+            Ordering::Greater => {
+                let child = self.node_mut(key).child.take();
+                let children = self.merge_pairs(child);
+
+                let root = if is_root {
+                    children
+                } else {
+                    self.cut(key);
+
+                    match (self.root, children) {
+                        (Some(root), Some(children)) => Some(self.meld(root, children)),
+                        (root, _) => root,
+                    }
+                };
+
+                self.root = Some(match root {
+                    Some(root) => self.meld(root, key),
+                    None => key,
+                });
+            }
+        }
+
+        Some(old)
+    }
+
+    /// Meld two heaps, returning the key of the new root.
+    fn meld(&mut self, a: K, b: K) -> K {
+        let (parent, child) = if self.node(b).priority < self.node(a).priority {
+            (b, a)
+        } else {
+            (a, b)
+        };
+
+        let next = self.node(parent).child;
+
+        let node = self.node_mut(child);
+        node.prev = Some(parent);
+        node.next = next;
+        node.first = true;
+
+        if let Some(next) = next {
+            let next = self.node_mut(next);
+            next.prev = Some(child);
+            next.first = false;
+        }
+
+        self.node_mut(parent).child = Some(child);
+        parent
+    }
+
+    /// Combine a list of sibling heaps into one through the two-pass pairing
+    /// strategy, returning the key of the new root.
+    fn merge_pairs(&mut self, mut head: Option<K>) -> Option<K> {
+        // NB: Melded pairs are linked into a stack through their `next` field.
+        let mut stack = None;
+
+        while let Some(a) = head {
+            let melded = match self.take_next(a) {
+                Some(b) => {
+                    head = self.take_next(b);
+                    self.meld(a, b)
+                }
+                None => {
+                    head = None;
+                    a
+                }
+            };
+
+            self.node_mut(melded).next = stack;
+            stack = Some(melded);
+        }
+
+        let mut root = None;
+
+        while let Some(a) = stack {
+            stack = self.take_next(a);
+
+            root = Some(match root {
+                Some(root) => self.meld(root, a),
+                None => a,
+            });
+        }
+
+        root
+    }
+}
+
+impl<K, P> Default for KeyedHeap<K, P>
+where
+    K: Key,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, P> fmt::Debug for KeyedHeap<K, P>
+where
+    K: Key + fmt::Debug,
+    P: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the keys of a [`KeyedHeap`] and their priorities.
+///
+/// This is created by [`KeyedHeap::iter`].
+pub struct Iter<'a, K, P>
+where
+    K: 'a + Key,
+    P: 'a,
+{
+    iter: map::Iter<'a, K, Node<K, P>>,
+}
+
+impl<'a, K, P> Iterator for Iter<'a, K, P>
+where
+    K: 'a + Key,
+    P: 'a,
+{
+    type Item = (K, &'a P);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, node) = self.iter.next()?;
+        Some((key, &node.priority))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
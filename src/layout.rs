@@ -0,0 +1,312 @@
+//! Reports describing the storage layout picked for a key.
+//!
+//! The [`Key`] derive picks the storage of a [`Map`] based on the shape of
+//! the key, where unit variants are stored as slots in an array and variants
+//! holding other keys embed the storage of that key. [`report`] describes the
+//! result, so that the storage shape of composite keys can be checked in
+//! tests and documentation without reading generated code.
+//!
+//! [`Map`]: crate::Map
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use fixed_map::{layout, Key};
+//!
+//! #[derive(Clone, Copy, Key)]
+//! enum Part {
+//!     One,
+//!     Two,
+//! }
+//!
+//! #[derive(Clone, Copy, Key)]
+//! enum MyKey {
+//!     Simple,
+//!     Composite(Part),
+//! }
+//!
+//! let report = layout::report::<MyKey, u32>();
+//! assert_eq!(report.branches.len(), 2);
+//!
+//! let simple = report.branch("Simple").unwrap();
+//! assert_eq!(simple.size, core::mem::size_of::<Option<u32>>());
+//!
+//! let composite = report.branch("Composite").unwrap();
+//! assert_eq!(composite.size, layout::report::<Part, u32>().map.size);
+//! assert!(composite.offset.is_some());
+//! # }
+//! ```
+
+#[cfg(feature = "alloc")]
+use core::fmt;
+use core::mem;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use crate::map::MapStorage;
+#[cfg(feature = "alloc")]
+use crate::Key;
+
+/// A single branch in the storage of a [`Map`], as reported through
+/// [`MapStorage::layout`].
+///
+/// A branch is the field which holds the values of one variant of the key.
+///
+/// [`Map`]: crate::Map
+/// [`MapStorage::layout`]: crate::map::MapStorage::layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Branch {
+    /// The name of the variant stored in the branch.
+    pub name: &'static str,
+    /// The name of the type of the branch.
+    pub type_name: &'static str,
+    /// The size of the branch in bytes.
+    pub size: usize,
+    /// The alignment of the branch in bytes.
+    pub align: usize,
+    /// The offset of the branch from the start of the storage in bytes, if
+    /// it's known.
+    pub offset: Option<usize>,
+}
+
+impl Branch {
+    /// Describe a branch named `name` which is stored as a `T` at `offset`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::layout::Branch;
+    ///
+    /// let branch = Branch::new::<Option<u32>>("First", Some(0));
+    /// assert_eq!(branch.name, "First");
+    /// assert_eq!(branch.size, core::mem::size_of::<Option<u32>>());
+    /// assert_eq!(branch.offset, Some(0));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<T>(name: &'static str, offset: Option<usize>) -> Self {
+        Self {
+            name,
+            type_name: core::any::type_name::<T>(),
+            size: mem::size_of::<T>(),
+            align: mem::align_of::<T>(),
+            offset,
+        }
+    }
+}
+
+/// The size and alignment of a storage type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Shape {
+    /// The name of the type.
+    pub type_name: &'static str,
+    /// The size of the type in bytes.
+    pub size: usize,
+    /// The alignment of the type in bytes.
+    pub align: usize,
+}
+
+impl Shape {
+    /// Describe the shape of `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::layout::Shape;
+    ///
+    /// let shape = Shape::of::<u32>();
+    /// assert_eq!(shape.size, 4);
+    /// assert_eq!(shape.align, core::mem::align_of::<u32>());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn of<T>() -> Self {
+        Self {
+            type_name: core::any::type_name::<T>(),
+            size: mem::size_of::<T>(),
+            align: mem::align_of::<T>(),
+        }
+    }
+}
+
+/// A description of the storage picked for a [`Map`] and [`Set`], as returned
+/// by [`report`].
+///
+/// The [`Display`][fmt::Display] implementation renders the report as a
+/// table with one row for every branch.
+///
+/// [`Map`]: crate::Map
+/// [`Set`]: crate::Set
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Report {
+    /// The name of the key type.
+    pub key: &'static str,
+    /// The name of the value type.
+    pub value: &'static str,
+    /// The storage of a [`Map`][crate::Map] using the key.
+    pub map: Shape,
+    /// The storage of a [`Set`][crate::Set] using the key.
+    pub set: Shape,
+    /// The branches of the map storage in declaration order.
+    ///
+    /// This is empty for storage which doesn't describe its branches, such
+    /// as the storage of keys which aren't derived.
+    pub branches: Vec<Branch>,
+}
+
+#[cfg(feature = "alloc")]
+impl Report {
+    /// Look up the branch storing the variant `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{layout, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let report = layout::report::<MyKey, u8>();
+    /// assert_eq!(report.branch("Second").and_then(|b| b.offset), Some(2));
+    /// assert!(report.branch("Third").is_none());
+    /// ```
+    #[must_use]
+    pub fn branch(&self, name: &str) -> Option<&Branch> {
+        self.branches.iter().find(|branch| branch.name == name)
+    }
+
+    /// The number of bytes in the map storage which aren't covered by any
+    /// branch, such as padding added for alignment.
+    ///
+    /// This is `0` if the storage has no branches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{layout, Key};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// #[key(align = 64)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let report = layout::report::<MyKey, u8>();
+    /// assert_eq!(report.padding(), 60);
+    /// ```
+    #[must_use]
+    pub fn padding(&self) -> usize {
+        if self.branches.is_empty() {
+            return 0;
+        }
+
+        let used = self
+            .branches
+            .iter()
+            .map(|branch| branch.size)
+            .sum::<usize>();
+        self.map.size.saturating_sub(used)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Map<{}, {}>: size {}, align {}",
+            self.key, self.value, self.map.size, self.map.align
+        )?;
+        writeln!(
+            f,
+            "Set<{}>: size {}, align {}",
+            self.key, self.set.size, self.set.align
+        )?;
+
+        let width = self
+            .branches
+            .iter()
+            .map(|branch| branch.name.len())
+            .max()
+            .unwrap_or_default();
+
+        for branch in &self.branches {
+            write!(f, "  {:width$}  ", branch.name)?;
+
+            match branch.offset {
+                Some(offset) => write!(f, "offset {offset:>4}")?,
+                None => write!(f, "offset    ?")?,
+            }
+
+            writeln!(
+                f,
+                "  size {:>4}  align {:>3}  {}",
+                branch.size, branch.align, branch.type_name
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Describe the storage picked for a [`Map`] with keys of type `K` and values
+/// of type `V`, and for a [`Set`] of `K`.
+///
+/// Branches are reported by the storage generated through the [`Key`]
+/// derive, and by the storage of `bool` and `Option<K>`.
+///
+/// [`Map`]: crate::Map
+/// [`Set`]: crate::Set
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{layout, Key};
+///
+/// #[derive(Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second(bool),
+///     Third(Option<bool>),
+/// }
+///
+/// let report = layout::report::<MyKey, u16>();
+///
+/// let names = report.branches.iter().map(|b| b.name).collect::<Vec<_>>();
+/// assert_eq!(names, ["First", "Second", "Third"]);
+///
+/// for branch in &report.branches {
+///     let offset = branch.offset.unwrap();
+///     assert!(offset + branch.size <= report.map.size);
+/// }
+///
+/// println!("{report}");
+/// ```
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn report<K, V>() -> Report
+where
+    K: Key,
+{
+    let mut branches = Vec::new();
+    <K::MapStorage<V> as MapStorage<K, V>>::layout(|branch| branches.push(branch));
+
+    Report {
+        key: core::any::type_name::<K>(),
+        value: core::any::type_name::<V>(),
+        map: Shape::of::<K::MapStorage<V>>(),
+        set: Shape::of::<K::SetStorage>(),
+        branches,
+    }
+}
@@ -62,14 +62,29 @@
 //!
 //! The following features are available:
 //!
+//! * `alloc` - Enables functionality which requires allocation, like
+//!   [`Map::iter_dyn`] and [`PersistentMap`].
 //! * `std` - Disabling this feature causes this crate to be no-std. This means
 //!   that dynamic types cannot be used in keys, like ones enabled by the `map`
 //!   feature (default).
 //! * `hashbrown` - Causes [`Storage`] to be implemented by dynamic types such
 //!   as `&'static str` or `u32`. These are backed by a `hashbrown` (default).
+//! * `std-collections` - Backs storage for dynamic types with
+//!   [`std::collections::HashMap`] instead of `hashbrown`, for dependents which
+//!   want to avoid pulling in `hashbrown`. This takes precedence over the
+//!   `hashbrown` feature if both are enabled.
 //! * `entry` - Enables an [`entry`] API similar to that found on [`HashMap`].
 //! * `serde` - Causes [`Map`] and [`Set`] to implement [`Serialize`] and
 //!   [`Deserialize`] if it's implemented by the key and value.
+//! * `test-utils` - Enables the [`test_utils`] module, with functions that
+//!   downstream crates can use to check the contract of their keys.
+//! * `rayon` - Enables [`Map::par_values_mut`] to process values in parallel
+//!   using [`rayon`], and collecting a [`Map`] from a parallel iterator.
+//! * `metrics` - Enables the [`metrics`] module, which reports map and set
+//!   operations to a pluggable recorder.
+//! * `zeroize` - Causes [`Map`] and [`Set`] to implement [`Zeroize`], and
+//!   [`Map`] to implement [`ZeroizeOnDrop`] if its values do. Only values are
+//!   scrubbed, keys are not.
 //!
 //! <br>
 //!
@@ -274,11 +289,20 @@
 //! [`Key` derive]: https://docs.rs/fixed-map/latest/fixed_map/derive.Key.html
 //! [`Key`]: https://docs.rs/fixed-map/latest/fixed_map/derive.Key.html
 //! [`Map`]: https://docs.rs/fixed-map/latest/fixed_map/map/struct.Map.html
+//! [`Map::iter_dyn`]: https://docs.rs/fixed-map/latest/fixed_map/map/struct.Map.html#method.iter_dyn
+//! [`Map::par_values_mut`]: https://docs.rs/fixed-map/latest/fixed_map/map/struct.Map.html#method.par_values_mut
+//! [`metrics`]: https://docs.rs/fixed-map/latest/fixed_map/metrics/index.html
+//! [`PersistentMap`]: https://docs.rs/fixed-map/latest/fixed_map/persistent_map/struct.PersistentMap.html
 //! [`entry`]: https://docs.rs/fixed-map/latest/fixed_map/map/struct.Map.html#method.entry
 //! [`HashMap`]: https://doc.rust-lang.org/stable/std/collections/hash_map/struct.HashMap.html#method.entry
 //! [`Serialize`]: https://docs.rs/serde/1/serde/trait.Serialize.html
+//! [`rayon`]: https://docs.rs/rayon
+//! [`Zeroize`]: https://docs.rs/zeroize/1/zeroize/trait.Zeroize.html
+//! [`ZeroizeOnDrop`]: https://docs.rs/zeroize/1/zeroize/trait.ZeroizeOnDrop.html
 //! [`Set`]: https://docs.rs/fixed-map/latest/fixed_map/set/struct.Set.html
 //! [`Storage`]: https://docs.rs/fixed-map/latest/fixed_map/storage/trait.Storage.html
+//! [`std::collections::HashMap`]: https://doc.rust-lang.org/stable/std/collections/struct.HashMap.html
+//! [`test_utils`]: https://docs.rs/fixed-map/latest/fixed_map/test_utils/index.html
 //! [documentation]: https://docs.rs/fixed-map
 
 #![no_std]
@@ -287,10 +311,24 @@
 #![allow(clippy::module_name_repetitions)]
 #![allow(clippy::type_repetition_in_bounds)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
 pub mod raw;
 
+mod debug;
+
 mod key;
-pub use self::key::Key;
+#[cfg(feature = "serde")]
+pub use self::key::SerdeKey;
+pub use self::key::{CloneableKey, CopyKey, HashableKey, Key, KeyGroup};
+
+pub mod key_iter;
+
+mod key_table;
 
 pub mod map;
 #[doc(inline)]
@@ -300,6 +338,54 @@ pub mod set;
 #[doc(inline)]
 pub use self::set::Set;
 
+pub mod cell_map;
+#[doc(inline)]
+pub use self::cell_map::CellMap;
+
+pub mod total_map;
+#[doc(inline)]
+pub use self::total_map::TotalMap;
+
+pub mod profile;
+
+pub mod layout;
+
+pub mod schema;
+
+pub mod migrate;
+
+pub mod any_value_map;
+#[doc(inline)]
+pub use self::any_value_map::AnyValueMap;
+
+pub mod bi_map;
+#[doc(inline)]
+pub use self::bi_map::BiMap;
+
+pub mod history_map;
+#[doc(inline)]
+pub use self::history_map::HistoryMap;
+
+pub mod keyed_heap;
+#[doc(inline)]
+pub use self::keyed_heap::KeyedHeap;
+
+pub mod versioned_map;
+#[doc(inline)]
+pub use self::versioned_map::VersionedMap;
+
+#[cfg(feature = "alloc")]
+pub mod persistent_map;
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use self::persistent_map::PersistentMap;
+
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
 // Re-export the option bucket types for use in `derive(Key)`
 #[doc(hidden)]
 pub mod option_bucket;
@@ -358,11 +444,839 @@ pub mod macro_support;
 /// assert_eq!(core::mem::size_of::<Set<Bits>>(), 1);
 /// ```
 ///
+/// The position of each variant in the raw value of the set is stable and
+/// follows declaration order, and can be accessed through [`raw::BitIndex`].
+///
 /// > **Note:** not all operations will be implemented when this attribute is
 /// > present, so some container methods might not work.
 ///
 /// <br>
 ///
+/// #### `#[key(transparent)]`
+///
+/// This allows the derive to be used on a newtype struct with a single unnamed
+/// field, in which case storage is delegated to the storage of the wrapped
+/// key.
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// pub enum Color {
+///     Red,
+///     Green,
+/// }
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(transparent)]
+/// pub struct Background(Color);
+///
+/// let mut map = Map::new();
+/// map.insert(Background(Color::Red), 1);
+///
+/// assert_eq!(map.get(Background(Color::Red)), Some(&1));
+/// assert!(map.keys().eq([Background(Color::Red)]));
+///
+/// // Storage is the same as for the wrapped key.
+/// assert_eq!(
+///     core::mem::size_of::<Map<Background, u32>>(),
+///     core::mem::size_of::<Map<Color, u32>>()
+/// );
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(accessors)]`
+///
+/// This generates an extension trait named `<Enum>MapExt` for [`Map`], which
+/// has a getter, a mutable getter and a setter for every variant named after
+/// it in snake case. The field of a variant is taken as an argument.
+///
+/// Note that inherent methods of [`Map`] take precedence, so a variant named
+/// for example `Get` will need its accessor called through the trait.
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Clone, Copy, Key)]
+/// #[key(accessors)]
+/// pub enum Dir {
+///     North,
+///     South,
+///     Other(bool),
+/// }
+///
+/// let mut map = Map::new();
+/// assert_eq!(map.set_north(1), None);
+/// assert_eq!(map.set_other(true, 2), None);
+///
+/// if let Some(value) = map.north_mut() {
+///     *value += 10;
+/// }
+///
+/// assert_eq!(map.north(), Some(&11));
+/// assert_eq!(map.south(), None);
+/// assert_eq!(map.other(true), Some(&2));
+/// assert_eq!(map.other(false), None);
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(bit_consts)]`
+///
+/// Used together with `#[key(bitset)]`, this generates a module named
+/// `<enum>_bits` in snake case with a `pub const` mask for every variant, as it
+/// appears in the raw value of a [`Set`]. The constants are named
+/// `<ENUM>_<VARIANT>_BIT` so that they can be exported as is to C headers with
+/// tools such as [cbindgen], keeping both sides in sync.
+///
+/// [cbindgen]: https://github.com/mozilla/cbindgen
+///
+/// ```
+/// use fixed_map::{Key, Set};
+///
+/// #[derive(Clone, Copy, Key)]
+/// #[key(bitset, bit_consts)]
+/// pub enum Direction {
+///     North,
+///     East,
+///     South,
+///     West,
+/// }
+///
+/// assert_eq!(direction_bits::DIRECTION_NORTH_BIT, 0b0001);
+/// assert_eq!(direction_bits::DIRECTION_WEST_BIT, 0b1000);
+///
+/// let set = Set::from([Direction::North, Direction::South]);
+/// assert_eq!(set.as_raw(), direction_bits::DIRECTION_NORTH_BIT | direction_bits::DIRECTION_SOUTH_BIT);
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(bitflags = T)]`
+///
+/// Used together with `#[key(bitset)]`, this generates `From<Set<Key>> for T`
+/// and `From<T> for Set<Key>`, where `T` is a type declared through the
+/// [bitflags] crate. This allows sets to be passed to and from APIs which use
+/// flags without shifting bits by hand.
+///
+/// `T` must have a flag for every variant, named after it in screaming snake
+/// case, which is the same bit as the variant has in the raw value of a
+/// [`Set`]. This is checked at compile time. Flags of `T` which don't
+/// correspond to a variant are dropped when converting into a set.
+///
+/// [bitflags]: https://docs.rs/bitflags
+///
+/// ```
+/// use bitflags::bitflags;
+/// use fixed_map::{Key, Set};
+///
+/// bitflags! {
+///     pub struct Permissions: u32 {
+///         const READ = 0b001;
+///         const WRITE = 0b010;
+///         const EXECUTE = 0b100;
+///     }
+/// }
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(bitset, bitflags = Permissions)]
+/// pub enum Permission {
+///     Read,
+///     Write,
+///     Execute,
+/// }
+///
+/// let set = Set::from([Permission::Read, Permission::Execute]);
+/// let flags = Permissions::from(set);
+/// assert_eq!(flags, Permissions::READ | Permissions::EXECUTE);
+///
+/// let set = Set::from(Permissions::WRITE);
+/// assert!(set.iter().eq([Permission::Write]));
+/// ```
+///
+/// Flags which don't line up with the variants are rejected:
+///
+/// ```compile_fail
+/// use bitflags::bitflags;
+/// use fixed_map::Key;
+///
+/// bitflags! {
+///     pub struct Permissions: u32 {
+///         const READ = 0b010;
+///         const WRITE = 0b001;
+///     }
+/// }
+///
+/// #[derive(Clone, Copy, Key)]
+/// #[key(bitset, bitflags = Permissions)]
+/// pub enum Permission {
+///     Read,
+///     Write,
+/// }
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(groups)]`
+///
+/// Generates an enum named `<enum>Group` with a unit variant for every variant
+/// of the key, which implements [`KeyGroup`] and can be converted from the key.
+/// This selects the storage of a single variant for operations such as
+/// [`Map::len_of`], [`Map::clear_of`] and [`Set::contains_any_of`], which only
+/// visit the storage of that variant.
+///
+/// ```
+/// use fixed_map::{Key, Map, Set};
+///
+/// #[derive(Clone, Copy, Key)]
+/// #[key(groups)]
+/// enum MyKey {
+///     First,
+///     Second(bool),
+/// }
+///
+/// let map = Map::from_iter([(MyKey::Second(true), 1), (MyKey::Second(false), 2)]);
+/// assert_eq!(map.len_of(MyKeyGroup::First), 0);
+/// assert_eq!(map.len_of(MyKeyGroup::Second), 2);
+///
+/// let set = Set::from([MyKey::First]);
+/// assert!(set.contains_any_of(MyKeyGroup::from(MyKey::First)));
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(iter)]`
+///
+/// Implements [`FiniteKey`][key_iter::FiniteKey], so that every key can be
+/// enumerated through [`FiniteKey::all`][key_iter::FiniteKey::all] in the
+/// order a [`Map`] visits them, without constructing one. Variants holding a
+/// field are expanded into every key of the field, so the field has to
+/// implement [`FiniteKey`][key_iter::FiniteKey] as well or declare its values
+/// through [`#[key(values(...))]`](#keyvalues).
+///
+/// ```
+/// use fixed_map::key_iter::FiniteKey;
+/// use fixed_map::Key;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(iter)]
+/// enum MyKey {
+///     First,
+///     Second(bool),
+///     #[key(values(1, 2))]
+///     Third(u8),
+/// }
+///
+/// let keys = MyKey::all();
+/// assert_eq!(keys.len(), 5);
+/// assert!(keys.rev().eq([
+///     MyKey::Third(2),
+///     MyKey::Third(1),
+///     MyKey::Second(false),
+///     MyKey::Second(true),
+///     MyKey::First,
+/// ]));
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(layout_test)]`
+///
+/// Generates a `#[cfg(test)]` module with a unit test asserting that the
+/// storage of every unit variant is laid out in declaration order, see
+/// [Storage layout](#storage-layout). This guards against refactors silently
+/// changing the iteration order, [`Ord`], [`Hash`] or serialized form of maps
+/// and sets using the key.
+///
+/// [`Hash`]: core::hash::Hash
+///
+/// ```
+/// use fixed_map::Key;
+///
+/// #[derive(Clone, Copy, Key)]
+/// #[key(layout_test)]
+/// pub enum MyKey {
+///     First,
+///     Second,
+/// }
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(align = N)]`
+///
+/// Aligns the generated map and set storage to `N` bytes, which has to be a
+/// power of two. This in turn pads a [`Map`] or [`Set`] using the key to a
+/// multiple of `N` bytes, so maps which are sharded across threads in an array
+/// can be kept on separate cache lines to avoid false sharing.
+///
+/// ```
+/// use core::mem::align_of;
+///
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Clone, Copy, Key)]
+/// #[key(align = 64)]
+/// pub enum Counter {
+///     Hits,
+///     Misses,
+/// }
+///
+/// assert_eq!(align_of::<Map<Counter, u64>>(), 64);
+/// let shards: [Map<Counter, u64>; 4] = Default::default();
+/// assert_eq!(core::mem::size_of_val(&shards), 4 * 64);
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(maps_to(Type, ...))]`
+///
+/// Declares that the key converts into each listed key without mapping two
+/// keys onto the same key, by implementing [`migrate::MapsTo`]. Each listed key
+/// must implement `From` for this key. This makes [`Map::map_keys_into`]
+/// available to move maps over to the other key.
+///
+/// ```
+/// # #[cfg(feature = "hashbrown")] {
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(maps_to(u32))]
+/// pub enum Level {
+///     Low,
+///     High,
+/// }
+///
+/// impl From<Level> for u32 {
+///     fn from(level: Level) -> u32 {
+///         match level {
+///             Level::Low => 1,
+///             Level::High => 10,
+///         }
+///     }
+/// }
+///
+/// let map = Map::from_iter([(Level::Low, "low"), (Level::High, "high")]);
+/// let map: Map<u32, _> = map.map_keys_into();
+///
+/// assert_eq!(map.get(10), Some(&"high"));
+/// # }
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(primitive = T)]`
+///
+/// Generates `From<Key> for T` and `TryFrom<T> for Key` for an enum with unit
+/// variants, where the value of every variant is its index in declaration
+/// order. This matches the order variants are stored in and the bit index
+/// used by `#[key(bitset)]`, see [`raw::BitIndex`]. Values which don't
+/// correspond to a variant are rejected with [`raw::InvalidPrimitive`].
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(primitive = u8)]
+/// pub enum Level {
+///     Low,
+///     Medium,
+///     High,
+/// }
+///
+/// assert_eq!(u8::from(Level::High), 2);
+/// assert_eq!(Level::try_from(1), Ok(Level::Medium));
+/// assert!(Level::try_from(3).is_err());
+///
+/// let map = Map::from_iter([(Level::Medium, "medium"), (Level::High, "high")]);
+/// assert!(map.keys().map(u8::from).eq([1, 2]));
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(sparse)]`
+///
+/// Stores maps and sets of an enum with unit variants in
+/// [`SparseMapStorage`] and [`SparseSetStorage`] instead of an array with one
+/// slot per variant. Only the entries which are present are stored, as a
+/// sorted table of their [`raw::SparseIndex`] next to their values, and
+/// lookups are binary searches.
+///
+/// This is intended for enums with a very large number of variants, such as
+/// ones generated from an IDL, where most maps only hold a few of them. The
+/// derive implements [`raw::SparseIndex`] for the enum, which limits it to
+/// 65536 variants. It requires the `alloc` feature and can't be combined with
+/// `#[key(bitset)]` or `#[key(align = N)]`.
+///
+/// [`SparseMapStorage`]: crate::map::storage::SparseMapStorage
+/// [`SparseSetStorage`]: crate::set::storage::SparseSetStorage
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(sparse)]
+/// pub enum Message {
+///     Ping,
+///     Pong,
+///     Open,
+///     Close,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(Message::Close, 2);
+/// map.insert(Message::Ping, 1);
+///
+/// assert!(map.keys().eq([Message::Ping, Message::Close]));
+/// assert_eq!(map.stats().len, 2);
+/// assert_eq!(map.stats().capacity, 0);
+/// # }
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(masked)]`
+///
+/// Stores maps of an enum with unit variants in [`MaskedMapStorage`], which
+/// keeps track of which entries are present in a bit mask instead of storing an
+/// `Option<V>` for every variant. Together with `#[key(bitset)]` this means
+/// that a `Map<K, ()>` takes up as little space as a `Set<K>`.
+///
+/// This is opt-in rather than picked automatically for zero-sized values,
+/// since the storage of a key is chosen once for every value type and stable
+/// Rust can't specialize it for some of them. Compared to the default storage
+/// of an enum with unit variants, maps using this storage:
+///
+/// * Are never [`Copy`], since values might be uninitialized and have to be
+///   dropped by the storage.
+/// * Don't implement [`SliceStorage`], since values aren't stored as a slice of
+///   `Option<V>`. So [`Map::range`], [`Map::insert_for_range`] and
+///   `Map::par_values_mut` are not available.
+///
+/// It can't be combined with `#[key(sparse)]` or `#[key(align = N)]`.
+///
+/// [`MaskedMapStorage`]: crate::map::storage::MaskedMapStorage
+/// [`SliceStorage`]: crate::map::SliceStorage
+/// [`Map::range`]: crate::Map::range
+/// [`Map::insert_for_range`]: crate::Map::insert_for_range
+///
+/// ```
+/// use core::mem::size_of;
+///
+/// use fixed_map::{Key, Map, Set};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(bitset, masked)]
+/// pub enum Permission {
+///     Read,
+///     Write,
+///     Execute,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(Permission::Execute, ());
+/// map.insert(Permission::Read, ());
+///
+/// assert!(map.keys().eq([Permission::Read, Permission::Execute]));
+/// assert_eq!(size_of::<Map<Permission, ()>>(), size_of::<Set<Permission>>());
+/// assert_eq!(size_of::<Map<Permission, ()>>(), 1);
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(variants(...))]`
+///
+/// Declares the order of every variant of the key, including variants which
+/// are disabled through `#[cfg(...)]`. Disabled variants are listed as reserved
+/// slots in [`Key::KEY_VARIANTS`], so that [`schema::slot`] and
+/// [`schema::layout_hash`] stay the same regardless of which features are
+/// enabled. Every variant of the enum must be listed.
+///
+/// ```
+/// use fixed_map::{schema, Key};
+///
+/// #[derive(Clone, Copy, Key)]
+/// #[key(variants(First, Second, Third))]
+/// pub enum MyKey {
+///     First,
+///     #[cfg(feature = "does-not-exist")]
+///     Second,
+///     Third,
+/// }
+///
+/// assert_eq!(schema::slot(MyKey::Third), Some(2));
+/// assert!(!MyKey::KEY_VARIANTS[1].is_enabled());
+/// ```
+///
+/// <br>
+///
+/// ## Variant attributes
+///
+/// <br>
+///
+/// #### `#[cfg(...)]`
+///
+/// Variants can be conditionally compiled. Disabled variants are removed
+/// before the key is derived, so storage is only laid out for the variants
+/// which are enabled and its size depends on the active configuration. To keep
+/// disabled variants as reserved slots in the schema of the key, see
+/// [`#[key(variants(...))]`](#keyvariants).
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// pub enum MyKey {
+///     First,
+///     #[cfg(feature = "does-not-exist")]
+///     Second,
+///     Third,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(MyKey::First, 1);
+/// map.insert(MyKey::Third, 3);
+///
+/// assert!(map.keys().eq([MyKey::First, MyKey::Third]));
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(value = "Type")]`
+///
+/// Associates a distinct value type with each variant, which makes the key
+/// usable in an [`AnyValueMap`]. If used, it has to be specified on every
+/// variant and it's only supported on enums where every variant is a unit
+/// variant.
+///
+/// This also generates a module named after the enum in snake case, which
+/// contains one marker type per variant used to access its value. The module
+/// can be given another name through `#[key(value_module = name)]`, which is
+/// needed if an item with the default name already exists.
+///
+/// ```
+/// use fixed_map::{AnyValueMap, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// pub enum Setting {
+///     #[key(value = "u32")]
+///     Width,
+///     #[key(value = "&'static str")]
+///     Title,
+/// }
+///
+/// let mut map = AnyValueMap::new();
+/// map.insert::<setting::Width>(800);
+/// map.insert::<setting::Title>("Hello");
+///
+/// assert_eq!(map.get::<setting::Width>(), Some(&800));
+/// assert_eq!(map.get::<setting::Title>(), Some(&"Hello"));
+/// ```
+///
+/// The generated module clashes with an existing item of the same name:
+///
+/// ```compile_fail
+/// use fixed_map::Key;
+///
+/// mod direction {}
+///
+/// #[derive(Clone, Copy, Key)]
+/// enum Direction {
+///     #[key(value = "u32")]
+///     North,
+///     #[key(value = "u32")]
+///     South,
+/// }
+/// ```
+///
+/// Which is avoided by naming it:
+///
+/// ```
+/// use fixed_map::{AnyValueMap, Key};
+///
+/// mod direction {}
+///
+/// #[derive(Clone, Copy, Key)]
+/// #[key(value_module = direction_value)]
+/// enum Direction {
+///     #[key(value = "u32")]
+///     North,
+///     #[key(value = "u32")]
+///     South,
+/// }
+///
+/// let mut map = AnyValueMap::new();
+/// map.insert::<direction_value::North>(10);
+/// assert_eq!(map.get::<direction_value::North>(), Some(&10));
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(values(...))]`
+///
+/// Declares the only values the field of a variant can take. The field is
+/// then stored densely with one slot per declared value, in declaration order,
+/// so its type doesn't have to implement [`Key`] and no `hashbrown` storage is
+/// needed for types like `u8`. Each value has to be a constant expression, and
+/// the field type must be [`Copy`] and [`PartialEq`].
+///
+/// Lookups of undeclared values miss, while inserting one panics.
+///
+/// If every value is a string literal the field can be a `&'static str`. A
+/// lookup then dispatches on the length of the string and a byte position
+/// which tells the declared values apart, so that at most one string
+/// comparison is performed.
+///
+/// ```
+/// use fixed_map::{Key, Set};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// pub enum Header {
+///     #[key(values("accept", "host", "user-agent"))]
+///     Known(&'static str),
+/// }
+///
+/// let mut set = Set::new();
+/// set.insert(Header::Known("host"));
+///
+/// assert!(set.contains(Header::Known("host")));
+/// assert!(!set.contains(Header::Known("hose")));
+/// ```
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// pub enum Setting {
+///     #[key(values(1, 2, 4, 8))]
+///     Mode(u8),
+///     Off,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(Setting::Mode(8), "eight");
+/// map.insert(Setting::Mode(1), "one");
+///
+/// assert_eq!(map.get(Setting::Mode(8)), Some(&"eight"));
+/// assert_eq!(map.get(Setting::Mode(3)), None);
+/// assert!(map.keys().eq([Setting::Mode(1), Setting::Mode(8)]));
+/// ```
+///
+/// The attribute is only supported on a variant with a single field:
+///
+/// ```compile_fail
+/// use fixed_map::Key;
+///
+/// #[derive(Clone, Copy, Key)]
+/// pub enum Setting {
+///     #[key(values(1, 2))]
+///     Off,
+///     Enabled(bool),
+/// }
+/// ```
+///
+/// And it can't be combined with `#[key(lazy)]` or `#[key(dynamic =
+/// "ordered")]`, since the declared values already decide the storage:
+///
+/// ```compile_fail
+/// use fixed_map::Key;
+///
+/// #[derive(Clone, Copy, Key)]
+/// pub enum Setting {
+///     #[key(lazy, values(1, 2))]
+///     Mode(u8),
+/// }
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(lazy)]`
+///
+/// Stores the map storage of the field of a variant behind a pointer which is
+/// only allocated once a value is inserted into it, through
+/// [`LazyMapStorage`][crate::map::storage::LazyMapStorage]. This cuts the
+/// baseline size of a map whose variants are mostly left unused, such as keys
+/// with many `u32` or nested enum branches. Sets are unaffected.
+///
+/// This requires the `alloc` feature.
+///
+/// ```
+/// # #[cfg(all(feature = "hashbrown", feature = "alloc"))] {
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// pub enum Route {
+///     #[key(lazy)]
+///     Channel(u32),
+///     #[key(lazy)]
+///     Peer(u64),
+///     Local,
+/// }
+///
+/// let mut map = Map::new();
+/// assert!(map.is_empty());
+///
+/// map.insert(Route::Channel(4), "four");
+///
+/// assert_eq!(map.get(Route::Channel(4)), Some(&"four"));
+/// assert_eq!(map.get(Route::Peer(4)), None);
+/// assert!(map.keys().eq([Route::Channel(4)]));
+/// # }
+/// ```
+///
+/// The attribute is only supported on a variant with a single field:
+///
+/// ```compile_fail
+/// use fixed_map::Key;
+///
+/// #[derive(Clone, Copy, Key)]
+/// pub enum Route {
+///     #[key(lazy)]
+///     Local,
+///     Enabled(bool),
+/// }
+/// ```
+///
+/// And it can't be combined with `#[key(dynamic = "ordered")]`:
+///
+/// ```compile_fail
+/// use fixed_map::Key;
+///
+/// #[derive(Clone, Copy, Key)]
+/// pub enum Route {
+///     #[key(lazy, dynamic = "ordered")]
+///     Channel(u32),
+/// }
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(dynamic = "ordered")]`
+///
+/// Stores the field of a variant holding a dynamic type such as
+/// `&'static str` or `u32` in a
+/// [`OrderedMapStorage`][crate::map::storage::OrderedMapStorage] or
+/// [`OrderedSetStorage`][crate::set::storage::OrderedSetStorage], which are
+/// backed by a `BTreeMap` and a `BTreeSet` instead of a hash map. Entries of
+/// the variant are then visited in the order of their fields, so iteration,
+/// `Debug` output and serialization don't change between runs. This is useful
+/// for snapshot tests and reproducible builds.
+///
+/// The field must implement `Ord`, and this requires the `alloc` feature.
+///
+/// ```
+/// # #[cfg(all(feature = "hashbrown", feature = "alloc"))] {
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// pub enum Header {
+///     #[key(dynamic = "ordered")]
+///     Custom(&'static str),
+///     Host,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(Header::Custom("x-trace"), 1);
+/// map.insert(Header::Host, 2);
+/// map.insert(Header::Custom("accept"), 3);
+///
+/// assert_eq!(
+///     format!("{map:?}"),
+///     r#"{Custom("accept"): 3, Custom("x-trace"): 1, Host: 2}"#
+/// );
+/// # }
+/// ```
+///
+/// <br>
+///
+/// #### `#[key(optional)]`
+///
+/// Only stores a variant holding a dynamic type such as `u32` or
+/// `&'static str` if the `hashbrown` or `std-collections` feature of fixed-map
+/// is enabled, instead of failing to compile without them. This allows a
+/// single key to be used by both `std` and `no_std` builds, where the variant
+/// is compiled out of storage through
+/// [`UnavailableMapStorage`][crate::map::storage::UnavailableMapStorage] and
+/// [`UnavailableSetStorage`][crate::set::storage::UnavailableSetStorage] in
+/// the latter. Looking up the variant then finds nothing, while inserting it
+/// panics.
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// pub enum Metric {
+///     Uptime,
+///     #[key(optional)]
+///     Custom(&'static str),
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(Metric::Uptime, 10);
+/// assert_eq!(map.get(Metric::Custom("requests")), None);
+/// ```
+///
+/// Without `#[key(optional)]` and the features, the derive fails with an error
+/// saying that `&'static str` can only be used in a key if the `hashbrown` or
+/// `std-collections` feature of fixed-map is enabled:
+///
+#[cfg_attr(any(feature = "hashbrown", feature = "std-collections"), doc = "```")]
+#[cfg_attr(
+    not(any(feature = "hashbrown", feature = "std-collections")),
+    doc = "```compile_fail"
+)]
+/// use fixed_map::Key;
+///
+/// #[derive(Clone, Copy, Key)]
+/// pub enum Metric {
+///     Uptime,
+///     Custom(&'static str),
+/// }
+/// ```
+///
+/// The attribute is only supported on a variant with a single field:
+///
+/// ```compile_fail
+/// use fixed_map::Key;
+///
+/// #[derive(Clone, Copy, Key)]
+/// pub enum Metric {
+///     #[key(optional)]
+///     Uptime,
+///     Enabled(bool),
+/// }
+/// ```
+///
+/// The field has to be of a dynamic type, since other fields are always
+/// stored:
+///
+/// ```compile_fail
+/// use fixed_map::Key;
+///
+/// #[derive(Clone, Copy, Key)]
+/// pub enum Metric {
+///     #[key(optional)]
+///     Enabled(bool),
+/// }
+/// ```
+///
+/// And it can't be combined with `#[key(lazy)]`, `#[key(dynamic =
+/// "ordered")]` or `#[key(values(...))]`:
+///
+/// ```compile_fail
+/// use fixed_map::Key;
+///
+/// #[derive(Clone, Copy, Key)]
+/// pub enum Metric {
+///     #[key(lazy, optional)]
+///     Custom(u32),
+/// }
+/// ```
+///
+/// <br>
+///
 /// ## Guide
 ///
 /// Given the following enum:
@@ -455,7 +1369,42 @@ pub mod macro_support;
 /// impl ::fixed_map::Key for MyKey {
 ///     type MapStorage<V> = MyKeyMapStorage<V>;
 ///     type SetStorage = MyKeySetStorage;
+///     const KEY_SCHEMA_HASH: u64 = {
+///         let hasher = ::fixed_map::schema::Hasher::new();
+///         let hasher = hasher.write_str("First");
+///         let hasher = hasher.write_str("Second");
+///         let hasher = hasher.write_str("Third");
+///         hasher.finish()
+///     };
+/// }
+/// ```
+///
+/// The same traits can be implemented by hand to use custom storage for a key
+/// which can't be derived, see the [`map::storage`] module for an example.
+///
+/// <br>
+///
+/// ## Storage layout
+///
+/// The fields of generated storage follow the order in which variants are
+/// declared, and the storage of nested keys follows the order of their own
+/// variants. Since iteration visits storage field by field, this is the order
+/// of [`Map::iter`] and [`Set::iter`], which in turn determines how maps and
+/// sets are ordered through [`Ord`], hashed and serialized. Reordering
+/// variants is therefore a breaking change for persisted data, which
+/// [`Key::KEY_SCHEMA_HASH`] can be used to detect.
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// pub enum MyKey {
+///     Second,
+///     First,
 /// }
+///
+/// let map = Map::from_iter([(MyKey::First, 1), (MyKey::Second, 2)]);
+/// assert!(map.keys().eq([MyKey::Second, MyKey::First]));
 /// ```
 #[doc(inline)]
 pub use fixed_map_derive::Key;
@@ -7,6 +7,9 @@
 #![allow(clippy::missing_inline_in_public_items)]
 
 use core::cmp::Ordering;
+use core::mem;
+
+use crate::map::{ExtractIfCursor, MapStorage};
 
 #[inline]
 fn flatten<T>(value: (usize, &Option<T>)) -> Option<(usize, &T)> {
@@ -70,3 +73,492 @@ where
     let b = b.into_iter().enumerate().filter(filter_bool);
     a.cmp(b)
 }
+
+/// `get_disjoint_mut` implementation for storage which is laid out as an array
+/// with one slot per key, where `index` returns the slot of a key.
+#[inline]
+pub fn __storage_get_disjoint_mut<'a, K, V>(
+    slots: &'a mut [Option<V>],
+    key: &dyn Fn(usize) -> Option<K>,
+    output: &mut [Option<&'a mut V>],
+    index: fn(K) -> usize,
+) {
+    for (slot, value) in slots.iter_mut().enumerate() {
+        if let Some(n) = (0..output.len()).find(|&n| key(n).map(index) == Some(slot)) {
+            output[n] = value.as_mut();
+        }
+    }
+}
+
+/// The offset in bytes of `field` from the start of `storage`, which must be
+/// one of its fields. This is used to describe the layout of storage, where
+/// `mem::offset_of!` isn't available on the minimum supported Rust version.
+#[inline]
+pub fn __storage_offset_of<S, T>(storage: &S, field: &T) -> usize {
+    field as *const T as usize - storage as *const S as usize
+}
+
+/// `drain` implementation for storage with a fixed layout, which has no
+/// allocation to keep and is emptied up front.
+#[inline]
+pub fn __storage_drain<S, K, V>(storage: &mut S) -> S::IntoIter
+where
+    S: MapStorage<K, V>,
+{
+    mem::replace(storage, S::empty()).into_iter()
+}
+
+/// Add up the [`Key::CAPACITY`][crate::Key::CAPACITY] of the variants of a
+/// key, where `None` stands for a variant which can hold any number of keys.
+#[inline]
+pub const fn __storage_capacity_add(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => a.checked_add(b),
+        _ => None,
+    }
+}
+
+/// `extract_if` cursor for storage with a fixed layout, which visits the slot
+/// of every key in order.
+pub struct StorageExtractSlots<I> {
+    slots: I,
+}
+
+impl<I> StorageExtractSlots<I> {
+    /// Construct a cursor over an iterator of keys and their slots.
+    #[inline]
+    pub fn new<T>(slots: T) -> Self
+    where
+        T: IntoIterator<IntoIter = I>,
+    {
+        Self {
+            slots: slots.into_iter(),
+        }
+    }
+}
+
+impl<'a, I, K, V> ExtractIfCursor<K, V> for StorageExtractSlots<I>
+where
+    I: Iterator<Item = (K, &'a mut Option<V>)>,
+    K: Copy,
+    V: 'a,
+{
+    #[inline]
+    fn next_if<F>(&mut self, filter: &mut F) -> Option<(K, V)>
+    where
+        F: ?Sized + FnMut(K, &mut V) -> bool,
+    {
+        for (key, slot) in &mut self.slots {
+            let Some(value) = slot else {
+                continue;
+            };
+
+            if filter(key, value) {
+                return slot.take().map(|value| (key, value));
+            }
+        }
+
+        None
+    }
+}
+
+/// A single step of an iterator over the variants of a composite key, which is
+/// driven by [`StorageIter`].
+///
+/// This is implemented by the iterator state generated by `derive(Key)`, so
+/// that the code driving the iteration only exists once instead of once per
+/// iterator and key.
+pub trait StorageStep {
+    /// The item produced by the iterator.
+    type Item;
+
+    /// Try to produce the next item out of the variant at `index`, returning
+    /// `None` if it is exhausted.
+    fn step(&mut self, index: usize) -> Option<Self::Item>;
+}
+
+/// A single step from the back of an iterator over the variants of a
+/// composite key, see [`StorageStep`].
+pub trait StorageStepBack: StorageStep {
+    /// Try to produce the next item from the back out of the variant at
+    /// `index`, returning `None` if it is exhausted.
+    fn step_back(&mut self, index: usize) -> Option<Self::Item>;
+}
+
+/// The variants which remain to be visited by a [`StorageIter`].
+///
+/// This is implemented for the unsigned integers, which are used as a mask
+/// with one bit for each variant, and for [`StorageSpan`] for keys which have
+/// more variants than the widest integer has bits. The derive picks the
+/// narrowest one which fits the key, so the iterators of small keys don't
+/// carry any `usize` counters.
+pub trait StorageRemaining: Copy {
+    /// The first `len` variants remain.
+    fn all(len: usize) -> Self;
+
+    /// Keep the variant at `index` only if it's `present`.
+    #[must_use]
+    fn retain(self, index: usize, present: bool) -> Self;
+
+    /// Only keep the variant at `index`.
+    #[must_use]
+    fn restrict(self, index: usize) -> Self;
+
+    /// The first variant which remains.
+    fn first(&self) -> Option<usize>;
+
+    /// The last variant which remains.
+    fn last(&self) -> Option<usize>;
+
+    /// Mark the variant at `index`, which is either the first or the last
+    /// variant which remains, as visited.
+    fn remove(&mut self, index: usize);
+}
+
+macro_rules! storage_remaining_mask {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl StorageRemaining for $ty {
+                #[inline]
+                fn all(len: usize) -> Self {
+                    match <$ty>::MAX.checked_shr(<$ty>::BITS.saturating_sub(len as u32)) {
+                        Some(mask) if len > 0 => mask,
+                        _ => 0,
+                    }
+                }
+
+                #[inline]
+                fn retain(self, index: usize, present: bool) -> Self {
+                    if present {
+                        self
+                    } else {
+                        self & !bit::<$ty>(index)
+                    }
+                }
+
+                #[inline]
+                fn restrict(self, index: usize) -> Self {
+                    self & bit::<$ty>(index)
+                }
+
+                #[inline]
+                fn first(&self) -> Option<usize> {
+                    if *self == 0 {
+                        return None;
+                    }
+
+                    Some(self.trailing_zeros() as usize)
+                }
+
+                #[inline]
+                fn last(&self) -> Option<usize> {
+                    if *self == 0 {
+                        return None;
+                    }
+
+                    Some((<$ty>::BITS - 1 - self.leading_zeros()) as usize)
+                }
+
+                #[inline]
+                fn remove(&mut self, index: usize) {
+                    *self &= !bit::<$ty>(index);
+                }
+            }
+        )*
+    };
+}
+
+storage_remaining_mask!(u8, u16, u32, u64, u128);
+
+/// The bit of the variant at `index`, or zero if it's out of range.
+#[inline]
+fn bit<T>(index: usize) -> T
+where
+    T: From<u8> + core::ops::Shl<u32, Output = T>,
+{
+    match u32::try_from(index) {
+        Ok(index) if index < (mem::size_of::<T>() * 8) as u32 => T::from(1) << index,
+        _ => T::from(0),
+    }
+}
+
+/// The variants in the range `start..end` remain, which is used for keys with
+/// more variants than the widest integer mask has bits.
+///
+/// Unlike a mask this can't skip variants which are known to be empty.
+#[derive(Clone, Copy)]
+pub struct StorageSpan<T> {
+    start: T,
+    end: T,
+}
+
+impl<T> StorageRemaining for StorageSpan<T>
+where
+    T: Copy + Into<usize> + TryFrom<usize>,
+{
+    #[inline]
+    fn all(len: usize) -> Self {
+        Self {
+            start: counter(0),
+            end: counter(len),
+        }
+    }
+
+    #[inline]
+    fn retain(self, _: usize, _: bool) -> Self {
+        self
+    }
+
+    #[inline]
+    fn restrict(self, index: usize) -> Self {
+        if (self.start.into()..self.end.into()).contains(&index) {
+            Self {
+                start: counter(index),
+                end: counter(index + 1),
+            }
+        } else {
+            Self {
+                start: self.end,
+                end: self.end,
+            }
+        }
+    }
+
+    #[inline]
+    fn first(&self) -> Option<usize> {
+        let start = self.start.into();
+        (start < self.end.into()).then_some(start)
+    }
+
+    #[inline]
+    fn last(&self) -> Option<usize> {
+        let end = self.end.into();
+        (self.start.into() < end).then(|| end - 1)
+    }
+
+    #[inline]
+    fn remove(&mut self, index: usize) {
+        if index == self.start.into() {
+            self.start = counter(index + 1);
+        } else {
+            self.end = counter(index);
+        }
+    }
+}
+
+/// Convert a variant index into the counter of a [`StorageSpan`], which is
+/// picked by the derive so that every index fits.
+#[inline]
+fn counter<T>(index: usize) -> T
+where
+    T: TryFrom<usize>,
+{
+    match T::try_from(index) {
+        Ok(index) => index,
+        Err(..) => panic!("variant index {index} out of range"),
+    }
+}
+
+/// Iterator driving a [`StorageStep`] over the variants which remain in `R`.
+///
+/// Variants which are known to be empty up front can be excluded when the
+/// iterator is constructed, so that it doesn't poll the iterators of empty
+/// nested storage.
+#[derive(Clone)]
+pub struct StorageIter<S, R> {
+    remaining: R,
+    state: S,
+}
+
+impl<S, R> StorageIter<S, R>
+where
+    R: StorageRemaining,
+{
+    /// Construct a new iterator over `end` variants.
+    #[inline]
+    pub fn new(end: usize, state: S) -> Self {
+        Self::with_remaining(R::all(end), state)
+    }
+
+    /// Construct a new iterator over the given `remaining` variants.
+    #[inline]
+    pub const fn with_remaining(remaining: R, state: S) -> Self {
+        Self { remaining, state }
+    }
+
+    /// Restrict the iterator to only visit the variant at `index`.
+    #[inline]
+    #[must_use]
+    pub fn restrict(mut self, index: usize) -> Self {
+        self.remaining = self.remaining.restrict(index);
+        self
+    }
+}
+
+impl<S, R> Iterator for StorageIter<S, R>
+where
+    S: StorageStep,
+    R: StorageRemaining,
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(index) = self.remaining.first() {
+            if let Some(item) = self.state.step(index) {
+                return Some(item);
+            }
+
+            self.remaining.remove(index);
+        }
+
+        None
+    }
+}
+
+impl<S, R> DoubleEndedIterator for StorageIter<S, R>
+where
+    S: StorageStepBack,
+    R: StorageRemaining,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some(index) = self.remaining.last() {
+            if let Some(item) = self.state.step_back(index) {
+                return Some(item);
+            }
+
+            self.remaining.remove(index);
+        }
+
+        None
+    }
+}
+
+/// Seals [`RawStorage`], which is implemented by the derive for the set storage
+/// of `#[key(bitset)]` keys.
+///
+/// [`RawStorage`]: crate::raw::RawStorage
+pub trait __RawStorageSealed {}
+
+/// Assert that a `#[key(lazy)]` or `#[key(dynamic = "ordered")]` variant or a
+/// `#[key(sparse)]` key can be used, which requires the `alloc` feature to be
+/// enabled.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __storage_require_alloc {
+    (sparse $key:ident) => {};
+    (ordered $variant:ident) => {};
+    ($variant:ident) => {};
+}
+
+/// Assert that a `#[key(lazy)]` or `#[key(dynamic = "ordered")]` variant or a
+/// `#[key(sparse)]` key can be used, which requires the `alloc` feature to be
+/// enabled.
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __storage_require_alloc {
+    (sparse $key:ident) => {
+        ::core::compile_error!(::core::concat!(
+            "key `",
+            ::core::stringify!($key),
+            "` is marked #[key(sparse)] which requires the `alloc` feature of fixed-map to be enabled"
+        ));
+    };
+    (ordered $variant:ident) => {
+        ::core::compile_error!(::core::concat!(
+            "variant `",
+            ::core::stringify!($variant),
+            "` is marked #[key(dynamic = \"ordered\")] which requires the `alloc` feature of fixed-map to be enabled"
+        ));
+    };
+    ($variant:ident) => {
+        ::core::compile_error!(::core::concat!(
+            "variant `",
+            ::core::stringify!($variant),
+            "` is marked #[key(lazy)] which requires the `alloc` feature of fixed-map to be enabled"
+        ));
+    };
+}
+
+pub use crate::__storage_require_alloc;
+
+/// Assert that a dynamic key type can be used, which requires the `hashbrown`
+/// or `std-collections` feature to be enabled.
+#[cfg(any(feature = "hashbrown", feature = "std-collections"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __storage_require_hashbrown {
+    ($variant:ident, $ty:ty) => {};
+}
+
+/// Assert that a dynamic key type can be used, which requires the `hashbrown`
+/// or `std-collections` feature to be enabled.
+#[cfg(not(any(feature = "hashbrown", feature = "std-collections")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __storage_require_hashbrown {
+    ($variant:ident, $ty:ty) => {
+        ::core::compile_error!(::core::concat!(
+            "variant `",
+            ::core::stringify!($variant),
+            "` uses `",
+            ::core::stringify!($ty),
+            "` which can only be used in a key if the `hashbrown` or `std-collections` feature of fixed-map is enabled"
+        ));
+    };
+}
+
+pub use crate::__storage_require_hashbrown;
+
+/// The map storage of a `#[key(optional)]` variant holding the dynamic type
+/// `K`, which is only stored if the `hashbrown` or `std-collections` feature
+/// is enabled.
+#[cfg(any(feature = "hashbrown", feature = "std-collections"))]
+pub type __OptionalMapStorage<K, V> = crate::map::storage::HashbrownMapStorage<K, V>;
+
+/// The map storage of a `#[key(optional)]` variant holding the dynamic type
+/// `K`, which is only stored if the `hashbrown` or `std-collections` feature
+/// is enabled.
+#[cfg(not(any(feature = "hashbrown", feature = "std-collections")))]
+pub type __OptionalMapStorage<K, V> = crate::map::storage::UnavailableMapStorage<K, V>;
+
+/// The set storage of a `#[key(optional)]` variant holding the dynamic type
+/// `T`, which is only stored if the `hashbrown` or `std-collections` feature
+/// is enabled.
+#[cfg(any(feature = "hashbrown", feature = "std-collections"))]
+pub type __OptionalSetStorage<T> = crate::set::storage::HashbrownSetStorage<T>;
+
+/// The set storage of a `#[key(optional)]` variant holding the dynamic type
+/// `T`, which is only stored if the `hashbrown` or `std-collections` feature
+/// is enabled.
+#[cfg(not(any(feature = "hashbrown", feature = "std-collections")))]
+pub type __OptionalSetStorage<T> = crate::set::storage::UnavailableSetStorage<T>;
+
+/// The schema hash of the field of a `#[key(optional)]` variant, which is
+/// the schema hash of the key when it's available.
+#[cfg(any(feature = "hashbrown", feature = "std-collections"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __storage_optional_schema_hash {
+    ($ty:ty) => {
+        <$ty as $crate::Key>::KEY_SCHEMA_HASH
+    };
+}
+
+/// The schema hash of the field of a `#[key(optional)]` variant, which is
+/// the schema hash of the key when it's available.
+#[cfg(not(any(feature = "hashbrown", feature = "std-collections")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __storage_optional_schema_hash {
+    ($ty:ty) => {
+        $crate::schema::Hasher::new()
+            .write_str(::core::stringify!($ty))
+            .finish()
+    };
+}
+
+pub use crate::__storage_optional_schema_hash;
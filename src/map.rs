@@ -3,23 +3,41 @@
 mod entry;
 pub use self::entry::Entry;
 
-pub(crate) mod storage;
-pub use self::storage::{MapStorage, OccupiedEntry, VacantEntry};
+pub mod storage;
+pub use self::storage::{
+    ClassStorage, DrainStorage, EntriesStorage, ExtractIfCursor, ExtractIfStorage, MapStorage,
+    OccupiedEntry, SliceStorage, StatsStorage, VacantEntry,
+};
 
-use core::cmp::{Ord, Ordering, PartialOrd};
-use core::fmt;
-use core::hash::{Hash, Hasher};
+mod dyn_map;
+pub use self::dyn_map::DynMap;
 
-use crate::Key;
+mod iter;
+pub use self::iter::{
+    ExtractIf, GroupIter, IndexedIter, IntoKeys, IntoValues, Iter, Keys, Range, Values,
+};
 
-/// The iterator produced by [`Map::iter`].
-pub type Iter<'a, K, V> = <<K as Key>::MapStorage<V> as MapStorage<K, V>>::Iter<'a>;
+mod stats;
+pub use self::stats::Stats;
 
-/// The iterator produced by [`Map::keys`].
-pub type Keys<'a, K, V> = <<K as Key>::MapStorage<V> as MapStorage<K, V>>::Keys<'a>;
+mod error;
+pub use self::error::{AlreadyPresent, OccupiedError};
+
+mod restricted;
+pub use self::restricted::{RestrictedIter, RestrictedMap};
+
+use core::cmp::{Ord, Ordering, PartialOrd};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{Product, Sum};
+use core::ops::{AddAssign, Index, IndexMut, MulAssign, RangeBounds};
 
-/// The iterator produced by [`Map::values`].
-pub type Values<'a, K, V> = <<K as Key>::MapStorage<V> as MapStorage<K, V>>::Values<'a>;
+use crate::debug::KeyName;
+use crate::map::storage::assert_disjoint;
+use crate::raw::{index_range, KeyIndex};
+use crate::set::SetStorage;
+use crate::total_map::{Incomplete, TotalMap};
+use crate::{CloneableKey, HashableKey, Key, KeyGroup, Set};
 
 /// The iterator produced by [`Map::iter`].
 pub type IterMut<'a, K, V> = <<K as Key>::MapStorage<V> as MapStorage<K, V>>::IterMut<'a>;
@@ -30,8 +48,33 @@ pub type ValuesMut<'a, K, V> = <<K as Key>::MapStorage<V> as MapStorage<K, V>>::
 /// The iterator produced by [`Map::into_iter`].
 pub type IntoIter<K, V> = <<K as Key>::MapStorage<V> as MapStorage<K, V>>::IntoIter;
 
+/// The iterator produced by [`Map::entries_mut`].
+pub type EntriesMut<'a, K, V> = <<K as Key>::MapStorage<V> as EntriesStorage<K, V>>::EntriesMut<'a>;
+
+/// The iterator produced by [`Map::drain`].
+pub type Drain<'a, K, V> = <<K as Key>::MapStorage<V> as DrainStorage<K, V>>::Drain<'a>;
+
+/// The parallel iterator produced by [`Map::par_values_mut`].
+#[cfg(feature = "rayon")]
+pub type ParValuesMut<'a, V> = rayon::iter::FilterMap<
+    rayon::slice::IterMut<'a, Option<V>>,
+    fn(&'a mut Option<V>) -> Option<&'a mut V>,
+>;
+
 /// A fixed map with storage specialized through the [`Key`] trait.
 ///
+/// # Iteration order
+///
+/// Entries are visited in the order in which the variants of the key are
+/// declared, and the entries of a nested key in the order of that key. The
+/// exception are variants holding a dynamic type such as `u32`, which are
+/// stored in a hash map: their entries are visited together at the position of
+/// the variant, but in an unspecified order among themselves.
+///
+/// Removing an entry never changes the order of the remaining entries. Dynamic
+/// storage keeps its capacity when entries are removed, see
+/// [`Map::remove_and_shrink`] to give it back.
+///
 /// # Examples
 ///
 /// ```
@@ -106,6 +149,107 @@ pub type IntoIter<K, V> = <<K as Key>::MapStorage<V> as MapStorage<K, V>>::IntoI
 ///
 /// assert_eq!(map.values().cloned().collect::<Vec<_>>(), vec![&42u32]);
 /// ```
+///
+/// Values can be updated through a shared reference by storing them in a
+/// [`Cell`]. The key has to be present, so this is best suited for maps which
+/// are populated up front:
+///
+/// ```
+/// use std::cell::Cell;
+///
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(MyKey::First, Cell::new(1));
+///
+/// let shared = &map;
+///
+/// if let Some(cell) = shared.get(MyKey::First) {
+///     cell.set(cell.get() + 1);
+/// }
+///
+/// assert_eq!(map.get(MyKey::First).map(Cell::get), Some(2));
+/// ```
+///
+/// If values need to be added or removed through a shared reference, see
+/// [`CellMap`].
+///
+/// Values don't need to implement any traits beyond what an operation
+/// requires. A map is [`Clone`] if its values are, and [`Copy`] if its values
+/// are, so trait objects can be stored behind references or smart pointers:
+///
+/// ```
+/// use std::fmt::Debug;
+/// use std::rc::Rc;
+///
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum MyKey {
+///     First(bool),
+///     Second,
+/// }
+///
+/// let mut map = Map::<MyKey, Rc<dyn Debug>>::new();
+/// map.insert(MyKey::First(true), Rc::new(1u32));
+/// map.insert(MyKey::Second, Rc::new("two"));
+///
+/// let copy = map.clone();
+/// assert_eq!(format!("{:?}", copy), r#"{First(true): 1, Second: "two"}"#);
+///
+/// let a = 1u32;
+/// let b = Map::<MyKey, &dyn Debug>::from_iter([(MyKey::Second, &a as &dyn Debug)]);
+/// let c = b;
+/// assert_eq!(format!("{:?}", (b, c)), "({Second: 1}, {Second: 1})");
+/// ```
+///
+/// In generic code these bounds can be expressed through [`CloneableKey`],
+/// [`CopyKey`] and [`HashableKey`].
+///
+/// Storage for zero-sized values only needs to record which keys are present,
+/// so for keys with unit variants a `Map<K, ()>` is as compact as a
+/// [`Set<K>`]. Keys which also use `#[key(bitset)]` can be marked with
+/// `#[key(masked)]`, which stores maps in a [`MaskedMapStorage`] that tracks
+/// the entries which are present in a bit mask, so that a `Map<K, ()>` is as
+/// compact as the bitset:
+///
+/// ```
+/// use core::mem::size_of;
+///
+/// use fixed_map::{Key, Map, Set};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+///     Third,
+/// }
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// #[key(bitset, masked)]
+/// enum MyFlag {
+///     First,
+///     Second,
+///     Third,
+/// }
+///
+/// assert_eq!(size_of::<Map<MyKey, ()>>(), size_of::<Set<MyKey>>());
+/// assert_eq!(size_of::<Map<MyFlag, ()>>(), size_of::<Set<MyFlag>>());
+/// ```
+///
+/// [`Cell`]: core::cell::Cell
+/// [`MaskedMapStorage`]: crate::map::storage::MaskedMapStorage
+/// [`Set<K>`]: crate::Set
+/// [`CellMap`]: crate::CellMap
+/// [`CloneableKey`]: crate::CloneableKey
+/// [`CopyKey`]: crate::CopyKey
+/// [`HashableKey`]: crate::HashableKey
 #[repr(transparent)]
 pub struct Map<K, V>
 where
@@ -163,6 +307,25 @@ impl<K, V> Map<K, V>
 where
     K: Key,
 {
+    /// The number of distinct keys the map can hold, or `None` if the key
+    /// has dynamic storage. See [`Key::CAPACITY`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    /// }
+    ///
+    /// const CAPACITY: Option<usize> = Map::<MyKey, u32>::CAPACITY;
+    /// assert_eq!(CAPACITY, Some(3));
+    /// ```
+    pub const CAPACITY: Option<usize> = K::CAPACITY;
+
     /// Creates an empty [`Map`].
     ///
     /// # Examples
@@ -186,6 +349,52 @@ where
         }
     }
 
+    /// Access the storage of the map mutably.
+    #[inline]
+    pub(crate) fn as_storage_mut(&mut self) -> &mut K::MapStorage<V> {
+        &mut self.storage
+    }
+
+    /// Creates a map covering exactly the keys produced by `keys`, with values
+    /// computed by calling `func` with each key.
+    ///
+    /// If a key is produced more than once, `func` is called for every
+    /// occurrence and the last value is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    ///     Third,
+    /// }
+    ///
+    /// let map = Map::from_keys([MyKey::First, MyKey::Second(true)], |_| 0);
+    /// assert!(map.iter().eq([(MyKey::First, &0), (MyKey::Second(true), &0)]));
+    ///
+    /// let map = Map::from_keys([MyKey::Third, MyKey::Second(false)], |key| format!("{key:?}"));
+    /// assert_eq!(map.get(MyKey::Second(false)).map(String::as_str), Some("Second(false)"));
+    /// assert_eq!(map.len(), 2);
+    /// ```
+    #[inline]
+    pub fn from_keys<I, F>(keys: I, mut func: F) -> Map<K, V>
+    where
+        I: IntoIterator<Item = K>,
+        F: FnMut(K) -> V,
+    {
+        let mut map = Map::<K, V>::new();
+
+        for key in keys {
+            map.storage.insert(key, func(key));
+        }
+
+        map
+    }
+
     /// An iterator visiting all key-value pairs in arbitrary order.
     /// The iterator element type is `(K, &'a V)`.
     ///
@@ -209,7 +418,198 @@ where
     /// ```
     #[inline]
     pub fn iter(&self) -> Iter<'_, K, V> {
-        self.storage.iter()
+        Iter::new(&self.storage)
+    }
+
+    /// An iterator visiting all key-value pairs together with the index of
+    /// the slot which stores them, as given by [`KeyIndex`].
+    ///
+    /// This is only available for keys which implement [`KeyIndex`], which
+    /// includes every derived enum whose variants are all unit variants. It
+    /// can be used to keep arrays or bitsets of length [`KeyIndex::LEN`]
+    /// aligned with the layout of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::raw::KeyIndex;
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     One,
+    ///     Two,
+    ///     Three,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::One, 1);
+    /// map.insert(MyKey::Three, 3);
+    ///
+    /// assert!(map.iter_indexed().eq([(0, MyKey::One, &1), (2, MyKey::Three, &3)]));
+    ///
+    /// let mut dirty = [false; MyKey::LEN];
+    ///
+    /// for (index, _, _) in map.iter_indexed() {
+    ///     dirty[index] = true;
+    /// }
+    ///
+    /// assert_eq!(dirty, [true, false, true]);
+    /// ```
+    #[inline]
+    pub fn iter_indexed(&self) -> IndexedIter<'_, K, V>
+    where
+        K: KeyIndex,
+    {
+        IndexedIter::new(&self.storage)
+    }
+
+    /// An iterator visiting the key-value pairs whose keys fall within
+    /// `range`, in declaration order.
+    ///
+    /// Since the storage of keys whose variants are all unit variants is laid
+    /// out as an array in declaration order, the range is resolved through
+    /// [`KeyIndex`] into a contiguous slice of the storage and keys outside of
+    /// it are never visited.
+    ///
+    /// This is only available for keys whose storage implements
+    /// [`SliceStorage`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is declared after its end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     A,
+    ///     B,
+    ///     C,
+    ///     D,
+    ///     E,
+    ///     F,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::A, 1);
+    /// map.insert(MyKey::B, 2);
+    /// map.insert(MyKey::D, 4);
+    /// map.insert(MyKey::E, 5);
+    /// map.insert(MyKey::F, 6);
+    ///
+    /// assert!(map.range(MyKey::B..=MyKey::E).eq([(MyKey::B, &2), (MyKey::D, &4), (MyKey::E, &5)]));
+    /// assert!(map.range(MyKey::B..MyKey::E).rev().eq([(MyKey::D, &4), (MyKey::B, &2)]));
+    /// assert!(map.range(..MyKey::B).eq([(MyKey::A, &1)]));
+    /// assert!(map.range(MyKey::E..).eq([(MyKey::E, &5), (MyKey::F, &6)]));
+    /// assert_eq!(map.range(MyKey::C..MyKey::C).next(), None);
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn range<R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: KeyIndex,
+        K::MapStorage<V>: SliceStorage<K, V>,
+        R: RangeBounds<K>,
+    {
+        let Some(range) = index_range(&range) else {
+            range_out_of_order::<K, V>();
+        };
+
+        Range::new(&self.storage.as_slice()[range.clone()], range.start)
+    }
+
+    /// Inserts the value returned by `func` for every key within `range`,
+    /// replacing any values which are already present.
+    ///
+    /// Since the storage of keys whose variants are all unit variants is laid
+    /// out as an array in declaration order, this writes to a contiguous slice
+    /// of the storage without looking up each key.
+    ///
+    /// This is only available for keys whose storage implements
+    /// [`SliceStorage`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is declared after its end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum Tier {
+    ///     Free,
+    ///     Basic,
+    ///     Pro,
+    ///     Enterprise,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(Tier::Free, 0);
+    /// map.insert_for_range(Tier::Basic..=Tier::Enterprise, |tier| tier as u32 * 10);
+    ///
+    /// assert!(map.values().copied().eq([0, 10, 20, 30]));
+    ///
+    /// map.insert_for_range(..Tier::Pro, |_| 1);
+    /// assert!(map.values().copied().eq([1, 1, 20, 30]));
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn insert_for_range<R, F>(&mut self, range: R, mut func: F)
+    where
+        K: KeyIndex,
+        K::MapStorage<V>: SliceStorage<K, V>,
+        R: RangeBounds<K>,
+        F: FnMut(K) -> V,
+    {
+        let Some(range) = index_range(&range) else {
+            range_out_of_order::<K, V>();
+        };
+
+        let start = range.start;
+
+        for (index, slot) in self.storage.as_mut_slice()[range].iter_mut().enumerate() {
+            if let Some(key) = K::from_index(start + index) {
+                *slot = Some(func(key));
+            }
+        }
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order, which is
+    /// boxed so that its type doesn't depend on the storage of the key.
+    ///
+    /// This is only available when the `alloc` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+    /// enum MyKey {
+    ///     One,
+    ///     Two(bool),
+    /// }
+    ///
+    /// fn sum(iter: Box<dyn Iterator<Item = (MyKey, &u32)> + '_>) -> u32 {
+    ///     iter.map(|(_, v)| *v).sum()
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::One, 1);
+    /// map.insert(MyKey::Two(true), 2);
+    ///
+    /// assert_eq!(sum(map.iter_dyn()), 3);
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn iter_dyn(&self) -> alloc::boxed::Box<dyn Iterator<Item = (K, &V)> + '_> {
+        alloc::boxed::Box::new(self.storage.iter())
     }
 
     /// An iterator visiting all keys in arbitrary order.
@@ -258,7 +658,72 @@ where
     /// ```
     #[inline]
     pub fn keys(&self) -> Keys<'_, K, V> {
-        self.storage.keys()
+        Keys::new(&self.storage)
+    }
+
+    /// Collect the keys whose values satisfy `pred` into a [`Set`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map, Set};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+    /// pub enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    ///     Third,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second(true), 20);
+    /// map.insert(MyKey::Second(false), 2);
+    /// map.insert(MyKey::Third, 30);
+    ///
+    /// let large = map.keys_where(|value| *value >= 10);
+    /// assert_eq!(large, Set::from([MyKey::Second(true), MyKey::Third]));
+    /// ```
+    #[inline]
+    pub fn keys_where<F>(&self, mut pred: F) -> Set<K>
+    where
+        F: FnMut(&V) -> bool,
+    {
+        let mut storage = K::SetStorage::empty();
+
+        for (key, value) in self.storage.iter() {
+            if pred(value) {
+                storage.insert(key);
+            }
+        }
+
+        Set::from_storage(storage)
+    }
+
+    /// Construct a read-only [`RestrictedMap`] view of this map, in which keys
+    /// outside of `allowed` are treated as absent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map, Set};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    /// }
+    ///
+    /// let map = Map::from_iter([(MyKey::First, 1), (MyKey::Second(true), 2)]);
+    /// let allowed = Set::from([MyKey::Second(true), MyKey::Second(false)]);
+    ///
+    /// let view = map.restrict(&allowed);
+    /// assert_eq!(view.get(MyKey::First), None);
+    /// assert!(view.iter().eq([(MyKey::Second(true), &2)]));
+    /// ```
+    #[inline]
+    pub fn restrict<'a>(&'a self, allowed: &'a Set<K>) -> RestrictedMap<'a, K, V> {
+        RestrictedMap::new(self, allowed)
     }
 
     /// An iterator visiting all values in arbitrary order.
@@ -305,327 +770,419 @@ where
     /// ```
     #[inline]
     pub fn values(&self) -> Values<'_, K, V> {
-        self.storage.values()
+        Values::new(&self.storage)
     }
 
-    /// An iterator visiting all key-value pairs in arbitrary order,
-    /// with mutable references to the values.
-    /// The iterator element type is `(K, &'a mut V)`.
+    /// Calls `func` with every value in the same order as [`Map::values`],
+    /// together with the [`class_index`][Key::class_index] of its key, which
+    /// for a derived key is the index of its variant.
+    ///
+    /// Unlike going through [`Map::iter`], this doesn't construct the keys of
+    /// nested storage, which makes value-only traversals of composite keys
+    /// cheaper.
     ///
     /// # Examples
     ///
     /// ```
     /// use fixed_map::{Key, Map};
     ///
-    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
-    /// enum MyKey {
-    ///     First,
-    ///     Second,
-    /// }
-    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Part {
+    ///     One,
+    ///     Two,
+    /// }
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(Part),
+    ///     Third(bool),
+    /// }
+    ///
     /// let mut map = Map::new();
     /// map.insert(MyKey::First, 1);
-    /// map.insert(MyKey::Second, 2);
+    /// map.insert(MyKey::Second(Part::One), 2);
+    /// map.insert(MyKey::Second(Part::Two), 3);
+    /// map.insert(MyKey::Third(true), 4);
     ///
-    /// // Update all values
-    /// for (_, val) in map.iter_mut() {
-    ///     *val *= 2;
-    /// }
+    /// let mut totals = [0; 3];
     ///
-    /// assert!(map.iter().eq([(MyKey::First, &2), (MyKey::Second, &4)]));
+    /// map.values_with_index(|index, value| {
+    ///     totals[index] += *value;
+    /// });
+    ///
+    /// assert_eq!(totals, [1, 5, 4]);
     /// ```
+    #[inline]
+    pub fn values_with_index<F>(&self, func: F)
+    where
+        F: FnMut(usize, &V),
+    {
+        self.storage.values_with_index(func);
+    }
+
+    /// Applies `func` to the entries of the map in the order of [`Map::iter`],
+    /// returning the first non-`None` result.
     ///
-    /// Using a composite key:
+    /// Derived storage tests each slot directly and returns as soon as a match
+    /// is found, rather than driving the chain of iterators behind
+    /// [`Map::iter`], which makes this cheaper for searches on hot paths.
+    ///
+    /// # Examples
     ///
     /// ```
     /// use fixed_map::{Key, Map};
     ///
-    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
     /// enum MyKey {
-    ///     First(bool),
-    ///     Second,
+    ///     First,
+    ///     Second(bool),
+    ///     Third,
     /// }
     ///
     /// let mut map = Map::new();
-    /// map.insert(MyKey::First(true), 1);
-    /// map.insert(MyKey::Second, 2);
-    ///
-    /// // Update all values
-    /// for (_, val) in map.iter_mut() {
-    ///     *val *= 2;
-    /// }
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second(false), 20);
+    /// map.insert(MyKey::Third, 30);
     ///
-    /// assert!(map.iter().eq([(MyKey::First(true), &2), (MyKey::Second, &4)]));
+    /// let found = map.find_map(|key, value| (*value >= 10).then_some((key, *value)));
+    /// assert_eq!(found, Some((MyKey::Second(false), 20)));
+    /// assert_eq!(map.find_map(|_, value| (*value > 30).then_some(())), None);
     /// ```
     #[inline]
-    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
-        self.storage.iter_mut()
+    pub fn find_map<B, F>(&self, func: F) -> Option<B>
+    where
+        F: FnMut(K, &V) -> Option<B>,
+    {
+        self.storage.find_map(func)
     }
 
-    /// An iterator visiting all values mutably in arbitrary order.
-    /// The iterator element type is `&'a mut V`.
+    /// Returns the position in the order of [`Map::iter`] of the first entry
+    /// for which `pred` returns `true`.
+    ///
+    /// This uses the same search as [`Map::find_map`].
     ///
     /// # Examples
     ///
     /// ```
     /// use fixed_map::{Key, Map};
     ///
-    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
-    /// pub enum MyKey {
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
     ///     First,
-    ///     Second,
-    /// }
-    ///
-    /// let mut map = Map::new();
-    /// map.insert(MyKey::First, 2);
-    /// map.insert(MyKey::Second, 5);
-    ///
-    /// for (index, val) in map.values_mut().enumerate() {
-    ///     *val *= index + 1;
+    ///     Second(bool),
+    ///     Third,
     /// }
     ///
-    /// assert!(map.values().copied().eq([2, 10]));
-    ///
     /// let mut map = Map::new();
-    /// map.insert(MyKey::First, 2);
-    /// map.insert(MyKey::Second, 5);
-    ///
-    /// for (index, val) in map.values_mut().rev().enumerate() {
-    ///     *val *= index + 1;
-    /// }
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second(false), 2);
+    /// map.insert(MyKey::Third, 3);
     ///
-    /// assert!(map.values().copied().eq([4, 5]));
+    /// assert_eq!(map.position(|key, _| key == MyKey::Third), Some(2));
+    /// assert_eq!(map.position(|_, value| *value > 3), None);
     /// ```
+    #[inline]
+    pub fn position<F>(&self, mut pred: F) -> Option<usize>
+    where
+        F: FnMut(K, &V) -> bool,
+    {
+        let mut n = 0;
+
+        self.storage.find_map(|key, value| {
+            if pred(key, value) {
+                return Some(n);
+            }
+
+            n += 1;
+            None
+        })
+    }
+
+    /// Returns the first key-value pair in the map, in the order of
+    /// [`Map::iter`].
     ///
-    /// Using a composite key:
+    /// For derived keys this is the entry of the earliest declared variant,
+    /// which is found without going through the iterator.
+    ///
+    /// # Examples
     ///
     /// ```
     /// use fixed_map::{Key, Map};
     ///
-    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
-    /// pub enum MyKey {
-    ///     First(bool),
-    ///     Second,
-    /// }
-    ///
-    /// let mut map = Map::new();
-    /// map.insert(MyKey::First(false), 2);
-    /// map.insert(MyKey::Second, 5);
-    ///
-    /// for (index, val) in map.values_mut().enumerate() {
-    ///     *val *= index + 1;
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    ///     Third,
     /// }
     ///
-    /// assert!(map.values().copied().eq([2, 10]));
-    ///
     /// let mut map = Map::new();
-    /// map.insert(MyKey::First(false), 2);
-    /// map.insert(MyKey::Second, 5);
-    ///
-    /// for (index, val) in map.values_mut().rev().enumerate() {
-    ///     *val *= index + 1;
-    /// }
+    /// assert_eq!(map.first_key_value(), None);
     ///
-    /// assert!(map.values().copied().eq([4, 5]));
+    /// map.insert(MyKey::Third, 3);
+    /// map.insert(MyKey::Second(false), 2);
+    /// assert_eq!(map.first_key_value(), Some((MyKey::Second(false), &2)));
     /// ```
     #[inline]
-    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
-        self.storage.values_mut()
+    pub fn first_key_value(&self) -> Option<(K, &V)> {
+        self.storage.first_key_value()
     }
 
-    /// Returns `true` if the map currently contains the given key.
+    /// Returns the last key-value pair in the map, in the order of
+    /// [`Map::iter`].
+    ///
+    /// For derived keys this is the entry of the latest declared variant,
+    /// which is found by searching from the last variant backwards.
     ///
     /// # Examples
     ///
     /// ```
     /// use fixed_map::{Key, Map};
     ///
-    /// #[derive(Clone, Copy, Key)]
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
     /// enum MyKey {
     ///     First,
-    ///     Second,
+    ///     Second(bool),
+    ///     Third,
     /// }
     ///
     /// let mut map = Map::new();
-    /// map.insert(MyKey::First, "a");
-    /// assert_eq!(map.contains_key(MyKey::First), true);
-    /// assert_eq!(map.contains_key(MyKey::Second), false);
+    /// assert_eq!(map.last_key_value(), None);
+    ///
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second(true), 2);
+    /// map.insert(MyKey::Second(false), 3);
+    /// assert_eq!(map.last_key_value(), Some((MyKey::Second(false), &3)));
     /// ```
     #[inline]
-    pub fn contains_key(&self, key: K) -> bool {
-        self.storage.contains_key(key)
+    pub fn last_key_value(&self) -> Option<(K, &V)> {
+        self.storage.last_key_value()
     }
 
-    /// Returns a reference to the value corresponding to the key.
+    /// Removes and returns the first key-value pair in the map, in the order
+    /// of [`Map::iter`].
     ///
     /// # Examples
     ///
     /// ```
     /// use fixed_map::{Key, Map};
     ///
-    /// #[derive(Clone, Copy, Key)]
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
     /// enum MyKey {
     ///     First,
     ///     Second,
+    ///     Third,
     /// }
     ///
     /// let mut map = Map::new();
-    /// map.insert(MyKey::First, "a");
-    /// assert_eq!(map.get(MyKey::First).copied(), Some("a"));
-    /// assert_eq!(map.get(MyKey::Second), None);
+    /// map.insert(MyKey::Third, 3);
+    /// map.insert(MyKey::First, 1);
+    ///
+    /// assert_eq!(map.pop_first(), Some((MyKey::First, 1)));
+    /// assert_eq!(map.pop_first(), Some((MyKey::Third, 3)));
+    /// assert_eq!(map.pop_first(), None);
     /// ```
+    #[inline]
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let (key, _) = self.storage.first_key_value()?;
+        let value = self.storage.remove(key)?;
+        Some((key, value))
+    }
+
+    /// Removes and returns the last key-value pair in the map, in the order
+    /// of [`Map::iter`].
     ///
-    /// Using a composite key:
+    /// # Examples
     ///
     /// ```
     /// use fixed_map::{Key, Map};
     ///
-    /// #[derive(Clone, Copy, Key)]
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
     /// enum MyKey {
-    ///     First(bool),
+    ///     First,
     ///     Second,
+    ///     Third,
     /// }
     ///
     /// let mut map = Map::new();
-    /// map.insert(MyKey::First(true), "a");
-    /// assert_eq!(map.get(MyKey::First(true)).copied(), Some("a"));
-    /// assert_eq!(map.get(MyKey::Second), None);
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second, 2);
+    ///
+    /// assert_eq!(map.pop_last(), Some((MyKey::Second, 2)));
+    /// assert_eq!(map.pop_last(), Some((MyKey::First, 1)));
+    /// assert_eq!(map.pop_last(), None);
     /// ```
     #[inline]
-    pub fn get(&self, key: K) -> Option<&V> {
-        self.storage.get(key)
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let (key, _) = self.storage.last_key_value()?;
+        let value = self.storage.remove(key)?;
+        Some((key, value))
     }
 
-    /// Returns a mutable reference to the value corresponding to the key.
+    /// Returns a [`Set`] containing every key present in the map.
+    ///
+    /// Derived storage copies whether each slot is occupied directly into the
+    /// storage of the set, rather than inserting the keys one at a time.
     ///
     /// # Examples
     ///
     /// ```
-    /// use fixed_map::{Key, Map};
+    /// use fixed_map::{Key, Map, Set};
     ///
-    /// #[derive(Clone, Copy, Key)]
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
     /// enum MyKey {
     ///     First,
-    ///     Second,
+    ///     Second(bool),
+    ///     Third,
     /// }
     ///
     /// let mut map = Map::new();
-    /// map.insert(MyKey::First, "a");
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second(false), 2);
     ///
-    /// if let Some(x) = map.get_mut(MyKey::First) {
-    ///     *x = "b";
-    /// }
+    /// let mut required = Set::new();
+    /// required.insert(MyKey::First);
+    /// required.insert(MyKey::Third);
     ///
-    /// assert_eq!(map.get(MyKey::First).copied(), Some("b"));
+    /// let mut missing = required;
+    /// missing.subtract(&map.keys_set());
+    ///
+    /// assert!(map.keys_set().iter().eq([MyKey::First, MyKey::Second(false)]));
+    /// assert!(missing.iter().eq([MyKey::Third]));
     /// ```
+    #[inline]
+    pub fn keys_set(&self) -> Set<K> {
+        Set::from_storage(self.storage.key_set())
+    }
+
+    /// Creates a consuming iterator visiting all the keys in the same order as
+    /// [`Map::keys`]. The map cannot be used after calling this.
     ///
-    /// Using a composite key:
+    /// # Examples
     ///
     /// ```
     /// use fixed_map::{Key, Map};
     ///
-    /// #[derive(Clone, Copy, Key)]
-    /// enum MyKey {
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+    /// pub enum MyKey {
     ///     First(bool),
-    ///     Second(()),
+    ///     Second,
     ///     Third,
     /// }
     ///
     /// let mut map = Map::new();
-    /// map.insert(MyKey::First(true), "a");
-    ///
-    /// if let Some(x) = map.get_mut(MyKey::First(true)) {
-    ///     *x = "b";
-    /// }
+    /// map.insert(MyKey::First(true), String::from("a"));
+    /// map.insert(MyKey::Third, String::from("c"));
     ///
-    /// assert_eq!(map.get(MyKey::First(true)).copied(), Some("b"));
+    /// let keys = map.into_keys();
+    /// assert_eq!(keys.len(), 2);
+    /// assert!(keys.eq([MyKey::First(true), MyKey::Third]));
     /// ```
     #[inline]
-    pub fn get_mut(&mut self, key: K) -> Option<&mut V> {
-        self.storage.get_mut(key)
+    pub fn into_keys(self) -> IntoKeys<K, V> {
+        IntoKeys::new(self.storage)
     }
 
-    /// Inserts a key-value pair into the map.
-    ///
-    /// If the map did not have this key present, [`None`] is returned.
-    ///
-    /// If the map did have this key present, the value is updated, and the old
-    /// value is returned.
+    /// Creates a consuming iterator visiting all the values in the same order
+    /// as [`Map::values`]. The map cannot be used after calling this.
     ///
     /// # Examples
     ///
     /// ```
     /// use fixed_map::{Key, Map};
     ///
-    /// #[derive(Clone, Copy, Key)]
-    /// enum MyKey {
-    ///     One,
-    ///     Two,
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+    /// pub enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    ///     Third,
     /// }
     ///
     /// let mut map = Map::new();
-    /// assert_eq!(map.insert(MyKey::One, "a"), None);
-    /// assert_eq!(map.is_empty(), false);
+    /// map.insert(MyKey::First(true), String::from("a"));
+    /// map.insert(MyKey::Third, String::from("c"));
     ///
-    /// map.insert(MyKey::Two, "b");
-    /// assert_eq!(map.insert(MyKey::Two, "c"), Some("b"));
-    /// assert_eq!(map.get(MyKey::Two), Some(&"c"));
+    /// let values = map.into_values().rev().collect::<Vec<_>>();
+    /// assert_eq!(values, ["c", "a"]);
     /// ```
     #[inline]
-    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
-        self.storage.insert(key, value)
+    pub fn into_values(self) -> IntoValues<K, V> {
+        IntoValues::new(self.storage)
     }
 
-    /// Removes a key from the map, returning the value at the key if the key
-    /// was previously in the map.
+    /// Consumes the map, converting every value with `func` into a map with
+    /// the same keys.
+    ///
+    /// This converts the storage of the map directly rather than inserting
+    /// every entry into a new map, which is useful when converting between
+    /// representations of the same configuration.
     ///
     /// # Examples
     ///
     /// ```
     /// use fixed_map::{Key, Map};
     ///
-    /// #[derive(Clone, Copy, Key)]
-    /// enum MyKey {
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum Part {
     ///     One,
     ///     Two,
     /// }
     ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(Part),
+    ///     Third(bool),
+    /// }
+    ///
     /// let mut map = Map::new();
-    /// map.insert(MyKey::One, "a");
-    /// assert_eq!(map.remove(MyKey::One), Some("a"));
-    /// assert_eq!(map.remove(MyKey::One), None);
+    /// map.insert(MyKey::First, "1");
+    /// map.insert(MyKey::Second(Part::Two), "2");
+    /// map.insert(MyKey::Third(true), "3");
+    ///
+    /// let map: Map<MyKey, u32> = map.map_values(|_, value| value.parse().unwrap());
+    ///
+    /// assert_eq!(map.get(MyKey::First), Some(&1));
+    /// assert_eq!(map.get(MyKey::Second(Part::Two)), Some(&2));
+    /// assert_eq!(map.get(MyKey::Third(true)), Some(&3));
+    /// assert_eq!(map.len(), 3);
     /// ```
     #[inline]
-    pub fn remove(&mut self, key: K) -> Option<V> {
-        self.storage.remove(key)
+    pub fn map_values<W, F>(self, func: F) -> Map<K, W>
+    where
+        F: FnMut(K, V) -> W,
+    {
+        Map {
+            storage: self.storage.map_values(func),
+        }
     }
 
-    /// Retains only the elements specified by the predicate.
-    ///
-    /// In other words, remove all pairs (k, v) for which f(k, &mut v) returns false.
-    /// The elements are visited in unsorted (and unspecified) order.
+    /// An iterator visiting all key-value pairs in arbitrary order,
+    /// with mutable references to the values.
+    /// The iterator element type is `(K, &'a mut V)`.
     ///
     /// # Examples
     ///
     /// ```
     /// use fixed_map::{Key, Map};
     ///
-    /// #[derive(Clone, Copy, Key)]
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
     /// enum MyKey {
     ///     First,
     ///     Second,
     /// }
     ///
-    /// let mut map: Map<MyKey, i32> = Map::new();
-    ///
-    /// map.insert(MyKey::First, 42);
-    /// map.insert(MyKey::Second, -10);
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second, 2);
     ///
-    /// map.retain(|k, v| *v > 0);
+    /// // Update all values
+    /// for (_, val) in map.iter_mut() {
+    ///     *val *= 2;
+    /// }
     ///
-    /// assert_eq!(map.len(), 1);
-    /// assert_eq!(map.get(MyKey::First), Some(&42));
-    /// assert_eq!(map.get(MyKey::Second), None);
+    /// assert!(map.iter().eq([(MyKey::First, &2), (MyKey::Second, &4)]));
     /// ```
     ///
     /// Using a composite key:
@@ -633,45 +1190,155 @@ where
     /// ```
     /// use fixed_map::{Key, Map};
     ///
-    /// #[derive(Clone, Copy, Key)]
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
     /// enum MyKey {
     ///     First(bool),
     ///     Second,
     /// }
     ///
-    /// let mut map: Map<MyKey, i32> = Map::new();
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First(true), 1);
+    /// map.insert(MyKey::Second, 2);
     ///
-    /// map.insert(MyKey::First(true), 42);
-    /// map.insert(MyKey::First(false), -31);
-    /// map.insert(MyKey::Second, 100);
+    /// // Update all values
+    /// for (_, val) in map.iter_mut() {
+    ///     *val *= 2;
+    /// }
     ///
-    /// let mut other = map.clone();
-    /// assert_eq!(map.len(), 3);
+    /// assert!(map.iter().eq([(MyKey::First(true), &2), (MyKey::Second, &4)]));
+    /// ```
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        self.storage.iter_mut()
+    }
+
+    /// An iterator visiting all values mutably in arbitrary order.
+    /// The iterator element type is `&'a mut V`.
     ///
-    /// map.retain(|k, v| *v > 0);
+    /// # Examples
     ///
-    /// assert_eq!(map.len(), 2);
-    /// assert_eq!(map.get(MyKey::First(true)), Some(&42));
-    /// assert_eq!(map.get(MyKey::First(false)), None);
-    /// assert_eq!(map.get(MyKey::Second), Some(&100));
+    /// ```
+    /// use fixed_map::{Key, Map};
     ///
-    /// other.retain(|k, v| matches!(k, MyKey::First(_)));
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+    /// pub enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
     ///
-    /// assert_eq!(other.len(), 2);
-    /// assert_eq!(other.get(MyKey::First(true)), Some(&42));
-    /// assert_eq!(other.get(MyKey::First(false)), Some(&-31));
-    /// assert_eq!(other.get(MyKey::Second), None);
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 2);
+    /// map.insert(MyKey::Second, 5);
+    ///
+    /// for (index, val) in map.values_mut().enumerate() {
+    ///     *val *= index + 1;
+    /// }
+    ///
+    /// assert!(map.values().copied().eq([2, 10]));
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 2);
+    /// map.insert(MyKey::Second, 5);
+    ///
+    /// for (index, val) in map.values_mut().rev().enumerate() {
+    ///     *val *= index + 1;
+    /// }
+    ///
+    /// assert!(map.values().copied().eq([4, 5]));
+    /// ```
+    ///
+    /// Using a composite key:
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+    /// pub enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First(false), 2);
+    /// map.insert(MyKey::Second, 5);
+    ///
+    /// for (index, val) in map.values_mut().enumerate() {
+    ///     *val *= index + 1;
+    /// }
+    ///
+    /// assert!(map.values().copied().eq([2, 10]));
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First(false), 2);
+    /// map.insert(MyKey::Second, 5);
+    ///
+    /// for (index, val) in map.values_mut().rev().enumerate() {
+    ///     *val *= index + 1;
+    /// }
+    ///
+    /// assert!(map.values().copied().eq([4, 5]));
     /// ```
     #[inline]
-    pub fn retain<F>(&mut self, f: F)
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        self.storage.values_mut()
+    }
+
+    /// A parallel iterator visiting all values mutably, which can be used to
+    /// distribute expensive processing of values over the [`rayon`] thread
+    /// pool.
+    ///
+    /// The work is split directly over the array of slots in the storage, so
+    /// no intermediate collection is allocated and empty slots are skipped as
+    /// they are visited.
+    ///
+    /// This is only available when the `rayon` feature is enabled and for keys
+    /// whose storage implements [`SliceStorage`].
+    ///
+    /// [`rayon`]: https://docs.rs/rayon
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    /// use rayon::prelude::*;
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum Tile {
+    ///     North,
+    ///     East,
+    ///     South,
+    ///     West,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(Tile::North, vec![1u32; 64]);
+    /// map.insert(Tile::East, vec![2u32; 64]);
+    /// map.insert(Tile::South, vec![3u32; 64]);
+    ///
+    /// map.par_values_mut().for_each(|pixels| {
+    ///     pixels.iter_mut().for_each(|p| *p *= 10);
+    /// });
+    ///
+    /// assert_eq!(map.get(Tile::East).map(|p| p[0]), Some(20));
+    /// assert_eq!(map.get(Tile::West), None);
+    /// assert_eq!(map.values().map(|p| p.iter().sum::<u32>()).sum::<u32>(), 64 * 60);
+    /// ```
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub fn par_values_mut(&mut self) -> ParValuesMut<'_, V>
     where
-        F: FnMut(K, &mut V) -> bool,
+        K::MapStorage<V>: SliceStorage<K, V>,
+        V: Send,
     {
-        self.storage.retain(f);
+        use rayon::iter::{IntoParallelRefMutIterator as _, ParallelIterator as _};
+
+        self.storage
+            .as_mut_slice()
+            .par_iter_mut()
+            .filter_map(Option::as_mut as fn(&mut Option<V>) -> Option<&mut V>)
     }
 
-    /// Clears the map, removing all key-value pairs. Keeps the allocated memory
-    /// for reuse.
+    /// Returns `true` if the map currently contains the given key.
     ///
     /// # Examples
     ///
@@ -680,21 +1347,24 @@ where
     ///
     /// #[derive(Clone, Copy, Key)]
     /// enum MyKey {
-    ///     One,
-    ///     Two,
+    ///     First,
+    ///     Second,
     /// }
     ///
     /// let mut map = Map::new();
-    /// map.insert(MyKey::One, "a");
-    /// map.clear();
-    /// assert!(map.is_empty());
+    /// map.insert(MyKey::First, "a");
+    /// assert_eq!(map.contains_key(MyKey::First), true);
+    /// assert_eq!(map.contains_key(MyKey::Second), false);
     /// ```
     #[inline]
-    pub fn clear(&mut self) {
-        self.storage.clear();
+    pub fn contains_key(&self, key: K) -> bool {
+        let found = self.storage.contains_key(key);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_lookup(key, found);
+        found
     }
 
-    /// Returns true if the map contains no elements.
+    /// Returns a reference to the value corresponding to the key.
     ///
     /// # Examples
     ///
@@ -708,28 +1378,36 @@ where
     /// }
     ///
     /// let mut map = Map::new();
-    /// assert!(map.is_empty());
-    /// map.insert(MyKey::First, 1);
-    /// assert!(!map.is_empty());
+    /// map.insert(MyKey::First, "a");
+    /// assert_eq!(map.get(MyKey::First).copied(), Some("a"));
+    /// assert_eq!(map.get(MyKey::Second), None);
     /// ```
     ///
-    /// An empty key:
+    /// Using a composite key:
     ///
     /// ```
     /// use fixed_map::{Key, Map};
     ///
     /// #[derive(Clone, Copy, Key)]
-    /// enum MyKey {}
+    /// enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    /// }
     ///
-    /// let map = Map::<MyKey, u32>::new();
-    /// assert!(map.is_empty());
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First(true), "a");
+    /// assert_eq!(map.get(MyKey::First(true)).copied(), Some("a"));
+    /// assert_eq!(map.get(MyKey::Second), None);
     /// ```
     #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.storage.is_empty()
+    pub fn get(&self, key: K) -> Option<&V> {
+        let value = self.storage.get(key);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_lookup(key, value.is_some());
+        value
     }
 
-    /// Gets the current length of a [`Map`].
+    /// Returns a mutable reference to the value corresponding to the key.
     ///
     /// # Examples
     ///
@@ -742,17 +1420,14 @@ where
     ///     Second,
     /// }
     ///
-    /// let mut map: Map<MyKey, i32> = Map::new();
-    /// assert_eq!(map.len(), 0);
-    ///
-    /// map.insert(MyKey::First, 42);
-    /// assert_eq!(map.len(), 1);
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, "a");
     ///
-    /// map.insert(MyKey::First, 42);
-    /// assert_eq!(map.len(), 1);
+    /// if let Some(x) = map.get_mut(MyKey::First) {
+    ///     *x = "b";
+    /// }
     ///
-    /// map.remove(MyKey::First);
-    /// assert_eq!(map.len(), 0);
+    /// assert_eq!(map.get(MyKey::First).copied(), Some("b"));
     /// ```
     ///
     /// Using a composite key:
@@ -763,27 +1438,29 @@ where
     /// #[derive(Clone, Copy, Key)]
     /// enum MyKey {
     ///     First(bool),
-    ///     Second,
+    ///     Second(()),
+    ///     Third,
     /// }
     ///
-    /// let mut map: Map<MyKey, i32> = Map::new();
-    /// assert_eq!(map.len(), 0);
-    ///
-    /// map.insert(MyKey::First(true), 42);
-    /// assert_eq!(map.len(), 1);
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First(true), "a");
     ///
-    /// map.insert(MyKey::First(false), 42);
-    /// assert_eq!(map.len(), 2);
+    /// if let Some(x) = map.get_mut(MyKey::First(true)) {
+    ///     *x = "b";
+    /// }
     ///
-    /// map.remove(MyKey::First(true));
-    /// assert_eq!(map.len(), 1);
+    /// assert_eq!(map.get(MyKey::First(true)).copied(), Some("b"));
     /// ```
     #[inline]
-    pub fn len(&self) -> usize {
-        self.storage.len()
+    pub fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        let value = self.storage.get_mut(key);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_lookup(key, value.is_some());
+        value
     }
 
-    /// Gets the given key’s corresponding [`Entry`] in the [`Map`] for in-place manipulation.
+    /// Modifies the value corresponding to the key in place if it's present,
+    /// returning `true` if `func` was called.
     ///
     /// # Examples
     ///
@@ -792,21 +1469,73 @@ where
     ///
     /// #[derive(Clone, Copy, Key)]
     /// enum MyKey {
-    ///     Even,
-    ///     Odd,
+    ///     First,
+    ///     Second(bool),
     /// }
     ///
-    /// let mut map: Map<MyKey, u32> = Map::new();
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 1);
     ///
-    /// for n in [3, 45, 3, 23, 2, 10, 59, 11, 51, 70] {
-    ///     map
-    ///         .entry(if n % 2 == 0 { MyKey::Even } else { MyKey::Odd })
-    ///         .and_modify(|x| *x += 1)
-    ///         .or_insert(1);
+    /// assert!(map.update(MyKey::First, |v| *v += 1));
+    /// assert!(!map.update(MyKey::Second(true), |v| *v += 1));
+    ///
+    /// assert_eq!(map.get(MyKey::First), Some(&2));
+    /// assert_eq!(map.get(MyKey::Second(true)), None);
+    /// ```
+    #[inline]
+    pub fn update<F>(&mut self, key: K, func: F) -> bool
+    where
+        F: FnOnce(&mut V),
+    {
+        match self.get_mut(key) {
+            Some(value) => {
+                func(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns mutable references to the values corresponding to several keys
+    /// at once.
+    ///
+    /// Every element of the returned array is the value of the key at the
+    /// same position, or `None` if it isn't present.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any key is passed more than once, since that would return
+    /// two mutable references to the same value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::mem;
+    ///
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum Dir {
+    ///     North,
+    ///     East,
+    ///     South,
+    ///     West,
     /// }
     ///
-    /// assert_eq!(map.get(MyKey::Even), Some(&3));
-    /// assert_eq!(map.get(MyKey::Odd), Some(&7));
+    /// let mut map = Map::new();
+    /// map.insert(Dir::North, 1);
+    /// map.insert(Dir::South, 3);
+    ///
+    /// if let [Some(north), Some(south)] = map.get_disjoint_mut([Dir::North, Dir::South]) {
+    ///     mem::swap(north, south);
+    /// }
+    ///
+    /// assert_eq!(map.get(Dir::North), Some(&3));
+    /// assert_eq!(map.get(Dir::South), Some(&1));
+    ///
+    /// let [east, north] = map.get_disjoint_mut([Dir::East, Dir::North]);
+    /// assert_eq!(east, None);
+    /// assert_eq!(north, Some(&mut 3));
     /// ```
     ///
     /// Using a composite key:
@@ -814,131 +1543,1957 @@ where
     /// ```
     /// use fixed_map::{Key, Map};
     ///
-    /// #[derive(Clone, Copy, Key)]
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
     /// enum MyKey {
     ///     First(bool),
     ///     Second,
     /// }
     ///
-    /// let mut map: Map<MyKey, Vec<i32>> = Map::new();
+    /// let mut map = Map::from_iter([(MyKey::First(true), 1), (MyKey::Second, 2)]);
     ///
-    /// map.entry(MyKey::First(true)).or_default().push(1);
-    /// map.entry(MyKey::Second).or_insert_with(|| vec![2; 8]).truncate(4);
+    /// let [a, b] = map.get_disjoint_mut([MyKey::Second, MyKey::First(true)]);
+    /// *a.unwrap() += 10;
+    /// *b.unwrap() += 20;
+    ///
+    /// assert!(map.values().eq([&21, &12]));
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn get_disjoint_mut<const N: usize>(&mut self, keys: [K; N]) -> [Option<&mut V>; N]
+    where
+        K: PartialEq,
+    {
+        assert_disjoint(&keys, |a, b| a == b);
+
+        let mut output = core::array::from_fn(|_| None);
+        self.storage
+            .get_disjoint_mut(&|n| Some(keys[n]), &|a, b| a == b, &mut output);
+        output
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, [`None`] is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the old
+    /// value is returned.
+    ///
+    /// # Examples
     ///
-    /// assert_eq!(map.get(MyKey::First(true)), Some(&vec![1]));
-    /// assert_eq!(map.get(MyKey::Second), Some(&vec![2; 4]));
     /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     One,
+    ///     Two,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// assert_eq!(map.insert(MyKey::One, "a"), None);
+    /// assert_eq!(map.is_empty(), false);
+    ///
+    /// map.insert(MyKey::Two, "b");
+    /// assert_eq!(map.insert(MyKey::Two, "c"), Some("b"));
+    /// assert_eq!(map.get(MyKey::Two), Some(&"c"));
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record(crate::metrics::Operation::Insert, key);
+        self.storage.insert(key, value)
+    }
+
+    /// Inserts a key-value pair into the map unless the key is already
+    /// present, returning a mutable reference to the inserted value.
+    ///
+    /// # Errors
+    ///
+    /// If the key is already present the map is left unmodified, and an
+    /// [`AlreadyPresent`] error carrying the rejected key and value is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     One,
+    ///     Two,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// assert_eq!(map.insert_unique(MyKey::One, "a"), Ok(&mut "a"));
+    ///
+    /// let error = map.insert_unique(MyKey::One, "b").unwrap_err();
+    /// assert_eq!(error.into_parts(), (MyKey::One, "b"));
+    /// assert_eq!(map.get(MyKey::One), Some(&"a"));
+    /// ```
+    #[inline]
+    pub fn insert_unique(&mut self, key: K, value: V) -> Result<&mut V, AlreadyPresent<K, V>> {
+        match self.storage.entry(key) {
+            Entry::Occupied(..) => Err(AlreadyPresent::new(key, value)),
+            Entry::Vacant(entry) => {
+                #[cfg(feature = "metrics")]
+                crate::metrics::record(crate::metrics::Operation::Insert, key);
+                Ok(entry.insert(value))
+            }
+        }
+    }
+
+    /// Tries to insert a key-value pair into the map, and returns a mutable
+    /// reference to the value in the entry.
+    ///
+    /// This mirrors the unstable `HashMap::try_insert`. Unlike
+    /// [`Map::insert_unique`], the error borrows the map and carries the
+    /// occupied entry, so the existing value can be inspected or modified.
+    ///
+    /// # Errors
+    ///
+    /// If the key is already occupied the map is left unmodified, and an
+    /// [`OccupiedError`] carrying the occupied entry and the rejected value is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::map::OccupiedEntry;
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     One,
+    ///     Two(bool),
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// assert_eq!(map.try_insert(MyKey::Two(true), "a").ok(), Some(&mut "a"));
+    ///
+    /// let error = map.try_insert(MyKey::Two(true), "b").unwrap_err();
+    /// assert_eq!(error.key(), MyKey::Two(true));
+    /// assert_eq!(error.entry().get(), &"a");
+    /// assert_eq!(error.value(), &"b");
+    /// ```
+    #[inline]
+    pub fn try_insert(
+        &mut self,
+        key: K,
+        value: V,
+    ) -> Result<&mut V, OccupiedError<'_, K::MapStorage<V>, K, V>> {
+        let value = self.storage.try_insert(key, value)?;
+        #[cfg(feature = "metrics")]
+        crate::metrics::record(crate::metrics::Operation::Insert, key);
+        Ok(value)
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key
+    /// was previously in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     One,
+    ///     Two,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::One, "a");
+    /// assert_eq!(map.remove(MyKey::One), Some("a"));
+    /// assert_eq!(map.remove(MyKey::One), None);
+    /// ```
+    #[inline]
+    pub fn remove(&mut self, key: K) -> Option<V> {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record(crate::metrics::Operation::Remove, key);
+        self.storage.remove(key)
+    }
+
+    /// Removes a key from the map like [`Map::remove`], and releases any
+    /// memory the storage the key belongs to no longer needs.
+    ///
+    /// This only makes a difference for keys which are stored dynamically,
+    /// such as variants holding a `u32` or marked with `#[key(lazy)]` or
+    /// `#[key(sparse)]`. Their storage is shrunk to fit the remaining entries,
+    /// and lazy storage is deallocated once it's empty. Storage with a fixed
+    /// layout behaves exactly like [`Map::remove`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "hashbrown")] {
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     Port(u32),
+    ///     Other,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    ///
+    /// for port in 0..64 {
+    ///     map.insert(MyKey::Port(port), port);
+    /// }
+    ///
+    /// for port in 1..64 {
+    ///     assert_eq!(map.remove(MyKey::Port(port)), Some(port));
+    /// }
+    ///
+    /// assert!(map.stats().dynamic_capacity >= 64);
+    ///
+    /// assert_eq!(map.remove_and_shrink(MyKey::Port(0)), Some(0));
+    /// assert_eq!(map.remove_and_shrink(MyKey::Port(0)), None);
+    /// assert_eq!(map.stats().dynamic_capacity, 0);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn remove_and_shrink(&mut self, key: K) -> Option<V> {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record(crate::metrics::Operation::Remove, key);
+        self.storage.remove_and_shrink(key)
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all pairs (k, v) for which f(k, &mut v) returns false.
+    /// The elements are visited in unsorted (and unspecified) order.
+    ///
+    /// The predicate is called exactly once for every entry. Modifications it
+    /// makes to a value are kept if the entry is retained, while entries for
+    /// which it returns `false` are dropped once it returns. Use
+    /// [`Map::retain_with`] if the predicate only needs to read the values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map: Map<MyKey, i32> = Map::new();
+    ///
+    /// map.insert(MyKey::First, 42);
+    /// map.insert(MyKey::Second, -10);
+    ///
+    /// map.retain(|k, v| *v > 0);
+    ///
+    /// assert_eq!(map.len(), 1);
+    /// assert_eq!(map.get(MyKey::First), Some(&42));
+    /// assert_eq!(map.get(MyKey::Second), None);
+    /// ```
+    ///
+    /// Using a composite key:
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    /// }
+    ///
+    /// let mut map: Map<MyKey, i32> = Map::new();
+    ///
+    /// map.insert(MyKey::First(true), 42);
+    /// map.insert(MyKey::First(false), -31);
+    /// map.insert(MyKey::Second, 100);
+    ///
+    /// let mut other = map.clone();
+    /// assert_eq!(map.len(), 3);
+    ///
+    /// map.retain(|k, v| *v > 0);
+    ///
+    /// assert_eq!(map.len(), 2);
+    /// assert_eq!(map.get(MyKey::First(true)), Some(&42));
+    /// assert_eq!(map.get(MyKey::First(false)), None);
+    /// assert_eq!(map.get(MyKey::Second), Some(&100));
+    ///
+    /// other.retain(|k, v| matches!(k, MyKey::First(_)));
+    ///
+    /// assert_eq!(other.len(), 2);
+    /// assert_eq!(other.get(MyKey::First(true)), Some(&42));
+    /// assert_eq!(other.get(MyKey::First(false)), Some(&-31));
+    /// assert_eq!(other.get(MyKey::Second), None);
+    /// ```
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(K, &mut V) -> bool,
+    {
+        self.storage.retain(f);
+    }
+
+    /// Retains only the elements specified by the predicate, which is only
+    /// given shared access to the values.
+    ///
+    /// This behaves like [`Map::retain`], except that the predicate can't
+    /// modify the values it inspects, so it can be shared with code which
+    /// only borrows the map immutably, such as a filter over [`Map::iter`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    ///     Third,
+    /// }
+    ///
+    /// fn keep(_: MyKey, value: &i32) -> bool {
+    ///     *value > 0
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 42);
+    /// map.insert(MyKey::Second(true), -1);
+    /// map.insert(MyKey::Second(false), 7);
+    /// map.insert(MyKey::Third, 0);
+    ///
+    /// let expected = map.iter().filter(|&(k, v)| keep(k, v)).count();
+    /// map.retain_with(keep);
+    ///
+    /// assert_eq!(map.len(), expected);
+    /// assert!(map.iter().eq([(MyKey::First, &42), (MyKey::Second(false), &7)]));
+    /// ```
+    #[inline]
+    pub fn retain_with<F>(&mut self, mut f: F)
+    where
+        F: FnMut(K, &V) -> bool,
+    {
+        self.storage.retain(|key, value| f(key, value));
+    }
+
+    /// Retains only the entries whose keys are contained in `allowed`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map, Set};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    ///     Third,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second(true), 2);
+    /// map.insert(MyKey::Second(false), 3);
+    /// map.insert(MyKey::Third, 4);
+    ///
+    /// let allowed = Set::from([MyKey::Second(false), MyKey::Third]);
+    /// map.retain_keys(&allowed);
+    ///
+    /// assert!(map.iter().eq([(MyKey::Second(false), &3), (MyKey::Third, &4)]));
+    /// ```
+    #[inline]
+    pub fn retain_keys(&mut self, allowed: &Set<K>) {
+        self.storage.retain_keys(allowed.as_storage());
+    }
+
+    /// Clears the map, removing all key-value pairs. Keeps the allocated memory
+    /// for reuse, including the capacity of any nested dynamic storage such as
+    /// the hash map of a `u32` field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     One,
+    ///     Two,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::One, "a");
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.storage.clear();
+    }
+
+    /// Clears the map, returning all key-value pairs as an iterator. Like
+    /// [`Map::clear`] this keeps the allocated memory for reuse, including the
+    /// capacity of any nested dynamic storage.
+    ///
+    /// The map is empty once the iterator is dropped, even if it wasn't fully
+    /// consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "hashbrown")] {
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(u32),
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second(7), 2);
+    ///
+    /// let capacity = map.stats().dynamic_capacity;
+    ///
+    /// assert!(map.drain().eq([(MyKey::First, 1), (MyKey::Second(7), 2)]));
+    /// assert!(map.is_empty());
+    /// assert_eq!(map.stats().dynamic_capacity, capacity);
+    ///
+    /// map.insert(MyKey::First, 3);
+    /// map.insert(MyKey::Second(8), 4);
+    ///
+    /// let mut drain = map.drain();
+    /// assert_eq!(drain.next(), Some((MyKey::First, 3)));
+    /// drop(drain);
+    ///
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    #[inline]
+    pub fn drain(&mut self) -> Drain<'_, K, V>
+    where
+        K::MapStorage<V>: DrainStorage<K, V>,
+    {
+        self.storage.drain()
+    }
+
+    /// Creates an iterator which removes and yields every entry for which
+    /// `filter` returns `true`, visiting entries in [iteration
+    /// order](Map#iteration-order).
+    ///
+    /// The filter is handed a mutable reference to the value of every entry,
+    /// so entries which are kept can be modified along the way. If the
+    /// iterator is dropped before it's exhausted, the entries which haven't
+    /// been visited are kept.
+    ///
+    /// This is only available for keys whose storage implements
+    /// [`ExtractIfStorage`], which includes every derived key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    ///     Third,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second(true), 2);
+    /// map.insert(MyKey::Third, 3);
+    ///
+    /// let odd = map.extract_if(|_, v| {
+    ///     *v *= 10;
+    ///     *v % 20 != 0
+    /// });
+    ///
+    /// assert!(odd.eq([(MyKey::First, 10), (MyKey::Third, 30)]));
+    /// assert!(map.into_iter().eq([(MyKey::Second(true), 20)]));
+    /// ```
+    #[inline]
+    pub fn extract_if<F>(&mut self, filter: F) -> ExtractIf<'_, K, V, F>
+    where
+        K::MapStorage<V>: ExtractIfStorage<K, V>,
+        F: FnMut(K, &mut V) -> bool,
+    {
+        ExtractIf::new(&mut self.storage, filter)
+    }
+
+    /// Clears every key which belongs to the same class as `key`, that is
+    /// every key which shares its [`variant_name`][Key::variant_name]. Like
+    /// [`Map::clear`] this keeps the allocated memory of the class for reuse.
+    ///
+    /// This is only available for keys whose storage implements
+    /// [`ClassStorage`], which includes every derived key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(Option<bool>),
+    ///     Third(bool),
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second(Some(true)), 2);
+    /// map.insert(MyKey::Second(None), 3);
+    /// map.insert(MyKey::Third(true), 4);
+    ///
+    /// map.clear_key_class(MyKey::Second(None));
+    /// assert!(map.keys().eq([MyKey::First, MyKey::Third(true)]));
+    ///
+    /// map.clear_key_class(MyKey::Third(false));
+    /// assert!(map.keys().eq([MyKey::First]));
+    /// ```
+    #[inline]
+    pub fn clear_key_class(&mut self, key: K)
+    where
+        K::MapStorage<V>: ClassStorage<K, V>,
+    {
+        self.storage.clear_class(key.class_index());
+    }
+
+    /// An iterator visiting the entries whose keys belong to the same class as
+    /// `key`, that is every key which shares its
+    /// [`variant_name`][Key::variant_name].
+    ///
+    /// For derived keys this only visits the storage of the variant of `key`,
+    /// without scanning the storage of any other variant.
+    ///
+    /// This is only available for keys whose storage implements
+    /// [`ClassStorage`], which includes every derived key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum Part {
+    ///     Head,
+    ///     Body,
+    /// }
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     Simple,
+    ///     Composite(Part),
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::Simple, 1);
+    /// map.insert(MyKey::Composite(Part::Head), 2);
+    /// map.insert(MyKey::Composite(Part::Body), 3);
+    ///
+    /// assert!(map.iter_group(MyKey::Composite(Part::Head)).eq([
+    ///     (MyKey::Composite(Part::Head), &2),
+    ///     (MyKey::Composite(Part::Body), &3),
+    /// ]));
+    /// assert!(map.iter_group(MyKey::Simple).eq([(MyKey::Simple, &1)]));
+    /// ```
+    #[inline]
+    pub fn iter_group(&self, key: K) -> GroupIter<'_, K, V>
+    where
+        K::MapStorage<V>: ClassStorage<K, V>,
+    {
+        GroupIter::new(&self.storage, key.class_index())
+    }
+
+    /// Returns the number of entries in the class selected by `group`.
+    ///
+    /// For derived keys this only inspects the storage of the selected
+    /// variant, rather than filtering every entry in the map.
+    ///
+    /// This is only available for keys whose storage implements
+    /// [`ClassStorage`], which includes every derived key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// #[key(groups)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second(true), 2);
+    /// map.insert(MyKey::Second(false), 3);
+    ///
+    /// assert_eq!(map.len_of(MyKeyGroup::First), 1);
+    /// assert_eq!(map.len_of(MyKeyGroup::Second), 2);
+    /// ```
+    #[inline]
+    pub fn len_of<G>(&self, group: G) -> usize
+    where
+        G: KeyGroup<Key = K>,
+        K::MapStorage<V>: ClassStorage<K, V>,
+    {
+        self.storage.class_len(group.class_index())
+    }
+
+    /// Clears every entry in the class selected by `group`. Like
+    /// [`Map::clear`] this keeps the allocated memory of the class for reuse.
+    ///
+    /// This is only available for keys whose storage implements
+    /// [`ClassStorage`], which includes every derived key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// #[key(groups)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second(true), 2);
+    /// map.insert(MyKey::Second(false), 3);
+    ///
+    /// map.clear_of(MyKeyGroup::Second);
+    /// assert!(map.keys().eq([MyKey::First]));
+    /// ```
+    #[inline]
+    pub fn clear_of<G>(&mut self, group: G)
+    where
+        G: KeyGroup<Key = K>,
+        K::MapStorage<V>: ClassStorage<K, V>,
+    {
+        self.storage.clear_class(group.class_index());
+    }
+
+    /// Validate the internal consistency of the storage of this map.
+    ///
+    /// This checks that the length of the map agrees with its iterators and
+    /// that every iterated element can be looked up, which helps catch bugs
+    /// in storage implementations early. The check is only performed in
+    /// builds with debug assertions enabled, and is a no-op otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if debug assertions are enabled and the storage is
+    /// inconsistent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First(true), 1);
+    /// map.insert(MyKey::Second, 2);
+    /// map.debug_assert_invariants();
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn debug_assert_invariants(&self) {
+        if cfg!(debug_assertions) {
+            self.storage.assert_invariants();
+        }
+    }
+
+    /// Returns true if the map contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// assert!(map.is_empty());
+    /// map.insert(MyKey::First, 1);
+    /// assert!(!map.is_empty());
+    /// ```
+    ///
+    /// An empty key:
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {}
+    ///
+    /// let map = Map::<MyKey, u32>::new();
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+
+    /// Gets the current length of a [`Map`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map: Map<MyKey, i32> = Map::new();
+    /// assert_eq!(map.len(), 0);
+    ///
+    /// map.insert(MyKey::First, 42);
+    /// assert_eq!(map.len(), 1);
+    ///
+    /// map.insert(MyKey::First, 42);
+    /// assert_eq!(map.len(), 1);
+    ///
+    /// map.remove(MyKey::First);
+    /// assert_eq!(map.len(), 0);
+    /// ```
+    ///
+    /// Using a composite key:
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    /// }
+    ///
+    /// let mut map: Map<MyKey, i32> = Map::new();
+    /// assert_eq!(map.len(), 0);
+    ///
+    /// map.insert(MyKey::First(true), 42);
+    /// assert_eq!(map.len(), 1);
+    ///
+    /// map.insert(MyKey::First(false), 42);
+    /// assert_eq!(map.len(), 2);
+    ///
+    /// map.remove(MyKey::First(true));
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.storage.len()
+    }
+
+    /// Collects occupancy [`Stats`] for the map, aggregated across the
+    /// storage of every variant of the key.
+    ///
+    /// This is only available for keys whose storage implements
+    /// [`StatsStorage`], which includes every derived key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "hashbrown")] {
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(Option<bool>),
+    ///     Third(u32),
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::Second(None), 1);
+    ///
+    /// let stats = map.stats();
+    /// assert_eq!(stats.capacity, 4);
+    /// assert_eq!(stats.len, 1);
+    /// assert_eq!(stats.dynamic_buckets, 1);
+    /// assert_eq!(stats.dynamic_capacity, 0);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn stats(&self) -> Stats
+    where
+        K::MapStorage<V>: StatsStorage<K, V>,
+    {
+        self.storage.stats()
+    }
+
+    /// Gets the given key’s corresponding [`Entry`] in the [`Map`] for in-place manipulation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     Even,
+    ///     Odd,
+    /// }
+    ///
+    /// let mut map: Map<MyKey, u32> = Map::new();
+    ///
+    /// for n in [3, 45, 3, 23, 2, 10, 59, 11, 51, 70] {
+    ///     map
+    ///         .entry(if n % 2 == 0 { MyKey::Even } else { MyKey::Odd })
+    ///         .and_modify(|x| *x += 1)
+    ///         .or_insert(1);
+    /// }
+    ///
+    /// assert_eq!(map.get(MyKey::Even), Some(&3));
+    /// assert_eq!(map.get(MyKey::Odd), Some(&7));
+    /// ```
+    ///
+    /// Using a composite key:
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    /// }
+    ///
+    /// let mut map: Map<MyKey, Vec<i32>> = Map::new();
+    ///
+    /// map.entry(MyKey::First(true)).or_default().push(1);
+    /// map.entry(MyKey::Second).or_insert_with(|| vec![2; 8]).truncate(4);
+    ///
+    /// assert_eq!(map.get(MyKey::First(true)), Some(&vec![1]));
+    /// assert_eq!(map.get(MyKey::Second), Some(&vec![2; 4]));
+    /// ```
+    #[inline]
+    pub fn entry(&mut self, key: K) -> Entry<'_, K::MapStorage<V>, K, V> {
+        let entry = K::MapStorage::entry(&mut self.storage, key);
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_lookup(key, matches!(entry, Entry::Occupied(..)));
+
+        entry
+    }
+
+    /// Gets the given key’s corresponding [`Entry`] in the [`Map`] for in-place
+    /// manipulation, taking the key by reference.
+    ///
+    /// This mirrors `entry_ref` in `hashbrown`. Composite keys are passed down
+    /// through the storage of their variants by reference, and are only
+    /// copied by the storage which ends up owning the key, like the one used
+    /// for `&'static str` payloads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     Even,
+    ///     Odd,
+    /// }
+    ///
+    /// let mut map: Map<MyKey, u32> = Map::new();
+    /// let keys = [MyKey::Even, MyKey::Odd, MyKey::Odd];
+    ///
+    /// for key in &keys {
+    ///     *map.entry_ref(key).or_default() += 1;
+    /// }
+    ///
+    /// assert_eq!(map.get(MyKey::Even), Some(&1));
+    /// assert_eq!(map.get(MyKey::Odd), Some(&2));
+    /// ```
+    ///
+    /// Using a composite key:
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First(Option<bool>),
+    ///     Second,
+    /// }
+    ///
+    /// let mut map: Map<MyKey, u32> = Map::new();
+    /// let keys = [MyKey::First(Some(true)), MyKey::Second, MyKey::First(Some(true))];
+    ///
+    /// for key in &keys {
+    ///     *map.entry_ref(key).or_default() += 1;
+    /// }
+    ///
+    /// assert_eq!(map.get(MyKey::First(Some(true))), Some(&2));
+    /// assert_eq!(map.entry_ref(&MyKey::Second).key(), MyKey::Second);
+    /// ```
+    #[inline]
+    pub fn entry_ref(&mut self, key: &K) -> Entry<'_, K::MapStorage<V>, K, V> {
+        let entry = K::MapStorage::entry_ref(&mut self.storage, key);
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_lookup(*key, matches!(entry, Entry::Occupied(..)));
+
+        entry
+    }
+
+    /// Returns an iterator over an [`Entry`] for every key in the domain of
+    /// `K`, in the same order as [`Map::iter`], whether it's occupied or not.
+    ///
+    /// This makes it possible to decide what to do with every key in one pass
+    /// without looking up each key separately through [`Map::entry`]. It's
+    /// only available for keys with a finite domain, whose storage implements
+    /// [`EntriesStorage`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::map::{Entry, OccupiedEntry, VacantEntry};
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    ///     Third,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::Second(true), 10);
+    ///
+    /// for entry in map.entries_mut() {
+    ///     match entry {
+    ///         Entry::Occupied(mut entry) => {
+    ///             *entry.get_mut() += 1;
+    ///         }
+    ///         Entry::Vacant(entry) => {
+    ///             if entry.key() != MyKey::Third {
+    ///                 entry.insert(0);
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// assert!(map.iter().eq([
+    ///     (MyKey::First, &0),
+    ///     (MyKey::Second(true), &11),
+    ///     (MyKey::Second(false), &0),
+    /// ]));
+    /// ```
+    #[inline]
+    pub fn entries_mut(&mut self) -> EntriesMut<'_, K, V>
+    where
+        K::MapStorage<V>: EntriesStorage<K, V>,
+    {
+        self.storage.entries_mut()
+    }
+
+    /// Converts the map into a [`TotalMap`] if it contains a value for every
+    /// key in the domain of `K`.
+    ///
+    /// Otherwise an [`Incomplete`] error is returned, which lists exactly
+    /// which keys are missing. It's only available for keys with a finite
+    /// domain, whose storage implements [`EntriesStorage`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 1);
+    ///
+    /// let error = map.try_into_total().unwrap_err();
+    /// assert_eq!(error.to_string(), "missing key `Second`");
+    ///
+    /// let mut map = error.into_map();
+    /// map.insert(MyKey::Second, 2);
+    ///
+    /// let map = map.try_into_total().unwrap();
+    /// assert_eq!(map[MyKey::Second], 2);
+    /// ```
+    pub fn try_into_total(mut self) -> Result<TotalMap<K, V>, Incomplete<K, V>>
+    where
+        K::MapStorage<V>: EntriesStorage<K, V>,
+    {
+        let mut missing = Set::new();
+
+        for entry in self.entries_mut() {
+            if let Entry::Vacant(entry) = entry {
+                missing.insert(entry.key());
+            }
+        }
+
+        if missing.is_empty() {
+            return Ok(TotalMap::new_unchecked(self));
+        }
+
+        Err(Incomplete::new(self, missing))
+    }
+
+    /// Converts the map into a [`TotalMap`], panicking with the given message
+    /// if any key is missing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some key is missing from the map, with a message which
+    /// includes `msg` followed by the name of every missing key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map = Map::from([(MyKey::First, 1), (MyKey::Second, 2)]);
+    /// let map = map.expect_all("all keys should be configured");
+    /// assert_eq!(map[MyKey::First], 1);
+    /// ```
+    ///
+    /// ```should_panic
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map = Map::from([(MyKey::First, 1)]);
+    /// map.expect_all("all keys should be configured");
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn expect_all(self, msg: &str) -> TotalMap<K, V>
+    where
+        K::MapStorage<V>: EntriesStorage<K, V>,
+    {
+        match self.try_into_total() {
+            Ok(map) => map,
+            Err(error) => incomplete(msg, &error),
+        }
+    }
+
+    /// Converts the map into a [`TotalMap`], panicking if any key is missing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some key is missing from the map, with a message which
+    /// includes the name of every missing key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map = Map::from([(MyKey::First, 1), (MyKey::Second, 2)]);
+    /// let map = map.unwrap_all();
+    /// assert_eq!(map[MyKey::Second], 2);
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn unwrap_all(self) -> TotalMap<K, V>
+    where
+        K::MapStorage<V>: EntriesStorage<K, V>,
+    {
+        match self.try_into_total() {
+            Ok(map) => map,
+            Err(error) => incomplete(core::any::type_name::<Map<K, V>>(), &error),
+        }
+    }
+
+    /// Moves all key-value pairs from `other` into this map.
+    ///
+    /// If a key is present in both maps, `resolve` is called with the key, a
+    /// mutable reference to the existing value and the value from `other`,
+    /// allowing the caller to decide how the conflict is resolved. Keys which
+    /// are only present in `other` are inserted as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    ///     Third,
+    /// }
+    ///
+    /// let mut a = Map::from_iter([(MyKey::First, 1), (MyKey::Second, 2)]);
+    /// let b = Map::from_iter([(MyKey::Second, 3), (MyKey::Third, 4)]);
+    ///
+    /// let mut conflicts = Vec::new();
+    ///
+    /// a.merge_from(b, |key, existing, value| {
+    ///     conflicts.push(key);
+    ///     *existing = (*existing).max(value);
+    /// });
+    ///
+    /// assert_eq!(conflicts, [MyKey::Second]);
+    /// assert!(a.iter().eq([(MyKey::First, &1), (MyKey::Second, &3), (MyKey::Third, &4)]));
+    /// ```
+    ///
+    /// Using a composite key:
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    /// }
+    ///
+    /// let mut a = Map::from_iter([(MyKey::First(true), vec![1]), (MyKey::Second, vec![2])]);
+    /// let b = Map::from_iter([(MyKey::First(true), vec![3]), (MyKey::First(false), vec![4])]);
+    ///
+    /// a.merge_from(b, |_, existing, value| existing.extend(value));
+    ///
+    /// assert_eq!(a.get(MyKey::First(true)), Some(&vec![1, 3]));
+    /// assert_eq!(a.get(MyKey::First(false)), Some(&vec![4]));
+    /// assert_eq!(a.get(MyKey::Second), Some(&vec![2]));
+    /// ```
+    #[inline]
+    pub fn merge_from<F>(&mut self, other: Map<K, V>, mut resolve: F)
+    where
+        F: FnMut(K, &mut V, V),
+    {
+        for (key, value) in other {
+            match self.entry(key) {
+                Entry::Occupied(mut entry) => {
+                    resolve(key, entry.get_mut(), value);
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                }
+            }
+        }
+    }
+
+    /// Combine this map with `other` into a map of pairs, containing only the
+    /// keys which are present in both maps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum Direction {
+    ///     North,
+    ///     East,
+    ///     South,
+    ///     West,
+    /// }
+    ///
+    /// let speed = Map::from_iter([(Direction::North, 2), (Direction::East, 1), (Direction::West, 3)]);
+    /// let blocked = Map::from_iter([(Direction::North, false), (Direction::South, true), (Direction::West, true)]);
+    ///
+    /// let map = speed.zip(blocked);
+    /// assert!(map.iter().eq([(Direction::North, &(2, false)), (Direction::West, &(3, true))]));
+    /// ```
+    #[inline]
+    pub fn zip<W>(self, other: Map<K, W>) -> Map<K, (V, W)> {
+        self.zip_with(other, |_, a, b| (a, b))
+    }
+
+    /// Combine this map with `other` by calling `func` with the key and both
+    /// values for every key which is present in both maps, and collecting the
+    /// results into a new map.
+    ///
+    /// Keys which are only present in one of the maps are dropped together
+    /// with their values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum Direction {
+    ///     North,
+    ///     East,
+    ///     South,
+    ///     West,
+    /// }
+    ///
+    /// let base = Map::from_iter([(Direction::North, 2), (Direction::East, 1), (Direction::West, 3)]);
+    /// let bonus = Map::from_iter([(Direction::North, 10), (Direction::South, 20), (Direction::West, 30)]);
+    ///
+    /// let map = base.zip_with(bonus, |_, base, bonus| base + bonus);
+    /// assert!(map.iter().eq([(Direction::North, &12), (Direction::West, &33)]));
+    /// ```
+    ///
+    /// Using a composite key:
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    /// }
+    ///
+    /// let names = Map::from_iter([(MyKey::First(true), "a"), (MyKey::First(false), "b")]);
+    /// let counts = Map::from_iter([(MyKey::First(false), 2), (MyKey::Second, 3)]);
+    ///
+    /// let map = names.zip_with(counts, |_, name, count| name.repeat(count));
+    /// assert!(map.into_iter().eq([(MyKey::First(false), String::from("bb"))]));
+    /// ```
+    #[inline]
+    pub fn zip_with<W, U, F>(self, mut other: Map<K, W>, mut func: F) -> Map<K, U>
+    where
+        F: FnMut(K, V, W) -> U,
+    {
+        let mut output = Map::<K, U>::new();
+
+        for (key, value) in self.storage.into_iter() {
+            if let Some(other) = other.storage.remove(key) {
+                output.storage.insert(key, func(key, value, other));
+            }
+        }
+
+        output
+    }
+
+    /// Serialize only the entries whose keys are in `keys`, in the same format
+    /// as the [`Serialize`] implementation of [`Map`].
+    ///
+    /// Keys in `keys` which are not present in the map are skipped. Together
+    /// with [`Map::deserialize_update`] this can be used to only transfer the
+    /// entries which have changed.
+    ///
+    /// This is only available when the `serde` feature is enabled.
+    ///
+    /// [`Serialize`]: serde::Serialize
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Map, Set};
+    ///
+    /// let map = Map::from_iter([(true, 1), (false, 2)]);
+    /// let dirty = Set::from([false]);
+    ///
+    /// let mut out = Vec::new();
+    /// map.serialize_subset(&dirty, &mut serde_json::Serializer::new(&mut out))?;
+    /// assert_eq!(out, br#"{"false":2}"#);
+    /// # Ok::<_, serde_json::Error>(())
+    /// ```
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn serialize_subset<S>(&self, keys: &Set<K>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: serde::Serialize,
+        V: serde::Serialize,
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap as _;
+
+        let len = keys.iter().filter(|&k| self.contains_key(k)).count();
+        let mut map = serializer.serialize_map(Some(len))?;
+
+        for key in keys.iter() {
+            if let Some(value) = self.get(key) {
+                map.serialize_entry(&key, value)?;
+            }
+        }
+
+        map.end()
+    }
+
+    /// Deserialize a map and insert its entries into this map, replacing the
+    /// values of keys which are already present.
+    ///
+    /// Keys which are not mentioned by the deserializer are left untouched,
+    /// unlike the [`Deserialize`] implementation of [`Map`] which always
+    /// produces a new map.
+    ///
+    /// This is only available when the `serde` feature is enabled.
+    ///
+    /// [`Deserialize`]: serde::Deserialize
+    ///
+    /// # Errors
+    ///
+    /// Errors if the deserializer fails. Entries which were deserialized before
+    /// the error are still inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::Map;
+    ///
+    /// let mut map = Map::from_iter([(true, 1), (false, 2)]);
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#"{"false":20}"#);
+    /// map.deserialize_update(&mut de)?;
+    ///
+    /// assert_eq!(map.get(true), Some(&1));
+    /// assert_eq!(map.get(false), Some(&20));
+    /// # Ok::<_, serde_json::Error>(())
+    /// ```
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn deserialize_update<'de, D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        K: serde::Deserialize<'de>,
+        V: serde::Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(UpdateVisitor(self))
+    }
+
+    /// Convert the keys of this map into the key `K2`, moving every entry into
+    /// storage for the new key.
+    ///
+    /// This requires the conversion to be declared injective through
+    /// [`MapsTo`][crate::migrate::MapsTo], which is implemented with
+    /// `#[key(maps_to(K2))]`. For conversions which might drop or merge keys,
+    /// see [`migrate::remap`][crate::migrate::remap].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// #[key(maps_to(New))]
+    /// enum Old {
+    ///     First,
+    ///     Second(bool),
+    /// }
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum New {
+    ///     First,
+    ///     Second(bool),
+    ///     Third,
+    /// }
+    ///
+    /// impl From<Old> for New {
+    ///     fn from(value: Old) -> Self {
+    ///         match value {
+    ///             Old::First => New::First,
+    ///             Old::Second(value) => New::Second(value),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let old = Map::from_iter([(Old::First, 1), (Old::Second(true), 2)]);
+    /// let new: Map<New, _> = old.map_keys_into();
+    ///
+    /// assert!(new.iter().eq([(New::First, &1), (New::Second(true), &2)]));
+    /// ```
+    #[inline]
+    pub fn map_keys_into<K2>(self) -> Map<K2, V>
+    where
+        K: crate::migrate::MapsTo<K2>,
+        K2: Key,
+    {
+        let mut storage = K2::MapStorage::empty();
+
+        for (key, value) in self.storage.into_iter() {
+            storage.insert(key.into(), value);
+        }
+
+        Map { storage }
+    }
+}
+
+/// [`Clone`] implementation for a [`Map`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum MyKey {
+///     First(bool),
+///     Second,
+/// }
+///
+/// let mut a = Map::new();
+/// a.insert(MyKey::First(true), 1);
+/// let mut b = a.clone();
+/// b.insert(MyKey::Second, 2);
+///
+/// assert_ne!(a, b);
+///
+/// assert_eq!(a.get(MyKey::First(true)), Some(&1));
+/// assert_eq!(a.get(MyKey::Second), None);
+///
+/// assert_eq!(b.get(MyKey::First(true)), Some(&1));
+/// assert_eq!(b.get(MyKey::Second), Some(&2));
+/// ```
+impl<K, V> Clone for Map<K, V>
+where
+    K: CloneableKey<V>,
+{
+    #[inline]
+    fn clone(&self) -> Map<K, V> {
+        Map {
+            storage: K::__clone_storage(&self.storage),
+        }
+    }
+}
+
+/// The [`Copy`] implementation for a [`Map`] depends on its [`Key`]. If the
+/// derived key only consists of unit variants the corresponding [`Map`] will be
+/// [`Copy`] as well.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let mut a = Map::new();
+/// a.insert(MyKey::First, 1);
+/// let mut b = a;
+/// b.insert(MyKey::Second, 2);
+///
+/// assert_ne!(a, b);
+///
+/// assert_eq!(a.get(MyKey::First), Some(&1));
+/// assert_eq!(a.get(MyKey::Second), None);
+///
+/// assert_eq!(b.get(MyKey::First), Some(&1));
+/// assert_eq!(b.get(MyKey::Second), Some(&2));
+/// ```
+impl<K, V> Copy for Map<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: Copy,
+{
+}
+
+/// The [`Default`] implementation for a [`Map`] produces an empty map.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let a = Map::<MyKey, u32>::default();
+/// let b = Map::<MyKey, u32>::new();
+///
+/// assert_eq!(a, b);
+/// ```
+impl<K, V> Default for Map<K, V>
+where
+    K: Key,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The [`Debug`][fmt::Debug] implementation for a [`Map`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let mut a = Map::new();
+/// a.insert(MyKey::First, 42);
+///
+/// assert_eq!("{First: 42}", format!("{:?}", a));
+/// ```
+///
+/// The alternate format `{:#?}` groups entries by the variant of their key,
+/// as returned by [`Key::variant_name`]:
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum Part {
+///     A,
+///     B,
+/// }
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum MyKey {
+///     First(Option<Part>),
+///     Second,
+/// }
+///
+/// let mut a = Map::new();
+/// a.insert(MyKey::First(Some(Part::A)), 1);
+/// a.insert(MyKey::First(None), 2);
+/// a.insert(MyKey::Second, 3);
+///
+/// let expected = r#"{
+///     First: {
+///         First(Some(A)): 1,
+///         First(None): 2,
+///     },
+///     Second: {
+///         Second: 3,
+///     },
+/// }"#;
+///
+/// assert_eq!(format!("{:#?}", a), expected);
+/// ```
+impl<K, V> fmt::Debug for Map<K, V>
+where
+    K: Key + fmt::Debug,
+    V: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return crate::debug::grouped_map(self, f);
+        }
+
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// [`PartialEq`] implementation for a [`Map`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let mut a = Map::new();
+/// a.insert(MyKey::First, 42);
+/// // Note: `a` is Copy since it's using a simple key.
+/// let mut b = a;
+///
+/// assert_eq!(a, b);
+///
+/// b.insert(MyKey::Second, 42);
+/// assert_ne!(a, b);
+/// ```
+///
+/// Using a composite key:
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum MyKey {
+///     First(bool),
+///     Second,
+/// }
+///
+/// let mut a = Map::new();
+/// a.insert(MyKey::First(true), 42);
+/// let mut b = a.clone();
+///
+/// assert_eq!(a, b);
+///
+/// b.insert(MyKey::Second, 42);
+/// assert_ne!(a, b);
+/// ```
+impl<K, V> PartialEq for Map<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.storage == other.storage
+    }
+}
+
+impl<K, V> Eq for Map<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: Eq,
+{
+}
+
+/// [`Hash`] implementation for a [`Set`].
+///
+/// [`Set`]: crate::Set
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Hash, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let mut a = Map::new();
+/// a.insert(MyKey::First, 1);
+///
+/// let mut set = HashSet::new();
+/// set.insert(a);
+/// ```
+///
+/// Using a composite key:
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Hash, Key)]
+/// enum MyKey {
+///     First(bool),
+///     Second,
+/// }
+///
+/// let mut a = Map::new();
+/// a.insert(MyKey::First(true), 1);
+///
+/// // TODO: support this
+/// // let mut set = HashSet::new();
+/// // set.insert(a);
+/// ```
+impl<K, V> Hash for Map<K, V>
+where
+    K: HashableKey<V>,
+{
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        K::__hash_storage(&self.storage, state);
+    }
+}
+
+/// [`PartialOrd`] implementation for a [`Map`].
+///
+/// For more details on ordering, see the [`Key`] documentation.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Hash, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+///     Third,
+/// }
+///
+/// let mut a = Map::new();
+/// a.insert(MyKey::First, 1);
+///
+/// let mut b = Map::new();
+/// b.insert(MyKey::Third, 1);
+///
+/// assert!(a < b);
+///
+/// let mut empty = Map::new();
+/// assert!(empty < a);
+/// assert!(empty < b);
+/// ```
+///
+/// Using a composite key:
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Hash, Key)]
+/// enum MyKey {
+///     First(bool),
+///     Second,
+/// }
+///
+/// let mut a = Map::new();
+/// a.insert(MyKey::First(true), 1);
+///
+/// let mut b = Map::new();
+/// b.insert(MyKey::Second, 1);
+///
+/// // TODO: support this
+/// // assert!(a < b);
+/// ```
+impl<K, V> PartialOrd for Map<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.storage.partial_cmp(&other.storage)
+    }
+
+    #[inline]
+    fn lt(&self, other: &Self) -> bool {
+        self.storage.lt(&other.storage)
+    }
+
+    #[inline]
+    fn le(&self, other: &Self) -> bool {
+        self.storage.le(&other.storage)
+    }
+
+    #[inline]
+    fn gt(&self, other: &Self) -> bool {
+        self.storage.gt(&other.storage)
+    }
+
+    #[inline]
+    fn ge(&self, other: &Self) -> bool {
+        self.storage.ge(&other.storage)
+    }
+}
+
+/// [`Ord`] implementation for a [`Map`].
+///
+/// For more details on ordering, see the [`Key`] documentation.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Hash, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let mut a = Map::new();
+/// a.insert(MyKey::First, 1);
+///
+/// let mut b = Map::new();
+/// b.insert(MyKey::Second, 1);
+///
+/// let mut list = vec![b, a];
+/// list.sort();
+///
+/// assert_eq!(list, [a, b]);
+/// ```
+///
+/// Using a composite key:
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Hash, Key)]
+/// enum MyKey {
+///     First(bool),
+///     Second,
+/// }
+///
+/// let mut a = Map::new();
+/// a.insert(MyKey::First(true), 1);
+///
+/// let mut b = Map::new();
+/// b.insert(MyKey::Second, 1);
+///
+/// // TODO: support this
+/// // let mut list = vec![a, b];
+/// // list.sort();
+/// ```
+impl<K, V> Ord for Map<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: Ord,
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.storage.cmp(&other.storage)
+    }
+
+    #[inline]
+    fn max(self, other: Self) -> Self {
+        Self {
+            storage: self.storage.max(other.storage),
+        }
+    }
+
+    #[inline]
+    fn min(self, other: Self) -> Self {
+        Self {
+            storage: self.storage.min(other.storage),
+        }
+    }
+
+    #[inline]
+    fn clamp(self, min: Self, max: Self) -> Self {
+        Self {
+            storage: self.storage.clamp(min.storage, max.storage),
+        }
+    }
+}
+
+/// [`IntoIterator`] implementation which uses [`Map::iter`]. See its
+/// documentation for more.
+impl<'a, K, V> IntoIterator for &'a Map<K, V>
+where
+    K: Key,
+{
+    type Item = (K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
     #[inline]
-    pub fn entry(&mut self, key: K) -> Entry<'_, K::MapStorage<V>, K, V> {
-        K::MapStorage::entry(&mut self.storage, key)
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
-/// [`Clone`] implementation for a [`Map`].
-///
-/// # Examples
-///
-/// ```
-/// use fixed_map::{Key, Map};
-///
-/// #[derive(Debug, Clone, Copy, Key)]
-/// enum MyKey {
-///     First(bool),
-///     Second,
-/// }
-///
-/// let mut a = Map::new();
-/// a.insert(MyKey::First(true), 1);
-/// let mut b = a.clone();
-/// b.insert(MyKey::Second, 2);
-///
-/// assert_ne!(a, b);
-///
-/// assert_eq!(a.get(MyKey::First(true)), Some(&1));
-/// assert_eq!(a.get(MyKey::Second), None);
-///
-/// assert_eq!(b.get(MyKey::First(true)), Some(&1));
-/// assert_eq!(b.get(MyKey::Second), Some(&2));
-/// ```
-impl<K, V> Clone for Map<K, V>
+/// [`IntoIterator`] implementation which uses [`Map::iter_mut`]. See its
+/// documentation for more.
+impl<'a, K, V> IntoIterator for &'a mut Map<K, V>
 where
     K: Key,
-    K::MapStorage<V>: Clone,
 {
+    type Item = (K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
     #[inline]
-    fn clone(&self) -> Map<K, V> {
-        Map {
-            storage: self.storage.clone(),
-        }
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
     }
 }
 
-/// The [`Copy`] implementation for a [`Map`] depends on its [`Key`]. If the
-/// derived key only consists of unit variants the corresponding [`Map`] will be
-/// [`Copy`] as well.
+/// Produce an owning iterator visiting all key-value pairs of the [`Map`] in an
+/// arbitrary order. The iterator element type is `(K, V)`.
 ///
 /// # Examples
 ///
 /// ```
 /// use fixed_map::{Key, Map};
 ///
-/// #[derive(Debug, Clone, Copy, Key)]
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
 /// enum MyKey {
 ///     First,
 ///     Second,
+///     Third,
 /// }
 ///
-/// let mut a = Map::new();
-/// a.insert(MyKey::First, 1);
-/// let mut b = a;
-/// b.insert(MyKey::Second, 2);
-///
-/// assert_ne!(a, b);
+/// let mut map = Map::new();
+/// map.insert(MyKey::First, 1);
+/// map.insert(MyKey::Third, 3);
 ///
-/// assert_eq!(a.get(MyKey::First), Some(&1));
-/// assert_eq!(a.get(MyKey::Second), None);
+/// let mut it = map.into_iter();
+/// assert_eq!(it.next(), Some((MyKey::First, 1)));
+/// assert_eq!(it.next(), Some((MyKey::Third, 3)));
+/// assert_eq!(it.next(), None);
 ///
-/// assert_eq!(b.get(MyKey::First), Some(&1));
-/// assert_eq!(b.get(MyKey::Second), Some(&2));
+/// let mut it = map.into_iter().rev();
+/// assert_eq!(it.next(), Some((MyKey::Third, 3)));
+/// assert_eq!(it.next(), Some((MyKey::First, 1)));
+/// assert_eq!(it.next(), None);
 /// ```
-impl<K, V> Copy for Map<K, V>
-where
-    K: Key,
-    K::MapStorage<V>: Copy,
-{
-}
-
-/// The [`Default`] implementation for a [`Map`] produces an empty map.
 ///
-/// # Examples
+/// Into iterator with a composite key:
 ///
 /// ```
 /// use fixed_map::{Key, Map};
 ///
-/// #[derive(Debug, Clone, Copy, Key)]
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
 /// enum MyKey {
-///     First,
+///     First(bool),
 ///     Second,
+///     Third,
 /// }
 ///
-/// let a = Map::<MyKey, u32>::default();
-/// let b = Map::<MyKey, u32>::new();
+/// let mut map = Map::<_, u32>::new();
+/// map.insert(MyKey::First(false), 1);
+/// map.insert(MyKey::Third, 3);
 ///
-/// assert_eq!(a, b);
+/// let mut it = map.into_iter();
+/// assert_eq!(it.next(), Some((MyKey::First(false), 1)));
+/// assert_eq!(it.next(), Some((MyKey::Third, 3)));
+/// assert_eq!(it.next(), None);
+///
+/// let mut it = map.into_iter().rev();
+/// assert_eq!(it.next(), Some((MyKey::Third, 3)));
+/// assert_eq!(it.next(), Some((MyKey::First(false), 1)));
+/// assert_eq!(it.next(), None);
 /// ```
-impl<K, V> Default for Map<K, V>
+impl<K, V> IntoIterator for Map<K, V>
 where
     K: Key,
 {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
     #[inline]
-    fn default() -> Self {
-        Self::new()
+    fn into_iter(self) -> Self::IntoIter {
+        self.storage.into_iter()
     }
 }
 
-/// The [`Debug`][fmt::Debug] implementation for a [`Map`].
+/// A simple [`FromIterator`] implementation for [`Map`].
 ///
-/// # Examples
+/// # Example
 ///
 /// ```
 /// use fixed_map::{Key, Map};
@@ -949,247 +3504,292 @@ where
 ///     Second,
 /// }
 ///
-/// let mut a = Map::new();
-/// a.insert(MyKey::First, 42);
+/// let v = vec![(MyKey::First, 1), (MyKey::Second, 2), (MyKey::First, 3)];
+/// let m: Map<_, u8> = v.into_iter().collect();
 ///
-/// assert_eq!("{First: 42}", format!("{:?}", a));
+/// let mut n = Map::new();
+/// n.insert(MyKey::Second, 2);
+/// n.insert(MyKey::First, 3);
+///
+/// assert_eq!(m, n);
 /// ```
-impl<K, V> fmt::Debug for Map<K, V>
+impl<K, V> FromIterator<(K, V)> for Map<K, V>
 where
-    K: Key + fmt::Debug,
-    V: fmt::Debug,
+    K: Key,
 {
     #[inline]
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_map().entries(self.iter()).finish()
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
     }
 }
 
-/// [`PartialEq`] implementation for a [`Map`].
+/// Inserts every key-value pair of an iterator into the [`Map`], replacing
+/// the values of keys which are already present.
 ///
 /// # Examples
 ///
 /// ```
 /// use fixed_map::{Key, Map};
 ///
-/// #[derive(Debug, Clone, Copy, Key)]
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
 /// enum MyKey {
 ///     First,
 ///     Second,
+///     Third,
 /// }
 ///
-/// let mut a = Map::new();
-/// a.insert(MyKey::First, 42);
-/// // Note: `a` is Copy since it's using a simple key.
-/// let mut b = a;
-///
-/// assert_eq!(a, b);
-///
-/// b.insert(MyKey::Second, 42);
-/// assert_ne!(a, b);
-/// ```
-///
-/// Using a composite key:
-///
-/// ```
-/// use fixed_map::{Key, Map};
-///
-/// #[derive(Debug, Clone, Copy, Key)]
-/// enum MyKey {
-///     First(bool),
-///     Second,
-/// }
-///
-/// let mut a = Map::new();
-/// a.insert(MyKey::First(true), 42);
-/// let mut b = a.clone();
-///
-/// assert_eq!(a, b);
+/// let mut map = Map::from_iter([(MyKey::First, 1), (MyKey::Second, 2)]);
+/// map.extend([(MyKey::Second, 20), (MyKey::Third, 30)]);
 ///
-/// b.insert(MyKey::Second, 42);
-/// assert_ne!(a, b);
+/// assert!(map.into_iter().eq([(MyKey::First, 1), (MyKey::Second, 20), (MyKey::Third, 30)]));
 /// ```
-impl<K, V> PartialEq for Map<K, V>
+impl<K, V> Extend<(K, V)> for Map<K, V>
 where
     K: Key,
-    K::MapStorage<V>: PartialEq,
 {
     #[inline]
-    fn eq(&self, other: &Self) -> bool {
-        self.storage == other.storage
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = (K, V)>,
+    {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
     }
 }
 
-impl<K, V> Eq for Map<K, V>
-where
-    K: Key,
-    K::MapStorage<V>: Eq,
-{
-}
-
-/// [`Hash`] implementation for a [`Set`].
-///
-/// [`Set`]: crate::Set
+/// Inserts copies of every borrowed key-value pair of an iterator into the
+/// [`Map`], replacing the values of keys which are already present.
 ///
 /// # Examples
 ///
 /// ```
-/// use std::collections::HashSet;
-///
 /// use fixed_map::{Key, Map};
 ///
-/// #[derive(Debug, Clone, Copy, Hash, Key)]
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
 /// enum MyKey {
 ///     First,
 ///     Second,
 /// }
 ///
-/// let mut a = Map::new();
-/// a.insert(MyKey::First, 1);
-///
-/// let mut set = HashSet::new();
-/// set.insert(a);
-/// ```
-///
-/// Using a composite key:
-///
-/// ```
-/// use std::collections::HashSet;
-///
-/// use fixed_map::{Key, Map};
-///
-/// #[derive(Debug, Clone, Copy, Hash, Key)]
-/// enum MyKey {
-///     First(bool),
-///     Second,
-/// }
+/// let pairs = vec![(MyKey::First, 1), (MyKey::Second, 2)];
 ///
-/// let mut a = Map::new();
-/// a.insert(MyKey::First(true), 1);
+/// let mut map = Map::from_iter([(MyKey::First, 10)]);
+/// map.extend(pairs.iter().map(|(k, v)| (k, v)));
 ///
-/// // TODO: support this
-/// // let mut set = HashSet::new();
-/// // set.insert(a);
+/// assert!(map.into_iter().eq([(MyKey::First, 1), (MyKey::Second, 2)]));
 /// ```
-impl<K, V> Hash for Map<K, V>
+impl<'a, K, V> Extend<(&'a K, &'a V)> for Map<K, V>
 where
     K: Key,
-    K::MapStorage<V>: Hash,
+    V: Copy,
 {
     #[inline]
-    fn hash<H>(&self, state: &mut H)
+    fn extend<T>(&mut self, iter: T)
     where
-        H: Hasher,
+        T: IntoIterator<Item = (&'a K, &'a V)>,
     {
-        self.storage.hash(state);
+        for (&k, &v) in iter {
+            self.insert(k, v);
+        }
     }
 }
 
-/// [`PartialOrd`] implementation for a [`Map`].
+impl<K, V, const N: usize> From<[(K, V); N]> for Map<K, V>
+where
+    K: Key,
+{
+    /// Construct a map from an array of key-value pairs. If a key occurs more
+    /// than once, the last value is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, Key, PartialEq)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    /// }
+    ///
+    /// let map1 = Map::from([(MyKey::Second(true), 2), (MyKey::First, 1), (MyKey::First, 3)]);
+    /// let map2: Map<_, _> = [(MyKey::First, 3), (MyKey::Second(true), 2)].into();
+    ///
+    /// assert_eq!(map1, map2);
+    /// assert!(map1.into_iter().eq([(MyKey::First, 3), (MyKey::Second(true), 2)]));
+    /// ```
+    #[inline]
+    fn from(arr: [(K, V); N]) -> Self {
+        Self::from_iter(arr)
+    }
+}
+
+/// Get a reference to the value of a key through indexing, like with
+/// [`Map::get`].
 ///
-/// For more details on ordering, see the [`Key`] documentation.
+/// # Panics
+///
+/// Panics if the key is not present in the map.
 ///
 /// # Examples
 ///
 /// ```
 /// use fixed_map::{Key, Map};
 ///
-/// #[derive(Debug, Clone, Copy, Hash, Key)]
+/// #[derive(Clone, Copy, Key)]
 /// enum MyKey {
 ///     First,
-///     Second,
-///     Third,
+///     Second(bool),
 /// }
 ///
-/// let mut a = Map::new();
-/// a.insert(MyKey::First, 1);
+/// let map = Map::from([(MyKey::First, 1), (MyKey::Second(true), 2)]);
 ///
-/// let mut b = Map::new();
-/// b.insert(MyKey::Third, 1);
+/// assert_eq!(map[MyKey::First], 1);
+/// assert_eq!(map[MyKey::Second(true)], 2);
+/// ```
+///
+/// Indexing a key which is not present panics:
+///
+/// ```should_panic
+/// use fixed_map::{Key, Map};
 ///
-/// assert!(a < b);
+/// #[derive(Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second(bool),
+/// }
 ///
-/// let mut empty = Map::new();
-/// assert!(empty < a);
-/// assert!(empty < b);
+/// let map = Map::from([(MyKey::First, 1)]);
+/// let _ = map[MyKey::Second(false)];
 /// ```
+impl<K, V> Index<K> for Map<K, V>
+where
+    K: Key,
+{
+    type Output = V;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, key: K) -> &V {
+        match self.get(key) {
+            Some(value) => value,
+            None => missing_key::<K, V>(key),
+        }
+    }
+}
+
+/// Get a mutable reference to the value of a key through indexing, like with
+/// [`Map::get_mut`].
 ///
-/// Using a composite key:
+/// # Panics
+///
+/// Panics if the key is not present in the map. Use [`Map::entry`] to insert
+/// a value for a key which might be missing.
+///
+/// # Examples
 ///
 /// ```
 /// use fixed_map::{Key, Map};
 ///
-/// #[derive(Debug, Clone, Copy, Hash, Key)]
+/// #[derive(Clone, Copy, Key)]
 /// enum MyKey {
-///     First(bool),
-///     Second,
+///     North,
+///     Other(bool),
 /// }
 ///
-/// let mut a = Map::new();
-/// a.insert(MyKey::First(true), 1);
+/// let mut map = Map::from([(MyKey::North, 1), (MyKey::Other(true), 2)]);
 ///
-/// let mut b = Map::new();
-/// b.insert(MyKey::Second, 1);
+/// map[MyKey::North] += 1;
+/// map[MyKey::Other(true)] *= 10;
 ///
-/// // TODO: support this
-/// // assert!(a < b);
+/// assert_eq!(map.get(MyKey::North), Some(&2));
+/// assert_eq!(map.get(MyKey::Other(true)), Some(&20));
 /// ```
-impl<K, V> PartialOrd for Map<K, V>
+impl<K, V> IndexMut<K> for Map<K, V>
 where
     K: Key,
-    K::MapStorage<V>: PartialOrd,
 {
     #[inline]
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.storage.partial_cmp(&other.storage)
-    }
-
-    #[inline]
-    fn lt(&self, other: &Self) -> bool {
-        self.storage.lt(&other.storage)
+    #[track_caller]
+    fn index_mut(&mut self, key: K) -> &mut V {
+        match self.get_mut(key) {
+            Some(value) => value,
+            None => missing_key::<K, V>(key),
+        }
     }
+}
 
-    #[inline]
-    fn le(&self, other: &Self) -> bool {
-        self.storage.le(&other.storage)
-    }
+/// Panic since the given key was indexed but is not present in the map.
+#[cold]
+#[inline(never)]
+#[track_caller]
+fn missing_key<K, V>(key: K) -> !
+where
+    K: Key,
+{
+    panic!(
+        "{}: key `{}` is not present",
+        core::any::type_name::<Map<K, V>>(),
+        KeyName(key)
+    )
+}
 
-    #[inline]
-    fn gt(&self, other: &Self) -> bool {
-        self.storage.gt(&other.storage)
-    }
+/// Panic since the start of a range is declared after its end.
+#[cold]
+#[inline(never)]
+#[track_caller]
+fn range_out_of_order<K, V>() -> !
+where
+    K: Key,
+{
+    panic!(
+        "{}: range start is declared after range end",
+        core::any::type_name::<Map<K, V>>(),
+    )
+}
 
-    #[inline]
-    fn ge(&self, other: &Self) -> bool {
-        self.storage.ge(&other.storage)
-    }
+/// Panic since keys were missing when converting into a total map.
+#[cold]
+#[inline(never)]
+#[track_caller]
+fn incomplete<K, V>(msg: &str, error: &Incomplete<K, V>) -> !
+where
+    K: Key,
+{
+    panic!("{msg}: {error}")
 }
 
-/// [`Ord`] implementation for a [`Map`].
+/// Sums a sequence of maps by merging them key-wise, adding together values
+/// which are present for the same key.
 ///
-/// For more details on ordering, see the [`Key`] documentation.
+/// This is useful when reducing partial per-thread counters.
 ///
 /// # Examples
 ///
 /// ```
 /// use fixed_map::{Key, Map};
 ///
-/// #[derive(Debug, Clone, Copy, Hash, Key)]
+/// #[derive(Debug, Clone, Copy, Key)]
 /// enum MyKey {
 ///     First,
 ///     Second,
+///     Third,
 /// }
 ///
-/// let mut a = Map::new();
-/// a.insert(MyKey::First, 1);
-///
-/// let mut b = Map::new();
-/// b.insert(MyKey::Second, 1);
+/// let a = Map::from_iter([(MyKey::First, 1), (MyKey::Second, 2)]);
+/// let b = Map::from_iter([(MyKey::Second, 3), (MyKey::Third, 4)]);
 ///
-/// let mut list = vec![b, a];
-/// list.sort();
+/// let total: Map<_, u32> = [a, b].into_iter().sum();
 ///
-/// assert_eq!(list, [a, b]);
+/// assert_eq!(total.get(MyKey::First), Some(&1));
+/// assert_eq!(total.get(MyKey::Second), Some(&5));
+/// assert_eq!(total.get(MyKey::Third), Some(&4));
 /// ```
 ///
 /// Using a composite key:
@@ -1197,190 +3797,247 @@ where
 /// ```
 /// use fixed_map::{Key, Map};
 ///
-/// #[derive(Debug, Clone, Copy, Hash, Key)]
+/// #[derive(Debug, Clone, Copy, Key)]
 /// enum MyKey {
 ///     First(bool),
 ///     Second,
 /// }
 ///
-/// let mut a = Map::new();
-/// a.insert(MyKey::First(true), 1);
+/// let a = Map::from_iter([(MyKey::First(true), 1), (MyKey::Second, 2)]);
+/// let b = Map::from_iter([(MyKey::First(true), 3), (MyKey::First(false), 4)]);
 ///
-/// let mut b = Map::new();
-/// b.insert(MyKey::Second, 1);
+/// let total: Map<_, u32> = [a, b].into_iter().sum();
 ///
-/// // TODO: support this
-/// // let mut list = vec![a, b];
-/// // list.sort();
+/// assert_eq!(total.get(MyKey::First(true)), Some(&4));
+/// assert_eq!(total.get(MyKey::First(false)), Some(&4));
+/// assert_eq!(total.get(MyKey::Second), Some(&2));
 /// ```
-impl<K, V> Ord for Map<K, V>
+impl<K, V> Sum for Map<K, V>
 where
     K: Key,
-    K::MapStorage<V>: Ord,
+    V: AddAssign,
 {
     #[inline]
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.storage.cmp(&other.storage)
-    }
-
-    #[inline]
-    fn max(self, other: Self) -> Self {
-        Self {
-            storage: self.storage.max(other.storage),
-        }
-    }
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = Self>,
+    {
+        let mut output = Self::new();
 
-    #[inline]
-    fn min(self, other: Self) -> Self {
-        Self {
-            storage: self.storage.min(other.storage),
+        for map in iter {
+            output.merge_from(map, |_, existing, value| *existing += value);
         }
-    }
 
-    #[inline]
-    fn clamp(self, min: Self, max: Self) -> Self {
-        Self {
-            storage: self.storage.clamp(min.storage, max.storage),
-        }
+        output
     }
 }
 
-impl<'a, K, V> IntoIterator for &'a Map<K, V>
+/// Multiplies a sequence of maps by merging them key-wise, multiplying
+/// together values which are present for the same key.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+///     Third,
+/// }
+///
+/// let a = Map::from_iter([(MyKey::First, 2), (MyKey::Second, 3)]);
+/// let b = Map::from_iter([(MyKey::Second, 4), (MyKey::Third, 5)]);
+///
+/// let total: Map<_, u32> = [a, b].into_iter().product();
+///
+/// assert_eq!(total.get(MyKey::First), Some(&2));
+/// assert_eq!(total.get(MyKey::Second), Some(&12));
+/// assert_eq!(total.get(MyKey::Third), Some(&5));
+/// ```
+impl<K, V> Product for Map<K, V>
 where
     K: Key,
+    V: MulAssign,
 {
-    type Item = (K, &'a V);
-    type IntoIter = Iter<'a, K, V>;
-
     #[inline]
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter()
-    }
-}
+    fn product<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = Self>,
+    {
+        let mut output = Self::new();
 
-/// [`IntoIterator`] implementation which uses [`Map::iter_mut`]. See its
-/// documentation for more.
-impl<'a, K, V> IntoIterator for &'a mut Map<K, V>
-where
-    K: Key,
-{
-    type Item = (K, &'a mut V);
-    type IntoIter = IterMut<'a, K, V>;
+        for map in iter {
+            output.merge_from(map, |_, existing, value| *existing *= value);
+        }
 
-    #[inline]
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter_mut()
+        output
     }
 }
 
-/// Produce an owning iterator visiting all key-value pairs of the [`Map`] in an
-/// arbitrary order. The iterator element type is `(K, V)`.
+/// Collects key-value pairs from a parallel iterator into a [`Map`].
+///
+/// Every thread inserts its pairs into a map of its own, and the per-thread
+/// maps are then merged by key in the order of the parallel iterator. So like
+/// [`FromIterator`], the last value of a key is the one which is kept.
+///
+/// This is only available when the `rayon` feature is enabled. Counters kept
+/// in per-thread maps can be added up with the [`Sum`] implementation of
+/// [`Map`] instead, which is also what a parallel
+/// [`sum`][rayon::iter::ParallelIterator::sum] uses.
 ///
 /// # Examples
 ///
 /// ```
 /// use fixed_map::{Key, Map};
+/// use rayon::prelude::*;
 ///
-/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
-/// enum MyKey {
-///     First,
-///     Second,
-///     Third,
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Parity {
+///     Even,
+///     Odd,
 /// }
 ///
-/// let mut map = Map::new();
-/// map.insert(MyKey::First, 1);
-/// map.insert(MyKey::Third, 3);
+/// let last: Map<_, u32> = (0..1000u32)
+///     .into_par_iter()
+///     .map(|n| (if n % 2 == 0 { Parity::Even } else { Parity::Odd }, n))
+///     .collect();
 ///
-/// let mut it = map.into_iter();
-/// assert_eq!(it.next(), Some((MyKey::First, 1)));
-/// assert_eq!(it.next(), Some((MyKey::Third, 3)));
-/// assert_eq!(it.next(), None);
+/// assert_eq!(last.get(Parity::Even), Some(&998));
+/// assert_eq!(last.get(Parity::Odd), Some(&999));
 ///
-/// let mut it = map.into_iter().rev();
-/// assert_eq!(it.next(), Some((MyKey::Third, 3)));
-/// assert_eq!(it.next(), Some((MyKey::First, 1)));
-/// assert_eq!(it.next(), None);
+/// let counts: Map<_, u32> = (0..1000u32)
+///     .into_par_iter()
+///     .map(|n| Map::from([(if n % 3 == 0 { Parity::Even } else { Parity::Odd }, 1)]))
+///     .sum();
+///
+/// assert_eq!(counts.get(Parity::Even), Some(&334));
+/// assert_eq!(counts.get(Parity::Odd), Some(&666));
 /// ```
+#[cfg(feature = "rayon")]
+impl<K, V> rayon::iter::FromParallelIterator<(K, V)> for Map<K, V>
+where
+    K: Key + Send,
+    K::MapStorage<V>: Send,
+    V: Send,
+{
+    #[inline]
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: rayon::iter::IntoParallelIterator<Item = (K, V)>,
+    {
+        use rayon::iter::ParallelIterator as _;
+
+        par_iter
+            .into_par_iter()
+            .fold(Self::new, |mut map, (key, value)| {
+                map.insert(key, value);
+                map
+            })
+            .reduce(Self::new, |mut map, other| {
+                map.merge_from(other, |_, existing, value| *existing = value);
+                map
+            })
+    }
+}
+
+/// Inserts every key-value pair of a parallel iterator into the [`Map`],
+/// replacing the values of keys which are already present.
 ///
-/// Into iterator with a composite key:
+/// This is only available when the `rayon` feature is enabled.
+///
+/// # Examples
 ///
 /// ```
 /// use fixed_map::{Key, Map};
+/// use rayon::prelude::*;
 ///
-/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
 /// enum MyKey {
-///     First(bool),
+///     First,
 ///     Second,
 ///     Third,
 /// }
 ///
-/// let mut map = Map::<_, u32>::new();
-/// map.insert(MyKey::First(false), 1);
-/// map.insert(MyKey::Third, 3);
-///
-/// let mut it = map.into_iter();
-/// assert_eq!(it.next(), Some((MyKey::First(false), 1)));
-/// assert_eq!(it.next(), Some((MyKey::Third, 3)));
-/// assert_eq!(it.next(), None);
+/// let mut map = Map::from([(MyKey::First, 1), (MyKey::Second, 2)]);
+/// map.par_extend(vec![(MyKey::Second, 20), (MyKey::Third, 30)]);
 ///
-/// let mut it = map.into_iter().rev();
-/// assert_eq!(it.next(), Some((MyKey::Third, 3)));
-/// assert_eq!(it.next(), Some((MyKey::First(false), 1)));
-/// assert_eq!(it.next(), None);
+/// assert!(map.into_iter().eq([(MyKey::First, 1), (MyKey::Second, 20), (MyKey::Third, 30)]));
 /// ```
-impl<K, V> IntoIterator for Map<K, V>
+#[cfg(feature = "rayon")]
+impl<K, V> rayon::iter::ParallelExtend<(K, V)> for Map<K, V>
 where
-    K: Key,
+    K: Key + Send,
+    K::MapStorage<V>: Send,
+    V: Send,
 {
-    type Item = (K, V);
-    type IntoIter = IntoIter<K, V>;
-
     #[inline]
-    fn into_iter(self) -> Self::IntoIter {
-        self.storage.into_iter()
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: rayon::iter::IntoParallelIterator<Item = (K, V)>,
+    {
+        let other = <Self as rayon::iter::FromParallelIterator<(K, V)>>::from_par_iter(par_iter);
+        self.merge_from(other, |_, existing, value| *existing = value);
     }
 }
 
-/// A simple [`FromIterator`] implementation for [`Map`].
+/// Zeroizes every value in place, including the values of dynamic storage,
+/// before clearing the map.
 ///
-/// # Example
+/// Only values are scrubbed. Keys are not, so the fields of a dynamic variant
+/// like `Session(u32)` below remain in the memory of its hash table after the
+/// map has been cleared. Copies of values which were left behind when a hash
+/// table grew are not scrubbed either. Keys which are secret themselves should
+/// therefore not be stored in a [`Map`].
+///
+/// # Examples
 ///
 /// ```
 /// use fixed_map::{Key, Map};
+/// use zeroize::Zeroize;
 ///
-/// #[derive(Debug, Clone, Copy, Key)]
-/// enum MyKey {
-///     First,
-///     Second,
+/// #[derive(Clone, Copy, Key)]
+/// enum Secret {
+///     Master,
+///     Session(u32),
 /// }
 ///
-/// let v = vec![(MyKey::First, 1), (MyKey::Second, 2), (MyKey::First, 3)];
-/// let m: Map<_, u8> = v.into_iter().collect();
-///
-/// let mut n = Map::new();
-/// n.insert(MyKey::Second, 2);
-/// n.insert(MyKey::First, 3);
+/// let mut map = Map::new();
+/// map.insert(Secret::Master, [0x42u8; 32]);
+/// map.insert(Secret::Session(1), [0x17u8; 32]);
 ///
-/// assert_eq!(m, n);
+/// map.zeroize();
+/// assert!(map.is_empty());
 /// ```
-impl<K, V> FromIterator<(K, V)> for Map<K, V>
+#[cfg(feature = "zeroize")]
+impl<K, V> zeroize::Zeroize for Map<K, V>
 where
     K: Key,
+    V: zeroize::Zeroize,
 {
     #[inline]
-    fn from_iter<T>(iter: T) -> Self
-    where
-        T: IntoIterator<Item = (K, V)>,
-    {
-        let mut map = Self::new();
-        for (k, v) in iter {
-            map.insert(k, v);
+    fn zeroize(&mut self) {
+        for value in self.values_mut() {
+            value.zeroize();
         }
-        map
+
+        self.clear();
     }
 }
 
+/// Every value zeroizes itself when the map is dropped.
+///
+/// This has the same limitations as [`Zeroize`][zeroize::Zeroize]: keys and
+/// copies of values left behind when a hash table grew are not scrubbed.
+#[cfg(feature = "zeroize")]
+impl<K, V> zeroize::ZeroizeOnDrop for Map<K, V>
+where
+    K: Key,
+    V: zeroize::ZeroizeOnDrop,
+{
+}
+
 #[cfg(feature = "serde")]
 impl<K, V> serde::Serialize for Map<K, V>
 where
@@ -1415,34 +4072,41 @@ where
     where
         D: serde::Deserializer<'de>,
     {
-        struct MapVisitor<K, V>(core::marker::PhantomData<(K, V)>);
-
-        impl<'de, K, V> serde::de::Visitor<'de> for MapVisitor<K, V>
-        where
-            K: Key + serde::de::Deserialize<'de>,
-            V: serde::Deserialize<'de>,
-        {
-            type Value = Map<K, V>;
+        let mut map = Map::new();
+        map.deserialize_update(deserializer)?;
+        Ok(map)
+    }
+}
 
-            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-                formatter.write_str("a map")
-            }
+/// Visitor which inserts every deserialized entry into an existing map.
+///
+/// See [`Map::deserialize_update`].
+#[cfg(feature = "serde")]
+struct UpdateVisitor<'a, K, V>(&'a mut Map<K, V>)
+where
+    K: Key;
 
-            #[inline]
-            fn visit_map<T>(self, mut visitor: T) -> Result<Self::Value, T::Error>
-            where
-                T: serde::de::MapAccess<'de>,
-            {
-                let mut map = Map::new();
+#[cfg(feature = "serde")]
+impl<'de, K, V> serde::de::Visitor<'de> for UpdateVisitor<'_, K, V>
+where
+    K: Key + serde::de::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+{
+    type Value = ();
 
-                while let Some((key, value)) = visitor.next_entry()? {
-                    map.insert(key, value);
-                }
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a map")
+    }
 
-                Ok(map)
-            }
+    #[inline]
+    fn visit_map<T>(self, mut visitor: T) -> Result<Self::Value, T::Error>
+    where
+        T: serde::de::MapAccess<'de>,
+    {
+        while let Some((key, value)) = visitor.next_entry()? {
+            self.0.insert(key, value);
         }
 
-        deserializer.deserialize_map(MapVisitor(core::marker::PhantomData))
+        Ok(())
     }
 }
@@ -0,0 +1,198 @@
+use crate::{Key, Map};
+
+/// An object-safe view of a [`Map`], which can be used to handle maps without
+/// naming the iterator or storage types of their keys.
+///
+/// This is implemented by [`Map`], and can for example be used to handle maps
+/// of different keys uniformly through `&dyn DynMap<K, V>` where `K` is a
+/// common key type.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::map::DynMap;
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Part {
+///     Head,
+///     Body,
+/// }
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Slot {
+///     Armor(Part),
+///     Weapon,
+/// }
+///
+/// fn total(maps: &[&dyn DynMap<Slot, u32>]) -> u32 {
+///     let mut total = 0;
+///
+///     for map in maps {
+///         map.for_each_dyn(&mut |_, value| total += *value);
+///     }
+///
+///     total
+/// }
+///
+/// let mut a = Map::new();
+/// a.insert(Slot::Armor(Part::Head), 1);
+/// a.insert(Slot::Weapon, 2);
+///
+/// let mut b = Map::new();
+/// b.insert(Slot::Armor(Part::Body), 3);
+///
+/// assert_eq!(total(&[&a, &b]), 6);
+/// ```
+pub trait DynMap<K, V> {
+    /// Gets the number of elements in the map, see [`Map::len`].
+    fn len(&self) -> usize;
+
+    /// Returns true if the map contains no elements, see [`Map::is_empty`].
+    fn is_empty(&self) -> bool;
+
+    /// Returns `true` if the map contains a value for the specified key, see
+    /// [`Map::contains_key`].
+    fn contains_key(&self, key: K) -> bool;
+
+    /// Returns a reference to the value corresponding to the key, see
+    /// [`Map::get`].
+    fn get(&self, key: K) -> Option<&V>;
+
+    /// Returns a mutable reference to the value corresponding to the key, see
+    /// [`Map::get_mut`].
+    fn get_mut(&mut self, key: K) -> Option<&mut V>;
+
+    /// Inserts a key-value pair into the map, see [`Map::insert`].
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+
+    /// Removes a key from the map, see [`Map::remove`].
+    fn remove(&mut self, key: K) -> Option<V>;
+
+    /// Clears the map, removing all key-value pairs, see [`Map::clear`].
+    fn clear(&mut self);
+
+    /// Calls the given closure with every key-value pair in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::map::DynMap;
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second, 2);
+    ///
+    /// let map: &dyn DynMap<MyKey, u32> = &map;
+    ///
+    /// let mut keys = Vec::new();
+    /// map.for_each_dyn(&mut |key, _| keys.push(key));
+    /// assert_eq!(keys, [MyKey::First, MyKey::Second]);
+    /// ```
+    fn for_each_dyn<'a>(&'a self, f: &mut dyn FnMut(K, &'a V));
+
+    /// An iterator visiting all key-value pairs in the map, see
+    /// [`Map::iter_dyn`].
+    ///
+    /// The default implementation collects the pairs visited by
+    /// [`for_each_dyn`][DynMap::for_each_dyn].
+    ///
+    /// This is only available when the `alloc` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::map::DynMap;
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    /// }
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(MyKey::First, 1);
+    /// map.insert(MyKey::Second(false), 2);
+    ///
+    /// let map: &dyn DynMap<MyKey, u32> = &map;
+    /// assert!(map.iter_dyn().eq([(MyKey::First, &1), (MyKey::Second(false), &2)]));
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn iter_dyn<'a>(&'a self) -> alloc::boxed::Box<dyn Iterator<Item = (K, &'a V)> + 'a>
+    where
+        K: 'a,
+    {
+        let mut entries = alloc::vec::Vec::with_capacity(self.len());
+        self.for_each_dyn(&mut |key, value| entries.push((key, value)));
+        alloc::boxed::Box::new(entries.into_iter())
+    }
+}
+
+impl<K, V> DynMap<K, V> for Map<K, V>
+where
+    K: Key,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        Map::len(self)
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        Map::is_empty(self)
+    }
+
+    #[inline]
+    fn contains_key(&self, key: K) -> bool {
+        Map::contains_key(self, key)
+    }
+
+    #[inline]
+    fn get(&self, key: K) -> Option<&V> {
+        Map::get(self, key)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        Map::get_mut(self, key)
+    }
+
+    #[inline]
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        Map::insert(self, key, value)
+    }
+
+    #[inline]
+    fn remove(&mut self, key: K) -> Option<V> {
+        Map::remove(self, key)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        Map::clear(self);
+    }
+
+    #[inline]
+    fn for_each_dyn<'a>(&'a self, f: &mut dyn FnMut(K, &'a V)) {
+        for (key, value) in Map::iter(self) {
+            f(key, value);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn iter_dyn<'a>(&'a self) -> alloc::boxed::Box<dyn Iterator<Item = (K, &'a V)> + 'a>
+    where
+        K: 'a,
+    {
+        Map::iter_dyn(self)
+    }
+}
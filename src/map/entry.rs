@@ -1,3 +1,5 @@
+use core::option;
+
 use crate::map::{MapStorage, OccupiedEntry, VacantEntry};
 
 /// A view into a single entry in a map, which may either be vacant or occupied.
@@ -204,6 +206,66 @@ where
         }
     }
 
+    /// Returns a reference to this entry’s key.
+    ///
+    /// For composite keys this is the full key including the payload of any
+    /// nested keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Clone, Copy, Key, Debug, PartialEq)]
+    /// enum MyKey {
+    ///     First(Option<bool>),
+    ///     Second,
+    /// }
+    ///
+    /// let mut map: Map<MyKey, i32> = Map::new();
+    /// assert_eq!(map.entry(MyKey::First(Some(true))).key_ref(), &MyKey::First(Some(true)));
+    ///
+    /// map.insert(MyKey::First(None), 1);
+    /// assert_eq!(map.entry(MyKey::First(None)).key_ref(), &MyKey::First(None));
+    /// ```
+    #[inline]
+    pub fn key_ref(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key_ref(),
+            Entry::Vacant(entry) => entry.key_ref(),
+        }
+    }
+
+    /// Returns an iterator over the key and value of this entry, which yields
+    /// a single `(K, &V)` pair if the entry is occupied and nothing if it's
+    /// vacant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map: Map<MyKey, i32> = Map::new();
+    /// map.insert(MyKey::First, 1);
+    ///
+    /// assert!(map.entry(MyKey::First).iter().eq([(MyKey::First, &1)]));
+    /// assert_eq!(map.entry(MyKey::Second).iter().next(), None);
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> option::IntoIter<(K, &V)> {
+        match self {
+            Entry::Occupied(entry) => Some((entry.key(), entry.get())),
+            Entry::Vacant(..) => None,
+        }
+        .into_iter()
+    }
+
     /// Provides in-place mutable access to an occupied entry before any
     /// potential inserts into the map.
     ///
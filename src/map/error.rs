@@ -0,0 +1,197 @@
+use core::fmt;
+
+use crate::debug::KeyName;
+use crate::map::{MapStorage, OccupiedEntry};
+use crate::Key;
+
+/// Error returned by [`Map::insert_unique`] when the key is already present,
+/// which carries the rejected key and value.
+///
+/// [`Map::insert_unique`]: crate::Map::insert_unique
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::map::AlreadyPresent;
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(MyKey::First, 1);
+///
+/// let error = map.insert_unique(MyKey::First, 2).unwrap_err();
+/// assert_eq!(error, AlreadyPresent::new(MyKey::First, 2));
+/// assert_eq!(error.to_string(), "key `First` is already present");
+/// assert_eq!(error.into_parts(), (MyKey::First, 2));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlreadyPresent<K, V> {
+    key: K,
+    value: V,
+}
+
+impl<K, V> AlreadyPresent<K, V> {
+    /// Construct a new error for the given rejected key and value.
+    #[inline]
+    #[must_use]
+    pub const fn new(key: K, value: V) -> Self {
+        Self { key, value }
+    }
+
+    /// The key which is already present.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// The value which was rejected.
+    #[inline]
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+
+    /// Convert into the rejected key and value.
+    #[inline]
+    pub fn into_parts(self) -> (K, V) {
+        (self.key, self.value)
+    }
+}
+
+impl<K, V> fmt::Display for AlreadyPresent<K, V>
+where
+    K: Key,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "key `{}` is already present", KeyName(self.key))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V> std::error::Error for AlreadyPresent<K, V>
+where
+    K: Key + fmt::Debug,
+    V: fmt::Debug,
+{
+}
+
+/// Error returned by [`Map::try_insert`] when the key is already occupied,
+/// which carries the occupied entry and the rejected value.
+///
+/// This mirrors `OccupiedError` of the unstable `HashMap::try_insert`.
+///
+/// [`Map::try_insert`]: crate::Map::try_insert
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::map::OccupiedEntry;
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(MyKey::First, 1);
+///
+/// let mut error = map.try_insert(MyKey::First, 2).unwrap_err();
+/// assert_eq!(error.key(), MyKey::First);
+/// assert_eq!(error.value(), &2);
+/// assert_eq!(error.entry().get(), &1);
+/// assert_eq!(error.to_string(), "key `First` is already occupied");
+///
+/// *error.entry_mut().get_mut() += 10;
+/// assert_eq!(map.get(MyKey::First), Some(&11));
+/// ```
+pub struct OccupiedError<'a, S: 'a, K, V>
+where
+    S: MapStorage<K, V>,
+{
+    entry: S::Occupied<'a>,
+    value: V,
+}
+
+impl<'a, S: 'a, K, V> OccupiedError<'a, S, K, V>
+where
+    S: MapStorage<K, V>,
+{
+    /// Construct a new error for the given occupied entry and rejected value.
+    #[inline]
+    pub fn new(entry: S::Occupied<'a>, value: V) -> Self {
+        Self { entry, value }
+    }
+
+    /// The key which is already occupied.
+    #[inline]
+    pub fn key(&self) -> K {
+        self.entry.key()
+    }
+
+    /// The value which was rejected.
+    #[inline]
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+
+    /// The entry of the key which is already occupied.
+    #[inline]
+    pub fn entry(&self) -> &S::Occupied<'a> {
+        &self.entry
+    }
+
+    /// The entry of the key which is already occupied, mutably.
+    #[inline]
+    pub fn entry_mut(&mut self) -> &mut S::Occupied<'a> {
+        &mut self.entry
+    }
+
+    /// Convert into the occupied entry and the rejected value.
+    #[inline]
+    pub fn into_parts(self) -> (S::Occupied<'a>, V) {
+        (self.entry, self.value)
+    }
+}
+
+impl<'a, S: 'a, K, V> fmt::Debug for OccupiedError<'a, S, K, V>
+where
+    S: MapStorage<K, V>,
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedError")
+            .field("key", &self.entry.key())
+            .field("old_value", self.entry.get())
+            .field("new_value", &self.value)
+            .finish()
+    }
+}
+
+impl<'a, S: 'a, K, V> fmt::Display for OccupiedError<'a, S, K, V>
+where
+    S: MapStorage<K, V>,
+    K: Key,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "key `{}` is already occupied", KeyName(self.entry.key()))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, S: 'a, K, V> std::error::Error for OccupiedError<'a, S, K, V>
+where
+    S: MapStorage<K, V>,
+    K: Key + fmt::Debug,
+    V: fmt::Debug,
+{
+}
@@ -0,0 +1,460 @@
+use core::iter;
+use core::marker::PhantomData;
+use core::slice;
+
+use crate::map::{ClassStorage, ExtractIfCursor, ExtractIfStorage, MapStorage};
+use crate::raw::KeyIndex;
+use crate::Key;
+
+macro_rules! counted_iter {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $storage:ident, $storage_fn:ident, $item:ty
+    ) => {
+        $(#[$meta])*
+        pub struct $name<'a, K, V>
+        where
+            K: 'a + Key,
+            V: 'a,
+        {
+            iter: <K::MapStorage<V> as MapStorage<K, V>>::$storage<'a>,
+            len: usize,
+        }
+
+        impl<'a, K, V> $name<'a, K, V>
+        where
+            K: 'a + Key,
+            V: 'a,
+        {
+            #[inline]
+            pub(crate) fn new(storage: &'a K::MapStorage<V>) -> Self {
+                Self {
+                    iter: storage.$storage_fn(),
+                    len: storage.len(),
+                }
+            }
+        }
+
+        impl<'a, K, V> Iterator for $name<'a, K, V>
+        where
+            K: 'a + Key,
+            V: 'a,
+        {
+            type Item = $item;
+
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                let item = self.iter.next()?;
+                self.len -= 1;
+                Some(item)
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+
+            #[inline]
+            fn count(self) -> usize {
+                self.len
+            }
+        }
+
+        impl<'a, K, V> DoubleEndedIterator for $name<'a, K, V>
+        where
+            K: 'a + Key,
+            V: 'a,
+            <K::MapStorage<V> as MapStorage<K, V>>::$storage<'a>: DoubleEndedIterator,
+        {
+            #[inline]
+            fn next_back(&mut self) -> Option<Self::Item> {
+                let item = self.iter.next_back()?;
+                self.len -= 1;
+                Some(item)
+            }
+        }
+
+        impl<'a, K, V> ExactSizeIterator for $name<'a, K, V>
+        where
+            K: 'a + Key,
+            V: 'a,
+        {
+            #[inline]
+            fn len(&self) -> usize {
+                self.len
+            }
+        }
+
+        impl<'a, K, V> Clone for $name<'a, K, V>
+        where
+            K: 'a + Key,
+            V: 'a,
+            <K::MapStorage<V> as MapStorage<K, V>>::$storage<'a>: Clone,
+        {
+            #[inline]
+            fn clone(&self) -> Self {
+                Self {
+                    iter: self.iter.clone(),
+                    len: self.len,
+                }
+            }
+        }
+    };
+}
+
+counted_iter! {
+    /// The iterator produced by [`Map::iter`][crate::Map::iter].
+    ///
+    /// This knows the number of remaining entries up front, so it implements
+    /// [`ExactSizeIterator`] without visiting every slot of the map.
+    Iter, Iter, iter, (K, &'a V)
+}
+
+counted_iter! {
+    /// The iterator produced by [`Map::keys`][crate::Map::keys].
+    ///
+    /// This knows the number of remaining keys up front, so it implements
+    /// [`ExactSizeIterator`] without visiting every slot of the map.
+    Keys, Keys, keys, K
+}
+
+counted_iter! {
+    /// The iterator produced by [`Map::values`][crate::Map::values].
+    ///
+    /// This knows the number of remaining values up front, so it implements
+    /// [`ExactSizeIterator`] without visiting every slot of the map.
+    Values, Values, values, &'a V
+}
+
+macro_rules! owned_iter {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $item:ty, |$key:pat_param, $value:pat_param| $map:expr
+    ) => {
+        $(#[$meta])*
+        pub struct $name<K, V>
+        where
+            K: Key,
+        {
+            iter: <K::MapStorage<V> as MapStorage<K, V>>::IntoIter,
+            len: usize,
+        }
+
+        impl<K, V> $name<K, V>
+        where
+            K: Key,
+        {
+            #[inline]
+            pub(crate) fn new(storage: K::MapStorage<V>) -> Self {
+                Self {
+                    len: storage.len(),
+                    iter: storage.into_iter(),
+                }
+            }
+        }
+
+        impl<K, V> Iterator for $name<K, V>
+        where
+            K: Key,
+        {
+            type Item = $item;
+
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                let ($key, $value) = self.iter.next()?;
+                self.len -= 1;
+                Some($map)
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+
+            #[inline]
+            fn count(self) -> usize {
+                self.len
+            }
+        }
+
+        impl<K, V> DoubleEndedIterator for $name<K, V>
+        where
+            K: Key,
+            <K::MapStorage<V> as MapStorage<K, V>>::IntoIter: DoubleEndedIterator,
+        {
+            #[inline]
+            fn next_back(&mut self) -> Option<Self::Item> {
+                let ($key, $value) = self.iter.next_back()?;
+                self.len -= 1;
+                Some($map)
+            }
+        }
+
+        impl<K, V> ExactSizeIterator for $name<K, V>
+        where
+            K: Key,
+        {
+            #[inline]
+            fn len(&self) -> usize {
+                self.len
+            }
+        }
+    };
+}
+
+owned_iter! {
+    /// The iterator produced by [`Map::into_keys`][crate::Map::into_keys].
+    ///
+    /// This knows the number of remaining keys up front, so it implements
+    /// [`ExactSizeIterator`] without visiting every slot of the map.
+    IntoKeys, K, |key, _| key
+}
+
+owned_iter! {
+    /// The iterator produced by [`Map::into_values`][crate::Map::into_values].
+    ///
+    /// This knows the number of remaining values up front, so it implements
+    /// [`ExactSizeIterator`] without visiting every slot of the map.
+    IntoValues, V, |_, value| value
+}
+
+/// The iterator produced by [`Map::iter_group`][crate::Map::iter_group].
+pub struct GroupIter<'a, K, V>
+where
+    K: 'a + Key,
+    K::MapStorage<V>: ClassStorage<K, V>,
+    V: 'a,
+{
+    iter: <K::MapStorage<V> as ClassStorage<K, V>>::ClassIter<'a>,
+}
+
+impl<'a, K, V> GroupIter<'a, K, V>
+where
+    K: 'a + Key,
+    K::MapStorage<V>: ClassStorage<K, V>,
+    V: 'a,
+{
+    #[inline]
+    pub(crate) fn new(storage: &'a K::MapStorage<V>, class: usize) -> Self {
+        Self {
+            iter: storage.iter_class(class),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for GroupIter<'a, K, V>
+where
+    K: 'a + Key,
+    K::MapStorage<V>: ClassStorage<K, V>,
+    V: 'a,
+{
+    type Item = (K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for GroupIter<'a, K, V>
+where
+    K: 'a + Key,
+    K::MapStorage<V>: ClassStorage<K, V>,
+    V: 'a,
+    <K::MapStorage<V> as ClassStorage<K, V>>::ClassIter<'a>: DoubleEndedIterator,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+/// The iterator produced by [`Map::iter_indexed`][crate::Map::iter_indexed].
+pub struct IndexedIter<'a, K, V>
+where
+    K: 'a + Key,
+    V: 'a,
+{
+    iter: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> IndexedIter<'a, K, V>
+where
+    K: 'a + Key,
+    V: 'a,
+{
+    #[inline]
+    pub(crate) fn new(storage: &'a K::MapStorage<V>) -> Self {
+        Self {
+            iter: Iter::new(storage),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for IndexedIter<'a, K, V>
+where
+    K: 'a + KeyIndex,
+    V: 'a,
+{
+    type Item = (usize, K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.iter.next()?;
+        Some((key.to_index(), key, value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for IndexedIter<'a, K, V>
+where
+    K: 'a + KeyIndex,
+    V: 'a,
+    <K::MapStorage<V> as MapStorage<K, V>>::Iter<'a>: DoubleEndedIterator,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.iter.next_back()?;
+        Some((key.to_index(), key, value))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for IndexedIter<'a, K, V>
+where
+    K: 'a + KeyIndex,
+    V: 'a,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<'a, K, V> Clone for IndexedIter<'a, K, V>
+where
+    K: 'a + Key,
+    V: 'a,
+    <K::MapStorage<V> as MapStorage<K, V>>::Iter<'a>: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+/// The iterator produced by [`Map::range`][crate::Map::range].
+pub struct Range<'a, K, V> {
+    iter: iter::Enumerate<slice::Iter<'a, Option<V>>>,
+    start: usize,
+    _marker: PhantomData<K>,
+}
+
+impl<'a, K, V> Range<'a, K, V> {
+    #[inline]
+    pub(crate) fn new(slots: &'a [Option<V>], start: usize) -> Self {
+        Self {
+            iter: slots.iter().enumerate(),
+            start,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V>
+where
+    K: KeyIndex,
+{
+    type Item = (K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.start;
+
+        self.iter
+            .find_map(|(index, value)| Some((K::from_index(start + index)?, value.as_ref()?)))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V>
+where
+    K: KeyIndex,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            let (index, value) = self.iter.next_back()?;
+
+            if let (Some(key), Some(value)) = (K::from_index(self.start + index), value) {
+                return Some((key, value));
+            }
+        }
+    }
+}
+
+impl<'a, K, V> Clone for Range<'a, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            start: self.start,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// The iterator produced by [`Map::extract_if`][crate::Map::extract_if].
+pub struct ExtractIf<'a, K, V, F>
+where
+    K: 'a + Key,
+    K::MapStorage<V>: ExtractIfStorage<K, V>,
+    V: 'a,
+{
+    cursor: <K::MapStorage<V> as ExtractIfStorage<K, V>>::ExtractIf<'a>,
+    filter: F,
+}
+
+impl<'a, K, V, F> ExtractIf<'a, K, V, F>
+where
+    K: 'a + Key,
+    K::MapStorage<V>: ExtractIfStorage<K, V>,
+    V: 'a,
+{
+    #[inline]
+    pub(crate) fn new(storage: &'a mut K::MapStorage<V>, filter: F) -> Self {
+        Self {
+            cursor: storage.extract_if(),
+            filter,
+        }
+    }
+}
+
+impl<'a, K, V, F> Iterator for ExtractIf<'a, K, V, F>
+where
+    K: 'a + Key,
+    K::MapStorage<V>: ExtractIfStorage<K, V>,
+    V: 'a,
+    F: FnMut(K, &mut V) -> bool,
+{
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cursor.next_if(&mut self.filter)
+    }
+}
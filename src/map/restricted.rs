@@ -0,0 +1,182 @@
+use core::fmt;
+use core::iter;
+
+use crate::map::Iter;
+use crate::{Key, Map, Set};
+
+type Keys<'a, K, V> = iter::Map<RestrictedIter<'a, K, V>, fn((K, &'a V)) -> K>;
+type Values<'a, K, V> = iter::Map<RestrictedIter<'a, K, V>, fn((K, &'a V)) -> &'a V>;
+
+/// A read-only view of a [`Map`] where keys outside of an allowed [`Set`]
+/// are treated as absent.
+///
+/// This is constructed through [`Map::restrict`], and makes it possible to
+/// hand out capability-scoped access to a shared map without cloning it.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Map, Set};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Setting {
+///     Volume,
+///     Brightness,
+///     Password,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(Setting::Volume, 10);
+/// map.insert(Setting::Password, 1234);
+///
+/// let allowed = Set::from([Setting::Volume, Setting::Brightness]);
+/// let view = map.restrict(&allowed);
+///
+/// assert_eq!(view.get(Setting::Volume), Some(&10));
+/// assert_eq!(view.get(Setting::Password), None);
+/// assert!(!view.contains_key(Setting::Password));
+/// assert_eq!(view.len(), 1);
+/// assert!(view.keys().eq([Setting::Volume]));
+/// ```
+pub struct RestrictedMap<'a, K, V>
+where
+    K: Key,
+{
+    map: &'a Map<K, V>,
+    allowed: &'a Set<K>,
+}
+
+impl<'a, K, V> RestrictedMap<'a, K, V>
+where
+    K: Key,
+{
+    #[inline]
+    pub(crate) fn new(map: &'a Map<K, V>, allowed: &'a Set<K>) -> Self {
+        Self { map, allowed }
+    }
+
+    /// The set of keys which are visible through this view.
+    #[inline]
+    pub fn allowed(&self) -> &'a Set<K> {
+        self.allowed
+    }
+
+    /// Returns `true` if the key is allowed and present in the map, see
+    /// [`Map::contains_key`].
+    #[inline]
+    pub fn contains_key(&self, key: K) -> bool {
+        self.allowed.contains(key) && self.map.contains_key(key)
+    }
+
+    /// Returns a reference to the value of the key if it is allowed, see
+    /// [`Map::get`].
+    #[inline]
+    pub fn get(&self, key: K) -> Option<&'a V> {
+        if !self.allowed.contains(key) {
+            return None;
+        }
+
+        self.map.get(key)
+    }
+
+    /// Gets the number of visible elements.
+    ///
+    /// This visits every element of the underlying map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns `true` if no elements are visible.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// An iterator visiting all visible key-value pairs in the order of the
+    /// underlying map, see [`Map::iter`].
+    #[inline]
+    pub fn iter(&self) -> RestrictedIter<'a, K, V> {
+        RestrictedIter {
+            iter: self.map.iter(),
+            allowed: self.allowed,
+        }
+    }
+
+    /// An iterator visiting all visible keys, see [`Map::keys`].
+    #[inline]
+    pub fn keys(&self) -> Keys<'a, K, V> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// An iterator visiting all visible values, see [`Map::values`].
+    #[inline]
+    pub fn values(&self) -> Values<'a, K, V> {
+        self.iter().map(|(_, value)| value)
+    }
+}
+
+impl<K, V> Clone for RestrictedMap<'_, K, V>
+where
+    K: Key,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for RestrictedMap<'_, K, V> where K: Key {}
+
+impl<K, V> fmt::Debug for RestrictedMap<'_, K, V>
+where
+    K: Key + fmt::Debug,
+    V: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &RestrictedMap<'a, K, V>
+where
+    K: Key,
+{
+    type Item = (K, &'a V);
+    type IntoIter = RestrictedIter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The iterator produced by [`RestrictedMap::iter`].
+pub struct RestrictedIter<'a, K, V>
+where
+    K: 'a + Key,
+    V: 'a,
+{
+    iter: Iter<'a, K, V>,
+    allowed: &'a Set<K>,
+}
+
+impl<'a, K, V> Iterator for RestrictedIter<'a, K, V>
+where
+    K: 'a + Key,
+    V: 'a,
+{
+    type Item = (K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let allowed = self.allowed;
+        self.iter.find(|(key, _)| allowed.contains(*key))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
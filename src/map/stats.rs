@@ -0,0 +1,115 @@
+/// Occupancy statistics of a [`Map`], as returned by [`Map::stats`].
+///
+/// Statistics are aggregated across the storage of every variant, including
+/// the storage of nested keys.
+///
+/// [`Map`]: crate::Map
+/// [`Map::stats`]: crate::Map::stats
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "hashbrown")] {
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second(bool),
+///     Third(u32),
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(MyKey::First, 1);
+/// map.insert(MyKey::Third(42), 2);
+///
+/// let stats = map.stats();
+/// assert_eq!(stats.capacity, 3);
+/// assert_eq!(stats.len, 2);
+/// assert_eq!(stats.dynamic_buckets, 1);
+/// assert!(stats.dynamic_capacity >= 1);
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// The number of keys which can be stored without allocating, which
+    /// excludes keys stored in dynamic buckets.
+    pub capacity: usize,
+    /// The number of values stored in the map.
+    pub len: usize,
+    /// The number of dynamic buckets, such as hash maps for keys like `u32`.
+    pub dynamic_buckets: usize,
+    /// The number of values which can be stored in dynamic buckets without
+    /// reallocating.
+    pub dynamic_capacity: usize,
+}
+
+impl Stats {
+    /// Statistics for fixed storage with room for `capacity` values of which
+    /// `len` are occupied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::map::Stats;
+    ///
+    /// let stats = Stats::fixed(2, 1);
+    /// assert_eq!(stats.capacity, 2);
+    /// assert_eq!(stats.dynamic_buckets, 0);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn fixed(capacity: usize, len: usize) -> Self {
+        Self {
+            capacity,
+            len,
+            dynamic_buckets: 0,
+            dynamic_capacity: 0,
+        }
+    }
+
+    /// Statistics for a single dynamic bucket holding `len` values, with room
+    /// for `capacity` values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::map::Stats;
+    ///
+    /// let stats = Stats::dynamic(1, 4);
+    /// assert_eq!(stats.capacity, 0);
+    /// assert_eq!(stats.dynamic_buckets, 1);
+    /// assert_eq!(stats.dynamic_capacity, 4);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn dynamic(len: usize, capacity: usize) -> Self {
+        Self {
+            capacity: 0,
+            len,
+            dynamic_buckets: 1,
+            dynamic_capacity: capacity,
+        }
+    }
+
+    /// Combine the statistics of two storages by summing them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::map::Stats;
+    ///
+    /// let stats = Stats::fixed(2, 1).merge(Stats::dynamic(3, 4));
+    /// assert_eq!(stats, Stats { capacity: 2, len: 4, dynamic_buckets: 1, dynamic_capacity: 4 });
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn merge(self, other: Self) -> Self {
+        Self {
+            capacity: self.capacity + other.capacity,
+            len: self.len + other.len,
+            dynamic_buckets: self.dynamic_buckets + other.dynamic_buckets,
+            dynamic_capacity: self.dynamic_capacity + other.dynamic_capacity,
+        }
+    }
+}
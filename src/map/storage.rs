@@ -1,20 +1,343 @@
 //! Module that defines the [`MapStorage`] trait.
+//!
+//! The storage of a key is picked through [`Key::MapStorage`] and
+//! [`Key::SetStorage`], which means that downstream crates can implement
+//! [`Key`] with storage of their own by implementing [`MapStorage`] and
+//! [`SetStorage`].
+//!
+//! [`Key`]: crate::Key
+//! [`Key::MapStorage`]: crate::Key::MapStorage
+//! [`Key::SetStorage`]: crate::Key::SetStorage
+//! [`SetStorage`]: crate::set::SetStorage
+//!
+//! # Examples
+//!
+//! A key for a decimal digit, which is stored in an array indexed by the
+//! digit:
+//!
+//! ```
+//! use core::iter::{self, Enumerate, FilterMap};
+//! use core::{array, slice};
+//!
+//! use fixed_map::map::{Entry, MapStorage, OccupiedEntry, VacantEntry};
+//! use fixed_map::set::SetStorage;
+//! use fixed_map::{Key, Map, Set};
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq)]
+//! struct Digit(u8);
+//!
+//! impl Digit {
+//!     fn new(digit: u8) -> Option<Digit> {
+//!         (digit < 10).then_some(Digit(digit))
+//!     }
+//!
+//!     fn index(self) -> usize {
+//!         usize::from(self.0)
+//!     }
+//!
+//!     fn from_index(index: usize) -> Digit {
+//!         Digit(index as u8)
+//!     }
+//! }
+//!
+//! struct DigitMapStorage<V> {
+//!     data: [Option<V>; 10],
+//! }
+//!
+//! struct DigitVacant<'a, V> {
+//!     key: Digit,
+//!     slot: &'a mut Option<V>,
+//! }
+//!
+//! struct DigitOccupied<'a, V> {
+//!     key: Digit,
+//!     slot: &'a mut Option<V>,
+//! }
+//!
+//! impl<'a, V> VacantEntry<'a, Digit, V> for DigitVacant<'a, V> {
+//!     fn key(&self) -> Digit {
+//!         self.key
+//!     }
+//!
+//!     fn key_ref(&self) -> &Digit {
+//!         &self.key
+//!     }
+//!
+//!     fn insert(self, value: V) -> &'a mut V {
+//!         self.slot.insert(value)
+//!     }
+//! }
+//!
+//! impl<'a, V> OccupiedEntry<'a, Digit, V> for DigitOccupied<'a, V> {
+//!     fn key(&self) -> Digit {
+//!         self.key
+//!     }
+//!
+//!     fn key_ref(&self) -> &Digit {
+//!         &self.key
+//!     }
+//!
+//!     fn get(&self) -> &V {
+//!         self.slot.as_ref().unwrap()
+//!     }
+//!
+//!     fn get_mut(&mut self) -> &mut V {
+//!         self.slot.as_mut().unwrap()
+//!     }
+//!
+//!     fn into_mut(self) -> &'a mut V {
+//!         self.slot.as_mut().unwrap()
+//!     }
+//!
+//!     fn insert(&mut self, value: V) -> V {
+//!         self.slot.replace(value).unwrap()
+//!     }
+//!
+//!     fn remove(self) -> V {
+//!         self.slot.take().unwrap()
+//!     }
+//! }
+//!
+//! type Slots<'a, V> = Enumerate<slice::Iter<'a, Option<V>>>;
+//! type SlotsMut<'a, V> = Enumerate<slice::IterMut<'a, Option<V>>>;
+//!
+//! impl<V> MapStorage<Digit, V> for DigitMapStorage<V> {
+//!     type Iter<'this> = FilterMap<Slots<'this, V>, fn((usize, &'this Option<V>)) -> Option<(Digit, &'this V)>> where V: 'this;
+//!     type Keys<'this> = FilterMap<Slots<'this, V>, fn((usize, &'this Option<V>)) -> Option<Digit>> where V: 'this;
+//!     type Values<'this> = iter::Flatten<slice::Iter<'this, Option<V>>> where V: 'this;
+//!     type IterMut<'this> = FilterMap<SlotsMut<'this, V>, fn((usize, &'this mut Option<V>)) -> Option<(Digit, &'this mut V)>> where V: 'this;
+//!     type ValuesMut<'this> = iter::Flatten<slice::IterMut<'this, Option<V>>> where V: 'this;
+//!     type IntoIter = FilterMap<Enumerate<array::IntoIter<Option<V>, 10>>, fn((usize, Option<V>)) -> Option<(Digit, V)>>;
+//!     type Occupied<'this> = DigitOccupied<'this, V> where V: 'this;
+//!     type Vacant<'this> = DigitVacant<'this, V> where V: 'this;
+//!
+//!     fn empty() -> Self {
+//!         Self { data: [(); 10].map(|_| None) }
+//!     }
+//!
+//!     fn len(&self) -> usize {
+//!         self.data.iter().flatten().count()
+//!     }
+//!
+//!     fn is_empty(&self) -> bool {
+//!         self.data.iter().all(Option::is_none)
+//!     }
+//!
+//!     fn insert(&mut self, key: Digit, value: V) -> Option<V> {
+//!         self.data[key.index()].replace(value)
+//!     }
+//!
+//!     fn contains_key(&self, key: Digit) -> bool {
+//!         self.data[key.index()].is_some()
+//!     }
+//!
+//!     fn get(&self, key: Digit) -> Option<&V> {
+//!         self.data[key.index()].as_ref()
+//!     }
+//!
+//!     fn get_mut(&mut self, key: Digit) -> Option<&mut V> {
+//!         self.data[key.index()].as_mut()
+//!     }
+//!
+//!     fn remove(&mut self, key: Digit) -> Option<V> {
+//!         self.data[key.index()].take()
+//!     }
+//!
+//!     fn retain<F>(&mut self, mut func: F)
+//!     where
+//!         F: FnMut(Digit, &mut V) -> bool,
+//!     {
+//!         for (index, slot) in self.data.iter_mut().enumerate() {
+//!             if let Some(value) = slot {
+//!                 if !func(Digit::from_index(index), value) {
+//!                     *slot = None;
+//!                 }
+//!             }
+//!         }
+//!     }
+//!
+//!     fn clear(&mut self) {
+//!         self.data = [(); 10].map(|_| None);
+//!     }
+//!
+//!     fn iter(&self) -> Self::Iter<'_> {
+//!         self.data.iter().enumerate().filter_map(|(i, v)| Some((Digit::from_index(i), v.as_ref()?)))
+//!     }
+//!
+//!     fn keys(&self) -> Self::Keys<'_> {
+//!         self.data.iter().enumerate().filter_map(|(i, v)| v.as_ref().map(|_| Digit::from_index(i)))
+//!     }
+//!
+//!     fn values(&self) -> Self::Values<'_> {
+//!         self.data.iter().flatten()
+//!     }
+//!
+//!     fn iter_mut(&mut self) -> Self::IterMut<'_> {
+//!         self.data.iter_mut().enumerate().filter_map(|(i, v)| Some((Digit::from_index(i), v.as_mut()?)))
+//!     }
+//!
+//!     fn values_mut(&mut self) -> Self::ValuesMut<'_> {
+//!         self.data.iter_mut().flatten()
+//!     }
+//!
+//!     fn into_iter(self) -> Self::IntoIter {
+//!         self.data.into_iter().enumerate().filter_map(|(i, v)| Some((Digit::from_index(i), v?)))
+//!     }
+//!
+//!     fn entry(&mut self, key: Digit) -> Entry<'_, Self, Digit, V> {
+//!         let slot = &mut self.data[key.index()];
+//!
+//!         if slot.is_some() {
+//!             Entry::Occupied(DigitOccupied { key, slot })
+//!         } else {
+//!             Entry::Vacant(DigitVacant { key, slot })
+//!         }
+//!     }
+//! }
+//!
+//! #[derive(Clone)]
+//! struct DigitIter {
+//!     bits: u16,
+//! }
+//!
+//! impl Iterator for DigitIter {
+//!     type Item = Digit;
+//!
+//!     fn next(&mut self) -> Option<Digit> {
+//!         if self.bits == 0 {
+//!             return None;
+//!         }
+//!
+//!         let index = self.bits.trailing_zeros();
+//!         self.bits &= !(1 << index);
+//!         Some(Digit::from_index(index as usize))
+//!     }
+//! }
+//!
+//! struct DigitSetStorage {
+//!     bits: u16,
+//! }
+//!
+//! impl SetStorage<Digit> for DigitSetStorage {
+//!     type Iter<'this> = DigitIter;
+//!     type IntoIter = DigitIter;
+//!
+//!     fn empty() -> Self {
+//!         Self { bits: 0 }
+//!     }
+//!
+//!     fn len(&self) -> usize {
+//!         self.bits.count_ones() as usize
+//!     }
+//!
+//!     fn is_empty(&self) -> bool {
+//!         self.bits == 0
+//!     }
+//!
+//!     fn insert(&mut self, value: Digit) -> bool {
+//!         let mask = 1 << value.0;
+//!         let inserted = self.bits & mask == 0;
+//!         self.bits |= mask;
+//!         inserted
+//!     }
+//!
+//!     fn contains(&self, value: Digit) -> bool {
+//!         self.bits & (1 << value.0) != 0
+//!     }
+//!
+//!     fn remove(&mut self, value: Digit) -> bool {
+//!         let mask = 1 << value.0;
+//!         let removed = self.bits & mask != 0;
+//!         self.bits &= !mask;
+//!         removed
+//!     }
+//!
+//!     fn retain<F>(&mut self, mut f: F)
+//!     where
+//!         F: FnMut(Digit) -> bool,
+//!     {
+//!         for digit in self.iter() {
+//!             if !f(digit) {
+//!                 self.bits &= !(1 << digit.0);
+//!             }
+//!         }
+//!     }
+//!
+//!     fn clear(&mut self) {
+//!         self.bits = 0;
+//!     }
+//!
+//!     fn iter(&self) -> Self::Iter<'_> {
+//!         DigitIter { bits: self.bits }
+//!     }
+//!
+//!     fn into_iter(self) -> Self::IntoIter {
+//!         DigitIter { bits: self.bits }
+//!     }
+//! }
+//!
+//! impl Key for Digit {
+//!     type MapStorage<V> = DigitMapStorage<V>;
+//!     type SetStorage = DigitSetStorage;
+//!     const KEY_SCHEMA_HASH: u64 = fixed_map::schema::Hasher::new().write_str("Digit").finish();
+//! }
+//!
+//! let mut map = Map::new();
+//! map.insert(Digit::new(7).unwrap(), "seven");
+//! map.insert(Digit::new(2).unwrap(), "two");
+//! *map.entry(Digit::new(4).unwrap()).or_default() = "four";
+//!
+//! assert_eq!(map.get(Digit(2)), Some(&"two"));
+//! assert!(map.keys().eq([Digit(2), Digit(4), Digit(7)]));
+//!
+//! let set = map.keys().collect::<Set<_>>();
+//! assert!(set.contains(Digit(4)));
+//! assert!(set.iter().eq([Digit(2), Digit(4), Digit(7)]));
+//! ```
 
 mod boolean;
-pub(crate) use self::boolean::BooleanMapStorage;
+pub use self::boolean::BooleanMapStorage;
 
-#[cfg(feature = "hashbrown")]
+#[cfg(any(feature = "hashbrown", feature = "std-collections"))]
+pub(crate) mod map;
+
+#[cfg(any(feature = "hashbrown", feature = "std-collections"))]
 mod hashbrown;
-#[cfg(feature = "hashbrown")]
-pub(crate) use self::hashbrown::HashbrownMapStorage;
+#[cfg(any(feature = "hashbrown", feature = "std-collections"))]
+pub use self::hashbrown::HashbrownMapStorage;
+
+#[cfg(feature = "alloc")]
+mod lazy;
+#[cfg(feature = "alloc")]
+pub use self::lazy::LazyMapStorage;
+
+mod masked;
+pub use self::masked::{Mask, MaskedMapStorage};
 
 mod option;
-pub(crate) use self::option::OptionMapStorage;
+pub use self::option::OptionMapStorage;
+
+#[cfg(feature = "alloc")]
+mod ordered;
+#[cfg(feature = "alloc")]
+pub use self::ordered::OrderedMapStorage;
 
 mod singleton;
-pub(crate) use self::singleton::SingletonMapStorage;
+pub use self::singleton::SingletonMapStorage;
+
+#[cfg(feature = "alloc")]
+mod sparse;
+#[cfg(feature = "alloc")]
+pub use self::sparse::SparseMapStorage;
+
+pub(crate) mod unavailable;
+pub use self::unavailable::UnavailableMapStorage;
 
-use crate::map::Entry;
+use crate::debug::KeyName;
+use crate::layout::Branch;
+use crate::map::{Entry, OccupiedError, Stats};
+use crate::set::SetStorage;
+use crate::Key;
 
 /// The trait defining how storage works.
 ///
@@ -89,6 +412,31 @@ pub trait MapStorage<K, V>: Sized {
     /// This is the storage abstraction for [`Map::remove`][crate::Map::remove].
     fn remove(&mut self, key: K) -> Option<V>;
 
+    /// This is the storage abstraction for
+    /// [`Map::try_insert`][crate::Map::try_insert].
+    ///
+    /// The default implementation goes through [`MapStorage::entry`], so the
+    /// key is only looked up once.
+    #[inline]
+    fn try_insert(&mut self, key: K, value: V) -> Result<&mut V, OccupiedError<'_, Self, K, V>> {
+        match self.entry(key) {
+            Entry::Occupied(entry) => Err(OccupiedError::new(entry, value)),
+            Entry::Vacant(entry) => Ok(entry.insert(value)),
+        }
+    }
+
+    /// This is the storage abstraction for
+    /// [`Map::remove_and_shrink`][crate::Map::remove_and_shrink].
+    ///
+    /// The default implementation calls [`MapStorage::remove`], since storage
+    /// with a fixed layout has no capacity to give back. Dynamic storage
+    /// overrides it to release the memory it no longer needs, and composite
+    /// storage forwards it to the branch the key belongs to.
+    #[inline]
+    fn remove_and_shrink(&mut self, key: K) -> Option<V> {
+        self.remove(key)
+    }
+
     /// This is the storage abstraction for [`Map::retain`][crate::Map::retain].
     fn retain<F>(&mut self, f: F)
     where
@@ -106,6 +454,140 @@ pub trait MapStorage<K, V>: Sized {
     /// This is the storage abstraction for [`Map::values`][crate::Map::values].
     fn values(&self) -> Self::Values<'_>;
 
+    /// This is the storage abstraction for
+    /// [`Map::values_with_index`][crate::Map::values_with_index].
+    ///
+    /// The default implementation goes through [`MapStorage::iter`] and
+    /// [`Key::class_index`]. Composite storage overrides it to visit the
+    /// values of every branch directly, without constructing their keys.
+    #[inline]
+    fn values_with_index<F>(&self, mut func: F)
+    where
+        K: Key,
+        F: FnMut(usize, &V),
+    {
+        for (key, value) in self.iter() {
+            func(key.class_index(), value);
+        }
+    }
+
+    /// This is the storage abstraction for
+    /// [`Map::find_map`][crate::Map::find_map].
+    ///
+    /// The default implementation goes through [`MapStorage::iter`]. Derived
+    /// storage overrides it to test each slot directly and return as soon as
+    /// a match is found, skipping branches which are empty.
+    #[inline]
+    fn find_map<B, F>(&self, mut func: F) -> Option<B>
+    where
+        F: FnMut(K, &V) -> Option<B>,
+    {
+        self.iter().find_map(|(key, value)| func(key, value))
+    }
+
+    /// This is the storage abstraction for
+    /// [`Map::first_key_value`][crate::Map::first_key_value].
+    ///
+    /// The default implementation takes the first entry of
+    /// [`MapStorage::iter`].
+    #[inline]
+    fn first_key_value(&self) -> Option<(K, &V)> {
+        self.iter().next()
+    }
+
+    /// This is the storage abstraction for
+    /// [`Map::last_key_value`][crate::Map::last_key_value].
+    ///
+    /// The default implementation walks all of [`MapStorage::iter`]. Derived
+    /// storage overrides it to search from the last variant backwards.
+    #[inline]
+    fn last_key_value(&self) -> Option<(K, &V)> {
+        self.iter().last()
+    }
+
+    /// This is the storage abstraction for
+    /// [`Map::keys_set`][crate::Map::keys_set].
+    ///
+    /// The default implementation inserts every key from
+    /// [`MapStorage::keys`]. Derived storage overrides it to copy the
+    /// occupancy of each slot directly.
+    #[inline]
+    fn key_set(&self) -> K::SetStorage
+    where
+        K: Key,
+    {
+        let mut output = K::SetStorage::empty();
+
+        for key in self.keys() {
+            output.insert(key);
+        }
+
+        output
+    }
+
+    /// This is the storage abstraction for
+    /// [`Map::retain_keys`][crate::Map::retain_keys].
+    ///
+    /// The default implementation calls [`MapStorage::retain`] with a closure
+    /// which looks up every key in `allowed`. Derived storage overrides it to
+    /// clear each slot which isn't occupied in `allowed` directly.
+    #[inline]
+    fn retain_keys(&mut self, allowed: &K::SetStorage)
+    where
+        K: Key,
+    {
+        self.retain(|key, _| allowed.contains(key));
+    }
+
+    /// Stores a value produced by `func` in every vacant slot of the storage
+    /// which exists without being allocated.
+    ///
+    /// This is used by [`CellMap`][crate::CellMap] so that keys with fixed
+    /// storage can be updated through a shared reference. The default
+    /// implementation does nothing, which is what dynamic storage such as a
+    /// hash map wants. Derived storage fills every unit variant and the fixed
+    /// slots of nested keys.
+    #[inline]
+    fn fill_fixed<F>(&mut self, _func: F)
+    where
+        F: FnMut() -> V,
+    {
+    }
+
+    /// This is the storage abstraction for
+    /// [`Map::map_values`][crate::Map::map_values].
+    ///
+    /// The default implementation moves every entry through
+    /// [`MapStorage::into_iter`] into empty storage. Derived storage overrides
+    /// it to convert each slot in place, without looking up keys again.
+    #[inline]
+    fn map_values<W, F>(self, mut func: F) -> K::MapStorage<W>
+    where
+        K: Key,
+        F: FnMut(K, V) -> W,
+    {
+        let mut output = K::MapStorage::<W>::empty();
+
+        for (key, value) in self.into_iter() {
+            output.insert(key, func(key, value));
+        }
+
+        output
+    }
+
+    /// This is the storage abstraction for
+    /// [`layout::report`][crate::layout::report].
+    ///
+    /// Visits every branch of the storage in declaration order. The default
+    /// implementation visits nothing. Derived storage reports the field which
+    /// holds the values of each variant.
+    #[inline]
+    fn layout<F>(_func: F)
+    where
+        F: FnMut(Branch),
+    {
+    }
+
     /// This is the storage abstraction for [`Map::iter_mut`][crate::Map::iter_mut].
     fn iter_mut(&mut self) -> Self::IterMut<'_>;
 
@@ -117,6 +599,267 @@ pub trait MapStorage<K, V>: Sized {
 
     /// This is the storage abstraction for [`Map::entry`][crate::Map::entry].
     fn entry(&mut self, key: K) -> Entry<'_, Self, K, V>;
+
+    /// This is the storage abstraction for [`Map::entry_ref`][crate::Map::entry_ref].
+    ///
+    /// Composite storages pass the nested part of the key on by reference, so
+    /// that it's only copied by the storage which needs to own it.
+    #[inline]
+    fn entry_ref(&mut self, key: &K) -> Entry<'_, Self, K, V>
+    where
+        K: Copy,
+    {
+        self.entry(*key)
+    }
+
+    /// This is the storage abstraction for
+    /// [`Map::get_disjoint_mut`][crate::Map::get_disjoint_mut].
+    ///
+    /// For every position in `output`, `key` returns the key whose value
+    /// should be stored there, or `None` if the position is left to other
+    /// storage. `eq` tests if two keys are the same. The map has already
+    /// checked that no key is requested more than once.
+    ///
+    /// The default implementation visits every entry once through
+    /// [`MapStorage::iter_mut`]. Storage which can address entries directly
+    /// can override it, like composite keys do to only visit the storage of
+    /// the variants which are requested.
+    fn get_disjoint_mut<'a>(
+        &'a mut self,
+        key: &dyn Fn(usize) -> Option<K>,
+        eq: &dyn Fn(&K, &K) -> bool,
+        output: &mut [Option<&'a mut V>],
+    ) {
+        for (k, value) in self.iter_mut() {
+            if let Some(n) = (0..output.len()).find(|&n| key(n).is_some_and(|key| eq(&key, &k))) {
+                output[n] = Some(value);
+            }
+        }
+    }
+
+    /// This is the storage abstraction for
+    /// [`Map::debug_assert_invariants`][crate::Map::debug_assert_invariants].
+    ///
+    /// The default implementation checks that the length of the storage
+    /// agrees with its iterators, and that every key which is iterated over
+    /// can be looked up. Storage which keeps additional bookkeeping can
+    /// override it to validate that as well.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the storage is inconsistent.
+    #[track_caller]
+    fn assert_invariants(&self)
+    where
+        K: Key,
+    {
+        let ty = core::any::type_name::<crate::Map<K, V>>();
+        let len = self.len();
+        assert_eq!(
+            self.is_empty(),
+            len == 0,
+            "{ty}: is_empty disagrees with len"
+        );
+        assert_eq!(self.iter().count(), len, "{ty}: iter disagrees with len");
+        assert_eq!(self.keys().count(), len, "{ty}: keys disagrees with len");
+        assert_eq!(
+            self.values().count(),
+            len,
+            "{ty}: values disagrees with len"
+        );
+
+        for key in self.keys() {
+            assert!(
+                self.contains_key(key),
+                "{ty}: iterated key `{}` is not contained",
+                KeyName(key)
+            );
+            assert!(
+                self.get(key).is_some(),
+                "{ty}: iterated key `{}` has no value",
+                KeyName(key)
+            );
+        }
+    }
+}
+
+/// Storage for keys with a finite domain, which can hand out an [`Entry`] for
+/// every key in the domain at once.
+///
+/// This is implemented for the storage of derived keys as long as the storage
+/// of every field implements it, but not for storage of unbounded keys like
+/// [`u32`] or [`&str`][str].
+///
+/// # Type Arguments
+///
+/// - `K` is the key being stored.
+/// - `V` is the value being stored.
+pub trait EntriesStorage<K, V>: MapStorage<K, V> {
+    /// Iterator over an entry for every key in the domain.
+    type EntriesMut<'this>: Iterator<Item = Entry<'this, Self, K, V>>
+    where
+        Self: 'this,
+        V: 'this;
+
+    /// This is the storage abstraction for [`Map::entries_mut`][crate::Map::entries_mut].
+    fn entries_mut(&mut self) -> Self::EntriesMut<'_>;
+}
+
+/// Storage which can report occupancy [`Stats`].
+///
+/// This is implemented for the storage of derived keys as long as the storage
+/// of every field implements it.
+///
+/// # Type Arguments
+///
+/// - `K` is the key being stored.
+/// - `V` is the value being stored.
+pub trait StatsStorage<K, V>: MapStorage<K, V> {
+    /// This is the storage abstraction for [`Map::stats`][crate::Map::stats].
+    fn stats(&self) -> Stats;
+}
+
+/// Storage which can remove every entry through an iterator, while keeping any
+/// memory it has allocated for reuse.
+///
+/// Storage with a fixed layout has nothing to keep, and is simply emptied up
+/// front. Dynamic storage hands out the draining iterator of its collection,
+/// so the capacity of for example a variant holding a `u32` is retained.
+///
+/// This is implemented for the storage of derived keys as long as the storage
+/// of every field implements it, and for the storage of keys provided by this
+/// crate.
+///
+/// # Type Arguments
+///
+/// - `K` is the key being stored.
+/// - `V` is the value being stored.
+pub trait DrainStorage<K, V>: MapStorage<K, V> {
+    /// Iterator which removes every entry in storage. Any entries which
+    /// haven't been yielded when it's dropped are removed as well.
+    type Drain<'this>: Iterator<Item = (K, V)>
+    where
+        Self: 'this;
+
+    /// This is the storage abstraction for [`Map::drain`][crate::Map::drain].
+    fn drain(&mut self) -> Self::Drain<'_>;
+}
+
+/// Storage which can remove the entries matching a filter through a cursor,
+/// while leaving the other entries in place.
+///
+/// The filter is passed to every step of the cursor rather than being stored
+/// in it, so that the storage of a derived key can hand it down to the
+/// storage of its fields with the key mapped to the variant holding them.
+///
+/// This is implemented for the storage of derived keys as long as the storage
+/// of every field implements it, and for the storage of keys provided by this
+/// crate.
+///
+/// # Type Arguments
+///
+/// - `K` is the key being stored.
+/// - `V` is the value being stored.
+pub trait ExtractIfStorage<K, V>: MapStorage<K, V> {
+    /// Cursor over the entries in storage. Entries which haven't been
+    /// visited when it's dropped are left in place.
+    type ExtractIf<'this>: ExtractIfCursor<K, V>
+    where
+        Self: 'this;
+
+    /// This is the storage abstraction for [`Map::extract_if`][crate::Map::extract_if].
+    fn extract_if(&mut self) -> Self::ExtractIf<'_>;
+}
+
+/// The cursor of an [`ExtractIfStorage`].
+///
+/// # Type Arguments
+///
+/// - `K` is the key being stored.
+/// - `V` is the value being stored.
+pub trait ExtractIfCursor<K, V> {
+    /// Remove and return the next entry for which `filter` returns `true`, or
+    /// `None` if every entry has been visited.
+    ///
+    /// The filter is called at most once for every entry.
+    fn next_if<F>(&mut self, filter: &mut F) -> Option<(K, V)>
+    where
+        F: ?Sized + FnMut(K, &mut V) -> bool;
+}
+
+/// A cursor which is absent has no entries to visit, which is used by storage
+/// that is allocated on demand.
+impl<C, K, V> ExtractIfCursor<K, V> for Option<C>
+where
+    C: ExtractIfCursor<K, V>,
+{
+    #[inline]
+    fn next_if<F>(&mut self, filter: &mut F) -> Option<(K, V)>
+    where
+        F: ?Sized + FnMut(K, &mut V) -> bool,
+    {
+        self.as_mut()?.next_if(filter)
+    }
+}
+
+/// Storage which can address every key of a class at once, where keys belong
+/// to the same class if they share a [`variant_name`].
+///
+/// A class is identified by the [`class_index`] of its keys. For derived keys
+/// this is the storage of a single variant, so operating on a class doesn't
+/// have to visit the storage of any other variant. Indexes which don't
+/// correspond to a class are treated as an empty class.
+///
+/// Clearing a class keeps any memory allocated by it, so that it can be
+/// refilled without growing again.
+///
+/// This is implemented for the storage of derived keys and of keys provided
+/// by this crate.
+///
+/// [`variant_name`]: crate::Key::variant_name
+/// [`class_index`]: crate::Key::class_index
+///
+/// # Type Arguments
+///
+/// - `K` is the key being stored.
+/// - `V` is the value being stored.
+pub trait ClassStorage<K, V>: MapStorage<K, V> {
+    /// Immutable iterator over the entries of a class.
+    type ClassIter<'this>: Iterator<Item = (K, &'this V)>
+    where
+        Self: 'this,
+        V: 'this;
+
+    /// This is the storage abstraction for [`Map::len_of`][crate::Map::len_of].
+    fn class_len(&self, class: usize) -> usize;
+
+    /// This is the storage abstraction for [`Map::clear_of`][crate::Map::clear_of]
+    /// and [`Map::clear_key_class`][crate::Map::clear_key_class].
+    fn clear_class(&mut self, class: usize);
+
+    /// This is the storage abstraction for [`Map::iter_group`][crate::Map::iter_group].
+    fn iter_class(&self, class: usize) -> Self::ClassIter<'_>;
+}
+
+/// Storage laid out as a slice with one slot for every key, where the slot of
+/// a key is its [`KeyIndex::to_index`].
+///
+/// This is implemented for the storage of derived enums whose variants are
+/// all unit variants, unless `#[key(sparse)]` is used.
+///
+/// [`KeyIndex::to_index`]: crate::raw::KeyIndex::to_index
+///
+/// # Type Arguments
+///
+/// - `K` is the key being stored.
+/// - `V` is the value being stored.
+pub trait SliceStorage<K, V>: MapStorage<K, V> {
+    /// This is the storage abstraction for [`Map::range`][crate::Map::range].
+    fn as_slice(&self) -> &[Option<V>];
+
+    /// This is the storage abstraction for
+    /// [`Map::insert_for_range`][crate::Map::insert_for_range].
+    fn as_mut_slice(&mut self) -> &mut [Option<V>];
 }
 
 /// A view into an occupied entry in a [`Map`][crate::Map]. It is part of the
@@ -171,6 +914,41 @@ pub trait OccupiedEntry<'a, K, V> {
     /// ```
     fn key(&self) -> K;
 
+    /// Gets a reference to the key in the entry.
+    ///
+    /// For composite keys this is the full key including the payload of any
+    /// nested keys, as it is stored in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    /// use fixed_map::map::{Entry, OccupiedEntry};
+    ///
+    /// #[derive(Clone, Copy, Key, Debug, PartialEq)]
+    /// enum Inner {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// #[derive(Clone, Copy, Key, Debug, PartialEq)]
+    /// enum MyKey {
+    ///     First(Option<Inner>),
+    ///     Second,
+    /// }
+    ///
+    /// let mut map: Map<MyKey, i32> = Map::new();
+    /// map.insert(MyKey::First(Some(Inner::Second)), 12);
+    ///
+    /// let occupied = match map.entry(MyKey::First(Some(Inner::Second))) {
+    ///     Entry::Occupied(entry) => entry,
+    ///     _ => unreachable!(),
+    /// };
+    ///
+    /// assert_eq!(occupied.key_ref(), &MyKey::First(Some(Inner::Second)));
+    /// ```
+    fn key_ref(&self) -> &K;
+
     /// Gets a reference to the value in the entry.
     ///
     /// # Examples
@@ -470,6 +1248,40 @@ pub trait VacantEntry<'a, K, V> {
     /// ```
     fn key(&self) -> K;
 
+    /// Gets a reference to the key that would be used when inserting a value
+    /// through the `VacantEntry`.
+    ///
+    /// For composite keys this is the full key including the payload of any
+    /// nested keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map};
+    /// use fixed_map::map::{Entry, VacantEntry};
+    ///
+    /// #[derive(Clone, Copy, Key, Debug, PartialEq)]
+    /// enum Inner {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// #[derive(Clone, Copy, Key, Debug, PartialEq)]
+    /// enum MyKey {
+    ///     First(Option<Inner>),
+    ///     Second,
+    /// }
+    ///
+    /// let mut map: Map<MyKey, i32> = Map::new();
+    /// let vacant = match map.entry(MyKey::First(None)) {
+    ///     Entry::Vacant(entry) => entry,
+    ///     _ => unreachable!(),
+    /// };
+    ///
+    /// assert_eq!(vacant.key_ref(), &MyKey::First(None));
+    /// ```
+    fn key_ref(&self) -> &K;
+
     /// Sets the value of the entry with the `VacantEntry`’s key,
     /// and returns a mutable reference to it.
     ///
@@ -516,3 +1328,22 @@ pub trait VacantEntry<'a, K, V> {
     /// ```
     fn insert(self, value: V) -> &'a mut V;
 }
+
+/// Assert that no key is passed to
+/// [`Map::get_disjoint_mut`][crate::Map::get_disjoint_mut] more than once,
+/// where `eq` tests if two keys are the same.
+#[track_caller]
+pub(crate) fn assert_disjoint<K, F>(keys: &[K], mut eq: F)
+where
+    K: Key,
+    F: FnMut(&K, &K) -> bool,
+{
+    for (n, key) in keys.iter().enumerate() {
+        if keys[..n].iter().any(|other| eq(other, key)) {
+            panic!(
+                "key `{}` was passed to get_disjoint_mut more than once",
+                KeyName(*key)
+            );
+        }
+    }
+}
@@ -2,12 +2,19 @@
 
 #![allow(missing_copy_implementations)]
 
+use core::array;
 use core::iter;
-use core::mem;
 use core::option;
 
-use crate::map::{Entry, MapStorage, OccupiedEntry, VacantEntry};
+use crate::layout::Branch;
+use crate::macro_support::{__storage_drain, __storage_offset_of, StorageExtractSlots};
+use crate::map::{
+    ClassStorage, DrainStorage, EntriesStorage, Entry, ExtractIfStorage, MapStorage, OccupiedEntry,
+    Stats, StatsStorage, VacantEntry,
+};
 use crate::option_bucket::{NoneBucket, OptionBucket, SomeBucket};
+use crate::set::storage::BooleanSetStorage;
+use crate::set::SetStorage;
 
 const TRUE_BIT: u8 = 0b10;
 const FALSE_BIT: u8 = 0b01;
@@ -154,6 +161,11 @@ impl<'a, V> VacantEntry<'a, bool, V> for Vacant<'a, V> {
         self.key
     }
 
+    #[inline]
+    fn key_ref(&self) -> &bool {
+        &self.key
+    }
+
     #[inline]
     fn insert(self, value: V) -> &'a mut V {
         self.inner.insert(value)
@@ -166,6 +178,11 @@ impl<'a, V> OccupiedEntry<'a, bool, V> for Occupied<'a, V> {
         self.key
     }
 
+    #[inline]
+    fn key_ref(&self) -> &bool {
+        &self.key
+    }
+
     #[inline]
     fn get(&self) -> &V {
         self.inner.as_ref()
@@ -244,9 +261,9 @@ impl<V> MapStorage<bool, V> for BooleanMapStorage<V> {
     #[inline]
     fn insert(&mut self, key: bool, value: V) -> Option<V> {
         if key {
-            mem::replace(&mut self.t, Some(value))
+            self.t.replace(value)
         } else {
-            mem::replace(&mut self.f, Some(value))
+            self.f.replace(value)
         }
     }
 
@@ -303,6 +320,18 @@ impl<V> MapStorage<bool, V> for BooleanMapStorage<V> {
         }
     }
 
+    #[inline]
+    fn find_map<B, F>(&self, mut func: F) -> Option<B>
+    where
+        F: FnMut(bool, &V) -> Option<B>,
+    {
+        if let Some(output) = self.t.as_ref().and_then(|t| func(true, t)) {
+            return Some(output);
+        }
+
+        self.f.as_ref().and_then(|f| func(false, f))
+    }
+
     #[inline]
     fn clear(&mut self) {
         self.t = None;
@@ -354,18 +383,186 @@ impl<V> MapStorage<bool, V> for BooleanMapStorage<V> {
         a.chain(b)
     }
 
+    #[inline]
+    fn first_key_value(&self) -> Option<(bool, &V)> {
+        match &self.t {
+            Some(t) => Some((true, t)),
+            None => Some((false, self.f.as_ref()?)),
+        }
+    }
+
+    #[inline]
+    fn last_key_value(&self) -> Option<(bool, &V)> {
+        match &self.f {
+            Some(f) => Some((false, f)),
+            None => Some((true, self.t.as_ref()?)),
+        }
+    }
+
+    #[inline]
+    fn key_set(&self) -> BooleanSetStorage {
+        let mut output = BooleanSetStorage::empty();
+
+        if self.t.is_some() {
+            output.insert(true);
+        }
+
+        if self.f.is_some() {
+            output.insert(false);
+        }
+
+        output
+    }
+
+    #[inline]
+    fn retain_keys(&mut self, allowed: &BooleanSetStorage) {
+        if !allowed.contains(true) {
+            self.t = None;
+        }
+
+        if !allowed.contains(false) {
+            self.f = None;
+        }
+    }
+
+    #[inline]
+    fn fill_fixed<F>(&mut self, mut func: F)
+    where
+        F: FnMut() -> V,
+    {
+        if self.t.is_none() {
+            self.t = Some(func());
+        }
+
+        if self.f.is_none() {
+            self.f = Some(func());
+        }
+    }
+
+    #[inline]
+    fn map_values<W, F>(self, mut func: F) -> BooleanMapStorage<W>
+    where
+        F: FnMut(bool, V) -> W,
+    {
+        BooleanMapStorage {
+            t: self.t.map(|v| func(true, v)),
+            f: self.f.map(|v| func(false, v)),
+        }
+    }
+
+    #[inline]
+    fn layout<F>(mut func: F)
+    where
+        F: FnMut(Branch),
+    {
+        let storage = Self::empty();
+
+        func(Branch::new::<Option<V>>(
+            "true",
+            Some(__storage_offset_of(&storage, &storage.t)),
+        ));
+        func(Branch::new::<Option<V>>(
+            "false",
+            Some(__storage_offset_of(&storage, &storage.f)),
+        ));
+    }
+
     #[inline]
     fn entry(&mut self, key: bool) -> Entry<'_, Self, bool, V> {
         if key {
-            match OptionBucket::new(&mut self.t) {
-                OptionBucket::Some(inner) => Entry::Occupied(Occupied { key, inner }),
-                OptionBucket::None(inner) => Entry::Vacant(Vacant { key, inner }),
-            }
+            option_to_entry(&mut self.t, key)
         } else {
-            match OptionBucket::new(&mut self.f) {
-                OptionBucket::Some(inner) => Entry::Occupied(Occupied { key, inner }),
-                OptionBucket::None(inner) => Entry::Vacant(Vacant { key, inner }),
-            }
+            option_to_entry(&mut self.f, key)
         }
     }
 }
+
+impl<V> EntriesStorage<bool, V> for BooleanMapStorage<V> {
+    type EntriesMut<'this>
+        = array::IntoIter<Entry<'this, Self, bool, V>, 2>
+    where
+        V: 'this;
+
+    #[inline]
+    fn entries_mut(&mut self) -> Self::EntriesMut<'_> {
+        [
+            option_to_entry(&mut self.t, true),
+            option_to_entry(&mut self.f, false),
+        ]
+        .into_iter()
+    }
+}
+
+#[inline]
+fn option_to_entry<V>(
+    option: &mut Option<V>,
+    key: bool,
+) -> Entry<'_, BooleanMapStorage<V>, bool, V> {
+    match OptionBucket::new(option) {
+        OptionBucket::Some(inner) => Entry::Occupied(Occupied { key, inner }),
+        OptionBucket::None(inner) => Entry::Vacant(Vacant { key, inner }),
+    }
+}
+
+impl<V> ClassStorage<bool, V> for BooleanMapStorage<V> {
+    type ClassIter<'this>
+        = option::IntoIter<(bool, &'this V)>
+    where
+        V: 'this;
+
+    #[inline]
+    fn class_len(&self, class: usize) -> usize {
+        usize::from(self.iter_class(class).next().is_some())
+    }
+
+    #[inline]
+    fn clear_class(&mut self, class: usize) {
+        match class {
+            0 => self.f = None,
+            1 => self.t = None,
+            _ => {}
+        }
+    }
+
+    #[inline]
+    fn iter_class(&self, class: usize) -> Self::ClassIter<'_> {
+        let entry = match class {
+            0 => self.f.as_ref().map(|value| (false, value)),
+            1 => self.t.as_ref().map(|value| (true, value)),
+            _ => None,
+        };
+
+        entry.into_iter()
+    }
+}
+
+impl<V> StatsStorage<bool, V> for BooleanMapStorage<V> {
+    #[inline]
+    fn stats(&self) -> Stats {
+        Stats::fixed(2, self.len())
+    }
+}
+
+impl<V> DrainStorage<bool, V> for BooleanMapStorage<V> {
+    type Drain<'this>
+        = IntoIter<V>
+    where
+        V: 'this;
+
+    #[inline]
+    fn drain(&mut self) -> Self::Drain<'_> {
+        __storage_drain(self)
+    }
+}
+
+impl<V> ExtractIfStorage<bool, V> for BooleanMapStorage<V> {
+    type ExtractIf<'this>
+        = StorageExtractSlots<array::IntoIter<(bool, &'this mut Option<V>), 2>>
+    where
+        V: 'this;
+
+    #[inline]
+    fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+        StorageExtractSlots::new([(true, &mut self.t), (false, &mut self.f)])
+    }
+}
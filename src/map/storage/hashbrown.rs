@@ -1,18 +1,24 @@
 use core::hash::Hash;
 use core::iter;
+use core::mem;
+use core::option;
 
-use crate::map::{Entry, MapStorage, OccupiedEntry, VacantEntry};
-
-type S = ::hashbrown::hash_map::DefaultHashBuilder;
-type Occupied<'a, K, V> = ::hashbrown::hash_map::OccupiedEntry<'a, K, V, S>;
-type Vacant<'a, K, V> = ::hashbrown::hash_map::VacantEntry<'a, K, V, S>;
-type HashMapEntry<'a, K, V> = ::hashbrown::hash_map::Entry<'a, K, V, S>;
+use crate::map::storage::map::{
+    hash_map, Entry as HashMapEntry, HashMap, OccupiedEntry as Occupied, VacantEntry as Vacant,
+};
+use crate::map::{
+    ClassStorage, DrainStorage, Entry, ExtractIfCursor, ExtractIfStorage, MapStorage,
+    OccupiedEntry, Stats, StatsStorage, VacantEntry,
+};
 
 /// [`MapStorage`] for dynamic types, using [`hashbrown::HashMap`].
 ///
 /// This allows for dynamic types such as `&'static str` or `u32` to be used as
 /// a [`Key`][crate::Key].
 ///
+/// If the `std-collections` feature is enabled, this is backed by
+/// [`std::collections::HashMap`] instead.
+///
 /// # Examples
 ///
 /// ```
@@ -32,7 +38,7 @@ type HashMapEntry<'a, K, V> = ::hashbrown::hash_map::Entry<'a, K, V, S>;
 /// ```
 #[repr(transparent)]
 pub struct HashbrownMapStorage<K, V> {
-    inner: ::hashbrown::HashMap<K, V>,
+    inner: HashMap<K, V>,
 }
 
 impl<K, V> Clone for HashbrownMapStorage<K, V>
@@ -75,6 +81,11 @@ where
         *self.key()
     }
 
+    #[inline]
+    fn key_ref(&self) -> &K {
+        self.key()
+    }
+
     #[inline]
     fn get(&self) -> &V {
         self.get()
@@ -110,6 +121,11 @@ where
         *self.key()
     }
 
+    #[inline]
+    fn key_ref(&self) -> &K {
+        self.key()
+    }
+
     #[inline]
     fn insert(self, value: V) -> &'a mut V {
         self.insert(value)
@@ -121,37 +137,32 @@ where
     K: Copy + Eq + Hash,
 {
     type Iter<'this>
-        = iter::Map<
-        ::hashbrown::hash_map::Iter<'this, K, V>,
-        fn((&'this K, &'this V)) -> (K, &'this V),
-    >
+        = iter::Map<hash_map::Iter<'this, K, V>, fn((&'this K, &'this V)) -> (K, &'this V)>
     where
         K: 'this,
         V: 'this;
     type Keys<'this>
-        = iter::Copied<::hashbrown::hash_map::Keys<'this, K, V>>
+        = iter::Copied<hash_map::Keys<'this, K, V>>
     where
         K: 'this,
         V: 'this;
     type Values<'this>
-        = ::hashbrown::hash_map::Values<'this, K, V>
+        = hash_map::Values<'this, K, V>
     where
         K: 'this,
         V: 'this;
     type IterMut<'this>
-        = iter::Map<
-        ::hashbrown::hash_map::IterMut<'this, K, V>,
-        fn((&'this K, &'this mut V)) -> (K, &'this mut V),
-    >
+        =
+        iter::Map<hash_map::IterMut<'this, K, V>, fn((&'this K, &'this mut V)) -> (K, &'this mut V)>
     where
         K: 'this,
         V: 'this;
     type ValuesMut<'this>
-        = ::hashbrown::hash_map::ValuesMut<'this, K, V>
+        = hash_map::ValuesMut<'this, K, V>
     where
         K: 'this,
         V: 'this;
-    type IntoIter = ::hashbrown::hash_map::IntoIter<K, V>;
+    type IntoIter = hash_map::IntoIter<K, V>;
     type Occupied<'this>
         = Occupied<'this, K, V>
     where
@@ -166,7 +177,7 @@ where
     #[inline]
     fn empty() -> Self {
         Self {
-            inner: ::hashbrown::HashMap::new(),
+            inner: HashMap::new(),
         }
     }
 
@@ -205,6 +216,13 @@ where
         self.inner.remove(&key)
     }
 
+    #[inline]
+    fn remove_and_shrink(&mut self, key: K) -> Option<V> {
+        let value = self.inner.remove(&key)?;
+        self.inner.shrink_to_fit();
+        Some(value)
+    }
+
     #[inline]
     fn retain<F>(&mut self, mut func: F)
     where
@@ -258,3 +276,128 @@ where
         }
     }
 }
+
+impl<K, V> ClassStorage<K, V> for HashbrownMapStorage<K, V>
+where
+    K: Copy + Eq + Hash,
+{
+    type ClassIter<'this>
+        = iter::Flatten<option::IntoIter<<Self as MapStorage<K, V>>::Iter<'this>>>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn class_len(&self, class: usize) -> usize {
+        if class == 0 {
+            self.inner.len()
+        } else {
+            0
+        }
+    }
+
+    /// Every key of a dynamic type belongs to the same class, so this clears
+    /// the whole map while keeping its capacity.
+    #[inline]
+    fn clear_class(&mut self, class: usize) {
+        if class == 0 {
+            self.inner.clear();
+        }
+    }
+
+    #[inline]
+    fn iter_class(&self, class: usize) -> Self::ClassIter<'_> {
+        (class == 0).then(|| self.iter()).into_iter().flatten()
+    }
+}
+
+impl<K, V> StatsStorage<K, V> for HashbrownMapStorage<K, V>
+where
+    K: Copy + Eq + Hash,
+{
+    #[inline]
+    fn stats(&self) -> Stats {
+        Stats::dynamic(self.inner.len(), self.inner.capacity())
+    }
+}
+
+impl<K, V> DrainStorage<K, V> for HashbrownMapStorage<K, V>
+where
+    K: Copy + Eq + Hash,
+{
+    type Drain<'this>
+        = hash_map::Drain<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn drain(&mut self) -> Self::Drain<'_> {
+        self.inner.drain()
+    }
+}
+
+/// See [`HashbrownMapStorage::extract_if`].
+pub struct ExtractIf<'a, K, V>
+where
+    K: Eq + Hash,
+{
+    inner: &'a mut HashMap<K, V>,
+    taken: hash_map::IntoIter<K, V>,
+}
+
+impl<K, V> ExtractIfCursor<K, V> for ExtractIf<'_, K, V>
+where
+    K: Copy + Eq + Hash,
+{
+    #[inline]
+    fn next_if<F>(&mut self, filter: &mut F) -> Option<(K, V)>
+    where
+        F: ?Sized + FnMut(K, &mut V) -> bool,
+    {
+        for (key, mut value) in &mut self.taken {
+            if filter(key, &mut value) {
+                return Some((key, value));
+            }
+
+            self.inner.insert(key, value);
+        }
+
+        None
+    }
+}
+
+impl<K, V> Drop for ExtractIf<'_, K, V>
+where
+    K: Eq + Hash,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.inner.extend(&mut self.taken);
+    }
+}
+
+impl<K, V> ExtractIfStorage<K, V> for HashbrownMapStorage<K, V>
+where
+    K: Copy + Eq + Hash,
+{
+    type ExtractIf<'this>
+        = ExtractIf<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+
+    // NB: Neither hash map provides a stable way to remove entries while
+    // iterating without holding on to the filter, so the entries are moved
+    // out and the ones which are kept are inserted back.
+    #[inline]
+    fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+        let capacity = self.inner.len();
+        let taken = mem::replace(&mut self.inner, HashMap::with_capacity(capacity));
+
+        ExtractIf {
+            inner: &mut self.inner,
+            taken: taken.into_iter(),
+        }
+    }
+}
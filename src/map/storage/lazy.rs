@@ -0,0 +1,421 @@
+use core::iter;
+use core::option;
+
+use alloc::boxed::Box;
+
+use crate::map::{
+    DrainStorage, EntriesStorage, Entry, ExtractIfStorage, MapStorage, Stats, StatsStorage,
+};
+use crate::set::SetStorage;
+use crate::Key;
+
+type Inner<K, V> = <K as Key>::MapStorage<V>;
+
+type Iter<'a, K, V> = iter::Flatten<option::IntoIter<<Inner<K, V> as MapStorage<K, V>>::Iter<'a>>>;
+type Keys<'a, K, V> = iter::Flatten<option::IntoIter<<Inner<K, V> as MapStorage<K, V>>::Keys<'a>>>;
+type Values<'a, K, V> =
+    iter::Flatten<option::IntoIter<<Inner<K, V> as MapStorage<K, V>>::Values<'a>>>;
+type IterMut<'a, K, V> =
+    iter::Flatten<option::IntoIter<<Inner<K, V> as MapStorage<K, V>>::IterMut<'a>>>;
+type ValuesMut<'a, K, V> =
+    iter::Flatten<option::IntoIter<<Inner<K, V> as MapStorage<K, V>>::ValuesMut<'a>>>;
+type IntoIter<K, V> = iter::Flatten<option::IntoIter<<Inner<K, V> as MapStorage<K, V>>::IntoIter>>;
+
+/// [`MapStorage`] which only allocates the storage of a nested key once a
+/// value is inserted into it.
+///
+/// This is used by variants marked with `#[key(lazy)]`, so that a map only
+/// pays for the pointer of a branch which is never used rather than for the
+/// full storage of it.
+///
+/// Note that [`Map::entry`][crate::Map::entry] allocates the storage, since
+/// the returned entry borrows from it.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "hashbrown")] {
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     #[key(lazy)]
+///     Channel(u32),
+///     Main,
+/// }
+///
+/// let mut map = Map::new();
+/// assert!(map.is_empty());
+///
+/// map.insert(MyKey::Channel(7), 1);
+/// map.insert(MyKey::Main, 2);
+///
+/// assert_eq!(map.get(MyKey::Channel(7)), Some(&1));
+/// assert_eq!(map.get(MyKey::Channel(8)), None);
+/// assert!(map.keys().eq([MyKey::Channel(7), MyKey::Main]));
+/// # }
+/// ```
+pub struct LazyMapStorage<K, V>
+where
+    K: Key,
+{
+    inner: Option<Box<K::MapStorage<V>>>,
+}
+
+impl<K, V> LazyMapStorage<K, V>
+where
+    K: Key,
+{
+    /// Access the inner storage, allocating it if necessary.
+    #[inline]
+    fn get_or_alloc(&mut self) -> &mut K::MapStorage<V> {
+        self.inner
+            .get_or_insert_with(|| Box::new(K::MapStorage::empty()))
+    }
+}
+
+impl<K, V> Clone for LazyMapStorage<K, V>
+where
+    K: Key,
+    V: Clone,
+    K::MapStorage<V>: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K, V> PartialEq for LazyMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: PartialEq,
+    V: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        // NB: Storage which hasn't been allocated is equal to allocated
+        // storage which is empty.
+        match (&self.inner, &other.inner) {
+            (Some(a), Some(b)) => a == b,
+            (Some(inner), None) | (None, Some(inner)) => inner.is_empty(),
+            (None, None) => true,
+        }
+    }
+}
+
+impl<K, V> Eq for LazyMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V> MapStorage<K, V> for LazyMapStorage<K, V>
+where
+    K: Key,
+{
+    type Iter<'this>
+        = Iter<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+    type Keys<'this>
+        = Keys<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+    type Values<'this>
+        = Values<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+    type IterMut<'this>
+        = IterMut<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+    type ValuesMut<'this>
+        = ValuesMut<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+    type IntoIter = IntoIter<K, V>;
+    type Occupied<'this>
+        = <K::MapStorage<V> as MapStorage<K, V>>::Occupied<'this>
+    where
+        K: 'this,
+        V: 'this;
+    type Vacant<'this>
+        = <K::MapStorage<V> as MapStorage<K, V>>::Vacant<'this>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn empty() -> Self {
+        Self { inner: None }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.as_ref().map_or(0, |inner| inner.len())
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.inner.as_ref().map_or(true, |inner| inner.is_empty())
+    }
+
+    #[inline]
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.get_or_alloc().insert(key, value)
+    }
+
+    #[inline]
+    fn contains_key(&self, key: K) -> bool {
+        self.inner
+            .as_ref()
+            .is_some_and(|inner| inner.contains_key(key))
+    }
+
+    #[inline]
+    fn get(&self, key: K) -> Option<&V> {
+        self.inner.as_ref()?.get(key)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        self.inner.as_mut()?.get_mut(key)
+    }
+
+    #[inline]
+    fn remove(&mut self, key: K) -> Option<V> {
+        self.inner.as_mut()?.remove(key)
+    }
+
+    /// Deallocates the storage of the branch once its last value is removed.
+    #[inline]
+    fn remove_and_shrink(&mut self, key: K) -> Option<V> {
+        let inner = self.inner.as_mut()?;
+        let value = inner.remove_and_shrink(key)?;
+
+        if inner.is_empty() {
+            self.inner = None;
+        }
+
+        Some(value)
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, func: F)
+    where
+        F: FnMut(K, &mut V) -> bool,
+    {
+        if let Some(inner) = &mut self.inner {
+            inner.retain(func);
+        }
+    }
+
+    #[inline]
+    fn first_key_value(&self) -> Option<(K, &V)> {
+        self.inner.as_ref()?.first_key_value()
+    }
+
+    #[inline]
+    fn last_key_value(&self) -> Option<(K, &V)> {
+        self.inner.as_ref()?.last_key_value()
+    }
+
+    #[inline]
+    fn key_set(&self) -> K::SetStorage {
+        self.inner
+            .as_ref()
+            .map_or_else(SetStorage::empty, |inner| inner.key_set())
+    }
+
+    #[inline]
+    fn retain_keys(&mut self, allowed: &K::SetStorage) {
+        if let Some(inner) = &mut self.inner {
+            inner.retain_keys(allowed);
+        }
+    }
+
+    #[inline]
+    fn find_map<B, F>(&self, func: F) -> Option<B>
+    where
+        F: FnMut(K, &V) -> Option<B>,
+    {
+        self.inner.as_ref()?.find_map(func)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        if let Some(inner) = &mut self.inner {
+            inner.clear();
+        }
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter<'_> {
+        self.inner
+            .as_deref()
+            .map(|inner| inner.iter())
+            .into_iter()
+            .flatten()
+    }
+
+    #[inline]
+    fn keys(&self) -> Self::Keys<'_> {
+        self.inner
+            .as_deref()
+            .map(|inner| inner.keys())
+            .into_iter()
+            .flatten()
+    }
+
+    #[inline]
+    fn values(&self) -> Self::Values<'_> {
+        self.inner
+            .as_deref()
+            .map(|inner| inner.values())
+            .into_iter()
+            .flatten()
+    }
+
+    #[inline]
+    fn values_with_index<F>(&self, func: F)
+    where
+        F: FnMut(usize, &V),
+    {
+        if let Some(inner) = &self.inner {
+            inner.values_with_index(func);
+        }
+    }
+
+    #[inline]
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.inner
+            .as_deref_mut()
+            .map(|inner| inner.iter_mut())
+            .into_iter()
+            .flatten()
+    }
+
+    #[inline]
+    fn values_mut(&mut self) -> Self::ValuesMut<'_> {
+        self.inner
+            .as_deref_mut()
+            .map(|inner| inner.values_mut())
+            .into_iter()
+            .flatten()
+    }
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner
+            .map(|inner| MapStorage::into_iter(*inner))
+            .into_iter()
+            .flatten()
+    }
+
+    #[inline]
+    fn entry(&mut self, key: K) -> Entry<'_, Self, K, V> {
+        match self.get_or_alloc().entry(key) {
+            Entry::Occupied(entry) => Entry::Occupied(entry),
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+
+    #[inline]
+    fn entry_ref(&mut self, key: &K) -> Entry<'_, Self, K, V>
+    where
+        K: Copy,
+    {
+        match self.get_or_alloc().entry_ref(key) {
+            Entry::Occupied(entry) => Entry::Occupied(entry),
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<K, V> EntriesStorage<K, V> for LazyMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: EntriesStorage<K, V>,
+{
+    type EntriesMut<'this>
+        = iter::Map<
+        <K::MapStorage<V> as EntriesStorage<K, V>>::EntriesMut<'this>,
+        fn(Entry<'this, K::MapStorage<V>, K, V>) -> Entry<'this, Self, K, V>,
+    >
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn entries_mut(&mut self) -> Self::EntriesMut<'_> {
+        let map: fn(_) -> _ = |entry| match entry {
+            Entry::Occupied(entry) => Entry::Occupied(entry),
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        };
+
+        self.get_or_alloc().entries_mut().map(map)
+    }
+}
+
+impl<K, V> StatsStorage<K, V> for LazyMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: StatsStorage<K, V>,
+{
+    #[inline]
+    fn stats(&self) -> Stats {
+        self.inner
+            .as_ref()
+            .map_or_else(Stats::default, |inner| inner.stats())
+    }
+}
+
+/// Draining keeps the storage of the branch allocated.
+impl<K, V> DrainStorage<K, V> for LazyMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: DrainStorage<K, V>,
+{
+    type Drain<'this>
+        = iter::Flatten<option::IntoIter<<Inner<K, V> as DrainStorage<K, V>>::Drain<'this>>>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn drain(&mut self) -> Self::Drain<'_> {
+        self.inner
+            .as_deref_mut()
+            .map(|inner| inner.drain())
+            .into_iter()
+            .flatten()
+    }
+}
+
+/// Extracting entries keeps the storage of the branch allocated.
+impl<K, V> ExtractIfStorage<K, V> for LazyMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: ExtractIfStorage<K, V>,
+{
+    type ExtractIf<'this>
+        = Option<<Inner<K, V> as ExtractIfStorage<K, V>>::ExtractIf<'this>>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+        self.inner.as_deref_mut().map(|inner| inner.extract_if())
+    }
+}
@@ -0,0 +1,31 @@
+//! Selects the hash map implementation which backs dynamic storage.
+//!
+//! This is [`hashbrown`] by default, or [`std::collections`] if the
+//! `std-collections` feature is enabled.
+
+#[cfg(feature = "std-collections")]
+pub(crate) use ::std::collections::{hash_map, hash_set, HashMap, HashSet};
+
+#[cfg(feature = "std-collections")]
+pub(crate) type OccupiedEntry<'a, K, V> = hash_map::OccupiedEntry<'a, K, V>;
+
+#[cfg(feature = "std-collections")]
+pub(crate) type VacantEntry<'a, K, V> = hash_map::VacantEntry<'a, K, V>;
+
+#[cfg(feature = "std-collections")]
+pub(crate) type Entry<'a, K, V> = hash_map::Entry<'a, K, V>;
+
+#[cfg(not(feature = "std-collections"))]
+pub(crate) use ::hashbrown::{hash_map, hash_set, HashMap, HashSet};
+
+#[cfg(not(feature = "std-collections"))]
+type S = hash_map::DefaultHashBuilder;
+
+#[cfg(not(feature = "std-collections"))]
+pub(crate) type OccupiedEntry<'a, K, V> = hash_map::OccupiedEntry<'a, K, V, S>;
+
+#[cfg(not(feature = "std-collections"))]
+pub(crate) type VacantEntry<'a, K, V> = hash_map::VacantEntry<'a, K, V, S>;
+
+#[cfg(not(feature = "std-collections"))]
+pub(crate) type Entry<'a, K, V> = hash_map::Entry<'a, K, V, S>;
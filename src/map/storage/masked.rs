@@ -0,0 +1,964 @@
+#![allow(unsafe_code)]
+
+use core::cell::Cell;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::iter;
+use core::marker::PhantomData;
+use core::mem::{self, MaybeUninit};
+use core::option;
+use core::slice;
+
+use crate::map::{
+    ClassStorage, DrainStorage, EntriesStorage, Entry, ExtractIfCursor, ExtractIfStorage,
+    MapStorage, OccupiedEntry, Stats, StatsStorage, VacantEntry,
+};
+use crate::raw::KeyIndex;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An unsigned integer used as the presence mask of a [`MaskedMapStorage`].
+///
+/// This is implemented for `u8`, `u16`, `u32`, `u64` and `u128`, and can't be
+/// implemented outside of this crate.
+pub trait Mask: 'static + sealed::Sealed + Copy + Eq {
+    #[doc(hidden)]
+    const EMPTY: Self;
+
+    #[doc(hidden)]
+    fn test(self, index: usize) -> bool;
+
+    #[doc(hidden)]
+    fn set(&mut self, index: usize);
+
+    #[doc(hidden)]
+    fn unset(&mut self, index: usize);
+
+    #[doc(hidden)]
+    fn count(self) -> usize;
+}
+
+macro_rules! mask {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl sealed::Sealed for $ty {}
+
+            impl Mask for $ty {
+                const EMPTY: Self = 0;
+
+                #[inline]
+                fn test(self, index: usize) -> bool {
+                    match u32::try_from(index).ok().and_then(|index| (1 as $ty).checked_shl(index)) {
+                        Some(bit) => self & bit != 0,
+                        None => false,
+                    }
+                }
+
+                #[inline]
+                fn set(&mut self, index: usize) {
+                    match u32::try_from(index).ok().and_then(|index| (1 as $ty).checked_shl(index)) {
+                        Some(bit) => *self |= bit,
+                        None => panic!("index {index} is out of bounds of a {} mask", <$ty>::BITS),
+                    }
+                }
+
+                #[inline]
+                fn unset(&mut self, index: usize) {
+                    if let Some(bit) = u32::try_from(index).ok().and_then(|index| (1 as $ty).checked_shl(index)) {
+                        *self &= !bit;
+                    }
+                }
+
+                #[inline]
+                fn count(self) -> usize {
+                    self.count_ones() as usize
+                }
+            }
+        )*
+    };
+}
+
+mask!(u8, u16, u32, u64, u128);
+
+/// [`MapStorage`] for keys with unit variants, which keeps track of which
+/// entries are present in a bit mask next to an array of possibly
+/// uninitialized values.
+///
+/// Unlike the array storage of a key, which stores an `Option<V>` for every
+/// variant, this doesn't need a discriminant per slot. So a `Map<K, ()>` only
+/// takes up the space of its mask, which is the same as a `Set<K>` using
+/// `#[key(bitset)]`.
+///
+/// This is used by enums marked with `#[key(masked)]`.
+///
+/// # Examples
+///
+/// ```
+/// use core::mem;
+///
+/// use fixed_map::{Key, Map, Set};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(bitset, masked)]
+/// enum Flag {
+///     Read,
+///     Write,
+///     Execute,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(Flag::Execute, ());
+/// map.insert(Flag::Read, ());
+///
+/// assert!(map.keys().eq([Flag::Read, Flag::Execute]));
+/// assert_eq!(mem::size_of::<Map<Flag, ()>>(), mem::size_of::<Set<Flag>>());
+/// ```
+pub struct MaskedMapStorage<K, V, M, const N: usize>
+where
+    M: Mask,
+{
+    /// Invariant: bit `index` is only set if `values[index]` is initialized.
+    mask: M,
+    values: [MaybeUninit<V>; N],
+    _marker: PhantomData<K>,
+}
+
+impl<K, V, M, const N: usize> MaskedMapStorage<K, V, M, N>
+where
+    M: Mask,
+{
+    #[inline]
+    fn new() -> Self {
+        Self {
+            mask: M::EMPTY,
+            // SAFETY: An array of `MaybeUninit` doesn't need to be initialized.
+            values: unsafe { MaybeUninit::uninit().assume_init() },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the value at the given index, if it's present.
+    #[inline]
+    fn slot(&self, index: usize) -> Option<&V> {
+        let value = self.values.get(index)?;
+
+        if !self.mask.test(index) {
+            return None;
+        }
+
+        // SAFETY: The bit is set, so the value is initialized.
+        Some(unsafe { value.assume_init_ref() })
+    }
+
+    /// Get the value at the given index mutably, if it's present.
+    #[inline]
+    fn slot_mut(&mut self, index: usize) -> Option<&mut V> {
+        let value = self.values.get_mut(index)?;
+
+        if !self.mask.test(index) {
+            return None;
+        }
+
+        // SAFETY: The bit is set, so the value is initialized.
+        Some(unsafe { value.assume_init_mut() })
+    }
+
+    /// Take the value at the given index out of the storage.
+    #[inline]
+    fn take(&mut self, index: usize) -> Option<V> {
+        let value = self.values.get_mut(index)?;
+
+        if !self.mask.test(index) {
+            return None;
+        }
+
+        self.mask.unset(index);
+        // SAFETY: The bit was set, so the value is initialized, and unsetting
+        // it makes sure that it isn't read again.
+        Some(unsafe { value.assume_init_read() })
+    }
+
+    /// Store a value at the given index, returning the previous one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    #[inline]
+    fn put(&mut self, index: usize, value: V) -> Option<V> {
+        let previous = self.take(index);
+        let slot = &mut self.values[index];
+        // NB: The bit is set before the value is written, so that a mask
+        // which is too narrow panics before a value is leaked into the slot.
+        self.mask.set(index);
+        slot.write(value);
+        previous
+    }
+}
+
+impl<K, V, M, const N: usize> Drop for MaskedMapStorage<K, V, M, N>
+where
+    M: Mask,
+{
+    #[inline]
+    fn drop(&mut self) {
+        if mem::needs_drop::<V>() {
+            for index in 0..N {
+                drop(self.take(index));
+            }
+        }
+    }
+}
+
+impl<K, V, M, const N: usize> Clone for MaskedMapStorage<K, V, M, N>
+where
+    V: Clone,
+    M: Mask,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        let mut storage = Self::new();
+
+        for index in 0..N {
+            if let Some(value) = self.slot(index) {
+                storage.put(index, value.clone());
+            }
+        }
+
+        storage
+    }
+}
+
+impl<K, V, M, const N: usize> PartialEq for MaskedMapStorage<K, V, M, N>
+where
+    V: PartialEq,
+    M: Mask,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.mask == other.mask && (0..N).all(|index| self.slot(index) == other.slot(index))
+    }
+}
+
+impl<K, V, M, const N: usize> Eq for MaskedMapStorage<K, V, M, N>
+where
+    V: Eq,
+    M: Mask,
+{
+}
+
+impl<K, V, M, const N: usize> Hash for MaskedMapStorage<K, V, M, N>
+where
+    V: Hash,
+    M: Mask,
+{
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        state.write_usize(self.mask.count());
+
+        for index in 0..N {
+            if let Some(value) = self.slot(index) {
+                state.write_usize(index);
+                value.hash(state);
+            }
+        }
+    }
+}
+
+/// Ordered like the array storage of a key, which compares the index and value
+/// of every present entry in order.
+impl<K, V, M, const N: usize> PartialOrd for MaskedMapStorage<K, V, M, N>
+where
+    V: PartialOrd,
+    M: Mask,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let a = (0..N).filter_map(|index| Some((index, self.slot(index)?)));
+        let b = (0..N).filter_map(|index| Some((index, other.slot(index)?)));
+        a.partial_cmp(b)
+    }
+}
+
+impl<K, V, M, const N: usize> Ord for MaskedMapStorage<K, V, M, N>
+where
+    V: Ord,
+    M: Mask,
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        let a = (0..N).filter_map(|index| Some((index, self.slot(index)?)));
+        let b = (0..N).filter_map(|index| Some((index, other.slot(index)?)));
+        a.cmp(b)
+    }
+}
+
+/// See [`MaskedMapStorage::iter`].
+pub struct Iter<'a, K, V, M> {
+    mask: M,
+    values: iter::Enumerate<slice::Iter<'a, MaybeUninit<V>>>,
+    _marker: PhantomData<K>,
+}
+
+impl<'a, K, V, M> Iter<'a, K, V, M>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    #[inline]
+    fn present(&self, (index, value): (usize, &'a MaybeUninit<V>)) -> Option<(K, &'a V)> {
+        if !self.mask.test(index) {
+            return None;
+        }
+
+        // SAFETY: The mask is a copy of the mask of the storage which is
+        // borrowed, so a set bit means that the value is initialized.
+        Some((K::from_index(index)?, unsafe { value.assume_init_ref() }))
+    }
+}
+
+impl<K, V, M> Clone for Iter<'_, K, V, M>
+where
+    M: Copy,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            mask: self.mask,
+            values: self.values.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V, M> Iterator for Iter<'a, K, V, M>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    type Item = (K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let slot = self.values.next()?;
+
+            if let Some(item) = self.present(slot) {
+                return Some(item);
+            }
+        }
+    }
+}
+
+impl<K, V, M> DoubleEndedIterator for Iter<'_, K, V, M>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            let slot = self.values.next_back()?;
+
+            if let Some(item) = self.present(slot) {
+                return Some(item);
+            }
+        }
+    }
+}
+
+impl<K, V, M> iter::FusedIterator for Iter<'_, K, V, M>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+}
+
+/// See [`MaskedMapStorage::iter_mut`].
+pub struct IterMut<'a, K, V, M> {
+    mask: M,
+    values: iter::Enumerate<slice::IterMut<'a, MaybeUninit<V>>>,
+    _marker: PhantomData<K>,
+}
+
+impl<'a, K, V, M> IterMut<'a, K, V, M>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    #[inline]
+    fn present(&self, (index, value): (usize, &'a mut MaybeUninit<V>)) -> Option<(K, &'a mut V)> {
+        if !self.mask.test(index) {
+            return None;
+        }
+
+        // SAFETY: The mask is a copy of the mask of the storage which is
+        // borrowed, so a set bit means that the value is initialized.
+        Some((K::from_index(index)?, unsafe { value.assume_init_mut() }))
+    }
+}
+
+impl<'a, K, V, M> Iterator for IterMut<'a, K, V, M>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    type Item = (K, &'a mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let slot = self.values.next()?;
+
+            if let Some(item) = self.present(slot) {
+                return Some(item);
+            }
+        }
+    }
+}
+
+impl<K, V, M> DoubleEndedIterator for IterMut<'_, K, V, M>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            let slot = self.values.next_back()?;
+
+            if let Some(item) = self.present(slot) {
+                return Some(item);
+            }
+        }
+    }
+}
+
+impl<K, V, M> iter::FusedIterator for IterMut<'_, K, V, M>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+}
+
+/// See [`MaskedMapStorage::into_iter`].
+pub struct IntoIter<K, V, M, const N: usize>
+where
+    M: Mask,
+{
+    storage: MaskedMapStorage<K, V, M, N>,
+    range: core::ops::Range<usize>,
+}
+
+impl<K, V, M, const N: usize> Iterator for IntoIter<K, V, M, N>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let index = self.range.next()?;
+
+            if let Some(key) = K::from_index(index) {
+                if let Some(value) = self.storage.take(index) {
+                    return Some((key, value));
+                }
+            }
+        }
+    }
+}
+
+impl<K, V, M, const N: usize> DoubleEndedIterator for IntoIter<K, V, M, N>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            let index = self.range.next_back()?;
+
+            if let Some(key) = K::from_index(index) {
+                if let Some(value) = self.storage.take(index) {
+                    return Some((key, value));
+                }
+            }
+        }
+    }
+}
+
+impl<K, V, M, const N: usize> iter::FusedIterator for IntoIter<K, V, M, N>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+}
+
+/// Update the bit at the given index of a mask shared between entries.
+#[inline]
+fn update<M>(mask: &Cell<M>, index: usize, func: fn(&mut M, usize))
+where
+    M: Mask,
+{
+    let mut bits = mask.get();
+    func(&mut bits, index);
+    mask.set(bits);
+}
+
+/// See [`MaskedMapStorage::entry`].
+///
+/// The mask is behind a [`Cell`], since every entry produced by
+/// [`MaskedMapStorage::entries_mut`] updates the same mask.
+pub struct Occupied<'a, K, V, M> {
+    mask: &'a Cell<M>,
+    value: &'a mut MaybeUninit<V>,
+    index: usize,
+    key: K,
+}
+
+impl<'a, K, V, M> OccupiedEntry<'a, K, V> for Occupied<'a, K, V, M>
+where
+    K: Copy,
+    M: Mask,
+{
+    #[inline]
+    fn key(&self) -> K {
+        self.key
+    }
+
+    #[inline]
+    fn key_ref(&self) -> &K {
+        &self.key
+    }
+
+    #[inline]
+    fn get(&self) -> &V {
+        // SAFETY: The entry is only constructed for a set bit.
+        unsafe { self.value.assume_init_ref() }
+    }
+
+    #[inline]
+    fn get_mut(&mut self) -> &mut V {
+        // SAFETY: The entry is only constructed for a set bit.
+        unsafe { self.value.assume_init_mut() }
+    }
+
+    #[inline]
+    fn into_mut(self) -> &'a mut V {
+        // SAFETY: The entry is only constructed for a set bit.
+        unsafe { self.value.assume_init_mut() }
+    }
+
+    #[inline]
+    fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    #[inline]
+    fn remove(self) -> V {
+        update(self.mask, self.index, M::unset);
+        // SAFETY: The entry is only constructed for a set bit, and unsetting it
+        // makes sure that the value isn't read again.
+        unsafe { self.value.assume_init_read() }
+    }
+}
+
+/// See [`MaskedMapStorage::entry`].
+pub struct Vacant<'a, K, V, M> {
+    mask: &'a Cell<M>,
+    value: &'a mut MaybeUninit<V>,
+    index: usize,
+    key: K,
+}
+
+impl<'a, K, V, M> VacantEntry<'a, K, V> for Vacant<'a, K, V, M>
+where
+    K: Copy,
+    M: Mask,
+{
+    #[inline]
+    fn key(&self) -> K {
+        self.key
+    }
+
+    #[inline]
+    fn key_ref(&self) -> &K {
+        &self.key
+    }
+
+    #[inline]
+    fn insert(self, value: V) -> &'a mut V {
+        let value = self.value.write(value);
+        update(self.mask, self.index, M::set);
+        value
+    }
+}
+
+impl<K, V, M, const N: usize> MapStorage<K, V> for MaskedMapStorage<K, V, M, N>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    type Iter<'this>
+        = Iter<'this, K, V, M>
+    where
+        K: 'this,
+        V: 'this;
+    type Keys<'this>
+        = iter::Map<Iter<'this, K, V, M>, fn((K, &'this V)) -> K>
+    where
+        K: 'this,
+        V: 'this;
+    type Values<'this>
+        = iter::Map<Iter<'this, K, V, M>, fn((K, &'this V)) -> &'this V>
+    where
+        K: 'this,
+        V: 'this;
+    type IterMut<'this>
+        = IterMut<'this, K, V, M>
+    where
+        K: 'this,
+        V: 'this;
+    type ValuesMut<'this>
+        = iter::Map<IterMut<'this, K, V, M>, fn((K, &'this mut V)) -> &'this mut V>
+    where
+        K: 'this,
+        V: 'this;
+    type IntoIter = IntoIter<K, V, M, N>;
+    type Occupied<'this>
+        = Occupied<'this, K, V, M>
+    where
+        K: 'this,
+        V: 'this;
+    type Vacant<'this>
+        = Vacant<'this, K, V, M>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn empty() -> Self {
+        Self::new()
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.mask.count()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.mask == M::EMPTY
+    }
+
+    #[inline]
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.put(key.to_index(), value)
+    }
+
+    #[inline]
+    fn contains_key(&self, key: K) -> bool {
+        self.slot(key.to_index()).is_some()
+    }
+
+    #[inline]
+    fn get(&self, key: K) -> Option<&V> {
+        self.slot(key.to_index())
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        self.slot_mut(key.to_index())
+    }
+
+    #[inline]
+    fn remove(&mut self, key: K) -> Option<V> {
+        self.take(key.to_index())
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, mut func: F)
+    where
+        F: FnMut(K, &mut V) -> bool,
+    {
+        for index in 0..N {
+            let Some(key) = K::from_index(index) else {
+                continue;
+            };
+
+            if let Some(value) = self.slot_mut(index) {
+                if !func(key, value) {
+                    drop(self.take(index));
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn fill_fixed<F>(&mut self, mut func: F)
+    where
+        F: FnMut() -> V,
+    {
+        for index in 0..N {
+            if K::from_index(index).is_some() && !self.mask.test(index) {
+                self.put(index, func());
+            }
+        }
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        for index in 0..N {
+            drop(self.take(index));
+        }
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter<'_> {
+        Iter {
+            mask: self.mask,
+            values: self.values.iter().enumerate(),
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn keys(&self) -> Self::Keys<'_> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    #[inline]
+    fn values(&self) -> Self::Values<'_> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    #[inline]
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        IterMut {
+            mask: self.mask,
+            values: self.values.iter_mut().enumerate(),
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn values_mut(&mut self) -> Self::ValuesMut<'_> {
+        self.iter_mut().map(|(_, value)| value)
+    }
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            storage: self,
+            range: 0..N,
+        }
+    }
+
+    #[inline]
+    fn entry(&mut self, key: K) -> Entry<'_, Self, K, V> {
+        let index = key.to_index();
+        let mask = Cell::from_mut(&mut self.mask);
+        entry(mask, &mut self.values[index], index, key)
+    }
+}
+
+/// Construct the entry for a slot of the storage.
+#[inline]
+fn entry<'a, K, V, M, const N: usize>(
+    mask: &'a Cell<M>,
+    value: &'a mut MaybeUninit<V>,
+    index: usize,
+    key: K,
+) -> Entry<'a, MaskedMapStorage<K, V, M, N>, K, V>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    if mask.get().test(index) {
+        Entry::Occupied(Occupied {
+            mask,
+            value,
+            index,
+            key,
+        })
+    } else {
+        Entry::Vacant(Vacant {
+            mask,
+            value,
+            index,
+            key,
+        })
+    }
+}
+
+/// See [`MaskedMapStorage::entries_mut`].
+pub struct EntriesMut<'a, K, V, M, const N: usize>
+where
+    M: Mask,
+{
+    mask: &'a Cell<M>,
+    values: iter::Enumerate<slice::IterMut<'a, MaybeUninit<V>>>,
+    _marker: PhantomData<K>,
+}
+
+impl<'a, K, V, M, const N: usize> Iterator for EntriesMut<'a, K, V, M, N>
+where
+    K: 'a + KeyIndex,
+    M: Mask,
+{
+    type Item = Entry<'a, MaskedMapStorage<K, V, M, N>, K, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (index, value) = self.values.next()?;
+
+            if let Some(key) = K::from_index(index) {
+                return Some(entry(self.mask, value, index, key));
+            }
+        }
+    }
+}
+
+impl<K, V, M, const N: usize> EntriesStorage<K, V> for MaskedMapStorage<K, V, M, N>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    type EntriesMut<'this>
+        = EntriesMut<'this, K, V, M, N>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn entries_mut(&mut self) -> Self::EntriesMut<'_> {
+        EntriesMut {
+            mask: Cell::from_mut(&mut self.mask),
+            values: self.values.iter_mut().enumerate(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V, M, const N: usize> ClassStorage<K, V> for MaskedMapStorage<K, V, M, N>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    type ClassIter<'this>
+        = option::IntoIter<(K, &'this V)>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn class_len(&self, class: usize) -> usize {
+        usize::from(self.slot(class).is_some())
+    }
+
+    #[inline]
+    fn clear_class(&mut self, class: usize) {
+        drop(self.take(class));
+    }
+
+    #[inline]
+    fn iter_class(&self, class: usize) -> Self::ClassIter<'_> {
+        let key = K::from_index(class);
+        key.and_then(|key| Some((key, self.slot(class)?)))
+            .into_iter()
+    }
+}
+
+impl<K, V, M, const N: usize> StatsStorage<K, V> for MaskedMapStorage<K, V, M, N>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    #[inline]
+    fn stats(&self) -> Stats {
+        Stats::fixed(N, self.mask.count())
+    }
+}
+
+impl<K, V, M, const N: usize> DrainStorage<K, V> for MaskedMapStorage<K, V, M, N>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    type Drain<'this>
+        = IntoIter<K, V, M, N>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn drain(&mut self) -> Self::Drain<'_> {
+        mem::replace(self, Self::new()).into_iter()
+    }
+}
+
+/// See [`MaskedMapStorage::extract_if`].
+pub struct ExtractIf<'a, K, V, M, const N: usize>
+where
+    M: Mask,
+{
+    storage: &'a mut MaskedMapStorage<K, V, M, N>,
+    index: usize,
+}
+
+impl<K, V, M, const N: usize> ExtractIfCursor<K, V> for ExtractIf<'_, K, V, M, N>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    #[inline]
+    fn next_if<F>(&mut self, filter: &mut F) -> Option<(K, V)>
+    where
+        F: ?Sized + FnMut(K, &mut V) -> bool,
+    {
+        while self.index < N {
+            let index = self.index;
+            self.index += 1;
+
+            let Some(key) = K::from_index(index) else {
+                continue;
+            };
+
+            if let Some(value) = self.storage.slot_mut(index) {
+                if filter(key, value) {
+                    return Some((key, self.storage.take(index)?));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<K, V, M, const N: usize> ExtractIfStorage<K, V> for MaskedMapStorage<K, V, M, N>
+where
+    K: KeyIndex,
+    M: Mask,
+{
+    type ExtractIf<'this>
+        = ExtractIf<'this, K, V, M, N>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+        ExtractIf {
+            storage: self,
+            index: 0,
+        }
+    }
+}
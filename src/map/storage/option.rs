@@ -1,9 +1,14 @@
 use core::iter;
-use core::mem;
 use core::option;
 
-use crate::map::{Entry, MapStorage, OccupiedEntry, VacantEntry};
+use crate::layout::Branch;
+use crate::macro_support::{__storage_offset_of, StorageExtractSlots};
+use crate::map::{
+    ClassStorage, DrainStorage, EntriesStorage, Entry, ExtractIfCursor, ExtractIfStorage,
+    MapStorage, OccupiedEntry, Stats, StatsStorage, VacantEntry,
+};
 use crate::option_bucket::{NoneBucket, OptionBucket, SomeBucket};
+use crate::set::storage::OptionSetStorage;
 use crate::Key;
 
 type Iter<'a, K, V> = iter::Chain<
@@ -30,6 +35,26 @@ type ValuesMut<'a, K, V> = iter::Chain<
     <<K as Key>::MapStorage<V> as MapStorage<K, V>>::ValuesMut<'a>,
     option::IterMut<'a, V>,
 >;
+type EntriesMut<'a, K, V> = iter::Chain<
+    iter::Map<
+        <<K as Key>::MapStorage<V> as EntriesStorage<K, V>>::EntriesMut<'a>,
+        fn(
+            Entry<'a, <K as Key>::MapStorage<V>, K, V>,
+        ) -> Entry<'a, OptionMapStorage<K, V>, Option<K>, V>,
+    >,
+    iter::Once<Entry<'a, OptionMapStorage<K, V>, Option<K>, V>>,
+>;
+type ClassIter<'a, K, V> = iter::Chain<
+    iter::Flatten<
+        option::IntoIter<
+            iter::Map<
+                <<K as Key>::MapStorage<V> as MapStorage<K, V>>::Iter<'a>,
+                fn((K, &'a V)) -> (Option<K>, &'a V),
+            >,
+        >,
+    >,
+    iter::Map<option::IntoIter<&'a V>, fn(&'a V) -> (Option<K>, &'a V)>,
+>;
 type IntoIter<K, V> = iter::Chain<
     iter::Map<
         <<K as Key>::MapStorage<V> as MapStorage<K, V>>::IntoIter,
@@ -126,7 +151,10 @@ where
     K: Key,
 {
     None(NoneBucket<'a, V>),
-    Some(<K::MapStorage<V> as MapStorage<K, V>>::Vacant<'a>),
+    Some(
+        Option<K>,
+        <K::MapStorage<V> as MapStorage<K, V>>::Vacant<'a>,
+    ),
 }
 
 pub enum Occupied<'a, K: 'a, V>
@@ -134,7 +162,10 @@ where
     K: Key,
 {
     None(SomeBucket<'a, V>),
-    Some(<K::MapStorage<V> as MapStorage<K, V>>::Occupied<'a>),
+    Some(
+        Option<K>,
+        <K::MapStorage<V> as MapStorage<K, V>>::Occupied<'a>,
+    ),
 }
 
 impl<'a, K, V> VacantEntry<'a, Option<K>, V> for Vacant<'a, K, V>
@@ -145,7 +176,15 @@ where
     fn key(&self) -> Option<K> {
         match self {
             Vacant::None(_) => None,
-            Vacant::Some(entry) => Some(entry.key()),
+            Vacant::Some(key, _) => *key,
+        }
+    }
+
+    #[inline]
+    fn key_ref(&self) -> &Option<K> {
+        match self {
+            Vacant::None(_) => &None,
+            Vacant::Some(key, _) => key,
         }
     }
 
@@ -153,7 +192,7 @@ where
     fn insert(self, value: V) -> &'a mut V {
         match self {
             Vacant::None(entry) => entry.insert(value),
-            Vacant::Some(entry) => entry.insert(value),
+            Vacant::Some(_, entry) => entry.insert(value),
         }
     }
 }
@@ -166,7 +205,15 @@ where
     fn key(&self) -> Option<K> {
         match self {
             Occupied::None(_) => None,
-            Occupied::Some(entry) => Some(entry.key()),
+            Occupied::Some(key, _) => *key,
+        }
+    }
+
+    #[inline]
+    fn key_ref(&self) -> &Option<K> {
+        match self {
+            Occupied::None(_) => &None,
+            Occupied::Some(key, _) => key,
         }
     }
 
@@ -174,7 +221,7 @@ where
     fn get(&self) -> &V {
         match self {
             Occupied::None(entry) => entry.as_ref(),
-            Occupied::Some(entry) => entry.get(),
+            Occupied::Some(_, entry) => entry.get(),
         }
     }
 
@@ -182,7 +229,7 @@ where
     fn get_mut(&mut self) -> &mut V {
         match self {
             Occupied::None(entry) => entry.as_mut(),
-            Occupied::Some(entry) => entry.get_mut(),
+            Occupied::Some(_, entry) => entry.get_mut(),
         }
     }
 
@@ -190,7 +237,7 @@ where
     fn into_mut(self) -> &'a mut V {
         match self {
             Occupied::None(entry) => entry.into_mut(),
-            Occupied::Some(entry) => entry.into_mut(),
+            Occupied::Some(_, entry) => entry.into_mut(),
         }
     }
 
@@ -198,7 +245,7 @@ where
     fn insert(&mut self, value: V) -> V {
         match self {
             Occupied::None(entry) => entry.replace(value),
-            Occupied::Some(entry) => entry.insert(value),
+            Occupied::Some(_, entry) => entry.insert(value),
         }
     }
 
@@ -206,7 +253,7 @@ where
     fn remove(self) -> V {
         match self {
             Occupied::None(entry) => entry.take(),
-            Occupied::Some(entry) => entry.remove(),
+            Occupied::Some(_, entry) => entry.remove(),
         }
     }
 }
@@ -274,7 +321,7 @@ where
     fn insert(&mut self, key: Option<K>, value: V) -> Option<V> {
         match key {
             Some(key) => self.some.insert(key, value),
-            None => mem::replace(&mut self.none, Some(value)),
+            None => self.none.replace(value),
         }
     }
 
@@ -310,6 +357,14 @@ where
         }
     }
 
+    #[inline]
+    fn remove_and_shrink(&mut self, key: Option<K>) -> Option<V> {
+        match key {
+            Some(key) => self.some.remove_and_shrink(key),
+            None => self.none.take(),
+        }
+    }
+
     #[inline]
     fn retain<F>(&mut self, mut func: F)
     where
@@ -323,6 +378,18 @@ where
         }
     }
 
+    #[inline]
+    fn find_map<B, F>(&self, mut func: F) -> Option<B>
+    where
+        F: FnMut(Option<K>, &V) -> Option<B>,
+    {
+        if let Some(output) = self.some.find_map(|k, v| func(Some(k), v)) {
+            return Some(output);
+        }
+
+        self.none.as_ref().and_then(|none| func(None, none))
+    }
+
     #[inline]
     fn clear(&mut self) {
         self.some.clear();
@@ -352,6 +419,95 @@ where
         self.some.values().chain(self.none.iter())
     }
 
+    #[inline]
+    fn first_key_value(&self) -> Option<(Option<K>, &V)> {
+        match self.some.first_key_value() {
+            Some((k, v)) => Some((Some(k), v)),
+            None => Some((None, self.none.as_ref()?)),
+        }
+    }
+
+    #[inline]
+    fn last_key_value(&self) -> Option<(Option<K>, &V)> {
+        match &self.none {
+            Some(v) => Some((None, v)),
+            None => {
+                let (k, v) = self.some.last_key_value()?;
+                Some((Some(k), v))
+            }
+        }
+    }
+
+    #[inline]
+    fn key_set(&self) -> <Option<K> as Key>::SetStorage {
+        OptionSetStorage::from_parts(self.some.key_set(), self.none.is_some())
+    }
+
+    #[inline]
+    fn retain_keys(&mut self, allowed: &<Option<K> as Key>::SetStorage) {
+        let (some, none) = allowed.as_parts();
+        self.some.retain_keys(some);
+
+        if !none {
+            self.none = None;
+        }
+    }
+
+    #[inline]
+    fn fill_fixed<F>(&mut self, mut func: F)
+    where
+        F: FnMut() -> V,
+    {
+        self.some.fill_fixed(&mut func);
+
+        if self.none.is_none() {
+            self.none = Some(func());
+        }
+    }
+
+    #[inline]
+    fn map_values<W, F>(self, mut func: F) -> <Option<K> as Key>::MapStorage<W>
+    where
+        F: FnMut(Option<K>, V) -> W,
+    {
+        OptionMapStorage {
+            some: self.some.map_values(|k, v| func(Some(k), v)),
+            none: self.none.map(|v| func(None, v)),
+        }
+    }
+
+    #[inline]
+    fn layout<F>(mut func: F)
+    where
+        F: FnMut(Branch),
+    {
+        let storage = Self::empty();
+
+        func(Branch::new::<K::MapStorage<V>>(
+            "Some",
+            Some(__storage_offset_of(&storage, &storage.some)),
+        ));
+        func(Branch::new::<Option<V>>(
+            "None",
+            Some(__storage_offset_of(&storage, &storage.none)),
+        ));
+    }
+
+    #[inline]
+    fn values_with_index<F>(&self, mut func: F)
+    where
+        Option<K>: Key,
+        F: FnMut(usize, &V),
+    {
+        for value in self.some.values() {
+            func(0, value);
+        }
+
+        if let Some(value) = &self.none {
+            func(1, value);
+        }
+    }
+
     #[inline]
     fn iter_mut(&mut self) -> Self::IterMut<'_> {
         let map: fn(_) -> _ = |(k, b)| (Some(k), b);
@@ -377,15 +533,194 @@ where
 
     #[inline]
     fn entry(&mut self, key: Option<K>) -> Entry<'_, Self, Option<K>, V> {
+        self.entry_ref(&key)
+    }
+
+    #[inline]
+    fn entry_ref(&mut self, key: &Option<K>) -> Entry<'_, Self, Option<K>, V> {
         match key {
-            Some(key) => match self.some.entry(key) {
-                Entry::Occupied(entry) => Entry::Occupied(Occupied::Some(entry)),
-                Entry::Vacant(entry) => Entry::Vacant(Vacant::Some(entry)),
-            },
-            None => match OptionBucket::new(&mut self.none) {
-                OptionBucket::Some(some) => Entry::Occupied(Occupied::None(some)),
-                OptionBucket::None(none) => Entry::Vacant(Vacant::None(none)),
-            },
+            Some(key) => some_entry(self.some.entry_ref(key)),
+            None => none_entry(&mut self.none),
+        }
+    }
+}
+
+impl<K, V> EntriesStorage<Option<K>, V> for OptionMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: EntriesStorage<K, V>,
+{
+    type EntriesMut<'this>
+        = EntriesMut<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn entries_mut(&mut self) -> Self::EntriesMut<'_> {
+        let some: fn(_) -> _ = some_entry;
+
+        self.some
+            .entries_mut()
+            .map(some)
+            .chain(iter::once(none_entry(&mut self.none)))
+    }
+}
+
+#[inline]
+fn some_entry<K, V>(
+    entry: Entry<'_, K::MapStorage<V>, K, V>,
+) -> Entry<'_, OptionMapStorage<K, V>, Option<K>, V>
+where
+    K: Key,
+{
+    match entry {
+        Entry::Occupied(entry) => Entry::Occupied(Occupied::Some(Some(entry.key()), entry)),
+        Entry::Vacant(entry) => Entry::Vacant(Vacant::Some(Some(entry.key()), entry)),
+    }
+}
+
+#[inline]
+fn none_entry<K, V>(none: &mut Option<V>) -> Entry<'_, OptionMapStorage<K, V>, Option<K>, V>
+where
+    K: Key,
+{
+    match OptionBucket::new(none) {
+        OptionBucket::Some(some) => Entry::Occupied(Occupied::None(some)),
+        OptionBucket::None(none) => Entry::Vacant(Vacant::None(none)),
+    }
+}
+
+impl<K, V> ClassStorage<Option<K>, V> for OptionMapStorage<K, V>
+where
+    K: Key,
+{
+    type ClassIter<'this>
+        = ClassIter<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn class_len(&self, class: usize) -> usize {
+        match class {
+            0 => self.some.len(),
+            1 => usize::from(self.none.is_some()),
+            _ => 0,
+        }
+    }
+
+    #[inline]
+    fn clear_class(&mut self, class: usize) {
+        match class {
+            0 => self.some.clear(),
+            1 => self.none = None,
+            _ => {}
+        }
+    }
+
+    #[inline]
+    fn iter_class(&self, class: usize) -> Self::ClassIter<'_> {
+        let (some, none) = match class {
+            0 => {
+                let map: fn(_) -> _ = |(k, v)| (Some(k), v);
+                (Some(self.some.iter().map(map)), None)
+            }
+            1 => (None, self.none.as_ref()),
+            _ => (None, None),
+        };
+
+        let map: fn(_) -> _ = |v| (None, v);
+        some.into_iter().flatten().chain(none.into_iter().map(map))
+    }
+}
+
+impl<K, V> StatsStorage<Option<K>, V> for OptionMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: StatsStorage<K, V>,
+{
+    #[inline]
+    fn stats(&self) -> Stats {
+        self.some
+            .stats()
+            .merge(Stats::fixed(1, usize::from(self.none.is_some())))
+    }
+}
+
+impl<K, V> DrainStorage<Option<K>, V> for OptionMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: DrainStorage<K, V>,
+{
+    type Drain<'this>
+        = iter::Chain<
+        iter::Map<
+            <K::MapStorage<V> as DrainStorage<K, V>>::Drain<'this>,
+            fn((K, V)) -> (Option<K>, V),
+        >,
+        iter::Map<option::IntoIter<V>, fn(V) -> (Option<K>, V)>,
+    >
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn drain(&mut self) -> Self::Drain<'_> {
+        let map: fn(_) -> _ = |(k, v)| (Some(k), v);
+        let a = self.some.drain().map(map);
+        let map: fn(_) -> _ = |v| (None, v);
+        let b = self.none.take().into_iter().map(map);
+        a.chain(b)
+    }
+}
+
+/// See [`OptionMapStorage::extract_if`].
+pub struct ExtractIf<'a, K, V>
+where
+    K: 'a + Key,
+    K::MapStorage<V>: ExtractIfStorage<K, V>,
+    V: 'a,
+{
+    some: <K::MapStorage<V> as ExtractIfStorage<K, V>>::ExtractIf<'a>,
+    none: StorageExtractSlots<iter::Once<(Option<K>, &'a mut Option<V>)>>,
+}
+
+impl<'a, K, V> ExtractIfCursor<Option<K>, V> for ExtractIf<'a, K, V>
+where
+    K: 'a + Key,
+    K::MapStorage<V>: ExtractIfStorage<K, V>,
+    V: 'a,
+{
+    #[inline]
+    fn next_if<F>(&mut self, filter: &mut F) -> Option<(Option<K>, V)>
+    where
+        F: ?Sized + FnMut(Option<K>, &mut V) -> bool,
+    {
+        if let Some((k, v)) = self.some.next_if(&mut |k, v| filter(Some(k), v)) {
+            return Some((Some(k), v));
+        }
+
+        self.none.next_if(filter)
+    }
+}
+
+impl<K, V> ExtractIfStorage<Option<K>, V> for OptionMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: ExtractIfStorage<K, V>,
+{
+    type ExtractIf<'this>
+        = ExtractIf<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+        ExtractIf {
+            some: self.some.extract_if(),
+            none: StorageExtractSlots::new(iter::once((None, &mut self.none))),
         }
     }
 }
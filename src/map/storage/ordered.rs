@@ -0,0 +1,400 @@
+use core::iter;
+use core::mem;
+use core::option;
+
+use alloc::collections::btree_map::{self, BTreeMap};
+
+use crate::map::{
+    ClassStorage, DrainStorage, Entry, ExtractIfCursor, ExtractIfStorage, MapStorage,
+    OccupiedEntry, Stats, StatsStorage, VacantEntry,
+};
+
+/// [`MapStorage`] for dynamic types, using [`BTreeMap`].
+///
+/// Unlike [`HashbrownMapStorage`][crate::map::storage::HashbrownMapStorage],
+/// entries are visited in the order of their keys, so iteration, `Debug`
+/// output and serialization are the same from one run to the next.
+///
+/// This is used by variants marked with `#[key(dynamic = "ordered")]`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "hashbrown")] {
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     #[key(dynamic = "ordered")]
+///     Name(&'static str),
+///     Other,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(MyKey::Name("b"), 2);
+/// map.insert(MyKey::Other, 3);
+/// map.insert(MyKey::Name("a"), 1);
+///
+/// assert_eq!(map.get(MyKey::Name("a")), Some(&1));
+/// assert_eq!(map.get(MyKey::Name("c")), None);
+/// assert!(map.keys().eq([MyKey::Name("a"), MyKey::Name("b"), MyKey::Other]));
+/// # }
+/// ```
+#[repr(transparent)]
+pub struct OrderedMapStorage<K, V> {
+    inner: BTreeMap<K, V>,
+}
+
+impl<K, V> Clone for OrderedMapStorage<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K, V> PartialEq for OrderedMapStorage<K, V>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.eq(&other.inner)
+    }
+}
+
+impl<K, V> Eq for OrderedMapStorage<K, V>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> for btree_map::OccupiedEntry<'a, K, V>
+where
+    K: Copy + Ord,
+{
+    #[inline]
+    fn key(&self) -> K {
+        *btree_map::OccupiedEntry::key(self)
+    }
+
+    #[inline]
+    fn key_ref(&self) -> &K {
+        btree_map::OccupiedEntry::key(self)
+    }
+
+    #[inline]
+    fn get(&self) -> &V {
+        btree_map::OccupiedEntry::get(self)
+    }
+
+    #[inline]
+    fn get_mut(&mut self) -> &mut V {
+        btree_map::OccupiedEntry::get_mut(self)
+    }
+
+    #[inline]
+    fn into_mut(self) -> &'a mut V {
+        btree_map::OccupiedEntry::into_mut(self)
+    }
+
+    #[inline]
+    fn insert(&mut self, value: V) -> V {
+        btree_map::OccupiedEntry::insert(self, value)
+    }
+
+    #[inline]
+    fn remove(self) -> V {
+        btree_map::OccupiedEntry::remove(self)
+    }
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> for btree_map::VacantEntry<'a, K, V>
+where
+    K: Copy + Ord,
+{
+    #[inline]
+    fn key(&self) -> K {
+        *btree_map::VacantEntry::key(self)
+    }
+
+    #[inline]
+    fn key_ref(&self) -> &K {
+        btree_map::VacantEntry::key(self)
+    }
+
+    #[inline]
+    fn insert(self, value: V) -> &'a mut V {
+        btree_map::VacantEntry::insert(self, value)
+    }
+}
+
+impl<K, V> MapStorage<K, V> for OrderedMapStorage<K, V>
+where
+    K: Copy + Ord,
+{
+    type Iter<'this>
+        = iter::Map<btree_map::Iter<'this, K, V>, fn((&'this K, &'this V)) -> (K, &'this V)>
+    where
+        K: 'this,
+        V: 'this;
+    type Keys<'this>
+        = iter::Copied<btree_map::Keys<'this, K, V>>
+    where
+        K: 'this,
+        V: 'this;
+    type Values<'this>
+        = btree_map::Values<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+    type IterMut<'this>
+        = iter::Map<
+        btree_map::IterMut<'this, K, V>,
+        fn((&'this K, &'this mut V)) -> (K, &'this mut V),
+    >
+    where
+        K: 'this,
+        V: 'this;
+    type ValuesMut<'this>
+        = btree_map::ValuesMut<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+    type IntoIter = btree_map::IntoIter<K, V>;
+    type Occupied<'this>
+        = btree_map::OccupiedEntry<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+    type Vacant<'this>
+        = btree_map::VacantEntry<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn empty() -> Self {
+        Self {
+            inner: BTreeMap::new(),
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    #[inline]
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.inner.insert(key, value)
+    }
+
+    #[inline]
+    fn contains_key(&self, key: K) -> bool {
+        self.inner.contains_key(&key)
+    }
+
+    #[inline]
+    fn get(&self, key: K) -> Option<&V> {
+        self.inner.get(&key)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        self.inner.get_mut(&key)
+    }
+
+    #[inline]
+    fn remove(&mut self, key: K) -> Option<V> {
+        self.inner.remove(&key)
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, mut func: F)
+    where
+        F: FnMut(K, &mut V) -> bool,
+    {
+        self.inner.retain(|&k, v| func(k, v));
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter<'_> {
+        let map: fn(_) -> _ = |(k, v): (&K, &V)| (*k, v);
+        self.inner.iter().map(map)
+    }
+
+    #[inline]
+    fn keys(&self) -> Self::Keys<'_> {
+        self.inner.keys().copied()
+    }
+
+    #[inline]
+    fn values(&self) -> Self::Values<'_> {
+        self.inner.values()
+    }
+
+    #[inline]
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        let map: fn(_) -> _ = |(k, v): (&K, &mut V)| (*k, v);
+        self.inner.iter_mut().map(map)
+    }
+
+    #[inline]
+    fn values_mut(&mut self) -> Self::ValuesMut<'_> {
+        self.inner.values_mut()
+    }
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+
+    #[inline]
+    fn entry(&mut self, key: K) -> Entry<'_, Self, K, V> {
+        match self.inner.entry(key) {
+            btree_map::Entry::Occupied(entry) => Entry::Occupied(entry),
+            btree_map::Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<K, V> ClassStorage<K, V> for OrderedMapStorage<K, V>
+where
+    K: Copy + Ord,
+{
+    type ClassIter<'this>
+        = iter::Flatten<option::IntoIter<<Self as MapStorage<K, V>>::Iter<'this>>>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn class_len(&self, class: usize) -> usize {
+        if class == 0 {
+            self.inner.len()
+        } else {
+            0
+        }
+    }
+
+    #[inline]
+    fn clear_class(&mut self, class: usize) {
+        if class == 0 {
+            self.inner.clear();
+        }
+    }
+
+    #[inline]
+    fn iter_class(&self, class: usize) -> Self::ClassIter<'_> {
+        (class == 0).then(|| self.iter()).into_iter().flatten()
+    }
+}
+
+/// A [`BTreeMap`] has no spare capacity, so its capacity is reported as the
+/// number of entries it holds.
+impl<K, V> StatsStorage<K, V> for OrderedMapStorage<K, V>
+where
+    K: Copy + Ord,
+{
+    #[inline]
+    fn stats(&self) -> Stats {
+        Stats::dynamic(self.inner.len(), self.inner.len())
+    }
+}
+
+impl<K, V> DrainStorage<K, V> for OrderedMapStorage<K, V>
+where
+    K: Copy + Ord,
+{
+    type Drain<'this>
+        = btree_map::IntoIter<K, V>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn drain(&mut self) -> Self::Drain<'_> {
+        mem::take(&mut self.inner).into_iter()
+    }
+}
+
+/// See [`OrderedMapStorage::extract_if`].
+pub struct ExtractIf<'a, K, V>
+where
+    K: Ord,
+{
+    inner: &'a mut BTreeMap<K, V>,
+    taken: btree_map::IntoIter<K, V>,
+}
+
+impl<K, V> ExtractIfCursor<K, V> for ExtractIf<'_, K, V>
+where
+    K: Copy + Ord,
+{
+    #[inline]
+    fn next_if<F>(&mut self, filter: &mut F) -> Option<(K, V)>
+    where
+        F: ?Sized + FnMut(K, &mut V) -> bool,
+    {
+        for (key, mut value) in &mut self.taken {
+            if filter(key, &mut value) {
+                return Some((key, value));
+            }
+
+            self.inner.insert(key, value);
+        }
+
+        None
+    }
+}
+
+impl<K, V> Drop for ExtractIf<'_, K, V>
+where
+    K: Ord,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.inner.extend(&mut self.taken);
+    }
+}
+
+impl<K, V> ExtractIfStorage<K, V> for OrderedMapStorage<K, V>
+where
+    K: Copy + Ord,
+{
+    type ExtractIf<'this>
+        = ExtractIf<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+
+    // NB: `BTreeMap::extract_if` isn't stable, so the entries are moved out
+    // and the ones which are kept are inserted back.
+    #[inline]
+    fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+        let taken = mem::take(&mut self.inner);
+
+        ExtractIf {
+            inner: &mut self.inner,
+            taken: taken.into_iter(),
+        }
+    }
+}
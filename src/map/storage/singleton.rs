@@ -1,6 +1,8 @@
-use core::mem;
-
-use crate::map::{Entry, MapStorage};
+use crate::macro_support::{__storage_drain, StorageExtractSlots};
+use crate::map::{
+    ClassStorage, DrainStorage, EntriesStorage, Entry, ExtractIfStorage, MapStorage, OccupiedEntry,
+    Stats, StatsStorage, VacantEntry,
+};
 use crate::option_bucket::{NoneBucket, OptionBucket, SomeBucket};
 
 /// [`MapStorage`] type that can only inhabit a single value (like `()`).
@@ -22,6 +24,76 @@ where
 
 impl<V> Eq for SingletonMapStorage<V> where V: Eq {}
 
+pub struct Vacant<'a, K, V> {
+    key: K,
+    inner: NoneBucket<'a, V>,
+}
+
+pub struct Occupied<'a, K, V> {
+    key: K,
+    inner: SomeBucket<'a, V>,
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> for Vacant<'a, K, V>
+where
+    K: Default,
+{
+    #[inline]
+    fn key(&self) -> K {
+        K::default()
+    }
+
+    #[inline]
+    fn key_ref(&self) -> &K {
+        &self.key
+    }
+
+    #[inline]
+    fn insert(self, value: V) -> &'a mut V {
+        self.inner.insert(value)
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> for Occupied<'a, K, V>
+where
+    K: Default,
+{
+    #[inline]
+    fn key(&self) -> K {
+        K::default()
+    }
+
+    #[inline]
+    fn key_ref(&self) -> &K {
+        &self.key
+    }
+
+    #[inline]
+    fn get(&self) -> &V {
+        self.inner.as_ref()
+    }
+
+    #[inline]
+    fn get_mut(&mut self) -> &mut V {
+        self.inner.as_mut()
+    }
+
+    #[inline]
+    fn into_mut(self) -> &'a mut V {
+        self.inner.into_mut()
+    }
+
+    #[inline]
+    fn insert(&mut self, value: V) -> V {
+        self.inner.replace(value)
+    }
+
+    #[inline]
+    fn remove(self) -> V {
+        self.inner.take()
+    }
+}
+
 impl<K, V> MapStorage<K, V> for SingletonMapStorage<V>
 where
     K: Default,
@@ -48,11 +120,11 @@ where
         V: 'this;
     type IntoIter = core::option::IntoIter<(K, V)>;
     type Occupied<'this>
-        = SomeBucket<'this, V>
+        = Occupied<'this, K, V>
     where
         V: 'this;
     type Vacant<'this>
-        = NoneBucket<'this, V>
+        = Vacant<'this, K, V>
     where
         V: 'this;
 
@@ -75,7 +147,7 @@ where
 
     #[inline]
     fn insert(&mut self, _: K, value: V) -> Option<V> {
-        mem::replace(&mut self.inner, Some(value))
+        self.inner.replace(value)
     }
 
     #[inline]
@@ -110,6 +182,16 @@ where
         }
     }
 
+    #[inline]
+    fn fill_fixed<F>(&mut self, mut func: F)
+    where
+        F: FnMut() -> V,
+    {
+        if self.inner.is_none() {
+            self.inner = Some(func());
+        }
+    }
+
     #[inline]
     fn clear(&mut self) {
         self.inner = None;
@@ -122,7 +204,7 @@ where
 
     #[inline]
     fn keys(&self) -> Self::Keys<'_> {
-        Some(K::default()).into_iter()
+        self.inner.as_ref().map(|_| K::default()).into_iter()
     }
 
     #[inline]
@@ -148,8 +230,101 @@ where
     #[inline]
     fn entry(&mut self, _key: K) -> Entry<'_, Self, K, V> {
         match OptionBucket::new(&mut self.inner) {
-            OptionBucket::Some(some) => Entry::Occupied(some),
-            OptionBucket::None(none) => Entry::Vacant(none),
+            OptionBucket::Some(inner) => Entry::Occupied(Occupied {
+                key: K::default(),
+                inner,
+            }),
+            OptionBucket::None(inner) => Entry::Vacant(Vacant {
+                key: K::default(),
+                inner,
+            }),
+        }
+    }
+}
+
+impl<K, V> EntriesStorage<K, V> for SingletonMapStorage<V>
+where
+    K: Default,
+{
+    type EntriesMut<'this>
+        = core::iter::Once<Entry<'this, Self, K, V>>
+    where
+        V: 'this;
+
+    #[inline]
+    fn entries_mut(&mut self) -> Self::EntriesMut<'_> {
+        core::iter::once(self.entry(K::default()))
+    }
+}
+
+impl<K, V> ClassStorage<K, V> for SingletonMapStorage<V>
+where
+    K: Default,
+{
+    type ClassIter<'this>
+        = core::option::IntoIter<(K, &'this V)>
+    where
+        V: 'this;
+
+    #[inline]
+    fn class_len(&self, class: usize) -> usize {
+        usize::from(class == 0 && self.inner.is_some())
+    }
+
+    #[inline]
+    fn clear_class(&mut self, class: usize) {
+        if class == 0 {
+            self.inner = None;
         }
     }
+
+    #[inline]
+    fn iter_class(&self, class: usize) -> Self::ClassIter<'_> {
+        let inner = if class == 0 {
+            self.inner.as_ref()
+        } else {
+            None
+        };
+        inner.map(|v| (K::default(), v)).into_iter()
+    }
+}
+
+impl<K, V> StatsStorage<K, V> for SingletonMapStorage<V>
+where
+    K: Default,
+{
+    #[inline]
+    fn stats(&self) -> Stats {
+        Stats::fixed(1, usize::from(self.inner.is_some()))
+    }
+}
+
+impl<K, V> DrainStorage<K, V> for SingletonMapStorage<V>
+where
+    K: Default,
+{
+    type Drain<'this>
+        = core::option::IntoIter<(K, V)>
+    where
+        V: 'this;
+
+    #[inline]
+    fn drain(&mut self) -> Self::Drain<'_> {
+        __storage_drain(self)
+    }
+}
+
+impl<K, V> ExtractIfStorage<K, V> for SingletonMapStorage<V>
+where
+    K: Copy + Default,
+{
+    type ExtractIf<'this>
+        = StorageExtractSlots<core::iter::Once<(K, &'this mut Option<V>)>>
+    where
+        V: 'this;
+
+    #[inline]
+    fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+        StorageExtractSlots::new(core::iter::once((K::default(), &mut self.inner)))
+    }
 }
@@ -0,0 +1,541 @@
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::iter;
+use core::marker::PhantomData;
+use core::mem;
+use core::option;
+use core::slice;
+
+use alloc::vec::{self, Vec};
+
+use crate::map::{
+    ClassStorage, DrainStorage, Entry, ExtractIfCursor, ExtractIfStorage, MapStorage,
+    OccupiedEntry, Stats, StatsStorage, VacantEntry,
+};
+use crate::raw::SparseIndex;
+
+/// [`MapStorage`] for keys with a large number of variants, which only stores
+/// the entries which are present.
+///
+/// The [`SparseIndex`] of every present key is kept sorted in one vector, with
+/// the values at the same position in another vector. Lookups are binary
+/// searches, and iteration visits entries in the same order as the array
+/// storage of a key would.
+///
+/// This is used by enums marked with `#[key(sparse)]`, so that a map which
+/// only holds a few entries doesn't pay for a slot for every variant.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(sparse)]
+/// enum Opcode {
+///     Nop,
+///     Load,
+///     Store,
+///     Jump,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(Opcode::Jump, 3);
+/// map.insert(Opcode::Load, 1);
+///
+/// assert_eq!(map.get(Opcode::Load), Some(&1));
+/// assert_eq!(map.get(Opcode::Store), None);
+/// assert!(map.iter().eq([(Opcode::Load, &1), (Opcode::Jump, &3)]));
+/// ```
+pub struct SparseMapStorage<K, V> {
+    indexes: Vec<u16>,
+    values: Vec<V>,
+    _marker: PhantomData<K>,
+}
+
+impl<K, V> SparseMapStorage<K, V>
+where
+    K: SparseIndex,
+{
+    /// Find the position of the given key, or where it should be inserted.
+    #[inline]
+    fn search(&self, key: K) -> Result<usize, usize> {
+        self.indexes.binary_search(&key.to_sparse_index())
+    }
+}
+
+/// Get the key stored at an index taken from the table of a storage.
+#[inline]
+fn key<K>(index: u16) -> K
+where
+    K: SparseIndex,
+{
+    match K::from_sparse_index(index) {
+        Some(key) => key,
+        None => panic!("sparse index {index} doesn't correspond to a key"),
+    }
+}
+
+impl<K, V> Clone for SparseMapStorage<K, V>
+where
+    V: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            indexes: self.indexes.clone(),
+            values: self.values.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V> PartialEq for SparseMapStorage<K, V>
+where
+    V: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.indexes == other.indexes && self.values == other.values
+    }
+}
+
+impl<K, V> Eq for SparseMapStorage<K, V> where V: Eq {}
+
+impl<K, V> Hash for SparseMapStorage<K, V>
+where
+    V: Hash,
+{
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        state.write_usize(self.indexes.len());
+
+        for (&index, value) in self.indexes.iter().zip(&self.values) {
+            state.write_usize(usize::from(index));
+            value.hash(state);
+        }
+    }
+}
+
+/// Ordered like the array storage of a key, which compares the index and value
+/// of every present entry in order.
+impl<K, V> PartialOrd for SparseMapStorage<K, V>
+where
+    V: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let a = self.indexes.iter().zip(&self.values);
+        let b = other.indexes.iter().zip(&other.values);
+        a.partial_cmp(b)
+    }
+}
+
+impl<K, V> Ord for SparseMapStorage<K, V>
+where
+    V: Ord,
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        let a = self.indexes.iter().zip(&self.values);
+        let b = other.indexes.iter().zip(&other.values);
+        a.cmp(b)
+    }
+}
+
+/// See [`SparseMapStorage::entry`].
+pub struct Occupied<'a, K, V> {
+    indexes: &'a mut Vec<u16>,
+    values: &'a mut Vec<V>,
+    position: usize,
+    key: K,
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> for Occupied<'a, K, V>
+where
+    K: Copy,
+{
+    #[inline]
+    fn key(&self) -> K {
+        self.key
+    }
+
+    #[inline]
+    fn key_ref(&self) -> &K {
+        &self.key
+    }
+
+    #[inline]
+    fn get(&self) -> &V {
+        &self.values[self.position]
+    }
+
+    #[inline]
+    fn get_mut(&mut self) -> &mut V {
+        &mut self.values[self.position]
+    }
+
+    #[inline]
+    fn into_mut(self) -> &'a mut V {
+        &mut self.values[self.position]
+    }
+
+    #[inline]
+    fn insert(&mut self, value: V) -> V {
+        mem::replace(&mut self.values[self.position], value)
+    }
+
+    #[inline]
+    fn remove(self) -> V {
+        self.indexes.remove(self.position);
+        self.values.remove(self.position)
+    }
+}
+
+/// See [`SparseMapStorage::entry`].
+pub struct Vacant<'a, K, V> {
+    indexes: &'a mut Vec<u16>,
+    values: &'a mut Vec<V>,
+    position: usize,
+    key: K,
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> for Vacant<'a, K, V>
+where
+    K: SparseIndex,
+{
+    #[inline]
+    fn key(&self) -> K {
+        self.key
+    }
+
+    #[inline]
+    fn key_ref(&self) -> &K {
+        &self.key
+    }
+
+    #[inline]
+    fn insert(self, value: V) -> &'a mut V {
+        self.indexes
+            .insert(self.position, self.key.to_sparse_index());
+        self.values.insert(self.position, value);
+        &mut self.values[self.position]
+    }
+}
+
+/// Iterator over the keys stored in a table of indexes.
+type Keys<'a, K> = iter::Map<iter::Copied<slice::Iter<'a, u16>>, fn(u16) -> K>;
+
+impl<K, V> MapStorage<K, V> for SparseMapStorage<K, V>
+where
+    K: SparseIndex,
+{
+    type Iter<'this>
+        = iter::Zip<Keys<'this, K>, slice::Iter<'this, V>>
+    where
+        K: 'this,
+        V: 'this;
+    type Keys<'this>
+        = Keys<'this, K>
+    where
+        K: 'this,
+        V: 'this;
+    type Values<'this>
+        = slice::Iter<'this, V>
+    where
+        K: 'this,
+        V: 'this;
+    type IterMut<'this>
+        = iter::Zip<Keys<'this, K>, slice::IterMut<'this, V>>
+    where
+        K: 'this,
+        V: 'this;
+    type ValuesMut<'this>
+        = slice::IterMut<'this, V>
+    where
+        K: 'this,
+        V: 'this;
+    type IntoIter = iter::Zip<iter::Map<vec::IntoIter<u16>, fn(u16) -> K>, vec::IntoIter<V>>;
+    type Occupied<'this>
+        = Occupied<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+    type Vacant<'this>
+        = Vacant<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn empty() -> Self {
+        Self {
+            indexes: Vec::new(),
+            values: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.indexes.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.indexes.is_empty()
+    }
+
+    #[inline]
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.search(key) {
+            Ok(position) => Some(mem::replace(&mut self.values[position], value)),
+            Err(position) => {
+                self.indexes.insert(position, key.to_sparse_index());
+                self.values.insert(position, value);
+                None
+            }
+        }
+    }
+
+    #[inline]
+    fn contains_key(&self, key: K) -> bool {
+        self.search(key).is_ok()
+    }
+
+    #[inline]
+    fn get(&self, key: K) -> Option<&V> {
+        let position = self.search(key).ok()?;
+        self.values.get(position)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        let position = self.search(key).ok()?;
+        self.values.get_mut(position)
+    }
+
+    #[inline]
+    fn remove(&mut self, key: K) -> Option<V> {
+        let position = self.search(key).ok()?;
+        self.indexes.remove(position);
+        Some(self.values.remove(position))
+    }
+
+    #[inline]
+    fn remove_and_shrink(&mut self, key: K) -> Option<V> {
+        let value = self.remove(key)?;
+        self.indexes.shrink_to_fit();
+        self.values.shrink_to_fit();
+        Some(value)
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, mut func: F)
+    where
+        F: FnMut(K, &mut V) -> bool,
+    {
+        let mut keep = 0;
+
+        for position in 0..self.indexes.len() {
+            if func(key(self.indexes[position]), &mut self.values[position]) {
+                self.indexes.swap(keep, position);
+                self.values.swap(keep, position);
+                keep += 1;
+            }
+        }
+
+        self.indexes.truncate(keep);
+        self.values.truncate(keep);
+    }
+
+    #[inline]
+    fn fill_fixed<F>(&mut self, mut func: F)
+    where
+        F: FnMut() -> V,
+    {
+        for index in 0..K::LEN {
+            let Some(key) = K::from_index(index) else {
+                continue;
+            };
+
+            if let Err(position) = self.search(key) {
+                self.indexes.insert(position, key.to_sparse_index());
+                self.values.insert(position, func());
+            }
+        }
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.indexes.clear();
+        self.values.clear();
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter<'_> {
+        self.keys().zip(self.values.iter())
+    }
+
+    #[inline]
+    fn keys(&self) -> Self::Keys<'_> {
+        self.indexes.iter().copied().map(key)
+    }
+
+    #[inline]
+    fn values(&self) -> Self::Values<'_> {
+        self.values.iter()
+    }
+
+    #[inline]
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        let keys: Keys<'_, K> = self.indexes.iter().copied().map(key);
+        keys.zip(self.values.iter_mut())
+    }
+
+    #[inline]
+    fn values_mut(&mut self) -> Self::ValuesMut<'_> {
+        self.values.iter_mut()
+    }
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        let keys: iter::Map<_, fn(u16) -> K> = self.indexes.into_iter().map(key);
+        keys.zip(self.values)
+    }
+
+    #[inline]
+    fn entry(&mut self, key: K) -> Entry<'_, Self, K, V> {
+        match self.search(key) {
+            Ok(position) => Entry::Occupied(Occupied {
+                indexes: &mut self.indexes,
+                values: &mut self.values,
+                position,
+                key,
+            }),
+            Err(position) => Entry::Vacant(Vacant {
+                indexes: &mut self.indexes,
+                values: &mut self.values,
+                position,
+                key,
+            }),
+        }
+    }
+}
+
+impl<K, V> ClassStorage<K, V> for SparseMapStorage<K, V>
+where
+    K: SparseIndex,
+{
+    type ClassIter<'this>
+        = option::IntoIter<(K, &'this V)>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn class_len(&self, class: usize) -> usize {
+        let Some(key) = K::from_index(class) else {
+            return 0;
+        };
+
+        usize::from(self.contains_key(key))
+    }
+
+    #[inline]
+    fn clear_class(&mut self, class: usize) {
+        if let Some(key) = K::from_index(class) {
+            self.remove(key);
+        }
+    }
+
+    #[inline]
+    fn iter_class(&self, class: usize) -> Self::ClassIter<'_> {
+        let key = K::from_index(class);
+        key.and_then(|key| Some((key, self.get(key)?))).into_iter()
+    }
+}
+
+impl<K, V> StatsStorage<K, V> for SparseMapStorage<K, V>
+where
+    K: SparseIndex,
+{
+    #[inline]
+    fn stats(&self) -> Stats {
+        Stats::dynamic(self.indexes.len(), self.indexes.capacity())
+    }
+}
+
+impl<K, V> DrainStorage<K, V> for SparseMapStorage<K, V>
+where
+    K: SparseIndex,
+{
+    type Drain<'this>
+        = iter::Zip<iter::Map<vec::Drain<'this, u16>, fn(u16) -> K>, vec::Drain<'this, V>>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn drain(&mut self) -> Self::Drain<'_> {
+        let keys: iter::Map<_, fn(u16) -> K> = self.indexes.drain(..).map(key);
+        keys.zip(self.values.drain(..))
+    }
+}
+
+/// See [`SparseMapStorage::extract_if`].
+pub struct ExtractIf<'a, K, V> {
+    indexes: &'a mut Vec<u16>,
+    values: &'a mut Vec<V>,
+    position: usize,
+    _marker: PhantomData<K>,
+}
+
+impl<K, V> ExtractIfCursor<K, V> for ExtractIf<'_, K, V>
+where
+    K: SparseIndex,
+{
+    #[inline]
+    fn next_if<F>(&mut self, filter: &mut F) -> Option<(K, V)>
+    where
+        F: ?Sized + FnMut(K, &mut V) -> bool,
+    {
+        while self.position < self.indexes.len() {
+            let position = self.position;
+            let key = key(self.indexes[position]);
+
+            if filter(key, &mut self.values[position]) {
+                self.indexes.remove(position);
+                return Some((key, self.values.remove(position)));
+            }
+
+            self.position += 1;
+        }
+
+        None
+    }
+}
+
+impl<K, V> ExtractIfStorage<K, V> for SparseMapStorage<K, V>
+where
+    K: SparseIndex,
+{
+    type ExtractIf<'this>
+        = ExtractIf<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+        ExtractIf {
+            indexes: &mut self.indexes,
+            values: &mut self.values,
+            position: 0,
+            _marker: PhantomData,
+        }
+    }
+}
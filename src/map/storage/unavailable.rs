@@ -0,0 +1,350 @@
+use core::cmp::Ordering;
+use core::convert::Infallible;
+use core::hash::{Hash, Hasher};
+use core::iter;
+use core::marker::PhantomData;
+
+use crate::map::{
+    ClassStorage, DrainStorage, Entry, ExtractIfCursor, ExtractIfStorage, MapStorage,
+    OccupiedEntry, Stats, StatsStorage, VacantEntry,
+};
+
+/// [`MapStorage`] for a variant marked with `#[key(optional)]` whose field
+/// can't be used as a key, since neither the `hashbrown` nor the
+/// `std-collections` feature of fixed-map is enabled.
+///
+/// The storage is always empty. Lookups of the variant find nothing, while
+/// inserting into it panics.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::map::storage::UnavailableMapStorage;
+/// use fixed_map::map::MapStorage;
+///
+/// let mut storage = UnavailableMapStorage::<u32, &str>::empty();
+/// assert_eq!(storage.get(1), None);
+/// assert!(storage.iter().next().is_none());
+/// ```
+///
+/// ```should_panic
+/// use fixed_map::map::storage::UnavailableMapStorage;
+/// use fixed_map::map::MapStorage;
+///
+/// let mut storage = UnavailableMapStorage::<u32, &str>::empty();
+/// storage.insert(1, "one");
+/// ```
+pub struct UnavailableMapStorage<K, V> {
+    _marker: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K, V> Clone for UnavailableMapStorage<K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for UnavailableMapStorage<K, V> {}
+
+impl<K, V> PartialEq for UnavailableMapStorage<K, V> {
+    #[inline]
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<K, V> Eq for UnavailableMapStorage<K, V> {}
+
+impl<K, V> Hash for UnavailableMapStorage<K, V> {
+    #[inline]
+    fn hash<H>(&self, _: &mut H)
+    where
+        H: Hasher,
+    {
+    }
+}
+
+impl<K, V> PartialOrd for UnavailableMapStorage<K, V> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K, V> Ord for UnavailableMapStorage<K, V> {
+    #[inline]
+    fn cmp(&self, _: &Self) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+/// The entry type of [`UnavailableMapStorage`], which can't be constructed.
+pub struct Never<'a, K, V> {
+    never: Infallible,
+    _marker: PhantomData<&'a mut (K, V)>,
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> for Never<'a, K, V> {
+    #[inline]
+    fn key(&self) -> K {
+        match self.never {}
+    }
+
+    #[inline]
+    fn key_ref(&self) -> &K {
+        match self.never {}
+    }
+
+    #[inline]
+    fn get(&self) -> &V {
+        match self.never {}
+    }
+
+    #[inline]
+    fn get_mut(&mut self) -> &mut V {
+        match self.never {}
+    }
+
+    #[inline]
+    fn into_mut(self) -> &'a mut V {
+        match self.never {}
+    }
+
+    #[inline]
+    fn insert(&mut self, _: V) -> V {
+        match self.never {}
+    }
+
+    #[inline]
+    fn remove(self) -> V {
+        match self.never {}
+    }
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> for Never<'a, K, V> {
+    #[inline]
+    fn key(&self) -> K {
+        match self.never {}
+    }
+
+    #[inline]
+    fn key_ref(&self) -> &K {
+        match self.never {}
+    }
+
+    #[inline]
+    fn insert(self, _: V) -> &'a mut V {
+        match self.never {}
+    }
+}
+
+impl<K, V> MapStorage<K, V> for UnavailableMapStorage<K, V> {
+    type Iter<'this>
+        = iter::Empty<(K, &'this V)>
+    where
+        K: 'this,
+        V: 'this;
+    type Keys<'this>
+        = iter::Empty<K>
+    where
+        K: 'this,
+        V: 'this;
+    type Values<'this>
+        = iter::Empty<&'this V>
+    where
+        K: 'this,
+        V: 'this;
+    type IterMut<'this>
+        = iter::Empty<(K, &'this mut V)>
+    where
+        K: 'this,
+        V: 'this;
+    type ValuesMut<'this>
+        = iter::Empty<&'this mut V>
+    where
+        K: 'this,
+        V: 'this;
+    type IntoIter = iter::Empty<(K, V)>;
+    type Occupied<'this>
+        = Never<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+    type Vacant<'this>
+        = Never<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn empty() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        0
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    #[track_caller]
+    fn insert(&mut self, _: K, _: V) -> Option<V> {
+        unavailable::<K>()
+    }
+
+    #[inline]
+    fn contains_key(&self, _: K) -> bool {
+        false
+    }
+
+    #[inline]
+    fn get(&self, _: K) -> Option<&V> {
+        None
+    }
+
+    #[inline]
+    fn get_mut(&mut self, _: K) -> Option<&mut V> {
+        None
+    }
+
+    #[inline]
+    fn remove(&mut self, _: K) -> Option<V> {
+        None
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, _: F)
+    where
+        F: FnMut(K, &mut V) -> bool,
+    {
+    }
+
+    #[inline]
+    fn clear(&mut self) {}
+
+    #[inline]
+    fn iter(&self) -> Self::Iter<'_> {
+        iter::empty()
+    }
+
+    #[inline]
+    fn keys(&self) -> Self::Keys<'_> {
+        iter::empty()
+    }
+
+    #[inline]
+    fn values(&self) -> Self::Values<'_> {
+        iter::empty()
+    }
+
+    #[inline]
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        iter::empty()
+    }
+
+    #[inline]
+    fn values_mut(&mut self) -> Self::ValuesMut<'_> {
+        iter::empty()
+    }
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        iter::empty()
+    }
+
+    #[inline]
+    #[track_caller]
+    fn entry(&mut self, _: K) -> Entry<'_, Self, K, V> {
+        unavailable::<K>()
+    }
+}
+
+impl<K, V> ClassStorage<K, V> for UnavailableMapStorage<K, V> {
+    type ClassIter<'this>
+        = iter::Empty<(K, &'this V)>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn class_len(&self, _: usize) -> usize {
+        0
+    }
+
+    #[inline]
+    fn clear_class(&mut self, _: usize) {}
+
+    #[inline]
+    fn iter_class(&self, _: usize) -> Self::ClassIter<'_> {
+        iter::empty()
+    }
+}
+
+impl<K, V> StatsStorage<K, V> for UnavailableMapStorage<K, V> {
+    #[inline]
+    fn stats(&self) -> Stats {
+        Stats::default()
+    }
+}
+
+impl<K, V> DrainStorage<K, V> for UnavailableMapStorage<K, V> {
+    type Drain<'this>
+        = iter::Empty<(K, V)>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn drain(&mut self) -> Self::Drain<'_> {
+        iter::empty()
+    }
+}
+
+/// See [`UnavailableMapStorage::extract_if`].
+pub struct ExtractIf<K, V> {
+    _marker: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K, V> ExtractIfCursor<K, V> for ExtractIf<K, V> {
+    #[inline]
+    fn next_if<F>(&mut self, _: &mut F) -> Option<(K, V)>
+    where
+        F: ?Sized + FnMut(K, &mut V) -> bool,
+    {
+        None
+    }
+}
+
+impl<K, V> ExtractIfStorage<K, V> for UnavailableMapStorage<K, V> {
+    type ExtractIf<'this>
+        = ExtractIf<K, V>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+        ExtractIf {
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Panic since a key was inserted into storage which is unavailable.
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub(crate) fn unavailable<K>() -> ! {
+    panic!(
+        "`{}` can only be stored in a key if the `hashbrown` or `std-collections` feature of fixed-map is enabled",
+        core::any::type_name::<K>()
+    )
+}
@@ -0,0 +1,195 @@
+//! Instrumentation hooks for maps and sets.
+//!
+//! When the `metrics` feature is enabled, [`Map`] and [`Set`] report inserts,
+//! removals and lookups to a global [`Recorder`] which is installed
+//! through [`set_recorder`]. Each [`Event`] is labelled with the type of the
+//! key and the name of the variant being accessed, as returned by
+//! [`Key::variant_name`], which makes it possible to track hit rates per key
+//! without wrapping every call.
+//!
+//! Until a recorder is installed events are discarded.
+//!
+//! # Recorded operations
+//!
+//! Only operations which are given a single key are recorded, since those are
+//! the ones an [`Event`] can be labelled for:
+//!
+//! * [`Operation::Hit`] or [`Operation::Miss`] by [`Map::contains_key`],
+//!   [`Map::get`], [`Map::get_mut`], [`Map::entry`], [`Map::entry_ref`] and
+//!   [`Set::contains`]. Methods built on top of these, like [`Map::update`],
+//!   are recorded through them.
+//! * [`Operation::Insert`] by [`Map::insert`], [`Map::insert_unique`],
+//!   [`Map::try_insert`] and [`Set::insert`], including [`Set::insert_unique`]
+//!   which is built on top of it. Values inserted through an [`Entry`] are not
+//!   recorded again, since the lookup of the entry already was.
+//! * [`Operation::Remove`] by [`Map::remove`], [`Map::remove_and_shrink`] and
+//!   [`Set::remove`].
+//!
+//! Everything else is not recorded. This includes iteration and operations
+//! over many keys at once like [`Map::retain`], [`Map::clear`] or [`Extend`].
+//! Indexing a map is recorded as a lookup through [`Map::get`] or
+//! [`Map::get_mut`], and types built on top of a map like [`CellMap`] and
+//! [`RestrictedMap`] record the operations they perform on the map they wrap.
+//! For a [`CellMap`] these are the lookups of its slots, which might be
+//! occupied even though they hold no value.
+//!
+//! [`CellMap`]: crate::CellMap
+//! [`Entry`]: crate::map::Entry
+//! [`Map::contains_key`]: crate::Map::contains_key
+//! [`Map::get`]: crate::Map::get
+//! [`Map::get_mut`]: crate::Map::get_mut
+//! [`Map::entry`]: crate::Map::entry
+//! [`Map::entry_ref`]: crate::Map::entry_ref
+//! [`Map::update`]: crate::Map::update
+//! [`Map::insert`]: crate::Map::insert
+//! [`Map::insert_unique`]: crate::Map::insert_unique
+//! [`Map::try_insert`]: crate::Map::try_insert
+//! [`Map::remove`]: crate::Map::remove
+//! [`Map::remove_and_shrink`]: crate::Map::remove_and_shrink
+//! [`Map::retain`]: crate::Map::retain
+//! [`Map::clear`]: crate::Map::clear
+//! [`RestrictedMap`]: crate::map::RestrictedMap
+//! [`Set::contains`]: crate::Set::contains
+//! [`Set::insert`]: crate::Set::insert
+//! [`Set::insert_unique`]: crate::Set::insert_unique
+//! [`Set::remove`]: crate::Set::remove
+//!
+//! [`Map`]: crate::Map
+//! [`Set`]: crate::Set
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//!
+//! use fixed_map::metrics::{self, Event, Operation, Recorder};
+//! use fixed_map::{Key, Map};
+//!
+//! #[derive(Clone, Copy, Key)]
+//! enum Slot {
+//!     Head,
+//!     Tail,
+//! }
+//!
+//! struct HeadHits(AtomicUsize);
+//!
+//! impl Recorder for HeadHits {
+//!     fn record(&self, event: &Event) {
+//!         if event.operation == Operation::Hit && event.variant == "Head" {
+//!             self.0.fetch_add(1, Ordering::Relaxed);
+//!         }
+//!     }
+//! }
+//!
+//! static RECORDER: HeadHits = HeadHits(AtomicUsize::new(0));
+//!
+//! metrics::set_recorder(&RECORDER)?;
+//!
+//! let mut map = Map::new();
+//! map.insert(Slot::Head, 1);
+//!
+//! assert_eq!(map.get(Slot::Head), Some(&1));
+//! assert_eq!(map.get(Slot::Tail), None);
+//! assert_eq!(RECORDER.0.load(Ordering::Relaxed), 1);
+//! # Ok::<_, metrics::SetRecorderError>(())
+//! ```
+
+use core::fmt;
+
+use std::sync::OnceLock;
+
+use crate::Key;
+
+static RECORDER: OnceLock<&'static dyn Recorder> = OnceLock::new();
+
+/// The kind of operation an [`Event`] was recorded for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Operation {
+    /// A value was inserted.
+    Insert,
+    /// A value was removed, or an attempt was made to remove a missing value.
+    Remove,
+    /// A lookup found a value.
+    Hit,
+    /// A lookup didn't find a value.
+    Miss,
+}
+
+/// An event passed to a [`Recorder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Event {
+    /// The operation which was performed.
+    pub operation: Operation,
+    /// The name of the key type, as returned by [`core::any::type_name`].
+    pub key_type: &'static str,
+    /// The name of the variant of the key, as returned by
+    /// [`Key::variant_name`].
+    pub variant: &'static str,
+}
+
+/// A recorder of map and set operations.
+///
+/// See the [module level documentation][self] for an example.
+pub trait Recorder: Sync + Send {
+    /// Record a single event.
+    fn record(&self, event: &Event);
+}
+
+/// The error returned by [`set_recorder`] if a recorder has already been
+/// installed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SetRecorderError;
+
+impl fmt::Display for SetRecorderError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a recorder has already been installed")
+    }
+}
+
+impl std::error::Error for SetRecorderError {}
+
+/// Install the global recorder.
+///
+/// A recorder can only be installed once for the duration of the program.
+///
+/// # Errors
+///
+/// Errors with [`SetRecorderError`] if a recorder has already been installed.
+#[inline]
+pub fn set_recorder(recorder: &'static dyn Recorder) -> Result<(), SetRecorderError> {
+    RECORDER.set(recorder).map_err(|_| SetRecorderError)
+}
+
+/// Record an operation on the given key with the installed recorder, if any.
+#[inline]
+pub(crate) fn record<K>(operation: Operation, key: K)
+where
+    K: Key,
+{
+    if let Some(recorder) = RECORDER.get() {
+        recorder.record(&Event {
+            operation,
+            key_type: core::any::type_name::<K>(),
+            variant: key.variant_name(),
+        });
+    }
+}
+
+/// Record a lookup of the given key, which is a hit if `found` is `true`.
+#[inline]
+pub(crate) fn record_lookup<K>(key: K, found: bool)
+where
+    K: Key,
+{
+    let operation = if found {
+        Operation::Hit
+    } else {
+        Operation::Miss
+    };
+
+    record(operation, key);
+}
@@ -0,0 +1,433 @@
+//! Utilities for migrating maps when the variants of their keys change.
+//!
+//! This is useful to support rolling upgrades of persisted state, where data
+//! written with an older version of a key has to be loaded with a newer one.
+//!
+//! # Examples
+//!
+//! ```
+//! use fixed_map::{migrate, Map};
+//!
+//! mod v1 {
+//!     #[derive(Debug, Clone, Copy, PartialEq, fixed_map::Key)]
+//!     pub enum Color {
+//!         Red,
+//!         Green,
+//!         Purple,
+//!     }
+//! }
+//!
+//! mod v2 {
+//!     #[derive(Debug, Clone, Copy, PartialEq, fixed_map::Key)]
+//!     pub enum Color {
+//!         Green,
+//!         Red,
+//!     }
+//! }
+//!
+//! let old = Map::from_iter([(v1::Color::Red, 1), (v1::Color::Purple, 2)]);
+//!
+//! let new = migrate::remap(old, |key| match key {
+//!     v1::Color::Red => Some(v2::Color::Red),
+//!     v1::Color::Green => Some(v2::Color::Green),
+//!     v1::Color::Purple => None,
+//! });
+//!
+//! assert!(new.iter().eq([(v2::Color::Red, &1)]));
+//! ```
+
+use crate::{Key, Map};
+
+/// Marker for keys which convert [`Into`] the key `K` without mapping two keys
+/// onto the same key, which is what allows [`Map::map_keys_into`] to move
+/// every entry over.
+///
+/// This is implemented through the `#[key(maps_to(K))]` attribute of the
+/// [`Key`][key-derive] derive, which also requires `K` to implement
+/// `From<Self>`. If the conversion isn't injective, entries whose keys collide
+/// are overwritten.
+///
+/// [key-derive]: derive@crate::Key
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(maps_to(Color))]
+/// enum Primary {
+///     Red,
+///     Blue,
+/// }
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Color {
+///     Red,
+///     Green,
+///     Blue,
+/// }
+///
+/// impl From<Primary> for Color {
+///     fn from(value: Primary) -> Self {
+///         match value {
+///             Primary::Red => Color::Red,
+///             Primary::Blue => Color::Blue,
+///         }
+///     }
+/// }
+///
+/// let map = Map::from_iter([(Primary::Red, 1), (Primary::Blue, 2)]);
+/// let map: Map<Color, _> = map.map_keys_into();
+///
+/// assert!(map.iter().eq([(Color::Red, &1), (Color::Blue, &2)]));
+/// ```
+pub trait MapsTo<K>: Key + Into<K>
+where
+    K: Key,
+{
+}
+
+/// Re-key a map by passing every key in `old` through `f`.
+///
+/// Entries for which `f` returns `None` are dropped. If `f` maps multiple keys
+/// onto the same new key, the value which comes last in the iteration order
+/// of `old` is kept.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{migrate, Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Old {
+///     First,
+///     Second(bool),
+/// }
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum New {
+///     First,
+///     Second,
+/// }
+///
+/// let old = Map::from_iter([(Old::First, 1), (Old::Second(false), 2), (Old::Second(true), 3)]);
+///
+/// let new = migrate::remap(old, |key| match key {
+///     Old::First => Some(New::First),
+///     Old::Second(true) => Some(New::Second),
+///     Old::Second(false) => None,
+/// });
+///
+/// assert!(new.iter().eq([(New::First, &1), (New::Second, &3)]));
+/// ```
+#[inline]
+pub fn remap<OldK, NewK, V, F>(old: Map<OldK, V>, mut f: F) -> Map<NewK, V>
+where
+    OldK: Key,
+    NewK: Key,
+    F: FnMut(OldK) -> Option<NewK>,
+{
+    let mut output = Map::new();
+
+    for (key, value) in old {
+        if let Some(key) = f(key) {
+            output.insert(key, value);
+        }
+    }
+
+    output
+}
+
+/// The output of [`deserialize_remap`].
+///
+/// This is only available when the `serde` and `alloc` features are enabled.
+#[cfg(all(feature = "serde", feature = "alloc"))]
+#[non_exhaustive]
+pub struct Remapped<K, V, R>
+where
+    K: Key,
+{
+    /// The entries whose keys could be mapped.
+    pub map: Map<K, V>,
+    /// The raw keys and values of entries whose keys could not be mapped, in
+    /// the order in which they were deserialized.
+    pub unknown: alloc::vec::Vec<(R, V)>,
+}
+
+/// Deserialize a map whose keys are first deserialized as a raw key `R` and
+/// then mapped to `K` through `f`.
+///
+/// Entries for which `f` returns `None` are collected in the side channel
+/// [`Remapped::unknown`], rather than causing deserialization to fail. The
+/// raw key can for example be a `String`, or the version of the key which the
+/// data was serialized with.
+///
+/// This is only available when the `serde` and `alloc` features are enabled.
+///
+/// # Errors
+///
+/// Errors if the raw keys or the values fail to deserialize.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{migrate, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Color {
+///     Red,
+///     Green,
+/// }
+///
+/// let json = r#"{"red": 1, "purple": 2, "green": 3}"#;
+/// let mut deserializer = serde_json::Deserializer::from_str(json);
+///
+/// let remapped = migrate::deserialize_remap(&mut deserializer, |key: &String| match key.as_str() {
+///     "red" => Some(Color::Red),
+///     "green" => Some(Color::Green),
+///     _ => None,
+/// })?;
+///
+/// assert!(remapped.map.iter().eq([(Color::Red, &1), (Color::Green, &3)]));
+/// assert_eq!(remapped.unknown, [(String::from("purple"), 2)]);
+/// # Ok::<_, serde_json::Error>(())
+/// ```
+#[cfg(all(feature = "serde", feature = "alloc"))]
+pub fn deserialize_remap<'de, D, R, K, V, F>(
+    deserializer: D,
+    f: F,
+) -> Result<Remapped<K, V, R>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    R: serde::Deserialize<'de>,
+    K: Key,
+    V: serde::Deserialize<'de>,
+    F: FnMut(&R) -> Option<K>,
+{
+    use core::fmt;
+    use core::marker::PhantomData;
+
+    use alloc::vec::Vec;
+
+    struct RemapVisitor<R, K, V, F> {
+        f: F,
+        _marker: PhantomData<(R, K, V)>,
+    }
+
+    impl<'de, R, K, V, F> serde::de::Visitor<'de> for RemapVisitor<R, K, V, F>
+    where
+        R: serde::Deserialize<'de>,
+        K: Key,
+        V: serde::Deserialize<'de>,
+        F: FnMut(&R) -> Option<K>,
+    {
+        type Value = Remapped<K, V, R>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a map")
+        }
+
+        #[inline]
+        fn visit_map<T>(mut self, mut visitor: T) -> Result<Self::Value, T::Error>
+        where
+            T: serde::de::MapAccess<'de>,
+        {
+            let mut map = Map::new();
+            let mut unknown = Vec::new();
+
+            while let Some((raw, value)) = visitor.next_entry::<R, V>()? {
+                match (self.f)(&raw) {
+                    Some(key) => {
+                        map.insert(key, value);
+                    }
+                    None => {
+                        unknown.push((raw, value));
+                    }
+                }
+            }
+
+            Ok(Remapped { map, unknown })
+        }
+    }
+
+    deserializer.deserialize_map(RemapVisitor {
+        f,
+        _marker: PhantomData,
+    })
+}
+
+/// A map which captures entries with unknown keys when deserialized, instead of
+/// failing.
+///
+/// Every key is first deserialized as the raw key `R`, which defaults to
+/// `String`, and then deserialized into `K` from the raw key. Entries whose
+/// keys can't be deserialized into `K` end up in [`MapWithUnknown::unknown`],
+/// and are serialized after the known entries so that they survive a
+/// round trip through an older or newer version of a program.
+///
+/// Since keys are deserialized from an owned raw key, the [`Deserialize`]
+/// implementation of `K` can't borrow from the input. For raw keys which can't
+/// be converted through [`IntoDeserializer`], use [`deserialize_remap`] with a
+/// custom mapping instead.
+///
+/// This is only available when the `serde` and `alloc` features are enabled.
+///
+/// [`Deserialize`]: serde::Deserialize
+/// [`IntoDeserializer`]: serde::de::IntoDeserializer
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::migrate::MapWithUnknown;
+/// use fixed_map::Key;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Color {
+///     Red,
+///     Green,
+/// }
+///
+/// impl<'de> serde::Deserialize<'de> for Color {
+///     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+///     where
+///         D: serde::Deserializer<'de>,
+///     {
+///         match String::deserialize(deserializer)?.as_str() {
+///             "red" => Ok(Color::Red),
+///             "green" => Ok(Color::Green),
+///             other => Err(serde::de::Error::unknown_variant(other, &["red", "green"])),
+///         }
+///     }
+/// }
+///
+/// let json = r#"{"red": 1, "purple": 2}"#;
+/// let map: MapWithUnknown<Color, u32> = serde_json::from_str(json)?;
+///
+/// assert!(map.map.iter().eq([(Color::Red, &1)]));
+/// assert_eq!(map.unknown, [(String::from("purple"), 2)]);
+/// # Ok::<_, serde_json::Error>(())
+/// ```
+#[cfg(all(feature = "serde", feature = "alloc"))]
+#[non_exhaustive]
+pub struct MapWithUnknown<K, V, R = alloc::string::String>
+where
+    K: Key,
+{
+    /// The entries whose keys are known.
+    pub map: Map<K, V>,
+    /// The raw keys and values of entries whose keys are unknown, in the order
+    /// in which they were deserialized.
+    pub unknown: alloc::vec::Vec<(R, V)>,
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl<K, V, R> MapWithUnknown<K, V, R>
+where
+    K: Key,
+{
+    /// Construct a new empty map without any unknown entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::migrate::MapWithUnknown;
+    /// use fixed_map::Key;
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map = MapWithUnknown::<MyKey, u32>::new();
+    /// assert!(map.map.is_empty());
+    /// assert!(map.unknown.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            map: Map::new(),
+            unknown: alloc::vec::Vec::new(),
+        }
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl<K, V, R> Default for MapWithUnknown<K, V, R>
+where
+    K: Key,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl<K, V, R> core::fmt::Debug for MapWithUnknown<K, V, R>
+where
+    K: Key + core::fmt::Debug,
+    V: core::fmt::Debug,
+    R: core::fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MapWithUnknown")
+            .field("map", &self.map)
+            .field("unknown", &self.unknown)
+            .finish()
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl<K, V, R> serde::Serialize for MapWithUnknown<K, V, R>
+where
+    K: Key + serde::Serialize,
+    V: serde::Serialize,
+    R: serde::Serialize,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap as _;
+
+        let mut map = serializer.serialize_map(Some(self.map.len() + self.unknown.len()))?;
+
+        for (k, v) in &self.map {
+            map.serialize_entry(&k, v)?;
+        }
+
+        for (k, v) in &self.unknown {
+            map.serialize_entry(k, v)?;
+        }
+
+        map.end()
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl<'de, K, V, R> serde::Deserialize<'de> for MapWithUnknown<K, V, R>
+where
+    K: Key + serde::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+    R: Clone + serde::Deserialize<'de> + serde::de::IntoDeserializer<'de>,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let remapped = deserialize_remap(deserializer, |raw: &R| {
+            K::deserialize(raw.clone().into_deserializer()).ok()
+        })?;
+
+        Ok(Self {
+            map: remapped.map,
+            unknown: remapped.unknown,
+        })
+    }
+}
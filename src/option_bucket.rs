@@ -261,13 +261,13 @@ impl<'a, T> SomeBucket<'a, T> {
     }
 }
 
-impl<'a, K, V> OccupiedEntry<'a, K, V> for SomeBucket<'a, V>
-where
-    K: Default,
-{
+impl<'a, V> OccupiedEntry<'a, (), V> for SomeBucket<'a, V> {
     #[inline]
-    fn key(&self) -> K {
-        K::default()
+    fn key(&self) {}
+
+    #[inline]
+    fn key_ref(&self) -> &() {
+        &()
     }
 
     #[inline]
@@ -367,13 +367,13 @@ impl<'a, T> NoneBucket<'a, T> {
     }
 }
 
-impl<'a, K, V> VacantEntry<'a, K, V> for NoneBucket<'a, V>
-where
-    K: Default,
-{
+impl<'a, V> VacantEntry<'a, (), V> for NoneBucket<'a, V> {
+    #[inline]
+    fn key(&self) {}
+
     #[inline]
-    fn key(&self) -> K {
-        K::default()
+    fn key_ref(&self) -> &() {
+        &()
     }
 
     #[inline]
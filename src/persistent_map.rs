@@ -0,0 +1,654 @@
+//! Contains the fixed [`PersistentMap`] implementation.
+
+use core::array;
+use core::fmt;
+use core::iter;
+use core::marker::PhantomData;
+use core::slice;
+
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::map::Map;
+use crate::raw::KeyIndex;
+use crate::Key;
+
+/// The number of slots in every node of the tree.
+const WIDTH: usize = 8;
+
+/// A persistent (immutable) fixed map with structural sharing.
+///
+/// Modifying a [`PersistentMap`] returns a new map and leaves the original
+/// untouched. Entries are stored in a tree of dense chunks, where every leaf
+/// holds the slots of a few neighbouring keys as given by
+/// [`KeyIndex::to_index`] and every branch holds a few chunks below it. Chunks
+/// are shared through an [`Arc`], so a modification only copies the chunks on
+/// the path from the root to the slot of the modified key, and the new map
+/// shares everything else with the one it was derived from. Values are stored
+/// behind an [`Arc`] too, so values which weren't modified are shared even
+/// within the copied leaf, and cloning a [`PersistentMap`] only bumps a
+/// reference count.
+///
+/// This makes it cheap to keep many versions of a map around, like the
+/// history of an undo stack, without cloning every entry for each edit.
+/// Entries are visited in the same order as in a [`Map`].
+///
+/// Keys must implement [`KeyIndex`], i.e. be enums where every variant is a
+/// unit variant, since entries are located by the slot of their key. The
+/// [`Key`][key-derive] derive implements it for such enums.
+///
+/// This is only available when the `alloc` feature is enabled.
+///
+/// [key-derive]: derive@crate::Key
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, PersistentMap};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let empty = PersistentMap::new();
+/// let one = empty.insert(MyKey::First, String::from("one"));
+/// let two = one.insert(MyKey::Second, String::from("two"));
+///
+/// assert!(empty.is_empty());
+/// assert_eq!(one.len(), 1);
+/// assert_eq!(two.len(), 2);
+///
+/// // The value which wasn't modified is shared between versions.
+/// assert!(core::ptr::eq(one.get(MyKey::First).unwrap(), two.get(MyKey::First).unwrap()));
+///
+/// let undone = two.remove(MyKey::Second);
+/// assert_eq!(undone, one);
+/// ```
+pub struct PersistentMap<K, V>
+where
+    K: Key,
+{
+    root: Option<Arc<Node<V>>>,
+    len: usize,
+    _marker: PhantomData<K>,
+}
+
+/// A chunk of the tree, where a slot is `None` if none of the keys below it
+/// have a value. Chunks which would be entirely empty are removed.
+enum Node<V> {
+    Branch([Option<Arc<Node<V>>>; WIDTH]),
+    Leaf([Option<Arc<V>>; WIDTH]),
+}
+
+impl<V> Clone for Node<V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        match self {
+            Node::Branch(children) => Node::Branch(children.clone()),
+            Node::Leaf(values) => Node::Leaf(values.clone()),
+        }
+    }
+}
+
+impl<K, V> PersistentMap<K, V>
+where
+    K: KeyIndex,
+{
+    /// Creates an empty [`PersistentMap`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, PersistentMap};
+    ///
+    /// #[derive(Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map: PersistentMap<MyKey, i32> = PersistentMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new() -> PersistentMap<K, V> {
+        PersistentMap {
+            root: None,
+            len: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, PersistentMap};
+    ///
+    /// #[derive(Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map = PersistentMap::new().insert(MyKey::First, 42);
+    /// assert_eq!(map.get(MyKey::First), Some(&42));
+    /// assert_eq!(map.get(MyKey::Second), None);
+    /// ```
+    #[inline]
+    pub fn get(&self, key: K) -> Option<&V> {
+        let index = key.to_index();
+        let mut node = self.root.as_deref()?;
+        let mut span = root_span::<K>();
+
+        loop {
+            span /= WIDTH;
+            let slot = index / span % WIDTH;
+
+            match node {
+                Node::Branch(children) => node = children[slot].as_deref()?,
+                Node::Leaf(values) => return values[slot].as_deref(),
+            }
+        }
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, PersistentMap};
+    ///
+    /// #[derive(Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map = PersistentMap::new().insert(MyKey::First, 42);
+    /// assert!(map.contains_key(MyKey::First));
+    /// assert!(!map.contains_key(MyKey::Second));
+    /// ```
+    #[inline]
+    pub fn contains_key(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the number of elements in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, PersistentMap};
+    ///
+    /// #[derive(Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map = PersistentMap::new().insert(MyKey::First, 42);
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, PersistentMap};
+    ///
+    /// #[derive(Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map = PersistentMap::new();
+    /// assert!(map.is_empty());
+    /// assert!(!map.insert(MyKey::First, 42).is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// An iterator visiting all key-value pairs in order, with references to
+    /// the values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, PersistentMap};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map = PersistentMap::new()
+    ///     .insert(MyKey::Second, 2)
+    ///     .insert(MyKey::First, 1);
+    ///
+    /// assert!(map.iter().eq([(MyKey::First, &1), (MyKey::Second, &2)]));
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let root = (
+            0,
+            root_span::<K>(),
+            slice::from_ref(&self.root).iter().enumerate(),
+        );
+
+        Iter {
+            branches: vec![root],
+            values: (0, [].iter().enumerate()),
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns `true` if both maps are the same version, which means that they
+    /// share the same storage.
+    ///
+    /// This is a cheap way of checking whether a map has been modified, but
+    /// maps which are equal might still be different versions. Empty maps are
+    /// always the same version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, PersistentMap};
+    ///
+    /// #[derive(Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let a = PersistentMap::new().insert(MyKey::First, 1);
+    /// let b = a.clone();
+    /// let c = a.insert(MyKey::First, 1);
+    ///
+    /// assert!(PersistentMap::ptr_eq(&a, &b));
+    /// assert!(!PersistentMap::ptr_eq(&a, &c));
+    /// ```
+    #[inline]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        match (&this.root, &other.root) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns a new map where the key is associated with the given value.
+    ///
+    /// Only the chunks on the path to the slot of the key are copied, all
+    /// other chunks and values are shared with the current map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, PersistentMap};
+    ///
+    /// #[derive(Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let a = PersistentMap::new().insert(MyKey::First, 1);
+    /// let b = a.insert(MyKey::First, 2);
+    ///
+    /// assert_eq!(a.get(MyKey::First), Some(&1));
+    /// assert_eq!(b.get(MyKey::First), Some(&2));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn insert(&self, key: K, value: V) -> PersistentMap<K, V> {
+        let len = self.len + usize::from(!self.contains_key(key));
+        self.modify(key, Some(Arc::new(value)), len)
+    }
+
+    /// Returns a new map where the key is removed.
+    ///
+    /// If the key isn't present, the returned map is the same version as the
+    /// current one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, PersistentMap};
+    ///
+    /// #[derive(Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let a = PersistentMap::new().insert(MyKey::First, 1);
+    /// let b = a.remove(MyKey::First);
+    ///
+    /// assert_eq!(a.get(MyKey::First), Some(&1));
+    /// assert_eq!(b.get(MyKey::First), None);
+    /// assert!(PersistentMap::ptr_eq(&b, &b.remove(MyKey::Second)));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn remove(&self, key: K) -> PersistentMap<K, V> {
+        if !self.contains_key(key) {
+            return self.clone();
+        }
+
+        self.modify(key, None, self.len - 1)
+    }
+
+    /// Returns a new map where the value associated with the key has been
+    /// replaced by the result of calling `f` with the current value.
+    ///
+    /// If the key isn't present, the returned map is the same version as the
+    /// current one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, PersistentMap};
+    ///
+    /// #[derive(Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let a = PersistentMap::new().insert(MyKey::First, 1);
+    /// let b = a.update(MyKey::First, |value| value + 1);
+    ///
+    /// assert_eq!(a.get(MyKey::First), Some(&1));
+    /// assert_eq!(b.get(MyKey::First), Some(&2));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn update<F>(&self, key: K, f: F) -> PersistentMap<K, V>
+    where
+        F: FnOnce(&V) -> V,
+    {
+        let Some(value) = self.get(key) else {
+            return self.clone();
+        };
+
+        let value = f(value);
+        self.insert(key, value)
+    }
+
+    /// Construct a new map where the slot of the given key holds `value`.
+    fn modify(&self, key: K, value: Option<Arc<V>>, len: usize) -> PersistentMap<K, V> {
+        let mut root = self.root.clone();
+        store(&mut root, root_span::<K>(), key.to_index(), value);
+
+        PersistentMap {
+            root,
+            len,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V> Clone for PersistentMap<K, V>
+where
+    K: Key,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        PersistentMap {
+            root: self.root.clone(),
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V> Default for PersistentMap<K, V>
+where
+    K: KeyIndex,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> fmt::Debug for PersistentMap<K, V>
+where
+    K: KeyIndex + fmt::Debug,
+    V: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Entries are always stored in the same order, so maps with the same entries
+/// are compared by visiting them side by side.
+impl<K, V> PartialEq for PersistentMap<K, V>
+where
+    K: KeyIndex + PartialEq,
+    V: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        PersistentMap::ptr_eq(self, other)
+            || (self.len() == other.len() && self.iter().eq(other.iter()))
+    }
+}
+
+impl<K, V> Eq for PersistentMap<K, V>
+where
+    K: KeyIndex + PartialEq,
+    V: Eq,
+{
+}
+
+/// Constructs a [`PersistentMap`] from the contents of a [`Map`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Map, PersistentMap};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(MyKey::Second, 2);
+///
+/// let map = PersistentMap::from(map);
+/// assert!(map.iter().eq([(MyKey::Second, &2)]));
+/// ```
+impl<K, V> From<Map<K, V>> for PersistentMap<K, V>
+where
+    K: KeyIndex,
+{
+    #[inline]
+    fn from(map: Map<K, V>) -> Self {
+        let len = map.len();
+        let span = root_span::<K>();
+        let mut root = None;
+
+        // Nothing is shared yet, so every chunk is only allocated once.
+        for (key, value) in map {
+            store(&mut root, span, key.to_index(), Some(Arc::new(value)));
+        }
+
+        PersistentMap {
+            root,
+            len,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a PersistentMap<K, V>
+where
+    K: KeyIndex,
+{
+    type Item = (K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the entries of a [`PersistentMap`].
+///
+/// This is created by [`PersistentMap::iter`].
+pub struct Iter<'a, K, V>
+where
+    K: 'a + Key,
+    V: 'a,
+{
+    branches: Vec<Branch<'a, V>>,
+    /// The leaf being visited, with the first index in it.
+    values: (usize, Slots<'a, Option<Arc<V>>>),
+    len: usize,
+    _marker: PhantomData<K>,
+}
+
+type Slots<'a, T> = iter::Enumerate<slice::Iter<'a, T>>;
+
+/// A branch being visited, with the first index below it and the number of
+/// indexes below each of its slots.
+type Branch<'a, V> = (usize, usize, Slots<'a, Option<Arc<Node<V>>>>);
+
+impl<'a, K, V> Iterator for Iter<'a, K, V>
+where
+    K: 'a + KeyIndex,
+    V: 'a,
+{
+    type Item = (K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (start, values) = &mut self.values;
+
+            if let Some((slot, value)) = values.next() {
+                if let Some(value) = value {
+                    self.len -= 1;
+                    return Some((key(*start + slot), value));
+                }
+
+                continue;
+            }
+
+            let (start, span, children) = self.branches.last_mut()?;
+
+            let Some((slot, child)) = children.next() else {
+                self.branches.pop();
+                continue;
+            };
+
+            let (start, span) = (*start + slot * *span, *span);
+
+            match child.as_deref() {
+                Some(Node::Branch(children)) => {
+                    self.branches
+                        .push((start, span / WIDTH, children.iter().enumerate()));
+                }
+                Some(Node::Leaf(values)) => {
+                    self.values = (start, values.iter().enumerate());
+                }
+                None => {}
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V>
+where
+    K: 'a + KeyIndex,
+    V: 'a,
+{
+}
+
+/// The number of indexes below the root of the tree, which is the smallest
+/// power of [`WIDTH`] that fits every key.
+#[inline]
+fn root_span<K>() -> usize
+where
+    K: KeyIndex,
+{
+    let mut span = WIDTH;
+
+    while span < K::LEN {
+        span *= WIDTH;
+    }
+
+    span
+}
+
+/// Get the key stored at the given index of the tree.
+#[inline]
+fn key<K>(index: usize) -> K
+where
+    K: KeyIndex,
+{
+    match K::from_index(index) {
+        Some(key) => key,
+        None => panic!("index {index} doesn't correspond to a key"),
+    }
+}
+
+/// Store `value` at the given index below a node spanning `span` indexes.
+///
+/// Every chunk along the path which is shared with another map is copied,
+/// while chunks which are only used by this map are modified in place. Chunks
+/// which become empty are removed.
+fn store<V>(node: &mut Option<Arc<Node<V>>>, span: usize, index: usize, value: Option<Arc<V>>) {
+    let span = span / WIDTH;
+    let slot = index / span % WIDTH;
+
+    let chunk = node.get_or_insert_with(|| {
+        Arc::new(if span == 1 {
+            Node::Leaf(array::from_fn(|_| None))
+        } else {
+            Node::Branch(array::from_fn(|_| None))
+        })
+    });
+
+    let is_empty = match Arc::make_mut(chunk) {
+        Node::Branch(children) => {
+            store(&mut children[slot], span, index, value);
+            children.iter().all(Option::is_none)
+        }
+        Node::Leaf(values) => {
+            values[slot] = value;
+            values.iter().all(Option::is_none)
+        }
+    };
+
+    if is_empty {
+        *node = None;
+    }
+}
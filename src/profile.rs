@@ -0,0 +1,805 @@
+//! Storage profiles, which let the same key be stored with different
+//! trade-offs in different parts of a program.
+//!
+//! The storage generated for a key is the *compact* profile. It packs the
+//! slots of every variant as tightly as the layout of `V` allows, making use
+//! of niches where possible, so that maps and sets stay small.
+//!
+//! Wrapping a key in [`Fast`] selects the *fast* profile instead. It uses the
+//! same storage, but padded and aligned to a cache line, so that maps which
+//! are updated from hot paths don't share cache lines with neighbouring data.
+//! Operations which depend on the storage of the wrapped key, like
+//! [`Map::drain`][crate::Map::drain] or [`Map::range`][crate::Map::range], are
+//! forwarded to it, so they're available with either profile. The profile is
+//! selected through the key type, so a single key can be used with both
+//! profiles at once:
+//!
+//! ```
+//! use fixed_map::profile::Fast;
+//! use fixed_map::{Key, Map};
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq, Key)]
+//! enum MyKey {
+//!     First,
+//!     Second,
+//! }
+//!
+//! let mut compact: Map<MyKey, u8> = Map::new();
+//! compact.insert(MyKey::First, 1);
+//!
+//! let mut fast: Map<Fast<MyKey>, u8> = Map::new();
+//! fast.insert(Fast(MyKey::First), 1);
+//!
+//! assert_eq!(core::mem::size_of_val(&compact), 4);
+//! assert_eq!(core::mem::align_of_val(&fast), 64);
+//! assert!(fast.keys().map(Fast::into_inner).eq(compact.keys()));
+//! ```
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter;
+
+use crate::layout::Branch;
+use crate::map::{
+    ClassStorage, DrainStorage, EntriesStorage, Entry, ExtractIfCursor, ExtractIfStorage,
+    MapStorage, OccupiedEntry, SliceStorage, Stats, StatsStorage, VacantEntry,
+};
+use crate::raw::KeyIndex;
+use crate::schema::Variant;
+use crate::set::SetStorage;
+use crate::Key;
+
+type Inner<K, V> = <K as Key>::MapStorage<V>;
+
+/// A key which is stored using the fast profile, see the [module
+/// documentation][self].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::profile::Fast;
+/// use fixed_map::{Key, Set};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First,
+///     Second(bool),
+/// }
+///
+/// let mut set = Set::new();
+/// set.insert(Fast(MyKey::Second(true)));
+///
+/// assert!(set.contains(Fast(MyKey::Second(true))));
+/// assert!(!set.contains(Fast(MyKey::First)));
+/// assert_eq!(core::mem::align_of_val(&set), 64);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Fast<K>(pub K);
+
+impl<K> Fast<K> {
+    /// Get the wrapped key.
+    #[inline]
+    pub fn into_inner(self) -> K {
+        self.0
+    }
+}
+
+impl<K> From<K> for Fast<K> {
+    #[inline]
+    fn from(key: K) -> Self {
+        Fast(key)
+    }
+}
+
+impl<K> Key for Fast<K>
+where
+    K: Key,
+{
+    type MapStorage<V> = FastMapStorage<K, V>;
+    type SetStorage = FastSetStorage<K>;
+    const KEY_SCHEMA_HASH: u64 = K::KEY_SCHEMA_HASH;
+    const CAPACITY: Option<usize> = K::CAPACITY;
+    const KEY_VARIANTS: &'static [Variant] = K::KEY_VARIANTS;
+
+    #[inline]
+    fn variant_name(self) -> &'static str {
+        self.0.variant_name()
+    }
+
+    #[inline]
+    fn fmt_key(self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_key(f)
+    }
+
+    #[inline]
+    fn class_index(self) -> usize {
+        self.0.class_index()
+    }
+}
+
+impl<K> KeyIndex for Fast<K>
+where
+    K: KeyIndex,
+{
+    const LEN: usize = K::LEN;
+
+    #[inline]
+    fn to_index(self) -> usize {
+        self.0.to_index()
+    }
+
+    #[inline]
+    fn from_index(index: usize) -> Option<Self> {
+        K::from_index(index).map(Fast)
+    }
+}
+
+/// [`MapStorage`] for keys using the [`Fast`] profile, which aligns the
+/// storage of the wrapped key to a cache line.
+#[repr(align(64))]
+pub struct FastMapStorage<K, V>
+where
+    K: Key,
+{
+    inner: K::MapStorage<V>,
+}
+
+impl<K, V> Clone for FastMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K, V> Copy for FastMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: Copy,
+{
+}
+
+impl<K, V> PartialEq for FastMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<K, V> Eq for FastMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: Eq,
+{
+}
+
+impl<K, V> Hash for FastMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: Hash,
+{
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.inner.hash(state);
+    }
+}
+
+impl<K, V> PartialOrd for FastMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.inner.partial_cmp(&other.inner)
+    }
+}
+
+impl<K, V> Ord for FastMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: Ord,
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.inner.cmp(&other.inner)
+    }
+}
+
+impl<K, V> MapStorage<Fast<K>, V> for FastMapStorage<K, V>
+where
+    K: Key,
+{
+    type Iter<'this>
+        = iter::Map<
+        <Inner<K, V> as MapStorage<K, V>>::Iter<'this>,
+        fn((K, &'this V)) -> (Fast<K>, &'this V),
+    >
+    where
+        K: 'this,
+        V: 'this;
+    type Keys<'this>
+        = iter::Map<<Inner<K, V> as MapStorage<K, V>>::Keys<'this>, fn(K) -> Fast<K>>
+    where
+        K: 'this,
+        V: 'this;
+    type Values<'this>
+        = <Inner<K, V> as MapStorage<K, V>>::Values<'this>
+    where
+        K: 'this,
+        V: 'this;
+    type IterMut<'this>
+        = iter::Map<
+        <Inner<K, V> as MapStorage<K, V>>::IterMut<'this>,
+        fn((K, &'this mut V)) -> (Fast<K>, &'this mut V),
+    >
+    where
+        K: 'this,
+        V: 'this;
+    type ValuesMut<'this>
+        = <Inner<K, V> as MapStorage<K, V>>::ValuesMut<'this>
+    where
+        K: 'this,
+        V: 'this;
+    type IntoIter =
+        iter::Map<<Inner<K, V> as MapStorage<K, V>>::IntoIter, fn((K, V)) -> (Fast<K>, V)>;
+    type Occupied<'this>
+        = FastOccupiedEntry<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+    type Vacant<'this>
+        = FastVacantEntry<'this, K, V>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn empty() -> Self {
+        Self {
+            inner: K::MapStorage::empty(),
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    #[inline]
+    fn insert(&mut self, key: Fast<K>, value: V) -> Option<V> {
+        self.inner.insert(key.0, value)
+    }
+
+    #[inline]
+    fn contains_key(&self, key: Fast<K>) -> bool {
+        self.inner.contains_key(key.0)
+    }
+
+    #[inline]
+    fn get(&self, key: Fast<K>) -> Option<&V> {
+        self.inner.get(key.0)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: Fast<K>) -> Option<&mut V> {
+        self.inner.get_mut(key.0)
+    }
+
+    #[inline]
+    fn remove(&mut self, key: Fast<K>) -> Option<V> {
+        self.inner.remove(key.0)
+    }
+
+    #[inline]
+    fn remove_and_shrink(&mut self, key: Fast<K>) -> Option<V> {
+        self.inner.remove_and_shrink(key.0)
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, mut func: F)
+    where
+        F: FnMut(Fast<K>, &mut V) -> bool,
+    {
+        self.inner.retain(|k, v| func(Fast(k), v));
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter<'_> {
+        self.inner.iter().map(|(k, v)| (Fast(k), v))
+    }
+
+    #[inline]
+    fn keys(&self) -> Self::Keys<'_> {
+        self.inner.keys().map(Fast)
+    }
+
+    #[inline]
+    fn values(&self) -> Self::Values<'_> {
+        self.inner.values()
+    }
+
+    #[inline]
+    fn first_key_value(&self) -> Option<(Fast<K>, &V)> {
+        let (k, v) = self.inner.first_key_value()?;
+        Some((Fast(k), v))
+    }
+
+    #[inline]
+    fn last_key_value(&self) -> Option<(Fast<K>, &V)> {
+        let (k, v) = self.inner.last_key_value()?;
+        Some((Fast(k), v))
+    }
+
+    #[inline]
+    fn find_map<B, F>(&self, mut func: F) -> Option<B>
+    where
+        F: FnMut(Fast<K>, &V) -> Option<B>,
+    {
+        self.inner.find_map(|k, v| func(Fast(k), v))
+    }
+
+    #[inline]
+    fn fill_fixed<F>(&mut self, func: F)
+    where
+        F: FnMut() -> V,
+    {
+        self.inner.fill_fixed(func);
+    }
+
+    #[inline]
+    fn layout<F>(func: F)
+    where
+        F: FnMut(Branch),
+    {
+        <K::MapStorage<V> as MapStorage<K, V>>::layout(func);
+    }
+
+    #[inline]
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.inner.iter_mut().map(|(k, v)| (Fast(k), v))
+    }
+
+    #[inline]
+    fn values_mut(&mut self) -> Self::ValuesMut<'_> {
+        self.inner.values_mut()
+    }
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter().map(|(k, v)| (Fast(k), v))
+    }
+
+    #[inline]
+    fn entry(&mut self, key: Fast<K>) -> Entry<'_, Self, Fast<K>, V> {
+        match self.inner.entry(key.0) {
+            Entry::Occupied(inner) => Entry::Occupied(FastOccupiedEntry { key, inner }),
+            Entry::Vacant(inner) => Entry::Vacant(FastVacantEntry { key, inner }),
+        }
+    }
+}
+
+impl<K, V> EntriesStorage<Fast<K>, V> for FastMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: EntriesStorage<K, V>,
+{
+    type EntriesMut<'this>
+        = iter::Map<
+        <Inner<K, V> as EntriesStorage<K, V>>::EntriesMut<'this>,
+        fn(Entry<'this, Inner<K, V>, K, V>) -> Entry<'this, Self, Fast<K>, V>,
+    >
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn entries_mut(&mut self) -> Self::EntriesMut<'_> {
+        let map: fn(_) -> _ = |entry| match entry {
+            Entry::Occupied(inner) => Entry::Occupied(FastOccupiedEntry {
+                key: Fast(OccupiedEntry::key(&inner)),
+                inner,
+            }),
+            Entry::Vacant(inner) => Entry::Vacant(FastVacantEntry {
+                key: Fast(VacantEntry::key(&inner)),
+                inner,
+            }),
+        };
+
+        self.inner.entries_mut().map(map)
+    }
+}
+
+impl<K, V> SliceStorage<Fast<K>, V> for FastMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: SliceStorage<K, V>,
+{
+    #[inline]
+    fn as_slice(&self) -> &[Option<V>] {
+        self.inner.as_slice()
+    }
+
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [Option<V>] {
+        self.inner.as_mut_slice()
+    }
+}
+
+impl<K, V> ClassStorage<Fast<K>, V> for FastMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: ClassStorage<K, V>,
+{
+    type ClassIter<'this>
+        = iter::Map<
+        <Inner<K, V> as ClassStorage<K, V>>::ClassIter<'this>,
+        fn((K, &'this V)) -> (Fast<K>, &'this V),
+    >
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn class_len(&self, class: usize) -> usize {
+        self.inner.class_len(class)
+    }
+
+    #[inline]
+    fn clear_class(&mut self, class: usize) {
+        self.inner.clear_class(class);
+    }
+
+    #[inline]
+    fn iter_class(&self, class: usize) -> Self::ClassIter<'_> {
+        self.inner.iter_class(class).map(|(k, v)| (Fast(k), v))
+    }
+}
+
+impl<K, V> StatsStorage<Fast<K>, V> for FastMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: StatsStorage<K, V>,
+{
+    #[inline]
+    fn stats(&self) -> Stats {
+        self.inner.stats()
+    }
+}
+
+impl<K, V> DrainStorage<Fast<K>, V> for FastMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: DrainStorage<K, V>,
+{
+    type Drain<'this>
+        = iter::Map<<Inner<K, V> as DrainStorage<K, V>>::Drain<'this>, fn((K, V)) -> (Fast<K>, V)>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn drain(&mut self) -> Self::Drain<'_> {
+        self.inner.drain().map(|(k, v)| (Fast(k), v))
+    }
+}
+
+impl<K, V> ExtractIfStorage<Fast<K>, V> for FastMapStorage<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: ExtractIfStorage<K, V>,
+{
+    type ExtractIf<'this>
+        = FastExtractIf<<Inner<K, V> as ExtractIfStorage<K, V>>::ExtractIf<'this>>
+    where
+        K: 'this,
+        V: 'this;
+
+    #[inline]
+    fn extract_if(&mut self) -> Self::ExtractIf<'_> {
+        FastExtractIf {
+            inner: self.inner.extract_if(),
+        }
+    }
+}
+
+/// The [`ExtractIfCursor`] of [`FastMapStorage`].
+pub struct FastExtractIf<C> {
+    inner: C,
+}
+
+impl<C, K, V> ExtractIfCursor<Fast<K>, V> for FastExtractIf<C>
+where
+    C: ExtractIfCursor<K, V>,
+{
+    #[inline]
+    fn next_if<F>(&mut self, filter: &mut F) -> Option<(Fast<K>, V)>
+    where
+        F: ?Sized + FnMut(Fast<K>, &mut V) -> bool,
+    {
+        let (k, v) = self.inner.next_if(&mut |k, v: &mut V| filter(Fast(k), v))?;
+        Some((Fast(k), v))
+    }
+}
+
+/// An occupied entry of [`FastMapStorage`].
+pub struct FastOccupiedEntry<'a, K, V>
+where
+    K: 'a + Key,
+    V: 'a,
+{
+    key: Fast<K>,
+    inner: <Inner<K, V> as MapStorage<K, V>>::Occupied<'a>,
+}
+
+impl<'a, K, V> OccupiedEntry<'a, Fast<K>, V> for FastOccupiedEntry<'a, K, V>
+where
+    K: Key,
+{
+    #[inline]
+    fn key(&self) -> Fast<K> {
+        self.key
+    }
+
+    #[inline]
+    fn key_ref(&self) -> &Fast<K> {
+        &self.key
+    }
+
+    #[inline]
+    fn get(&self) -> &V {
+        self.inner.get()
+    }
+
+    #[inline]
+    fn get_mut(&mut self) -> &mut V {
+        self.inner.get_mut()
+    }
+
+    #[inline]
+    fn into_mut(self) -> &'a mut V {
+        self.inner.into_mut()
+    }
+
+    #[inline]
+    fn insert(&mut self, value: V) -> V {
+        self.inner.insert(value)
+    }
+
+    #[inline]
+    fn remove(self) -> V {
+        self.inner.remove()
+    }
+}
+
+/// A vacant entry of [`FastMapStorage`].
+pub struct FastVacantEntry<'a, K, V>
+where
+    K: 'a + Key,
+    V: 'a,
+{
+    key: Fast<K>,
+    inner: <Inner<K, V> as MapStorage<K, V>>::Vacant<'a>,
+}
+
+impl<'a, K, V> VacantEntry<'a, Fast<K>, V> for FastVacantEntry<'a, K, V>
+where
+    K: Key,
+{
+    #[inline]
+    fn key(&self) -> Fast<K> {
+        self.key
+    }
+
+    #[inline]
+    fn key_ref(&self) -> &Fast<K> {
+        &self.key
+    }
+
+    #[inline]
+    fn insert(self, value: V) -> &'a mut V {
+        self.inner.insert(value)
+    }
+}
+
+/// [`SetStorage`] for keys using the [`Fast`] profile, which aligns the
+/// storage of the wrapped key to a cache line.
+#[repr(align(64))]
+pub struct FastSetStorage<K>
+where
+    K: Key,
+{
+    inner: K::SetStorage,
+}
+
+impl<K> Clone for FastSetStorage<K>
+where
+    K: Key,
+    K::SetStorage: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K> Copy for FastSetStorage<K>
+where
+    K: Key,
+    K::SetStorage: Copy,
+{
+}
+
+impl<K> PartialEq for FastSetStorage<K>
+where
+    K: Key,
+    K::SetStorage: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<K> Eq for FastSetStorage<K>
+where
+    K: Key,
+    K::SetStorage: Eq,
+{
+}
+
+impl<K> Hash for FastSetStorage<K>
+where
+    K: Key,
+    K::SetStorage: Hash,
+{
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.inner.hash(state);
+    }
+}
+
+impl<K> PartialOrd for FastSetStorage<K>
+where
+    K: Key,
+    K::SetStorage: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.inner.partial_cmp(&other.inner)
+    }
+}
+
+impl<K> Ord for FastSetStorage<K>
+where
+    K: Key,
+    K::SetStorage: Ord,
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.inner.cmp(&other.inner)
+    }
+}
+
+impl<K> SetStorage<Fast<K>> for FastSetStorage<K>
+where
+    K: Key,
+{
+    type Iter<'this>
+        = iter::Map<<K::SetStorage as SetStorage<K>>::Iter<'this>, fn(K) -> Fast<K>>
+    where
+        K: 'this;
+    type IntoIter = iter::Map<<K::SetStorage as SetStorage<K>>::IntoIter, fn(K) -> Fast<K>>;
+
+    #[inline]
+    fn empty() -> Self {
+        Self {
+            inner: K::SetStorage::empty(),
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    #[inline]
+    fn insert(&mut self, value: Fast<K>) -> bool {
+        self.inner.insert(value.0)
+    }
+
+    #[inline]
+    fn contains(&self, value: Fast<K>) -> bool {
+        self.inner.contains(value.0)
+    }
+
+    #[inline]
+    fn remove(&mut self, value: Fast<K>) -> bool {
+        self.inner.remove(value.0)
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Fast<K>) -> bool,
+    {
+        self.inner.retain(|value| f(Fast(value)));
+    }
+
+    #[inline]
+    fn find<F>(&self, mut f: F) -> Option<Fast<K>>
+    where
+        F: FnMut(Fast<K>) -> bool,
+    {
+        self.inner.find(|value| f(Fast(value))).map(Fast)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    #[inline]
+    fn union_with(&mut self, other: &Self) {
+        self.inner.union_with(&other.inner);
+    }
+
+    #[inline]
+    fn intersect_with(&mut self, other: &Self) {
+        self.inner.intersect_with(&other.inner);
+    }
+
+    #[inline]
+    fn subtract(&mut self, other: &Self) {
+        self.inner.subtract(&other.inner);
+    }
+
+    #[inline]
+    fn intersection_len(&self, other: &Self) -> usize {
+        self.inner.intersection_len(&other.inner)
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter<'_> {
+        self.inner.iter().map(Fast)
+    }
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter().map(Fast)
+    }
+}
@@ -2,21 +2,398 @@
 //!
 //! This can be useful to implement more efficient serialization, since it might
 //! provide access to smaller primitive values.
+//!
+//! # Bit order
+//!
+//! The raw value of a [`Set`] using `#[key(bitset)]` storage has the bit
+//! `1 << n` set if the variant at index `n` is present, where `n` counts the
+//! variants of the key in declaration order starting at zero. Explicit
+//! discriminants do not affect the bit order. Variants which are disabled
+//! through `#[cfg(...)]` are not counted, so enabling them shifts the bits of
+//! any variants declared after them. This is the case even if they are listed
+//! in `#[key(variants(...))]`, since the reserved slots in
+//! [`Key::KEY_VARIANTS`] are not used by the bit order.
+//!
+//! This mapping is guaranteed across versions of this crate, so raw values can
+//! be stored on disk or shared across processes as long as the variants of the
+//! key are not reordered, removed or inserted anywhere other than at the end.
+//! The index of a variant is available through [`BitIndex`], and
+//! [`Key::KEY_SCHEMA_HASH`] can be used to detect incompatible changes.
+//!
+//! [`Set`]: crate::Set
+//! [`Key::KEY_SCHEMA_HASH`]: crate::Key::KEY_SCHEMA_HASH
+//!
+//! # Examples
+//!
+//! ```
+//! use fixed_map::{Key, Set};
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq, Key)]
+//! #[key(bitset)]
+//! enum Direction {
+//!     North,
+//!     East,
+//!     South = 10,
+//!     West,
+//! }
+//!
+//! let set = Set::from([Direction::North, Direction::South]);
+//! assert_eq!(set.as_raw(), 0b0101);
+//! ```
+
+use core::fmt;
+use core::ops::{self, Bound, RangeBounds};
+
+use crate::Key;
 
 /// Trait implemented for storage which can be easily converted to and from a
 /// raw value.
 ///
 /// This is implemented for [`SetStorage`] when the `#[key(bitset)]` attribute
-/// is present.
+/// is present. The trait is sealed, so it can only be implemented by the
+/// [`Key`][key-derive] derive.
 ///
 /// [`SetStorage`]: crate::set::SetStorage
-pub trait RawStorage: Sized {
+/// [key-derive]: derive@crate::Key
+pub trait RawStorage: Sized + crate::macro_support::__RawStorageSealed {
     /// The backing raw value.
     type Value;
 
+    /// The raw value with the bit of every variant of the key set.
+    const MASK: Self::Value;
+
     /// Get the raw value of the storage.
     fn as_raw(&self) -> Self::Value;
 
+    /// Borrow the raw value of the storage.
+    fn as_raw_ref(&self) -> &Self::Value;
+
     /// Build storage from raw storage.
     fn from_raw(raw: Self::Value) -> Self;
 }
+
+/// A raw value which can be split into 64-bit words.
+///
+/// This is implemented for the unsigned integers used as the
+/// [`RawStorage::Value`] of `#[key(bitset)]` storage, and allows the raw value
+/// of a set to be handed to code which operates on bits in bulk through
+/// [`Set::to_words`] and [`Set::from_words`]. Bit `n` of the raw value is bit
+/// `n % 64` of word `n / 64`.
+///
+/// [`Set::to_words`]: crate::Set::to_words
+/// [`Set::from_words`]: crate::Set::from_words
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::raw::Words;
+///
+/// assert_eq!(0b101u8.to_words(), [0b101]);
+/// assert_eq!((1u128 << 64 | 1).to_words(), [1, 1]);
+/// assert_eq!(u128::from_words(&[1, 1]), 1 << 64 | 1);
+/// assert_eq!(u16::from_words(&[]), 0);
+/// ```
+pub trait Words: Copy + ops::BitAnd<Output = Self> {
+    /// The words making up the value, least significant word first.
+    type Array: AsRef<[u64]> + AsMut<[u64]> + Copy;
+
+    /// Split the value into words.
+    fn to_words(self) -> Self::Array;
+
+    /// Construct the value from words, least significant word first.
+    ///
+    /// Missing words are treated as zero, while words and bits which don't
+    /// fit in the value are ignored.
+    fn from_words(words: &[u64]) -> Self;
+}
+
+macro_rules! single_word {
+    ($($ty:ty),*) => {
+        $(
+            impl Words for $ty {
+                type Array = [u64; 1];
+
+                #[inline]
+                fn to_words(self) -> Self::Array {
+                    [u64::from(self)]
+                }
+
+                #[inline]
+                #[allow(clippy::cast_possible_truncation)]
+                fn from_words(words: &[u64]) -> Self {
+                    words.first().map_or(0, |&word| word as $ty)
+                }
+            }
+        )*
+    };
+}
+
+single_word!(u8, u16, u32, u64);
+
+impl Words for u128 {
+    type Array = [u64; 2];
+
+    #[inline]
+    #[allow(clippy::cast_possible_truncation)]
+    fn to_words(self) -> Self::Array {
+        [self as u64, (self >> 64) as u64]
+    }
+
+    #[inline]
+    fn from_words(words: &[u64]) -> Self {
+        let low = words.first().map_or(0, |&word| u128::from(word));
+        let high = words.get(1).map_or(0, |&word| u128::from(word));
+        low | high << 64
+    }
+}
+
+/// A raw value which is laid out in memory as 64-bit words, least significant
+/// word first, and which can therefore be borrowed as a slice of them through
+/// [`Set::as_words`].
+///
+/// This is implemented for `u64`, and for `u128` on little endian targets.
+/// Smaller raw values can only be split into words through
+/// [`Set::to_words`].
+///
+/// [`Set::as_words`]: crate::Set::as_words
+/// [`Set::to_words`]: crate::Set::to_words
+pub trait AsWords: Words {
+    /// Borrow the value as words.
+    fn as_words(&self) -> &[u64];
+}
+
+impl AsWords for u64 {
+    #[inline]
+    fn as_words(&self) -> &[u64] {
+        core::slice::from_ref(self)
+    }
+}
+
+#[cfg(target_endian = "little")]
+const _: () = assert!(core::mem::align_of::<u128>() >= core::mem::align_of::<u64>());
+
+#[cfg(target_endian = "little")]
+impl AsWords for u128 {
+    #[inline]
+    #[allow(unsafe_code)]
+    fn as_words(&self) -> &[u64] {
+        // SAFETY: A `u128` has the size of two `u64` and at least their
+        // alignment, and on little endian targets its low word comes first.
+        unsafe { &*(self as *const u128).cast::<[u64; 2]>() }
+    }
+}
+
+/// The stable bit index of a key which uses `#[key(bitset)]` storage.
+///
+/// This is implemented automatically by the [`Key`][key-derive] derive when
+/// the `#[key(bitset)]` attribute is present. See the [module level
+/// documentation][self#bit-order] for the guarantees of the bit order.
+///
+/// [key-derive]: derive@crate::Key
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::raw::BitIndex;
+/// use fixed_map::{Key, Set};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(bitset)]
+/// enum Direction {
+///     North,
+///     East,
+///     South,
+///     West,
+/// }
+///
+/// assert_eq!(Direction::South.to_bit_index(), 2);
+/// assert_eq!(Direction::from_bit_index(3), Some(Direction::West));
+/// assert_eq!(Direction::from_bit_index(4), None);
+///
+/// let set = Set::from([Direction::East, Direction::West]);
+/// let expected = (1 << Direction::East.to_bit_index()) | (1 << Direction::West.to_bit_index());
+/// assert_eq!(set.as_raw(), expected);
+/// ```
+pub trait BitIndex: Key {
+    /// Get the index of the bit which represents this key in the raw value of
+    /// a [`Set`][crate::Set].
+    fn to_bit_index(self) -> u32;
+
+    /// Get the key represented by the bit at the given index, or `None` if no
+    /// enabled variant is represented by it.
+    fn from_bit_index(index: u32) -> Option<Self>;
+}
+
+/// The dense index of a key whose [`Map`] storage is laid out as an array with
+/// one slot per variant.
+///
+/// This is implemented automatically by the [`Key`][key-derive] derive for
+/// enums where every variant is a unit variant. The index of a variant is its
+/// position in declaration order, which is the slot it occupies in the storage
+/// of a [`Map`]. Arrays or bitsets with [`KeyIndex::LEN`] elements can
+/// therefore be kept aligned with the layout of a map, see
+/// [`Map::iter_indexed`].
+///
+/// [`Map`]: crate::Map
+/// [`Map::iter_indexed`]: crate::Map::iter_indexed
+/// [key-derive]: derive@crate::Key
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::raw::KeyIndex;
+/// use fixed_map::Key;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum Direction {
+///     North,
+///     East,
+///     South,
+///     West,
+/// }
+///
+/// assert_eq!(Direction::LEN, 4);
+/// assert_eq!(Direction::South.to_index(), 2);
+/// assert_eq!(Direction::from_index(3), Some(Direction::West));
+/// assert_eq!(Direction::from_index(4), None);
+/// ```
+pub trait KeyIndex: Key {
+    /// The number of slots in the storage of a [`Map`][crate::Map].
+    const LEN: usize;
+
+    /// Get the index of the slot which stores this key.
+    fn to_index(self) -> usize;
+
+    /// Get the key stored in the slot at the given index, or `None` if no
+    /// enabled variant is stored in it.
+    fn from_index(index: usize) -> Option<Self>;
+}
+
+/// The [`KeyIndex`] of a key as a `u16`, which is used by `#[key(sparse)]`
+/// storage to keep a compact sorted table of the slots which are present
+/// instead of whole keys.
+///
+/// This is implemented automatically by the [`Key`][key-derive] derive for
+/// enums marked with `#[key(sparse)]`, which can have at most 65536 variants.
+/// The sparse index of a variant is always the same as its
+/// [`KeyIndex::to_index`].
+///
+/// [key-derive]: derive@crate::Key
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use fixed_map::raw::{KeyIndex, SparseIndex};
+/// use fixed_map::Key;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(sparse)]
+/// enum Opcode {
+///     Nop,
+///     Load,
+///     Store,
+/// }
+///
+/// assert_eq!(Opcode::Store.to_sparse_index(), 2);
+/// assert_eq!(usize::from(Opcode::Store.to_sparse_index()), Opcode::Store.to_index());
+/// assert_eq!(Opcode::from_sparse_index(1), Some(Opcode::Load));
+/// assert_eq!(Opcode::from_sparse_index(3), None);
+/// # }
+/// ```
+pub trait SparseIndex: KeyIndex {
+    /// Get the index of the slot which stores this key.
+    fn to_sparse_index(self) -> u16;
+
+    /// Get the key stored in the slot at the given index, or `None` if no
+    /// variant is stored in it.
+    fn from_sparse_index(index: u16) -> Option<Self>;
+}
+
+/// Resolve a range of keys into the range of slots storing them, or `None` if
+/// the start of the range is declared after its end.
+pub(crate) fn index_range<K, R>(range: &R) -> Option<ops::Range<usize>>
+where
+    K: KeyIndex,
+    R: RangeBounds<K>,
+{
+    let start = match range.start_bound() {
+        Bound::Included(key) => key.to_index(),
+        Bound::Excluded(key) => key.to_index() + 1,
+        Bound::Unbounded => 0,
+    };
+
+    let end = match range.end_bound() {
+        Bound::Included(key) => key.to_index() + 1,
+        Bound::Excluded(key) => key.to_index(),
+        Bound::Unbounded => K::LEN,
+    };
+
+    (start <= end).then_some(start..end)
+}
+
+/// The error returned by the `TryFrom` implementation generated through
+/// `#[key(primitive = T)]` when a primitive value doesn't correspond to any
+/// variant of the key.
+///
+/// The value of a variant is its index in declaration order, which matches
+/// the order of storage and of [`BitIndex`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::raw::InvalidPrimitive;
+/// use fixed_map::Key;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(primitive = u8)]
+/// enum Direction {
+///     North,
+///     East,
+///     South,
+///     West,
+/// }
+///
+/// assert_eq!(u8::from(Direction::South), 2);
+/// assert_eq!(Direction::try_from(3), Ok(Direction::West));
+///
+/// let error = Direction::try_from(4).unwrap_err();
+/// assert_eq!(error, InvalidPrimitive::new(4));
+/// assert_eq!(error.value(), 4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidPrimitive<T> {
+    value: T,
+}
+
+impl<T> InvalidPrimitive<T> {
+    /// Construct a new error for the given primitive value.
+    #[inline]
+    #[must_use]
+    pub const fn new(value: T) -> Self {
+        Self { value }
+    }
+
+    /// The primitive value which doesn't correspond to a variant.
+    #[inline]
+    pub fn value(self) -> T {
+        self.value
+    }
+}
+
+impl<T> fmt::Display for InvalidPrimitive<T>
+where
+    T: fmt::Display,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} does not correspond to a variant of the key",
+            self.value
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for InvalidPrimitive<T> where T: fmt::Debug + fmt::Display {}
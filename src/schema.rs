@@ -0,0 +1,441 @@
+//! Helpers for versioning serialized data by the schema of its key.
+//!
+//! Every [`Key`] has a [`KEY_SCHEMA_HASH`] which changes whenever variants
+//! are added, removed, renamed or reordered. Embedding it alongside serialized
+//! data makes it possible to detect that the data was produced with a
+//! different version of the key, rather than silently decoding values into
+//! the wrong variants.
+//!
+//! Since [`KEY_SCHEMA_HASH`] only covers variants which are enabled in the
+//! current configuration, keys with variants behind `#[cfg(...)]` have a
+//! different hash depending on which features are enabled. Where data is
+//! exchanged between builds with different features, the position of each
+//! variant can instead be taken from [`KEY_VARIANTS`] through [`slot`]. By
+//! listing every variant with `#[key(variants(...))]`, disabled variants are
+//! kept as reserved slots, and [`layout_hash`] can be used to version the
+//! layout.
+//!
+//! [`KEY_SCHEMA_HASH`]: Key::KEY_SCHEMA_HASH
+//! [`KEY_VARIANTS`]: Key::KEY_VARIANTS
+//!
+//! # Examples
+//!
+//! ```
+//! use fixed_map::{schema, Key};
+//!
+//! #[derive(Clone, Copy, Key)]
+//! enum MyKey {
+//!     First,
+//!     Second,
+//! }
+//!
+//! let stored = MyKey::KEY_SCHEMA_HASH;
+//! assert!(schema::check::<MyKey>(stored).is_ok());
+//! assert!(schema::check::<MyKey>(stored ^ 1).is_err());
+//! ```
+
+use core::fmt;
+
+use crate::Key;
+
+/// A `const` hasher used to calculate [`Key::KEY_SCHEMA_HASH`].
+///
+/// This implements 64-bit FNV-1a, and is used by `derive(Key)` to hash the
+/// names of the variants of a key in declaration order.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::schema::Hasher;
+///
+/// const A: u64 = Hasher::new().write_str("First").finish();
+/// const B: u64 = Hasher::new().write_str("Second").finish();
+/// assert_ne!(A, B);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Hasher {
+    state: u64,
+}
+
+impl Hasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    /// Construct a new hasher.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            state: Self::OFFSET_BASIS,
+        }
+    }
+
+    /// Write a string to the hasher, followed by a terminator so that
+    /// consecutive strings can't be confused with each other.
+    #[inline]
+    #[must_use]
+    pub const fn write_str(self, string: &str) -> Self {
+        self.write_bytes(string.as_bytes()).write_byte(0xff)
+    }
+
+    /// Write a `u64` to the hasher, such as the schema hash of a nested key.
+    #[inline]
+    #[must_use]
+    pub const fn write_u64(self, value: u64) -> Self {
+        self.write_bytes(&value.to_le_bytes())
+    }
+
+    /// Get the hash of everything written to the hasher.
+    #[inline]
+    #[must_use]
+    pub const fn finish(self) -> u64 {
+        self.state
+    }
+
+    #[inline]
+    const fn write_bytes(mut self, bytes: &[u8]) -> Self {
+        let mut n = 0;
+
+        while n < bytes.len() {
+            self = self.write_byte(bytes[n]);
+            n += 1;
+        }
+
+        self
+    }
+
+    #[inline]
+    const fn write_byte(self, byte: u8) -> Self {
+        Self {
+            state: (self.state ^ byte as u64).wrapping_mul(Self::PRIME),
+        }
+    }
+}
+
+impl Default for Hasher {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A variant declared on a key, as listed in [`Key::KEY_VARIANTS`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::schema::Variant;
+///
+/// const VARIANT: Variant = Variant::new("First", true);
+/// assert_eq!(VARIANT.name(), "First");
+/// assert!(VARIANT.is_enabled());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Variant {
+    name: &'static str,
+    enabled: bool,
+}
+
+impl Variant {
+    /// Construct a new variant.
+    #[inline]
+    #[must_use]
+    pub const fn new(name: &'static str, enabled: bool) -> Self {
+        Self { name, enabled }
+    }
+
+    /// The name of the variant.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Test if the variant is enabled in the current configuration, or if it
+    /// has been disabled through `#[cfg(...)]` and is only reserved.
+    #[inline]
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// Get the stable slot of a key, which is the position of its variant among
+/// every variant declared on the key, including disabled ones.
+///
+/// If the key lists its variants with `#[key(variants(...))]`, this doesn't
+/// change when variants before it are disabled through `#[cfg(...)]`, which
+/// makes it suitable as an offset in binary serialization formats shared
+/// between builds with different features.
+///
+/// Returns `None` if the key doesn't list its variants in
+/// [`Key::KEY_VARIANTS`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{schema, Key};
+///
+/// #[derive(Clone, Copy, Key)]
+/// #[key(variants(First, Second, Third))]
+/// enum MyKey {
+///     First,
+///     #[cfg(any())]
+///     Second,
+///     Third(bool),
+/// }
+///
+/// assert_eq!(schema::slot(MyKey::First), Some(0));
+/// assert_eq!(schema::slot(MyKey::Third(true)), Some(2));
+/// assert_eq!(schema::slot(true), None);
+/// ```
+#[inline]
+pub fn slot<K>(key: K) -> Option<usize>
+where
+    K: Key,
+{
+    let name = key.variant_name();
+    K::KEY_VARIANTS
+        .iter()
+        .position(|variant| variant.name == name)
+}
+
+/// Calculate a hash of the names of every variant declared on the key `K`,
+/// including disabled ones.
+///
+/// In contrast to [`Key::KEY_SCHEMA_HASH`] this is the same regardless of
+/// which variants are enabled, so it can be used to version data which is
+/// laid out by [`slot`]. It doesn't cover the schema of nested keys.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{schema, Key};
+///
+/// mod enabled {
+///     #[derive(Clone, Copy, fixed_map::Key)]
+///     pub enum MyKey {
+///         First,
+///         Second,
+///     }
+/// }
+///
+/// mod disabled {
+///     #[derive(Clone, Copy, fixed_map::Key)]
+///     #[key(variants(First, Second))]
+///     pub enum MyKey {
+///         First,
+///         #[cfg(any())]
+///         Second,
+///     }
+/// }
+///
+/// const HASH: u64 = schema::layout_hash::<enabled::MyKey>();
+/// assert_eq!(HASH, schema::layout_hash::<disabled::MyKey>());
+/// assert_ne!(enabled::MyKey::KEY_SCHEMA_HASH, disabled::MyKey::KEY_SCHEMA_HASH);
+/// ```
+#[must_use]
+pub const fn layout_hash<K>() -> u64
+where
+    K: Key,
+{
+    let variants = K::KEY_VARIANTS;
+    let mut hasher = Hasher::new();
+    let mut n = 0;
+
+    while n < variants.len() {
+        hasher = hasher.write_str(variants[n].name);
+        n += 1;
+    }
+
+    hasher.finish()
+}
+
+/// Error raised when a schema hash doesn't match the schema of a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Mismatch {
+    /// The schema hash of the key.
+    pub expected: u64,
+    /// The schema hash which was found.
+    pub actual: u64,
+}
+
+impl fmt::Display for Mismatch {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "key schema mismatch, expected {:#018x} but found {:#018x}",
+            self.expected, self.actual
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Mismatch {}
+
+/// Check that the given schema hash matches the schema of the key `K`.
+///
+/// # Errors
+///
+/// Errors with [`Mismatch`] if the hash doesn't match.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{schema, Key};
+///
+/// #[derive(Clone, Copy, Key)]
+/// enum MyKey {
+///     First,
+///     Second(bool),
+/// }
+///
+/// assert!(schema::check::<MyKey>(MyKey::KEY_SCHEMA_HASH).is_ok());
+///
+/// let error = schema::check::<MyKey>(42).unwrap_err();
+/// assert_eq!(error.expected, MyKey::KEY_SCHEMA_HASH);
+/// assert_eq!(error.actual, 42);
+/// ```
+#[inline]
+pub fn check<K>(hash: u64) -> Result<(), Mismatch>
+where
+    K: Key,
+{
+    if hash == K::KEY_SCHEMA_HASH {
+        Ok(())
+    } else {
+        Err(Mismatch {
+            expected: K::KEY_SCHEMA_HASH,
+            actual: hash,
+        })
+    }
+}
+
+/// A collection whose serialized form depends on the schema of its key.
+pub trait Schema {
+    /// The schema hash of the key of the collection.
+    const KEY_SCHEMA_HASH: u64;
+}
+
+impl<K, V> Schema for crate::Map<K, V>
+where
+    K: Key,
+{
+    const KEY_SCHEMA_HASH: u64 = K::KEY_SCHEMA_HASH;
+}
+
+impl<K> Schema for crate::Set<K>
+where
+    K: Key,
+{
+    const KEY_SCHEMA_HASH: u64 = K::KEY_SCHEMA_HASH;
+}
+
+/// A wrapper which serializes a collection together with the schema hash of
+/// its key, and fails to deserialize if the schema hash doesn't match.
+///
+/// This is serialized as a tuple of the schema hash and the collection.
+///
+/// This is only available when the `serde` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::schema::Versioned;
+/// use fixed_map::Set;
+///
+/// let mut set = Set::new();
+/// set.insert(true);
+///
+/// let json = serde_json::to_string(&Versioned(&set))?;
+///
+/// let Versioned(decoded) = serde_json::from_str::<Versioned<Set<bool>>>(&json)?;
+/// assert_eq!(decoded, set);
+///
+/// // Without the schema hash this would silently decode `true` as `Some(true)`.
+/// assert!(serde_json::from_str::<Versioned<Set<Option<bool>>>>(&json).is_err());
+/// # Ok::<_, serde_json::Error>(())
+/// ```
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Versioned<T>(pub T);
+
+impl<T> Schema for &T
+where
+    T: Schema,
+{
+    const KEY_SCHEMA_HASH: u64 = T::KEY_SCHEMA_HASH;
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Versioned<T>
+where
+    T: Schema + serde::Serialize,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeTuple as _;
+
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&T::KEY_SCHEMA_HASH)?;
+        tuple.serialize_element(&self.0)?;
+        tuple.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::de::Deserialize<'de> for Versioned<T>
+where
+    T: Schema + serde::de::Deserialize<'de>,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct VersionedVisitor<T>(core::marker::PhantomData<T>);
+
+        impl<'de, T> serde::de::Visitor<'de> for VersionedVisitor<T>
+        where
+            T: Schema + serde::de::Deserialize<'de>,
+        {
+            type Value = Versioned<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a key schema hash followed by a collection")
+            }
+
+            #[inline]
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                use serde::de::Error as _;
+
+                let Some(hash) = seq.next_element::<u64>()? else {
+                    return Err(A::Error::invalid_length(0, &self));
+                };
+
+                if hash != T::KEY_SCHEMA_HASH {
+                    return Err(A::Error::custom(Mismatch {
+                        expected: T::KEY_SCHEMA_HASH,
+                        actual: hash,
+                    }));
+                }
+
+                let Some(value) = seq.next_element::<T>()? else {
+                    return Err(A::Error::invalid_length(1, &self));
+                };
+
+                Ok(Versioned(value))
+            }
+        }
+
+        deserializer.deserialize_tuple(2, VersionedVisitor(core::marker::PhantomData))
+    }
+}
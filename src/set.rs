@@ -3,15 +3,19 @@
 use core::cmp::Ordering;
 use core::fmt;
 use core::hash::{Hash, Hasher};
+use core::ops::RangeBounds;
+
+mod error;
+pub use self::error::AlreadyPresent;
 
 pub mod intersection;
 pub mod storage;
 
 pub use self::intersection::Intersection;
-pub use self::storage::SetStorage;
+pub use self::storage::{ClassStorage, RangeStorage, SetStorage};
 
-use crate::raw::RawStorage;
-use crate::Key;
+use crate::raw::{index_range, AsWords, BitIndex, KeyIndex, RawStorage, Words};
+use crate::{Key, KeyGroup, Map};
 
 /// The iterator produced by [`Set::iter`].
 pub type Iter<'a, T> = <<T as Key>::SetStorage as SetStorage<T>>::Iter<'a>;
@@ -133,6 +137,27 @@ impl<T> Set<T>
 where
     T: Key,
 {
+    /// The number of distinct keys the set can hold, or `None` if the key
+    /// has dynamic storage. See [`Key::CAPACITY`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// # #[cfg(feature = "hashbrown")]
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(u32),
+    /// }
+    ///
+    /// assert_eq!(Set::<bool>::CAPACITY, Some(2));
+    /// # #[cfg(feature = "hashbrown")]
+    /// assert_eq!(Set::<MyKey>::CAPACITY, None);
+    /// ```
+    pub const CAPACITY: Option<usize> = T::CAPACITY;
+
     /// Creates an empty [`Set`].
     ///
     /// # Examples
@@ -156,6 +181,75 @@ where
         }
     }
 
+    /// Creates a [`Set`] containing every key of the given [`Map`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map, Set};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    ///     Third,
+    /// }
+    ///
+    /// let map = Map::from_iter([(MyKey::First, 1), (MyKey::Second(false), 2)]);
+    /// let set = Set::from_keys_of(&map);
+    ///
+    /// assert!(set.iter().eq([MyKey::First, MyKey::Second(false)]));
+    /// ```
+    #[inline]
+    pub fn from_keys_of<V>(map: &Map<T, V>) -> Set<T> {
+        map.keys_set()
+    }
+
+    /// Convert the set into a map with one entry for every value in the set,
+    /// where the value of each entry is computed by calling `func` with the
+    /// key.
+    ///
+    /// This is the inverse of [`Set::from_keys_of`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Map, Set};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// #[key(bitset)]
+    /// enum Flag {
+    ///     Read,
+    ///     Write,
+    ///     Execute,
+    /// }
+    ///
+    /// let set = Set::from([Flag::Read, Flag::Execute]);
+    /// let map: Map<Flag, u32> = set.into_map_with(|_| 0);
+    ///
+    /// assert!(map.iter().eq([(Flag::Read, &0), (Flag::Execute, &0)]));
+    /// assert_eq!(Set::from_keys_of(&map), set);
+    /// ```
+    #[inline]
+    pub fn into_map_with<V, F>(self, func: F) -> Map<T, V>
+    where
+        F: FnMut(T) -> V,
+    {
+        Map::from_keys(self.storage.into_iter(), func)
+    }
+
+    /// Construct a set directly from its storage.
+    #[inline]
+    pub(crate) fn from_storage(storage: T::SetStorage) -> Set<T> {
+        Set { storage }
+    }
+
+    /// Access the storage of the set.
+    #[inline]
+    pub(crate) fn as_storage(&self) -> &T::SetStorage {
+        &self.storage
+    }
+
     /// An iterator visiting all values in arbitrary order.
     /// The iterator element type is `T`.
     ///
@@ -202,7 +296,81 @@ where
     /// ```
     #[inline]
     pub fn contains(&self, value: T) -> bool {
-        self.storage.contains(value)
+        let found = self.storage.contains(value);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_lookup(value, found);
+        found
+    }
+
+    /// Returns `true` if the set contains any value in the class selected by
+    /// `group`.
+    ///
+    /// For derived keys this only inspects the storage of the selected
+    /// variant, rather than filtering every value in the set.
+    ///
+    /// This is only available for keys whose storage implements
+    /// [`ClassStorage`], which includes every derived key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// #[key(groups)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    /// }
+    ///
+    /// let mut set = Set::new();
+    /// set.insert(MyKey::Second(false));
+    ///
+    /// assert!(!set.contains_any_of(MyKeyGroup::First));
+    /// assert!(set.contains_any_of(MyKeyGroup::Second));
+    /// ```
+    #[inline]
+    pub fn contains_any_of<G>(&self, group: G) -> bool
+    where
+        G: KeyGroup<Key = T>,
+        T::SetStorage: ClassStorage<T>,
+    {
+        self.storage.contains_class(group.class_index())
+    }
+
+    /// Returns the first value in the set, in the order of [`Set::iter`], for
+    /// which `f` returns `true`.
+    ///
+    /// Derived storage tests each value directly rather than going through
+    /// the iterator, and `#[key(bitset)]` storage stops as soon as no further
+    /// bits are set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    ///     Third,
+    /// }
+    ///
+    /// let mut set = Set::new();
+    /// set.insert(MyKey::First);
+    /// set.insert(MyKey::Second(false));
+    /// set.insert(MyKey::Third);
+    ///
+    /// assert_eq!(set.find(|value| value != MyKey::First), Some(MyKey::Second(false)));
+    /// assert_eq!(set.find(|value| value == MyKey::Second(true)), None);
+    /// ```
+    #[inline]
+    pub fn find<F>(&self, f: F) -> Option<T>
+    where
+        F: FnMut(T) -> bool,
+    {
+        self.storage.find(f)
     }
 
     /// Adds a value to the set.
@@ -232,9 +400,95 @@ where
     /// ```
     #[inline]
     pub fn insert(&mut self, value: T) -> bool {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record(crate::metrics::Operation::Insert, value);
         self.storage.insert(value)
     }
 
+    /// Adds every value within `range` to the set.
+    ///
+    /// Since keys whose variants are all unit variants are stored with one
+    /// slot for every variant in declaration order, this sets a contiguous run
+    /// of slots at once. With `#[key(bitset)]` this is a single mask
+    /// operation.
+    ///
+    /// This is only available for keys whose storage implements
+    /// [`RangeStorage`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is declared after its end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// #[key(bitset)]
+    /// enum Level {
+    ///     Trace,
+    ///     Debug,
+    ///     Info,
+    ///     Warn,
+    ///     Error,
+    /// }
+    ///
+    /// let mut set = Set::new();
+    /// set.insert_range(Level::Info..);
+    /// assert!(set.iter().eq([Level::Info, Level::Warn, Level::Error]));
+    ///
+    /// set.insert_range(Level::Trace..Level::Debug);
+    /// assert!(set.iter().eq([Level::Trace, Level::Info, Level::Warn, Level::Error]));
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn insert_range<R>(&mut self, range: R)
+    where
+        T: KeyIndex,
+        T::SetStorage: RangeStorage<T>,
+        R: RangeBounds<T>,
+    {
+        let Some(range) = index_range(&range) else {
+            range_out_of_order::<T>();
+        };
+
+        self.storage.insert_range(range.start, range.end);
+    }
+
+    /// Adds a value to the set unless it is already present.
+    ///
+    /// # Errors
+    ///
+    /// If the value is already present the set is left unmodified, and an
+    /// [`AlreadyPresent`] error carrying the rejected value is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     One,
+    ///     Two,
+    /// }
+    ///
+    /// let mut set = Set::new();
+    /// assert!(set.insert_unique(MyKey::One).is_ok());
+    ///
+    /// let error = set.insert_unique(MyKey::One).unwrap_err();
+    /// assert_eq!(error.value(), MyKey::One);
+    /// ```
+    #[inline]
+    pub fn insert_unique(&mut self, value: T) -> Result<(), AlreadyPresent<T>> {
+        if self.insert(value) {
+            Ok(())
+        } else {
+            Err(AlreadyPresent::new(value))
+        }
+    }
+
     /// Removes a value from the set. Returns `true` if the value was
     /// present in the set.
     ///
@@ -256,6 +510,8 @@ where
     /// ```
     #[inline]
     pub fn remove(&mut self, value: T) -> bool {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record(crate::metrics::Operation::Remove, value);
         self.storage.remove(value)
     }
 
@@ -332,6 +588,45 @@ where
         self.storage.retain(f);
     }
 
+    /// Retains only the elements specified by the predicate, which is also
+    /// passed the stable bit index of each element.
+    ///
+    /// This is only available for keys which implement [`BitIndex`], such as
+    /// keys using `#[key(bitset)]` storage. The index is the same as the one
+    /// returned by [`BitIndex::to_bit_index`], which makes it possible to
+    /// index into side tables maintained next to the set without computing
+    /// the index of each element separately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// #[key(bitset)]
+    /// enum Sensor {
+    ///     Front,
+    ///     Back,
+    ///     Left,
+    ///     Right,
+    /// }
+    ///
+    /// let readings = [12u32, 0, 7, 0];
+    ///
+    /// let mut set = Set::from([Sensor::Front, Sensor::Back, Sensor::Right]);
+    /// set.retain_indexed(|index, _| readings[index as usize] != 0);
+    ///
+    /// assert!(set.iter().eq([Sensor::Front]));
+    /// ```
+    #[inline]
+    pub fn retain_indexed<F>(&mut self, f: F)
+    where
+        T: BitIndex,
+        F: FnMut(u32, T) -> bool,
+    {
+        self.storage.retain_indexed(f);
+    }
+
     /// Clears the set, removing all values.
     ///
     /// # Examples
@@ -355,92 +650,597 @@ where
         self.storage.clear();
     }
 
+    /// Validate the internal consistency of the storage of this set.
+    ///
+    /// This checks that the length of the set agrees with its iterators and
+    /// that every iterated element can be looked up, which helps catch bugs
+    /// in storage implementations early. The check is only performed in
+    /// builds with debug assertions enabled, and is a no-op otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if debug assertions are enabled and the storage is
+    /// inconsistent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    /// }
+    ///
+    /// let mut set = Set::new();
+    /// set.insert(MyKey::First(true));
+    /// set.insert(MyKey::Second);
+    /// set.debug_assert_invariants();
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn debug_assert_invariants(&self) {
+        if cfg!(debug_assertions) {
+            self.storage.assert_invariants();
+        }
+    }
+
     /// Returns true if the set contains no elements.
     ///
-    /// # Examples
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     One,
+    ///     Two,
+    /// }
+    ///
+    /// let mut set = Set::new();
+    /// assert!(set.is_empty());
+    /// set.insert(MyKey::One);
+    /// assert!(!set.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+
+    /// Returns the number of elements in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut set = Set::new();
+    /// assert_eq!(set.len(), 0);
+    /// set.insert(MyKey::First);
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.storage.len()
+    }
+
+    /// Visits the values representing the intersection, i.e., the values that
+    /// are both in `self` and `other`.
+    ///
+    /// When an equal element is present in `self` and `other` then the
+    /// resulting `Intersection` may yield references to one or the other. This
+    /// can be relevant if `T` contains fields which are not compared by its
+    /// `Eq` implementation, and may hold different value between the two equal
+    /// copies of `T` in the two sets.
+    ///
+    /// The smaller of the two sets is used to drive the iteration, so for keys
+    /// without a fixed ordering such as those stored in a hash set the order
+    /// of the values depends on the sizes of the sets. Use
+    /// [`Set::intersection_ordered`] to always iterate in the order of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key, Debug)]
+    /// enum K {
+    ///     One,
+    ///     Two,
+    ///     Three,
+    /// }
+    ///
+    /// let a = Set::from([K::One]);
+    /// let b = Set::from([K::One, K::Two, K::Two]);
+    ///
+    /// let intersection = a.intersection(&b).collect::<Set<_>>();
+    /// assert_eq!(intersection, Set::from([K::One]));
+    /// ```
+    #[inline]
+    pub fn intersection<'a>(&'a self, other: &'a Set<T>) -> Intersection<'a, T> {
+        if self.len() <= other.len() {
+            Intersection {
+                iter: self.iter(),
+                other,
+                upper: self.len(),
+            }
+        } else {
+            Intersection {
+                iter: other.iter(),
+                other: self,
+                upper: other.len(),
+            }
+        }
+    }
+
+    /// Visits the values representing the intersection in the order in which
+    /// they are stored in `self`, i.e. in key-declaration order.
+    ///
+    /// Unlike [`Set::intersection`] this always iterates over `self` and looks
+    /// up values in `other`, regardless of which set is smaller. For keys
+    /// without a fixed ordering, such as ones stored in a hash set, this means
+    /// the order of `self` is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key, Debug, PartialEq)]
+    /// enum K {
+    ///     One,
+    ///     Two(bool),
+    ///     Three,
+    /// }
+    ///
+    /// let a = Set::from([K::One, K::Two(true), K::Two(false), K::Three]);
+    /// let b = Set::from([K::Three, K::Two(false)]);
+    ///
+    /// assert!(a.intersection_ordered(&b).eq([K::Two(false), K::Three]));
+    /// assert!(b.intersection_ordered(&a).eq([K::Two(false), K::Three]));
+    /// ```
+    #[inline]
+    pub fn intersection_ordered<'a>(&'a self, other: &'a Set<T>) -> Intersection<'a, T> {
+        Intersection {
+            iter: self.iter(),
+            other,
+            upper: self.len().min(other.len()),
+        }
+    }
+
+    /// Count the number of values which are both in `self` and `other`,
+    /// without building an iterator over the intersection.
+    ///
+    /// For sets using `#[key(bitset)]` storage this is a single popcount.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// #[key(bitset)]
+    /// enum Flag {
+    ///     A,
+    ///     B,
+    ///     C,
+    /// }
+    ///
+    /// let a = Set::from([Flag::A, Flag::B]);
+    /// let b = Set::from([Flag::B, Flag::C]);
+    /// assert_eq!(a.intersection_len(&b), 1);
+    /// ```
+    ///
+    /// Using a composite key:
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    /// }
+    ///
+    /// let a = Set::from([MyKey::First(true), MyKey::First(false)]);
+    /// let b = Set::from([MyKey::First(false), MyKey::Second]);
+    /// assert_eq!(a.intersection_len(&b), 1);
+    /// ```
+    #[inline]
+    pub fn intersection_len(&self, other: &Set<T>) -> usize {
+        self.storage.intersection_len(&other.storage)
+    }
+
+    /// Count the number of values which are in either `self` or `other`,
+    /// without building an iterator over the union.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// #[key(bitset)]
+    /// enum Flag {
+    ///     A,
+    ///     B,
+    ///     C,
+    /// }
+    ///
+    /// let a = Set::from([Flag::A, Flag::B]);
+    /// let b = Set::from([Flag::B, Flag::C]);
+    /// assert_eq!(a.union_len(&b), 3);
+    /// ```
+    #[inline]
+    pub fn union_len(&self, other: &Set<T>) -> usize {
+        self.len() + other.len() - self.intersection_len(other)
+    }
+
+    /// Calculate the [Jaccard index] of `self` and `other`, which is the size
+    /// of their intersection divided by the size of their union.
+    ///
+    /// Two empty sets are considered identical and have an index of `1.0`.
+    ///
+    /// [Jaccard index]: https://en.wikipedia.org/wiki/Jaccard_index
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// #[key(bitset)]
+    /// enum Flag {
+    ///     A,
+    ///     B,
+    ///     C,
+    ///     D,
+    /// }
+    ///
+    /// let a = Set::from([Flag::A, Flag::B]);
+    /// let b = Set::from([Flag::B, Flag::C, Flag::D]);
+    /// assert_eq!(a.jaccard(&b), 0.25);
+    /// assert_eq!(a.jaccard(&a), 1.0);
+    /// assert_eq!(Set::<Flag>::new().jaccard(&Set::new()), 1.0);
+    /// ```
+    #[inline]
+    pub fn jaccard(&self, other: &Set<T>) -> f64 {
+        let intersection = self.intersection_len(other);
+        let union = self.len() + other.len() - intersection;
+
+        if union == 0 {
+            return 1.0;
+        }
+
+        intersection as f64 / union as f64
+    }
+
+    /// Construct the union of all the given sets, i.e. a set containing every
+    /// value which is present in any of them.
+    ///
+    /// For sets using `#[key(bitset)]` storage this is implemented as a single
+    /// bitwise fold over the underlying words.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key, Debug)]
+    /// #[key(bitset)]
+    /// enum Permission {
+    ///     Read,
+    ///     Write,
+    ///     Execute,
+    /// }
+    ///
+    /// let subjects = [
+    ///     Set::from([Permission::Read]),
+    ///     Set::from([Permission::Read, Permission::Write]),
+    /// ];
+    ///
+    /// let any = Set::union_of(&subjects);
+    /// assert_eq!(any, Set::from([Permission::Read, Permission::Write]));
+    ///
+    /// assert!(Set::<Permission>::union_of(&[]).is_empty());
+    /// ```
+    ///
+    /// Using a composite key:
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key, Debug)]
+    /// enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    /// }
+    ///
+    /// let sets = [
+    ///     Set::from([MyKey::First(true)]),
+    ///     Set::from([MyKey::First(false), MyKey::Second]),
+    /// ];
+    ///
+    /// let any = Set::union_of(&sets);
+    /// assert_eq!(any, Set::from([MyKey::First(true), MyKey::First(false), MyKey::Second]));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn union_of(sets: &[Set<T>]) -> Set<T> {
+        let mut output = Set::<T>::new();
+
+        for set in sets {
+            output.storage.union_with(&set.storage);
+        }
+
+        output
+    }
+
+    /// Construct the intersection of all the given sets, i.e. a set containing
+    /// only the values which are present in every one of them.
+    ///
+    /// The intersection of an empty slice of sets is the empty set.
+    ///
+    /// For sets using `#[key(bitset)]` storage this is implemented as a single
+    /// bitwise fold over the underlying words.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key, Debug)]
+    /// #[key(bitset)]
+    /// enum Permission {
+    ///     Read,
+    ///     Write,
+    ///     Execute,
+    /// }
+    ///
+    /// let subjects = [
+    ///     Set::from([Permission::Read, Permission::Execute]),
+    ///     Set::from([Permission::Read, Permission::Write]),
+    /// ];
+    ///
+    /// let all = Set::intersection_of(&subjects);
+    /// assert_eq!(all, Set::from([Permission::Read]));
+    ///
+    /// assert!(Set::<Permission>::intersection_of(&[]).is_empty());
+    /// ```
+    ///
+    /// Using a composite key:
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key, Debug)]
+    /// enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    /// }
+    ///
+    /// let sets = [
+    ///     Set::from([MyKey::First(true), MyKey::Second]),
+    ///     Set::from([MyKey::First(false), MyKey::Second]),
+    /// ];
+    ///
+    /// let all = Set::intersection_of(&sets);
+    /// assert_eq!(all, Set::from([MyKey::Second]));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn intersection_of(sets: &[Set<T>]) -> Set<T> {
+        let mut output = Set::<T>::new();
+
+        let Some((first, rest)) = sets.split_first() else {
+            return output;
+        };
+
+        output.storage.union_with(&first.storage);
+
+        for set in rest {
+            output.storage.intersect_with(&set.storage);
+        }
+
+        output
+    }
+
+    /// Insert every value in `other` into `self`, making `self` the union of
+    /// the two sets without constructing a new set.
+    ///
+    /// For sets using `#[key(bitset)]` storage this is a single bitwise or.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key, Debug)]
+    /// #[key(bitset)]
+    /// enum Flag {
+    ///     A,
+    ///     B,
+    ///     C,
+    /// }
+    ///
+    /// let mut a = Set::from([Flag::A, Flag::B]);
+    /// a.union_with(&Set::from([Flag::B, Flag::C]));
+    /// assert_eq!(a, Set::from([Flag::A, Flag::B, Flag::C]));
+    /// ```
+    ///
+    /// Using a composite key:
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key, Debug)]
+    /// enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    /// }
+    ///
+    /// let mut a = Set::from([MyKey::First(true)]);
+    /// a.union_with(&Set::from([MyKey::First(false), MyKey::Second]));
+    /// assert_eq!(a, Set::from([MyKey::First(true), MyKey::First(false), MyKey::Second]));
+    /// ```
+    #[inline]
+    pub fn union_with(&mut self, other: &Set<T>) {
+        self.storage.union_with(&other.storage);
+    }
+
+    /// Retain only the values in `self` which are also in `other`, making
+    /// `self` the intersection of the two sets without constructing a new set.
+    ///
+    /// For sets using `#[key(bitset)]` storage this is a single bitwise and.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key, Debug)]
+    /// #[key(bitset)]
+    /// enum Flag {
+    ///     A,
+    ///     B,
+    ///     C,
+    /// }
+    ///
+    /// let mut a = Set::from([Flag::A, Flag::B]);
+    /// a.intersect_with(&Set::from([Flag::B, Flag::C]));
+    /// assert_eq!(a, Set::from([Flag::B]));
+    /// ```
+    ///
+    /// Using a composite key:
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key, Debug)]
+    /// enum MyKey {
+    ///     First(bool),
+    ///     Second,
+    /// }
+    ///
+    /// let mut a = Set::from([MyKey::First(true), MyKey::Second]);
+    /// a.intersect_with(&Set::from([MyKey::First(false), MyKey::Second]));
+    /// assert_eq!(a, Set::from([MyKey::Second]));
+    /// ```
+    #[inline]
+    pub fn intersect_with(&mut self, other: &Set<T>) {
+        self.storage.intersect_with(&other.storage);
+    }
+
+    /// Remove every value in `other` from `self`, making `self` the
+    /// difference of the two sets without constructing a new set.
+    ///
+    /// For sets using `#[key(bitset)]` storage this is a single bitwise and
+    /// with the complement of `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Clone, Copy, Key, Debug)]
+    /// #[key(bitset)]
+    /// enum Flag {
+    ///     A,
+    ///     B,
+    ///     C,
+    /// }
+    ///
+    /// let mut a = Set::from([Flag::A, Flag::B]);
+    /// a.subtract(&Set::from([Flag::B, Flag::C]));
+    /// assert_eq!(a, Set::from([Flag::A]));
+    /// ```
+    ///
+    /// Using a composite key:
     ///
     /// ```
     /// use fixed_map::{Key, Set};
     ///
-    /// #[derive(Clone, Copy, Key)]
+    /// #[derive(Clone, Copy, Key, Debug)]
     /// enum MyKey {
-    ///     One,
-    ///     Two,
+    ///     First(bool),
+    ///     Second,
     /// }
     ///
-    /// let mut set = Set::new();
-    /// assert!(set.is_empty());
-    /// set.insert(MyKey::One);
-    /// assert!(!set.is_empty());
+    /// let mut a = Set::from([MyKey::First(true), MyKey::First(false), MyKey::Second]);
+    /// a.subtract(&Set::from([MyKey::First(false), MyKey::Second]));
+    /// assert_eq!(a, Set::from([MyKey::First(true)]));
     /// ```
     #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.storage.is_empty()
+    pub fn subtract(&mut self, other: &Set<T>) {
+        self.storage.subtract(&other.storage);
     }
 
-    /// Returns the number of elements in the set.
+    /// Compare the set against a `previous` snapshot of it, returning the
+    /// values which have been added since as the first set and the values
+    /// which have been removed since as the second.
+    ///
+    /// For sets using `#[key(bitset)]` storage each side is a single bitwise
+    /// and with the complement of the other set.
     ///
     /// # Examples
     ///
     /// ```
     /// use fixed_map::{Key, Set};
     ///
-    /// #[derive(Clone, Copy, Key)]
-    /// enum MyKey {
-    ///     First,
-    ///     Second,
+    /// #[derive(Clone, Copy, Key, Debug)]
+    /// #[key(bitset)]
+    /// enum Flag {
+    ///     Grounded,
+    ///     Jumping,
+    ///     Crouching,
     /// }
     ///
-    /// let mut set = Set::new();
-    /// assert_eq!(set.len(), 0);
-    /// set.insert(MyKey::First);
-    /// assert_eq!(set.len(), 1);
-    /// ```
-    #[inline]
-    pub fn len(&self) -> usize {
-        self.storage.len()
-    }
-
-    /// Visits the values representing the intersection, i.e., the values that
-    /// are both in `self` and `other`.
+    /// let previous = Set::from([Flag::Grounded, Flag::Crouching]);
+    /// let current = Set::from([Flag::Jumping, Flag::Crouching]);
     ///
-    /// When an equal element is present in `self` and `other` then the
-    /// resulting `Intersection` may yield references to one or the other. This
-    /// can be relevant if `T` contains fields which are not compared by its
-    /// `Eq` implementation, and may hold different value between the two equal
-    /// copies of `T` in the two sets.
+    /// let (added, removed) = current.changes_since(&previous);
+    /// assert_eq!(added, Set::from([Flag::Jumping]));
+    /// assert_eq!(removed, Set::from([Flag::Grounded]));
+    /// ```
     ///
-    /// # Examples
+    /// Using a composite key:
     ///
     /// ```
     /// use fixed_map::{Key, Set};
     ///
     /// #[derive(Clone, Copy, Key, Debug)]
-    /// enum K {
-    ///     One,
-    ///     Two,
-    ///     Three,
+    /// enum MyKey {
+    ///     First(bool),
+    ///     Second,
     /// }
     ///
-    /// let a = Set::from([K::One]);
-    /// let b = Set::from([K::One, K::Two, K::Two]);
+    /// let previous = Set::from([MyKey::First(true), MyKey::Second]);
+    /// let current = Set::from([MyKey::First(false), MyKey::Second]);
     ///
-    /// let intersection = a.intersection(&b).collect::<Set<_>>();
-    /// assert_eq!(intersection, Set::from([K::One]));
+    /// let (added, removed) = current.changes_since(&previous);
+    /// assert_eq!(added, Set::from([MyKey::First(false)]));
+    /// assert_eq!(removed, Set::from([MyKey::First(true)]));
     /// ```
+    #[must_use]
     #[inline]
-    pub fn intersection<'a>(&'a self, other: &'a Set<T>) -> Intersection<'a, T> {
-        if self.len() <= other.len() {
-            Intersection {
-                iter: self.iter(),
-                other,
-            }
-        } else {
-            Intersection {
-                iter: other.iter(),
-                other: self,
-            }
-        }
+    pub fn changes_since(&self, previous: &Set<T>) -> (Set<T>, Set<T>) {
+        let mut added = Set::<T>::new();
+        added.storage.union_with(&self.storage);
+        added.storage.subtract(&previous.storage);
+
+        let mut removed = Set::<T>::new();
+        removed.storage.union_with(&previous.storage);
+        removed.storage.subtract(&self.storage);
+
+        (added, removed)
     }
 }
 
@@ -451,6 +1251,11 @@ where
 {
     /// Get the raw value of the set.
     ///
+    /// For keys using `#[key(bitset)]` the bit order of the raw value is
+    /// stable, see [`raw`][crate::raw#bit-order] for details. Note that
+    /// variants disabled through `#[cfg(...)]` don't occupy a bit, even if
+    /// they're reserved through `#[key(variants(...))]`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -504,6 +1309,118 @@ where
             storage: <T::SetStorage as RawStorage>::from_raw(raw),
         }
     }
+
+    /// Borrow the raw value of the set as 64-bit words, least significant word
+    /// first.
+    ///
+    /// This allows sets to be handed to code which operates on words in bulk
+    /// without copying them. It's only available if the raw value is laid out
+    /// as words in memory, which is the case for bitsets with 33 to 64
+    /// variants, and for bitsets with 65 to 128 variants on little endian
+    /// targets, see [`AsWords`]. Any set can be copied into words using
+    /// [`Set::to_words`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Debug, Clone, Copy, Key)]
+    /// #[key(bitset)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    ///     Third,
+    ///     # T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16, T17, T18,
+    ///     # T19, T20, T21, T22, T23, T24, T25, T26, T27, T28, T29, T30, T31, T32,
+    ///     // More than 32 variants.
+    /// }
+    ///
+    /// let set = Set::from([MyKey::First, MyKey::Third]);
+    ///
+    /// let words: &[u64] = set.as_words();
+    /// assert_eq!(words, [0b101]);
+    /// assert_eq!(words.iter().map(|w| w.count_ones()).sum::<u32>(), 2);
+    /// ```
+    #[inline]
+    pub fn as_words(&self) -> &[u64]
+    where
+        <T::SetStorage as RawStorage>::Value: AsWords,
+    {
+        self.storage.as_raw_ref().as_words()
+    }
+
+    /// Copy the raw value of the set into 64-bit words, least significant
+    /// word first.
+    ///
+    /// Unlike [`Set::as_words`] this is available for bitsets of any size.
+    /// The returned array can be borrowed as `&[u64]` through [`AsRef`], and
+    /// sets with up to 64 variants produce a single word. See [`Words`] for
+    /// how bits are laid out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Debug, Clone, Copy, Key)]
+    /// #[key(bitset)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    ///     Third,
+    /// }
+    ///
+    /// let set = Set::from([MyKey::First, MyKey::Third]);
+    /// assert_eq!(set.to_words(), [0b101]);
+    ///
+    /// let words = set.to_words();
+    /// let words: &[u64] = words.as_ref();
+    /// assert_eq!(words.iter().map(|w| w.count_ones()).sum::<u32>(), 2);
+    /// ```
+    #[inline]
+    pub fn to_words(&self) -> <<T::SetStorage as RawStorage>::Value as Words>::Array
+    where
+        <T::SetStorage as RawStorage>::Value: Words,
+    {
+        self.storage.as_raw().to_words()
+    }
+
+    /// Construct the set from 64-bit words, least significant word first, as
+    /// produced by [`Set::as_words`] or [`Set::to_words`].
+    ///
+    /// Missing words are treated as zero, while words and bits which don't
+    /// correspond to a variant of the key are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, Set};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// #[key(bitset)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    ///     Third,
+    /// }
+    ///
+    /// let set = Set::<MyKey>::from_words(&[0b110]);
+    /// assert!(set.iter().eq([MyKey::Second, MyKey::Third]));
+    ///
+    /// let set = Set::<MyKey>::from_words(&[u64::MAX]);
+    /// assert_eq!(set.len(), 3);
+    ///
+    /// assert!(Set::<MyKey>::from_words(&[]).is_empty());
+    /// ```
+    #[inline]
+    pub fn from_words(words: &[u64]) -> Self
+    where
+        <T::SetStorage as RawStorage>::Value: Words,
+    {
+        let raw = <T::SetStorage as RawStorage>::Value::from_words(words);
+        Self::from_raw(raw & <T::SetStorage as RawStorage>::MASK)
+    }
 }
 
 /// [`Clone`] implementation for a [`Set`].
@@ -626,12 +1543,50 @@ where
 ///
 /// assert_eq!("{First}", format!("{:?}", a));
 /// ```
+///
+/// The alternate format `{:#?}` groups values by their variant, as returned by
+/// [`Key::variant_name`]. This includes sets with `#[key(bitset)]` storage:
+///
+/// ```
+/// use fixed_map::{Key, Set};
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// #[key(bitset)]
+/// enum Direction {
+///     North,
+///     South,
+/// }
+///
+/// #[derive(Debug, Clone, Copy, Key)]
+/// enum MyKey {
+///     Move(Direction),
+///     Stop,
+/// }
+///
+/// let a = Set::from([MyKey::Move(Direction::North), MyKey::Move(Direction::South), MyKey::Stop]);
+///
+/// let expected = r#"{
+///     Move: {
+///         Move(North),
+///         Move(South),
+///     },
+///     Stop: {
+///         Stop,
+///     },
+/// }"#;
+///
+/// assert_eq!(format!("{:#?}", a), expected);
+/// ```
 impl<T> fmt::Debug for Set<T>
 where
     T: Key + fmt::Debug,
 {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return crate::debug::grouped_set(self, f);
+        }
+
         f.debug_set().entries(self.iter()).finish()
     }
 }
@@ -914,6 +1869,8 @@ where
     }
 }
 
+/// [`IntoIterator`] implementation which uses [`Set::iter`]. See its
+/// documentation for more.
 impl<'a, T> IntoIterator for &'a Set<T>
 where
     T: Key,
@@ -927,6 +1884,47 @@ where
     }
 }
 
+/// [`IntoIterator`] implementation which uses [`Set::iter`]. See its
+/// documentation for more.
+///
+/// Elements of a set are keys which can't be modified in place, so this yields
+/// the same items as iterating over `&Set`.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Set};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let mut set = Set::new();
+/// set.insert(MyKey::Second);
+///
+/// let mut keys = Vec::new();
+///
+/// for key in &mut set {
+///     keys.push(key);
+/// }
+///
+/// assert_eq!(keys, [MyKey::Second]);
+/// ```
+impl<'a, T> IntoIterator for &'a mut Set<T>
+where
+    T: Key,
+{
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 /// Produce an owning iterator which iterates over all elements in the set in
 /// order.
 ///
@@ -992,12 +1990,113 @@ where
         I: IntoIterator<Item = T>,
     {
         let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
 
+/// Inserts every value of an iterator into the [`Set`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Set};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+///     Third,
+/// }
+///
+/// let mut set = Set::from([MyKey::Third]);
+/// set.extend([MyKey::First, MyKey::Third]);
+///
+/// assert!(set.iter().eq([MyKey::First, MyKey::Third]));
+/// ```
+impl<T> Extend<T> for Set<T>
+where
+    T: Key,
+{
+    #[inline]
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
         for value in iter {
-            set.insert(value);
+            self.insert(value);
         }
+    }
+}
 
-        set
+/// Inserts copies of every borrowed value of an iterator into the [`Set`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Set};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// let values = vec![MyKey::Second, MyKey::First];
+///
+/// let mut set = Set::new();
+/// set.extend(&values);
+///
+/// assert!(set.iter().eq([MyKey::First, MyKey::Second]));
+/// ```
+impl<'a, T> Extend<&'a T> for Set<T>
+where
+    T: Key,
+{
+    #[inline]
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        for &value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+/// Clears the set.
+///
+/// Keys are not scrubbed, so this only empties the set. The keys of dynamic
+/// variants remain in the memory of their hash table after the set has been
+/// cleared, so keys which are secret themselves should not be stored in a
+/// [`Set`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Set};
+/// use zeroize::Zeroize;
+///
+/// #[derive(Clone, Copy, Key)]
+/// enum Permission {
+///     Read,
+///     Write,
+/// }
+///
+/// let mut set = Set::new();
+/// set.insert(Permission::Write);
+///
+/// set.zeroize();
+/// assert!(set.is_empty());
+/// ```
+#[cfg(feature = "zeroize")]
+impl<T> zeroize::Zeroize for Set<T>
+where
+    T: Key,
+{
+    #[inline]
+    fn zeroize(&mut self) {
+        self.clear();
     }
 }
 
@@ -1088,3 +2187,17 @@ where
         Self::from_iter(arr)
     }
 }
+
+/// Panic since the start of a range is declared after its end.
+#[cold]
+#[inline(never)]
+#[track_caller]
+fn range_out_of_order<T>() -> !
+where
+    T: Key,
+{
+    panic!(
+        "{}: range start is declared after range end",
+        core::any::type_name::<Set<T>>(),
+    )
+}
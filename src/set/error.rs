@@ -0,0 +1,62 @@
+use core::fmt;
+
+use crate::debug::KeyName;
+use crate::Key;
+
+/// Error returned by [`Set::insert_unique`] when the value is already
+/// present, which carries the rejected value.
+///
+/// [`Set::insert_unique`]: crate::Set::insert_unique
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::set::AlreadyPresent;
+/// use fixed_map::{Key, Set};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First,
+///     Second(bool),
+/// }
+///
+/// let mut set = Set::new();
+/// set.insert(MyKey::Second(true));
+///
+/// let error = set.insert_unique(MyKey::Second(true)).unwrap_err();
+/// assert_eq!(error, AlreadyPresent::new(MyKey::Second(true)));
+/// assert_eq!(error.to_string(), "value `Second(true)` is already present");
+/// assert_eq!(error.value(), MyKey::Second(true));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlreadyPresent<T> {
+    value: T,
+}
+
+impl<T> AlreadyPresent<T> {
+    /// Construct a new error for the given rejected value.
+    #[inline]
+    #[must_use]
+    pub const fn new(value: T) -> Self {
+        Self { value }
+    }
+
+    /// The value which is already present.
+    #[inline]
+    pub fn value(self) -> T {
+        self.value
+    }
+}
+
+impl<T> fmt::Display for AlreadyPresent<T>
+where
+    T: Key,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "value `{}` is already present", KeyName(self.value))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for AlreadyPresent<T> where T: Key + fmt::Debug {}
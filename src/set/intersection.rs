@@ -1,15 +1,18 @@
 //! Module that defines the [`Intersection`] for [`Set`].
 
 use core::fmt;
+use core::iter::FusedIterator;
 
 use super::{Iter, Key, Set};
 
 /// A lazy iterator producing elements in the intersection of `Set`s.
 ///
-/// This `struct` is created by the [`intersection`] method on [`Set`]. See its
-/// documentation for more.
+/// This `struct` is created by the [`intersection`] and
+/// [`intersection_ordered`] methods on [`Set`]. See their documentation for
+/// more.
 ///
 /// [`intersection`]: Set::intersection
+/// [`intersection_ordered`]: Set::intersection_ordered
 ///
 /// # Examples
 ///
@@ -36,6 +39,8 @@ pub struct Intersection<'a, T: 'a + Key> {
     pub(super) iter: Iter<'a, T>,
     // the second set
     pub(super) other: &'a Set<T>,
+    // upper bound on the number of remaining elements
+    pub(super) upper: usize,
 }
 
 impl<T: Key> Clone for Intersection<'_, T> {
@@ -60,6 +65,7 @@ where
             let elt = self.iter.next()?;
 
             if self.other.contains(elt) {
+                self.upper = self.upper.saturating_sub(1);
                 return Some(elt);
             }
         }
@@ -68,7 +74,10 @@ where
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
         let (_, upper) = self.iter.size_hint();
-        (0, upper)
+        (
+            0,
+            Some(upper.map_or(self.upper, |upper| upper.min(self.upper))),
+        )
     }
 
     #[inline]
@@ -87,6 +96,13 @@ where
     }
 }
 
+impl<'a, T> FusedIterator for Intersection<'a, T>
+where
+    T: Key,
+    Iter<'a, T>: FusedIterator,
+{
+}
+
 impl<T> fmt::Debug for Intersection<'_, T>
 where
     T: fmt::Debug + Key,
@@ -6,14 +6,31 @@ pub use self::singleton::SingletonSetStorage;
 mod boolean;
 pub use self::boolean::BooleanSetStorage;
 
-#[cfg(feature = "hashbrown")]
+#[cfg(any(feature = "hashbrown", feature = "std-collections"))]
 mod hashbrown;
-#[cfg(feature = "hashbrown")]
+#[cfg(any(feature = "hashbrown", feature = "std-collections"))]
 pub use self::hashbrown::HashbrownSetStorage;
 
 mod option;
 pub use self::option::OptionSetStorage;
 
+#[cfg(feature = "alloc")]
+mod ordered;
+#[cfg(feature = "alloc")]
+pub use self::ordered::OrderedSetStorage;
+
+#[cfg(feature = "alloc")]
+mod sparse;
+#[cfg(feature = "alloc")]
+pub use self::sparse::SparseSetStorage;
+
+mod unavailable;
+pub use self::unavailable::UnavailableSetStorage;
+
+use crate::debug::KeyName;
+use crate::raw::BitIndex;
+use crate::Key;
+
 /// The trait defining how storage works for [`Set`][crate::Set].
 ///
 /// # Type Arguments
@@ -51,12 +68,161 @@ pub trait SetStorage<T>: Sized {
     where
         F: FnMut(T) -> bool;
 
+    /// This is the storage abstraction for
+    /// [`Set::retain_indexed`][crate::Set::retain_indexed]. The default
+    /// implementation looks up the bit index of every value, but storage
+    /// which already knows the position of each value, such as bitsets,
+    /// should override it.
+    #[inline]
+    fn retain_indexed<F>(&mut self, mut f: F)
+    where
+        T: BitIndex,
+        F: FnMut(u32, T) -> bool,
+    {
+        self.retain(|value| f(value.to_bit_index(), value));
+    }
+
+    /// This is the storage abstraction for [`Set::find`][crate::Set::find].
+    ///
+    /// The default implementation goes through [`SetStorage::iter`], but
+    /// storage which can skip absent values cheaply, such as bitsets, should
+    /// override it.
+    #[inline]
+    fn find<F>(&self, mut f: F) -> Option<T>
+    where
+        T: Copy,
+        F: FnMut(T) -> bool,
+    {
+        self.iter().find(|&value| f(value))
+    }
+
     /// This is the storage abstraction for [`Set::clear`][crate::Set::clear].
     fn clear(&mut self);
 
+    /// Insert every value in `other` into this storage.
+    ///
+    /// This is the storage abstraction for [`Set::union_with`][crate::Set::union_with]
+    /// and [`Set::union_of`][crate::Set::union_of]. The default implementation
+    /// inserts values one at a time, but storage which can combine values in
+    /// bulk, such as bitsets, should override it.
+    #[inline]
+    fn union_with(&mut self, other: &Self) {
+        for value in other.iter() {
+            self.insert(value);
+        }
+    }
+
+    /// Retain only the values in this storage which are also in `other`.
+    ///
+    /// This is the storage abstraction for
+    /// [`Set::intersect_with`][crate::Set::intersect_with] and
+    /// [`Set::intersection_of`][crate::Set::intersection_of]. The default
+    /// implementation checks values one at a time, but storage which can
+    /// combine values in bulk, such as bitsets, should override it.
+    #[inline]
+    fn intersect_with(&mut self, other: &Self) {
+        self.retain(|value| other.contains(value));
+    }
+
+    /// Remove every value in `other` from this storage.
+    ///
+    /// This is the storage abstraction for
+    /// [`Set::subtract`][crate::Set::subtract]. The default implementation
+    /// checks values one at a time, but storage which can combine values in
+    /// bulk, such as bitsets, should override it.
+    #[inline]
+    fn subtract(&mut self, other: &Self) {
+        self.retain(|value| !other.contains(value));
+    }
+
+    /// Count the number of values which are both in this storage and in
+    /// `other`.
+    ///
+    /// This is the storage abstraction for
+    /// [`Set::intersection_len`][crate::Set::intersection_len]. The default
+    /// implementation checks values one at a time, but storage which can
+    /// combine values in bulk, such as bitsets, should override it.
+    #[inline]
+    fn intersection_len(&self, other: &Self) -> usize {
+        let mut len = 0;
+
+        for value in self.iter() {
+            len += usize::from(other.contains(value));
+        }
+
+        len
+    }
+
+    /// This is the storage abstraction for
+    /// [`Set::debug_assert_invariants`][crate::Set::debug_assert_invariants].
+    ///
+    /// The default implementation checks that the length of the storage
+    /// agrees with its iterator, and that every value which is iterated over
+    /// is contained. Storage which keeps additional bookkeeping can override
+    /// it to validate that as well.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the storage is inconsistent.
+    #[track_caller]
+    fn assert_invariants(&self)
+    where
+        T: Key,
+    {
+        let ty = core::any::type_name::<crate::Set<T>>();
+        let len = self.len();
+        assert_eq!(
+            self.is_empty(),
+            len == 0,
+            "{ty}: is_empty disagrees with len"
+        );
+        assert_eq!(self.iter().count(), len, "{ty}: iter disagrees with len");
+
+        for value in self.iter() {
+            assert!(
+                self.contains(value),
+                "{ty}: iterated value `{}` is not contained",
+                KeyName(value)
+            );
+        }
+    }
+
     /// This is the storage abstraction for [`Set::iter`][crate::Set::iter].
     fn iter(&self) -> Self::Iter<'_>;
 
     /// This is the storage abstraction for [`Set::into_iter`][crate::Set::into_iter].
     fn into_iter(self) -> Self::IntoIter;
 }
+
+/// Storage which can test for the presence of any value of a class at once,
+/// see [`map::ClassStorage`][crate::map::ClassStorage].
+///
+/// This is implemented for the storage of derived keys and of keys provided
+/// by this crate.
+///
+/// # Type Arguments
+///
+/// - `T` is the key being stored.
+pub trait ClassStorage<T>: SetStorage<T> {
+    /// This is the storage abstraction for [`Set::contains_any_of`][crate::Set::contains_any_of].
+    fn contains_class(&self, class: usize) -> bool;
+}
+
+/// Storage with one slot for every key, where the slot of a key is its
+/// [`KeyIndex::to_index`], so that a contiguous range of keys can be inserted
+/// at once.
+///
+/// This is implemented for the storage of derived enums whose variants are
+/// all unit variants, unless `#[key(sparse)]` is used.
+///
+/// [`KeyIndex::to_index`]: crate::raw::KeyIndex::to_index
+///
+/// # Type Arguments
+///
+/// - `T` is the key being stored.
+pub trait RangeStorage<T>: SetStorage<T> {
+    /// This is the storage abstraction for
+    /// [`Set::insert_range`][crate::Set::insert_range], which inserts every
+    /// key stored in a slot within `start..end`.
+    fn insert_range(&mut self, start: usize, end: usize);
+}
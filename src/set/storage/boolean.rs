@@ -3,7 +3,7 @@
 
 use core::mem;
 
-use crate::set::SetStorage;
+use crate::set::{ClassStorage, SetStorage};
 
 const TRUE_BIT: u8 = 0b10;
 const FALSE_BIT: u8 = 0b01;
@@ -138,7 +138,7 @@ impl SetStorage<bool> for BooleanSetStorage {
     #[inline]
     fn insert(&mut self, value: bool) -> bool {
         let update = self.bits | to_bits(value);
-        test(mem::replace(&mut self.bits, update), value)
+        !test(mem::replace(&mut self.bits, update), value)
     }
 
     #[inline]
@@ -148,9 +148,8 @@ impl SetStorage<bool> for BooleanSetStorage {
 
     #[inline]
     fn remove(&mut self, value: bool) -> bool {
-        let value = to_bits(value);
-        let update = self.bits & !value;
-        mem::replace(&mut self.bits, update) != 0
+        let update = self.bits & !to_bits(value);
+        test(mem::replace(&mut self.bits, update), value)
     }
 
     #[inline]
@@ -167,11 +166,47 @@ impl SetStorage<bool> for BooleanSetStorage {
         }
     }
 
+    #[inline]
+    fn find<F>(&self, mut f: F) -> Option<bool>
+    where
+        F: FnMut(bool) -> bool,
+    {
+        if test(self.bits, true) && f(true) {
+            return Some(true);
+        }
+
+        if test(self.bits, false) && f(false) {
+            return Some(false);
+        }
+
+        None
+    }
+
     #[inline]
     fn clear(&mut self) {
         self.bits = 0;
     }
 
+    #[inline]
+    fn union_with(&mut self, other: &Self) {
+        self.bits |= other.bits;
+    }
+
+    #[inline]
+    fn intersect_with(&mut self, other: &Self) {
+        self.bits &= other.bits;
+    }
+
+    #[inline]
+    fn subtract(&mut self, other: &Self) {
+        self.bits &= !other.bits;
+    }
+
+    #[inline]
+    fn intersection_len(&self, other: &Self) -> usize {
+        (self.bits & other.bits).count_ones() as usize
+    }
+
     #[inline]
     fn iter(&self) -> Self::Iter<'_> {
         Iter { bits: self.bits }
@@ -183,6 +218,17 @@ impl SetStorage<bool> for BooleanSetStorage {
     }
 }
 
+impl ClassStorage<bool> for BooleanSetStorage {
+    #[inline]
+    fn contains_class(&self, class: usize) -> bool {
+        match class {
+            0 => test(self.bits, false),
+            1 => test(self.bits, true),
+            _ => false,
+        }
+    }
+}
+
 #[inline]
 const fn test(bits: u8, value: bool) -> bool {
     bits & to_bits(value) != 0
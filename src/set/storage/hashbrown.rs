@@ -1,13 +1,17 @@
 use core::hash::Hash;
 use core::iter;
 
-use crate::set::SetStorage;
+use crate::map::storage::map::{hash_set, HashSet};
+use crate::set::{ClassStorage, SetStorage};
 
 /// [`SetStorage`] for dynamically stored types, using [`hashbrown::HashSet`].
 ///
 /// This allows for dynamic types such as `&'static str` or `u32` to be used as
 /// a [`Key`][crate::Key].
 ///
+/// If the `std-collections` feature is enabled, this is backed by
+/// [`std::collections::HashSet`] instead.
+///
 /// # Examples
 ///
 /// ```
@@ -27,7 +31,7 @@ use crate::set::SetStorage;
 /// ```
 #[repr(transparent)]
 pub struct HashbrownSetStorage<T> {
-    inner: ::hashbrown::HashSet<T>,
+    inner: HashSet<T>,
 }
 
 impl<T> Clone for HashbrownSetStorage<T>
@@ -59,15 +63,15 @@ where
     T: Copy + Eq + Hash,
 {
     type Iter<'this>
-        = iter::Copied<::hashbrown::hash_set::Iter<'this, T>>
+        = iter::Copied<hash_set::Iter<'this, T>>
     where
         T: 'this;
-    type IntoIter = ::hashbrown::hash_set::IntoIter<T>;
+    type IntoIter = hash_set::IntoIter<T>;
 
     #[inline]
     fn empty() -> Self {
         Self {
-            inner: ::hashbrown::HashSet::new(),
+            inner: HashSet::new(),
         }
     }
 
@@ -119,3 +123,13 @@ where
         self.inner.into_iter()
     }
 }
+
+impl<T> ClassStorage<T> for HashbrownSetStorage<T>
+where
+    T: Copy + Eq + Hash,
+{
+    #[inline]
+    fn contains_class(&self, class: usize) -> bool {
+        class == 0 && !self.inner.is_empty()
+    }
+}
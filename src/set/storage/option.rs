@@ -2,7 +2,7 @@ use core::iter;
 use core::mem;
 use core::option;
 
-use crate::set::SetStorage;
+use crate::set::{ClassStorage, SetStorage};
 use crate::Key;
 
 type Iter<'a, T> = iter::Chain<
@@ -54,6 +54,23 @@ where
     none: bool,
 }
 
+impl<T> OptionSetStorage<T>
+where
+    T: Key,
+{
+    /// Construct storage from its parts.
+    #[inline]
+    pub(crate) fn from_parts(some: T::SetStorage, none: bool) -> Self {
+        Self { some, none }
+    }
+
+    /// Access the parts of the storage.
+    #[inline]
+    pub(crate) fn as_parts(&self) -> (&T::SetStorage, bool) {
+        (&self.some, self.none)
+    }
+}
+
 impl<T> Clone for OptionSetStorage<T>
 where
     T: Key,
@@ -118,14 +135,14 @@ where
 
     #[inline]
     fn is_empty(&self) -> bool {
-        self.some.is_empty() && self.none
+        self.some.is_empty() && !self.none
     }
 
     #[inline]
     fn insert(&mut self, value: Option<T>) -> bool {
         match value {
             Some(value) => self.some.insert(value),
-            None => mem::replace(&mut self.none, true),
+            None => !mem::replace(&mut self.none, true),
         }
     }
 
@@ -157,6 +174,22 @@ where
         }
     }
 
+    #[inline]
+    fn find<F>(&self, mut func: F) -> Option<Option<T>>
+    where
+        F: FnMut(Option<T>) -> bool,
+    {
+        if let Some(value) = self.some.find(|value| func(Some(value))) {
+            return Some(Some(value));
+        }
+
+        if self.none && func(None) {
+            return Some(None);
+        }
+
+        None
+    }
+
     #[inline]
     fn clear(&mut self) {
         self.some.clear();
@@ -181,3 +214,17 @@ where
             .chain(self.none.then_some(None::<T>))
     }
 }
+
+impl<T> ClassStorage<Option<T>> for OptionSetStorage<T>
+where
+    T: Key,
+{
+    #[inline]
+    fn contains_class(&self, class: usize) -> bool {
+        match class {
+            0 => !self.some.is_empty(),
+            1 => self.none,
+            _ => false,
+        }
+    }
+}
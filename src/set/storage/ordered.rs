@@ -0,0 +1,139 @@
+use core::iter;
+
+use alloc::collections::btree_set::{self, BTreeSet};
+
+use crate::set::{ClassStorage, SetStorage};
+
+/// [`SetStorage`] for dynamically stored types, using [`BTreeSet`].
+///
+/// Values are visited in order, so iteration, `Debug` output and
+/// serialization are the same from one run to the next.
+///
+/// This is used by variants marked with `#[key(dynamic = "ordered")]`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "hashbrown")] {
+/// use fixed_map::{Key, Set};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     #[key(dynamic = "ordered")]
+///     Name(&'static str),
+///     Other,
+/// }
+///
+/// let mut set = Set::new();
+/// set.insert(MyKey::Name("b"));
+/// set.insert(MyKey::Name("a"));
+///
+/// assert!(set.contains(MyKey::Name("a")));
+/// assert!(!set.contains(MyKey::Other));
+/// assert!(set.iter().eq([MyKey::Name("a"), MyKey::Name("b")]));
+/// # }
+/// ```
+#[repr(transparent)]
+pub struct OrderedSetStorage<T> {
+    inner: BTreeSet<T>,
+}
+
+impl<T> Clone for OrderedSetStorage<T>
+where
+    T: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        OrderedSetStorage {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> PartialEq for OrderedSetStorage<T>
+where
+    T: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.eq(&other.inner)
+    }
+}
+
+impl<T> Eq for OrderedSetStorage<T> where T: Eq {}
+
+impl<T> SetStorage<T> for OrderedSetStorage<T>
+where
+    T: Copy + Ord,
+{
+    type Iter<'this>
+        = iter::Copied<btree_set::Iter<'this, T>>
+    where
+        T: 'this;
+    type IntoIter = btree_set::IntoIter<T>;
+
+    #[inline]
+    fn empty() -> Self {
+        Self {
+            inner: BTreeSet::new(),
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    #[inline]
+    fn insert(&mut self, value: T) -> bool {
+        self.inner.insert(value)
+    }
+
+    #[inline]
+    fn contains(&self, value: T) -> bool {
+        self.inner.contains(&value)
+    }
+
+    #[inline]
+    fn remove(&mut self, value: T) -> bool {
+        self.inner.remove(&value)
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, mut func: F)
+    where
+        F: FnMut(T) -> bool,
+    {
+        self.inner.retain(|&value| func(value));
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter<'_> {
+        self.inner.iter().copied()
+    }
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl<T> ClassStorage<T> for OrderedSetStorage<T>
+where
+    T: Copy + Ord,
+{
+    #[inline]
+    fn contains_class(&self, class: usize) -> bool {
+        class == 0 && !self.inner.is_empty()
+    }
+}
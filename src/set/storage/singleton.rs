@@ -1,6 +1,6 @@
 use core::mem;
 
-use crate::set::SetStorage;
+use crate::set::{ClassStorage, SetStorage};
 
 /// [`SetStorage`]  types that can only inhabit a single value (like `()`).
 #[repr(transparent)]
@@ -51,7 +51,9 @@ where
     where
         F: FnMut(T) -> bool,
     {
-        self.is_set = func(T::default());
+        if self.is_set {
+            self.is_set = func(T::default());
+        }
     }
 
     #[inline]
@@ -69,3 +71,13 @@ where
         self.is_set.then_some(T::default()).into_iter()
     }
 }
+
+impl<T> ClassStorage<T> for SingletonSetStorage
+where
+    T: Default + Clone,
+{
+    #[inline]
+    fn contains_class(&self, class: usize) -> bool {
+        class == 0 && self.is_set
+    }
+}
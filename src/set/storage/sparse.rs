@@ -0,0 +1,205 @@
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::iter;
+use core::marker::PhantomData;
+use core::slice;
+
+use alloc::vec::{self, Vec};
+
+use crate::raw::SparseIndex;
+use crate::set::{ClassStorage, SetStorage};
+
+/// [`SetStorage`] for keys with a large number of variants, which only stores
+/// the values which are present.
+///
+/// The [`SparseIndex`] of every present value is kept sorted in a vector, so
+/// iteration visits them in the same order as the array storage of a key
+/// would.
+///
+/// This is used by enums marked with `#[key(sparse)]`.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Set};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(sparse)]
+/// enum Opcode {
+///     Nop,
+///     Load,
+///     Store,
+///     Jump,
+/// }
+///
+/// let set = Set::from([Opcode::Jump, Opcode::Nop]);
+///
+/// assert!(set.contains(Opcode::Nop));
+/// assert!(!set.contains(Opcode::Load));
+/// assert!(set.iter().eq([Opcode::Nop, Opcode::Jump]));
+/// ```
+pub struct SparseSetStorage<T> {
+    indexes: Vec<u16>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> SparseSetStorage<T>
+where
+    T: SparseIndex,
+{
+    /// Find the position of the given value, or where it should be inserted.
+    #[inline]
+    fn search(&self, value: T) -> Result<usize, usize> {
+        self.indexes.binary_search(&value.to_sparse_index())
+    }
+}
+
+/// Get the value stored at an index taken from the table of a storage.
+#[inline]
+fn value<T>(index: u16) -> T
+where
+    T: SparseIndex,
+{
+    match T::from_sparse_index(index) {
+        Some(value) => value,
+        None => panic!("sparse index {index} doesn't correspond to a value"),
+    }
+}
+
+impl<T> Clone for SparseSetStorage<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            indexes: self.indexes.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> PartialEq for SparseSetStorage<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.indexes == other.indexes
+    }
+}
+
+impl<T> Eq for SparseSetStorage<T> {}
+
+impl<T> Hash for SparseSetStorage<T> {
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        state.write_usize(self.indexes.len());
+
+        for &index in &self.indexes {
+            state.write_usize(usize::from(index));
+        }
+    }
+}
+
+/// Ordered like the array storage of a key, which compares the index of every
+/// present value in order.
+impl<T> PartialOrd for SparseSetStorage<T> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for SparseSetStorage<T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.indexes.cmp(&other.indexes)
+    }
+}
+
+impl<T> SetStorage<T> for SparseSetStorage<T>
+where
+    T: SparseIndex,
+{
+    type Iter<'this>
+        = iter::Map<iter::Copied<slice::Iter<'this, u16>>, fn(u16) -> T>
+    where
+        T: 'this;
+    type IntoIter = iter::Map<vec::IntoIter<u16>, fn(u16) -> T>;
+
+    #[inline]
+    fn empty() -> Self {
+        Self {
+            indexes: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.indexes.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.indexes.is_empty()
+    }
+
+    #[inline]
+    fn insert(&mut self, value: T) -> bool {
+        match self.search(value) {
+            Ok(..) => false,
+            Err(position) => {
+                self.indexes.insert(position, value.to_sparse_index());
+                true
+            }
+        }
+    }
+
+    #[inline]
+    fn contains(&self, value: T) -> bool {
+        self.search(value).is_ok()
+    }
+
+    #[inline]
+    fn remove(&mut self, value: T) -> bool {
+        match self.search(value) {
+            Ok(position) => {
+                self.indexes.remove(position);
+                true
+            }
+            Err(..) => false,
+        }
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, mut func: F)
+    where
+        F: FnMut(T) -> bool,
+    {
+        self.indexes.retain(|&index| func(value(index)));
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.indexes.clear();
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter<'_> {
+        self.indexes.iter().copied().map(value)
+    }
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.indexes.into_iter().map(value)
+    }
+}
+
+impl<T> ClassStorage<T> for SparseSetStorage<T>
+where
+    T: SparseIndex,
+{
+    #[inline]
+    fn contains_class(&self, class: usize) -> bool {
+        T::from_index(class).is_some_and(|value| self.contains(value))
+    }
+}
@@ -0,0 +1,137 @@
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::iter;
+use core::marker::PhantomData;
+
+use crate::map::storage::unavailable;
+use crate::set::{ClassStorage, SetStorage};
+
+/// [`SetStorage`] for a variant marked with `#[key(optional)]` whose field
+/// can't be used as a key, since neither the `hashbrown` nor the
+/// `std-collections` feature of fixed-map is enabled.
+///
+/// The storage is always empty. Lookups of the variant find nothing, while
+/// inserting into it panics.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::set::storage::UnavailableSetStorage;
+/// use fixed_map::set::SetStorage;
+///
+/// let mut storage = UnavailableSetStorage::<u32>::empty();
+/// assert!(!storage.contains(1));
+/// assert!(!storage.remove(1));
+/// ```
+pub struct UnavailableSetStorage<T> {
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for UnavailableSetStorage<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for UnavailableSetStorage<T> {}
+
+impl<T> PartialEq for UnavailableSetStorage<T> {
+    #[inline]
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<T> Eq for UnavailableSetStorage<T> {}
+
+impl<T> Hash for UnavailableSetStorage<T> {
+    #[inline]
+    fn hash<H>(&self, _: &mut H)
+    where
+        H: Hasher,
+    {
+    }
+}
+
+impl<T> PartialOrd for UnavailableSetStorage<T> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for UnavailableSetStorage<T> {
+    #[inline]
+    fn cmp(&self, _: &Self) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+impl<T> SetStorage<T> for UnavailableSetStorage<T> {
+    type Iter<'this>
+        = iter::Empty<T>
+    where
+        T: 'this;
+    type IntoIter = iter::Empty<T>;
+
+    #[inline]
+    fn empty() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        0
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    #[track_caller]
+    fn insert(&mut self, _: T) -> bool {
+        unavailable::unavailable::<T>()
+    }
+
+    #[inline]
+    fn contains(&self, _: T) -> bool {
+        false
+    }
+
+    #[inline]
+    fn remove(&mut self, _: T) -> bool {
+        false
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, _: F)
+    where
+        F: FnMut(T) -> bool,
+    {
+    }
+
+    #[inline]
+    fn clear(&mut self) {}
+
+    #[inline]
+    fn iter(&self) -> Self::Iter<'_> {
+        iter::empty()
+    }
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        iter::empty()
+    }
+}
+
+impl<T> ClassStorage<T> for UnavailableSetStorage<T> {
+    #[inline]
+    fn contains_class(&self, _: usize) -> bool {
+        false
+    }
+}
@@ -0,0 +1,392 @@
+//! Functions for exercising the contract of a [`Key`] implementation.
+//!
+//! These are intended to be called from the tests of downstream crates, to
+//! catch a broken or misused [`Key`] derive. Each function takes a slice of
+//! *distinct* keys which it will exercise, for keys with a small number of
+//! values this should be every possible key.
+//!
+//! Every check panics with a descriptive message if the contract is violated.
+//!
+//! This module is only available when the `test-utils` feature is enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use fixed_map::{test_utils, Key};
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq, Key)]
+//! enum MyKey {
+//!     First,
+//!     Second(bool),
+//!     Third,
+//! }
+//!
+//! let keys = [
+//!     MyKey::First,
+//!     MyKey::Second(true),
+//!     MyKey::Second(false),
+//!     MyKey::Third,
+//! ];
+//!
+//! test_utils::check_key(&keys);
+//! ```
+
+use core::fmt;
+
+use crate::map::{Entry, OccupiedEntry, VacantEntry};
+use crate::{Key, Map, Set};
+
+/// Run every available check over the given distinct keys.
+///
+/// This is equivalent to calling [`check_map`], [`check_entry`] and
+/// [`check_set`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{test_utils, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// #[key(bitset)]
+/// enum MyKey {
+///     First,
+///     Second,
+/// }
+///
+/// test_utils::check_key(&[MyKey::First, MyKey::Second]);
+/// ```
+pub fn check_key<K>(keys: &[K])
+where
+    K: Key + PartialEq + fmt::Debug,
+    K::MapStorage<usize>: PartialEq,
+    K::SetStorage: PartialEq,
+{
+    check_distinct(keys);
+    check_map(keys);
+    check_entry(keys);
+    check_set(keys);
+}
+
+/// Check insert, get, remove and iteration round-trips as well as equality of
+/// [`Map`] over the given distinct keys.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{test_utils, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First,
+///     Second(bool),
+/// }
+///
+/// test_utils::check_map(&[MyKey::First, MyKey::Second(true), MyKey::Second(false)]);
+/// ```
+pub fn check_map<K>(keys: &[K])
+where
+    K: Key + PartialEq + fmt::Debug,
+    K::MapStorage<usize>: PartialEq,
+{
+    check_distinct(keys);
+
+    let mut map = Map::new();
+    assert!(map.is_empty(), "new map is not empty");
+
+    for (n, &key) in keys.iter().enumerate() {
+        assert_eq!(map.get(key), None, "{key:?}: present before insert");
+        assert!(!map.contains_key(key), "{key:?}: contained before insert");
+        assert_eq!(map.insert(key, n), None, "{key:?}: insert into vacant");
+        assert_eq!(map.len(), n + 1, "{key:?}: length after insert");
+        assert_eq!(map.get(key), Some(&n), "{key:?}: get after insert");
+        assert!(map.contains_key(key), "{key:?}: contains after insert");
+    }
+
+    for (n, &key) in keys.iter().enumerate() {
+        assert_eq!(map.get(key), Some(&n), "{key:?}: get after all inserts");
+        assert_eq!(map.insert(key, n), Some(n), "{key:?}: insert into occupied");
+
+        let count = map.iter().filter(|&(k, v)| k == key && *v == n).count();
+        assert_eq!(count, 1, "{key:?}: should be yielded exactly once by iter");
+
+        let count = map.keys().filter(|&k| k == key).count();
+        assert_eq!(count, 1, "{key:?}: should be yielded exactly once by keys");
+    }
+
+    assert_eq!(map.len(), keys.len(), "length after all inserts");
+    assert_eq!(map.iter().count(), keys.len(), "length of iter");
+    assert_eq!(map.keys().count(), keys.len(), "length of keys");
+    assert_eq!(map.values().count(), keys.len(), "length of values");
+    assert!(
+        map.iter().map(|(_, v)| v).eq(map.values()),
+        "iter and values disagree"
+    );
+    assert!(
+        map.iter().map(|(k, _)| k).eq(map.keys()),
+        "iter and keys disagree"
+    );
+
+    let mut reversed = Map::new();
+
+    for (n, &key) in keys.iter().enumerate().rev() {
+        reversed.insert(key, n);
+    }
+
+    assert!(
+        map == reversed,
+        "maps with the same contents inserted in different order are not equal"
+    );
+
+    for value in map.values_mut() {
+        *value += 1;
+    }
+
+    for (n, &key) in keys.iter().enumerate() {
+        assert_eq!(
+            map.get(key),
+            Some(&(n + 1)),
+            "{key:?}: get after values_mut"
+        );
+
+        if let Some(value) = map.get_mut(key) {
+            *value -= 1;
+        }
+    }
+
+    assert!(map == reversed, "maps not equal after get_mut");
+
+    for (n, &key) in keys.iter().enumerate() {
+        assert_eq!(map.remove(key), Some(n), "{key:?}: remove occupied");
+        assert_eq!(map.remove(key), None, "{key:?}: remove vacant");
+        assert_eq!(map.get(key), None, "{key:?}: get after remove");
+        assert_eq!(
+            map.len(),
+            keys.len() - n - 1,
+            "{key:?}: length after remove"
+        );
+        assert!(map != reversed, "{key:?}: equal after remove");
+
+        let count = map.keys().filter(|&k| k == key).count();
+        assert_eq!(count, 0, "{key:?}: yielded by keys after remove");
+    }
+
+    assert!(map.is_empty(), "map is not empty after removing all keys");
+
+    for (n, &key) in keys.iter().enumerate() {
+        map.insert(key, n);
+    }
+
+    let mut output = 0;
+
+    for (key, value) in map {
+        let n = keys
+            .iter()
+            .position(|&k| k == key)
+            .unwrap_or_else(|| panic!("{key:?}: unknown key yielded by into_iter"));
+
+        assert_eq!(value, n, "{key:?}: wrong value yielded by into_iter");
+        output += 1;
+    }
+
+    assert_eq!(output, keys.len(), "length of into_iter");
+}
+
+/// Check the laws of the entry API of [`Map`] over the given distinct keys.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{test_utils, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First,
+///     Second(bool),
+/// }
+///
+/// test_utils::check_entry(&[MyKey::First, MyKey::Second(true), MyKey::Second(false)]);
+/// ```
+pub fn check_entry<K>(keys: &[K])
+where
+    K: Key + PartialEq + fmt::Debug,
+{
+    check_distinct(keys);
+
+    let mut map = Map::new();
+
+    for (n, &key) in keys.iter().enumerate() {
+        match map.entry(key) {
+            Entry::Occupied(..) => panic!("{key:?}: entry occupied before insert"),
+            Entry::Vacant(entry) => {
+                assert_eq!(entry.key(), key, "{key:?}: key of vacant entry");
+                assert_eq!(*entry.insert(n), n, "{key:?}: vacant entry insert");
+            }
+        }
+
+        assert_eq!(map.get(key), Some(&n), "{key:?}: get after entry insert");
+
+        match map.entry(key) {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.key(), key, "{key:?}: key of occupied entry");
+                assert_eq!(*entry.get(), n, "{key:?}: occupied entry get");
+                *entry.get_mut() += 1;
+                assert_eq!(entry.insert(n), n + 1, "{key:?}: occupied entry insert");
+            }
+            Entry::Vacant(..) => panic!("{key:?}: entry vacant after insert"),
+        }
+
+        assert_eq!(map.len(), n + 1, "{key:?}: length after entry insert");
+    }
+
+    for (n, &key) in keys.iter().enumerate() {
+        match map.entry(key) {
+            Entry::Occupied(entry) => {
+                assert_eq!(entry.remove(), n, "{key:?}: occupied entry remove");
+            }
+            Entry::Vacant(..) => panic!("{key:?}: entry vacant before remove"),
+        }
+
+        assert_eq!(map.get(key), None, "{key:?}: get after entry remove");
+        assert!(
+            matches!(map.entry(key), Entry::Vacant(..)),
+            "{key:?}: entry occupied after remove"
+        );
+    }
+
+    assert!(
+        map.is_empty(),
+        "map is not empty after removing all entries"
+    );
+}
+
+/// Check insert, contains, remove and iteration round-trips as well as
+/// equality of [`Set`] over the given distinct keys.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{test_utils, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First,
+///     Second(bool),
+/// }
+///
+/// test_utils::check_set(&[MyKey::First, MyKey::Second(true), MyKey::Second(false)]);
+/// ```
+pub fn check_set<K>(keys: &[K])
+where
+    K: Key + PartialEq + fmt::Debug,
+    K::SetStorage: PartialEq,
+{
+    check_distinct(keys);
+
+    let mut set = Set::new();
+    assert!(set.is_empty(), "new set is not empty");
+
+    for (n, &key) in keys.iter().enumerate() {
+        assert!(!set.contains(key), "{key:?}: contained before insert");
+        assert!(set.insert(key), "{key:?}: insert into vacant");
+        assert!(!set.insert(key), "{key:?}: insert into occupied");
+        assert!(set.contains(key), "{key:?}: contains after insert");
+        assert_eq!(set.len(), n + 1, "{key:?}: length after insert");
+    }
+
+    for &key in keys {
+        let count = set.iter().filter(|&k| k == key).count();
+        assert_eq!(count, 1, "{key:?}: should be yielded exactly once by iter");
+    }
+
+    assert_eq!(set.iter().count(), keys.len(), "length of iter");
+
+    let mut reversed = Set::new();
+
+    for &key in keys.iter().rev() {
+        reversed.insert(key);
+    }
+
+    assert!(
+        set == reversed,
+        "sets with the same contents inserted in different order are not equal"
+    );
+
+    for (n, &key) in keys.iter().enumerate() {
+        assert!(set.remove(key), "{key:?}: remove occupied");
+        assert!(!set.remove(key), "{key:?}: remove vacant");
+        assert!(!set.contains(key), "{key:?}: contains after remove");
+        assert_eq!(
+            set.len(),
+            keys.len() - n - 1,
+            "{key:?}: length after remove"
+        );
+        assert!(set != reversed, "{key:?}: equal after remove");
+    }
+
+    assert!(set.is_empty(), "set is not empty after removing all keys");
+
+    for &key in keys {
+        set.insert(key);
+    }
+
+    let mut output = 0;
+
+    for key in set {
+        assert!(
+            keys.contains(&key),
+            "{key:?}: unknown key yielded by into_iter"
+        );
+        output += 1;
+    }
+
+    assert_eq!(output, keys.len(), "length of into_iter");
+}
+
+/// Check that a [`Map`] and a [`Set`] populated with the given distinct keys
+/// survive a serialization round-trip.
+///
+/// The `round_trip` callbacks are expected to serialize and deserialize the
+/// given collection with the format under test.
+///
+/// This is only available when the `serde` feature is enabled.
+#[cfg(feature = "serde")]
+pub fn check_serde<K, M, S>(keys: &[K], mut map_round_trip: M, mut set_round_trip: S)
+where
+    K: Key + PartialEq + fmt::Debug,
+    K::MapStorage<usize>: PartialEq,
+    K::SetStorage: PartialEq,
+    M: FnMut(&Map<K, usize>) -> Map<K, usize>,
+    S: FnMut(&Set<K>) -> Set<K>,
+{
+    check_distinct(keys);
+
+    let mut map = Map::new();
+    let mut set = Set::new();
+
+    for (n, &key) in keys.iter().enumerate() {
+        map.insert(key, n);
+        set.insert(key);
+
+        assert!(
+            map_round_trip(&map) == map,
+            "{key:?}: map differs after round-trip"
+        );
+        assert!(
+            set_round_trip(&set) == set,
+            "{key:?}: set differs after round-trip"
+        );
+    }
+}
+
+/// Assert that every key in the given slice is distinct.
+fn check_distinct<K>(keys: &[K])
+where
+    K: PartialEq + fmt::Debug,
+{
+    for (n, key) in keys.iter().enumerate() {
+        assert!(
+            !keys[..n].contains(key),
+            "{key:?}: keys passed to the check must be distinct"
+        );
+    }
+}
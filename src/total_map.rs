@@ -0,0 +1,485 @@
+//! Contains the fixed [`TotalMap`] implementation.
+
+use core::fmt;
+use core::ops::{Index, IndexMut};
+
+use crate::debug::KeyName;
+use crate::map::{
+    EntriesStorage, Entry, IntoIter, Iter, IterMut, Keys, Map, VacantEntry, Values, ValuesMut,
+};
+use crate::{Key, Set};
+
+/// A fixed map which is known to contain a value for every key in the domain
+/// of `K`.
+///
+/// Since no key can be missing, values are accessed without going through an
+/// [`Option`], and there are no methods to remove them.
+///
+/// A total map is constructed by checking that a [`Map`] is complete through
+/// [`Map::try_into_total`], [`Map::expect_all`] or [`Map::unwrap_all`], or
+/// directly from a function with [`TotalMap::from_fn`]. It's only available
+/// for keys with a finite domain, whose storage implements
+/// [`EntriesStorage`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First,
+///     Second(bool),
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(MyKey::First, 1);
+/// map.insert(MyKey::Second(true), 2);
+/// map.insert(MyKey::Second(false), 3);
+///
+/// let mut map = map.expect_all("every key should be configured");
+/// map[MyKey::First] += 10;
+///
+/// assert_eq!(map.get(MyKey::First), &11);
+/// assert_eq!(map[MyKey::Second(false)], 3);
+/// ```
+#[repr(transparent)]
+pub struct TotalMap<K, V>
+where
+    K: Key,
+{
+    map: Map<K, V>,
+}
+
+impl<K, V> TotalMap<K, V>
+where
+    K: Key,
+{
+    /// Wrap a map which is known to be complete.
+    #[inline]
+    pub(crate) fn new_unchecked(map: Map<K, V>) -> Self {
+        Self { map }
+    }
+
+    /// Constructs a [`TotalMap`] by calling `func` with every key in the
+    /// domain of `K`.
+    ///
+    /// Keys are visited in the same order as [`Map::iter`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::total_map::TotalMap;
+    /// use fixed_map::Key;
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second(bool),
+    /// }
+    ///
+    /// let map = TotalMap::from_fn(|key| matches!(key, MyKey::Second(true)));
+    ///
+    /// assert!(map.iter().eq([
+    ///     (MyKey::First, &false),
+    ///     (MyKey::Second(true), &true),
+    ///     (MyKey::Second(false), &false),
+    /// ]));
+    /// ```
+    #[inline]
+    pub fn from_fn<F>(mut func: F) -> Self
+    where
+        K::MapStorage<V>: EntriesStorage<K, V>,
+        F: FnMut(K) -> V,
+    {
+        let mut map = Map::<K, V>::new();
+
+        for entry in map.entries_mut() {
+            if let Entry::Vacant(entry) = entry {
+                let key = entry.key();
+                entry.insert(func(key));
+            }
+        }
+
+        Self { map }
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::total_map::TotalMap;
+    /// use fixed_map::Key;
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map = TotalMap::from_fn(|key| matches!(key, MyKey::First));
+    /// assert_eq!(map.get(MyKey::First), &true);
+    /// assert_eq!(map.get(MyKey::Second), &false);
+    /// ```
+    #[inline]
+    pub fn get(&self, key: K) -> &V {
+        &self.map[key]
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::total_map::TotalMap;
+    /// use fixed_map::Key;
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = TotalMap::from_fn(|_| 0);
+    /// *map.get_mut(MyKey::Second) += 2;
+    /// assert_eq!(map.get(MyKey::Second), &2);
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self, key: K) -> &mut V {
+        &mut self.map[key]
+    }
+
+    /// Replaces the value corresponding to the key, returning the previous
+    /// value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::total_map::TotalMap;
+    /// use fixed_map::Key;
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = TotalMap::from_fn(|_| 0);
+    /// assert_eq!(map.replace(MyKey::First, 1), 0);
+    /// assert_eq!(map.replace(MyKey::First, 2), 1);
+    /// ```
+    #[inline]
+    pub fn replace(&mut self, key: K, value: V) -> V {
+        core::mem::replace(self.get_mut(key), value)
+    }
+
+    /// Returns the number of elements in the map, which is the number of keys
+    /// in the domain of `K`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the domain of `K` is empty, such as for an enum
+    /// without variants.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// An iterator visiting all key-value pairs in the order of [`Map::iter`].
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.map.iter()
+    }
+
+    /// An iterator visiting all key-value pairs with mutable references to
+    /// the values.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        self.map.iter_mut()
+    }
+
+    /// An iterator visiting all keys in the order of [`Map::keys`].
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        self.map.keys()
+    }
+
+    /// An iterator visiting all values in the order of [`Map::values`].
+    #[inline]
+    pub fn values(&self) -> Values<'_, K, V> {
+        self.map.values()
+    }
+
+    /// An iterator visiting mutable references to all values.
+    #[inline]
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        self.map.values_mut()
+    }
+
+    /// Access the underlying [`Map`].
+    #[inline]
+    pub fn as_map(&self) -> &Map<K, V> {
+        &self.map
+    }
+
+    /// Convert into the underlying [`Map`], which loses the guarantee that
+    /// every key is present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::total_map::TotalMap;
+    /// use fixed_map::Key;
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = TotalMap::from_fn(|_| 0).into_map();
+    /// assert_eq!(map.remove(MyKey::First), Some(0));
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    #[inline]
+    pub fn into_map(self) -> Map<K, V> {
+        self.map
+    }
+}
+
+impl<K, V> TryFrom<Map<K, V>> for TotalMap<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: EntriesStorage<K, V>,
+{
+    type Error = Incomplete<K, V>;
+
+    #[inline]
+    fn try_from(map: Map<K, V>) -> Result<Self, Self::Error> {
+        map.try_into_total()
+    }
+}
+
+impl<K, V> From<TotalMap<K, V>> for Map<K, V>
+where
+    K: Key,
+{
+    #[inline]
+    fn from(map: TotalMap<K, V>) -> Self {
+        map.map
+    }
+}
+
+impl<K, V> Clone for TotalMap<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<K, V> Copy for TotalMap<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: Copy,
+{
+}
+
+impl<K, V> PartialEq for TotalMap<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<K, V> Eq for TotalMap<K, V>
+where
+    K: Key,
+    K::MapStorage<V>: Eq,
+{
+}
+
+impl<K, V> fmt::Debug for TotalMap<K, V>
+where
+    K: Key + fmt::Debug,
+    V: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.map.fmt(f)
+    }
+}
+
+impl<K, V> Index<K> for TotalMap<K, V>
+where
+    K: Key,
+{
+    type Output = V;
+
+    #[inline]
+    fn index(&self, key: K) -> &V {
+        self.get(key)
+    }
+}
+
+impl<K, V> IndexMut<K> for TotalMap<K, V>
+where
+    K: Key,
+{
+    #[inline]
+    fn index_mut(&mut self, key: K) -> &mut V {
+        self.get_mut(key)
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a TotalMap<K, V>
+where
+    K: Key,
+{
+    type Item = (K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut TotalMap<K, V>
+where
+    K: Key,
+{
+    type Item = (K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K, V> IntoIterator for TotalMap<K, V>
+where
+    K: Key,
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
+    }
+}
+
+/// Error returned by [`Map::try_into_total`] when some keys are missing from
+/// the map, which carries the map back together with the missing keys.
+///
+/// The [`Display`][fmt::Display] implementation lists every missing key by
+/// name.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, Map};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+/// enum MyKey {
+///     First,
+///     Second(bool),
+///     Third,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert(MyKey::Second(true), 1);
+///
+/// let error = map.try_into_total().unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "missing keys `First`, `Second(false)`, `Third`"
+/// );
+/// assert!(error
+///     .missing()
+///     .iter()
+///     .eq([MyKey::First, MyKey::Second(false), MyKey::Third]));
+/// assert_eq!(error.into_map().len(), 1);
+/// ```
+pub struct Incomplete<K, V>
+where
+    K: Key,
+{
+    map: Map<K, V>,
+    missing: Set<K>,
+}
+
+impl<K, V> Incomplete<K, V>
+where
+    K: Key,
+{
+    #[inline]
+    pub(crate) fn new(map: Map<K, V>, missing: Set<K>) -> Self {
+        Self { map, missing }
+    }
+
+    /// The keys which are missing from the map.
+    #[inline]
+    pub fn missing(&self) -> &Set<K> {
+        &self.missing
+    }
+
+    /// Convert back into the incomplete map.
+    #[inline]
+    pub fn into_map(self) -> Map<K, V> {
+        self.map
+    }
+}
+
+impl<K, V> fmt::Display for Incomplete<K, V>
+where
+    K: Key,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.missing.len() == 1 {
+            f.write_str("missing key ")?;
+        } else {
+            f.write_str("missing keys ")?;
+        }
+
+        for (n, key) in self.missing.iter().enumerate() {
+            if n > 0 {
+                f.write_str(", ")?;
+            }
+
+            write!(f, "`{}`", KeyName(key))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<K, V> fmt::Debug for Incomplete<K, V>
+where
+    K: Key + fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Incomplete")
+            .field("missing", &self.missing)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V> std::error::Error for Incomplete<K, V> where K: Key + fmt::Debug {}
@@ -0,0 +1,621 @@
+//! Contains the fixed [`VersionedMap`] implementation.
+
+use core::fmt;
+
+use crate::map::{self, Map};
+use crate::Key;
+
+/// The generation of a slot in a [`VersionedMap`].
+///
+/// The generation of a slot is bumped every time a value is inserted into or
+/// removed from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Generation(u32);
+
+impl Generation {
+    /// Get the raw value of the generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// assert_eq!(map.generation(MyKey::First).get(), 0);
+    ///
+    /// map.insert(MyKey::First, 1);
+    /// assert_eq!(map.generation(MyKey::First).get(), 1);
+    /// ```
+    #[inline]
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+}
+
+/// A handle to a value stored in a [`VersionedMap`].
+///
+/// A handle is only valid as long as the value it was created for is still in
+/// the map. It is invalidated when the value is removed or replaced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle<K> {
+    key: K,
+    generation: Generation,
+}
+
+impl<K> Handle<K>
+where
+    K: Copy,
+{
+    /// The key this handle refers to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// let (handle, _) = map.insert(MyKey::First, 1);
+    /// assert_eq!(handle.key(), MyKey::First);
+    /// ```
+    #[inline]
+    pub fn key(&self) -> K {
+        self.key
+    }
+
+    /// The generation of the slot when this handle was created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// let (handle, _) = map.insert(MyKey::First, 1);
+    /// assert_eq!(handle.generation(), map.generation(MyKey::First));
+    /// ```
+    #[inline]
+    pub fn generation(&self) -> Generation {
+        self.generation
+    }
+}
+
+/// A slot for a single key, which keeps its generation after its value has
+/// been removed.
+struct Slot<V> {
+    generation: Generation,
+    value: Option<V>,
+}
+
+/// A fixed map where every slot carries a generation, which allows stale
+/// handles to values to be detected.
+///
+/// Inserting a value returns a [`Handle`], which can later be used to access
+/// the value. Since the generation of a slot is bumped every time a value is
+/// inserted into or removed from it, a handle to a value which has since been
+/// removed or replaced no longer resolves, even if the key has been populated
+/// again.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_map::{Key, VersionedMap};
+///
+/// #[derive(Clone, Copy, Key)]
+/// enum Slot {
+///     Player,
+///     Enemy,
+/// }
+///
+/// let mut map = VersionedMap::new();
+/// let (player, _) = map.insert(Slot::Player, "alice");
+/// assert_eq!(map.get(player), Some(&"alice"));
+///
+/// map.remove(player);
+/// map.insert(Slot::Player, "bob");
+///
+/// // The handle to the old player is stale.
+/// assert_eq!(map.get(player), None);
+/// assert_eq!(map.get_key(Slot::Player), Some(&"bob"));
+/// ```
+///
+/// Using a composite key:
+///
+/// ```
+/// use fixed_map::{Key, VersionedMap};
+///
+/// #[derive(Clone, Copy, Key)]
+/// enum Slot {
+///     Unit(bool),
+///     Empty,
+/// }
+///
+/// let mut map = VersionedMap::new();
+/// let (first, _) = map.insert(Slot::Unit(true), 1);
+/// let (second, old) = map.insert(Slot::Unit(true), 2);
+///
+/// assert_eq!(old, Some(1));
+/// assert!(!map.is_valid(first));
+/// assert_eq!(map.get(second), Some(&2));
+/// ```
+pub struct VersionedMap<K, V>
+where
+    K: Key,
+{
+    slots: Map<K, Slot<V>>,
+    len: usize,
+}
+
+impl<K, V> VersionedMap<K, V>
+where
+    K: Key,
+{
+    /// Creates an empty [`VersionedMap`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let map: VersionedMap<MyKey, u32> = VersionedMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new() -> VersionedMap<K, V> {
+        VersionedMap {
+            slots: Map::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of values in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// let (handle, _) = map.insert(MyKey::First, 1);
+    /// assert_eq!(map.len(), 1);
+    ///
+    /// map.remove(handle);
+    /// assert_eq!(map.len(), 0);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// assert!(map.is_empty());
+    /// map.insert(MyKey::First, 1);
+    /// assert!(!map.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the current generation of the slot for the given key.
+    ///
+    /// Slots which have never been used are at generation zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// let before = map.generation(MyKey::First);
+    ///
+    /// map.insert(MyKey::First, 1);
+    /// map.remove_key(MyKey::First);
+    ///
+    /// assert!(map.generation(MyKey::First) > before);
+    /// ```
+    #[inline]
+    pub fn generation(&self, key: K) -> Generation {
+        self.slots
+            .get(key)
+            .map_or(Generation(0), |slot| slot.generation)
+    }
+
+    /// Returns a handle to the value currently associated with the key, if
+    /// any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// let (handle, _) = map.insert(MyKey::First, 1);
+    ///
+    /// assert_eq!(map.handle(MyKey::First), Some(handle));
+    /// assert_eq!(map.handle(MyKey::Second), None);
+    /// ```
+    #[inline]
+    pub fn handle(&self, key: K) -> Option<Handle<K>> {
+        let slot = self.slots.get(key)?;
+        slot.value.as_ref()?;
+
+        Some(Handle {
+            key,
+            generation: slot.generation,
+        })
+    }
+
+    /// Returns `true` if the handle refers to a value which is still in the
+    /// map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// let (handle, _) = map.insert(MyKey::First, 1);
+    /// assert!(map.is_valid(handle));
+    ///
+    /// map.remove_key(MyKey::First);
+    /// assert!(!map.is_valid(handle));
+    /// ```
+    #[inline]
+    pub fn is_valid(&self, handle: Handle<K>) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// Inserts a value into the map, returning a handle to it together with
+    /// the value it replaced.
+    ///
+    /// This bumps the generation of the slot, so any existing handles to the
+    /// key are invalidated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// let (a, old) = map.insert(MyKey::First, 1);
+    /// assert_eq!(old, None);
+    ///
+    /// let (b, old) = map.insert(MyKey::First, 2);
+    /// assert_eq!(old, Some(1));
+    /// assert_ne!(a, b);
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> (Handle<K>, Option<V>) {
+        let slot = self.slots.entry(key).or_insert_with(|| Slot {
+            generation: Generation(0),
+            value: None,
+        });
+
+        slot.generation = Generation(slot.generation.0.wrapping_add(1));
+        let old = slot.value.replace(value);
+
+        if old.is_none() {
+            self.len += 1;
+        }
+
+        let handle = Handle {
+            key,
+            generation: slot.generation,
+        };
+
+        (handle, old)
+    }
+
+    /// Returns a reference to the value the handle refers to, or `None` if the
+    /// handle is stale.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// let (a, _) = map.insert(MyKey::First, 1);
+    /// let (b, _) = map.insert(MyKey::First, 2);
+    ///
+    /// assert_eq!(map.get(a), None);
+    /// assert_eq!(map.get(b), Some(&2));
+    /// ```
+    #[inline]
+    pub fn get(&self, handle: Handle<K>) -> Option<&V> {
+        let slot = self.slots.get(handle.key)?;
+
+        if slot.generation != handle.generation {
+            return None;
+        }
+
+        slot.value.as_ref()
+    }
+
+    /// Returns a mutable reference to the value the handle refers to, or
+    /// `None` if the handle is stale.
+    ///
+    /// Modifying a value in place doesn't bump the generation of its slot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// let (handle, _) = map.insert(MyKey::First, 1);
+    ///
+    /// if let Some(value) = map.get_mut(handle) {
+    ///     *value += 1;
+    /// }
+    ///
+    /// assert_eq!(map.get(handle), Some(&2));
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self, handle: Handle<K>) -> Option<&mut V> {
+        let slot = self.slots.get_mut(handle.key)?;
+
+        if slot.generation != handle.generation {
+            return None;
+        }
+
+        slot.value.as_mut()
+    }
+
+    /// Returns a reference to the value currently associated with the key,
+    /// regardless of its generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// map.insert(MyKey::First, 1);
+    ///
+    /// assert_eq!(map.get_key(MyKey::First), Some(&1));
+    /// assert_eq!(map.get_key(MyKey::Second), None);
+    /// ```
+    #[inline]
+    pub fn get_key(&self, key: K) -> Option<&V> {
+        self.slots.get(key)?.value.as_ref()
+    }
+
+    /// Removes the value the handle refers to, or returns `None` without
+    /// modifying the map if the handle is stale.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// let (a, _) = map.insert(MyKey::First, 1);
+    /// let (b, _) = map.insert(MyKey::First, 2);
+    ///
+    /// assert_eq!(map.remove(a), None);
+    /// assert_eq!(map.remove(b), Some(2));
+    /// assert_eq!(map.remove(b), None);
+    /// ```
+    #[inline]
+    pub fn remove(&mut self, handle: Handle<K>) -> Option<V> {
+        if !self.is_valid(handle) {
+            return None;
+        }
+
+        self.remove_key(handle.key)
+    }
+
+    /// Removes the value currently associated with the key, regardless of its
+    /// generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Clone, Copy, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// map.insert(MyKey::First, 1);
+    ///
+    /// assert_eq!(map.remove_key(MyKey::First), Some(1));
+    /// assert_eq!(map.remove_key(MyKey::First), None);
+    /// ```
+    pub fn remove_key(&mut self, key: K) -> Option<V> {
+        let slot = self.slots.get_mut(key)?;
+        let value = slot.value.take()?;
+        slot.generation = Generation(slot.generation.0.wrapping_add(1));
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Iterate over handles to every value in the map together with the
+    /// values, in the order of their keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_map::{Key, VersionedMap};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    /// enum MyKey {
+    ///     First,
+    ///     Second,
+    /// }
+    ///
+    /// let mut map = VersionedMap::new();
+    /// map.insert(MyKey::Second, 2);
+    /// let (first, _) = map.insert(MyKey::First, 1);
+    /// map.remove_key(MyKey::Second);
+    ///
+    /// assert!(map.iter().eq([(first, &1)]));
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            iter: self.slots.iter(),
+        }
+    }
+}
+
+impl<K, V> Default for VersionedMap<K, V>
+where
+    K: Key,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> fmt::Debug for VersionedMap<K, V>
+where
+    K: Key + fmt::Debug,
+    V: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|(handle, value)| (handle.key, value)))
+            .finish()
+    }
+}
+
+/// An iterator over the handles and values of a [`VersionedMap`].
+///
+/// This is created by [`VersionedMap::iter`].
+pub struct Iter<'a, K, V>
+where
+    K: 'a + Key,
+    V: 'a,
+{
+    iter: map::Iter<'a, K, Slot<V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V>
+where
+    K: 'a + Key,
+    V: 'a,
+{
+    type Item = (Handle<K>, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for (key, slot) in self.iter.by_ref() {
+            let Some(value) = &slot.value else {
+                continue;
+            };
+
+            let handle = Handle {
+                key,
+                generation: slot.generation,
+            };
+
+            return Some((handle, value));
+        }
+
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
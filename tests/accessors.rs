@@ -0,0 +1,39 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(accessors)]
+enum Part {
+    Head,
+    Body,
+    Type,
+    Limb(bool),
+    Finger(Option<u8>),
+}
+
+#[test]
+fn accessors() {
+    let mut map = Map::new();
+
+    assert_eq!(map.set_head(1), None);
+    assert_eq!(map.set_head(2), Some(1));
+    assert_eq!(map.set_type(3), None);
+    assert_eq!(map.set_limb(true, 4), None);
+    assert_eq!(map.set_finger(Some(2), 5), None);
+
+    assert_eq!(map.head(), Some(&2));
+    assert_eq!(map.body(), None);
+    assert_eq!(map.r#type(), Some(&3));
+    assert_eq!(map.limb(true), Some(&4));
+    assert_eq!(map.limb(false), None);
+    assert_eq!(map.finger(Some(2)), Some(&5));
+    assert_eq!(map.finger(None), None);
+
+    assert_eq!(map.body_mut(), None);
+
+    map.insert(Part::Body, 6);
+    *map.body_mut().unwrap() += 1;
+    assert_eq!(map.get(Part::Body), Some(&7));
+    assert_eq!(map.get(Part::Limb(true)), Some(&4));
+}
@@ -0,0 +1,64 @@
+use core::mem::{align_of, size_of};
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(align = 64)]
+enum Unit {
+    First,
+    Second,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(align = 64)]
+enum Single {
+    Only,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(align = 128, bitset)]
+enum Bits {
+    First,
+    Second,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(align = 64)]
+enum Composite {
+    First(bool),
+    Second,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(align = 32, transparent)]
+struct Wrapper(Unit);
+
+#[test]
+fn alignment() {
+    assert_eq!(align_of::<Map<Unit, u32>>(), 64);
+    assert_eq!(size_of::<Map<Unit, u32>>(), 64);
+    assert_eq!(align_of::<Set<Unit>>(), 64);
+    assert_eq!(align_of::<Map<Single, u32>>(), 64);
+    assert_eq!(align_of::<Set<Single>>(), 64);
+    assert_eq!(align_of::<Set<Bits>>(), 128);
+    assert_eq!(size_of::<[Set<Bits>; 2]>(), 256);
+    assert_eq!(align_of::<Map<Composite, u32>>(), 64);
+    assert_eq!(align_of::<Set<Composite>>(), 64);
+    assert_eq!(align_of::<Map<Wrapper, u32>>(), 64);
+    assert_eq!(align_of::<Set<Wrapper>>(), 64);
+}
+
+#[test]
+fn behavior() {
+    let mut map = Map::new();
+    map.insert(Composite::First(true), 1);
+    map.insert(Composite::Second, 2);
+    assert!(map
+        .iter()
+        .eq([(Composite::First(true), &1), (Composite::Second, &2)]));
+
+    let mut set = Set::new();
+    set.insert(Bits::Second);
+    assert_eq!(set.as_raw(), 0b10);
+    assert!(set.iter().eq([Bits::Second]));
+}
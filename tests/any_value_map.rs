@@ -0,0 +1,113 @@
+use fixed_map::{AnyValueMap, Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum WindowSetting {
+    #[key(value = "(u32, u32)")]
+    Size,
+    #[key(value = "String")]
+    Title,
+    #[key(value = "Vec<&'static str>")]
+    Tags,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum HTTPHeader {
+    #[key(value = "u64")]
+    ContentLength,
+    #[key(value = "&'static str")]
+    ContentType,
+}
+
+/// An existing item with the name the marker module would get by default.
+mod direction {
+    pub(crate) const NORTH: &str = "north";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(value_module = direction_value)]
+enum Direction {
+    #[key(value = "u32")]
+    North,
+    #[key(value = "&'static str")]
+    South,
+}
+
+#[test]
+fn typed_access() {
+    let mut map = AnyValueMap::new();
+    assert!(map.is_empty());
+
+    assert_eq!(map.insert::<window_setting::Size>((800, 600)), None);
+    assert_eq!(
+        map.insert::<window_setting::Title>(String::from("Main")),
+        None
+    );
+    assert_eq!(
+        map.insert::<window_setting::Size>((1024, 768)),
+        Some((800, 600))
+    );
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get::<window_setting::Size>(), Some(&(1024, 768)));
+    assert_eq!(map.get::<window_setting::Tags>(), None);
+
+    map.get_mut::<window_setting::Title>()
+        .unwrap()
+        .push_str(" Window");
+
+    assert_eq!(
+        map.get::<window_setting::Title>().map(String::as_str),
+        Some("Main Window")
+    );
+
+    assert!(map.keys().eq([WindowSetting::Size, WindowSetting::Title]));
+
+    assert_eq!(map.remove::<window_setting::Size>(), Some((1024, 768)));
+    assert!(!map.contains_key(WindowSetting::Size));
+    assert!(map.remove_key(WindowSetting::Title));
+    assert!(map.is_empty());
+}
+
+#[test]
+fn traits() {
+    let mut a = AnyValueMap::new();
+    a.insert::<window_setting::Tags>(vec!["a", "b"]);
+    a.insert::<window_setting::Size>((1, 2));
+
+    let mut b = a.clone();
+    assert_eq!(a, b);
+
+    b.get_mut::<window_setting::Tags>().unwrap().clear();
+    assert_ne!(a, b);
+
+    assert_eq!(format!("{:?}", a), "{Size: (1, 2), Tags: [\"a\", \"b\"]}");
+
+    a.clear();
+    assert_eq!(a, AnyValueMap::default());
+}
+
+#[test]
+fn alongside_storage() {
+    let mut map = AnyValueMap::new();
+    map.insert::<http_header::ContentLength>(42);
+    map.insert::<http_header::ContentType>("text/plain");
+
+    assert_eq!(map.get::<http_header::ContentLength>(), Some(&42));
+    assert_eq!(map.get::<http_header::ContentType>(), Some(&"text/plain"));
+
+    let mut other = Map::new();
+    other.insert(HTTPHeader::ContentType, 1);
+    assert!(other.keys().eq([HTTPHeader::ContentType]));
+}
+
+#[test]
+fn value_module() {
+    let mut map = AnyValueMap::new();
+    map.insert::<direction_value::North>(10);
+    map.insert::<direction_value::South>(direction::NORTH);
+
+    assert_eq!(map.get::<direction_value::North>(), Some(&10));
+    assert_eq!(map.get::<direction_value::South>(), Some(&"north"));
+    assert!(map.keys().eq([Direction::North, Direction::South]));
+}
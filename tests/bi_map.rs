@@ -0,0 +1,70 @@
+use fixed_map::{BiMap, Key};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum Internal {
+    Ok,
+    Moved(bool),
+    Missing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum Wire {
+    Code200,
+    Code301,
+    Code308,
+    Code404,
+}
+
+fn assert_synced(map: &BiMap<Internal, Wire>) {
+    assert_eq!(map.left().len(), map.right().len());
+
+    for (left, right) in map {
+        assert_eq!(map.get_by_right(right), Some(&left));
+    }
+}
+
+#[test]
+fn bijection() {
+    let mut map = BiMap::new();
+    map.insert(Internal::Ok, Wire::Code200);
+    map.insert(Internal::Moved(false), Wire::Code301);
+    map.insert(Internal::Moved(true), Wire::Code308);
+    assert_synced(&map);
+
+    assert_eq!(
+        map.insert(Internal::Missing, Wire::Code301),
+        (None, Some(Internal::Moved(false)))
+    );
+    assert_synced(&map);
+    assert!(!map.contains_left(Internal::Moved(false)));
+
+    assert_eq!(
+        map.insert(Internal::Ok, Wire::Code308),
+        (Some(Wire::Code200), Some(Internal::Moved(true)))
+    );
+    assert_synced(&map);
+
+    assert!(map.iter().eq([
+        (Internal::Ok, Wire::Code308),
+        (Internal::Missing, Wire::Code301)
+    ]));
+
+    assert_eq!(map.remove_by_left(Internal::Ok), Some(Wire::Code308));
+    assert_eq!(map.remove_by_right(Wire::Code404), None);
+    assert_eq!(map.remove_by_right(Wire::Code301), Some(Internal::Missing));
+    assert_synced(&map);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn reinsert_same_pair() {
+    let mut map = BiMap::new();
+    map.insert(Internal::Ok, Wire::Code200);
+
+    assert_eq!(
+        map.insert(Internal::Ok, Wire::Code200),
+        (Some(Wire::Code200), None)
+    );
+    assert_eq!(map.len(), 1);
+    assert_synced(&map);
+}
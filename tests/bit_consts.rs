@@ -0,0 +1,25 @@
+use fixed_map::raw::BitIndex;
+use fixed_map::{Key, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset, bit_consts)]
+enum HttpMethod {
+    Get,
+    Post,
+    Delete,
+}
+
+#[test]
+fn bit_consts() {
+    use self::http_method_bits::*;
+
+    assert_eq!(HTTP_METHOD_GET_BIT, 1 << HttpMethod::Get.to_bit_index());
+    assert_eq!(HTTP_METHOD_POST_BIT, 1 << HttpMethod::Post.to_bit_index());
+    assert_eq!(
+        HTTP_METHOD_DELETE_BIT,
+        1 << HttpMethod::Delete.to_bit_index()
+    );
+
+    let set = Set::from([HttpMethod::Get, HttpMethod::Delete]);
+    assert_eq!(set.as_raw(), HTTP_METHOD_GET_BIT | HTTP_METHOD_DELETE_BIT);
+}
@@ -0,0 +1,45 @@
+use bitflags::bitflags;
+use fixed_map::{Key, Set};
+
+bitflags! {
+    struct Permissions: u32 {
+        const READ = 1 << 0;
+        const WRITE = 1 << 1;
+        const EXECUTE = 1 << 2;
+        const SET_UID = 1 << 3;
+        const LEGACY = 1 << 16;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset, bitflags = Permissions)]
+enum Permission {
+    Read,
+    Write,
+    Execute,
+    SetUid,
+}
+
+#[test]
+fn round_trip() {
+    let set = Set::from([Permission::Read, Permission::SetUid]);
+    let flags = Permissions::from(set);
+    assert_eq!(flags, Permissions::READ | Permissions::SET_UID);
+    assert_eq!(Set::from(flags), set);
+
+    assert_eq!(
+        Permissions::from(Set::<Permission>::new()),
+        Permissions::empty()
+    );
+    assert!(Set::<Permission>::from(Permissions::empty()).is_empty());
+}
+
+#[test]
+fn unknown_flags_are_dropped() {
+    let set = Set::from(Permissions::all());
+    assert_eq!(set.len(), 4);
+    assert_eq!(
+        Permissions::from(set),
+        Permissions::all() - Permissions::LEGACY
+    );
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Clone, Copy, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Clone, Copy, Key)]
+enum Single {
+    Only,
+}
+
+#[derive(Clone, Copy, Key)]
+#[key(transparent)]
+struct Wrapper(Part);
+
+#[derive(Clone, Copy, Key)]
+enum Composite {
+    Unit,
+    Nested(Part),
+    Optional(Option<bool>),
+    Wrapped(Wrapper),
+    Single(Single),
+    Unit2(()),
+    #[key(values(1, 2, 4))]
+    Mode(u8),
+}
+
+#[derive(Clone, Copy, Key)]
+enum Dynamic {
+    Unit,
+    Name(&'static str),
+}
+
+#[derive(Clone, Copy, Key)]
+enum NestedDynamic {
+    Unit,
+    Inner(Dynamic),
+}
+
+#[test]
+fn capacity() {
+    assert_eq!(Part::CAPACITY, Some(2));
+    assert_eq!(Single::CAPACITY, Some(1));
+    assert_eq!(Wrapper::CAPACITY, Some(2));
+    assert_eq!(Composite::CAPACITY, Some(1 + 2 + 3 + 2 + 1 + 1 + 3));
+    assert_eq!(Dynamic::CAPACITY, None);
+    assert_eq!(NestedDynamic::CAPACITY, None);
+    assert_eq!(<Option<Part>>::CAPACITY, Some(3));
+}
+
+#[test]
+fn map_and_set() {
+    const MAP: Option<usize> = Map::<Composite, u32>::CAPACITY;
+    const SET: Option<usize> = Set::<Composite>::CAPACITY;
+
+    assert_eq!(MAP, Composite::CAPACITY);
+    assert_eq!(SET, Composite::CAPACITY);
+    assert_eq!(Map::<Dynamic, u32>::CAPACITY, None);
+}
+
+#[test]
+fn fills_to_capacity() {
+    let mut set = Set::new();
+
+    for key in [
+        Composite::Unit,
+        Composite::Nested(Part::One),
+        Composite::Nested(Part::Two),
+        Composite::Optional(None),
+        Composite::Optional(Some(false)),
+        Composite::Optional(Some(true)),
+        Composite::Wrapped(Wrapper(Part::One)),
+        Composite::Wrapped(Wrapper(Part::Two)),
+        Composite::Single(Single::Only),
+        Composite::Unit2(()),
+        Composite::Mode(1),
+        Composite::Mode(2),
+        Composite::Mode(4),
+    ] {
+        set.insert(key);
+    }
+
+    assert_eq!(Some(set.len()), Set::<Composite>::CAPACITY);
+}
@@ -0,0 +1,92 @@
+use fixed_map::{CellMap, Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    First,
+    Second(bool),
+    Third,
+}
+
+#[test]
+fn len_tracks_occupied_slots() {
+    let mut map = CellMap::new();
+    map.reserve(MyKey::First);
+    map.reserve(MyKey::Second(true));
+    assert!(map.is_empty());
+
+    let shared = &map;
+    assert_eq!(shared.replace_value(MyKey::First, 1), Ok(None));
+    assert_eq!(shared.replace_value(MyKey::First, 2), Ok(Some(1)));
+    assert_eq!(shared.len(), 1);
+
+    assert_eq!(shared.take(MyKey::First), Some(2));
+    assert_eq!(shared.take(MyKey::First), None);
+    assert!(shared.is_empty());
+
+    assert_eq!(map.insert(MyKey::Second(true), 4), None);
+    assert_eq!(map.insert(MyKey::Second(true), 5), Some(4));
+    assert_eq!(map.insert(MyKey::Third, 6), None);
+    assert_eq!(map.len(), 2);
+
+    let copy = map.clone();
+    assert_eq!(copy.len(), 2);
+
+    assert_eq!(map.remove(MyKey::First), None);
+    assert_eq!(map.remove(MyKey::Third), Some(6));
+    assert_eq!(map.len(), 1);
+
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(copy.len(), 2);
+}
+
+#[test]
+fn from_map() {
+    let map = CellMap::from(Map::from_iter([(MyKey::First, 1), (MyKey::Third, 3)]));
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.take(MyKey::Third), Some(3));
+    assert_eq!(map.len(), 1);
+    assert!(map.into_map().into_iter().eq([(MyKey::First, 1)]));
+}
+
+#[test]
+fn fixed_slots_without_reserve() {
+    let map = CellMap::default();
+    assert!(map.has_slot(MyKey::First));
+    assert!(map.has_slot(MyKey::Second(false)));
+    assert!(map.has_slot(MyKey::Third));
+
+    assert_eq!(map.replace_value(MyKey::Third, 3), Ok(None));
+    assert_eq!(map.replace_value(MyKey::Second(false), 2), Ok(None));
+    assert_eq!(map.len(), 2);
+    assert!(map
+        .iter()
+        .eq([(MyKey::Second(false), 2), (MyKey::Third, 3)]));
+
+    let mut map = map;
+    assert_eq!(map.remove(MyKey::Third), Some(3));
+    map.clear();
+    assert!(map.has_slot(MyKey::Third));
+    assert_eq!(map.replace_value(MyKey::Third, 4), Ok(None));
+}
+
+#[cfg(feature = "hashbrown")]
+#[test]
+fn dynamic_slots_need_reserve() {
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    enum Dynamic {
+        First,
+        Second(u32),
+    }
+
+    let mut map = CellMap::new();
+    assert_eq!(map.replace_value(Dynamic::First, 1), Ok(None));
+    assert_eq!(map.replace_value(Dynamic::Second(2), 2), Err(2));
+
+    map.reserve(Dynamic::Second(2));
+    assert_eq!(map.replace_value(Dynamic::Second(2), 2), Ok(None));
+
+    assert_eq!(map.remove(Dynamic::Second(2)), Some(2));
+    assert!(!map.has_slot(Dynamic::Second(2)));
+    assert_eq!(map.len(), 1);
+}
@@ -0,0 +1,163 @@
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Unit {
+    First,
+    #[cfg(any())]
+    Disabled,
+    Second,
+    #[cfg(test)]
+    Enabled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset, variants(First, Disabled, Second))]
+enum Bits {
+    First,
+    #[cfg(any())]
+    Disabled,
+    Second,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(variants(First, Disabled, DisabledSimple, Second, Enabled))]
+enum Composite {
+    First(Unit),
+    #[cfg(any())]
+    Disabled(bool),
+    #[cfg(any())]
+    DisabledSimple,
+    Second,
+    #[cfg(test)]
+    Enabled(bool),
+}
+
+#[test]
+fn unit_variants() {
+    let mut map = Map::new();
+    map.insert(Unit::First, 1);
+    map.insert(Unit::Second, 2);
+    map.insert(Unit::Enabled, 3);
+
+    assert_eq!(map.len(), 3);
+    assert!(map
+        .iter()
+        .eq([(Unit::First, &1), (Unit::Second, &2), (Unit::Enabled, &3)]));
+    assert!(map.keys().eq([Unit::First, Unit::Second, Unit::Enabled]));
+    assert!(map.values().copied().eq([1, 2, 3]));
+
+    map.retain(|k, _| k != Unit::Second);
+    assert!(map.into_iter().eq([(Unit::First, 1), (Unit::Enabled, 3)]));
+
+    let set = Set::from([Unit::Second, Unit::Enabled]);
+    assert!(set.iter().eq([Unit::Second, Unit::Enabled]));
+}
+
+#[test]
+fn bitset_variants() {
+    let mut set = Set::from([Bits::First, Bits::Second]);
+    assert!(set.iter().eq([Bits::First, Bits::Second]));
+    set.retain(|k| k == Bits::Second);
+    assert!(set.into_iter().eq([Bits::Second]));
+}
+
+#[test]
+fn composite_variants() {
+    let mut map = Map::new();
+    map.insert(Composite::First(Unit::Enabled), 1);
+    map.insert(Composite::Second, 2);
+    map.insert(Composite::Enabled(true), 3);
+
+    assert_eq!(map.len(), 3);
+    assert!(map.iter().eq([
+        (Composite::First(Unit::Enabled), &1),
+        (Composite::Second, &2),
+        (Composite::Enabled(true), &3)
+    ]));
+    assert!(map.iter().rev().eq([
+        (Composite::Enabled(true), &3),
+        (Composite::Second, &2),
+        (Composite::First(Unit::Enabled), &1)
+    ]));
+
+    *map.entry(Composite::Enabled(false)).or_default() += 4;
+    assert_eq!(map.get(Composite::Enabled(false)), Some(&4));
+
+    map.retain(|k, _| k != Composite::Second);
+    assert!(map.keys().eq([
+        Composite::First(Unit::Enabled),
+        Composite::Enabled(true),
+        Composite::Enabled(false)
+    ]));
+
+    let set = Set::from([Composite::Second, Composite::Enabled(false)]);
+    assert!(set
+        .iter()
+        .eq([Composite::Second, Composite::Enabled(false)]));
+}
+
+#[test]
+fn bit_index() {
+    use fixed_map::raw::BitIndex;
+
+    assert_eq!(Bits::First.to_bit_index(), 0);
+    assert_eq!(Bits::Second.to_bit_index(), 1);
+    assert_eq!(Bits::from_bit_index(1), Some(Bits::Second));
+    assert_eq!(Bits::from_bit_index(2), None);
+    assert_eq!(Set::from([Bits::Second]).as_raw(), 0b10);
+    // Reserved slots are not used by the bit order.
+    assert_eq!(fixed_map::schema::slot(Bits::Second), Some(2));
+}
+
+#[test]
+fn key_index() {
+    use fixed_map::raw::KeyIndex;
+
+    assert_eq!(Unit::LEN, 3);
+    assert_eq!(Unit::Enabled.to_index(), 2);
+    assert_eq!(Unit::from_index(3), None);
+}
+
+#[test]
+fn retain_indexed() {
+    use fixed_map::raw::BitIndex;
+
+    let mut set = Set::from([Bits::First, Bits::Second]);
+    let mut seen = Vec::new();
+
+    set.retain_indexed(|index, key| {
+        assert_eq!(index, key.to_bit_index());
+        seen.push(index);
+        index != 0
+    });
+
+    assert_eq!(seen, [0, 1]);
+    assert!(set.iter().eq([Bits::Second]));
+}
+
+#[test]
+fn reserved_slots() {
+    use fixed_map::schema;
+
+    let enabled: Vec<_> = Composite::KEY_VARIANTS
+        .iter()
+        .map(|variant| (variant.name(), variant.is_enabled()))
+        .collect();
+
+    assert_eq!(
+        enabled,
+        [
+            ("First", true),
+            ("Disabled", false),
+            ("DisabledSimple", false),
+            ("Second", true),
+            ("Enabled", true)
+        ]
+    );
+
+    assert_eq!(schema::slot(Composite::First(Unit::Second)), Some(0));
+    assert_eq!(schema::slot(Composite::Second), Some(3));
+    assert_eq!(schema::slot(Composite::Enabled(false)), Some(4));
+    assert_eq!(schema::slot(Bits::Second), Some(2));
+    assert_eq!(schema::slot(Unit::Second), Some(1));
+}
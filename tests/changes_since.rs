@@ -0,0 +1,80 @@
+use fixed_map::{Key, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum Flag {
+    A,
+    B,
+    C,
+    D,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    A,
+    B,
+    C,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Composite {
+    Flag(Flag),
+    Part(Part),
+    Unit,
+}
+
+#[test]
+fn bitset() {
+    let previous = Set::from([Flag::A, Flag::B]);
+    let current = Set::from([Flag::B, Flag::C, Flag::D]);
+
+    let (added, removed) = current.changes_since(&previous);
+    assert_eq!(added, Set::from([Flag::C, Flag::D]));
+    assert_eq!(removed, Set::from([Flag::A]));
+
+    // The changes reconstruct the current set from the previous one.
+    let mut rebuilt = previous;
+    rebuilt.union_with(&added);
+    rebuilt.subtract(&removed);
+    assert_eq!(rebuilt, current);
+}
+
+#[test]
+fn array() {
+    let previous = Set::from([Part::A, Part::C]);
+    let current = Set::from([Part::B, Part::C]);
+
+    let (added, removed) = current.changes_since(&previous);
+    assert_eq!(added, Set::from([Part::B]));
+    assert_eq!(removed, Set::from([Part::A]));
+}
+
+#[test]
+fn composite() {
+    let previous = Set::from([Composite::Flag(Flag::A), Composite::Part(Part::B)]);
+    let current = Set::from([
+        Composite::Flag(Flag::A),
+        Composite::Flag(Flag::D),
+        Composite::Unit,
+    ]);
+
+    let (added, removed) = current.changes_since(&previous);
+    assert_eq!(
+        added,
+        Set::from([Composite::Flag(Flag::D), Composite::Unit])
+    );
+    assert_eq!(removed, Set::from([Composite::Part(Part::B)]));
+}
+
+#[test]
+fn unchanged() {
+    let set = Set::from([Part::A, Part::B]);
+
+    let (added, removed) = set.changes_since(&set);
+    assert!(added.is_empty());
+    assert!(removed.is_empty());
+
+    let (added, removed) = set.changes_since(&Set::new());
+    assert_eq!(added, set);
+    assert!(removed.is_empty());
+}
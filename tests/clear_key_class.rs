@@ -0,0 +1,86 @@
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(transparent)]
+struct Wrapper(Part);
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Unit,
+    Nested(Part),
+    Option(Option<bool>),
+    Number(u32),
+    #[key(lazy)]
+    Lazy(u32),
+}
+
+#[test]
+fn derived() {
+    let mut map = Map::new();
+    map.insert(MyKey::Unit, 1);
+    map.insert(MyKey::Nested(Part::One), 2);
+    map.insert(MyKey::Nested(Part::Two), 3);
+    map.insert(MyKey::Option(None), 4);
+    map.insert(MyKey::Number(5), 5);
+    map.insert(MyKey::Lazy(6), 6);
+
+    map.clear_key_class(MyKey::Nested(Part::One));
+    assert_eq!(map.get(MyKey::Nested(Part::Two)), None);
+
+    map.clear_key_class(MyKey::Unit);
+    map.clear_key_class(MyKey::Option(Some(true)));
+    map.clear_key_class(MyKey::Lazy(0));
+    assert!(map.keys().eq([MyKey::Number(5)]));
+}
+
+#[test]
+fn keeps_capacity() {
+    let mut map = Map::new();
+
+    for n in 0..64 {
+        map.insert(MyKey::Number(n), n);
+    }
+
+    let capacity = map.stats().dynamic_capacity;
+    map.clear_key_class(MyKey::Number(0));
+    assert!(map.is_empty());
+    assert_eq!(map.stats().dynamic_capacity, capacity);
+}
+
+#[test]
+fn clear_keeps_capacity() {
+    let mut map = Map::new();
+
+    for n in 0..64 {
+        map.insert(MyKey::Number(n), n);
+        map.insert(MyKey::Lazy(n), n);
+    }
+
+    let capacity = map.stats().dynamic_capacity;
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(map.stats().dynamic_capacity, capacity);
+}
+
+#[test]
+fn builtin() {
+    let mut map = Map::from_iter([(Some(true), 1), (Some(false), 2), (None, 3)]);
+    map.clear_key_class(Some(false));
+    assert!(map.keys().eq([None]));
+
+    let mut map = Map::from_iter([(true, 1), (false, 2)]);
+    map.clear_key_class(true);
+    assert!(map.keys().eq([false]));
+
+    let mut map = Map::from_iter([(Wrapper(Part::One), 1), (Wrapper(Part::Two), 2)]);
+    map.clear_key_class(Wrapper(Part::Two));
+    assert!(map.keys().eq([Wrapper(Part::One)]));
+}
@@ -0,0 +1,35 @@
+//! Keys shared between tests, covering every kind of variant a composite key
+//! supports.
+
+#![allow(dead_code)]
+
+use fixed_map::Key;
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+pub enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+pub enum Single {
+    Only,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(transparent)]
+pub struct Wrapper(pub Part);
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+pub enum MyKey {
+    Unit,
+    Dynamic(u32),
+    Nested(Part),
+    Optional(Option<bool>),
+    Wrapped(Wrapper),
+    Single(Single),
+    #[key(values(1, 2, 4))]
+    Mode(u8),
+    #[key(lazy)]
+    Lazy(Part),
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, Key)]
+enum MyKey {
+    First(bool),
+    Second,
+}
+
+#[test]
+fn empty() {
+    let map = Map::<MyKey, u32>::new();
+    assert_eq!(format!("{map:#?}"), "{}");
+
+    let set = Set::<MyKey>::new();
+    assert_eq!(format!("{set:#?}"), "{}");
+}
+
+#[test]
+fn pretty_values() {
+    let mut map = Map::new();
+    map.insert(MyKey::First(false), (1, 2));
+
+    let expected = r#"{
+    First: {
+        First(false): (
+            1,
+            2,
+        ),
+    },
+}"#;
+
+    assert_eq!(format!("{map:#?}"), expected);
+}
+
+#[test]
+fn primitive_keys() {
+    let mut set = Set::new();
+    set.insert(Some(true));
+    set.insert(None);
+
+    let expected = r#"{
+    Some: {
+        Some(true),
+    },
+    None: {
+        None,
+    },
+}"#;
+
+    assert_eq!(format!("{set:#?}"), expected);
+}
+
+#[test]
+fn fmt_key_without_debug() {
+    use core::fmt;
+
+    #[derive(Clone, Copy, Key)]
+    enum Inner {
+        Leaf,
+    }
+
+    #[derive(Clone, Copy, Key)]
+    #[key(transparent)]
+    struct Wrapper(Inner);
+
+    #[derive(Clone, Copy, Key)]
+    enum Outer {
+        Unit,
+        Nested(Option<Wrapper>),
+        Flag(bool),
+        #[key(values(1, 2))]
+        Level(u8),
+        Number(u32),
+    }
+
+    struct Name<K>(K);
+
+    impl<K: Key> fmt::Display for Name<K> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.fmt_key(f)
+        }
+    }
+
+    assert_eq!(Name(Outer::Unit).to_string(), "Unit");
+    assert_eq!(
+        Name(Outer::Nested(Some(Wrapper(Inner::Leaf)))).to_string(),
+        "Nested(Some(Leaf))"
+    );
+    assert_eq!(Name(Outer::Nested(None)).to_string(), "Nested(None)");
+    assert_eq!(Name(Outer::Flag(true)).to_string(), "Flag(true)");
+    assert_eq!(Name(Outer::Level(2)).to_string(), "Level(2)");
+    assert_eq!(Name(Outer::Level(3)).to_string(), "Level(..)");
+    assert_eq!(Name(Outer::Number(42)).to_string(), "Number(42)");
+    assert_eq!(Name(()).to_string(), "()");
+}
@@ -0,0 +1,78 @@
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+mod common;
+
+use fixed_map::Map;
+
+use self::common::{MyKey, Part, Single, Wrapper};
+
+const KEYS: [MyKey; 9] = [
+    MyKey::Unit,
+    MyKey::Dynamic(7),
+    MyKey::Nested(Part::Two),
+    MyKey::Optional(Some(false)),
+    MyKey::Optional(None),
+    MyKey::Wrapped(Wrapper(Part::One)),
+    MyKey::Single(Single::Only),
+    MyKey::Mode(4),
+    MyKey::Lazy(Part::One),
+];
+
+fn populated() -> Map<MyKey, usize> {
+    let mut map = Map::new();
+
+    for (n, key) in KEYS.into_iter().enumerate().rev() {
+        map.insert(key, n);
+    }
+
+    map
+}
+
+#[test]
+fn drain_all() {
+    let mut map = populated();
+    let stats = map.stats();
+
+    assert!(map
+        .drain()
+        .eq(KEYS.into_iter().enumerate().map(|(n, k)| (k, n))));
+    assert!(map.is_empty());
+    assert!(map.drain().next().is_none());
+
+    // Both the dynamic storage and the lazy branch stay allocated.
+    assert_eq!(map.stats().dynamic_capacity, stats.dynamic_capacity);
+    assert_eq!(map.stats().capacity, stats.capacity);
+    map.debug_assert_invariants();
+}
+
+#[test]
+fn drop_partially_consumed() {
+    let mut map = populated();
+
+    let mut drain = map.drain();
+    assert_eq!(drain.next(), Some((MyKey::Unit, 0)));
+    assert_eq!(drain.next(), Some((MyKey::Dynamic(7), 1)));
+    drop(drain);
+
+    assert!(map.is_empty());
+    assert_eq!(map.iter().count(), 0);
+    map.debug_assert_invariants();
+
+    map.insert(MyKey::Nested(Part::One), 1);
+    assert!(map.iter().eq([(MyKey::Nested(Part::One), &1)]));
+}
+
+#[test]
+fn built_in_keys() {
+    let mut map = Map::from_iter([(Some(true), 1), (None, 2)]);
+    assert!(map.drain().eq([(Some(true), 1), (None, 2)]));
+    assert!(map.is_empty());
+
+    let mut map = Map::from_iter([((), 1)]);
+    assert!(map.drain().eq([((), 1)]));
+    assert!(map.is_empty());
+
+    let mut map = Map::from_iter([(Part::Two, 2), (Part::One, 1)]);
+    assert!(map.drain().eq([(Part::One, 1), (Part::Two, 2)]));
+    assert!(map.is_empty());
+}
@@ -0,0 +1,114 @@
+use fixed_map::map::{EntriesStorage, Entry, OccupiedEntry, VacantEntry};
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum Unit {
+    First,
+    Second,
+    Third,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum Only {
+    Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum Composite {
+    First,
+    Unit(Unit),
+    Flag(Option<bool>),
+    #[key(values(1, 2))]
+    Mode(u8),
+    Only(Only),
+}
+
+fn keys<K, V>(map: &mut Map<K, V>) -> Vec<(K, bool)>
+where
+    K: Key<MapStorage<V>: EntriesStorage<K, V>>,
+{
+    map.entries_mut()
+        .map(|entry| match entry {
+            Entry::Occupied(entry) => (entry.key(), true),
+            Entry::Vacant(entry) => (entry.key(), false),
+        })
+        .collect()
+}
+
+#[test]
+fn unit() {
+    let mut map = Map::new();
+    map.insert(Unit::Second, 2);
+
+    assert_eq!(
+        keys(&mut map),
+        [
+            (Unit::First, false),
+            (Unit::Second, true),
+            (Unit::Third, false)
+        ]
+    );
+
+    for entry in map.entries_mut() {
+        match entry {
+            Entry::Occupied(entry) => {
+                entry.remove();
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(1);
+            }
+        }
+    }
+
+    assert!(map.iter().eq([(Unit::First, &1), (Unit::Third, &1)]));
+}
+
+#[test]
+fn composite() {
+    let mut map = Map::new();
+    map.insert(Composite::Unit(Unit::Third), 'a');
+    map.insert(Composite::Flag(None), 'b');
+    map.insert(Composite::Mode(2), 'c');
+
+    assert_eq!(
+        keys(&mut map),
+        [
+            (Composite::First, false),
+            (Composite::Unit(Unit::First), false),
+            (Composite::Unit(Unit::Second), false),
+            (Composite::Unit(Unit::Third), true),
+            (Composite::Flag(Some(true)), false),
+            (Composite::Flag(Some(false)), false),
+            (Composite::Flag(None), true),
+            (Composite::Mode(1), false),
+            (Composite::Mode(2), true),
+            (Composite::Only(Only::Value), false),
+        ]
+    );
+
+    for entry in map.entries_mut() {
+        if let Entry::Vacant(entry) = entry {
+            entry.insert('z');
+        }
+    }
+
+    assert_eq!(map.len(), 10);
+    assert_eq!(map.get(Composite::Unit(Unit::Third)), Some(&'a'));
+    assert_eq!(map.get(Composite::Flag(Some(false))), Some(&'z'));
+    assert_eq!(map.get(Composite::Only(Only::Value)), Some(&'z'));
+    map.debug_assert_invariants();
+}
+
+#[test]
+fn primitives() {
+    let mut map = Map::new();
+    map.insert(Some(false), 1);
+    assert_eq!(
+        keys(&mut map),
+        [(Some(true), false), (Some(false), true), (None, false)]
+    );
+
+    let mut map = Map::new();
+    map.insert((), 1);
+    assert_eq!(keys(&mut map), [((), true)]);
+}
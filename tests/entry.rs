@@ -87,3 +87,98 @@ fn compound() {
         &2
     );
 }
+
+#[test]
+fn nested_key_ref() {
+    use fixed_map::map::Entry;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    enum Inner {
+        First,
+        Second(Option<bool>),
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    #[key(transparent)]
+    struct Wrapper(Inner);
+
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    enum MyKey {
+        Simple,
+        Nested(Option<Inner>),
+        Wrapped(Wrapper),
+        Unit(()),
+    }
+
+    let keys = [
+        MyKey::Simple,
+        MyKey::Nested(None),
+        MyKey::Nested(Some(Inner::First)),
+        MyKey::Nested(Some(Inner::Second(None))),
+        MyKey::Nested(Some(Inner::Second(Some(true)))),
+        MyKey::Wrapped(Wrapper(Inner::Second(Some(false)))),
+        MyKey::Unit(()),
+    ];
+
+    let mut map: Map<MyKey, usize> = Map::new();
+
+    for (n, key) in keys.into_iter().enumerate() {
+        let entry = map.entry(key);
+        assert!(matches!(entry, Entry::Vacant(..)));
+        assert_eq!(entry.key(), key);
+        assert_eq!(entry.key_ref(), &key);
+        entry.or_insert(n);
+    }
+
+    for (n, key) in keys.into_iter().enumerate() {
+        let entry = map.entry(key);
+        assert!(matches!(entry, Entry::Occupied(..)));
+        assert_eq!(entry.key(), key);
+        assert_eq!(entry.key_ref(), &key);
+        assert_eq!(entry.or_default(), &n);
+    }
+}
+
+#[cfg(feature = "hashbrown")]
+#[test]
+fn entry_ref() {
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    enum Inner {
+        Name(&'static str),
+        Flag(Option<bool>),
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    #[key(transparent)]
+    struct Wrapper(Inner);
+
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    enum MyKey {
+        Simple,
+        Nested(Option<Inner>),
+        Wrapped(Wrapper),
+    }
+
+    let keys = [
+        MyKey::Simple,
+        MyKey::Nested(Some(Inner::Name("a"))),
+        MyKey::Nested(Some(Inner::Flag(None))),
+        MyKey::Wrapped(Wrapper(Inner::Name("b"))),
+        MyKey::Nested(Some(Inner::Name("a"))),
+        MyKey::Simple,
+    ];
+
+    let mut map: Map<MyKey, u32> = Map::new();
+
+    for key in &keys {
+        let entry = map.entry_ref(key);
+        assert_eq!(entry.key_ref(), key);
+        *entry.or_default() += 1;
+    }
+
+    assert_eq!(map.get(MyKey::Simple), Some(&2));
+    assert_eq!(map.get(MyKey::Nested(Some(Inner::Name("a")))), Some(&2));
+    assert_eq!(map.get(MyKey::Nested(Some(Inner::Flag(None)))), Some(&1));
+    assert_eq!(map.get(MyKey::Wrapped(Wrapper(Inner::Name("b")))), Some(&1));
+    assert_eq!(map.len(), 4);
+}
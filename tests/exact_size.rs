@@ -0,0 +1,36 @@
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum MyKey {
+    First,
+    Second(bool),
+    Third(Option<bool>),
+}
+
+#[test]
+fn exact_size() {
+    let mut map = Map::new();
+    map.insert(MyKey::First, 1);
+    map.insert(MyKey::Second(true), 2);
+    map.insert(MyKey::Third(Some(false)), 3);
+    map.insert(MyKey::Third(None), 4);
+
+    assert_eq!(map.iter().len(), 4);
+    assert_eq!(map.keys().len(), 4);
+    assert_eq!(map.values().len(), 4);
+
+    let mut keys = map.keys();
+    assert_eq!(keys.next(), Some(MyKey::First));
+    assert_eq!(keys.next_back(), Some(MyKey::Third(None)));
+    assert_eq!(keys.size_hint(), (2, Some(2)));
+    assert_eq!(keys.clone().count(), 2);
+    assert!(keys.eq([MyKey::Second(true), MyKey::Third(Some(false))]));
+
+    let mut values = map.values();
+    values.by_ref().for_each(drop);
+    assert_eq!(values.len(), 0);
+    assert_eq!(values.next(), None);
+
+    map.clear();
+    assert_eq!(map.iter().len(), 0);
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    First,
+    Dynamic(u32),
+    Nested(Part),
+}
+
+#[test]
+fn map_extend() {
+    let mut map = Map::from_iter([(MyKey::First, 1), (MyKey::Nested(Part::Two), 2)]);
+
+    map.extend(
+        [3, 4]
+            .into_iter()
+            .map(|n| (MyKey::Dynamic(n), n))
+            .chain([(MyKey::First, 10)]),
+    );
+
+    assert_eq!(map.len(), 4);
+    assert_eq!(map.get(MyKey::First), Some(&10));
+    assert_eq!(map.get(MyKey::Dynamic(3)), Some(&3));
+    assert_eq!(map.get(MyKey::Dynamic(4)), Some(&4));
+    assert_eq!(map.get(MyKey::Nested(Part::Two)), Some(&2));
+}
+
+#[test]
+fn map_extend_ref() {
+    let pairs = [(MyKey::Nested(Part::One), 1), (MyKey::First, 2)];
+
+    let mut map = Map::new();
+    map.extend(pairs.iter().map(|(k, v)| (k, v)));
+    map.extend([(&MyKey::First, &20)]);
+
+    assert!(map
+        .into_iter()
+        .eq([(MyKey::First, 20), (MyKey::Nested(Part::One), 1)]));
+}
+
+#[test]
+fn set_extend() {
+    let mut set = Set::from([MyKey::Nested(Part::Two)]);
+
+    set.extend(
+        (0..3)
+            .map(MyKey::Dynamic)
+            .filter(|k| *k != MyKey::Dynamic(1)),
+    );
+    set.extend(&[MyKey::First, MyKey::Nested(Part::Two)]);
+
+    assert_eq!(set.len(), 4);
+    assert!(set.contains(MyKey::First));
+    assert!(set.contains(MyKey::Dynamic(0)));
+    assert!(!set.contains(MyKey::Dynamic(1)));
+    assert!(set.contains(MyKey::Dynamic(2)));
+    assert!(set.contains(MyKey::Nested(Part::Two)));
+}
@@ -0,0 +1,133 @@
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+mod common;
+
+use fixed_map::Map;
+
+use self::common::{MyKey, Part, Single, Wrapper};
+
+const KEYS: [MyKey; 10] = [
+    MyKey::Unit,
+    MyKey::Dynamic(7),
+    MyKey::Nested(Part::One),
+    MyKey::Nested(Part::Two),
+    MyKey::Optional(Some(false)),
+    MyKey::Optional(None),
+    MyKey::Wrapped(Wrapper(Part::One)),
+    MyKey::Single(Single::Only),
+    MyKey::Mode(4),
+    MyKey::Lazy(Part::One),
+];
+
+fn populated() -> Map<MyKey, usize> {
+    let mut map = Map::new();
+
+    for (n, key) in KEYS.into_iter().enumerate().rev() {
+        map.insert(key, n);
+    }
+
+    map
+}
+
+#[test]
+fn extract_matching() {
+    let mut map = populated();
+    let mut visited = Vec::new();
+
+    let extracted = map
+        .extract_if(|k, v| {
+            visited.push(k);
+            *v % 2 == 0
+        })
+        .collect::<Vec<_>>();
+
+    // Every entry is visited exactly once, in iteration order.
+    assert_eq!(visited, KEYS);
+
+    let expected = KEYS.into_iter().enumerate().map(|(n, k)| (k, n));
+    assert!(extracted
+        .into_iter()
+        .eq(expected.clone().filter(|(_, n)| n % 2 == 0)));
+    assert!(map.into_iter().eq(expected.filter(|(_, n)| n % 2 != 0)));
+}
+
+#[test]
+fn modify_kept() {
+    let mut map = populated();
+
+    assert!(map
+        .extract_if(|_, v| {
+            *v *= 10;
+            false
+        })
+        .next()
+        .is_none());
+
+    assert!(map
+        .into_iter()
+        .eq(KEYS.into_iter().enumerate().map(|(n, k)| (k, n * 10))));
+}
+
+#[test]
+fn drop_partially_consumed() {
+    let mut map = populated();
+
+    let mut extract = map.extract_if(|_, _| true);
+    assert_eq!(extract.next(), Some((MyKey::Unit, 0)));
+    assert_eq!(extract.next(), Some((MyKey::Dynamic(7), 1)));
+    drop(extract);
+
+    // Entries which haven't been visited are kept.
+    assert!(map.iter().map(|(k, v)| (k, *v)).eq(KEYS
+        .into_iter()
+        .enumerate()
+        .map(|(n, k)| (k, n))
+        .skip(2)));
+    map.debug_assert_invariants();
+}
+
+#[test]
+fn dynamic_entries() {
+    let mut map = Map::new();
+
+    for n in 0..16 {
+        map.insert(MyKey::Dynamic(n), n);
+    }
+
+    let mut extracted = map
+        .extract_if(|k, _| matches!(k, MyKey::Dynamic(n) if n >= 8))
+        .collect::<Vec<_>>();
+    extracted.sort_by_key(|(_, v)| *v);
+
+    assert!(extracted
+        .into_iter()
+        .eq((8..16).map(|n| (MyKey::Dynamic(n), n))));
+    assert_eq!(map.len(), 8);
+
+    for n in 0..8 {
+        assert_eq!(map.get(MyKey::Dynamic(n)), Some(&n));
+    }
+
+    let mut extract = map.extract_if(|_, _| true);
+    assert!(extract.next().is_some());
+    drop(extract);
+    assert_eq!(map.len(), 7);
+    map.debug_assert_invariants();
+}
+
+#[test]
+fn built_in_keys() {
+    let mut map = Map::from_iter([(Some(true), 1), (Some(false), 2), (None, 3)]);
+    assert!(map
+        .extract_if(|_, v| *v != 2)
+        .eq([(Some(true), 1), (None, 3)]));
+    assert!(map.into_iter().eq([(Some(false), 2)]));
+
+    let mut map = Map::from_iter([((), 1)]);
+    assert!(map.extract_if(|_, _| true).eq([((), 1)]));
+    assert!(map.is_empty());
+
+    let mut map = Map::from_iter([(Part::Two, 2), (Part::One, 1)]);
+    assert!(map.extract_if(|k, _| k == Part::Two).eq([(Part::Two, 2)]));
+    assert!(map.into_iter().eq([(Part::One, 1)]));
+}
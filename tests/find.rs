@@ -0,0 +1,94 @@
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+use std::fmt::Debug;
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+    Three,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum Flags {
+    A,
+    B,
+    C,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Unit,
+    Nested(Part),
+    Optional(Option<bool>),
+    Dynamic(u32),
+    #[key(lazy)]
+    Lazy(bool),
+    Flags(Flags),
+    Last,
+}
+
+/// Check that searching agrees with searching through the iterators.
+fn check<K>(keys: &[K])
+where
+    K: Key + Debug + PartialEq,
+{
+    let map = keys
+        .iter()
+        .enumerate()
+        .map(|(n, &key)| (key, n))
+        .collect::<Map<_, _>>();
+    let set = keys.iter().copied().collect::<Set<_>>();
+
+    for (n, key) in map.keys().enumerate() {
+        assert_eq!(map.position(|k, _| k == key), Some(n));
+        assert_eq!(
+            map.find_map(|k, v| (k == key).then_some(*v)),
+            map.get(key).copied()
+        );
+        assert_eq!(set.find(|k| k == key), Some(key));
+    }
+
+    let expected = map.iter().find(|(_, v)| **v % 2 == 1).map(|(k, _)| k);
+    assert_eq!(map.find_map(|k, v| (*v % 2 == 1).then_some(k)), expected);
+
+    assert_eq!(map.position(|_, _| false), None);
+    assert_eq!(map.find_map(|_, _| None::<()>), None);
+    assert_eq!(set.find(|_| false), None);
+}
+
+#[test]
+fn find_unit() {
+    check(&[Part::Three, Part::One]);
+    check::<Part>(&[]);
+    check(&[Flags::C, Flags::A]);
+    check(&[Flags::B]);
+}
+
+#[test]
+fn find_composite() {
+    check(&[
+        MyKey::Last,
+        MyKey::Dynamic(4),
+        MyKey::Optional(None),
+        MyKey::Nested(Part::Three),
+        MyKey::Lazy(false),
+        MyKey::Flags(Flags::C),
+        MyKey::Unit,
+        MyKey::Nested(Part::One),
+        MyKey::Optional(Some(true)),
+    ]);
+    check(&[MyKey::Flags(Flags::A)]);
+    check::<MyKey>(&[]);
+}
+
+#[test]
+fn find_builtin() {
+    check(&[false, true]);
+    check(&[Some(Part::Two), None, Some(Part::One)]);
+    check(&[(), ()]);
+    check(&[3u32, 1, 2]);
+}
@@ -0,0 +1,98 @@
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+use std::fmt::Debug;
+
+use fixed_map::profile::Fast;
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+    Three,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Unit,
+    Nested(Part),
+    Optional(Option<bool>),
+    #[key(values(1, 2, 4))]
+    Mode(u8),
+    #[key(dynamic = "ordered")]
+    Name(&'static str),
+    #[key(lazy)]
+    Lazy(bool),
+    Last,
+}
+
+fn check<K>(keys: &[K])
+where
+    K: Key + Debug + PartialEq,
+{
+    let mut map = keys
+        .iter()
+        .enumerate()
+        .map(|(n, &key)| (key, n))
+        .collect::<Map<_, _>>();
+
+    assert_eq!(map.first_key_value(), map.iter().next());
+    assert_eq!(map.last_key_value(), map.iter().last());
+
+    let expected = map.iter().map(|(k, v)| (k, *v)).collect::<Vec<_>>();
+
+    let mut forward = map.iter().map(|(k, v)| (k, *v)).collect::<Map<_, _>>();
+    let mut popped = Vec::new();
+
+    while let Some(entry) = forward.pop_first() {
+        popped.push(entry);
+    }
+
+    assert_eq!(popped, expected);
+
+    let mut popped = Vec::new();
+
+    while let Some(entry) = map.pop_last() {
+        popped.push(entry);
+    }
+
+    popped.reverse();
+    assert_eq!(popped, expected);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn first_last_unit() {
+    check::<Part>(&[]);
+    check(&[Part::Two]);
+    check(&[Part::Three, Part::One]);
+    check(&[Fast(Part::Three), Fast(Part::Two)]);
+}
+
+#[test]
+fn first_last_composite() {
+    check::<MyKey>(&[]);
+    check(&[MyKey::Lazy(true)]);
+    check(&[
+        MyKey::Last,
+        MyKey::Name("b"),
+        MyKey::Mode(4),
+        MyKey::Nested(Part::Three),
+        MyKey::Optional(None),
+        MyKey::Name("a"),
+        MyKey::Lazy(false),
+        MyKey::Unit,
+        MyKey::Optional(Some(true)),
+        MyKey::Mode(1),
+    ]);
+}
+
+#[test]
+fn first_last_builtin() {
+    check(&[true, false]);
+    check(&[false]);
+    check(&[None, Some(Part::Two), Some(Part::One)]);
+    check(&[Some(true)]);
+    check(&[()]);
+    check(&[5u32]);
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Unit,
+    Nested(Part),
+    Number(u32),
+}
+
+#[test]
+fn from_keys() {
+    let keys = [MyKey::Number(3), MyKey::Unit, MyKey::Nested(Part::Two)];
+    let map = Map::from_keys(keys, |_| "value");
+
+    assert_eq!(map.len(), 3);
+
+    for key in keys {
+        assert_eq!(map.get(key), Some(&"value"));
+    }
+}
+
+#[test]
+fn from_keys_duplicates() {
+    let mut calls = 0;
+
+    let map = Map::from_keys([Part::One, Part::Two, Part::One], |key| {
+        calls += 1;
+        (key, calls)
+    });
+
+    assert_eq!(calls, 3);
+    assert!(map
+        .into_iter()
+        .eq([(Part::One, (Part::One, 3)), (Part::Two, (Part::Two, 2))]));
+}
+
+#[test]
+fn into_map_with() {
+    let set = Set::from([MyKey::Unit, MyKey::Nested(Part::One), MyKey::Number(7)]);
+
+    let map = set
+        .clone()
+        .into_map_with(|key| matches!(key, MyKey::Number(_)));
+
+    assert_eq!(Set::from_keys_of(&map), set);
+    assert_eq!(map.get(MyKey::Number(7)), Some(&true));
+    assert_eq!(map.get(MyKey::Unit), Some(&false));
+    assert!(Set::<MyKey>::new().into_map_with(|_| 0).is_empty());
+}
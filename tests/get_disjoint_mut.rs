@@ -0,0 +1,180 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Dir {
+    North,
+    East,
+    South,
+    West,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Unit,
+    Dir(Dir),
+    Number(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Outer {
+    Unit,
+    Inner(MyKey),
+}
+
+#[test]
+fn unit() {
+    let mut map = Map::from_iter([(Dir::North, 1), (Dir::South, 3), (Dir::West, 4)]);
+
+    let [west, east, north] = map.get_disjoint_mut([Dir::West, Dir::East, Dir::North]);
+    assert_eq!(east, None);
+    *west.unwrap() *= 10;
+    *north.unwrap() *= 10;
+
+    assert!(map.values().eq([&10, &3, &40]));
+}
+
+#[test]
+fn composite() {
+    let mut map = Map::new();
+    map.insert(MyKey::Unit, 1);
+    map.insert(MyKey::Dir(Dir::East), 2);
+    map.insert(MyKey::Number(7), 3);
+
+    let [a, b, c, d] = map.get_disjoint_mut([
+        MyKey::Number(7),
+        MyKey::Dir(Dir::East),
+        MyKey::Dir(Dir::West),
+        MyKey::Unit,
+    ]);
+
+    assert_eq!(c, None);
+    core::mem::swap(a.unwrap(), d.unwrap());
+    *b.unwrap() += 1;
+
+    assert_eq!(map.get(MyKey::Unit), Some(&3));
+    assert_eq!(map.get(MyKey::Dir(Dir::East)), Some(&3));
+    assert_eq!(map.get(MyKey::Number(7)), Some(&1));
+}
+
+#[test]
+fn composite_same_variant() {
+    let mut map = Map::from_iter([
+        (MyKey::Dir(Dir::North), 1),
+        (MyKey::Dir(Dir::South), 2),
+        (MyKey::Number(1), 3),
+        (MyKey::Number(2), 4),
+    ]);
+
+    let [a, b, c, d] = map.get_disjoint_mut([
+        MyKey::Number(2),
+        MyKey::Dir(Dir::South),
+        MyKey::Number(1),
+        MyKey::Dir(Dir::North),
+    ]);
+
+    *a.unwrap() += 40;
+    *b.unwrap() += 20;
+    *c.unwrap() += 30;
+    *d.unwrap() += 10;
+
+    assert_eq!(map.get(MyKey::Dir(Dir::North)), Some(&11));
+    assert_eq!(map.get(MyKey::Dir(Dir::South)), Some(&22));
+    assert_eq!(map.get(MyKey::Number(1)), Some(&33));
+    assert_eq!(map.get(MyKey::Number(2)), Some(&44));
+}
+
+#[test]
+fn composite_missing() {
+    let mut map = Map::from_iter([(MyKey::Dir(Dir::North), 1), (MyKey::Number(1), 2)]);
+
+    let [a, b, c, d] = map.get_disjoint_mut([
+        MyKey::Unit,
+        MyKey::Dir(Dir::East),
+        MyKey::Number(2),
+        MyKey::Number(1),
+    ]);
+
+    assert_eq!(a, None);
+    assert_eq!(b, None);
+    assert_eq!(c, None);
+    assert_eq!(d, Some(&mut 2));
+
+    let mut map = Map::<MyKey, u32>::new();
+
+    let [a, b, c] = map.get_disjoint_mut([MyKey::Unit, MyKey::Dir(Dir::West), MyKey::Number(3)]);
+    assert_eq!((a, b, c), (None, None, None));
+}
+
+#[test]
+fn nested_composite() {
+    let mut map = Map::from_iter([
+        (Outer::Unit, 1),
+        (Outer::Inner(MyKey::Unit), 2),
+        (Outer::Inner(MyKey::Dir(Dir::West)), 3),
+        (Outer::Inner(MyKey::Number(5)), 4),
+    ]);
+
+    let [a, b, c, d, e] = map.get_disjoint_mut([
+        Outer::Inner(MyKey::Number(5)),
+        Outer::Inner(MyKey::Dir(Dir::West)),
+        Outer::Inner(MyKey::Dir(Dir::East)),
+        Outer::Unit,
+        Outer::Inner(MyKey::Unit),
+    ]);
+
+    assert_eq!(c, None);
+    *a.unwrap() *= 10;
+    *b.unwrap() *= 10;
+    *d.unwrap() *= 10;
+    *e.unwrap() *= 10;
+
+    assert!(map.values().eq([&10, &20, &30, &40]));
+}
+
+#[test]
+fn empty() {
+    let mut map = Map::<Dir, u32>::new();
+    let [] = map.get_disjoint_mut([]);
+    assert_eq!(map.get_disjoint_mut([Dir::North]), [None]);
+}
+
+#[test]
+#[should_panic = "key `South` was passed to get_disjoint_mut more than once"]
+fn duplicate_unit() {
+    let mut map = Map::from_iter([(Dir::South, 1)]);
+    let _ = map.get_disjoint_mut([Dir::South, Dir::North, Dir::South]);
+}
+
+#[test]
+#[should_panic = "key `Number(1)` was passed to get_disjoint_mut more than once"]
+fn duplicate_composite() {
+    let mut map = Map::<MyKey, u32>::new();
+    let _ = map.get_disjoint_mut([MyKey::Number(1), MyKey::Number(1)]);
+}
+
+#[test]
+#[should_panic = "key `Unit` was passed to get_disjoint_mut more than once"]
+fn duplicate_composite_unit() {
+    let mut map = Map::from_iter([(MyKey::Unit, 1)]);
+    let _ = map.get_disjoint_mut([MyKey::Unit, MyKey::Number(1), MyKey::Unit]);
+}
+
+#[test]
+#[should_panic = "key `Dir(East)` was passed to get_disjoint_mut more than once"]
+fn duplicate_composite_nested() {
+    let mut map = Map::from_iter([(MyKey::Dir(Dir::East), 1)]);
+    let _ = map.get_disjoint_mut([MyKey::Dir(Dir::East), MyKey::Dir(Dir::East)]);
+}
+
+#[test]
+#[should_panic = "key `Inner(Number(2))` was passed to get_disjoint_mut more than once"]
+fn duplicate_nested_composite() {
+    let mut map = Map::<Outer, u32>::new();
+    let _ = map.get_disjoint_mut([
+        Outer::Inner(MyKey::Number(2)),
+        Outer::Unit,
+        Outer::Inner(MyKey::Number(2)),
+    ]);
+}
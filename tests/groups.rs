@@ -0,0 +1,85 @@
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+use fixed_map::{Key, KeyGroup, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(groups)]
+enum MyKey {
+    Unit,
+    Nested(Part),
+    Option(Option<bool>),
+    Number(u32),
+    #[key(lazy)]
+    Lazy(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(groups, bitset)]
+enum Flag {
+    First,
+    Second,
+    Third,
+}
+
+#[test]
+fn group_from_key() {
+    assert_eq!(MyKeyGroup::from(MyKey::Number(4)), MyKeyGroup::Number);
+    assert_eq!(MyKeyGroup::from(MyKey::Unit), MyKeyGroup::Unit);
+
+    for key in [
+        MyKey::Unit,
+        MyKey::Nested(Part::Two),
+        MyKey::Option(None),
+        MyKey::Number(1),
+        MyKey::Lazy(2),
+    ] {
+        assert_eq!(MyKeyGroup::from(key).class_index(), key.class_index());
+    }
+}
+
+#[test]
+fn map_len_and_clear_of() {
+    let mut map = Map::new();
+    map.insert(MyKey::Unit, 1);
+    map.insert(MyKey::Nested(Part::One), 2);
+    map.insert(MyKey::Nested(Part::Two), 3);
+    map.insert(MyKey::Option(None), 4);
+    map.insert(MyKey::Number(5), 5);
+    map.insert(MyKey::Number(6), 6);
+
+    assert_eq!(map.len_of(MyKeyGroup::Unit), 1);
+    assert_eq!(map.len_of(MyKeyGroup::Nested), 2);
+    assert_eq!(map.len_of(MyKeyGroup::Option), 1);
+    assert_eq!(map.len_of(MyKeyGroup::Number), 2);
+    assert_eq!(map.len_of(MyKeyGroup::Lazy), 0);
+
+    map.clear_of(MyKeyGroup::Nested);
+    map.clear_of(MyKeyGroup::Number);
+    map.clear_of(MyKeyGroup::Lazy);
+    assert!(map.keys().eq([MyKey::Unit, MyKey::Option(None)]));
+
+    let mut map = Map::from_iter([(Flag::First, 1), (Flag::Third, 3)]);
+    assert_eq!(map.len_of(FlagGroup::Second), 0);
+    assert_eq!(map.len_of(FlagGroup::Third), 1);
+    map.clear_of(FlagGroup::First);
+    assert!(map.keys().eq([Flag::Third]));
+}
+
+#[test]
+fn set_contains_any_of() {
+    let set = Set::from([MyKey::Nested(Part::Two), MyKey::Number(3)]);
+    assert!(!set.contains_any_of(MyKeyGroup::Unit));
+    assert!(set.contains_any_of(MyKeyGroup::Nested));
+    assert!(!set.contains_any_of(MyKeyGroup::Option));
+    assert!(set.contains_any_of(MyKeyGroup::Number));
+
+    let set = Set::from([Flag::Second]);
+    assert!(!set.contains_any_of(FlagGroup::First));
+    assert!(set.contains_any_of(FlagGroup::Second));
+}
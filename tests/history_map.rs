@@ -0,0 +1,59 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{HistoryMap, Key};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum Metric {
+    Cpu(u8),
+    Memory,
+    Disk(Option<bool>),
+}
+
+#[test]
+fn ring_wraps() {
+    let mut history = HistoryMap::<_, _, 4>::new();
+
+    for n in 0..10u32 {
+        let evicted = history.push(Metric::Memory, n);
+        assert_eq!(evicted, n.checked_sub(4));
+        assert_eq!(history.latest(Metric::Memory), Some(&n));
+    }
+
+    let values = history.history(Metric::Memory);
+    assert_eq!(values.len(), 4);
+    assert!(values.clone().eq(&[6, 7, 8, 9]));
+    assert_eq!(format!("{values:?}"), "[6, 7, 8, 9]");
+
+    let mut values = history.history(Metric::Memory);
+    assert_eq!(values.next(), Some(&6));
+    assert_eq!(values.next_back(), Some(&9));
+    assert_eq!(values.len(), 2);
+    assert!(values.eq(&[7, 8]));
+}
+
+#[test]
+fn dynamic_and_composite_keys() {
+    let mut history = HistoryMap::<_, _, 2>::new();
+    history.push(Metric::Cpu(0), 1.0);
+    history.push(Metric::Cpu(1), 2.0);
+    history.push(Metric::Cpu(0), 3.0);
+    history.push(Metric::Disk(None), 4.0);
+
+    assert!(history.history(Metric::Cpu(0)).eq(&[1.0, 3.0]));
+    assert!(history.history(Metric::Cpu(2)).eq(&[]));
+    assert_eq!(history.len(), 3);
+
+    assert!(history.remove(Metric::Cpu(1)));
+    assert_eq!(history.latest(Metric::Cpu(1)), None);
+    assert_eq!(history.len(), 2);
+}
+
+#[test]
+fn zero_capacity() {
+    let mut history = HistoryMap::<_, _, 0>::new();
+    assert_eq!(history.push(Metric::Memory, 1), Some(1));
+    assert_eq!(history.latest(Metric::Memory), None);
+    assert_eq!(history.history(Metric::Memory).len(), 0);
+    assert!(history.is_empty());
+    assert_eq!(history.iter().count(), 0);
+}
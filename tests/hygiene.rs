@@ -0,0 +1,46 @@
+//! Keys named like the generic parameters of the generated storage.
+
+#![allow(clippy::upper_case_acronyms)]
+
+use fixed_map::{Key, Map, Set};
+
+macro_rules! keys {
+    ($($unit:ident, $composite:ident;)*) => {
+        $(
+            #[derive(Debug, Clone, Copy, PartialEq, Key)]
+            enum $unit {
+                First,
+                Second,
+            }
+
+            #[derive(Debug, Clone, Copy, PartialEq, Key)]
+            enum $composite {
+                First($unit),
+                Second(bool),
+                Third,
+            }
+        )*
+
+        #[test]
+        fn generic_names() {
+            $(
+                let map = Map::from_iter([($unit::Second, 1), ($unit::First, 2)]);
+                assert_eq!(map.find_map(|_, v| (*v == 1).then_some(*v)), Some(1));
+                assert!(map.map_values(|_, v| v * 2).values().eq([&4, &2]));
+                let set = Set::from([$unit::First]);
+                assert!(set.contains($unit::First));
+
+                let map = Map::from_iter([($composite::First($unit::Second), 1), ($composite::Third, 2)]);
+                assert_eq!(map.find_map(|_, v| (*v == 2).then_some(*v)), Some(2));
+                assert!(map.map_values(|_, v| v * 2).values().eq([&2, &4]));
+                let set = Set::from([$composite::Third]);
+                assert!(set.contains($composite::Third));
+            )*
+        }
+    };
+}
+
+keys! {
+    B, CB;
+    W, CW;
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    First,
+    Flag(bool),
+    Dynamic(u32),
+    Nested(Part),
+    Optional(Option<Part>),
+}
+
+#[test]
+fn index_every_storage() {
+    let keys = [
+        MyKey::First,
+        MyKey::Flag(true),
+        MyKey::Dynamic(42),
+        MyKey::Nested(Part::Two),
+        MyKey::Optional(None),
+        MyKey::Optional(Some(Part::One)),
+    ];
+
+    let mut map = Map::new();
+
+    for (n, key) in keys.into_iter().enumerate() {
+        map.insert(key, n);
+    }
+
+    for (n, key) in keys.into_iter().enumerate() {
+        assert_eq!(map[key], n);
+        map[key] += 10;
+        assert_eq!(map[key], n + 10);
+    }
+
+    assert_eq!(map.len(), keys.len());
+}
+
+#[test]
+#[should_panic(expected = "key `Dynamic(7)` is not present")]
+fn index_missing() {
+    let map = Map::from([(MyKey::Dynamic(1), 1)]);
+    let _ = map[MyKey::Dynamic(7)];
+}
+
+#[test]
+#[should_panic(expected = "key `Nested(One)` is not present")]
+fn index_mut_missing() {
+    let mut map = Map::from([(MyKey::Nested(Part::Two), 1)]);
+    map[MyKey::Nested(Part::One)] = 2;
+}
@@ -0,0 +1,108 @@
+use std::ops::Bound;
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Tier {
+    A,
+    B,
+    C,
+    D,
+    E,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum Level {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+}
+
+#[test]
+fn set_array() {
+    let mut set = Set::new();
+    set.insert_range(Tier::B..=Tier::D);
+    assert!(set.iter().eq([Tier::B, Tier::C, Tier::D]));
+
+    set.insert_range(Tier::E..Tier::E);
+    assert_eq!(set.len(), 3);
+
+    set.insert_range((Bound::Excluded(Tier::D), Bound::Unbounded));
+    assert!(set.iter().eq([Tier::B, Tier::C, Tier::D, Tier::E]));
+
+    set.insert_range(..Tier::B);
+    assert!(set.iter().eq([Tier::A, Tier::B, Tier::C, Tier::D, Tier::E]));
+}
+
+#[test]
+fn set_bitset() {
+    let mut set = Set::new();
+    set.insert_range(Level::C..Level::F);
+    assert!(set.iter().eq([Level::C, Level::D, Level::E]));
+
+    set.insert_range(Level::B..Level::B);
+    assert_eq!(set.len(), 3);
+
+    set.insert_range(Level::H..);
+    assert!(set.iter().eq([Level::C, Level::D, Level::E, Level::H]));
+
+    // Covering every bit of the storage must not overflow the mask.
+    let mut set = Set::new();
+    set.insert_range(..);
+    assert_eq!(set.len(), 8);
+    assert_eq!(
+        set,
+        Set::from_iter([
+            Level::A,
+            Level::B,
+            Level::C,
+            Level::D,
+            Level::E,
+            Level::F,
+            Level::G,
+            Level::H,
+        ])
+    );
+}
+
+#[test]
+fn map() {
+    let mut map = Map::new();
+    map.insert(Tier::A, 100);
+    map.insert(Tier::C, 100);
+
+    let mut seen = Vec::new();
+
+    map.insert_for_range(Tier::B..=Tier::D, |tier| {
+        seen.push(tier);
+        tier as u32
+    });
+
+    assert_eq!(seen, [Tier::B, Tier::C, Tier::D]);
+    assert!(map
+        .iter()
+        .eq([(Tier::A, &100), (Tier::B, &1), (Tier::C, &2), (Tier::D, &3)]));
+
+    map.insert_for_range(Tier::D.., |_| 0);
+    assert_eq!(map.get(Tier::D), Some(&0));
+    assert_eq!(map.get(Tier::E), Some(&0));
+    assert_eq!(map.len(), 5);
+}
+
+#[test]
+#[should_panic = "range start is declared after range end"]
+fn set_out_of_order() {
+    Set::new().insert_range(Level::D..Level::B);
+}
+
+#[test]
+#[should_panic = "range start is declared after range end"]
+fn map_out_of_order() {
+    Map::new().insert_for_range(Tier::D..Tier::B, |_| 0);
+}
@@ -0,0 +1,45 @@
+#![cfg(all(feature = "hashbrown", feature = "std"))]
+
+use fixed_map::{map, set, Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    First,
+    Second(u32),
+}
+
+#[test]
+fn map() {
+    let mut map = Map::new();
+    *map.insert_unique(MyKey::Second(1), 1).unwrap() += 10;
+
+    let error = map.insert_unique(MyKey::Second(1), 2).unwrap_err();
+    assert_eq!(error, map::AlreadyPresent::new(MyKey::Second(1), 2));
+    assert_eq!(*error.key(), MyKey::Second(1));
+    assert_eq!(*error.value(), 2);
+    assert_eq!(error.to_string(), "key `Second(1)` is already present");
+    assert_eq!(map.get(MyKey::Second(1)), Some(&11));
+
+    assert!(map.insert_unique(MyKey::First, 3).is_ok());
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn set() {
+    let mut set = Set::new();
+    assert_eq!(set.insert_unique(MyKey::First), Ok(()));
+    assert_eq!(
+        set.insert_unique(MyKey::First),
+        Err(set::AlreadyPresent::new(MyKey::First))
+    );
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn error() {
+    let mut set = Set::new();
+    set.insert(MyKey::First);
+
+    let error: Box<dyn std::error::Error> = Box::new(set.insert_unique(MyKey::First).unwrap_err());
+    assert_eq!(error.to_string(), "value `First` is already present");
+}
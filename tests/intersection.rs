@@ -0,0 +1,145 @@
+use fixed_map::{Key, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+    Three,
+    Four,
+}
+
+#[test]
+fn size_hint() {
+    let a = Set::from([Part::One, Part::Two, Part::Three]);
+    let b = Set::from([Part::Two, Part::Four]);
+
+    let mut it = a.intersection(&b);
+    assert_eq!(it.size_hint(), (0, Some(2)));
+    assert_eq!(it.next(), Some(Part::Two));
+    assert_eq!(it.size_hint(), (0, Some(1)));
+    assert_eq!(it.next(), None);
+
+    let it = a.intersection_ordered(&b);
+    assert_eq!(it.size_hint(), (0, Some(2)));
+    assert_eq!(it.count(), 1);
+}
+
+#[cfg(feature = "hashbrown")]
+#[test]
+fn ordered() {
+    let a = (0u32..64).collect::<Set<_>>();
+    let b = (0u32..64).step_by(3).collect::<Set<_>>();
+
+    let expected = a.iter().filter(|n| b.contains(*n)).collect::<Vec<_>>();
+    assert_eq!(a.intersection_ordered(&b).collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn cardinality() {
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    #[key(bitset)]
+    enum Flag {
+        A,
+        B,
+        C,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    enum Composite {
+        Flag(Flag),
+        Part(Part),
+        Toggle(bool),
+    }
+
+    let a = Set::from([Flag::A, Flag::B]);
+    let b = Set::from([Flag::B, Flag::C]);
+    assert_eq!(a.intersection_len(&b), a.intersection(&b).count());
+    assert_eq!(a.union_len(&b), 3);
+
+    let a = Set::from([
+        Composite::Flag(Flag::A),
+        Composite::Part(Part::Two),
+        Composite::Toggle(true),
+        Composite::Toggle(false),
+    ]);
+    let b = Set::from([
+        Composite::Flag(Flag::A),
+        Composite::Part(Part::Three),
+        Composite::Toggle(false),
+    ]);
+    assert_eq!(a.intersection_len(&b), 2);
+    assert_eq!(a.union_len(&b), 5);
+    assert_eq!(a.jaccard(&b), 0.4);
+}
+
+#[test]
+fn in_place() {
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    #[key(bitset)]
+    enum Flag {
+        A,
+        B,
+        C,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    #[key(transparent)]
+    struct Wrapper(Flag);
+
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    enum Composite {
+        Flag(Flag),
+        Part(Part),
+        Toggle(bool),
+        Wrapped(Wrapper),
+        Unit,
+    }
+
+    fn check<T>(a: Set<T>, b: Set<T>)
+    where
+        T: Key + std::fmt::Debug,
+        Set<T>: PartialEq,
+    {
+        let union = a.iter().chain(b.iter()).collect::<Set<_>>();
+        let intersection = a.intersection(&b).collect::<Set<_>>();
+        let difference = a.iter().filter(|v| !b.contains(*v)).collect::<Set<_>>();
+
+        let mut c = a.iter().collect::<Set<_>>();
+        c.union_with(&b);
+        assert_eq!(c, union);
+
+        let mut c = a.iter().collect::<Set<_>>();
+        c.intersect_with(&b);
+        assert_eq!(c, intersection);
+
+        let mut c = a.iter().collect::<Set<_>>();
+        c.subtract(&b);
+        assert_eq!(c, difference);
+    }
+
+    check(Set::from([Flag::A, Flag::B]), Set::from([Flag::B, Flag::C]));
+    check(
+        Set::from([Part::One, Part::Three]),
+        Set::from([Part::Three, Part::Four]),
+    );
+    check(Set::from([true]), Set::from([true, false]));
+    check(
+        Set::from([Wrapper(Flag::A), Wrapper(Flag::C)]),
+        Set::from([Wrapper(Flag::C)]),
+    );
+    check(
+        Set::from([
+            Composite::Flag(Flag::A),
+            Composite::Part(Part::Two),
+            Composite::Toggle(true),
+            Composite::Wrapped(Wrapper(Flag::B)),
+            Composite::Unit,
+        ]),
+        Set::from([
+            Composite::Flag(Flag::A),
+            Composite::Part(Part::Three),
+            Composite::Wrapped(Wrapper(Flag::B)),
+            Composite::Toggle(false),
+        ]),
+    );
+}
@@ -0,0 +1,60 @@
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum MyKey {
+    First,
+    Second(bool),
+    Third,
+}
+
+fn collect<I>(iter: I) -> Vec<I::Item>
+where
+    I: IntoIterator,
+{
+    iter.into_iter().collect()
+}
+
+#[test]
+fn set() {
+    let mut set = Set::new();
+    set.insert(MyKey::Second(false));
+    set.insert(MyKey::Third);
+
+    let expected = [MyKey::Second(false), MyKey::Third];
+    assert_eq!(collect(&set), expected);
+    assert_eq!(collect(&mut set), expected);
+    assert_eq!(collect(set), expected);
+}
+
+#[test]
+fn map() {
+    let mut map = Map::new();
+    map.insert(MyKey::First, 1);
+    map.insert(MyKey::Second(true), 2);
+
+    assert_eq!(
+        collect(&map),
+        [(MyKey::First, &1), (MyKey::Second(true), &2)]
+    );
+
+    for (_, value) in &mut map {
+        *value *= 10;
+    }
+
+    assert_eq!(
+        collect(map),
+        [(MyKey::First, 10), (MyKey::Second(true), 20)]
+    );
+}
+
+#[test]
+fn entry() {
+    let mut map = Map::new();
+    map.insert(MyKey::Second(true), 'a');
+
+    assert!(map
+        .entry(MyKey::Second(true))
+        .iter()
+        .eq([(MyKey::Second(true), &'a')]));
+    assert_eq!(map.entry(MyKey::Second(false)).iter().next(), None);
+}
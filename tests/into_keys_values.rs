@@ -0,0 +1,80 @@
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Unit,
+    Dynamic(u32),
+    Nested(Part),
+    Optional(Option<bool>),
+    #[key(lazy)]
+    Lazy(Part),
+}
+
+const KEYS: [MyKey; 6] = [
+    MyKey::Unit,
+    MyKey::Dynamic(7),
+    MyKey::Nested(Part::Two),
+    MyKey::Optional(Some(false)),
+    MyKey::Optional(None),
+    MyKey::Lazy(Part::One),
+];
+
+fn map() -> Map<MyKey, String> {
+    KEYS.into_iter()
+        .enumerate()
+        .map(|(n, key)| (key, n.to_string()))
+        .collect()
+}
+
+#[test]
+fn into_keys() {
+    let keys = map().into_keys();
+    assert_eq!(keys.len(), KEYS.len());
+    assert!(keys.eq(KEYS));
+    assert!(Map::<MyKey, String>::new().into_keys().next().is_none());
+}
+
+#[test]
+fn into_values() {
+    let values = map().into_values();
+    assert_eq!(values.len(), KEYS.len());
+    assert!(values.eq(["0", "1", "2", "3", "4", "5"]));
+}
+
+#[test]
+fn exact_size() {
+    let mut values = map().into_values();
+    values.next();
+    assert_eq!(values.size_hint(), (KEYS.len() - 1, Some(KEYS.len() - 1)));
+    assert_eq!(values.count(), KEYS.len() - 1);
+}
+
+#[test]
+fn rev() {
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    enum Fixed {
+        First(bool),
+        Second(Part),
+        Third,
+    }
+
+    let mut map = Map::new();
+    map.insert(Fixed::First(false), 1);
+    map.insert(Fixed::Second(Part::Two), 2);
+    map.insert(Fixed::Third, 3);
+
+    assert!(map.into_keys().rev().eq([
+        Fixed::Third,
+        Fixed::Second(Part::Two),
+        Fixed::First(false)
+    ]));
+    assert!(map.into_values().rev().eq([3, 2, 1]));
+}
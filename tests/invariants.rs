@@ -0,0 +1,95 @@
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Simple {
+    First,
+    Second,
+    Third,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum Bits {
+    First,
+    Second,
+    Third,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Composite {
+    Unit,
+    Bool(bool),
+    Option(Option<Simple>),
+    Nested(Simple),
+    #[cfg(feature = "hashbrown")]
+    Dynamic(u32),
+}
+
+fn composite_keys() -> Vec<Composite> {
+    let keys = [
+        Composite::Unit,
+        Composite::Bool(true),
+        Composite::Bool(false),
+        Composite::Option(None),
+        Composite::Option(Some(Simple::Second)),
+        Composite::Nested(Simple::Third),
+    ]
+    .into_iter();
+
+    #[cfg(feature = "hashbrown")]
+    let keys = keys.chain([Composite::Dynamic(1), Composite::Dynamic(42)]);
+
+    keys.collect()
+}
+
+#[test]
+fn map_invariants() {
+    let mut map = Map::new();
+    map.debug_assert_invariants();
+
+    for (n, key) in composite_keys().into_iter().enumerate() {
+        map.insert(key, n);
+        map.debug_assert_invariants();
+    }
+
+    for key in composite_keys().into_iter().step_by(2) {
+        map.remove(key);
+        map.debug_assert_invariants();
+    }
+
+    map.retain(|key, _| !matches!(key, Composite::Option(..)));
+    map.debug_assert_invariants();
+
+    map.clear();
+    map.debug_assert_invariants();
+
+    let mut simple = Map::new();
+    simple.insert(Simple::Second, ());
+    simple.debug_assert_invariants();
+
+    let mut bits = Map::new();
+    bits.insert(Bits::Third, ());
+    bits.debug_assert_invariants();
+}
+
+#[test]
+fn set_invariants() {
+    let mut set = Set::new();
+    set.debug_assert_invariants();
+
+    for key in composite_keys() {
+        set.insert(key);
+        set.debug_assert_invariants();
+    }
+
+    for key in composite_keys().into_iter().skip(1).step_by(2) {
+        set.remove(key);
+        set.debug_assert_invariants();
+    }
+
+    let bits = Set::from([Bits::First, Bits::Third]);
+    bits.debug_assert_invariants();
+
+    let simple = Set::from([Simple::First, Simple::Third]);
+    simple.debug_assert_invariants();
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(transparent)]
+struct Wrapper(Part);
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Unit,
+    Nested(Part),
+    Option(Option<bool>),
+    Number(u32),
+    Empty,
+}
+
+#[test]
+fn derived() {
+    let mut map = Map::new();
+    map.insert(MyKey::Unit, 1);
+    map.insert(MyKey::Nested(Part::One), 2);
+    map.insert(MyKey::Nested(Part::Two), 3);
+    map.insert(MyKey::Option(None), 4);
+    map.insert(MyKey::Number(5), 5);
+
+    assert!(map.iter_group(MyKey::Unit).eq([(MyKey::Unit, &1)]));
+    assert!(map.iter_group(MyKey::Nested(Part::Two)).eq([
+        (MyKey::Nested(Part::One), &2),
+        (MyKey::Nested(Part::Two), &3)
+    ]));
+    assert!(map
+        .iter_group(MyKey::Option(Some(true)))
+        .eq([(MyKey::Option(None), &4)]));
+    assert!(map
+        .iter_group(MyKey::Number(0))
+        .eq([(MyKey::Number(5), &5)]));
+    assert!(map.iter_group(MyKey::Empty).next().is_none());
+}
+
+#[test]
+fn builtin() {
+    let map = Map::from_iter([(Some(true), 1), (Some(false), 2), (None, 3)]);
+    assert!(map
+        .iter_group(Some(false))
+        .eq([(Some(true), &1), (Some(false), &2)]));
+    assert!(map.iter_group(None).eq([(None, &3)]));
+
+    let map = Map::from_iter([(true, 1), (false, 2)]);
+    assert!(map.iter_group(false).eq([(false, &2)]));
+
+    let map = Map::from_iter([(Part::One, 1), (Part::Two, 2)]);
+    assert!(map.iter_group(Part::Two).eq([(Part::Two, &2)]));
+
+    let map = Map::from_iter([(Some(Part::One), 1), (Some(Part::Two), 2)]);
+    assert!(map
+        .iter_group(Some(Part::One))
+        .rev()
+        .map(|(_, v)| *v)
+        .eq([2, 1]));
+
+    let map = Map::from_iter([(Wrapper(Part::One), 1)]);
+    assert!(map
+        .iter_group(Wrapper(Part::One))
+        .eq([(Wrapper(Part::One), &1)]));
+    assert!(map.iter_group(Wrapper(Part::Two)).next().is_none());
+}
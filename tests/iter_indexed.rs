@@ -0,0 +1,28 @@
+use fixed_map::raw::KeyIndex;
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    First,
+    Second,
+    Third,
+    Fourth,
+}
+
+#[test]
+fn indexes() {
+    let map = Map::from_iter([(MyKey::Second, 'b'), (MyKey::Fourth, 'd')]);
+
+    assert!(map
+        .iter_indexed()
+        .eq([(1, MyKey::Second, &'b'), (3, MyKey::Fourth, &'d')]));
+    assert!(map
+        .iter_indexed()
+        .rev()
+        .eq([(3, MyKey::Fourth, &'d'), (1, MyKey::Second, &'b')]));
+    assert_eq!(map.iter_indexed().len(), 2);
+
+    for (index, key, _) in map.iter_indexed() {
+        assert_eq!(MyKey::from_index(index), Some(key));
+    }
+}
@@ -0,0 +1,182 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    First(u32),
+    Second(Part),
+    Third(Option<bool>),
+    Unit,
+    Fourth(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Fixed {
+    First(Part),
+    Second,
+    Third(Option<Part>),
+}
+
+/// More variants than fit in a `u64`, so the generated iterators track which
+/// ones remain in a `u128` mask.
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Wide {
+    Head(Part),
+    U0,
+    U1,
+    U2,
+    U3,
+    U4,
+    U5,
+    U6,
+    U7,
+    U8,
+    U9,
+    U10,
+    U11,
+    U12,
+    U13,
+    U14,
+    U15,
+    U16,
+    U17,
+    U18,
+    U19,
+    U20,
+    U21,
+    U22,
+    U23,
+    U24,
+    U25,
+    U26,
+    U27,
+    U28,
+    U29,
+    U30,
+    U31,
+    U32,
+    U33,
+    U34,
+    U35,
+    U36,
+    U37,
+    U38,
+    U39,
+    U40,
+    U41,
+    U42,
+    U43,
+    U44,
+    U45,
+    U46,
+    U47,
+    U48,
+    U49,
+    U50,
+    U51,
+    U52,
+    U53,
+    U54,
+    U55,
+    U56,
+    U57,
+    U58,
+    U59,
+    U60,
+    U61,
+    U62,
+    U63,
+    Tail(Part),
+    Last(Part),
+}
+
+#[test]
+fn skips_empty_branches() {
+    let mut map = Map::new();
+    assert!(map.iter().next().is_none());
+
+    map.insert(MyKey::Fourth(3), 3);
+    map.insert(MyKey::Second(Part::Two), 2);
+
+    assert!(map
+        .iter()
+        .eq([(MyKey::Second(Part::Two), &2), (MyKey::Fourth(3), &3)]));
+    assert!(map.keys().eq([MyKey::Second(Part::Two), MyKey::Fourth(3)]));
+    assert!(map.values().copied().eq([2, 3]));
+
+    for value in map.values_mut() {
+        *value *= 10;
+    }
+
+    for (_, value) in map.iter_mut() {
+        *value += 1;
+    }
+
+    assert!(map
+        .clone()
+        .into_iter()
+        .eq([(MyKey::Second(Part::Two), 21), (MyKey::Fourth(3), 31)]));
+
+    let set = map.keys().collect::<Set<_>>();
+    assert!(set.iter().eq([MyKey::Second(Part::Two), MyKey::Fourth(3)]));
+    assert!(set
+        .into_iter()
+        .eq([MyKey::Second(Part::Two), MyKey::Fourth(3)]));
+}
+
+#[test]
+fn skips_empty_branches_rev() {
+    let mut map = Map::new();
+    assert!(map.iter().next_back().is_none());
+
+    map.insert(Fixed::Third(None), 3);
+    map.insert(Fixed::First(Part::Two), 1);
+
+    assert!(map
+        .iter()
+        .rev()
+        .eq([(Fixed::Third(None), &3), (Fixed::First(Part::Two), &1)]));
+    assert!(map
+        .keys()
+        .rev()
+        .eq([Fixed::Third(None), Fixed::First(Part::Two)]));
+
+    map.remove(Fixed::Third(None));
+    assert!(map.iter().rev().eq([(Fixed::First(Part::Two), &1)]));
+
+    let set = map.keys().collect::<Set<_>>();
+    assert!(set.iter().rev().eq([Fixed::First(Part::Two)]));
+}
+
+#[test]
+fn wide_keys() {
+    let mut map = Map::new();
+    map.insert(Wide::Last(Part::One), 4);
+    map.insert(Wide::Tail(Part::Two), 3);
+    map.insert(Wide::U63, 2);
+    map.insert(Wide::Head(Part::One), 1);
+
+    let expected = [
+        (Wide::Head(Part::One), 1),
+        (Wide::U63, 2),
+        (Wide::Tail(Part::Two), 3),
+        (Wide::Last(Part::One), 4),
+    ];
+
+    assert!(map.iter().map(|(k, v)| (k, *v)).eq(expected));
+    assert!(map
+        .iter()
+        .rev()
+        .map(|(k, v)| (k, *v))
+        .eq(expected.into_iter().rev()));
+
+    let set = map.keys().collect::<Set<_>>();
+    assert!(set.iter().eq(expected.map(|(k, _)| k)));
+}
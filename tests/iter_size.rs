@@ -0,0 +1,231 @@
+use core::mem::size_of;
+
+use fixed_map::{map, set, Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Small {
+    First,
+    Second(Part),
+    Third,
+}
+
+/// More variants than the widest mask has bits, so the generated iterators
+/// track a span of variants instead.
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Huge {
+    Head(Part),
+    U0,
+    U1,
+    U2,
+    U3,
+    U4,
+    U5,
+    U6,
+    U7,
+    U8,
+    U9,
+    U10,
+    U11,
+    U12,
+    U13,
+    U14,
+    U15,
+    U16,
+    U17,
+    U18,
+    U19,
+    U20,
+    U21,
+    U22,
+    U23,
+    U24,
+    U25,
+    U26,
+    U27,
+    U28,
+    U29,
+    U30,
+    U31,
+    U32,
+    U33,
+    U34,
+    U35,
+    U36,
+    U37,
+    U38,
+    U39,
+    U40,
+    U41,
+    U42,
+    U43,
+    U44,
+    U45,
+    U46,
+    U47,
+    U48,
+    U49,
+    U50,
+    U51,
+    U52,
+    U53,
+    U54,
+    U55,
+    U56,
+    U57,
+    U58,
+    U59,
+    U60,
+    U61,
+    U62,
+    U63,
+    U64,
+    U65,
+    U66,
+    U67,
+    U68,
+    U69,
+    U70,
+    U71,
+    U72,
+    U73,
+    U74,
+    U75,
+    U76,
+    U77,
+    U78,
+    U79,
+    U80,
+    U81,
+    U82,
+    U83,
+    U84,
+    U85,
+    U86,
+    U87,
+    U88,
+    U89,
+    U90,
+    U91,
+    U92,
+    U93,
+    U94,
+    U95,
+    U96,
+    U97,
+    U98,
+    U99,
+    U100,
+    U101,
+    U102,
+    U103,
+    U104,
+    U105,
+    U106,
+    U107,
+    U108,
+    U109,
+    U110,
+    U111,
+    U112,
+    U113,
+    U114,
+    U115,
+    U116,
+    U117,
+    U118,
+    U119,
+    U120,
+    U121,
+    U122,
+    U123,
+    U124,
+    U125,
+    U126,
+    U127,
+    U128,
+    U129,
+    Tail(Part),
+}
+
+// NB: The state of a generated iterator holds one field per variant, and the
+// remaining variants are tracked next to it. None of them should need any
+// `usize` counters.
+
+#[test]
+fn map_iterators() {
+    assert_eq!(
+        size_of::<map::Iter<'static, Small, u8>>(),
+        size_of::<(u8, (Option<&u8>, map::Iter<'static, Part, u8>, Option<&u8>))>()
+    );
+
+    assert_eq!(
+        size_of::<map::Keys<'static, Small, u8>>(),
+        size_of::<(u8, (bool, map::Keys<'static, Part, u8>, bool))>()
+    );
+
+    assert_eq!(
+        size_of::<map::IntoIter<Small, u8>>(),
+        size_of::<(u8, (Option<u8>, map::IntoIter<Part, u8>, Option<u8>))>()
+    );
+}
+
+#[test]
+fn set_iterators() {
+    assert_eq!(
+        size_of::<set::Iter<'static, Small>>(),
+        size_of::<(u8, (bool, set::Iter<'static, Part>, bool))>()
+    );
+
+    assert_eq!(
+        size_of::<set::IntoIter<Small>>(),
+        size_of::<(u8, (bool, set::IntoIter<Part>, bool))>()
+    );
+}
+
+#[test]
+fn span_iterators() {
+    // The span over the variants of `Huge` is a pair of `u8` counters.
+    assert_eq!(
+        size_of::<set::IntoIter<Huge>>(),
+        size_of::<(
+            [u8; 2],
+            (set::IntoIter<Part>, [bool; 130], set::IntoIter<Part>)
+        )>()
+    );
+}
+
+#[test]
+fn huge_keys() {
+    let mut map = Map::new();
+    map.insert(Huge::Tail(Part::Two), 3);
+    map.insert(Huge::U129, 2);
+    map.insert(Huge::U0, 1);
+    map.insert(Huge::Head(Part::One), 0);
+
+    let expected = [
+        (Huge::Head(Part::One), 0),
+        (Huge::U0, 1),
+        (Huge::U129, 2),
+        (Huge::Tail(Part::Two), 3),
+    ];
+
+    assert!(map.iter().map(|(k, v)| (k, *v)).eq(expected));
+    assert!(map
+        .iter()
+        .rev()
+        .map(|(k, v)| (k, *v))
+        .eq(expected.into_iter().rev()));
+
+    let set = map.keys().collect::<Set<_>>();
+    assert!(set.iter().eq(expected.map(|(k, _)| k)));
+    assert!(set
+        .into_iter()
+        .rev()
+        .eq(expected.map(|(k, _)| k).into_iter().rev()));
+}
@@ -0,0 +1,80 @@
+use fixed_map::key_iter::{FiniteKey, KeyIter};
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(iter)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(iter)]
+enum MyKey {
+    Unit,
+    Nested(Part),
+    Flag(Option<bool>),
+    #[key(values(1, 4, 9))]
+    Square(u8),
+    Last,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(iter)]
+enum Empty {}
+
+const ALL: [MyKey; 10] = [
+    MyKey::Unit,
+    MyKey::Nested(Part::One),
+    MyKey::Nested(Part::Two),
+    MyKey::Flag(Some(true)),
+    MyKey::Flag(Some(false)),
+    MyKey::Flag(None),
+    MyKey::Square(1),
+    MyKey::Square(4),
+    MyKey::Square(9),
+    MyKey::Last,
+];
+
+#[test]
+fn composite() {
+    assert_eq!(MyKey::LEN, ALL.len());
+    assert!(MyKey::all().eq(ALL));
+    assert!(MyKey::all().rev().eq(ALL.into_iter().rev()));
+    assert_eq!(MyKey::from_ordinal(ALL.len()), None);
+}
+
+#[test]
+fn matches_map_order() {
+    let map = MyKey::all()
+        .enumerate()
+        .map(|(n, key)| (key, n))
+        .collect::<Map<_, _>>();
+
+    assert_eq!(map.len(), MyKey::LEN);
+    assert!(map.keys().eq(MyKey::all()));
+}
+
+#[test]
+fn exact_size() {
+    let mut it = MyKey::all();
+    assert_eq!(it.len(), 10);
+
+    assert_eq!(it.next(), Some(MyKey::Unit));
+    assert_eq!(it.next_back(), Some(MyKey::Last));
+    assert_eq!(it.len(), 8);
+
+    assert_eq!(it.nth(4), Some(MyKey::Flag(None)));
+    assert_eq!(it.len(), 3);
+
+    assert_eq!(it.nth(5), None);
+    assert_eq!(it.len(), 0);
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next_back(), None);
+}
+
+#[test]
+fn empty() {
+    assert_eq!(Empty::LEN, 0);
+    assert_eq!(KeyIter::<Empty>::new().next(), None);
+}
@@ -0,0 +1,46 @@
+use fixed_map::{key_table, Key};
+
+mod keys {
+    use fixed_map::Key;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    pub enum Direction {
+        North,
+        East,
+        South,
+        West,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum Flag {
+    A = 4,
+    B = 1,
+}
+
+#[test]
+fn qualified_key() {
+    let mut order = Vec::new();
+
+    let table = key_table!(keys::Direction {
+        West => { order.push(3); "W" },
+        North => { order.push(0); "N" },
+        South => { order.push(2); "S" },
+        East => { order.push(1); "E" },
+    });
+
+    // Values are evaluated in the order in which they are listed, while the
+    // map iterates in declaration order.
+    assert_eq!(order, [3, 0, 2, 1]);
+    assert!(table.values().copied().eq(["N", "E", "S", "W"]));
+}
+
+#[test]
+fn discriminants() {
+    let table = key_table!(Flag { B => 1u8, A => 4 });
+
+    assert_eq!(table.get(Flag::A), Some(&4));
+    assert_eq!(table.get(Flag::B), Some(&1));
+    assert_eq!(table.len(), 2);
+}
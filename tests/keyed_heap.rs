@@ -0,0 +1,146 @@
+use fixed_map::{Key, KeyedHeap, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum Node {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+}
+
+const EDGES: [(Node, Node, u32); 8] = [
+    (Node::A, Node::B, 7),
+    (Node::A, Node::C, 9),
+    (Node::A, Node::F, 14),
+    (Node::B, Node::C, 10),
+    (Node::B, Node::D, 15),
+    (Node::C, Node::D, 11),
+    (Node::C, Node::F, 2),
+    (Node::D, Node::E, 6),
+];
+
+#[test]
+fn dijkstra() {
+    let mut dist = Map::new();
+    let mut heap = KeyedHeap::new();
+    heap.push(Node::A, 0);
+
+    while let Some((node, d)) = heap.pop_min() {
+        dist.insert(node, d);
+
+        for (from, to, cost) in EDGES {
+            let next = match node {
+                n if n == from => to,
+                n if n == to => from,
+                _ => continue,
+            };
+
+            if dist.contains_key(next) {
+                continue;
+            }
+
+            let candidate = d + cost;
+
+            match heap.priority(next) {
+                Some(&current) if current <= candidate => {}
+                Some(..) => {
+                    heap.change_priority(next, candidate);
+                }
+                None => {
+                    heap.push(next, candidate);
+                }
+            }
+        }
+    }
+
+    assert!(dist.iter().eq([
+        (Node::A, &0),
+        (Node::B, &7),
+        (Node::C, &9),
+        (Node::D, &20),
+        (Node::E, &26),
+        (Node::F, &11),
+    ]));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum Composite {
+    Unit(Node),
+    Flag(Option<bool>),
+}
+
+fn keys() -> Vec<Composite> {
+    let mut keys = Vec::new();
+
+    for node in [Node::A, Node::B, Node::C, Node::D, Node::E, Node::F] {
+        keys.push(Composite::Unit(node));
+    }
+
+    for flag in [Some(true), Some(false), None] {
+        keys.push(Composite::Flag(flag));
+    }
+
+    keys
+}
+
+/// Compare the heap against a naive model through a deterministic sequence of
+/// pseudo-random operations.
+#[test]
+fn model() {
+    let keys = keys();
+    let mut heap = KeyedHeap::new();
+    let mut model = Map::new();
+    let mut state = 0x2545_f491_u32;
+
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state
+    };
+
+    for _ in 0..10_000 {
+        let key = keys[next() as usize % keys.len()];
+        let priority = next() % 32;
+
+        match next() % 4 {
+            0 => {
+                assert_eq!(heap.push(key, priority), model.insert(key, priority));
+            }
+            1 => {
+                let expected = model.get_mut(key).map(|p| std::mem::replace(p, priority));
+                assert_eq!(heap.change_priority(key, priority), expected);
+            }
+            2 => {
+                assert_eq!(heap.remove(key), model.remove(key));
+            }
+            _ => {
+                let popped = heap.pop_min();
+                let min = model.values().min().copied();
+                assert_eq!(popped.map(|(_, p)| p), min);
+
+                if let Some((key, p)) = popped {
+                    assert_eq!(model.remove(key), Some(p));
+                }
+            }
+        }
+
+        assert_eq!(heap.len(), model.len());
+        assert_eq!(
+            heap.peek_min().map(|(_, p)| *p),
+            model.values().min().copied()
+        );
+    }
+
+    let mut last = 0;
+
+    while let Some((key, p)) = heap.pop_min() {
+        assert!(p >= last);
+        assert_eq!(model.remove(key), Some(p));
+        last = p;
+    }
+
+    assert!(model.is_empty());
+}
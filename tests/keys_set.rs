@@ -0,0 +1,128 @@
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+use std::fmt::Debug;
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+    Three,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum Flags {
+    A,
+    B,
+    C,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(sparse)]
+enum Sparse {
+    A,
+    B,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Unit,
+    Nested(Part),
+    Optional(Option<bool>),
+    Dynamic(u32),
+    #[key(values(1, 2, 4))]
+    Mode(u8),
+    #[key(dynamic = "ordered")]
+    Name(&'static str),
+    #[key(lazy)]
+    Lazy(Flags),
+    Sparse(Sparse),
+}
+
+fn check<K>(keys: &[K])
+where
+    K: Key + Debug + PartialEq,
+{
+    let map = keys.iter().map(|&key| (key, ())).collect::<Map<_, _>>();
+
+    let set = map.keys_set();
+    assert_eq!(set.len(), map.len());
+    assert!(set.iter().eq(map.keys()));
+    assert!(Set::from_keys_of(&map).iter().eq(map.keys()));
+
+    for &key in keys {
+        assert!(set.contains(key));
+    }
+}
+
+/// Like [`check`], but for keys with dynamic storage which might iterate in
+/// an order that differs between a map and a set, such as when the
+/// `std-collections` feature uses `RandomState`.
+fn check_contents<K>(keys: &[K])
+where
+    K: Key + Debug + Ord,
+{
+    let map = keys.iter().map(|&key| (key, ())).collect::<Map<_, _>>();
+
+    let mut expected = map.keys().collect::<Vec<_>>();
+    expected.sort();
+
+    let set = map.keys_set();
+    assert_eq!(set.len(), map.len());
+
+    let mut actual = set.iter().collect::<Vec<_>>();
+    actual.sort();
+    assert_eq!(actual, expected);
+
+    let mut actual = Set::from_keys_of(&map).iter().collect::<Vec<_>>();
+    actual.sort();
+    assert_eq!(actual, expected);
+
+    for &key in keys {
+        assert!(set.contains(key));
+    }
+}
+
+#[test]
+fn keys_set_unit() {
+    check::<Part>(&[]);
+    check(&[Part::Three, Part::One]);
+    check(&[Flags::C, Flags::A]);
+    check(&[Sparse::B]);
+}
+
+#[test]
+fn keys_set_composite() {
+    check::<MyKey>(&[]);
+    check(&[
+        MyKey::Sparse(Sparse::A),
+        MyKey::Unit,
+        MyKey::Nested(Part::Two),
+        MyKey::Optional(None),
+        MyKey::Optional(Some(false)),
+        MyKey::Dynamic(3),
+        MyKey::Mode(4),
+        MyKey::Name("b"),
+        MyKey::Name("a"),
+        MyKey::Lazy(Flags::B),
+    ]);
+}
+
+#[test]
+fn keys_set_builtin() {
+    check(&[true]);
+    check(&[false, true]);
+    check(&[None, Some(Part::Two)]);
+    check(&[()]);
+    check_contents(&[2u32, 5, 9, 13]);
+}
+
+#[test]
+fn keys_set_lazy_unallocated() {
+    let mut map = Map::new();
+    map.insert(MyKey::Lazy(Flags::A), 1);
+    map.remove_and_shrink(MyKey::Lazy(Flags::A));
+    assert!(map.keys_set().is_empty());
+}
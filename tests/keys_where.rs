@@ -0,0 +1,40 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+#[key(bitset)]
+enum Bits {
+    First,
+    Second,
+    Third,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum Composite {
+    Bits(Bits),
+    Number(u32),
+    Empty,
+}
+
+#[test]
+fn bitset() {
+    let map = Map::from_iter([(Bits::First, 1), (Bits::Second, 2), (Bits::Third, 3)]);
+
+    let odd = map.keys_where(|value| value % 2 == 1);
+    assert_eq!(odd.as_raw(), 0b101);
+    assert!(map.keys_where(|_| false).is_empty());
+}
+
+#[test]
+fn composite() {
+    let map = Map::from_iter([
+        (Composite::Bits(Bits::Second), "a"),
+        (Composite::Number(7), "bb"),
+        (Composite::Number(9), "c"),
+        (Composite::Empty, "dd"),
+    ]);
+
+    let long = map.keys_where(|value| value.len() == 2);
+    assert_eq!(long, Set::from([Composite::Number(7), Composite::Empty]));
+}
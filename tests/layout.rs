@@ -0,0 +1,69 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(layout_test)]
+enum Unit {
+    Zulu,
+    Alpha,
+    Mike,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset, layout_test)]
+enum Bits {
+    Second,
+    First,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(layout_test)]
+enum Composite {
+    Last,
+    Nested(Unit),
+    First,
+    Flag(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(layout_test)]
+enum Fields {
+    Nested(Unit),
+}
+
+fn hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn declaration_order() {
+    let mut map = Map::new();
+    map.insert(Composite::Flag(false), 4);
+    map.insert(Composite::First, 3);
+    map.insert(Composite::Nested(Unit::Mike), 2);
+    map.insert(Composite::Nested(Unit::Zulu), 1);
+    map.insert(Composite::Last, 0);
+
+    assert!(map.values().copied().eq(0..5));
+
+    let a = Map::from_iter([(Unit::Zulu, 1)]);
+    let b = Map::from_iter([(Unit::Mike, 1)]);
+    assert!(a < b);
+
+    let mut c = Map::new();
+    c.insert(Unit::Alpha, 1);
+    c.insert(Unit::Zulu, 1);
+
+    let mut d = Map::new();
+    d.insert(Unit::Zulu, 1);
+    d.insert(Unit::Alpha, 1);
+
+    assert_eq!(hash(&c), hash(&d));
+    assert!(Set::from([Unit::Mike, Unit::Zulu])
+        .iter()
+        .eq([Unit::Zulu, Unit::Mike]));
+}
@@ -0,0 +1,134 @@
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+use core::mem::{align_of, size_of};
+
+use fixed_map::layout::{self, Branch};
+use fixed_map::map::storage::LazyMapStorage;
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+    Three,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Composite {
+    Unit,
+    Nested(Part),
+    Flag(bool),
+    Optional(Option<Part>),
+    #[key(lazy)]
+    Lazy(Part),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(transparent)]
+struct Wrapper(Part);
+
+fn assert_disjoint(branches: &[Branch], size: usize) {
+    let mut ranges = branches
+        .iter()
+        .map(|b| {
+            let offset = b.offset.expect("missing offset");
+            assert_eq!(offset % b.align, 0, "{} is misaligned", b.name);
+            offset..offset + b.size
+        })
+        .collect::<Vec<_>>();
+
+    ranges.sort_by_key(|r| r.start);
+
+    for w in ranges.windows(2) {
+        assert!(w[0].end <= w[1].start, "{:?} overlaps {:?}", w[0], w[1]);
+    }
+
+    assert!(ranges.iter().all(|r| r.end <= size));
+}
+
+#[test]
+fn unit_variants() {
+    let report = layout::report::<Part, u32>();
+
+    assert_eq!(report.map.size, size_of::<Map<Part, u32>>());
+    assert_eq!(report.set.size, size_of::<Set<Part>>());
+
+    let names = report.branches.iter().map(|b| b.name).collect::<Vec<_>>();
+    assert_eq!(names, ["One", "Two", "Three"]);
+
+    let slot = size_of::<Option<u32>>();
+    assert_eq!(report.branch("One").unwrap().offset, Some(0));
+    assert_eq!(report.branch("Two").unwrap().offset, Some(slot));
+    assert_eq!(report.branch("Three").unwrap().offset, Some(2 * slot));
+
+    assert_disjoint(&report.branches, report.map.size);
+}
+
+#[test]
+fn composite() {
+    let report = layout::report::<Composite, u32>();
+
+    let names = report.branches.iter().map(|b| b.name).collect::<Vec<_>>();
+    assert_eq!(names, ["Unit", "Nested", "Flag", "Optional", "Lazy"]);
+
+    let nested = report.branch("Nested").unwrap();
+    assert_eq!(nested.size, layout::report::<Part, u32>().map.size);
+    assert_eq!(
+        nested.type_name,
+        layout::report::<Part, u32>().map.type_name
+    );
+
+    let optional = report.branch("Optional").unwrap();
+    assert_eq!(
+        optional.size,
+        layout::report::<Option<Part>, u32>().map.size
+    );
+
+    let lazy = report.branch("Lazy").unwrap();
+    assert_eq!(lazy.size, size_of::<LazyMapStorage<Part, u32>>());
+    assert_eq!(lazy.align, align_of::<LazyMapStorage<Part, u32>>());
+
+    assert_disjoint(&report.branches, report.map.size);
+}
+
+#[test]
+fn builtin() {
+    let report = layout::report::<bool, u8>();
+    let names = report.branches.iter().map(|b| b.name).collect::<Vec<_>>();
+    assert_eq!(names, ["true", "false"]);
+    assert_disjoint(&report.branches, report.map.size);
+
+    let report = layout::report::<Option<Part>, u8>();
+    let names = report.branches.iter().map(|b| b.name).collect::<Vec<_>>();
+    assert_eq!(names, ["Some", "None"]);
+    assert_disjoint(&report.branches, report.map.size);
+
+    let report = layout::report::<u32, u8>();
+    assert!(report.branches.is_empty());
+    assert_eq!(report.padding(), 0);
+}
+
+#[test]
+fn transparent() {
+    let inner = layout::report::<Part, u16>();
+    let report = layout::report::<Wrapper, u16>();
+    assert_eq!(report.map.size, inner.map.size);
+    assert_eq!(report.branches, inner.branches);
+}
+
+#[test]
+fn display() {
+    let report = layout::report::<Part, u8>();
+    let output = report.to_string();
+    let mut lines = output.lines();
+
+    assert!(lines.next().unwrap().ends_with("size 6, align 1"));
+    assert!(lines.next().unwrap().starts_with("Set<"));
+    assert!(lines
+        .next()
+        .unwrap()
+        .starts_with("  One    offset    0  size    2"));
+    assert!(lines.next().unwrap().starts_with("  Two    offset    2"));
+    assert!(lines.next().unwrap().starts_with("  Three  offset    4"));
+    assert!(lines.next().is_none());
+}
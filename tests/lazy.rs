@@ -0,0 +1,104 @@
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+use core::mem::size_of;
+
+use fixed_map::map::Stats;
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Eager {
+    Channel(u32),
+    Main,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Lazy {
+    #[key(lazy)]
+    Channel(u32),
+    #[key(lazy)]
+    Nested(Part),
+    Main,
+}
+
+#[test]
+fn smaller_than_eager() {
+    assert!(size_of::<Map<Lazy, u64>>() < size_of::<Map<Eager, u64>>());
+}
+
+#[test]
+fn insert_and_iterate() {
+    let mut map = Map::new();
+    assert!(map.is_empty());
+    assert_eq!(map.len(), 0);
+    assert_eq!(map.get(Lazy::Channel(1)), None);
+    assert_eq!(map.remove(Lazy::Nested(Part::One)), None);
+    assert!(map.iter().next().is_none());
+
+    map.insert(Lazy::Channel(1), 1);
+    map.insert(Lazy::Nested(Part::Two), 2);
+    map.insert(Lazy::Main, 3);
+
+    assert!(!map.is_empty());
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.get(Lazy::Channel(1)), Some(&1));
+    assert_eq!(map.get(Lazy::Nested(Part::One)), None);
+    assert!(map.values().copied().eq([1, 2, 3]));
+    assert!(map
+        .keys()
+        .eq([Lazy::Channel(1), Lazy::Nested(Part::Two), Lazy::Main]));
+
+    for value in map.values_mut() {
+        *value *= 10;
+    }
+
+    assert_eq!(map.remove(Lazy::Nested(Part::Two)), Some(20));
+    map.retain(|key, _| key != Lazy::Main);
+    assert!(map.into_iter().eq([(Lazy::Channel(1), 10)]));
+}
+
+#[test]
+fn entry() {
+    let mut map = Map::new();
+    *map.entry(Lazy::Nested(Part::One)).or_default() += 1;
+    *map.entry(Lazy::Nested(Part::One)).or_default() += 1;
+    assert_eq!(map.get(Lazy::Nested(Part::One)), Some(&2));
+}
+
+#[test]
+fn unallocated_equals_empty() {
+    let a = Map::<Lazy, u32>::new();
+    let mut b = Map::new();
+    b.insert(Lazy::Channel(4), 4);
+    assert_ne!(a, b);
+    b.clear();
+    assert!(b.is_empty());
+    assert_eq!(a, b);
+}
+
+#[test]
+fn stats() {
+    let mut map = Map::new();
+    assert_eq!(
+        map.stats(),
+        Stats {
+            capacity: 1,
+            len: 0,
+            dynamic_buckets: 0,
+            dynamic_capacity: 0,
+        }
+    );
+
+    map.insert(Lazy::Nested(Part::One), 1);
+    map.insert(Lazy::Channel(4), 2);
+
+    let stats = map.stats();
+    assert_eq!(stats.capacity, 3);
+    assert_eq!(stats.len, 2);
+    assert_eq!(stats.dynamic_buckets, 1);
+}
@@ -0,0 +1,89 @@
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Unit,
+    Nested(Part),
+    Optional(Option<bool>),
+    Dynamic(u32),
+    #[key(values(1, 2))]
+    Mode(u8),
+    #[key(lazy)]
+    Lazy(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Level {
+    Low,
+    High,
+}
+
+fn convert<K>(map: Map<K, u32>) -> Map<K, String>
+where
+    K: Key + std::fmt::Debug,
+{
+    map.map_values(|key, value| format!("{key:?}={value}"))
+}
+
+#[test]
+fn map_values_unit() {
+    let map = Map::from([(Part::Two, 2)]);
+    let map = convert(map);
+    assert!(map.into_iter().eq([(Part::Two, String::from("Two=2"))]));
+
+    let map = convert(Map::from([(Level::Low, 1), (Level::High, 3)]));
+    assert!(map.into_iter().eq([
+        (Level::Low, String::from("Low=1")),
+        (Level::High, String::from("High=3")),
+    ]));
+}
+
+#[test]
+fn map_values_composite() {
+    let mut map = Map::new();
+    map.insert(MyKey::Unit, 1);
+    map.insert(MyKey::Nested(Part::Two), 2);
+    map.insert(MyKey::Optional(None), 3);
+    map.insert(MyKey::Optional(Some(true)), 4);
+    map.insert(MyKey::Dynamic(7), 5);
+    map.insert(MyKey::Mode(2), 6);
+    map.insert(MyKey::Lazy(false), 7);
+
+    let keys = map.keys().collect::<Vec<_>>();
+    let expected = map
+        .iter()
+        .map(|(key, value)| (key, format!("{key:?}={value}")))
+        .collect::<Vec<_>>();
+
+    let map = convert(map);
+    assert_eq!(map.len(), 7);
+    assert_eq!(map.keys().collect::<Vec<_>>(), keys);
+    assert_eq!(map.into_iter().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn map_values_builtin() {
+    let map = convert(Map::from([(false, 1)]));
+    assert_eq!(map.get(false).map(String::as_str), Some("false=1"));
+    assert_eq!(map.get(true), None);
+
+    let map = convert(Map::from([(Some(Part::One), 1), (None, 2)]));
+    assert!(map.into_iter().eq([
+        (Some(Part::One), String::from("Some(One)=1")),
+        (None, String::from("None=2")),
+    ]));
+}
+
+#[test]
+fn map_values_empty() {
+    let map = convert(Map::<MyKey, u32>::new());
+    assert!(map.is_empty());
+}
@@ -0,0 +1,62 @@
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(maps_to(Wide, Option<Narrow>))]
+enum Narrow {
+    First,
+    Second(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Wide {
+    Zeroth,
+    First,
+    Second(bool),
+}
+
+impl From<Narrow> for Wide {
+    fn from(value: Narrow) -> Self {
+        match value {
+            Narrow::First => Wide::First,
+            Narrow::Second(value) => Wide::Second(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(transparent, maps_to(Narrow))]
+struct Wrapper(Narrow);
+
+impl From<Wrapper> for Narrow {
+    fn from(value: Wrapper) -> Self {
+        value.0
+    }
+}
+
+#[test]
+fn map_keys_into() {
+    let map = Map::from_iter([
+        (Narrow::Second(false), 'a'),
+        (Narrow::First, 'b'),
+        (Narrow::Second(true), 'c'),
+    ]);
+
+    let wide: Map<Wide, char> = map.map_keys_into();
+    assert!(wide.iter().eq([
+        (Wide::First, &'b'),
+        (Wide::Second(true), &'c'),
+        (Wide::Second(false), &'a'),
+    ]));
+
+    let optional: Map<Option<Narrow>, char> = map.map_keys_into();
+    assert_eq!(optional.len(), 3);
+    assert_eq!(optional.get(Some(Narrow::First)), Some(&'b'));
+    assert_eq!(optional.get(None), None);
+}
+
+#[test]
+fn transparent() {
+    let map = Map::from_iter([(Wrapper(Narrow::First), 1)]);
+    let map: Map<Narrow, i32> = map.map_keys_into();
+    assert!(map.iter().eq([(Narrow::First, &1)]));
+}
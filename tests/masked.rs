@@ -0,0 +1,308 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::mem::size_of;
+use std::rc::Rc;
+
+use fixed_map::map::{Entry, OccupiedEntry, VacantEntry};
+use fixed_map::{Key, Map, Set};
+
+macro_rules! key {
+    ($(#[$meta:meta])* $name:ident) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Key)]
+        $(#[$meta])*
+        enum $name {
+            A,
+            B,
+            C,
+            D,
+            E,
+            F,
+        }
+    };
+}
+
+key!(
+    #[key(bitset, masked, groups)]
+    Masked
+);
+key!(Dense);
+
+const MASKED: [Masked; 6] = [
+    Masked::A,
+    Masked::B,
+    Masked::C,
+    Masked::D,
+    Masked::E,
+    Masked::F,
+];
+
+const DENSE: [Dense; 6] = [Dense::A, Dense::B, Dense::C, Dense::D, Dense::E, Dense::F];
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(masked)]
+enum Wide {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Outer {
+    Unit,
+    Inner(Masked),
+}
+
+fn hash<T>(value: &T) -> u64
+where
+    T: Hash,
+{
+    let mut state = DefaultHasher::new();
+    value.hash(&mut state);
+    state.finish()
+}
+
+#[test]
+fn size() {
+    assert_eq!(size_of::<Map<Masked, ()>>(), size_of::<Set<Masked>>());
+    assert_eq!(size_of::<Map<Masked, ()>>(), size_of::<u8>());
+    assert_eq!(size_of::<Map<Wide, ()>>(), size_of::<u16>());
+    assert!(size_of::<Map<Dense, ()>>() > size_of::<Set<Masked>>());
+}
+
+#[test]
+fn map() {
+    let mut map = Map::new();
+    assert_eq!(map.insert(Masked::E, 5), None);
+    assert_eq!(map.insert(Masked::B, 2), None);
+    assert_eq!(map.insert(Masked::E, 6), Some(5));
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(Masked::E), Some(&6));
+    assert_eq!(map.get(Masked::A), None);
+    assert!(map.iter().eq([(Masked::B, &2), (Masked::E, &6)]));
+    assert!(map.iter().rev().eq([(Masked::E, &6), (Masked::B, &2)]));
+
+    match map.entry(Masked::C) {
+        Entry::Vacant(entry) => {
+            entry.insert(3);
+        }
+        Entry::Occupied(..) => panic!("expected vacant entry"),
+    }
+
+    match map.entry(Masked::B) {
+        Entry::Occupied(entry) => assert_eq!(entry.remove(), 2),
+        Entry::Vacant(..) => panic!("expected occupied entry"),
+    }
+
+    assert!(map.keys().eq([Masked::C, Masked::E]));
+
+    for value in map.values_mut() {
+        *value *= 10;
+    }
+
+    assert!(map.values().eq([&30, &60]));
+
+    map.retain(|_, value| *value > 30);
+    assert!(map.keys().eq([Masked::E]));
+    assert_eq!(map.remove(Masked::E), Some(60));
+    assert_eq!(map.remove(Masked::E), None);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn unit_values() {
+    let mut map = Map::new();
+    map.insert(Wide::I, ());
+    map.insert(Wide::A, ());
+
+    assert_eq!(map.len(), 2);
+    assert!(map.contains_key(Wide::I));
+    assert!(!map.contains_key(Wide::H));
+    assert!(map.into_iter().eq([(Wide::A, ()), (Wide::I, ())]));
+}
+
+#[test]
+fn nested() {
+    let mut map = Map::new();
+    map.insert(Outer::Inner(Masked::D), 4);
+    map.insert(Outer::Unit, 0);
+    map.insert(Outer::Inner(Masked::A), 1);
+
+    assert!(map.keys().eq([
+        Outer::Unit,
+        Outer::Inner(Masked::A),
+        Outer::Inner(Masked::D)
+    ]));
+}
+
+#[test]
+fn groups() {
+    let mut map = Map::from_iter([(Masked::A, 1), (Masked::C, 3)]);
+    assert_eq!(map.len_of(MaskedGroup::C), 1);
+    assert_eq!(map.len_of(MaskedGroup::B), 0);
+
+    map.clear_of(MaskedGroup::A);
+    assert!(map.keys().eq([Masked::C]));
+}
+
+#[test]
+fn matches_dense_storage() {
+    // Every subset of the variants, with a value which depends on the index.
+    for mask in 0u32..64 {
+        for other in 0u32..64 {
+            let masked = |mask: u32| {
+                Map::from_iter(
+                    (0..6)
+                        .filter(|n| mask & (1 << n) != 0)
+                        .map(|n| (MASKED[n], n)),
+                )
+            };
+
+            let dense = |mask: u32| {
+                Map::from_iter(
+                    (0..6)
+                        .filter(|n| mask & (1 << n) != 0)
+                        .map(|n| (DENSE[n], n)),
+                )
+            };
+
+            let (a, b) = (masked(mask), masked(other));
+            let (c, d) = (dense(mask), dense(other));
+
+            assert_eq!(a == b, c == d);
+            assert_eq!(a.cmp(&b), c.cmp(&d), "{mask:06b} <=> {other:06b}");
+            assert_eq!(a.clone(), a);
+
+            if a == b {
+                assert_eq!(hash(&a), hash(&b));
+            }
+        }
+    }
+}
+
+#[test]
+fn drops_values() {
+    let value = Rc::new(());
+
+    let mut map = Map::new();
+    map.insert(Masked::A, value.clone());
+    map.insert(Masked::C, value.clone());
+    map.insert(Masked::F, value.clone());
+    assert_eq!(Rc::strong_count(&value), 4);
+
+    map.insert(Masked::C, value.clone());
+    assert_eq!(Rc::strong_count(&value), 4);
+
+    let copy = map.clone();
+    assert_eq!(Rc::strong_count(&value), 7);
+    drop(copy);
+    assert_eq!(Rc::strong_count(&value), 4);
+
+    let mut iter = map.clone().into_iter();
+    assert_eq!(iter.next().map(|(key, _)| key), Some(Masked::A));
+    assert_eq!(Rc::strong_count(&value), 6);
+    drop(iter);
+    assert_eq!(Rc::strong_count(&value), 4);
+
+    map.remove(Masked::A);
+    assert_eq!(Rc::strong_count(&value), 3);
+
+    map.clear();
+    assert_eq!(Rc::strong_count(&value), 1);
+
+    map.insert(Masked::B, value.clone());
+    drop(map);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn drain() {
+    let mut map = MASKED
+        .iter()
+        .rev()
+        .map(|&k| (k, k as u32))
+        .collect::<Map<_, _>>();
+
+    assert!(map.drain().eq(MASKED.iter().map(|&k| (k, k as u32))));
+    assert!(map.is_empty());
+    assert_eq!(map.stats().capacity, MASKED.len());
+}
+
+#[test]
+fn extract_if() {
+    let mut map = MASKED
+        .iter()
+        .rev()
+        .map(|&k| (k, k as u32))
+        .collect::<Map<_, _>>();
+
+    let extracted = map.extract_if(|_, v| *v % 2 == 0).collect::<Vec<_>>();
+
+    assert!(extracted.into_iter().eq(MASKED
+        .iter()
+        .map(|&k| (k, k as u32))
+        .filter(|(_, v)| v % 2 == 0)));
+    assert!(map.iter().map(|(k, v)| (k, *v)).eq(MASKED
+        .iter()
+        .map(|&k| (k, k as u32))
+        .filter(|(_, v)| v % 2 != 0)));
+}
+
+#[test]
+fn entries_mut() {
+    let value = Rc::new(());
+
+    let mut map = Map::new();
+    map.insert(Masked::A, value.clone());
+    map.insert(Masked::D, value.clone());
+
+    let mut keys = Vec::new();
+
+    for entry in map.entries_mut() {
+        match entry {
+            Entry::Occupied(entry) => {
+                keys.push(entry.key());
+                drop(entry.remove());
+            }
+            Entry::Vacant(entry) => {
+                if entry.key() == Masked::B {
+                    entry.insert(value.clone());
+                }
+            }
+        }
+    }
+
+    assert_eq!(keys, [Masked::A, Masked::D]);
+    assert!(map.keys().eq([Masked::B]));
+    assert_eq!(Rc::strong_count(&value), 2);
+
+    drop(map);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn narrow_mask_does_not_leak() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use fixed_map::map::storage::MaskedMapStorage;
+    use fixed_map::map::MapStorage;
+
+    let value = Rc::new(());
+    let mut storage = MaskedMapStorage::<Wide, Rc<()>, u8, 9>::empty();
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        storage.insert(Wide::I, value.clone());
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(storage.len(), 0);
+
+    drop(storage);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
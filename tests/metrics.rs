@@ -0,0 +1,74 @@
+#![cfg(feature = "metrics")]
+
+use std::sync::Mutex;
+
+use fixed_map::metrics::{self, Event, Operation, Recorder};
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    Head,
+    Body(bool),
+}
+
+struct Log(Mutex<Vec<(Operation, &'static str)>>);
+
+impl Recorder for Log {
+    fn record(&self, event: &Event) {
+        if event.key_type.ends_with("Part") {
+            self.0
+                .lock()
+                .unwrap()
+                .push((event.operation, event.variant));
+        }
+    }
+}
+
+static LOG: Log = Log(Mutex::new(Vec::new()));
+
+#[test]
+fn records_operations() {
+    metrics::set_recorder(&LOG).unwrap();
+    assert!(metrics::set_recorder(&LOG).is_err());
+
+    let mut map = Map::new();
+    map.insert(Part::Body(true), 1);
+    assert_eq!(map.get(Part::Body(true)), Some(&1));
+    assert_eq!(map.get(Part::Head), None);
+    assert!(!map.contains_key(Part::Body(false)));
+    map.remove(Part::Body(true));
+    *map.entry(Part::Head).or_default() += 1;
+    *map.entry_ref(&Part::Head).or_default() += 1;
+    assert_eq!(map.values().sum::<i32>(), 2);
+
+    let mut set = Set::new();
+    set.insert(Part::Head);
+    assert!(set.contains(Part::Head));
+    assert!(set.remove(Part::Head));
+    assert_eq!(set.iter().count(), 0);
+
+    assert!(map.insert_unique(Part::Head, 3).is_err());
+    assert!(map.insert_unique(Part::Body(false), 3).is_ok());
+    assert!(map.try_insert(Part::Head, 4).is_err());
+    assert!(map.try_insert(Part::Body(true), 4).is_ok());
+
+    let log = LOG.0.lock().unwrap();
+
+    assert_eq!(
+        *log,
+        [
+            (Operation::Insert, "Body"),
+            (Operation::Hit, "Body"),
+            (Operation::Miss, "Head"),
+            (Operation::Miss, "Body"),
+            (Operation::Remove, "Body"),
+            (Operation::Miss, "Head"),
+            (Operation::Hit, "Head"),
+            (Operation::Insert, "Head"),
+            (Operation::Hit, "Head"),
+            (Operation::Remove, "Head"),
+            (Operation::Insert, "Body"),
+            (Operation::Insert, "Body"),
+        ]
+    );
+}
@@ -0,0 +1,53 @@
+#![cfg(all(feature = "serde", feature = "alloc"))]
+
+use fixed_map::migrate::MapWithUnknown;
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Color {
+    Red,
+    Green,
+}
+
+impl serde::Serialize for Color {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(match self {
+            Color::Red => "red",
+            Color::Green => "green",
+        })
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Color {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        match String::deserialize(deserializer)?.as_str() {
+            "red" => Ok(Color::Red),
+            "green" => Ok(Color::Green),
+            other => Err(serde::de::Error::unknown_variant(other, &["red", "green"])),
+        }
+    }
+}
+
+#[test]
+fn round_trip_unknown() {
+    let json = r#"{"green":1,"purple":2,"red":3,"blue":4}"#;
+
+    let map: MapWithUnknown<Color, u32> = serde_json::from_str(json).unwrap();
+    assert!(map.map.iter().eq([(Color::Red, &3), (Color::Green, &1)]));
+    assert_eq!(
+        map.unknown,
+        [(String::from("purple"), 2), (String::from("blue"), 4)]
+    );
+
+    let output = serde_json::to_string(&map).unwrap();
+    assert_eq!(output, r#"{"red":3,"green":1,"purple":2,"blue":4}"#);
+
+    // Without the side table the unknown keys are an error.
+    assert!(serde_json::from_str::<Map<Color, u32>>(json).is_err());
+}
@@ -0,0 +1,265 @@
+//! Model-based tests which compare the behavior of [`Map`] and [`Set`] with
+//! the collections in `std` over random sequences of operations.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use fixed_map::{Key, Map, Set};
+use proptest::prelude::*;
+use proptest::sample::select;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Key)]
+enum Simple {
+    First,
+    Second,
+    Third,
+    Fourth,
+}
+
+fn simple() -> impl Strategy<Value = Simple> + Clone {
+    select(&[Simple::First, Simple::Second, Simple::Third, Simple::Fourth][..])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Key)]
+#[key(bitset)]
+enum Bits {
+    First,
+    Second,
+    Third,
+    Fourth,
+}
+
+fn bits() -> impl Strategy<Value = Bits> + Clone {
+    select(&[Bits::First, Bits::Second, Bits::Third, Bits::Fourth][..])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Key)]
+enum Composite {
+    Unit,
+    Bool(bool),
+    Option(Option<Simple>),
+    Nested(Simple),
+    #[cfg(feature = "hashbrown")]
+    Dynamic(u8),
+}
+
+fn composite() -> BoxedStrategy<Composite> {
+    let strategy = prop_oneof![
+        Just(Composite::Unit),
+        any::<bool>().prop_map(Composite::Bool),
+        proptest::option::of(simple()).prop_map(Composite::Option),
+        simple().prop_map(Composite::Nested),
+    ]
+    .boxed();
+
+    #[cfg(feature = "hashbrown")]
+    let strategy = prop_oneof![
+        4 => strategy,
+        1 => (0..8u8).prop_map(Composite::Dynamic),
+    ]
+    .boxed();
+
+    strategy
+}
+
+#[derive(Debug, Clone)]
+enum MapOp<K> {
+    Insert(K, u8),
+    Remove(K),
+    Get(K),
+    GetMut(K, u8),
+    EntryOrInsert(K, u8),
+    Retain(u8),
+    Clear,
+}
+
+fn map_ops<K>(key: impl Strategy<Value = K> + Clone) -> impl Strategy<Value = Vec<MapOp<K>>>
+where
+    K: Debug + Clone,
+{
+    let op = prop_oneof![
+        5 => (key.clone(), any::<u8>()).prop_map(|(k, v)| MapOp::Insert(k, v)),
+        3 => key.clone().prop_map(MapOp::Remove),
+        2 => key.clone().prop_map(MapOp::Get),
+        2 => (key.clone(), any::<u8>()).prop_map(|(k, v)| MapOp::GetMut(k, v)),
+        2 => (key, any::<u8>()).prop_map(|(k, v)| MapOp::EntryOrInsert(k, v)),
+        1 => any::<u8>().prop_map(MapOp::Retain),
+        1 => Just(MapOp::Clear),
+    ];
+
+    proptest::collection::vec(op, 0..100)
+}
+
+#[derive(Debug, Clone)]
+enum SetOp<K> {
+    Insert(K),
+    Remove(K),
+    Contains(K),
+    Retain(Vec<K>),
+    Clear,
+}
+
+fn set_ops<K>(key: impl Strategy<Value = K> + Clone) -> impl Strategy<Value = Vec<SetOp<K>>>
+where
+    K: Debug + Clone,
+{
+    let op = prop_oneof![
+        5 => key.clone().prop_map(SetOp::Insert),
+        3 => key.clone().prop_map(SetOp::Remove),
+        2 => key.clone().prop_map(SetOp::Contains),
+        1 => proptest::collection::vec(key, 0..8).prop_map(SetOp::Retain),
+        1 => Just(SetOp::Clear),
+    ];
+
+    proptest::collection::vec(op, 0..100)
+}
+
+fn map_model<K>(ops: Vec<MapOp<K>>)
+where
+    K: Key + Debug + Eq + Hash,
+{
+    let mut map = Map::new();
+    let mut model = HashMap::new();
+
+    for op in ops {
+        match op {
+            MapOp::Insert(key, value) => {
+                assert_eq!(map.insert(key, value), model.insert(key, value));
+            }
+            MapOp::Remove(key) => {
+                assert_eq!(map.remove(key), model.remove(&key));
+            }
+            MapOp::Get(key) => {
+                assert_eq!(map.get(key), model.get(&key));
+                assert_eq!(map.contains_key(key), model.contains_key(&key));
+            }
+            MapOp::GetMut(key, value) => {
+                let a = map.get_mut(key);
+                let b = model.get_mut(&key);
+                assert_eq!(a, b);
+
+                if let (Some(a), Some(b)) = (a, b) {
+                    *a = value;
+                    *b = value;
+                }
+            }
+            MapOp::EntryOrInsert(key, value) => {
+                assert_eq!(
+                    *map.entry(key).or_insert(value),
+                    *model.entry(key).or_insert(value)
+                );
+            }
+            MapOp::Retain(threshold) => {
+                // Retain visits every entry exactly once, in iteration order.
+                let expected = map.keys().collect::<Vec<_>>();
+                let mut visited = Vec::new();
+
+                map.retain(|key, value| {
+                    visited.push(key);
+                    *value < threshold
+                });
+
+                model.retain(|_, value| *value < threshold);
+                assert_eq!(visited, expected);
+            }
+            MapOp::Clear => {
+                map.clear();
+                model.clear();
+            }
+        }
+
+        map.debug_assert_invariants();
+        assert_eq!(map.len(), model.len());
+        assert_eq!(map.is_empty(), model.is_empty());
+        assert_eq!(
+            map.iter().map(|(k, v)| (k, *v)).collect::<HashMap<_, _>>(),
+            model
+        );
+    }
+}
+
+fn set_model<K>(ops: Vec<SetOp<K>>)
+where
+    K: Key + Debug + Eq + Hash,
+{
+    let mut set = Set::new();
+    let mut model = HashSet::new();
+
+    for op in ops {
+        match op {
+            SetOp::Insert(key) => {
+                assert_eq!(set.insert(key), model.insert(key));
+            }
+            SetOp::Remove(key) => {
+                assert_eq!(set.remove(key), model.remove(&key));
+            }
+            SetOp::Contains(key) => {
+                assert_eq!(set.contains(key), model.contains(&key));
+            }
+            SetOp::Retain(keep) => {
+                let expected = set.iter().collect::<Vec<_>>();
+                let mut visited = Vec::new();
+
+                set.retain(|key| {
+                    visited.push(key);
+                    keep.contains(&key)
+                });
+
+                model.retain(|key| keep.contains(key));
+                assert_eq!(visited, expected);
+            }
+            SetOp::Clear => {
+                set.clear();
+                model.clear();
+            }
+        }
+
+        set.debug_assert_invariants();
+        assert_eq!(set.len(), model.len());
+        assert_eq!(set.is_empty(), model.is_empty());
+        assert_eq!(set.iter().collect::<HashSet<_>>(), model);
+    }
+}
+
+proptest! {
+    #[test]
+    fn map_simple(ops in map_ops(simple())) {
+        map_model(ops);
+    }
+
+    #[test]
+    fn map_bits(ops in map_ops(bits())) {
+        map_model(ops);
+    }
+
+    #[test]
+    fn map_composite(ops in map_ops(composite())) {
+        map_model(ops);
+    }
+
+    #[test]
+    fn map_option(ops in map_ops(proptest::option::of(composite()))) {
+        map_model(ops);
+    }
+
+    #[test]
+    fn set_simple(ops in set_ops(simple())) {
+        set_model(ops);
+    }
+
+    #[test]
+    fn set_bits(ops in set_ops(bits())) {
+        set_model(ops);
+    }
+
+    #[test]
+    fn set_composite(ops in set_ops(composite())) {
+        set_model(ops);
+    }
+
+    #[test]
+    fn set_option(ops in set_ops(proptest::option::of(composite()))) {
+        set_model(ops);
+    }
+}
@@ -0,0 +1,199 @@
+//! The basic map API should work with values which implement none of
+//! `Default`, `Clone`, `Debug`, `PartialEq` or `Send`.
+
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+mod common;
+
+use fixed_map::map::{Entry, OccupiedEntry, VacantEntry};
+use fixed_map::{Key, Map, Set};
+
+use self::common::{MyKey, Part, Single, Wrapper};
+
+/// A composite key whose storage contains no allocations.
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Inline {
+    Unit,
+    Nested(Part),
+    Optional(Option<bool>),
+    #[key(values(1, 2, 4))]
+    Mode(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum Flags {
+    A,
+    B,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(sparse)]
+enum Sparse {
+    A,
+    B,
+}
+
+/// A value which is `!Default`, `!Clone`, `!Debug`, `!PartialEq` and `!Send`.
+struct Opaque(Rc<Cell<u32>>);
+
+impl Opaque {
+    fn new(value: u32) -> Self {
+        Self(Rc::new(Cell::new(value)))
+    }
+
+    fn get(&self) -> u32 {
+        self.0.get()
+    }
+}
+
+macro_rules! exercise {
+    ($a:expr, $b:expr) => {{
+        let (a, b) = ($a, $b);
+        let mut map = Map::new();
+        assert!(map.is_empty());
+
+        assert!(map.insert(a, Opaque::new(1)).is_none());
+        assert!(map.try_insert(b, Opaque::new(2)).is_ok());
+        assert!(map.try_insert(b, Opaque::new(3)).is_err());
+        assert_eq!(map.len(), 2);
+        assert!(map.contains_key(a));
+        assert_eq!(map.get(a).map(Opaque::get), Some(1));
+
+        if let Some(value) = map.get_mut(a) {
+            value.0.set(10);
+        }
+
+        for value in map.values_mut() {
+            value.0.set(value.get() + 1);
+        }
+
+        for (_, value) in map.iter_mut() {
+            value.0.set(value.get() + 1);
+        }
+
+        assert_eq!(map.values().map(Opaque::get).sum::<u32>(), 16);
+        assert_eq!(map.iter().count(), 2);
+        assert_eq!(map.keys().count(), 2);
+
+        let mut sum = 0;
+        map.values_with_index(|_, value| sum += value.get());
+        assert_eq!(sum, 16);
+
+        match map.entry(a) {
+            Entry::Occupied(mut entry) => {
+                entry.get_mut().0.set(1);
+            }
+            Entry::Vacant(..) => panic!("expected occupied entry"),
+        }
+
+        let value = map.remove(b).expect("missing value");
+        assert_eq!(value.get(), 4);
+
+        match map.entry(b) {
+            Entry::Vacant(entry) => {
+                entry.insert(Opaque::new(2));
+            }
+            Entry::Occupied(..) => panic!("expected vacant entry"),
+        }
+
+        assert_eq!(map.entry(b).or_insert_with(|| Opaque::new(0)).get(), 2);
+
+        let [x, y] = map.get_disjoint_mut([a, b]);
+        assert!(x.is_some() && y.is_some());
+
+        let keys = Set::from_keys_of(&map);
+        assert_eq!(keys.len(), 2);
+
+        map.retain(|key, _| key == a);
+        assert_eq!(map.len(), 1);
+
+        map.extend([(b, Opaque::new(5))]);
+        assert_eq!(map.drain().count(), 2);
+
+        let mut map = Map::from_iter([(a, Opaque::new(1)), (b, Opaque::new(2))]);
+        assert_eq!(map.extract_if(|key, _| key == a).count(), 1);
+        assert_eq!(map.remove_and_shrink(b).map(|v| v.get()), Some(2));
+
+        let map = Map::from_iter([(a, Opaque::new(1)), (b, Opaque::new(2))]);
+        assert_eq!(map.into_values().map(|v| v.get()).sum::<u32>(), 3);
+
+        let map = Map::from_iter([(a, Opaque::new(1)), (b, Opaque::new(2))]);
+        assert_eq!(map.into_keys().count(), 2);
+
+        let mut map = Map::from_iter([(a, Opaque::new(1)), (b, Opaque::new(2))]);
+        map.clear();
+        assert!(map.into_iter().next().is_none());
+    }};
+}
+
+#[test]
+fn opaque_values() {
+    exercise!(Part::One, Part::Two);
+    exercise!(Wrapper(Part::One), Wrapper(Part::Two));
+    exercise!(MyKey::Unit, MyKey::Dynamic(7));
+    exercise!(MyKey::Nested(Part::Two), MyKey::Optional(None));
+    exercise!(
+        MyKey::Optional(Some(true)),
+        MyKey::Wrapped(Wrapper(Part::One))
+    );
+    exercise!(MyKey::Single(Single::Only), MyKey::Mode(4));
+    exercise!(MyKey::Lazy(Part::One), MyKey::Lazy(Part::Two));
+    exercise!(Inline::Nested(Part::One), Inline::Mode(1));
+    exercise!(Flags::A, Flags::B);
+    exercise!(Sparse::A, Sparse::B);
+    exercise!(true, false);
+    exercise!(Some(Part::One), None);
+    exercise!(1u32, 2u32);
+    exercise!("a", "b");
+}
+
+/// Value impls should only require as much of the value as the operation needs.
+#[test]
+fn value_bounds() {
+    fn copy<T: Copy>(value: T) -> (T, T) {
+        (value, value)
+    }
+
+    fn default<T: Default>() -> T {
+        T::default()
+    }
+
+    let map = Map::<Part, Opaque>::default();
+    assert!(map.is_empty());
+    let map = default::<Map<MyKey, Opaque>>();
+    assert!(map.is_empty());
+    let set = default::<Set<MyKey>>();
+    assert!(set.is_empty());
+
+    // Clone but not Copy values.
+    let mut map = Map::<MyKey, String>::new();
+    map.insert(MyKey::Dynamic(1), String::from("a"));
+    map.insert(MyKey::Nested(Part::One), String::from("b"));
+    assert_eq!(map.clone(), map);
+
+    // Copy values only need the value to be copy.
+    let mut map = Map::<Part, u32>::new();
+    map.insert(Part::One, 1);
+    let (a, b) = copy(map);
+    assert_eq!(a, b);
+
+    let mut map = Map::<Wrapper, u32>::new();
+    map.insert(Wrapper(Part::Two), 2);
+    let (a, b) = copy(map);
+    assert_eq!(a, b);
+
+    let mut map = Map::<Inline, u32>::new();
+    map.insert(Inline::Optional(None), 1);
+    map.insert(Inline::Mode(2), 2);
+    let (a, b) = copy(map);
+    assert_eq!(a, b);
+
+    let mut map = Map::<Flags, u32>::new();
+    map.insert(Flags::B, 2);
+    let (a, b) = copy(map);
+    assert_eq!(a, b);
+}
@@ -0,0 +1,75 @@
+//! Variants marked with `#[key(optional)]` are only stored if the dynamic type
+//! they hold can be used as a key under the enabled features.
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    First,
+    #[key(optional)]
+    Second(u32),
+    #[key(optional)]
+    Third(&'static str),
+    Fourth(bool),
+}
+
+#[test]
+fn available_variants() {
+    let mut map = Map::new();
+    map.insert(MyKey::First, 1);
+    map.insert(MyKey::Fourth(true), 4);
+
+    assert_eq!(map.get(MyKey::First), Some(&1));
+    assert_eq!(map.get(MyKey::Second(2)), None);
+    assert!(!map.contains_key(MyKey::Third("three")));
+    assert_eq!(map.remove(MyKey::Second(2)), None);
+    assert_eq!(MyKey::CAPACITY, None);
+
+    let mut set = Set::new();
+    set.insert(MyKey::First);
+    assert!(!set.contains(MyKey::Second(2)));
+    assert!(!set.remove(MyKey::Third("three")));
+}
+
+#[test]
+#[cfg(any(feature = "hashbrown", feature = "std-collections"))]
+fn enabled() {
+    #[derive(Clone, Copy, Key)]
+    enum Plain {
+        First,
+        Second(u32),
+        Third(&'static str),
+        Fourth(bool),
+    }
+
+    let mut map = Map::new();
+    map.insert(MyKey::Second(2), 2);
+    map.insert(MyKey::Third("three"), 3);
+
+    assert_eq!(map.get(MyKey::Second(2)), Some(&2));
+    assert_eq!(map.len(), 2);
+    assert_eq!(format!("{map:?}"), r#"{Second(2): 2, Third("three"): 3}"#);
+
+    let mut set = Set::new();
+    assert!(set.insert(MyKey::Second(2)));
+    assert!(set.contains(MyKey::Second(2)));
+
+    // NB: Marking a variant as optional doesn't change the schema of the key.
+    assert_eq!(MyKey::KEY_SCHEMA_HASH, Plain::KEY_SCHEMA_HASH);
+}
+
+#[test]
+#[cfg(not(any(feature = "hashbrown", feature = "std-collections")))]
+#[should_panic = "`u32` can only be stored in a key if the `hashbrown` or `std-collections` feature of fixed-map is enabled"]
+fn disabled_insert() {
+    let mut map = Map::new();
+    map.insert(MyKey::Second(2), 2);
+}
+
+#[test]
+#[cfg(not(any(feature = "hashbrown", feature = "std-collections")))]
+#[should_panic = "can only be stored in a key"]
+fn disabled_set_insert() {
+    let mut set = Set::new();
+    set.insert(MyKey::Third("three"));
+}
@@ -0,0 +1,118 @@
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+use fixed_map::map::Stats;
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Header {
+    #[key(dynamic = "ordered")]
+    Custom(&'static str),
+    #[key(dynamic = "ordered")]
+    Code(u32),
+    Host,
+}
+
+const NAMES: [&str; 6] = ["x-trace", "accept", "x-id", "cookie", "b", "a"];
+
+#[test]
+fn iteration_is_sorted() {
+    let mut map = Map::new();
+    map.insert(Header::Host, 0);
+
+    for (n, name) in NAMES.into_iter().enumerate() {
+        map.insert(Header::Custom(name), n);
+    }
+
+    map.insert(Header::Code(404), 10);
+    map.insert(Header::Code(200), 11);
+
+    assert!(map.keys().eq([
+        Header::Custom("a"),
+        Header::Custom("accept"),
+        Header::Custom("b"),
+        Header::Custom("cookie"),
+        Header::Custom("x-id"),
+        Header::Custom("x-trace"),
+        Header::Code(200),
+        Header::Code(404),
+        Header::Host,
+    ]));
+
+    assert_eq!(
+        format!("{map:?}"),
+        "{Custom(\"a\"): 5, Custom(\"accept\"): 1, Custom(\"b\"): 4, Custom(\"cookie\"): 3, \
+         Custom(\"x-id\"): 2, Custom(\"x-trace\"): 0, Code(200): 11, Code(404): 10, Host: 0}"
+    );
+}
+
+#[test]
+fn insertion_order_does_not_matter() {
+    let a = NAMES
+        .into_iter()
+        .map(|name| (Header::Custom(name), name.len()))
+        .collect::<Map<_, _>>();
+    let b = NAMES
+        .into_iter()
+        .rev()
+        .map(|name| (Header::Custom(name), name.len()))
+        .collect::<Map<_, _>>();
+
+    assert_eq!(a, b);
+    assert!(a.iter().eq(b.iter()));
+    assert_eq!(format!("{a:?}"), format!("{b:?}"));
+}
+
+#[test]
+fn entry_drain_and_extract_if() {
+    let mut map = Map::new();
+    *map.entry(Header::Code(3)).or_default() += 1;
+    *map.entry(Header::Code(1)).or_default() += 1;
+    *map.entry(Header::Code(3)).or_default() += 1;
+
+    assert!(map
+        .iter()
+        .eq([(Header::Code(1), &1), (Header::Code(3), &2)]));
+
+    map.insert(Header::Code(2), 5);
+    let extracted = map.extract_if(|_, v| *v > 1).collect::<Vec<_>>();
+    assert_eq!(extracted, [(Header::Code(2), 5), (Header::Code(3), 2)]);
+    assert!(map.iter().eq([(Header::Code(1), &1)]));
+
+    assert!(map.drain().eq([(Header::Code(1), 1)]));
+    assert!(map.is_empty());
+}
+
+#[test]
+fn stats() {
+    let mut map = Map::new();
+    map.insert(Header::Custom("a"), 1);
+    map.insert(Header::Custom("b"), 2);
+
+    assert_eq!(
+        map.stats(),
+        Stats {
+            capacity: 1,
+            len: 2,
+            dynamic_buckets: 2,
+            dynamic_capacity: 2,
+        }
+    );
+}
+
+#[test]
+fn set() {
+    let mut set = Set::new();
+    set.insert(Header::Host);
+
+    for name in NAMES {
+        set.insert(Header::Custom(name));
+    }
+
+    assert!(set.contains(Header::Custom("cookie")));
+    assert!(!set.contains(Header::Custom("host")));
+    assert_eq!(
+        format!("{set:?}"),
+        "{Custom(\"a\"), Custom(\"accept\"), Custom(\"b\"), Custom(\"cookie\"), \
+         Custom(\"x-id\"), Custom(\"x-trace\"), Host}"
+    );
+}
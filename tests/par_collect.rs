@@ -0,0 +1,51 @@
+#![cfg(feature = "rayon")]
+
+use fixed_map::{Key, Map};
+use rayon::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    First,
+    Second(bool),
+    Third,
+}
+
+fn key(n: u32) -> MyKey {
+    match n % 4 {
+        0 => MyKey::First,
+        1 => MyKey::Second(true),
+        2 => MyKey::Second(false),
+        _ => MyKey::Third,
+    }
+}
+
+#[test]
+fn collect_matches_sequential() {
+    let values = (0..10_000u32).collect::<Vec<_>>();
+
+    let parallel: Map<_, _> = values.par_iter().map(|&n| (key(n), n)).collect();
+    let sequential: Map<_, _> = values.iter().map(|&n| (key(n), n)).collect();
+    assert_eq!(parallel, sequential);
+
+    let mut extended = Map::from([(MyKey::First, 0), (MyKey::Third, 0)]);
+    extended.par_extend(
+        values
+            .par_iter()
+            .filter(|&&n| n % 4 == 0)
+            .map(|&n| (key(n), n)),
+    );
+    assert!(extended
+        .into_iter()
+        .eq([(MyKey::First, 9996), (MyKey::Third, 0)]));
+}
+
+#[test]
+fn sum_counters() {
+    let counts: Map<_, u32> = (0..10_000u32)
+        .into_par_iter()
+        .map(|n| Map::from([(key(n), 1)]))
+        .sum();
+
+    assert!(counts.values().all(|&n| n == 2500));
+    assert_eq!(counts.len(), 4);
+}
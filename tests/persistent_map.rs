@@ -0,0 +1,305 @@
+#![cfg(feature = "alloc")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use fixed_map::raw::KeyIndex;
+use fixed_map::{Key, Map, PersistentMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Field {
+    Title,
+    Head,
+    Body,
+    Flag,
+}
+
+static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, PartialEq)]
+struct Document(u32);
+
+impl Clone for Document {
+    fn clone(&self) -> Self {
+        CLONES.fetch_add(1, Ordering::SeqCst);
+        Document(self.0)
+    }
+}
+
+#[test]
+fn undo_stack() {
+    let mut history = vec![PersistentMap::new()];
+
+    for (n, key) in [Field::Title, Field::Body, Field::Flag, Field::Title]
+        .into_iter()
+        .enumerate()
+    {
+        let next = history.last().unwrap().insert(key, Document(n as u32));
+        history.push(next);
+    }
+
+    history.push(history.last().unwrap().remove(Field::Body));
+
+    assert_eq!(CLONES.load(Ordering::SeqCst), 0);
+
+    let last = history.last().unwrap();
+    assert!(last
+        .iter()
+        .eq([(Field::Title, &Document(3)), (Field::Flag, &Document(2))]));
+
+    assert_eq!(history[1].get(Field::Title), Some(&Document(0)));
+    assert_eq!(history[2].len(), 2);
+    assert!(core::ptr::eq(
+        history[3].get(Field::Flag).unwrap(),
+        last.get(Field::Flag).unwrap()
+    ));
+
+    let update = last.update(Field::Head, |_| unreachable!());
+    assert!(PersistentMap::ptr_eq(last, &update));
+}
+
+#[test]
+fn from_map() {
+    let mut map = Map::new();
+    map.insert(Field::Head, 1);
+    map.insert(Field::Flag, 2);
+
+    let persistent = PersistentMap::from(map);
+    assert_eq!(persistent.len(), 2);
+    assert_eq!(persistent.get(Field::Flag), Some(&2));
+    assert_eq!(
+        persistent,
+        PersistentMap::new()
+            .insert(Field::Flag, 2)
+            .insert(Field::Head, 1)
+    );
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Wide {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+}
+
+#[test]
+fn many_variants() {
+    let keys = [
+        Wide::A,
+        Wide::B,
+        Wide::C,
+        Wide::D,
+        Wide::E,
+        Wide::F,
+        Wide::G,
+        Wide::H,
+        Wide::I,
+        Wide::J,
+        Wide::K,
+        Wide::L,
+        Wide::M,
+        Wide::N,
+        Wide::O,
+        Wide::P,
+        Wide::Q,
+        Wide::R,
+        Wide::S,
+        Wide::T,
+    ];
+
+    let mut map = PersistentMap::new();
+
+    for (n, &key) in keys.iter().enumerate().rev() {
+        map = map.insert(key, n);
+    }
+
+    assert_eq!(map.len(), keys.len());
+    assert_eq!(map.iter().len(), keys.len());
+    assert!(map.iter().eq(keys
+        .iter()
+        .copied()
+        .zip(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19])));
+
+    let edited = map.insert(Wide::S, 100).remove(Wide::B);
+    assert_eq!(edited.len(), keys.len() - 1);
+    assert_eq!(edited.get(Wide::S), Some(&100));
+    assert_eq!(edited.get(Wide::B), None);
+    assert_eq!(map.get(Wide::S), Some(&18));
+    assert_eq!(map.get(Wide::B), Some(&1));
+
+    for key in [Wide::A, Wide::K, Wide::T] {
+        assert!(core::ptr::eq(
+            map.get(key).unwrap(),
+            edited.get(key).unwrap()
+        ));
+    }
+
+    let emptied = keys.iter().fold(edited, |map, &key| map.remove(key));
+    assert!(emptied.is_empty());
+    assert_eq!(emptied.iter().next(), None);
+    assert_eq!(emptied, PersistentMap::new());
+
+    let from = PersistentMap::from(Map::from_iter(keys.iter().map(|&key| (key, key as usize))));
+    assert_eq!(from, map);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Deep {
+    V0,
+    V1,
+    V2,
+    V3,
+    V4,
+    V5,
+    V6,
+    V7,
+    V8,
+    V9,
+    V10,
+    V11,
+    V12,
+    V13,
+    V14,
+    V15,
+    V16,
+    V17,
+    V18,
+    V19,
+    V20,
+    V21,
+    V22,
+    V23,
+    V24,
+    V25,
+    V26,
+    V27,
+    V28,
+    V29,
+    V30,
+    V31,
+    V32,
+    V33,
+    V34,
+    V35,
+    V36,
+    V37,
+    V38,
+    V39,
+    V40,
+    V41,
+    V42,
+    V43,
+    V44,
+    V45,
+    V46,
+    V47,
+    V48,
+    V49,
+    V50,
+    V51,
+    V52,
+    V53,
+    V54,
+    V55,
+    V56,
+    V57,
+    V58,
+    V59,
+    V60,
+    V61,
+    V62,
+    V63,
+    V64,
+    V65,
+    V66,
+    V67,
+    V68,
+    V69,
+    V70,
+    V71,
+    V72,
+    V73,
+    V74,
+    V75,
+    V76,
+    V77,
+    V78,
+    V79,
+    V80,
+    V81,
+    V82,
+    V83,
+    V84,
+    V85,
+    V86,
+    V87,
+    V88,
+    V89,
+    V90,
+    V91,
+    V92,
+    V93,
+    V94,
+    V95,
+    V96,
+    V97,
+    V98,
+    V99,
+}
+
+#[test]
+fn deep_tree() {
+    let keys = (0..Deep::LEN).map(|n| Deep::from_index(n).unwrap());
+
+    // Insert in a scrambled order so that chunks are created all over.
+    let mut map = PersistentMap::new();
+
+    for n in (0..Deep::LEN).map(|n| n * 37 % Deep::LEN) {
+        map = map.insert(Deep::from_index(n).unwrap(), n);
+    }
+
+    assert_eq!(map.len(), 100);
+    assert_eq!(map.iter().len(), 100);
+    assert!(map.iter().map(|(k, v)| (k, *v)).eq(keys.clone().zip(0..)));
+
+    let mut edited = map.clone();
+
+    for key in keys.clone().filter(|key| key.to_index() % 3 == 0) {
+        edited = edited.remove(key);
+    }
+
+    assert_eq!(edited.len(), 100 - 34);
+    assert_eq!(edited.get(Deep::V3), None);
+    assert_eq!(edited.get(Deep::V4), Some(&4));
+    assert_eq!(map.get(Deep::V3), Some(&3));
+    assert!(core::ptr::eq(
+        map.get(Deep::V98).unwrap(),
+        edited.get(Deep::V98).unwrap()
+    ));
+
+    assert_eq!(
+        PersistentMap::from(Map::from_iter(keys.clone().zip(0..))),
+        map
+    );
+
+    // Removing every key prunes all chunks.
+    let emptied = keys.fold(map, |map, key| map.remove(key));
+    assert!(emptied.is_empty());
+    assert!(PersistentMap::ptr_eq(&emptied, &PersistentMap::new()));
+}
@@ -0,0 +1,35 @@
+use fixed_map::raw::{BitIndex, InvalidPrimitive};
+use fixed_map::Key;
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset, primitive = u16)]
+enum Bits {
+    First,
+    Second,
+    Third = 10,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(primitive = u8)]
+enum Single {
+    Only,
+}
+
+#[test]
+fn round_trip() {
+    for key in [Bits::First, Bits::Second, Bits::Third] {
+        let value = u16::from(key);
+        assert_eq!(u32::from(value), key.to_bit_index());
+        assert_eq!(Bits::try_from(value), Ok(key));
+    }
+
+    assert_eq!(u16::from(Bits::Third), 2);
+    assert_eq!(Bits::try_from(3), Err(InvalidPrimitive::new(3)));
+    assert_eq!(
+        Bits::try_from(10).unwrap_err().to_string(),
+        "10 does not correspond to a variant of the key"
+    );
+
+    assert_eq!(u8::from(Single::Only), 0);
+    assert_eq!(Single::try_from(1).unwrap_err().value(), 1);
+}
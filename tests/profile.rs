@@ -0,0 +1,135 @@
+#![cfg(feature = "hashbrown")]
+
+use core::mem;
+
+use fixed_map::map::{Entry, OccupiedEntry, VacantEntry};
+use fixed_map::profile::Fast;
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Key)]
+enum MyKey {
+    Unit,
+    Nested(Part),
+    Optional(Option<bool>),
+    Dynamic(u32),
+}
+
+#[test]
+fn layout() {
+    assert_eq!(mem::align_of::<Map<Fast<Part>, u8>>(), 64);
+    assert_eq!(mem::size_of::<Map<Fast<Part>, u8>>(), 64);
+    assert_eq!(mem::size_of::<Map<Part, u8>>(), 4);
+    assert_eq!(mem::align_of::<Set<Fast<MyKey>>>(), 64);
+    assert_eq!(Fast::<MyKey>::KEY_SCHEMA_HASH, MyKey::KEY_SCHEMA_HASH);
+    assert_eq!(Fast::<Part>::CAPACITY, Part::CAPACITY);
+}
+
+#[test]
+fn same_behavior() {
+    let keys = [
+        MyKey::Dynamic(3),
+        MyKey::Nested(Part::Two),
+        MyKey::Unit,
+        MyKey::Optional(None),
+        MyKey::Optional(Some(true)),
+    ];
+
+    let mut compact = Map::new();
+    let mut fast = Map::new();
+
+    for (n, key) in keys.into_iter().enumerate() {
+        assert_eq!(compact.insert(key, n), fast.insert(Fast(key), n));
+    }
+
+    assert!(fast.iter().map(|(k, v)| (k.0, v)).eq(compact.iter()));
+    assert_eq!(fast.len(), compact.len());
+    assert_eq!(fast.get(Fast(MyKey::Unit)), Some(&2));
+
+    match fast.entry(Fast(MyKey::Nested(Part::One))) {
+        Entry::Vacant(entry) => {
+            assert_eq!(entry.key(), Fast(MyKey::Nested(Part::One)));
+            entry.insert(10);
+        }
+        Entry::Occupied(..) => panic!("expected vacant entry"),
+    }
+
+    match fast.entry(Fast(MyKey::Unit)) {
+        Entry::Occupied(entry) => {
+            assert_eq!(entry.key(), Fast(MyKey::Unit));
+            assert_eq!(entry.remove(), 2);
+        }
+        Entry::Vacant(..) => panic!("expected occupied entry"),
+    }
+
+    fast.retain(|key, _| !matches!(key.0, MyKey::Optional(..)));
+    assert!(fast.keys().map(Fast::into_inner).eq([
+        MyKey::Nested(Part::One),
+        MyKey::Nested(Part::Two),
+        MyKey::Dynamic(3),
+    ]));
+
+    let set = fast.keys_set();
+    assert!(set.contains(Fast(MyKey::Dynamic(3))));
+    assert_eq!(set.len(), 3);
+    assert_eq!(format!("{fast:?}"), format!("{:?}", fast.clone()));
+}
+
+#[test]
+fn set_operations() {
+    let mut a = Set::new();
+    a.insert(Fast(Part::One));
+
+    let mut b = Set::new();
+    b.insert(Fast(Part::Two));
+
+    let mut union = a;
+    union.union_with(&b);
+    assert!(union.iter().eq([Fast(Part::One), Fast(Part::Two)]));
+    assert_eq!(union.find(|key| key.0 == Part::Two), Some(Fast(Part::Two)));
+
+    union.subtract(&a);
+    assert!(union == b);
+}
+
+#[test]
+fn storage_extensions() {
+    let mut fast = Map::new();
+    fast.insert(Fast(Part::One), 1);
+    fast.insert(Fast(Part::Two), 2);
+
+    assert!(fast
+        .iter_indexed()
+        .eq([(0, Fast(Part::One), &1), (1, Fast(Part::Two), &2)]));
+    assert!(fast.range(Fast(Part::Two)..).eq([(Fast(Part::Two), &2)]));
+    assert_eq!(fast.stats().len, 2);
+
+    for entry in fast.entries_mut() {
+        match entry {
+            Entry::Occupied(mut entry) => {
+                *entry.get_mut() *= 10;
+            }
+            Entry::Vacant(..) => panic!("expected occupied entry"),
+        }
+    }
+
+    let extracted = fast
+        .extract_if(|key, _| key == Fast(Part::One))
+        .collect::<Vec<_>>();
+    assert_eq!(extracted, [(Fast(Part::One), 10)]);
+    assert!(fast.drain().eq([(Fast(Part::Two), 20)]));
+    assert!(fast.is_empty());
+
+    let mut fast = Map::new();
+    fast.insert(Fast(MyKey::Dynamic(1)), 1);
+    fast.insert(Fast(MyKey::Unit), 2);
+    assert!(fast
+        .drain()
+        .map(|(k, v)| (k.0, v))
+        .eq([(MyKey::Unit, 2), (MyKey::Dynamic(1), 1)]));
+}
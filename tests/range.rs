@@ -0,0 +1,63 @@
+use std::ops::Bound;
+
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    A,
+    B,
+    C,
+    D,
+    E,
+}
+
+fn map() -> Map<MyKey, u32> {
+    Map::from_iter([(MyKey::A, 1), (MyKey::C, 3), (MyKey::D, 4), (MyKey::E, 5)])
+}
+
+#[test]
+fn bounds() {
+    let map = map();
+
+    assert!(map.range(..).eq(map.iter()));
+    assert!(map
+        .range(MyKey::B..=MyKey::D)
+        .eq([(MyKey::C, &3), (MyKey::D, &4)]));
+    assert!(map.range(MyKey::A..MyKey::C).eq([(MyKey::A, &1)]));
+    assert!(map.range(..=MyKey::A).eq([(MyKey::A, &1)]));
+    assert!(map.range(MyKey::D..).eq([(MyKey::D, &4), (MyKey::E, &5)]));
+    assert!(map
+        .range((Bound::Excluded(MyKey::C), Bound::Included(MyKey::E)))
+        .eq([(MyKey::D, &4), (MyKey::E, &5)]));
+    assert!(map
+        .range((Bound::Excluded(MyKey::E), Bound::Unbounded))
+        .next()
+        .is_none());
+    assert!(map.range(MyKey::B..MyKey::B).next().is_none());
+}
+
+#[test]
+fn double_ended() {
+    let map = map();
+
+    assert!(map.range(MyKey::A..=MyKey::D).rev().eq([
+        (MyKey::D, &4),
+        (MyKey::C, &3),
+        (MyKey::A, &1)
+    ]));
+
+    let mut range = map.range(..);
+    assert_eq!(range.next(), Some((MyKey::A, &1)));
+    assert_eq!(range.next_back(), Some((MyKey::E, &5)));
+    assert!(range.clone().eq([(MyKey::C, &3), (MyKey::D, &4)]));
+    assert_eq!(range.next(), Some((MyKey::C, &3)));
+    assert_eq!(range.next_back(), Some((MyKey::D, &4)));
+    assert_eq!(range.next(), None);
+    assert_eq!(range.next_back(), None);
+}
+
+#[test]
+#[should_panic = "range start is declared after range end"]
+fn out_of_order() {
+    let _ = map().range(MyKey::D..MyKey::B);
+}
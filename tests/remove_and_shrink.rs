@@ -0,0 +1,129 @@
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(transparent)]
+struct Port(u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    First,
+    Dynamic(u32),
+    Nested(Part),
+    Optional(Option<u32>),
+    Wrapped(Port),
+    #[key(lazy)]
+    Lazy(Part),
+    Last,
+}
+
+fn populated() -> Map<MyKey, u32> {
+    let mut map = Map::new();
+    map.insert(MyKey::Last, 0);
+    map.insert(MyKey::Lazy(Part::Two), 1);
+    map.insert(MyKey::Wrapped(Port(80)), 2);
+    map.insert(MyKey::Optional(None), 3);
+    map.insert(MyKey::Optional(Some(7)), 4);
+    map.insert(MyKey::Nested(Part::Two), 5);
+    map.insert(MyKey::Nested(Part::One), 6);
+    map.insert(MyKey::Dynamic(1), 7);
+    map.insert(MyKey::First, 8);
+    map
+}
+
+#[test]
+fn mixed_iteration_order() {
+    let mut map = populated();
+
+    let expected = [
+        (MyKey::First, 8),
+        (MyKey::Dynamic(1), 7),
+        (MyKey::Nested(Part::One), 6),
+        (MyKey::Nested(Part::Two), 5),
+        (MyKey::Optional(Some(7)), 4),
+        (MyKey::Optional(None), 3),
+        (MyKey::Wrapped(Port(80)), 2),
+        (MyKey::Lazy(Part::Two), 1),
+        (MyKey::Last, 0),
+    ];
+
+    assert!(map.iter().map(|(k, v)| (k, *v)).eq(expected));
+
+    // Removing entries never reorders the ones which remain, regardless of
+    // which flavour of removal is used.
+    assert_eq!(map.remove(MyKey::Nested(Part::One)), Some(6));
+    assert_eq!(map.remove_and_shrink(MyKey::Optional(Some(7))), Some(4));
+
+    let remaining = expected.iter().copied().filter(|(_, v)| *v != 6 && *v != 4);
+
+    assert!(map.iter().map(|(k, v)| (k, *v)).eq(remaining));
+    map.debug_assert_invariants();
+}
+
+#[test]
+fn dynamic_branches_shrink() {
+    let mut map = Map::new();
+
+    for n in 0..64 {
+        map.insert(MyKey::Dynamic(n), n);
+        map.insert(MyKey::Optional(Some(n)), n);
+        map.insert(MyKey::Wrapped(Port(n)), n);
+    }
+
+    for n in 1..64 {
+        map.remove(MyKey::Dynamic(n));
+        map.remove(MyKey::Optional(Some(n)));
+        map.remove(MyKey::Wrapped(Port(n)));
+    }
+
+    assert!(map.stats().dynamic_capacity >= 3 * 64);
+
+    assert_eq!(map.remove_and_shrink(MyKey::Dynamic(0)), Some(0));
+    assert_eq!(map.remove_and_shrink(MyKey::Optional(Some(0))), Some(0));
+    assert_eq!(map.remove_and_shrink(MyKey::Wrapped(Port(0))), Some(0));
+
+    assert!(map.is_empty());
+    assert_eq!(map.stats().dynamic_capacity, 0);
+}
+
+#[test]
+fn lazy_branch_deallocates() {
+    let mut map = Map::new();
+    let empty = map.stats().capacity;
+
+    map.insert(MyKey::Lazy(Part::One), 1);
+    map.insert(MyKey::Lazy(Part::Two), 2);
+    assert_eq!(map.stats().capacity, empty + 2);
+
+    assert_eq!(map.remove_and_shrink(MyKey::Lazy(Part::One)), Some(1));
+    assert_eq!(map.stats().capacity, empty + 2);
+
+    assert_eq!(map.remove_and_shrink(MyKey::Lazy(Part::Two)), Some(2));
+    assert_eq!(map.remove_and_shrink(MyKey::Lazy(Part::Two)), None);
+    assert_eq!(map.stats().capacity, empty);
+}
+
+#[test]
+fn fixed_branches_match_remove() {
+    let mut a = populated();
+    let mut b = populated();
+
+    for key in [
+        MyKey::First,
+        MyKey::Nested(Part::Two),
+        MyKey::Optional(None),
+        MyKey::Last,
+    ] {
+        assert_eq!(a.remove(key), b.remove_and_shrink(key));
+    }
+
+    assert_eq!(a, b);
+    assert_eq!(a.stats(), b.stats());
+}
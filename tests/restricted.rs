@@ -0,0 +1,37 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    First,
+    Second(u32),
+    Third(bool),
+}
+
+#[test]
+fn restricted() {
+    let map = Map::from_iter([
+        (MyKey::First, 1),
+        (MyKey::Second(2), 2),
+        (MyKey::Second(3), 3),
+        (MyKey::Third(true), 4),
+    ]);
+
+    let allowed = Set::from_iter([MyKey::Second(3), MyKey::Third(true), MyKey::Third(false)]);
+    let view = map.restrict(&allowed);
+
+    assert!(!view.is_empty());
+    assert_eq!(view.len(), 2);
+    assert!(view.contains_key(MyKey::Second(3)));
+    assert!(!view.contains_key(MyKey::Second(2)));
+    assert!(!view.contains_key(MyKey::Third(false)));
+    assert_eq!(view.get(MyKey::First), None);
+    assert!(view.values().copied().eq([3, 4]));
+    assert_eq!(format!("{view:?}"), "{Second(3): 3, Third(true): 4}");
+
+    let empty = Set::new();
+    let view = map.restrict(&empty);
+    assert!(view.is_empty());
+    assert_eq!(view.len(), 0);
+}
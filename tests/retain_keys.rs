@@ -0,0 +1,90 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Unit,
+    Nested(Part),
+    Number(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum Flag {
+    First,
+    Second,
+    Third,
+}
+
+#[test]
+fn round_trip() {
+    let mut map = Map::new();
+    map.insert(MyKey::Unit, 1);
+    map.insert(MyKey::Nested(Part::Two), 2);
+    map.insert(MyKey::Number(3), 3);
+
+    let set = Set::from_keys_of(&map);
+    assert_eq!(set.len(), map.len());
+    assert!(set.iter().eq(map.keys()));
+
+    let before = map.clone();
+    map.retain_keys(&set);
+    assert_eq!(map, before);
+}
+
+#[test]
+fn retain_keys() {
+    let mut map = Map::new();
+    map.insert(MyKey::Unit, 1);
+    map.insert(MyKey::Nested(Part::One), 2);
+    map.insert(MyKey::Nested(Part::Two), 3);
+    map.insert(MyKey::Number(4), 4);
+    map.insert(MyKey::Number(5), 5);
+
+    let allowed = Set::from([MyKey::Nested(Part::Two), MyKey::Number(5)]);
+    map.retain_keys(&allowed);
+    assert!(map.keys().eq([MyKey::Nested(Part::Two), MyKey::Number(5)]));
+
+    map.retain_keys(&Set::new());
+    assert!(map.is_empty());
+
+    let mut map = Map::from_iter([(Flag::First, 1), (Flag::Second, 2), (Flag::Third, 3)]);
+    map.retain_keys(&Set::from([Flag::First, Flag::Third]));
+    assert!(map.keys().eq([Flag::First, Flag::Third]));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Mixed {
+    Flag(bool),
+    Optional(Option<Part>),
+    #[key(lazy)]
+    Lazy(Part),
+}
+
+#[test]
+fn built_in_keys() {
+    let mut map = Map::new();
+    map.insert(Mixed::Flag(true), 1);
+    map.insert(Mixed::Flag(false), 2);
+    map.insert(Mixed::Optional(Some(Part::One)), 3);
+    map.insert(Mixed::Optional(None), 4);
+    map.insert(Mixed::Lazy(Part::One), 5);
+    map.insert(Mixed::Lazy(Part::Two), 6);
+
+    let allowed = Set::from([
+        Mixed::Flag(false),
+        Mixed::Optional(None),
+        Mixed::Lazy(Part::Two),
+    ]);
+
+    map.retain_keys(&allowed);
+    assert!(map.keys().eq(allowed.iter()));
+    map.debug_assert_invariants();
+}
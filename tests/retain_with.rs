@@ -0,0 +1,50 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Unit,
+    Nested(Part),
+    Number(u32),
+}
+
+#[test]
+fn matches_retain() {
+    let mut a = Map::new();
+    a.insert(MyKey::Unit, 1);
+    a.insert(MyKey::Nested(Part::One), 2);
+    a.insert(MyKey::Nested(Part::Two), 3);
+    a.insert(MyKey::Number(4), 4);
+    a.insert(MyKey::Number(5), 5);
+
+    let mut b = a.clone();
+
+    let mut visited = 0;
+
+    a.retain_with(|_, value| {
+        visited += 1;
+        value % 2 == 1
+    });
+
+    b.retain(|_, value| *value % 2 == 1);
+
+    assert_eq!(visited, 5);
+    assert_eq!(a, b);
+    assert_eq!(a.len(), 3);
+    assert_eq!(a.get(MyKey::Nested(Part::One)), None);
+    assert_eq!(a.get(MyKey::Number(5)), Some(&5));
+}
+
+#[test]
+fn by_key() {
+    let mut map = Map::from_iter([(Part::One, "one"), (Part::Two, "two")]);
+    map.retain_with(|key, _| key == Part::Two);
+    assert!(map.into_iter().eq([(Part::Two, "two")]));
+}
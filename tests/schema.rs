@@ -0,0 +1,49 @@
+use fixed_map::{schema, Key};
+
+mod v1 {
+    use fixed_map::Key;
+
+    #[derive(Clone, Copy, Key)]
+    pub enum Part {
+        One,
+        Two,
+    }
+
+    #[derive(Clone, Copy, Key)]
+    pub enum MyKey {
+        First,
+        Second(Part),
+    }
+}
+
+mod v2 {
+    use fixed_map::Key;
+
+    #[derive(Clone, Copy, Key)]
+    pub enum Part {
+        Two,
+        One,
+    }
+
+    #[derive(Clone, Copy, Key)]
+    pub enum MyKey {
+        First,
+        Second(Part),
+    }
+}
+
+#[derive(Clone, Copy, Key)]
+#[key(transparent)]
+struct Wrapper(v1::MyKey);
+
+#[test]
+fn schema_hash() {
+    assert_eq!(v1::MyKey::KEY_SCHEMA_HASH, v1::MyKey::KEY_SCHEMA_HASH);
+    assert_ne!(v1::MyKey::KEY_SCHEMA_HASH, v2::MyKey::KEY_SCHEMA_HASH);
+    assert_ne!(v1::Part::KEY_SCHEMA_HASH, v2::Part::KEY_SCHEMA_HASH);
+    assert_eq!(Wrapper::KEY_SCHEMA_HASH, v1::MyKey::KEY_SCHEMA_HASH);
+    assert_ne!(bool::KEY_SCHEMA_HASH, <Option<bool>>::KEY_SCHEMA_HASH);
+
+    assert!(schema::check::<v1::MyKey>(v1::MyKey::KEY_SCHEMA_HASH).is_ok());
+    assert!(schema::check::<v2::MyKey>(v1::MyKey::KEY_SCHEMA_HASH).is_err());
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "serde")]
+
+use fixed_map::{Map, SerdeKey, Set};
+
+fn round_trip_map<K>(map: &Map<K, u32>) -> Map<K, u32>
+where
+    K: SerdeKey,
+{
+    serde_json::from_str(&serde_json::to_string(map).unwrap()).unwrap()
+}
+
+fn round_trip_set<K>(set: &Set<K>) -> Set<K>
+where
+    K: SerdeKey,
+{
+    serde_json::from_str(&serde_json::to_string(set).unwrap()).unwrap()
+}
+
+#[test]
+fn serde_key() {
+    let map = Map::from_iter([(true, 1), (false, 2)]);
+    assert_eq!(round_trip_map(&map), map);
+
+    let set = Set::from([Some(true), None]);
+    assert_eq!(round_trip_set(&set), set);
+}
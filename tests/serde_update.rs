@@ -0,0 +1,50 @@
+#![cfg(feature = "serde")]
+
+use fixed_map::{Map, Set};
+
+#[test]
+fn serialize_subset() {
+    let map = Map::from_iter([(true, 1u32)]);
+
+    let mut out = Vec::new();
+    map.serialize_subset(
+        &Set::from([true, false]),
+        &mut serde_json::Serializer::new(&mut out),
+    )
+    .unwrap();
+    assert_eq!(out, br#"{"true":1}"#);
+
+    let mut out = Vec::new();
+    map.serialize_subset(&Set::new(), &mut serde_json::Serializer::new(&mut out))
+        .unwrap();
+    assert_eq!(out, b"{}");
+}
+
+#[test]
+fn delta_round_trip() {
+    let mut source = Map::from_iter([(true, 1u32), (false, 2)]);
+    let mut replica = source;
+
+    source.insert(false, 20);
+
+    let mut delta = Vec::new();
+    source
+        .serialize_subset(
+            &Set::from([false]),
+            &mut serde_json::Serializer::new(&mut delta),
+        )
+        .unwrap();
+
+    let mut de = serde_json::Deserializer::from_slice(&delta);
+    replica.deserialize_update(&mut de).unwrap();
+    assert_eq!(replica, source);
+}
+
+#[test]
+fn deserialize_update_error_keeps_map() {
+    let mut map = Map::from_iter([(true, 1u32)]);
+
+    let mut de = serde_json::Deserializer::from_str(r#"{"false":"oops"}"#);
+    assert!(map.deserialize_update(&mut de).is_err());
+    assert!(map.iter().eq([(true, &1)]));
+}
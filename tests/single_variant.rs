@@ -0,0 +1,86 @@
+use core::mem::size_of;
+
+use fixed_map::map::{Entry, VacantEntry};
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Key)]
+enum Only {
+    Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum OnlyBits {
+    Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Outer {
+    Wrapped(Only),
+    Other,
+}
+
+#[test]
+fn layout() {
+    assert_eq!(size_of::<Map<Only, u32>>(), size_of::<Option<u32>>());
+    assert_eq!(size_of::<Map<Only, Box<u32>>>(), size_of::<Box<u32>>());
+    assert_eq!(size_of::<Set<Only>>(), size_of::<bool>());
+    assert_eq!(size_of::<Set<OnlyBits>>(), size_of::<u8>());
+}
+
+#[test]
+fn map() {
+    let mut map = Map::new();
+    assert!(map.is_empty());
+    assert_eq!(map.insert(Only::Value, 1), None);
+    assert_eq!(map.insert(Only::Value, 2), Some(1));
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get(Only::Value), Some(&2));
+    assert!(map.iter().eq([(Only::Value, &2)]));
+
+    *map.entry(Only::Value).or_default() += 1;
+    assert_eq!(map.get(Only::Value), Some(&3));
+
+    map.retain(|_, value| *value > 5);
+    assert!(map.is_empty());
+
+    match map.entry(Only::Value) {
+        Entry::Vacant(entry) => {
+            entry.insert(4);
+        }
+        Entry::Occupied(..) => panic!("expected vacant entry"),
+    }
+
+    assert!(map.into_iter().eq([(Only::Value, 4)]));
+    assert_eq!(map.remove(Only::Value), Some(4));
+    assert!(map.iter().next().is_none());
+    assert!(Map::<Only, u32>::new() < Map::from_iter([(Only::Value, 0)]));
+}
+
+#[test]
+fn set() {
+    let mut set = Set::new();
+    assert!(set.insert(Only::Value));
+    assert!(!set.insert(Only::Value));
+    assert!(set.contains(Only::Value));
+    assert!(set.iter().eq([Only::Value]));
+    assert!(Set::new() < set);
+
+    set.retain(|_| false);
+    assert!(set.is_empty());
+    assert!(!set.remove(Only::Value));
+}
+
+#[test]
+fn nested() {
+    let mut map = Map::new();
+    map.insert(Outer::Wrapped(Only::Value), 1);
+    map.insert(Outer::Other, 2);
+    assert!(map
+        .iter()
+        .eq([(Outer::Wrapped(Only::Value), &1), (Outer::Other, &2)]));
+
+    let set = Set::from([Outer::Wrapped(Only::Value)]);
+    assert!(set.contains(Outer::Wrapped(Only::Value)));
+    assert!(!set.contains(Outer::Other));
+}
@@ -0,0 +1,231 @@
+#![cfg(feature = "alloc")]
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use fixed_map::map::{Entry, OccupiedEntry, VacantEntry};
+use fixed_map::raw::{KeyIndex, SparseIndex};
+use fixed_map::{Key, Map, Set};
+
+macro_rules! key {
+    ($(#[$meta:meta])* $name:ident) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Key)]
+        $(#[$meta])*
+        enum $name {
+            A,
+            B,
+            C,
+            D,
+            E,
+            F,
+        }
+    };
+}
+
+key!(
+    #[key(sparse, groups)]
+    Sparse
+);
+key!(Dense);
+
+const SPARSE: [Sparse; 6] = [
+    Sparse::A,
+    Sparse::B,
+    Sparse::C,
+    Sparse::D,
+    Sparse::E,
+    Sparse::F,
+];
+
+const DENSE: [Dense; 6] = [Dense::A, Dense::B, Dense::C, Dense::D, Dense::E, Dense::F];
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Outer {
+    Unit,
+    Inner(Sparse),
+}
+
+fn hash<T>(value: &T) -> u64
+where
+    T: Hash,
+{
+    let mut state = DefaultHasher::new();
+    value.hash(&mut state);
+    state.finish()
+}
+
+#[test]
+fn map() {
+    let mut map = Map::new();
+    assert_eq!(map.insert(Sparse::E, 5), None);
+    assert_eq!(map.insert(Sparse::B, 2), None);
+    assert_eq!(map.insert(Sparse::E, 6), Some(5));
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(Sparse::E), Some(&6));
+    assert_eq!(map.get(Sparse::A), None);
+    assert!(map.iter().eq([(Sparse::B, &2), (Sparse::E, &6)]));
+    assert!(map.iter().rev().eq([(Sparse::E, &6), (Sparse::B, &2)]));
+
+    match map.entry(Sparse::C) {
+        Entry::Vacant(entry) => {
+            entry.insert(3);
+        }
+        Entry::Occupied(..) => panic!("expected vacant entry"),
+    }
+
+    match map.entry(Sparse::B) {
+        Entry::Occupied(entry) => assert_eq!(entry.remove(), 2),
+        Entry::Vacant(..) => panic!("expected occupied entry"),
+    }
+
+    assert!(map.keys().eq([Sparse::C, Sparse::E]));
+
+    map.retain(|_, value| *value > 3);
+    assert!(map.keys().eq([Sparse::E]));
+    assert_eq!(map.remove(Sparse::E), Some(6));
+    assert!(map.is_empty());
+}
+
+#[test]
+fn set() {
+    let mut set = Set::new();
+    assert!(set.insert(Sparse::F));
+    assert!(set.insert(Sparse::A));
+    assert!(!set.insert(Sparse::F));
+
+    assert!(set.contains(Sparse::A));
+    assert!(!set.contains(Sparse::B));
+    assert!(set.iter().eq([Sparse::A, Sparse::F]));
+
+    assert!(set.remove(Sparse::A));
+    assert!(!set.remove(Sparse::A));
+    assert!(set.iter().eq([Sparse::F]));
+}
+
+#[test]
+fn nested() {
+    let mut map = Map::new();
+    map.insert(Outer::Inner(Sparse::D), 4);
+    map.insert(Outer::Unit, 0);
+    map.insert(Outer::Inner(Sparse::A), 1);
+
+    assert!(map.keys().eq([
+        Outer::Unit,
+        Outer::Inner(Sparse::A),
+        Outer::Inner(Sparse::D)
+    ]));
+}
+
+#[test]
+fn groups() {
+    let mut map = Map::from_iter([(Sparse::A, 1), (Sparse::C, 3)]);
+    assert_eq!(map.len_of(SparseGroup::C), 1);
+    assert_eq!(map.len_of(SparseGroup::B), 0);
+
+    map.clear_of(SparseGroup::A);
+    assert!(map.keys().eq([Sparse::C]));
+
+    let set = Set::from([Sparse::B]);
+    assert!(set.contains_any_of(SparseGroup::B));
+    assert!(!set.contains_any_of(SparseGroup::C));
+}
+
+#[test]
+fn sparse_index() {
+    for key in SPARSE {
+        let index = key.to_sparse_index();
+        assert_eq!(usize::from(index), key.to_index());
+        assert_eq!(Sparse::from_sparse_index(index), Some(key));
+    }
+
+    assert_eq!(Sparse::from_sparse_index(6), None);
+    assert_eq!(Sparse::from_sparse_index(u16::MAX), None);
+}
+
+#[test]
+fn matches_dense_storage() {
+    // Every subset of the variants, with a value which depends on the index.
+    for mask in 0u32..64 {
+        for other in 0u32..64 {
+            let sparse = |mask: u32| {
+                Map::from_iter(
+                    (0..6)
+                        .filter(|n| mask & (1 << n) != 0)
+                        .map(|n| (SPARSE[n], n)),
+                )
+            };
+
+            let dense = |mask: u32| {
+                Map::from_iter(
+                    (0..6)
+                        .filter(|n| mask & (1 << n) != 0)
+                        .map(|n| (DENSE[n], n)),
+                )
+            };
+
+            let (a, b) = (sparse(mask), sparse(other));
+            let (c, d) = (dense(mask), dense(other));
+
+            assert_eq!(a == b, c == d);
+            assert_eq!(a.cmp(&b), c.cmp(&d), "{mask:06b} <=> {other:06b}");
+
+            if a == b {
+                assert_eq!(hash(&a), hash(&b));
+            }
+
+            let sets = (Set::from_keys_of(&a), Set::from_keys_of(&b));
+            let dense_sets = (Set::from_keys_of(&c), Set::from_keys_of(&d));
+            assert_eq!(sets.0.cmp(&sets.1), dense_sets.0.cmp(&dense_sets.1));
+        }
+    }
+}
+
+#[test]
+fn remove_and_shrink() {
+    let mut map = SPARSE.iter().map(|&k| (k, k as u32)).collect::<Map<_, _>>();
+
+    for &key in &SPARSE[1..] {
+        assert!(map.remove(key).is_some());
+    }
+
+    assert!(map.stats().dynamic_capacity >= SPARSE.len());
+
+    assert_eq!(map.remove_and_shrink(Sparse::A), Some(0));
+    assert_eq!(map.remove_and_shrink(Sparse::A), None);
+    assert_eq!(map.stats().dynamic_capacity, 0);
+}
+
+#[test]
+fn drain() {
+    let mut map = SPARSE
+        .iter()
+        .rev()
+        .map(|&k| (k, k as u32))
+        .collect::<Map<_, _>>();
+    let capacity = map.stats().dynamic_capacity;
+
+    assert!(map.drain().eq(SPARSE.iter().map(|&k| (k, k as u32))));
+    assert!(map.is_empty());
+    assert_eq!(map.stats().dynamic_capacity, capacity);
+}
+
+#[test]
+fn extract_if() {
+    let mut map = SPARSE
+        .iter()
+        .rev()
+        .map(|&k| (k, k as u32))
+        .collect::<Map<_, _>>();
+
+    let extracted = map.extract_if(|_, v| *v % 2 == 0).collect::<Vec<_>>();
+
+    assert!(extracted.into_iter().eq(SPARSE
+        .iter()
+        .map(|&k| (k, k as u32))
+        .filter(|(_, v)| v % 2 == 0)));
+    assert!(map.iter().map(|(k, v)| (k, *v)).eq(SPARSE
+        .iter()
+        .map(|&k| (k, k as u32))
+        .filter(|(_, v)| v % 2 != 0)));
+}
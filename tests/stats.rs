@@ -0,0 +1,57 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::map::Stats;
+use fixed_map::{Key, Map};
+
+#[derive(Clone, Copy, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Clone, Copy, Key)]
+#[key(transparent)]
+struct Wrapper(Part);
+
+#[derive(Clone, Copy, Key)]
+enum MyKey {
+    Unit,
+    Nested(Part),
+    Wrapped(Wrapper),
+    Option(Option<bool>),
+    Singleton(()),
+    Number(u32),
+    Text(&'static str),
+    #[key(values(1, 2, 3))]
+    Values(u8),
+}
+
+#[test]
+fn stats() {
+    let mut map = Map::new();
+    assert_eq!(
+        map.stats(),
+        Stats {
+            capacity: 12,
+            len: 0,
+            dynamic_buckets: 2,
+            dynamic_capacity: 0,
+        }
+    );
+
+    map.insert(MyKey::Unit, 1);
+    map.insert(MyKey::Nested(Part::Two), 2);
+    map.insert(MyKey::Wrapped(Wrapper(Part::One)), 3);
+    map.insert(MyKey::Option(None), 4);
+    map.insert(MyKey::Singleton(()), 5);
+    map.insert(MyKey::Number(7), 6);
+    map.insert(MyKey::Values(2), 7);
+
+    let stats = map.stats();
+    assert_eq!(stats.capacity, 12);
+    assert_eq!(stats.len, map.len());
+    assert_eq!(stats.dynamic_buckets, 2);
+    assert!(stats.dynamic_capacity >= 1);
+
+    assert_eq!(Map::<Part, u32>::new().stats(), Stats::fixed(2, 0));
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "std-collections")]
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum MyKey {
+    Name(&'static str),
+    Number(u32),
+    Empty,
+}
+
+#[test]
+fn map() {
+    let mut map = Map::new();
+    assert_eq!(map.insert(MyKey::Name("foo"), 1), None);
+    assert_eq!(map.insert(MyKey::Number(42), 2), None);
+    assert_eq!(map.insert(MyKey::Empty, 3), None);
+    assert_eq!(map.insert(MyKey::Number(42), 4), Some(2));
+
+    assert_eq!(map.get(MyKey::Name("foo")), Some(&1));
+    assert_eq!(map.get(MyKey::Name("bar")), None);
+    assert_eq!(map.len(), 3);
+
+    map.retain(|key, _| key != MyKey::Empty);
+    assert_eq!(map.remove(MyKey::Number(42)), Some(4));
+    assert!(map.into_iter().eq([(MyKey::Name("foo"), 1)]));
+}
+
+#[test]
+fn set() {
+    let mut set = Set::new();
+    assert!(set.insert(MyKey::Number(1)));
+    assert!(!set.insert(MyKey::Number(1)));
+    assert!(set.contains(MyKey::Number(1)));
+    assert!(!set.contains(MyKey::Number(2)));
+    assert!(set.remove(MyKey::Number(1)));
+    assert!(set.is_empty());
+}
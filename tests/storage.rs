@@ -0,0 +1,62 @@
+//! Regression tests for the built-in storage of `bool`, `Option<T>` and `()`.
+
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[test]
+fn bool_set_insert_reports_new_values() {
+    let mut set = Set::<bool>::new();
+    assert!(set.insert(true));
+    assert!(!set.insert(true));
+    assert!(set.insert(false));
+    assert!(!set.insert(false));
+}
+
+#[test]
+fn bool_set_remove_reports_present_values() {
+    let mut set = Set::<bool>::new();
+    set.insert(false);
+    assert!(!set.remove(true));
+    assert!(set.contains(false));
+    assert!(set.remove(false));
+    assert!(!set.remove(false));
+}
+
+#[test]
+fn option_set_insert_none_reports_new_value() {
+    let mut set = Set::<Option<Part>>::new();
+    assert!(set.insert(None));
+    assert!(!set.insert(None));
+}
+
+#[test]
+fn option_set_is_empty() {
+    let mut set = Set::<Option<Part>>::new();
+    assert!(set.is_empty());
+    set.insert(None);
+    assert!(!set.is_empty());
+    set.remove(None);
+    set.insert(Some(Part::One));
+    assert!(!set.is_empty());
+}
+
+#[test]
+fn singleton_set_retain_does_not_resurrect() {
+    let mut set = Set::<()>::new();
+    set.retain(|()| true);
+    assert!(!set.contains(()));
+    assert!(set.is_empty());
+}
+
+#[test]
+fn singleton_map_keys_of_empty_map() {
+    let mut map = Map::<(), u32>::new();
+    assert_eq!(map.keys().count(), 0);
+    map.insert((), 1);
+    assert_eq!(map.keys().collect::<Vec<_>>(), [()]);
+}
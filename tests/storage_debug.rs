@@ -0,0 +1,80 @@
+use core::fmt::Debug;
+
+use fixed_map::map::MapStorage;
+use fixed_map::set::SetStorage;
+use fixed_map::Key;
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Unit,
+    Nested(Part),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum Flag {
+    First,
+    Second,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(transparent)]
+struct Wrapper(Part);
+
+// Keys which don't implement `Debug` still derive storage.
+#[derive(Clone, Copy, Key)]
+enum Opaque {
+    First,
+    Second,
+}
+
+fn map_debug<K>(entries: &[(K, u32)]) -> String
+where
+    K: Key,
+    K::MapStorage<u32>: Debug,
+{
+    let mut storage = K::MapStorage::<u32>::empty();
+
+    for &(key, value) in entries {
+        storage.insert(key, value);
+    }
+
+    format!("{storage:?}")
+}
+
+fn set_debug<K>(values: &[K]) -> String
+where
+    K: Key,
+    K::SetStorage: Debug,
+{
+    let mut storage = K::SetStorage::empty();
+
+    for &value in values {
+        storage.insert(value);
+    }
+
+    format!("{storage:?}")
+}
+
+#[test]
+fn storage_debug() {
+    assert_eq!(
+        map_debug(&[(MyKey::Unit, 1), (MyKey::Nested(Part::Two), 2)]),
+        "{Unit: 1, Nested(Two): 2}"
+    );
+    assert_eq!(map_debug(&[(Part::Two, 2)]), "{Two: 2}");
+    assert_eq!(map_debug(&[(Wrapper(Part::One), 1)]), "{Wrapper(One): 1}");
+
+    assert_eq!(set_debug(&[MyKey::Nested(Part::One)]), "{Nested(One)}");
+    assert_eq!(set_debug(&[Flag::Second, Flag::First]), "{First, Second}");
+    assert_eq!(set_debug(&[Wrapper(Part::Two)]), "{Wrapper(Two)}");
+
+    let _ = <Opaque as Key>::MapStorage::<u32>::empty();
+    let _ = <Opaque as Key>::SetStorage::empty();
+}
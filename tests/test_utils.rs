@@ -0,0 +1,70 @@
+#![cfg(feature = "test-utils")]
+
+use fixed_map::{test_utils, Key};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum Bits {
+    One,
+    Two,
+    Three,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Simple,
+    Composite(Part),
+    Bool(bool),
+    Option(Option<Part>),
+    Singleton(()),
+}
+
+#[test]
+fn check_unit() {
+    test_utils::check_key(&[Part::One, Part::Two]);
+    test_utils::check_key(&[Bits::One, Bits::Two, Bits::Three]);
+}
+
+#[test]
+fn check_composite() {
+    test_utils::check_key(&[
+        MyKey::Simple,
+        MyKey::Composite(Part::One),
+        MyKey::Composite(Part::Two),
+        MyKey::Bool(true),
+        MyKey::Bool(false),
+        MyKey::Option(None),
+        MyKey::Option(Some(Part::One)),
+        MyKey::Option(Some(Part::Two)),
+        MyKey::Singleton(()),
+    ]);
+}
+
+#[test]
+#[should_panic = "keys passed to the check must be distinct"]
+fn check_distinct() {
+    test_utils::check_key(&[Part::One, Part::One]);
+}
+
+#[cfg(feature = "hashbrown")]
+#[test]
+fn check_dynamic() {
+    #[derive(Debug, Clone, Copy, PartialEq, Key)]
+    enum Dynamic {
+        String(&'static str),
+        Number(u32),
+    }
+
+    test_utils::check_key(&[
+        Dynamic::String("foo"),
+        Dynamic::String("bar"),
+        Dynamic::Number(1),
+        Dynamic::Number(2),
+    ]);
+}
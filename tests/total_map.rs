@@ -0,0 +1,89 @@
+#![cfg(feature = "alloc")]
+
+use std::panic;
+
+use fixed_map::total_map::TotalMap;
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    First,
+    Second(Part),
+    Third(Option<bool>),
+    #[key(lazy)]
+    Lazy(bool),
+}
+
+fn all() -> Map<MyKey, u32> {
+    TotalMap::from_fn(|key| match key {
+        MyKey::First => 1,
+        MyKey::Second(_) => 2,
+        MyKey::Third(_) => 3,
+        MyKey::Lazy(_) => 4,
+    })
+    .into_map()
+}
+
+#[test]
+fn complete() {
+    let map = all();
+    assert_eq!(map.len(), 8);
+
+    let mut total = map.clone().unwrap_all();
+    assert_eq!(total.len(), 8);
+    assert_eq!(total[MyKey::Third(None)], 3);
+    assert_eq!(total.replace(MyKey::Lazy(false), 40), 4);
+    *total.get_mut(MyKey::First) += 10;
+    assert_eq!(total.get(MyKey::First), &11);
+
+    let total = TotalMap::try_from(map).unwrap();
+    assert!(total.keys().eq(all().keys()));
+}
+
+#[test]
+fn missing() {
+    let mut map = all();
+    map.remove(MyKey::Second(Part::Two));
+    map.remove(MyKey::Third(None));
+    map.remove_and_shrink(MyKey::Lazy(true));
+    map.remove_and_shrink(MyKey::Lazy(false));
+
+    let error = map.try_into_total().unwrap_err();
+    assert!(error.missing().iter().eq([
+        MyKey::Second(Part::Two),
+        MyKey::Third(None),
+        MyKey::Lazy(true),
+        MyKey::Lazy(false),
+    ]));
+    assert_eq!(
+        error.to_string(),
+        "missing keys `Second(Two)`, `Third(None)`, `Lazy(true)`, `Lazy(false)`"
+    );
+
+    let map = error.into_map();
+    assert_eq!(map.len(), 4);
+
+    let result = panic::catch_unwind(|| map.expect_all("incomplete config"));
+    let payload = result.unwrap_err();
+    let message = payload.downcast_ref::<String>().unwrap();
+    assert_eq!(
+        message,
+        "incomplete config: missing keys `Second(Two)`, `Third(None)`, `Lazy(true)`, `Lazy(false)`"
+    );
+}
+
+#[test]
+fn unwrap_all_names_type() {
+    let map = Map::<Part, u32>::from([(Part::One, 1)]);
+
+    let result = panic::catch_unwind(|| map.unwrap_all());
+    let payload = result.unwrap_err();
+    let message = payload.downcast_ref::<String>().unwrap();
+    assert!(message.ends_with(": missing key `Two`"), "{message}");
+}
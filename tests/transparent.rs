@@ -0,0 +1,94 @@
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Key)]
+enum Inner {
+    First,
+    Second(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Key)]
+#[key(transparent)]
+struct Wrapper(Inner);
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum Bits {
+    First,
+    Second,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(transparent)]
+struct BitsWrapper(Bits);
+
+#[test]
+fn map() {
+    let mut map = Map::new();
+    assert_eq!(map.insert(Wrapper(Inner::First), 1), None);
+    assert_eq!(map.insert(Wrapper(Inner::Second(true)), 2), None);
+    assert_eq!(map.insert(Wrapper(Inner::First), 3), Some(1));
+
+    assert_eq!(map.get(Wrapper(Inner::First)), Some(&3));
+    assert_eq!(map.len(), 2);
+    assert!(map.iter().eq([
+        (Wrapper(Inner::First), &3),
+        (Wrapper(Inner::Second(true)), &2)
+    ]));
+
+    *map.entry(Wrapper(Inner::Second(false))).or_default() += 4;
+    assert_eq!(map.get(Wrapper(Inner::Second(false))), Some(&4));
+
+    map.retain(|k, _| k != Wrapper(Inner::First));
+    assert!(map
+        .keys()
+        .eq([Wrapper(Inner::Second(true)), Wrapper(Inner::Second(false))]));
+
+    let copy = map;
+    assert_eq!(copy, map);
+}
+
+#[test]
+fn set() {
+    let mut set = Set::new();
+    assert!(set.insert(Wrapper(Inner::First)));
+    assert!(!set.insert(Wrapper(Inner::First)));
+    assert!(set.contains(Wrapper(Inner::First)));
+    assert!(set.into_iter().eq([Wrapper(Inner::First)]));
+}
+
+#[test]
+fn raw() {
+    let set = Set::from([BitsWrapper(Bits::Second)]);
+    assert_eq!(set.as_raw(), Set::from([Bits::Second]).as_raw());
+    assert_eq!(Set::from_raw(set.as_raw()), set);
+}
+
+#[test]
+fn storage_extensions() {
+    use fixed_map::map::{Entry, OccupiedEntry};
+    use fixed_map::raw::KeyIndex;
+
+    assert_eq!(BitsWrapper::LEN, Bits::LEN);
+    assert_eq!(BitsWrapper(Bits::Second).to_index(), 1);
+    assert_eq!(BitsWrapper::from_index(1), Some(BitsWrapper(Bits::Second)));
+
+    let mut map = Map::new();
+    map.insert(BitsWrapper(Bits::First), 1);
+    map.insert(BitsWrapper(Bits::Second), 2);
+
+    assert!(map.iter_indexed().eq([
+        (0, BitsWrapper(Bits::First), &1),
+        (1, BitsWrapper(Bits::Second), &2)
+    ]));
+    assert!(map
+        .range(BitsWrapper(Bits::Second)..)
+        .eq([(BitsWrapper(Bits::Second), &2)]));
+
+    for entry in map.entries_mut() {
+        if let Entry::Occupied(mut entry) = entry {
+            *entry.get_mut() += entry.key().to_index();
+        }
+    }
+
+    assert!(map.values().eq([&1, &3]));
+}
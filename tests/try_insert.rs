@@ -0,0 +1,79 @@
+#![cfg(all(feature = "hashbrown", feature = "std"))]
+
+use fixed_map::map::OccupiedEntry;
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(transparent)]
+struct Wrapper(Part);
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    First,
+    Second(u32),
+    Nested(Part),
+    Optional(Option<Part>),
+    Wrapped(Wrapper),
+}
+
+const KEYS: [MyKey; 7] = [
+    MyKey::First,
+    MyKey::Second(1),
+    MyKey::Nested(Part::Two),
+    MyKey::Optional(Some(Part::One)),
+    MyKey::Optional(None),
+    MyKey::Wrapped(Wrapper(Part::One)),
+    MyKey::Wrapped(Wrapper(Part::Two)),
+];
+
+#[test]
+fn every_storage() {
+    let mut map = Map::new();
+
+    for (n, key) in KEYS.into_iter().enumerate() {
+        *map.try_insert(key, n).unwrap() += 10;
+    }
+
+    for (n, key) in KEYS.into_iter().enumerate() {
+        let mut error = map.try_insert(key, 100).unwrap_err();
+        assert_eq!(error.key(), key);
+        assert_eq!(error.entry().get(), &(n + 10));
+        assert_eq!(*error.value(), 100);
+        *error.entry_mut().get_mut() += 1;
+    }
+
+    for (n, key) in KEYS.into_iter().enumerate() {
+        assert_eq!(map.get(key), Some(&(n + 11)));
+    }
+
+    assert_eq!(map.len(), KEYS.len());
+    map.debug_assert_invariants();
+}
+
+#[test]
+fn error() {
+    let mut map = Map::new();
+    map.insert(MyKey::Second(7), 1);
+
+    let error = map.try_insert(MyKey::Second(7), 2).unwrap_err();
+    assert_eq!(
+        format!("{error:?}"),
+        "OccupiedError { key: Second(7), old_value: 1, new_value: 2 }"
+    );
+
+    let (entry, value) = error.into_parts();
+    assert_eq!(entry.remove(), 1);
+    assert_eq!(value, 2);
+    assert!(map.is_empty());
+
+    map.insert(MyKey::First, 1);
+    let error: Box<dyn std::error::Error + '_> =
+        Box::new(map.try_insert(MyKey::First, 2).unwrap_err());
+    assert_eq!(error.to_string(), "key `First` is already occupied");
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Simple,
+    Nested(Part),
+    Flag(bool),
+    Dynamic(u32),
+}
+
+#[test]
+fn update() {
+    let mut map = Map::new();
+    map.insert(MyKey::Simple, 1);
+    map.insert(MyKey::Nested(Part::Two), 2);
+    map.insert(MyKey::Dynamic(7), 3);
+
+    for key in [MyKey::Simple, MyKey::Nested(Part::Two), MyKey::Dynamic(7)] {
+        assert!(map.update(key, |v| *v *= 10));
+    }
+
+    let mut called = false;
+
+    for key in [
+        MyKey::Nested(Part::One),
+        MyKey::Flag(true),
+        MyKey::Dynamic(8),
+    ] {
+        assert!(!map.update(key, |_| called = true));
+    }
+
+    assert!(!called);
+    assert!(map.values().copied().eq([10, 20, 30]));
+    assert_eq!(map.len(), 3);
+}
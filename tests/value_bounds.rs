@@ -0,0 +1,120 @@
+//! Maps should only require of their values what the operation at hand needs,
+//! so that trait objects and reference counted values can be stored.
+
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use fixed_map::{CloneableKey, CopyKey, HashableKey, Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Key)]
+enum Simple {
+    First,
+    Second,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Key)]
+enum Composite {
+    Unit,
+    Bool(bool),
+    Option(Option<Simple>),
+    Nested(Simple),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Key)]
+#[key(transparent)]
+struct Wrapper(Simple);
+
+fn assert_clone<T: Clone>() {}
+fn assert_copy<T: Copy>() {}
+fn assert_debug<T: Debug>() {}
+fn assert_eq<T: Eq>() {}
+fn assert_ord<T: Hash + Ord>() {}
+fn assert_send_sync<T: Send + Sync>() {}
+
+macro_rules! check {
+    ($k:ty) => {
+        assert_clone::<Map<$k, Rc<dyn Debug>>>();
+        assert_clone::<Map<$k, Rc<str>>>();
+        assert_copy::<Map<$k, &dyn Debug>>();
+        assert_copy::<Map<$k, &str>>();
+        assert_debug::<Map<$k, Box<dyn Debug>>>();
+        assert_debug::<Map<$k, &dyn Debug>>();
+        assert_debug::<Map<$k, &mut dyn Debug>>();
+        assert_eq::<Map<$k, Rc<str>>>();
+        assert_eq::<Map<$k, Box<str>>>();
+        assert_send_sync::<Map<$k, Box<dyn Debug + Send + Sync>>>();
+
+        let map = Map::<$k, Box<dyn Debug>>::new();
+        let _ = map.iter().clone();
+        let _ = map.keys().clone();
+        let _ = map.values().clone();
+
+        let map = Map::<$k, Rc<dyn Debug>>::new();
+        let _ = map.clone().into_iter();
+    };
+}
+
+#[test]
+fn value_bounds() {
+    check!(Simple);
+    check!(Composite);
+    check!(Wrapper);
+    check!(bool);
+    check!(Option<Composite>);
+    check!(());
+
+    assert_ord::<Map<Simple, Rc<str>>>();
+    assert_ord::<Map<Wrapper, Box<str>>>();
+}
+
+fn generic_clone<K, V>(map: &Map<K, V>) -> Map<K, V>
+where
+    K: CloneableKey<V>,
+{
+    map.clone()
+}
+
+fn generic_copy<K, V>(map: &Map<K, V>) -> Map<K, V>
+where
+    K: CopyKey<V>,
+{
+    *map
+}
+
+fn generic_hash<K, V>(map: &Map<K, V>) -> u64
+where
+    K: HashableKey<V>,
+{
+    use std::hash::{BuildHasher, BuildHasherDefault};
+    BuildHasherDefault::<std::collections::hash_map::DefaultHasher>::default().hash_one(map)
+}
+
+fn generic_combined<K, V>(map: &Map<K, V>) -> (Map<K, V>, u64)
+where
+    K: CopyKey<V> + HashableKey<V>,
+{
+    (*map, generic_hash(map))
+}
+
+#[test]
+fn marker_keys() {
+    let map = Map::from_iter([(Composite::Bool(true), Rc::<str>::from("a"))]);
+    assert_eq!(generic_clone(&map), map);
+
+    let map = Map::from_iter([(Composite::Nested(Simple::First), 1u32)]);
+    assert_eq!(generic_copy(&map), map);
+
+    let a = Map::from_iter([(Simple::First, 1u32)]);
+    assert_eq!(generic_hash(&a), generic_hash(&a.clone()));
+    assert_eq!(generic_combined(&a), (a, generic_hash(&a)));
+}
+
+#[cfg(feature = "hashbrown")]
+#[test]
+fn dynamic_value_bounds() {
+    assert_clone::<Map<u32, Rc<dyn Debug>>>();
+    assert_debug::<Map<u32, Box<dyn Debug>>>();
+    assert_eq::<Map<u32, Rc<str>>>();
+    assert_send_sync::<Map<u32, Box<dyn Debug + Send + Sync>>>();
+}
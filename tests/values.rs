@@ -0,0 +1,150 @@
+use core::mem::size_of;
+
+use fixed_map::map::{Entry, OccupiedEntry, VacantEntry};
+use fixed_map::{Key, Map, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Level(u8);
+
+impl Level {
+    const LOW: Level = Level(10);
+    const HIGH: Level = Level(200);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum Setting {
+    #[key(values(1, 2, 4, 8))]
+    Mode(u8),
+    #[key(values(Level::LOW, Level::HIGH))]
+    Level(Level),
+    Off,
+}
+
+#[test]
+fn layout() {
+    assert_eq!(
+        size_of::<Map<Setting, u32>>(),
+        size_of::<[Option<u32>; 7]>()
+    );
+    assert_eq!(size_of::<Set<Setting>>(), size_of::<[bool; 7]>());
+}
+
+#[test]
+fn map() {
+    let mut map = Map::new();
+    assert_eq!(map.insert(Setting::Mode(8), 'a'), None);
+    assert_eq!(map.insert(Setting::Mode(2), 'b'), None);
+    assert_eq!(map.insert(Setting::Level(Level::HIGH), 'c'), None);
+    assert_eq!(map.insert(Setting::Off, 'd'), None);
+    assert_eq!(map.insert(Setting::Mode(2), 'e'), Some('b'));
+    assert_eq!(map.len(), 4);
+
+    assert_eq!(map.get(Setting::Mode(8)), Some(&'a'));
+    assert_eq!(map.get(Setting::Mode(4)), None);
+    assert_eq!(map.get(Setting::Mode(3)), None);
+    assert!(!map.contains_key(Setting::Mode(3)));
+    assert_eq!(map.remove(Setting::Mode(3)), None);
+    assert_eq!(map.get(Setting::Level(Level(11))), None);
+
+    assert!(map.iter().eq([
+        (Setting::Mode(2), &'e'),
+        (Setting::Mode(8), &'a'),
+        (Setting::Level(Level::HIGH), &'c'),
+        (Setting::Off, &'d'),
+    ]));
+
+    map.retain(|key, _| key != Setting::Mode(8));
+    assert!(map.values().copied().eq(['e', 'c', 'd']));
+
+    assert_eq!(map.remove(Setting::Mode(2)), Some('e'));
+    assert!(map
+        .into_iter()
+        .eq([(Setting::Level(Level::HIGH), 'c'), (Setting::Off, 'd')]));
+}
+
+#[test]
+fn entry() {
+    let mut map = Map::new();
+
+    match map.entry(Setting::Mode(4)) {
+        Entry::Vacant(entry) => {
+            assert_eq!(entry.key(), Setting::Mode(4));
+            entry.insert(1);
+        }
+        Entry::Occupied(..) => panic!("expected vacant entry"),
+    }
+
+    match map.entry(Setting::Mode(4)) {
+        Entry::Occupied(entry) => assert_eq!(entry.remove(), 1),
+        Entry::Vacant(..) => panic!("expected occupied entry"),
+    }
+
+    assert!(map.is_empty());
+}
+
+#[test]
+fn set() {
+    let mut set = Set::new();
+    assert!(set.insert(Setting::Mode(4)));
+    assert!(set.insert(Setting::Level(Level::LOW)));
+    assert!(!set.insert(Setting::Mode(4)));
+    assert!(set.contains(Setting::Mode(4)));
+    assert!(!set.contains(Setting::Mode(5)));
+    assert!(!set.remove(Setting::Mode(5)));
+
+    assert!(set
+        .iter()
+        .eq([Setting::Mode(4), Setting::Level(Level::LOW)]));
+
+    assert!(set.remove(Setting::Mode(4)));
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+#[should_panic = "value is not one of the values declared through #[key(values(...))] on `Setting::Mode`"]
+fn insert_undeclared() {
+    let mut map = Map::new();
+    map.insert(Setting::Mode(3), ());
+}
+
+#[test]
+#[should_panic]
+fn set_insert_undeclared() {
+    let mut set = Set::new();
+    set.insert(Setting::Level(Level(0)));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum Name {
+    #[key(values("foo", "bar", "baz", "", "quux"))]
+    Known(&'static str),
+    // NB: No single byte position tells these apart.
+    #[key(values("ab", "ac", "bb"))]
+    Ambiguous(&'static str),
+}
+
+#[test]
+fn strings() {
+    let mut map = Map::new();
+    map.insert(Name::Known("baz"), 1);
+    map.insert(Name::Known(""), 2);
+    map.insert(Name::Ambiguous("bb"), 3);
+
+    assert_eq!(map.get(Name::Known("baz")), Some(&1));
+    assert_eq!(map.get(Name::Known(String::from("baz").leak())), Some(&1));
+    assert_eq!(map.get(Name::Known("")), Some(&2));
+    assert_eq!(map.get(Name::Known("bar")), None);
+    assert_eq!(map.get(Name::Known("bax")), None);
+    assert_eq!(map.get(Name::Known("quu")), None);
+    assert_eq!(map.get(Name::Ambiguous("bb")), Some(&3));
+    assert_eq!(map.get(Name::Ambiguous("ba")), None);
+
+    assert!(map
+        .keys()
+        .eq([Name::Known("baz"), Name::Known(""), Name::Ambiguous("bb")]));
+
+    let mut set = Set::new();
+    assert!(set.insert(Name::Known("quux")));
+    assert!(set.contains(Name::Known("quux")));
+    assert!(!set.contains(Name::Known("quuz")));
+}
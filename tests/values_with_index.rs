@@ -0,0 +1,85 @@
+#![cfg(all(feature = "hashbrown", feature = "alloc"))]
+
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(transparent)]
+struct Wrapper(Part);
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Unit,
+    Dynamic(u32),
+    Nested(Part),
+    Optional(Option<bool>),
+    Wrapped(Wrapper),
+    #[key(values(1, 2, 4))]
+    Mode(u8),
+    #[key(lazy)]
+    Lazy(Part),
+    Last,
+}
+
+const KEYS: [MyKey; 9] = [
+    MyKey::Unit,
+    MyKey::Dynamic(7),
+    MyKey::Nested(Part::Two),
+    MyKey::Optional(Some(false)),
+    MyKey::Optional(None),
+    MyKey::Wrapped(Wrapper(Part::One)),
+    MyKey::Mode(4),
+    MyKey::Lazy(Part::One),
+    MyKey::Last,
+];
+
+fn collect<K>(map: &Map<K, usize>) -> Vec<(usize, usize)>
+where
+    K: Key,
+{
+    let mut output = Vec::new();
+    map.values_with_index(|index, value| output.push((index, *value)));
+    output
+}
+
+#[test]
+fn matches_class_index() {
+    let map = KEYS
+        .into_iter()
+        .enumerate()
+        .map(|(n, key)| (key, n))
+        .collect::<Map<_, _>>();
+
+    let expected = map
+        .iter()
+        .map(|(key, value)| (key.class_index(), *value))
+        .collect::<Vec<_>>();
+
+    assert_eq!(collect(&map), expected);
+    assert_eq!(
+        collect(&map)
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>(),
+        [0, 1, 2, 3, 3, 4, 5, 6, 7]
+    );
+}
+
+#[test]
+fn empty() {
+    assert!(collect(&Map::<MyKey, usize>::new()).is_empty());
+}
+
+#[test]
+fn builtin_keys() {
+    let map = Map::from_iter([(Some(true), 1), (None, 2), (Some(false), 3)]);
+    assert_eq!(collect(&map), [(0, 1), (0, 3), (1, 2)]);
+
+    let map = Map::from_iter([(true, 1), (false, 2)]);
+    assert_eq!(collect(&map), [(1, 1), (0, 2)]);
+}
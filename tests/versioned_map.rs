@@ -0,0 +1,51 @@
+#![cfg(feature = "hashbrown")]
+
+use fixed_map::versioned_map::Handle;
+use fixed_map::{Key, VersionedMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Key)]
+enum Slot {
+    Camera,
+    Light(u8),
+    Mesh(Option<bool>),
+}
+
+#[test]
+fn stale_handles() {
+    let mut map = VersionedMap::new();
+    let (camera, _) = map.insert(Slot::Camera, "main");
+    let (light, _) = map.insert(Slot::Light(3), "sun");
+    let (mesh, _) = map.insert(Slot::Mesh(None), "cube");
+    assert_eq!(map.len(), 3);
+
+    let cached: Vec<Handle<Slot>> = vec![camera, light, mesh];
+
+    assert_eq!(map.remove(light), Some("sun"));
+    let (light2, _) = map.insert(Slot::Light(3), "moon");
+    assert_eq!(map.len(), 3);
+
+    let resolved: Vec<_> = cached.iter().map(|&h| map.get(h).copied()).collect();
+    assert_eq!(resolved, [Some("main"), None, Some("cube")]);
+
+    assert_eq!(map.get(light2), Some(&"moon"));
+    assert_eq!(light2.generation().get(), 3);
+    assert_eq!(map.handle(Slot::Light(3)), Some(light2));
+
+    assert!(map.get_mut(light).is_none());
+    assert_eq!(map.remove(light), None);
+    assert_eq!(map.get_key(Slot::Light(3)), Some(&"moon"));
+}
+
+#[test]
+fn iter_skips_vacant_slots() {
+    let mut map = VersionedMap::new();
+    map.insert(Slot::Camera, 1);
+    let (light, _) = map.insert(Slot::Light(1), 2);
+    map.insert(Slot::Mesh(Some(true)), 3);
+    map.remove_key(Slot::Camera);
+
+    let keys: Vec<_> = map.iter().map(|(h, v)| (h.key(), *v)).collect();
+    assert_eq!(keys, [(Slot::Light(1), 2), (Slot::Mesh(Some(true)), 3)]);
+    assert!(map.iter().any(|(h, _)| h == light));
+    assert_eq!(format!("{map:?}"), "{Light(1): 2, Mesh(Some(true)): 3}");
+}
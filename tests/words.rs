@@ -0,0 +1,192 @@
+use fixed_map::raw::Words;
+use fixed_map::{Key, Set};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum Small {
+    A,
+    B,
+    C,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+#[key(bitset)]
+enum Large {
+    V0,
+    V1,
+    V2,
+    V3,
+    V4,
+    V5,
+    V6,
+    V7,
+    V8,
+    V9,
+    V10,
+    V11,
+    V12,
+    V13,
+    V14,
+    V15,
+    V16,
+    V17,
+    V18,
+    V19,
+    V20,
+    V21,
+    V22,
+    V23,
+    V24,
+    V25,
+    V26,
+    V27,
+    V28,
+    V29,
+    V30,
+    V31,
+    V32,
+    V33,
+    V34,
+    V35,
+    V36,
+    V37,
+    V38,
+    V39,
+    V40,
+    V41,
+    V42,
+    V43,
+    V44,
+    V45,
+    V46,
+    V47,
+    V48,
+    V49,
+    V50,
+    V51,
+    V52,
+    V53,
+    V54,
+    V55,
+    V56,
+    V57,
+    V58,
+    V59,
+    V60,
+    V61,
+    V62,
+    V63,
+    V64,
+    V65,
+    V66,
+    V67,
+    V68,
+    V69,
+    V70,
+    V71,
+    V72,
+    V73,
+    V74,
+    V75,
+    V76,
+    V77,
+    V78,
+    V79,
+    V80,
+    V81,
+    V82,
+    V83,
+    V84,
+    V85,
+    V86,
+    V87,
+    V88,
+    V89,
+    V90,
+    V91,
+    V92,
+    V93,
+    V94,
+    V95,
+    V96,
+    V97,
+    V98,
+    V99,
+}
+
+#[test]
+fn single_word() {
+    let set = Set::from([Small::A, Small::C]);
+    assert_eq!(set.to_words(), [0b101]);
+    assert_eq!(Set::from_words(&set.to_words()), set);
+
+    // Words beyond the raw value are ignored.
+    assert_eq!(
+        Set::<Small>::from_words(&[0b010, u64::MAX]),
+        Set::from([Small::B])
+    );
+}
+
+#[test]
+fn unused_bits() {
+    let set = Set::<Small>::from_words(&[u64::MAX]);
+    assert_eq!(set.len(), 3);
+    assert_eq!(set.as_raw(), 0b111);
+    assert_eq!(set, Set::from([Small::A, Small::B, Small::C]));
+
+    let set = Set::<Large>::from_words(&[u64::MAX, u64::MAX]);
+    assert_eq!(set.len(), 100);
+    assert_eq!(set.to_words(), [u64::MAX, (1 << 36) - 1]);
+}
+
+#[test]
+fn stray_high_bits() {
+    // Bits above the last variant which still fit in the raw value.
+    let set = Set::<Small>::from_words(&[1 << 3 | 1 << 7]);
+    assert!(set.is_empty());
+    assert_eq!(set.as_raw(), 0);
+    assert_eq!(set, Set::new());
+
+    let set = Set::<Small>::from_words(&[0b100 | 1 << 5]);
+    assert!(set.iter().eq([Small::C]));
+    assert_eq!(set.to_words(), [0b100]);
+
+    let set = Set::<Large>::from_words(&[0, 1 << 35 | 1 << 36 | 1 << 63]);
+    assert!(set.iter().eq([Large::V99]));
+    assert_eq!(set.to_words(), [0, 1 << 35]);
+}
+
+#[test]
+fn two_words() {
+    let set = Set::from([Large::V0, Large::V63, Large::V64, Large::V99]);
+
+    let words = set.to_words();
+    assert_eq!(words, [1 | 1 << 63, 1 | 1 << 35]);
+    assert_eq!(Set::from_words(&words), set);
+
+    #[cfg(target_endian = "little")]
+    assert_eq!(set.as_words(), words);
+
+    // A missing high word is treated as zero.
+    assert_eq!(Set::<Large>::from_words(&[1]), Set::from([Large::V0]));
+}
+
+#[test]
+fn bulk() {
+    let sets = [
+        Set::from([Large::V1, Large::V70]),
+        Set::from([Large::V1, Large::V2]),
+        Set::from([Large::V70, Large::V99]),
+    ];
+
+    let mut counts = [0u32; 2];
+
+    for set in &sets {
+        for (count, word) in counts.iter_mut().zip(set.to_words()) {
+            *count += word.count_ones();
+        }
+    }
+
+    assert_eq!(counts, [3, 3]);
+    assert_eq!(u128::from_words(&sets[0].to_words()), sets[0].as_raw());
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "zeroize")]
+
+use fixed_map::{Key, Map, Set};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Secret {
+    Master,
+    Session(u32),
+    Nested(Part),
+}
+
+#[derive(Default)]
+struct Tracked<'a> {
+    scrubbed: Option<&'a mut bool>,
+}
+
+impl Zeroize for Tracked<'_> {
+    fn zeroize(&mut self) {
+        if let Some(scrubbed) = self.scrubbed.take() {
+            *scrubbed = true;
+        }
+    }
+}
+
+#[test]
+fn zeroize_map() {
+    let mut a = false;
+    let mut b = false;
+    let mut c = false;
+
+    let mut map = Map::new();
+    map.insert(
+        Secret::Master,
+        Tracked {
+            scrubbed: Some(&mut a),
+        },
+    );
+    map.insert(
+        Secret::Session(7),
+        Tracked {
+            scrubbed: Some(&mut b),
+        },
+    );
+    map.insert(
+        Secret::Nested(Part::Two),
+        Tracked {
+            scrubbed: Some(&mut c),
+        },
+    );
+
+    map.zeroize();
+    assert!(map.is_empty());
+    drop(map);
+
+    assert!(a && b && c);
+}
+
+#[test]
+fn zeroizing_map() {
+    let mut map = Zeroizing::new(Map::new());
+    map.insert(Secret::Session(1), [0x42u8; 16]);
+    map.insert(Secret::Nested(Part::One), [0x17u8; 16]);
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn zeroize_on_drop() {
+    fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+    assert_zeroize_on_drop::<Map<Secret, Zeroizing<[u8; 16]>>>();
+}
+
+#[test]
+fn zeroize_set() {
+    let mut set = Set::new();
+    set.insert(Secret::Master);
+    set.insert(Secret::Session(3));
+    set.zeroize();
+    assert!(set.is_empty());
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "hashbrown")]
+
+use std::rc::Rc;
+
+use fixed_map::{Key, Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Key)]
+enum MyKey {
+    Unit,
+    Direction(Direction),
+    Dynamic(u32),
+}
+
+#[test]
+fn zip() {
+    let a = Map::from_iter([(Direction::North, 1), (Direction::South, 2)]);
+    let b = Map::from_iter([(Direction::South, "s"), (Direction::West, "w")]);
+
+    let map = a.zip(b);
+    assert!(map.into_iter().eq([(Direction::South, (2, "s"))]));
+}
+
+#[test]
+fn zip_with_composite() {
+    let a = Map::from_iter([
+        (MyKey::Unit, 1),
+        (MyKey::Direction(Direction::East), 2),
+        (MyKey::Dynamic(7), 3),
+        (MyKey::Dynamic(8), 4),
+    ]);
+
+    let b = Map::from_iter([
+        (MyKey::Direction(Direction::East), 20),
+        (MyKey::Direction(Direction::West), 30),
+        (MyKey::Dynamic(8), 40),
+    ]);
+
+    let mut keys = Vec::new();
+
+    let map = a.zip_with(b, |key, a, b| {
+        keys.push(key);
+        a * b
+    });
+
+    assert_eq!(keys.len(), 2);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(MyKey::Direction(Direction::East)), Some(&40));
+    assert_eq!(map.get(MyKey::Dynamic(8)), Some(&160));
+}
+
+#[test]
+fn unmatched_values_are_dropped() {
+    let value = Rc::new(());
+
+    let a = Map::from_iter([
+        (Direction::North, value.clone()),
+        (Direction::East, value.clone()),
+    ]);
+    let b = Map::from_iter([
+        (Direction::East, value.clone()),
+        (Direction::West, value.clone()),
+    ]);
+
+    let map = a.zip(b);
+    assert_eq!(Rc::strong_count(&value), 3);
+
+    drop(map);
+    assert_eq!(Rc::strong_count(&value), 1);
+}